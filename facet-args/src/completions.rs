@@ -0,0 +1,521 @@
+use core::fmt::{self, Write};
+use core::str::FromStr;
+
+use facet_core::{Def, EnumDef, Facet, Field, ScalarAffinity, Shape};
+
+use crate::{
+    ArgsError, ArgsErrorKind, arbitrary_attr, choices, command_rows, find_long, find_short,
+    is_flag, is_newtype, is_positional, is_subcommand, long_name, paragraph, subcommand_enum,
+    takes_value, value_shape, variant_name,
+};
+
+/// A shell that [`completions`] can write a script for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shell {
+    /// Bash, loaded with `source` or from `bash_completion.d`
+    Bash,
+    /// Zsh, installed as `_program` somewhere on `$fpath`
+    Zsh,
+    /// Fish, installed as `program.fish` in `~/.config/fish/completions`
+    Fish,
+}
+
+impl Shell {
+    const ALL: [Shell; 3] = [Shell::Bash, Shell::Zsh, Shell::Fish];
+
+    fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Shell {
+    type Err = ArgsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Shell::ALL
+            .into_iter()
+            .find(|shell| shell.name() == s)
+            .ok_or_else(|| {
+                ArgsError::new(ArgsErrorKind::InvalidChoice {
+                    flag: "<shell>".to_string(),
+                    value: s.to_string(),
+                    choices: Shell::ALL.iter().map(|s| s.to_string()).collect(),
+                })
+            })
+    }
+}
+
+/// Generates a completion script for `T`, for `program` in `shell`.
+///
+/// The script completes flags, subcommands, the variants of enum-typed
+/// values, and file names for path-typed ones.
+pub fn completions<'facet, T: Facet<'facet>>(shell: Shell, program: &str) -> String {
+    completions_for_shape(T::SHAPE, shell, program)
+}
+
+/// Generates a completion script for a shape. See [`completions`].
+pub fn completions_for_shape(shape: &'static Shape, shell: Shell, program: &str) -> String {
+    let mut commands = Vec::new();
+    collect(shape, vec![program.to_string()], &mut commands);
+    match shell {
+        Shell::Bash => bash(program, &commands),
+        Shell::Zsh => zsh(program, &commands),
+        Shell::Fish => fish(program, &commands),
+    }
+}
+
+/// A command or subcommand, as far as completion is concerned
+struct Command {
+    /// The program and subcommand names leading to it
+    path: Vec<String>,
+    flags: Vec<Flag>,
+    /// Its subcommands, with their descriptions
+    commands: Vec<(String, String)>,
+    /// What its positional arguments complete to, if it takes any
+    positionals: Option<Value>,
+}
+
+impl Command {
+    /// Identifies the command in the scripts, e.g. `app__db__migrate`
+    fn key(&self) -> String {
+        self.path.join("__")
+    }
+}
+
+struct Flag {
+    long: String,
+    short: Option<&'static str>,
+    description: String,
+    /// What the flag's value completes to, if it takes one
+    value: Option<Value>,
+}
+
+impl Flag {
+    /// `--format` and `-f`
+    fn spellings(&self) -> Vec<String> {
+        let mut spellings = vec![format!("--{}", self.long)];
+        spellings.extend(self.short.map(|short| format!("-{short}")));
+        spellings
+    }
+}
+
+enum Value {
+    Any,
+    Path,
+    Choices(Vec<String>),
+}
+
+/// Adds the command for `shape` and all of its subcommands to `out`
+fn collect(shape: &'static Shape, path: Vec<String>, out: &mut Vec<Command>) {
+    match shape.def {
+        Def::Struct(sd) => collect_fields(sd.fields, path, out),
+        Def::Enum(ed) => {
+            let mut children = Vec::new();
+            let commands = collect_variants(shape, ed, &path, &mut children);
+            out.push(Command {
+                path,
+                flags: help_flag(&[]).into_iter().collect(),
+                commands,
+                positionals: None,
+            });
+            out.extend(children);
+        }
+        _ => collect_fields(&[], path, out),
+    }
+}
+
+fn collect_fields(fields: &'static [Field], path: Vec<String>, out: &mut Vec<Command>) {
+    let mut flags: Vec<Flag> = fields
+        .iter()
+        .filter(|f| is_flag(f))
+        .map(|f| Flag {
+            long: long_name(f),
+            short: arbitrary_attr(f, "short"),
+            description: paragraph(f.doc),
+            value: value(f),
+        })
+        .collect();
+    flags.extend(help_flag(fields));
+
+    let mut children = Vec::new();
+    let commands = match fields
+        .iter()
+        .find(|f| is_subcommand(f))
+        .and_then(subcommand_enum)
+    {
+        Some((shape, ed)) => collect_variants(shape, ed, &path, &mut children),
+        None => Vec::new(),
+    };
+
+    out.push(Command {
+        path,
+        flags,
+        commands,
+        positionals: fields
+            .iter()
+            .find(|f| is_positional(f))
+            .map(|f| value(f).unwrap_or(Value::Any)),
+    });
+    out.extend(children);
+}
+
+/// Adds a command for each variant to `out`, and returns their names and
+/// descriptions
+fn collect_variants(
+    shape: &'static Shape,
+    ed: EnumDef,
+    path: &[String],
+    out: &mut Vec<Command>,
+) -> Vec<(String, String)> {
+    for variant in ed.variants {
+        let mut path = path.to_vec();
+        path.push(variant_name(shape, variant));
+        if is_newtype(variant) {
+            collect(variant.data.fields[0].shape(), path, out);
+        } else {
+            collect_fields(variant.data.fields, path, out);
+        }
+    }
+    command_rows(shape, ed)
+}
+
+/// The built-in `--help`, unless a field took its name
+fn help_flag(fields: &[Field]) -> Option<Flag> {
+    find_long(fields, "help").is_none().then(|| Flag {
+        long: "help".to_string(),
+        short: find_short(fields, "h").is_none().then_some("h"),
+        description: "Print help".to_string(),
+        value: None,
+    })
+}
+
+fn value(field: &Field) -> Option<Value> {
+    if !takes_value(field) {
+        return None;
+    }
+    let shape = value_shape(field);
+    Some(match shape.def {
+        Def::Scalar(sd) if matches!(sd.affinity, ScalarAffinity::Path(_)) => Value::Path,
+        Def::Enum(ed) => Value::Choices(choices(shape, ed)),
+        _ => Value::Any,
+    })
+}
+
+/// Turns a program name into something usable in a function name
+fn ident(program: &str) -> String {
+    program
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// The keys of every subcommand, for the loop that works out which
+/// subcommand is being completed
+fn subcommand_keys(commands: &[Command]) -> Vec<String> {
+    commands.iter().skip(1).map(Command::key).collect()
+}
+
+fn bash(program: &str, commands: &[Command]) -> String {
+    let func = format!("_{}", ident(program));
+    let mut out = String::new();
+
+    writeln!(out, "{func}() {{").unwrap();
+    writeln!(out, "    local cur prev cmd i").unwrap();
+    writeln!(out, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"").unwrap();
+    writeln!(out, "    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"").unwrap();
+    writeln!(out, "    cmd=\"{program}\"").unwrap();
+    let keys = subcommand_keys(commands);
+    if !keys.is_empty() {
+        writeln!(out, "    for ((i = 1; i < COMP_CWORD; i++)); do").unwrap();
+        writeln!(out, "        case \"${{cmd}}__${{COMP_WORDS[i]}}\" in").unwrap();
+        writeln!(out, "            {})", keys.join("|")).unwrap();
+        writeln!(out, "                cmd=\"${{cmd}}__${{COMP_WORDS[i]}}\"").unwrap();
+        writeln!(out, "                ;;").unwrap();
+        writeln!(out, "        esac").unwrap();
+        writeln!(out, "    done").unwrap();
+    }
+    writeln!(out).unwrap();
+
+    writeln!(out, "    case \"${{cmd}}\" in").unwrap();
+    for command in commands {
+        writeln!(out, "        {})", command.key()).unwrap();
+
+        let value_flags: Vec<&Flag> = command.flags.iter().filter(|f| f.value.is_some()).collect();
+        if !value_flags.is_empty() {
+            writeln!(out, "            case \"${{prev}}\" in").unwrap();
+            for flag in value_flags {
+                writeln!(out, "                {})", flag.spellings().join("|")).unwrap();
+                match &flag.value {
+                    Some(Value::Path) => {
+                        writeln!(
+                            out,
+                            "                    COMPREPLY=($(compgen -f -- \"${{cur}}\"))"
+                        )
+                        .unwrap();
+                    }
+                    Some(Value::Choices(choices)) => {
+                        writeln!(
+                            out,
+                            "                    COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))",
+                            choices.join(" ")
+                        )
+                        .unwrap();
+                    }
+                    _ => {}
+                }
+                writeln!(out, "                    return 0").unwrap();
+                writeln!(out, "                    ;;").unwrap();
+            }
+            writeln!(out, "            esac").unwrap();
+        }
+
+        let spellings: Vec<String> = command.flags.iter().flat_map(Flag::spellings).collect();
+        let mut words: Vec<&str> = command
+            .commands
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if let Some(Value::Choices(choices)) = &command.positionals {
+            words.extend(choices.iter().map(String::as_str));
+        }
+        let mut replies = Vec::new();
+        if !words.is_empty() {
+            replies.push(format!(
+                "$(compgen -W \"{}\" -- \"${{cur}}\")",
+                words.join(" ")
+            ));
+        }
+        if let Some(Value::Path) = &command.positionals {
+            replies.push("$(compgen -f -- \"${cur}\")".to_string());
+        }
+
+        writeln!(out, "            if [[ \"${{cur}}\" == -* ]]; then").unwrap();
+        writeln!(
+            out,
+            "                COMPREPLY=($(compgen -W \"{}\" -- \"${{cur}}\"))",
+            spellings.join(" ")
+        )
+        .unwrap();
+        writeln!(out, "            else").unwrap();
+        writeln!(out, "                COMPREPLY=({})", replies.join(" ")).unwrap();
+        writeln!(out, "            fi").unwrap();
+        writeln!(out, "            ;;").unwrap();
+    }
+    writeln!(out, "    esac").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "complete -F {func} {program}").unwrap();
+
+    out
+}
+
+fn zsh(program: &str, commands: &[Command]) -> String {
+    let func = format!("_{}", ident(program));
+    let mut out = String::new();
+
+    writeln!(out, "#compdef {program}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "{func}() {{").unwrap();
+    writeln!(out, "    local cmd=\"{program}\" i").unwrap();
+    let keys = subcommand_keys(commands);
+    if !keys.is_empty() {
+        writeln!(out, "    for ((i = 2; i < CURRENT; i++)); do").unwrap();
+        writeln!(out, "        case \"${{cmd}}__${{words[i]}}\" in").unwrap();
+        writeln!(out, "            {})", keys.join("|")).unwrap();
+        writeln!(out, "                cmd=\"${{cmd}}__${{words[i]}}\"").unwrap();
+        writeln!(out, "                ;;").unwrap();
+        writeln!(out, "        esac").unwrap();
+        writeln!(out, "    done").unwrap();
+    }
+    writeln!(out).unwrap();
+
+    writeln!(out, "    case \"${{cmd}}\" in").unwrap();
+    for command in commands {
+        writeln!(out, "        {})", command.key()).unwrap();
+
+        let value_flags: Vec<&Flag> = command.flags.iter().filter(|f| f.value.is_some()).collect();
+        if !value_flags.is_empty() {
+            writeln!(out, "            case \"${{words[CURRENT-1]}}\" in").unwrap();
+            for flag in value_flags {
+                writeln!(out, "                {})", flag.spellings().join("|")).unwrap();
+                match &flag.value {
+                    Some(Value::Path) => writeln!(out, "                    _files").unwrap(),
+                    Some(Value::Choices(choices)) => {
+                        writeln!(out, "                    compadd -- {}", choices.join(" "))
+                            .unwrap()
+                    }
+                    _ => {}
+                }
+                writeln!(out, "                    return").unwrap();
+                writeln!(out, "                    ;;").unwrap();
+            }
+            writeln!(out, "            esac").unwrap();
+        }
+
+        writeln!(
+            out,
+            "            if [[ \"${{words[CURRENT]}}\" == -* ]]; then"
+        )
+        .unwrap();
+        writeln!(out, "                local -a options=(").unwrap();
+        for flag in &command.flags {
+            for spelling in flag.spellings() {
+                writeln!(
+                    out,
+                    "                    {}",
+                    zsh_quote(&describe(&spelling, &flag.description))
+                )
+                .unwrap();
+            }
+        }
+        writeln!(out, "                )").unwrap();
+        writeln!(out, "                _describe -t options 'option' options").unwrap();
+        writeln!(out, "            else").unwrap();
+        let mut completes_anything = false;
+        if !command.commands.is_empty() {
+            writeln!(out, "                local -a commands=(").unwrap();
+            for (name, description) in &command.commands {
+                writeln!(
+                    out,
+                    "                    {}",
+                    zsh_quote(&describe(name, description))
+                )
+                .unwrap();
+            }
+            writeln!(out, "                )").unwrap();
+            writeln!(
+                out,
+                "                _describe -t commands 'command' commands"
+            )
+            .unwrap();
+            completes_anything = true;
+        }
+        match &command.positionals {
+            Some(Value::Path) => {
+                writeln!(out, "                _files").unwrap();
+                completes_anything = true;
+            }
+            Some(Value::Choices(choices)) => {
+                writeln!(out, "                compadd -- {}", choices.join(" ")).unwrap();
+                completes_anything = true;
+            }
+            _ => {}
+        }
+        if !completes_anything {
+            writeln!(out, "                return 1").unwrap();
+        }
+        writeln!(out, "            fi").unwrap();
+        writeln!(out, "            ;;").unwrap();
+    }
+    writeln!(out, "    esac").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "if [ \"$funcstack[1]\" = \"{func}\" ]; then").unwrap();
+    writeln!(out, "    {func} \"$@\"").unwrap();
+    writeln!(out, "else").unwrap();
+    writeln!(out, "    compdef {func} {program}").unwrap();
+    writeln!(out, "fi").unwrap();
+
+    out
+}
+
+/// An entry for zsh's `_describe`: `name:description`
+fn describe(name: &str, description: &str) -> String {
+    let name = name.replace(':', "\\:");
+    if description.is_empty() {
+        name
+    } else {
+        format!("{name}:{description}")
+    }
+}
+
+fn zsh_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+fn fish(program: &str, commands: &[Command]) -> String {
+    let func = format!("__fish_{}_command", ident(program));
+    let mut out = String::new();
+
+    writeln!(out, "function {func}").unwrap();
+    writeln!(out, "    set -l cmd {program}").unwrap();
+    let keys = subcommand_keys(commands);
+    if !keys.is_empty() {
+        writeln!(out, "    set -l words (commandline -opc)").unwrap();
+        writeln!(out, "    set -e words[1]").unwrap();
+        writeln!(out, "    for word in $words").unwrap();
+        writeln!(out, "        switch \"$cmd\"__\"$word\"").unwrap();
+        writeln!(out, "            case {}", keys.join(" ")).unwrap();
+        writeln!(out, "                set cmd \"$cmd\"__\"$word\"").unwrap();
+        writeln!(out, "        end").unwrap();
+        writeln!(out, "    end").unwrap();
+    }
+    writeln!(out, "    echo $cmd").unwrap();
+    writeln!(out, "end").unwrap();
+    writeln!(out).unwrap();
+
+    // Only path-typed arguments complete to files
+    writeln!(out, "complete -c {program} -f").unwrap();
+    for command in commands {
+        let prefix = format!(
+            "complete -c {program} -n {}",
+            fish_quote(&format!("test ({func}) = {}", command.key()))
+        );
+        for flag in &command.flags {
+            let mut line = prefix.clone();
+            if let Some(short) = flag.short {
+                write!(line, " -s {short}").unwrap();
+            }
+            write!(line, " -l {}", flag.long).unwrap();
+            match &flag.value {
+                Some(Value::Any) => line.push_str(" -r"),
+                Some(Value::Path) => line.push_str(" -r -F"),
+                Some(Value::Choices(choices)) => {
+                    write!(line, " -x -a {}", fish_quote(&choices.join(" "))).unwrap()
+                }
+                None => {}
+            }
+            writeln!(out, "{line}{}", fish_description(&flag.description)).unwrap();
+        }
+        for (name, description) in &command.commands {
+            writeln!(
+                out,
+                "{prefix} -a {}{}",
+                fish_quote(name),
+                fish_description(description)
+            )
+            .unwrap();
+        }
+        match &command.positionals {
+            Some(Value::Path) => writeln!(out, "{prefix} -F").unwrap(),
+            Some(Value::Choices(choices)) => {
+                writeln!(out, "{prefix} -a {}", fish_quote(&choices.join(" "))).unwrap()
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+fn fish_description(description: &str) -> String {
+    if description.is_empty() {
+        String::new()
+    } else {
+        format!(" -d {}", fish_quote(description))
+    }
+}
+
+fn fish_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
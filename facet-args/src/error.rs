@@ -0,0 +1,186 @@
+use facet_core::{ParseError, Shape};
+use facet_reflect::ReflectError;
+
+/// An error that occurred while parsing command-line arguments
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgsError {
+    /// What went wrong
+    pub kind: ArgsErrorKind,
+}
+
+impl ArgsError {
+    pub(crate) fn new(kind: ArgsErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+/// The different ways parsing command-line arguments can fail
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ArgsErrorKind {
+    /// A flag that doesn't match any field
+    UnknownFlag {
+        /// The flag as given, e.g. `--verbos`
+        flag: String,
+        /// The closest known flag, if any is close enough to be a likely typo
+        suggestion: Option<String>,
+    },
+
+    /// A subcommand name that doesn't match any variant
+    UnknownSubcommand {
+        /// The name as given, e.g. `serv`
+        name: String,
+        /// The closest known subcommand, if any is close enough to be a likely typo
+        suggestion: Option<String>,
+    },
+
+    /// A flag that takes a value was given none
+    MissingValue {
+        /// The flag, e.g. `--jobs`
+        flag: String,
+    },
+
+    /// A flag that takes no value was given one, as in `--verbose=3` for a
+    /// counting flag
+    UnexpectedValue {
+        /// The flag, e.g. `--verbose`
+        flag: String,
+        /// The value as given
+        value: String,
+    },
+
+    /// The value given for a field could not be parsed into the field's type
+    InvalidValue {
+        /// The flag or positional argument, e.g. `--jobs` or `<path>`
+        flag: String,
+        /// The value as given
+        value: String,
+        /// The type of the field
+        shape: &'static Shape,
+        /// Why the type's `parse` function rejected it
+        error: ParseError,
+    },
+
+    /// The value given for an enum-typed field isn't one of its variants
+    InvalidChoice {
+        /// The flag or positional argument, e.g. `--format` or `<shell>`
+        flag: String,
+        /// The value as given
+        value: String,
+        /// The values that would have been accepted
+        choices: Vec<String>,
+    },
+
+    /// A field without a default was not given on the command line, nor
+    /// found in any other source
+    MissingRequired {
+        /// The flag or positional argument, e.g. `--jobs` or `<path>`, or
+        /// the dotted path of a field in a group, e.g. `db.url`
+        flag: String,
+        /// The other places that were looked in, e.g. ``environment variable
+        /// `APP_JOBS` ``
+        sources: Vec<String>,
+    },
+
+    /// A config file could not be read or parsed
+    InvalidConfig {
+        /// The path of the file, or the name it was given
+        path: String,
+        /// The line the problem is on, starting from 1
+        line: Option<usize>,
+        /// What's wrong
+        message: String,
+    },
+
+    /// A positional argument with no positional field left to put it in
+    UnexpectedPositional {
+        /// The argument as given
+        value: String,
+    },
+
+    /// `--help` or `-h` was given. Not a failure as such: the help text
+    /// should be printed to stdout and the program should exit successfully.
+    HelpRequested {
+        /// The generated help text
+        help: String,
+    },
+
+    /// The target type can't be built from arguments
+    ReflectError(ReflectError),
+}
+
+impl core::fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.kind {
+            ArgsErrorKind::UnknownFlag { flag, suggestion } => {
+                write!(f, "unknown flag `{flag}`")?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (did you mean `{suggestion}`?)")?;
+                }
+                Ok(())
+            }
+            ArgsErrorKind::UnknownSubcommand { name, suggestion } => {
+                write!(f, "unknown subcommand `{name}`")?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (did you mean `{suggestion}`?)")?;
+                }
+                Ok(())
+            }
+            ArgsErrorKind::MissingValue { flag } => {
+                write!(f, "`{flag}` expects a value")
+            }
+            ArgsErrorKind::UnexpectedValue { flag, value } => {
+                write!(f, "`{flag}` doesn't take a value, but was given `{value}`")
+            }
+            ArgsErrorKind::InvalidValue {
+                flag,
+                value,
+                shape,
+                error,
+            } => {
+                write!(f, "invalid value `{value}` for `{flag}` ({shape}): {error}")
+            }
+            ArgsErrorKind::InvalidChoice {
+                flag,
+                value,
+                choices,
+            } => {
+                write!(
+                    f,
+                    "invalid value `{value}` for `{flag}` (possible values: {})",
+                    choices.join(", ")
+                )
+            }
+            ArgsErrorKind::MissingRequired { flag, sources } => {
+                write!(f, "missing required argument `{flag}`")?;
+                if !sources.is_empty() {
+                    write!(f, " (also looked for {})", sources.join(", "))?;
+                }
+                Ok(())
+            }
+            ArgsErrorKind::InvalidConfig {
+                path,
+                line: Some(line),
+                message,
+            } => write!(f, "{path}:{line}: {message}"),
+            ArgsErrorKind::InvalidConfig {
+                path,
+                line: None,
+                message,
+            } => write!(f, "{path}: {message}"),
+            ArgsErrorKind::UnexpectedPositional { value } => {
+                write!(f, "unexpected positional argument `{value}`")
+            }
+            ArgsErrorKind::HelpRequested { help } => write!(f, "{help}"),
+            ArgsErrorKind::ReflectError(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl core::error::Error for ArgsError {}
+
+impl From<ReflectError> for ArgsError {
+    fn from(e: ReflectError) -> Self {
+        Self::new(ArgsErrorKind::ReflectError(e))
+    }
+}
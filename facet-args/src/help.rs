@@ -0,0 +1,213 @@
+use core::fmt::Write;
+
+use facet_core::{Def, EnumDef, Facet, Field, Shape};
+use facet_reflect::Wip;
+
+use crate::{
+    arbitrary_attr, choices, is_flag, is_list, is_newtype, is_positional, is_subcommand, long_name,
+    subcommand_enum, takes_value, variant_name,
+};
+
+/// Generates the `--help` text for `T`, as `program` would print it.
+///
+/// The description is taken from the doc comment on `T`, and each argument is
+/// listed with its doc comment's first paragraph.
+pub fn help<'facet, T: Facet<'facet>>(program: &str) -> String {
+    help_for_shape(T::SHAPE, program)
+}
+
+/// Generates the `--help` text for a shape. See [`help`].
+///
+/// For an enum, this lists its variants as subcommands.
+pub fn help_for_shape(shape: &'static Shape, program: &str) -> String {
+    match shape.def {
+        Def::Struct(sd) => help_for_fields(shape.doc, sd.fields, program),
+        Def::Enum(ed) => {
+            let mut out = String::new();
+            write_description(&mut out, shape.doc);
+            writeln!(out, "Usage: {program} <command>").unwrap();
+            write_section(&mut out, "Commands", &command_rows(shape, ed));
+            write_section(&mut out, "Options", &[help_row()]);
+            out
+        }
+        _ => help_for_fields(shape.doc, &[], program),
+    }
+}
+
+/// Generates the help text for a struct or enum variant with the given
+/// fields.
+pub(crate) fn help_for_fields(
+    doc: &'static [&'static str],
+    fields: &'static [Field],
+    program: &str,
+) -> String {
+    let mut out = String::new();
+    write_description(&mut out, doc);
+
+    let positionals: Vec<&'static Field> = fields.iter().filter(|f| is_positional(f)).collect();
+    let options: Vec<&'static Field> = fields.iter().filter(|f| is_flag(f)).collect();
+    let subcommand = fields.iter().find(|f| is_subcommand(f));
+
+    write!(out, "Usage: {program} [OPTIONS]").unwrap();
+    for &field in &positionals {
+        write!(out, " {}", positional_name(field)).unwrap();
+    }
+    if let Some(field) = subcommand {
+        if matches!(field.shape().def, Def::Option(_)) {
+            write!(out, " [command]").unwrap();
+        } else {
+            write!(out, " <command>").unwrap();
+        }
+    }
+    writeln!(out).unwrap();
+
+    if !positionals.is_empty() {
+        let rows = positionals
+            .iter()
+            .map(|&f| (positional_name(f), describe(f)))
+            .collect::<Vec<_>>();
+        write_section(&mut out, "Arguments", &rows);
+    }
+
+    if let Some((shape, ed)) = subcommand.and_then(subcommand_enum) {
+        write_section(&mut out, "Commands", &command_rows(shape, ed));
+    }
+
+    let mut rows = options
+        .iter()
+        .map(|&f| (option_name(f), describe(f)))
+        .collect::<Vec<_>>();
+    rows.push(help_row());
+    write_section(&mut out, "Options", &rows);
+
+    out
+}
+
+fn write_description(out: &mut String, doc: &[&str]) {
+    let description = paragraph(doc);
+    if !description.is_empty() {
+        writeln!(out, "{description}\n").unwrap();
+    }
+}
+
+fn help_row() -> (String, String) {
+    ("-h, --help".to_string(), "Print help".to_string())
+}
+
+/// One row per subcommand. A variant without a doc comment of its own
+/// borrows the one on the type it wraps.
+pub(crate) fn command_rows(shape: &'static Shape, ed: EnumDef) -> Vec<(String, String)> {
+    ed.variants
+        .iter()
+        .map(|variant| {
+            let mut text = paragraph(variant.doc);
+            if text.is_empty() && is_newtype(variant) {
+                text = paragraph(variant.data.fields[0].shape().doc);
+            }
+            (variant_name(shape, variant), text)
+        })
+        .collect()
+}
+
+fn write_section(out: &mut String, title: &str, rows: &[(String, String)]) {
+    let width = rows.iter().map(|(left, _)| left.len()).max().unwrap_or(0);
+    writeln!(out, "\n{title}:").unwrap();
+    for (left, right) in rows {
+        if right.is_empty() {
+            writeln!(out, "  {left}").unwrap();
+        } else {
+            writeln!(out, "  {left:width$}  {right}").unwrap();
+        }
+    }
+}
+
+/// `<path>` when the argument is required, `[path]` otherwise, and
+/// `[paths]...` when it takes any number of values
+fn positional_name(field: &'static Field) -> String {
+    if is_list(field) {
+        format!("[{}]...", field.name)
+    } else if is_optional(field) {
+        format!("[{}]", field.name)
+    } else {
+        format!("<{}>", field.name)
+    }
+}
+
+/// `-j, --concurrency <usize>`, with the type unwrapped from `Option`, and
+/// `--include <String>...` for flags that can be repeated
+fn option_name(field: &'static Field) -> String {
+    let mut name = match arbitrary_attr(field, "short") {
+        Some(short) => format!("-{short}, "),
+        None => "    ".to_string(),
+    };
+    write!(name, "--{}", long_name(field)).unwrap();
+
+    if takes_value(field) {
+        write!(name, " <{}>", value_shape(field)).unwrap();
+        if is_list(field) {
+            name.push_str("...");
+        }
+    }
+    name
+}
+
+/// The doc comment, followed by the default value if there is one worth
+/// showing
+fn describe(field: &'static Field) -> String {
+    let mut text = paragraph(field.doc);
+    let mut note = |extra: String| {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&extra);
+    };
+
+    if let Some(default) = default_value(field) {
+        note(format!("[default: {default}]"));
+    }
+    let shape = value_shape(field);
+    if let Def::Enum(ed) = shape.def {
+        note(format!(
+            "[possible values: {}]",
+            choices(shape, ed).join(", ")
+        ));
+    }
+    text
+}
+
+fn is_optional(field: &'static Field) -> bool {
+    matches!(field.shape().def, Def::Option(_)) || field.get_default_attr().is_some()
+}
+
+pub(crate) fn value_shape(field: &Field) -> &'static Shape {
+    match field.shape().def {
+        Def::Option(od) => od.t(),
+        Def::List(ld) => ld.t(),
+        _ => field.shape(),
+    }
+}
+
+/// Renders the value a `#[facet(default)]` field gets when omitted.
+fn default_value(field: &'static Field) -> Option<String> {
+    let default_fn = field.get_default_attr()?;
+    let shape = field.shape();
+    shape.vtable.display?;
+
+    let wip = Wip::alloc_shape(shape).ok()?;
+    let wip = match default_fn {
+        Some(default_fn) => wip.put_from_fn(default_fn),
+        None => wip.put_default(),
+    };
+    let value = wip.ok()?.build().ok()?;
+    Some(value.peek().to_string())
+}
+
+/// Joins doc comment lines up to the first blank one.
+pub(crate) fn paragraph(doc: &[&str]) -> String {
+    doc.iter()
+        .map(|line| line.trim())
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
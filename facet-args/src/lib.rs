@@ -4,106 +4,743 @@
 #![forbid(unsafe_code)]
 #![doc = include_str!("../README.md")]
 
-use facet_core::{Def, Facet, FieldAttribute};
+extern crate alloc;
+
+use alloc::borrow::Cow;
+
+use facet_core::{Def, EnumDef, Facet, Field, FieldAttribute, Shape, StructKind, Variant};
 use facet_reflect::{ReflectError, Wip};
 
-fn parse_field<'facet>(wip: Wip<'facet>, value: &'facet str) -> Result<Wip<'facet>, ReflectError> {
+mod error;
+pub use error::*;
+
+mod completions;
+pub use completions::*;
+
+mod help;
+pub use help::*;
+
+mod sources;
+pub use sources::*;
+
+/// Parses command-line arguments.
+///
+/// `T` is either a struct, whose fields are the flags and positional
+/// arguments, or an enum, whose variants are subcommands. Structs can also
+/// hold subcommands in a field marked `#[facet(subcommand)]`.
+///
+/// `--help` and `-h` (unless a field claims them) stop parsing with
+/// [`ArgsErrorKind::HelpRequested`], which holds the generated help text for
+/// the (sub)command they were given to.
+pub fn from_slice<'input, 'facet, T>(args: &[&'input str]) -> Result<T, ArgsError>
+where
+    T: Facet<'facet>,
+    'input: 'facet,
+{
+    log::trace!("Entering from_slice function");
+    Parser::new().parse(args)
+}
+
+/// Parses the arguments the program was started with.
+///
+/// If help was requested, prints it to stdout and exits successfully. On
+/// error, prints it to stderr and exits with status 2, the conventional
+/// status for command-line usage errors.
+pub fn from_std_args<T>() -> T
+where
+    T: for<'facet> Facet<'facet>,
+{
+    Parser::new().parse_std_args()
+}
+
+/// The name the program was invoked as, for usage lines. Falls back on the
+/// type's name if the platform doesn't tell.
+fn program_name(shape: &'static Shape) -> String {
+    std::env::args_os()
+        .next()
+        .and_then(|arg0| {
+            let path = std::path::PathBuf::from(arg0);
+            Some(path.file_stem()?.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| shape.to_string().to_lowercase())
+}
+
+/// Parses `args` into the current frame, which is a struct or an enum of
+/// subcommands. `program` is the command line up to here, for help output.
+fn parse_command<'input: 'facet, 'facet>(
+    wip: Wip<'facet>,
+    args: &[&'input str],
+    program: &str,
+    fallbacks: &Fallbacks,
+) -> Result<Wip<'facet>, ArgsError> {
     let shape = wip.shape();
     match shape.def {
-        Def::Scalar(_) => {
-            if shape.is_type::<String>() {
-                wip.put(value.to_string())
-            } else if shape.is_type::<&str>() {
-                wip.put(value)
-            } else if shape.is_type::<bool>() {
-                log::trace!("Boolean field detected, setting to true");
-                wip.put(value.to_lowercase() == "true")
+        Def::Struct(sd) => parse_fields(wip, sd.fields, shape.doc, args, program, fallbacks),
+        Def::Enum(ed) => {
+            let Some((&name, rest)) = args.split_first() else {
+                return Err(ArgsError::new(ArgsErrorKind::MissingRequired {
+                    flag: "<command>".to_string(),
+                }));
+            };
+            if name == "--help" || name == "-h" {
+                return Err(ArgsError::new(ArgsErrorKind::HelpRequested {
+                    help: help_for_shape(shape, program),
+                }));
+            }
+            let Some(index) = find_subcommand(shape, ed, name) else {
+                return Err(ArgsError::new(ArgsErrorKind::UnknownSubcommand {
+                    name: name.to_string(),
+                    suggestion: closest(variant_names(shape, ed), name),
+                }));
+            };
+
+            log::trace!("Selected subcommand: {}", name);
+            let variant = &ed.variants[index];
+            let program = format!("{program} {name}");
+            let wip = wip.variant(index)?;
+            if is_newtype(variant) {
+                let wip = parse_command(wip.field(0)?, rest, &program, fallbacks)?;
+                Ok(wip.pop()?)
             } else {
-                wip.parse(value)
+                parse_fields(
+                    wip,
+                    variant.data.fields,
+                    variant.doc,
+                    rest,
+                    &program,
+                    fallbacks,
+                )
             }
         }
-        _def => {
-            return Err(ReflectError::OperationFailed {
-                shape,
-                operation: "parsing field",
-            });
+        _ => Err(ReflectError::WasNotA {
+            expected: "struct or enum",
+            actual: shape,
         }
-    }?
-    .pop()
+        .into()),
+    }
 }
 
-/// Parses command-line arguments
-pub fn from_slice<'input, 'facet, T>(s: &[&'input str]) -> T
-where
-    T: Facet<'facet>,
-    'input: 'facet,
-{
-    log::trace!("Entering from_slice function");
-    let mut s = s;
-    let mut wip = Wip::alloc::<T>().expect("failed to allocate");
-    log::trace!("Allocated Poke for type T");
-
-    while let Some(token) = s.first() {
-        log::trace!("Processing token: {}", token);
-        s = &s[1..];
-
-        if let Some(key) = token.strip_prefix("--") {
-            log::trace!("Found named argument: {}", key);
-            let field_index = match wip.field_index(key) {
-                Some(index) => index,
-                None => panic!("Unknown argument: {}", key),
+/// Values that are only written once every argument has been seen, because
+/// they're built up over several of them.
+struct Pending<'input> {
+    /// The elements given so far for each `Vec` field, by field index
+    lists: Vec<Vec<&'input str>>,
+    /// How many times each counting flag was given, by field index
+    counts: Vec<usize>,
+}
+
+/// Parses `args` into `fields`, which belong to the struct or enum variant
+/// in the current frame.
+fn parse_fields<'input: 'facet, 'facet>(
+    mut wip: Wip<'facet>,
+    fields: &'static [Field],
+    doc: &'static [&'static str],
+    args: &[&'input str],
+    program: &str,
+    fallbacks: &Fallbacks,
+) -> Result<Wip<'facet>, ArgsError> {
+    let mut pending = Pending {
+        lists: vec![Vec::new(); fields.len()],
+        counts: vec![0; fields.len()],
+    };
+    let mut rest = args;
+    let mut options_ended = false;
+
+    while let Some((&arg, tail)) = rest.split_first() {
+        log::trace!("Processing token: {}", arg);
+
+        if options_ended {
+            wip = parse_positional(wip, &mut pending, fields, arg)?;
+            rest = tail;
+            continue;
+        }
+
+        if arg == "--" {
+            log::trace!("End of options");
+            options_ended = true;
+            rest = tail;
+            continue;
+        }
+
+        if (arg == "--help" && find_long(fields, "help").is_none())
+            || (arg == "-h" && find_short(fields, "h").is_none())
+        {
+            return Err(ArgsError::new(ArgsErrorKind::HelpRequested {
+                help: help_for_fields(doc, fields, program),
+            }));
+        }
+
+        if let Some(long) = arg.strip_prefix("--") {
+            log::trace!("Found named argument: {}", long);
+            let (key, inline) = match long.split_once('=') {
+                Some((key, value)) => (key, Some(value)),
+                None => (long, None),
             };
-            let field = wip.field(field_index).unwrap();
+            let flag = format!("--{key}");
 
-            if field.shape().is_type::<bool>() {
-                wip = parse_field(field, "true").unwrap();
+            if let Some(index) = find_long(fields, key) {
+                (wip, rest) = parse_flag(wip, &mut pending, fields, index, &flag, inline, tail)?;
+            } else if let Some(index) = key
+                .strip_prefix("no-")
+                .and_then(|key| find_long(fields, key))
+                .filter(|&index| fields[index].shape().is_type::<bool>() && inline.is_none())
+            {
+                log::trace!("Negated switch: {}", key);
+                wip = set_value(wip, index, &flag, "false")?;
+                rest = tail;
             } else {
-                let value = s.first().expect("expected value after argument");
-                log::trace!("Field value: {}", value);
-                s = &s[1..];
-                wip = parse_field(field, value).unwrap();
+                return Err(ArgsError::new(ArgsErrorKind::UnknownFlag {
+                    flag,
+                    suggestion: closest(fields.iter().filter(|f| is_flag(f)).map(long_name), key)
+                        .map(|name| format!("--{name}")),
+                }));
             }
-        } else if let Some(key) = token.strip_prefix("-") {
-            log::trace!("Found short named argument: {}", key);
-            let Def::Struct(sd) = wip.shape().def else {
-                panic!("Expected struct definition");
-            };
-            for (field_index, f) in sd.fields.iter().enumerate() {
-                if f.attributes
-                    .iter()
-                    .any(|a| matches!(a, FieldAttribute::Arbitrary(a) if a.contains("short") && a.contains(key))
-                   )
-                {
-                    log::trace!("Found field matching short_code: {} for field {}", key, f.name);
-                    let field = wip.field(field_index).unwrap();
-                    if field.shape().is_type::<bool>() {
-                        wip = parse_field(field, "true").unwrap();
-                    } else {
-                        let value = s.first().expect("expected value after argument");
-                        log::trace!("Field value: {}", value);
-                        s = &s[1..];
-                        wip = parse_field(field, value).unwrap();
-                    }
+        } else if let Some(bundle) = arg
+            .strip_prefix('-')
+            .filter(|key| !key.is_empty() && !looks_numeric(key))
+        {
+            log::trace!("Found short named arguments: {}", bundle);
+            // `-vvx` is `-v -v -x`, and `-j4` is `-j 4`: the first flag that
+            // takes a value takes the rest of the bundle, if there is any
+            rest = tail;
+            for (i, c) in bundle.char_indices() {
+                let short = &bundle[i..i + c.len_utf8()];
+                let flag = format!("-{short}");
+                let Some(index) = find_short(fields, short) else {
+                    return Err(ArgsError::new(ArgsErrorKind::UnknownFlag {
+                        flag,
+                        suggestion: suggest_short(fields, bundle, short),
+                    }));
+                };
+
+                if takes_value(&fields[index]) {
+                    let remainder = &bundle[i + c.len_utf8()..];
+                    let inline = Some(remainder.strip_prefix('=').unwrap_or(remainder))
+                        .filter(|value| !value.is_empty());
+                    (wip, rest) =
+                        parse_flag(wip, &mut pending, fields, index, &flag, inline, rest)?;
                     break;
                 }
+                (wip, rest) = parse_flag(wip, &mut pending, fields, index, &flag, None, rest)?;
             }
         } else {
-            log::trace!("Encountered positional argument: {}", token);
-            let Def::Struct(sd) = wip.shape().def else {
-                panic!("Expected struct definition");
-            };
-
-            for (field_index, f) in sd.fields.iter().enumerate() {
-                if f.attributes
-                    .iter()
-                    .any(|a| matches!(a, FieldAttribute::Arbitrary(a) if a.contains("positional")))
-                {
-                    let field = wip.field(field_index).unwrap();
-                    wip = parse_field(field, token).unwrap();
+            let mut subcommand = None;
+            for (index, field) in fields.iter().enumerate() {
+                if is_subcommand(field) && !wip.is_field_set(index)? {
+                    subcommand = Some(index);
                     break;
                 }
             }
+
+            // A subcommand name wins over a positional argument, but anything
+            // else goes to the positional argument if there's one left
+            let subcommand = subcommand.filter(|&index| {
+                next_positional(&wip, fields).is_none()
+                    || subcommand_enum(&fields[index])
+                        .is_some_and(|(shape, ed)| find_subcommand(shape, ed, arg).is_some())
+            });
+
+            if let Some(index) = subcommand {
+                log::trace!("Entering subcommand field: {}", fields[index].name);
+                // Everything from here on belongs to the subcommand
+                wip = parse_subcommand_field(wip, index, rest, program, fallbacks)?;
+                rest = &[];
+            } else {
+                wip = parse_positional(wip, &mut pending, fields, arg)?;
+                rest = tail;
+            }
+        }
+    }
+
+    let wip = write_pending(wip, pending, fields)?;
+    fill_missing(wip, fields, fallbacks, &[])
+}
+
+/// The positional field the next positional argument goes to: the first one
+/// not set yet, or failing that, one that takes any number of them.
+fn next_positional(wip: &Wip<'_>, fields: &[Field]) -> Option<usize> {
+    let mut index = None;
+    for (i, field) in fields.iter().enumerate() {
+        if !is_positional(field) {
+            continue;
+        }
+        if is_list(field) {
+            index = index.or(Some(i));
+        } else if !wip.is_field_set(i).unwrap_or(true) {
+            return Some(i);
+        }
+    }
+    index
+}
+
+fn parse_positional<'input: 'facet, 'facet>(
+    wip: Wip<'facet>,
+    pending: &mut Pending<'input>,
+    fields: &'static [Field],
+    arg: &'input str,
+) -> Result<Wip<'facet>, ArgsError> {
+    log::trace!("Encountered positional argument: {}", arg);
+    let Some(index) = next_positional(&wip, fields) else {
+        return Err(ArgsError::new(ArgsErrorKind::UnexpectedPositional {
+            value: arg.to_string(),
+        }));
+    };
+    if is_list(&fields[index]) {
+        pending.lists[index].push(arg);
+        Ok(wip)
+    } else {
+        set_value(wip, index, &display_name(&fields[index]), arg)
+    }
+}
+
+/// Parses the rest of the arguments into the `#[facet(subcommand)]` field at
+/// `index`, which may be optional.
+fn parse_subcommand_field<'input: 'facet, 'facet>(
+    wip: Wip<'facet>,
+    index: usize,
+    args: &[&'input str],
+    program: &str,
+    fallbacks: &Fallbacks,
+) -> Result<Wip<'facet>, ArgsError> {
+    let mut wip = wip.field(index)?;
+    let optional = matches!(wip.shape().def, Def::Option(_));
+    if optional {
+        wip = wip.push_some()?;
+    }
+    wip = parse_command(wip, args, program, fallbacks)?;
+    if optional {
+        wip = wip.pop()?;
+    }
+    Ok(wip.pop()?)
+}
+
+/// Handles a flag for the field at `index`. Its value is `inline` if it was
+/// given as `--key=value` or `-kvalue`, and is taken from `rest` otherwise,
+/// unless the flag is a switch or a counter.
+fn parse_flag<'input: 'facet, 'facet, 'a>(
+    wip: Wip<'facet>,
+    pending: &mut Pending<'input>,
+    fields: &'static [Field],
+    index: usize,
+    flag: &str,
+    inline: Option<&'input str>,
+    rest: &'a [&'input str],
+) -> Result<(Wip<'facet>, &'a [&'input str]), ArgsError> {
+    let field = &fields[index];
+    if is_counter(field) {
+        if let Some(value) = inline {
+            return Err(ArgsError::new(ArgsErrorKind::UnexpectedValue {
+                flag: flag.to_string(),
+                value: value.to_string(),
+            }));
+        }
+        pending.counts[index] += 1;
+        return Ok((wip, rest));
+    }
+    if field.shape().is_type::<bool>() {
+        return Ok((set_value(wip, index, flag, inline.unwrap_or("true"))?, rest));
+    }
+
+    let (value, rest) = match (inline, rest.split_first()) {
+        (Some(value), _) => (value, rest),
+        (None, Some((&value, rest))) if !value.starts_with("--") => (value, rest),
+        _ => {
+            return Err(ArgsError::new(ArgsErrorKind::MissingValue {
+                flag: flag.to_string(),
+            }));
+        }
+    };
+    log::trace!("Field value: {}", value);
+
+    if is_list(field) {
+        pending.lists[index].push(value);
+        Ok((wip, rest))
+    } else {
+        Ok((set_value(wip, index, flag, value)?, rest))
+    }
+}
+
+/// Writes the elements of `Vec` fields and the tallies of counting flags.
+fn write_pending<'input: 'facet, 'facet>(
+    mut wip: Wip<'facet>,
+    pending: Pending<'input>,
+    fields: &'static [Field],
+) -> Result<Wip<'facet>, ArgsError> {
+    for (index, values) in pending.lists.into_iter().enumerate() {
+        if values.is_empty() {
+            continue;
+        }
+        let flag = display_name(&fields[index]);
+        let mut list = wip.field(index)?.begin_pushback()?;
+        for value in values {
+            list = put_value(list.push()?, &flag, value)?.pop()?;
+        }
+        wip = list.pop()?;
+    }
+
+    for (index, count) in pending.counts.into_iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let flag = display_name(&fields[index]);
+        let count = count.to_string();
+        wip = wip
+            .field(index)?
+            .parse(&count)
+            .map_err(|e| value_error(e, &flag, &count))?
+            .pop()?;
+    }
+
+    Ok(wip)
+}
+
+/// Puts `value` into the field at `index`.
+fn set_value<'facet>(
+    wip: Wip<'facet>,
+    index: usize,
+    flag: &str,
+    value: impl Into<Cow<'facet, str>>,
+) -> Result<Wip<'facet>, ArgsError> {
+    Ok(put_value(wip.field(index)?, flag, value)?.pop()?)
+}
+
+/// Puts `value` into the current frame, wrapping it in `Some` if needed.
+fn put_value<'facet>(
+    wip: Wip<'facet>,
+    flag: &str,
+    value: impl Into<Cow<'facet, str>>,
+) -> Result<Wip<'facet>, ArgsError> {
+    let value = value.into();
+    let shape = wip.shape();
+    let res = match shape.def {
+        Def::Option(_) => {
+            let wip = put_value(wip.push_some()?, flag, value)?;
+            return Ok(wip.pop()?);
+        }
+        Def::Enum(ed) => {
+            let Some(index) = ed
+                .variants
+                .iter()
+                .position(|v| v.data.fields.is_empty() && variant_name(shape, v) == value)
+            else {
+                return Err(ArgsError::new(ArgsErrorKind::InvalidChoice {
+                    flag: flag.to_string(),
+                    value: value.into_owned(),
+                    choices: choices(shape, ed),
+                }));
+            };
+            wip.variant(index)
+        }
+        Def::Scalar(_) => {
+            if shape.is_type::<String>() {
+                wip.put(value.to_string())
+            } else if shape.is_type::<&str>() {
+                match &value {
+                    Cow::Borrowed(value) => wip.put(*value),
+                    // Values from the environment or a config file don't
+                    // outlive the parser
+                    Cow::Owned(_) => Err(ReflectError::OperationFailed {
+                        shape,
+                        operation: "borrowing a value that isn't from the command line",
+                    }),
+                }
+            } else if shape.is_type::<bool>() {
+                wip.parse(&value.to_lowercase())
+            } else {
+                wip.parse(&value)
+            }
+        }
+        _def => Err(ReflectError::OperationFailed {
+            shape,
+            operation: "parsing field",
+        }),
+    };
+    res.map_err(|e| value_error(e, flag, &value))
+}
+
+/// Turns a failure to parse `value` into an [`ArgsErrorKind::InvalidValue`]
+fn value_error(e: ReflectError, flag: &str, value: &str) -> ArgsError {
+    match e {
+        ReflectError::ParseFailed { shape, inner } => ArgsError::new(ArgsErrorKind::InvalidValue {
+            flag: flag.to_string(),
+            value: value.to_string(),
+            shape,
+            error: inner,
+        }),
+        e => e.into(),
+    }
+}
+
+/// Fills in every field the arguments didn't mention, from the fallbacks or
+/// from defaults, or reports the first one that can't be left out. `path`
+/// leads from the command's own fields to `fields`, when they belong to a
+/// group.
+fn fill_missing<'facet>(
+    mut wip: Wip<'facet>,
+    fields: &'static [Field],
+    fallbacks: &Fallbacks,
+    path: &[&'static str],
+) -> Result<Wip<'facet>, ArgsError> {
+    for (index, field) in fields.iter().enumerate() {
+        if wip.is_field_set(index)? {
+            continue;
+        }
+
+        let shape = field.shape();
+        if let Some(group) = group_fields(field) {
+            let mut path = path.to_vec();
+            path.push(field.name);
+            wip = fill_missing(wip.field(index)?, group, fallbacks, &path)?.pop()?;
+            continue;
+        }
+
+        let mut consulted = Vec::new();
+        let found = if is_subcommand(field) {
+            None
+        } else {
+            fallbacks.lookup(field, path, &mut consulted)
+        };
+        if let Some((name, value)) = found {
+            wip = set_fallback(wip, index, field, &name, value)?;
+            continue;
+        }
+
+        wip = match field.get_default_attr() {
+            Some(Some(default_fn)) => wip.field(index)?.put_from_fn(default_fn)?,
+            Some(None) => wip.field(index)?.put_default()?,
+            // Switches are off, counters are zero, options are `None` and
+            // lists are empty unless given
+            None if shape.is_type::<bool>()
+                || is_counter(field)
+                || matches!(shape.def, Def::Option(_) | Def::List(_)) =>
+            {
+                wip.field(index)?.put_default()?
+            }
+            None => {
+                let flag = if path.is_empty() {
+                    display_name(field)
+                } else {
+                    format!("{}.{}", path.join("."), field.name)
+                };
+                return Err(ArgsError::new(ArgsErrorKind::MissingRequired {
+                    flag,
+                    sources: consulted,
+                }));
+            }
+        }
+        .pop()?;
+    }
+    Ok(wip)
+}
+
+/// Puts a value from an environment variable or config file into the field
+/// at `index`. Lists are given as comma-separated values.
+fn set_fallback<'facet>(
+    wip: Wip<'facet>,
+    index: usize,
+    field: &Field,
+    name: &str,
+    value: String,
+) -> Result<Wip<'facet>, ArgsError> {
+    if !is_list(field) {
+        return set_value(wip, index, name, value);
+    }
+
+    let mut list = wip.field(index)?.begin_pushback()?;
+    for item in value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        list = put_value(list.push()?, name, item.to_string())?.pop()?;
+    }
+    Ok(list.pop()?)
+}
+
+/// Looks for the value of an arbitrary attribute such as `short = 'v'`
+fn arbitrary_attr(field: &Field, key: &str) -> Option<&'static str> {
+    field.attributes.iter().find_map(|attr| match attr {
+        FieldAttribute::Arbitrary(attr) => {
+            let (k, v) = attr.split_once('=')?;
+            (k.trim() == key).then(|| v.trim().trim_matches(|c| c == '\'' || c == '"'))
+        }
+        _ => None,
+    })
+}
+
+fn has_marker(field: &Field, marker: &str) -> bool {
+    field
+        .attributes
+        .iter()
+        .any(|attr| matches!(attr, FieldAttribute::Arbitrary(a) if a.trim() == marker))
+}
+
+fn is_positional(field: &Field) -> bool {
+    has_marker(field, "positional")
+}
+
+fn is_subcommand(field: &Field) -> bool {
+    has_marker(field, "subcommand")
+}
+
+/// Whether the field counts how many times its flag was given, e.g.
+/// `-vvv` for a verbosity level of 3
+fn is_counter(field: &Field) -> bool {
+    has_marker(field, "count")
+}
+
+fn is_list(field: &Field) -> bool {
+    matches!(field.shape().def, Def::List(_))
+}
+
+/// Whether the field's flag is followed by a value
+fn takes_value(field: &Field) -> bool {
+    !field.shape().is_type::<bool>() && !is_counter(field)
+}
+
+/// The fields of a group: a struct-typed field that isn't a flag itself,
+/// but whose fields are filled one by one from environment variables and
+/// config files
+fn group_fields(field: &Field) -> Option<&'static [Field]> {
+    match field.shape().def {
+        Def::Struct(sd) if !is_positional(field) && !is_subcommand(field) => Some(sd.fields),
+        _ => None,
+    }
+}
+
+/// Whether the field is given as `--name` or `-n`
+fn is_flag(field: &Field) -> bool {
+    !is_positional(field) && !is_subcommand(field) && group_fields(field).is_none()
+}
+
+/// The enum behind a `#[facet(subcommand)]` field, looking through `Option`
+fn subcommand_enum(field: &Field) -> Option<(&'static Shape, EnumDef)> {
+    let shape = match field.shape().def {
+        Def::Option(od) => od.t(),
+        _ => field.shape(),
+    };
+    match shape.def {
+        Def::Enum(ed) => Some((shape, ed)),
+        _ => None,
+    }
+}
+
+/// A tuple variant holding a single struct or enum, whose fields or variants
+/// are the subcommand's own
+fn is_newtype(variant: &Variant) -> bool {
+    variant.data.kind == StructKind::Tuple
+        && matches!(variant.data.fields, [field]
+            if matches!(field.shape().def, Def::Struct(_) | Def::Enum(_)))
+}
+
+/// The long flag for a field, without the leading dashes: `max_jobs`
+/// becomes `max-jobs`
+fn long_name(field: &Field) -> String {
+    field.name.replace('_', "-")
+}
+
+/// How a field is referred to in messages: `--max-jobs`, `<path>` or
+/// `<command>`
+fn display_name(field: &Field) -> String {
+    if is_positional(field) {
+        format!("<{}>", field.name)
+    } else if is_subcommand(field) {
+        "<command>".to_string()
+    } else {
+        format!("--{}", long_name(field))
+    }
+}
+
+/// The name a variant is given as on the command line, as a subcommand or
+/// as the value of an enum-typed field. Variant names are used as-is when the
+/// enum has a `rename_all`, and kebab-cased otherwise: `DbMigrate` becomes
+/// `db-migrate`.
+fn variant_name(shape: &'static Shape, variant: &Variant) -> String {
+    if shape.get_rename_all_attr().is_some() {
+        return variant.name.to_string();
+    }
+    let mut name = String::new();
+    for (i, c) in variant.name.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            name.push('-');
+        }
+        name.extend(c.to_lowercase());
+    }
+    name
+}
+
+fn variant_names(shape: &'static Shape, ed: EnumDef) -> impl Iterator<Item = String> {
+    ed.variants.iter().map(move |v| variant_name(shape, v))
+}
+
+/// The values an enum-typed field accepts: its unit variants
+fn choices(shape: &'static Shape, ed: EnumDef) -> Vec<String> {
+    ed.variants
+        .iter()
+        .filter(|v| v.data.fields.is_empty())
+        .map(|v| variant_name(shape, v))
+        .collect()
+}
+
+fn find_subcommand(shape: &'static Shape, ed: EnumDef, name: &str) -> Option<usize> {
+    variant_names(shape, ed).position(|candidate| candidate == name)
+}
+
+fn find_long(fields: &[Field], key: &str) -> Option<usize> {
+    fields
+        .iter()
+        .position(|f| is_flag(f) && (long_name(f) == key || f.name == key))
+}
+
+fn find_short(fields: &[Field], key: &str) -> Option<usize> {
+    fields
+        .iter()
+        .position(|f| is_flag(f) && arbitrary_attr(f, "short") == Some(key))
+}
+
+/// Whether an argument starting with `-` is a negative number rather than a
+/// short flag
+fn looks_numeric(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+}
+
+/// What an unknown short flag `-{short}`, met in `-{bundle}`, was likely
+/// meant to be: a short flag in the other case, or a long flag written with a
+/// single dash, like `-verbose`
+fn suggest_short(fields: &[Field], bundle: &str, short: &str) -> Option<String> {
+    let flags = || fields.iter().filter(|f| is_flag(f));
+    if let Some(other) = flags()
+        .filter_map(|f| arbitrary_attr(f, "short"))
+        .find(|other| other.to_lowercase() == short.to_lowercase())
+    {
+        return Some(format!("-{other}"));
+    }
+    if bundle.chars().count() > 1 {
+        return closest(flags().map(long_name), bundle).map(|name| format!("--{name}"));
+    }
+    None
+}
+
+/// The candidate closest to `key`, if it's close enough to be a typo
+fn closest(candidates: impl Iterator<Item = String>, key: &str) -> Option<String> {
+    candidates
+        .map(|candidate| (edit_distance(&candidate, key), candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings, counted in chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            cur.push(substitution.min(prev[j + 1] + 1).min(cur[j] + 1));
         }
+        prev = cur;
     }
-    wip.build().unwrap().materialize().unwrap()
+    prev[b.len()]
 }
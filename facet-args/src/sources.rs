@@ -0,0 +1,330 @@
+use std::path::{Path, PathBuf};
+
+use facet_core::{Facet, Field};
+use facet_reflect::Wip;
+
+use crate::{ArgsError, ArgsErrorKind, arbitrary_attr, has_marker, parse_command, program_name};
+
+/// Parses arguments, falling back on environment variables and config files
+/// for whatever the command line leaves out.
+///
+/// Each field takes its value from the first of these that has one:
+///
+/// 1. the command line,
+/// 2. its environment variable: the one named by `#[facet(env = "NAME")]`,
+///    or, with `#[facet(env)]` or an [`env_prefix`](Parser::env_prefix), one
+///    derived from the field's path, e.g. `APP_DB_URL` for `db.url`,
+/// 3. the config files, the last one given first,
+/// 4. its default: `#[facet(default)]`, `false`, `None`, an empty `Vec` or a
+///    count of zero.
+///
+/// Fields whose type is a struct are groups: they aren't flags themselves,
+/// but their fields can be set from environment variables and config files,
+/// one by one. A group can take some fields from the environment and the rest
+/// from a config file.
+///
+/// ```rust
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Config {
+///     #[facet(named)]
+///     port: u16,
+///
+///     db: Db,
+/// }
+///
+/// #[derive(Facet)]
+/// struct Db {
+///     url: String,
+///     pool_size: u32,
+/// }
+///
+/// # fn main() -> Result<(), facet_args::ArgsError> {
+/// let config: Config = facet_args::Parser::new()
+///     .config_str("app.conf", "port = 80\n[db]\nurl = \"postgres://localhost\"\npool_size = 4")
+///     .parse(&["--port", "8080"])?;
+/// assert_eq!(config.port, 8080);
+/// assert_eq!(config.db.pool_size, 4);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Parser {
+    env_prefix: Option<String>,
+    env: Option<Vec<(String, String)>>,
+    configs: Vec<ConfigSource>,
+}
+
+#[derive(Debug, Clone)]
+enum ConfigSource {
+    File(PathBuf),
+    Text { name: String, text: String },
+}
+
+impl Parser {
+    /// A parser that only reads the command line, and the environment
+    /// variables fields ask for with `#[facet(env)]`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks up every field in the environment, under `PREFIX_PATH`:
+    /// `port` is read from `APP_PORT` and `db.url` from `APP_DB_URL` when
+    /// the prefix is `APP`.
+    pub fn env_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Looks environment variables up in `vars` instead of the process's
+    /// environment, e.g. in tests.
+    pub fn env_vars<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let vars = vars
+            .into_iter()
+            .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()));
+        self.env = Some(vars.collect());
+        self
+    }
+
+    /// Reads values from a config file, if it exists.
+    ///
+    /// The file holds `key = value` lines, optionally grouped under
+    /// `[section]` headers for nested fields, and whole-line `#` or `;`
+    /// comments. A value is the rest of its line, without the quotes around
+    /// it if it has any: escapes and trailing comments are kept as they are,
+    /// and lists are written comma-separated (`tags = a, b`), not as arrays.
+    pub fn config_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.configs
+            .push(ConfigSource::File(path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Reads values from config text in the same format as
+    /// [`config_file`](Parser::config_file). `name` identifies it in error
+    /// messages.
+    pub fn config_str(mut self, name: impl Into<String>, text: impl Into<String>) -> Self {
+        self.configs.push(ConfigSource::Text {
+            name: name.into(),
+            text: text.into(),
+        });
+        self
+    }
+
+    /// Parses `args`, then fills in the rest from the other sources. See
+    /// [`from_slice`](crate::from_slice).
+    pub fn parse<'input, 'facet, T>(&self, args: &[&'input str]) -> Result<T, ArgsError>
+    where
+        T: Facet<'facet>,
+        'input: 'facet,
+    {
+        let fallbacks = self.load()?;
+        let wip = Wip::alloc::<T>()?;
+        let wip = parse_command(wip, args, &program_name(T::SHAPE), &fallbacks)?;
+        Ok(wip.build()?.materialize()?)
+    }
+
+    /// Parses the arguments the program was started with. See
+    /// [`from_std_args`](crate::from_std_args).
+    pub fn parse_std_args<T>(&self) -> T
+    where
+        T: for<'facet> Facet<'facet>,
+    {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        match self.parse(&args) {
+            Ok(value) => value,
+            Err(ArgsError {
+                kind: ArgsErrorKind::HelpRequested { help },
+            }) => {
+                print!("{help}");
+                std::process::exit(0)
+            }
+            Err(e) => {
+                eprintln!("error: {e}\n\nFor more information, try `--help`.");
+                std::process::exit(2)
+            }
+        }
+    }
+
+    fn load(&self) -> Result<Fallbacks, ArgsError> {
+        let mut configs = Vec::new();
+        for source in &self.configs {
+            configs.push(match source {
+                ConfigSource::File(path) => {
+                    let name = path.display().to_string();
+                    match std::fs::read_to_string(path) {
+                        Ok(text) => Config::parse(name, &text)?,
+                        // A missing config file is the same as an empty one
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Config {
+                            name,
+                            values: Vec::new(),
+                        },
+                        Err(e) => {
+                            return Err(ArgsError::new(ArgsErrorKind::InvalidConfig {
+                                path: name,
+                                line: None,
+                                message: e.to_string(),
+                            }));
+                        }
+                    }
+                }
+                ConfigSource::Text { name, text } => Config::parse(name.clone(), text)?,
+            });
+        }
+        Ok(Fallbacks {
+            env_prefix: self.env_prefix.clone(),
+            env: self.env.clone(),
+            configs,
+        })
+    }
+}
+
+/// The sources besides the command line, loaded and ready to be looked up
+#[derive(Default)]
+pub(crate) struct Fallbacks {
+    env_prefix: Option<String>,
+    /// The environment variables, when they don't come from the process
+    env: Option<Vec<(String, String)>>,
+    configs: Vec<Config>,
+}
+
+impl Fallbacks {
+    /// Looks for a value for `field`, which is at `path` below the command's
+    /// own fields. Returns the name the value was found under, for error
+    /// messages, and the value itself. Every source looked in is added to
+    /// `consulted`.
+    pub(crate) fn lookup(
+        &self,
+        field: &Field,
+        path: &[&str],
+        consulted: &mut Vec<String>,
+    ) -> Option<(String, String)> {
+        if let Some(var) = self.env_var(field, path) {
+            if let Some(value) = self.env_value(&var) {
+                log::trace!("Found environment variable: {}", var);
+                return Some((var, value));
+            }
+            consulted.push(format!("environment variable `{var}`"));
+        }
+
+        let key = path
+            .iter()
+            .copied()
+            .chain([field.name])
+            .collect::<Vec<_>>()
+            .join(".");
+        for config in self.configs.iter().rev() {
+            if let Some(value) = config.get(&key) {
+                log::trace!("Found `{}` in {}", key, config.name);
+                return Some((key, value.to_string()));
+            }
+            consulted.push(format!("`{key}` in {}", config.name));
+        }
+
+        None
+    }
+
+    fn env_value(&self, var: &str) -> Option<String> {
+        match &self.env {
+            Some(vars) => vars.iter().find(|(k, _)| k == var).map(|(_, v)| v.clone()),
+            None => std::env::var(var).ok(),
+        }
+    }
+
+    /// The environment variable for a field, if it has one
+    fn env_var(&self, field: &Field, path: &[&str]) -> Option<String> {
+        if let Some(name) = arbitrary_attr(field, "env") {
+            return Some(name.to_string());
+        }
+        if self.env_prefix.is_none() && !has_marker(field, "env") {
+            return None;
+        }
+        let name = self
+            .env_prefix
+            .iter()
+            .map(String::as_str)
+            .chain(path.iter().copied())
+            .chain([field.name])
+            .collect::<Vec<_>>()
+            .join("_");
+        Some(name.replace('-', "_").to_uppercase())
+    }
+}
+
+/// The values from one config file
+struct Config {
+    name: String,
+    values: Vec<(String, String)>,
+}
+
+impl Config {
+    fn parse(name: String, text: &str) -> Result<Self, ArgsError> {
+        let error = |line: usize, message: &str| {
+            ArgsError::new(ArgsErrorKind::InvalidConfig {
+                path: name.clone(),
+                line: Some(line + 1),
+                message: message.to_string(),
+            })
+        };
+
+        let mut values = Vec::new();
+        let mut section = String::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let Some(header) = header.strip_suffix(']') else {
+                    return Err(error(i, "unclosed section header"));
+                };
+                section = header.trim().to_string();
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(error(i, "expected `key = value`"));
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(error(i, "missing key before `=`"));
+            }
+            let key = if section.is_empty() {
+                key.to_string()
+            } else {
+                format!("{section}.{key}")
+            };
+            values.push((key, unquote(value.trim()).to_string()));
+        }
+
+        Ok(Self { name, values })
+    }
+
+    /// The value for a dotted key. Keys may be written with dashes instead
+    /// of underscores, and later lines override earlier ones.
+    fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key || k.replace('-', "_") == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
@@ -0,0 +1,150 @@
+use std::path::PathBuf;
+
+use facet::Facet;
+use facet_args::{ArgsErrorKind, Shell};
+
+/// Converts documents.
+#[derive(Debug, Facet)]
+struct Cli {
+    /// Print more output
+    #[facet(named, short = 'v')]
+    verbose: bool,
+
+    /// Output format
+    #[facet(named, short = 'f')]
+    #[facet(default = "default_format")]
+    format: Format,
+
+    #[facet(named)]
+    config: Option<PathBuf>,
+
+    #[facet(subcommand)]
+    command: Command,
+}
+
+fn default_format() -> Format {
+    Format::Json
+}
+
+#[derive(Debug, PartialEq, Facet)]
+#[repr(u8)]
+enum Format {
+    Json,
+    Yaml,
+}
+
+#[derive(Debug, PartialEq, Facet)]
+#[repr(u8)]
+enum Command {
+    /// Convert a file
+    Convert {
+        #[facet(positional)]
+        input: PathBuf,
+    },
+
+    /// Inspect the user's cache
+    Cache(CacheCommand),
+}
+
+#[derive(Debug, PartialEq, Facet)]
+#[repr(u8)]
+enum CacheCommand {
+    Clear,
+}
+
+#[test]
+fn enum_values_are_parsed() {
+    facet_testhelpers::setup();
+
+    let cli: Cli = facet_args::from_slice(&["-f", "yaml", "convert", "a.md"]).unwrap();
+    assert_eq!(cli.format, Format::Yaml);
+    assert_eq!(
+        cli.command,
+        Command::Convert {
+            input: PathBuf::from("a.md")
+        }
+    );
+
+    let err = facet_args::from_slice::<Cli>(&["--format", "toml", "cache", "clear"]).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::InvalidChoice {
+            flag: "--format".to_string(),
+            value: "toml".to_string(),
+            choices: vec!["json".to_string(), "yaml".to_string()],
+        }
+    );
+}
+
+#[test]
+fn help_lists_possible_values() {
+    facet_testhelpers::setup();
+
+    let help = facet_args::help::<Cli>("conv");
+    assert!(help.contains("Output format [possible values: json, yaml]\n"));
+}
+
+#[test]
+fn bash() {
+    facet_testhelpers::setup();
+
+    let script = facet_args::completions::<Cli>(Shell::Bash, "conv");
+    assert!(script.starts_with("_conv() {\n"));
+    assert!(script.contains("            conv__convert|conv__cache|conv__cache__clear)\n"));
+    assert!(script.contains(
+        "                --format|-f)\n                    COMPREPLY=($(compgen -W \"json yaml\" -- \"${cur}\"))\n"
+    ));
+    assert!(script.contains(
+        "                --config)\n                    COMPREPLY=($(compgen -f -- \"${cur}\"))\n"
+    ));
+    assert!(script.contains("compgen -W \"--verbose -v --format -f --config --help -h\""));
+    assert!(script.contains("COMPREPLY=($(compgen -W \"convert cache\" -- \"${cur}\"))\n"));
+    // `convert` takes a path
+    assert!(script.contains("        conv__convert)\n"));
+    assert!(script.contains("COMPREPLY=($(compgen -f -- \"${cur}\"))\n            fi\n"));
+    assert!(script.ends_with("complete -F _conv conv\n"));
+}
+
+#[test]
+fn zsh() {
+    facet_testhelpers::setup();
+
+    let script = facet_args::completions::<Cli>(Shell::Zsh, "conv");
+    assert!(script.starts_with("#compdef conv\n\n_conv() {\n"));
+    assert!(script.contains("                    '--verbose:Print more output'\n"));
+    assert!(script.contains("                    'convert:Convert a file'\n"));
+    assert!(script.contains("                    'cache:Inspect the user'\\''s cache'\n"));
+    assert!(script.contains("                    compadd -- json yaml\n"));
+    assert!(script.contains("                --config)\n                    _files\n"));
+    assert!(script.contains("    compdef _conv conv\n"));
+}
+
+#[test]
+fn fish() {
+    facet_testhelpers::setup();
+
+    let script = facet_args::completions::<Cli>(Shell::Fish, "conv");
+    let root = "complete -c conv -n 'test (__fish_conv_command) = conv'";
+    assert!(script.contains("            case conv__convert conv__cache conv__cache__clear\n"));
+    assert!(script.contains("complete -c conv -f\n"));
+    assert!(script.contains(&format!("{root} -s v -l verbose -d 'Print more output'\n")));
+    assert!(script.contains(&format!(
+        "{root} -s f -l format -x -a 'json yaml' -d 'Output format'\n"
+    )));
+    assert!(script.contains(&format!("{root} -l config -r -F\n")));
+    assert!(script.contains(&format!(
+        "{root} -a 'cache' -d 'Inspect the user\\'s cache'\n"
+    )));
+    assert!(
+        script.contains("complete -c conv -n 'test (__fish_conv_command) = conv__convert' -F\n")
+    );
+}
+
+#[test]
+fn shell_names() {
+    facet_testhelpers::setup();
+
+    assert_eq!("zsh".parse::<Shell>().unwrap(), Shell::Zsh);
+    assert_eq!(Shell::Fish.to_string(), "fish");
+    assert!("powershell".parse::<Shell>().is_err());
+}
@@ -0,0 +1,145 @@
+use facet::Facet;
+use facet_args::{ArgsError, ArgsErrorKind};
+
+#[derive(Debug, Facet)]
+struct Args {
+    #[facet(positional)]
+    path: String,
+
+    #[facet(named, short = 'v')]
+    verbose: bool,
+
+    #[facet(named, short = 'j')]
+    concurrency: usize,
+}
+
+fn parse(args: &[&str]) -> ArgsError {
+    facet_args::from_slice::<Args>(args).unwrap_err()
+}
+
+#[test]
+fn unknown_flag_suggests_closest() {
+    facet_testhelpers::setup();
+
+    let err = parse(&["--verbos", "example.rs"]);
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::UnknownFlag {
+            flag: "--verbos".to_string(),
+            suggestion: Some("--verbose".to_string()),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "unknown flag `--verbos` (did you mean `--verbose`?)"
+    );
+
+    let err = parse(&["--frobnicate", "example.rs"]);
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::UnknownFlag {
+            flag: "--frobnicate".to_string(),
+            suggestion: None,
+        }
+    );
+
+    let err = parse(&["-x"]);
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::UnknownFlag {
+            flag: "-x".to_string(),
+            suggestion: None,
+        }
+    );
+}
+
+#[test]
+fn unknown_short_flag_suggests_closest() {
+    facet_testhelpers::setup();
+
+    let err = parse(&["-V", "example.rs"]);
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::UnknownFlag {
+            flag: "-V".to_string(),
+            suggestion: Some("-v".to_string()),
+        }
+    );
+
+    let err = parse(&["-verbose", "example.rs"]);
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::UnknownFlag {
+            flag: "-e".to_string(),
+            suggestion: Some("--verbose".to_string()),
+        }
+    );
+}
+
+#[test]
+fn missing_value() {
+    facet_testhelpers::setup();
+
+    let err = parse(&["example.rs", "-j"]);
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::MissingValue {
+            flag: "-j".to_string()
+        }
+    );
+
+    let err = parse(&["example.rs", "--concurrency", "--verbose"]);
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::MissingValue {
+            flag: "--concurrency".to_string()
+        }
+    );
+}
+
+#[test]
+fn invalid_value() {
+    facet_testhelpers::setup();
+
+    let err = parse(&["example.rs", "-j", "lots"]);
+    match err.kind {
+        ArgsErrorKind::InvalidValue {
+            flag, value, shape, ..
+        } => {
+            assert_eq!(flag, "-j");
+            assert_eq!(value, "lots");
+            assert!(shape.is_type::<usize>());
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn missing_required() {
+    facet_testhelpers::setup();
+
+    let err = parse(&["example.rs"]);
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::MissingRequired {
+            flag: "--concurrency".to_string(),
+            sources: vec![],
+        }
+    );
+
+    let err = parse(&["-j", "2"]);
+    assert_eq!(err.to_string(), "missing required argument `<path>`");
+}
+
+#[test]
+fn unexpected_positional() {
+    facet_testhelpers::setup();
+
+    let err = parse(&["a.rs", "b.rs", "-j", "2"]);
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::UnexpectedPositional {
+            value: "b.rs".to_string()
+        }
+    );
+}
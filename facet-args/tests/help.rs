@@ -0,0 +1,86 @@
+use facet::Facet;
+use facet_args::ArgsErrorKind;
+
+fn default_jobs() -> usize {
+    4
+}
+
+/// Counts the lines of source files.
+///
+/// This paragraph is not part of the summary.
+#[derive(Debug, Facet)]
+struct Args {
+    /// File to read
+    #[facet(positional)]
+    path: String,
+
+    /// Where to write the report
+    #[facet(positional)]
+    output: Option<String>,
+
+    /// Print every file as it's counted
+    #[facet(named, short = 'v')]
+    verbose: bool,
+
+    /// How many files to read at once
+    #[facet(named, short = 'j')]
+    #[facet(default = "default_jobs")]
+    concurrency: usize,
+
+    #[facet(named)]
+    exclude: Option<String>,
+}
+
+#[test]
+fn help_text() {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_args::help::<Args>("loc"),
+        "\
+Counts the lines of source files.
+
+Usage: loc [OPTIONS] <path> [output]
+
+Arguments:
+  <path>    File to read
+  [output]  Where to write the report
+
+Options:
+  -v, --verbose              Print every file as it's counted
+  -j, --concurrency <usize>  How many files to read at once [default: 4]
+      --exclude <String>
+  -h, --help                 Print help
+"
+    );
+}
+
+#[test]
+fn help_flag_is_handled() {
+    facet_testhelpers::setup();
+
+    for flag in ["--help", "-h"] {
+        let err = facet_args::from_slice::<Args>(&["file.rs", flag]).unwrap_err();
+        match err.kind {
+            ArgsErrorKind::HelpRequested { help } => {
+                assert!(help.contains("Counts the lines of source files."));
+                assert!(help.contains("--concurrency <usize>"));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn help_flag_can_be_claimed_by_a_field() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Args {
+        #[facet(named, short = 'h')]
+        host: String,
+    }
+
+    let args: Args = facet_args::from_slice(&["-h", "example.com"]).unwrap();
+    assert_eq!(args.host, "example.com");
+}
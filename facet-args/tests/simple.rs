@@ -16,8 +16,43 @@ fn test_arg_parse() {
         concurrency: usize,
     }
 
-    let args: Args = facet_args::from_slice(&["--verbose", "-j", "14", "example.rs"]);
+    let args: Args = facet_args::from_slice(&["--verbose", "-j", "14", "example.rs"]).unwrap();
     assert!(args.verbose);
     assert_eq!(args.path, "example.rs");
     assert_eq!(args.concurrency, 14);
 }
+
+#[test]
+fn test_arg_parse_defaults() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Args {
+        #[facet(positional)]
+        input: String,
+
+        #[facet(positional)]
+        output: Option<String>,
+
+        #[facet(named)]
+        verbose: bool,
+
+        #[facet(named)]
+        #[facet(default)]
+        max_jobs: usize,
+
+        #[facet(named)]
+        offset: i64,
+    }
+
+    let args: Args = facet_args::from_slice(&["in.txt", "--offset", "-3"]).unwrap();
+    assert_eq!(args.input, "in.txt");
+    assert_eq!(args.output, None);
+    assert!(!args.verbose);
+    assert_eq!(args.max_jobs, 0);
+    assert_eq!(args.offset, -3);
+
+    let args: Args =
+        facet_args::from_slice(&["in.txt", "--max-jobs", "8", "--offset", "1"]).unwrap();
+    assert_eq!(args.max_jobs, 8);
+}
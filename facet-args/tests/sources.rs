@@ -0,0 +1,150 @@
+use facet::Facet;
+use facet_args::{ArgsErrorKind, Parser};
+
+#[derive(Debug, Facet)]
+struct Config {
+    #[facet(named)]
+    port: u16,
+
+    #[facet(named)]
+    #[facet(default)]
+    verbose: bool,
+
+    #[facet(named)]
+    tags: Vec<String>,
+
+    db: Db,
+}
+
+#[derive(Debug, Facet)]
+struct Db {
+    url: String,
+    pool_size: u32,
+}
+
+#[test]
+fn command_line_wins() {
+    facet_testhelpers::setup();
+
+    let config: Config = Parser::new()
+        .env_prefix("APP")
+        .env_vars([("APP_PORT", "81")])
+        .config_str("app.conf", "port = 82\n[db]\nurl = x\npool_size = 1")
+        .parse(&["--port", "80"])
+        .unwrap();
+    assert_eq!(config.port, 80);
+}
+
+#[test]
+fn environment_beats_config() {
+    facet_testhelpers::setup();
+
+    let config: Config = Parser::new()
+        .env_prefix("APP")
+        .env_vars([
+            ("APP_PORT", "81"),
+            ("APP_DB_URL", "postgres://env"),
+            ("APP_TAGS", "a, b,c"),
+        ])
+        .config_str(
+            "app.conf",
+            "port = 82\n\n[db]\nurl = \"postgres://config\"\npool-size = 4\n",
+        )
+        .parse(&[])
+        .unwrap();
+    assert_eq!(config.port, 81);
+    assert_eq!(config.tags, ["a", "b", "c"]);
+    // The group takes one field from each source
+    assert_eq!(config.db.url, "postgres://env");
+    assert_eq!(config.db.pool_size, 4);
+    assert!(!config.verbose);
+}
+
+#[test]
+fn later_config_files_win() {
+    facet_testhelpers::setup();
+
+    let config: Config = Parser::new()
+        .config_str("system.conf", "port = 1\n[db]\nurl = a\npool_size = 1")
+        .config_file("/nonexistent/app.conf")
+        .config_str("user.conf", "# overrides\nport = 2")
+        .parse(&[])
+        .unwrap();
+    assert_eq!(config.port, 2);
+    assert_eq!(config.db.url, "a");
+}
+
+#[test]
+fn env_attribute() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Args {
+        #[facet(named)]
+        #[facet(env = "API_TOKEN")]
+        token: String,
+
+        #[facet(named, env)]
+        level: Option<u8>,
+    }
+
+    let args: Args = Parser::new()
+        .env_vars([("API_TOKEN", "secret"), ("LEVEL", "3")])
+        .parse(&[])
+        .unwrap();
+    assert_eq!(args.token, "secret");
+    assert_eq!(args.level, Some(3));
+}
+
+#[test]
+fn missing_value_lists_sources() {
+    facet_testhelpers::setup();
+
+    let err = Parser::new()
+        .env_prefix("APP")
+        .env_vars(Vec::<(String, String)>::new())
+        .config_str("app.conf", "port = 80\n[db]\nurl = x")
+        .parse::<Config>(&[])
+        .unwrap_err();
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::MissingRequired {
+            flag: "db.pool_size".to_string(),
+            sources: vec![
+                "environment variable `APP_DB_POOL_SIZE`".to_string(),
+                "`db.pool_size` in app.conf".to_string(),
+            ],
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "missing required argument `db.pool_size` (also looked for environment variable \
+         `APP_DB_POOL_SIZE`, `db.pool_size` in app.conf)"
+    );
+}
+
+#[test]
+fn invalid_values_name_their_source() {
+    facet_testhelpers::setup();
+
+    let err = Parser::new()
+        .env_prefix("APP")
+        .env_vars([("APP_PORT", "eighty")])
+        .parse::<Config>(&[])
+        .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        ArgsErrorKind::InvalidValue { flag, value, .. } if flag == "APP_PORT" && value == "eighty"
+    ));
+}
+
+#[test]
+fn invalid_config() {
+    facet_testhelpers::setup();
+
+    let err = Parser::new()
+        .config_str("app.conf", "port = 80\n[db\n")
+        .parse::<Config>(&[])
+        .unwrap_err();
+    assert_eq!(err.to_string(), "app.conf:2: unclosed section header");
+}
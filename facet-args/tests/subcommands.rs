@@ -0,0 +1,168 @@
+use facet::Facet;
+use facet_args::ArgsErrorKind;
+
+/// Manages the app.
+#[derive(Debug, Facet)]
+struct Cli {
+    /// Print more output
+    #[facet(named, short = 'v')]
+    verbose: bool,
+
+    #[facet(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, PartialEq, Facet)]
+#[repr(u8)]
+#[facet(rename_all = "kebab-case")]
+enum Command {
+    Db(DbCommand),
+
+    /// Run the server
+    Serve {
+        /// Port to listen on
+        #[facet(named)]
+        port: u16,
+    },
+}
+
+/// Database maintenance
+#[derive(Debug, PartialEq, Facet)]
+#[repr(u8)]
+enum DbCommand {
+    /// Apply pending migrations
+    Migrate {
+        #[facet(named)]
+        dry_run: bool,
+    },
+
+    /// Drop every table
+    Reset,
+}
+
+#[derive(Debug, Facet)]
+struct Optional {
+    #[facet(named)]
+    quiet: bool,
+
+    #[facet(subcommand)]
+    command: Option<DbCommand>,
+}
+
+#[test]
+fn nested_subcommand() {
+    facet_testhelpers::setup();
+
+    let cli: Cli = facet_args::from_slice(&["-v", "db", "migrate", "--dry-run"]).unwrap();
+    assert!(cli.verbose);
+    assert_eq!(
+        cli.command,
+        Command::Db(DbCommand::Migrate { dry_run: true })
+    );
+
+    let cli: Cli = facet_args::from_slice(&["db", "reset"]).unwrap();
+    assert!(!cli.verbose);
+    assert_eq!(cli.command, Command::Db(DbCommand::Reset));
+}
+
+#[test]
+fn subcommand_flags() {
+    facet_testhelpers::setup();
+
+    let cli: Cli = facet_args::from_slice(&["serve", "--port", "80"]).unwrap();
+    assert_eq!(cli.command, Command::Serve { port: 80 });
+
+    // Flags after the subcommand belong to it
+    let err = facet_args::from_slice::<Cli>(&["serve", "--port", "80", "-v"]).unwrap_err();
+    assert!(matches!(err.kind, ArgsErrorKind::UnknownFlag { flag, .. } if flag == "-v"));
+}
+
+#[test]
+fn top_level_enum() {
+    facet_testhelpers::setup();
+
+    let command: DbCommand = facet_args::from_slice(&["migrate"]).unwrap();
+    assert_eq!(command, DbCommand::Migrate { dry_run: false });
+}
+
+#[test]
+fn optional_subcommand() {
+    facet_testhelpers::setup();
+
+    let args: Optional = facet_args::from_slice(&["--quiet"]).unwrap();
+    assert!(args.quiet);
+    assert_eq!(args.command, None);
+
+    let args: Optional = facet_args::from_slice(&["reset"]).unwrap();
+    assert_eq!(args.command, Some(DbCommand::Reset));
+}
+
+#[test]
+fn missing_subcommand() {
+    facet_testhelpers::setup();
+
+    let err = facet_args::from_slice::<Cli>(&["-v"]).unwrap_err();
+    assert!(matches!(err.kind, ArgsErrorKind::MissingRequired { flag, .. } if flag == "<command>"));
+}
+
+#[test]
+fn unknown_subcommand_suggestion() {
+    facet_testhelpers::setup();
+
+    let err = facet_args::from_slice::<Cli>(&["serv"]).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::UnknownSubcommand {
+            name: "serv".to_string(),
+            suggestion: Some("serve".to_string()),
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "unknown subcommand `serv` (did you mean `serve`?)"
+    );
+}
+
+#[test]
+fn help_lists_commands() {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        facet_args::help::<Cli>("app"),
+        "\
+Manages the app.
+
+Usage: app [OPTIONS] <command>
+
+Commands:
+  db     Database maintenance
+  serve  Run the server
+
+Options:
+  -v, --verbose  Print more output
+  -h, --help     Print help
+"
+    );
+}
+
+#[test]
+fn help_per_subcommand() {
+    facet_testhelpers::setup();
+
+    let err = facet_args::from_slice::<Cli>(&["db", "--help"]).unwrap_err();
+    let ArgsErrorKind::HelpRequested { help } = err.kind else {
+        panic!("expected help, got {err:?}");
+    };
+    assert!(help.contains("Usage: "));
+    assert!(help.contains(" db <command>\n"));
+    assert!(help.contains("  migrate  Apply pending migrations\n"));
+    assert!(help.contains("  reset    Drop every table\n"));
+
+    let err = facet_args::from_slice::<Cli>(&["serve", "-h"]).unwrap_err();
+    let ArgsErrorKind::HelpRequested { help } = err.kind else {
+        panic!("expected help, got {err:?}");
+    };
+    assert!(help.starts_with("Run the server\n\n"));
+    assert!(help.contains(" serve [OPTIONS]\n"));
+    assert!(help.contains("      --port <u16>  Port to listen on\n"));
+}
@@ -0,0 +1,126 @@
+use facet::Facet;
+use facet_args::ArgsErrorKind;
+
+#[derive(Debug, Facet)]
+struct Args {
+    #[facet(positional)]
+    files: Vec<String>,
+
+    #[facet(named, short = 'v', count)]
+    verbose: u8,
+
+    #[facet(named, short = 'x')]
+    extract: bool,
+
+    #[facet(named)]
+    #[facet(default)]
+    color: bool,
+
+    #[facet(named, short = 'I')]
+    include: Vec<String>,
+
+    #[facet(named, short = 'j')]
+    jobs: Option<usize>,
+}
+
+fn parse(args: &[&str]) -> Args {
+    facet_args::from_slice(args).unwrap()
+}
+
+#[test]
+fn equals_syntax() {
+    facet_testhelpers::setup();
+
+    let args = parse(&["--jobs=4", "--color=true"]);
+    assert_eq!(args.jobs, Some(4));
+    assert!(args.color);
+
+    let args = parse(&["-j=8", "--include=a=b"]);
+    assert_eq!(args.jobs, Some(8));
+    assert_eq!(args.include, ["a=b"]);
+}
+
+#[test]
+fn repeated_flags_accumulate() {
+    facet_testhelpers::setup();
+
+    let args = parse(&["-I", "src", "--include", "tests", "-Ibenches"]);
+    assert_eq!(args.include, ["src", "tests", "benches"]);
+
+    let args = parse(&[]);
+    assert!(args.include.is_empty());
+    assert!(args.files.is_empty());
+    assert_eq!(args.jobs, None);
+}
+
+#[test]
+fn multiple_positionals() {
+    facet_testhelpers::setup();
+
+    let args = parse(&["a.rs", "-x", "b.rs", "c.rs"]);
+    assert_eq!(args.files, ["a.rs", "b.rs", "c.rs"]);
+    assert!(args.extract);
+}
+
+#[test]
+fn bundled_short_flags() {
+    facet_testhelpers::setup();
+
+    let args = parse(&["-vvx"]);
+    assert_eq!(args.verbose, 2);
+    assert!(args.extract);
+
+    let args = parse(&["-xj4"]);
+    assert!(args.extract);
+    assert_eq!(args.jobs, Some(4));
+
+    let args = parse(&["-vj", "2", "-v", "--verbose"]);
+    assert_eq!(args.verbose, 3);
+    assert_eq!(args.jobs, Some(2));
+}
+
+#[test]
+fn counting_flags() {
+    facet_testhelpers::setup();
+
+    assert_eq!(parse(&[]).verbose, 0);
+    assert_eq!(parse(&["-vvv"]).verbose, 3);
+
+    let err = facet_args::from_slice::<Args>(&["--verbose=3"]).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ArgsErrorKind::UnexpectedValue {
+            flag: "--verbose".to_string(),
+            value: "3".to_string(),
+        }
+    );
+}
+
+#[test]
+fn negated_switches() {
+    facet_testhelpers::setup();
+
+    let args = parse(&["--color", "--no-color"]);
+    assert!(!args.color);
+
+    let err = facet_args::from_slice::<Args>(&["--no-jobs"]).unwrap_err();
+    assert!(matches!(err.kind, ArgsErrorKind::UnknownFlag { flag, .. } if flag == "--no-jobs"));
+}
+
+#[test]
+fn double_dash_ends_options() {
+    facet_testhelpers::setup();
+
+    let args = parse(&["-x", "--", "-v", "--jobs", "-"]);
+    assert!(args.extract);
+    assert_eq!(args.verbose, 0);
+    assert_eq!(args.files, ["-v", "--jobs", "-"]);
+}
+
+#[test]
+fn unknown_flag_in_bundle() {
+    facet_testhelpers::setup();
+
+    let err = facet_args::from_slice::<Args>(&["-vqx"]).unwrap_err();
+    assert!(matches!(err.kind, ArgsErrorKind::UnknownFlag { flag, .. } if flag == "-q"));
+}
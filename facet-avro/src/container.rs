@@ -0,0 +1,275 @@
+use std::borrow::Cow;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{self, Write};
+
+use facet_core::Facet;
+use facet_reflect::{LimitKind, Limits, Peek, ReflectError};
+use log::trace;
+use miniz_oxide::inflate::TINFLStatus;
+
+use crate::from_avro::Decoder;
+use crate::to_avro::{write_bytes, write_long, write_value};
+use crate::{AvroError, Schema};
+
+const MAGIC: &[u8; 4] = b"Obj\x01";
+
+/// Blocks are written once their encoded values reach this size
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// How the blocks of an object container file are compressed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    /// Blocks are left as they are
+    #[default]
+    Null,
+    /// Blocks are compressed with raw DEFLATE, as in RFC 1951
+    Deflate,
+}
+
+impl Codec {
+    fn name(self) -> &'static str {
+        match self {
+            Codec::Null => "null",
+            Codec::Deflate => "deflate",
+        }
+    }
+}
+
+/// Writes values to an Avro object container file: a header holding the
+/// schema derived from their type, then blocks of values.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Reading {
+///     sensor: String,
+///     celsius: f64,
+/// }
+///
+/// let readings = vec![
+///     Reading { sensor: "attic".to_string(), celsius: 31.5 },
+///     Reading { sensor: "cellar".to_string(), celsius: 12.0 },
+/// ];
+/// let file = facet_avro::to_container(&readings, facet_avro::Codec::Deflate);
+/// assert_eq!(&file[..4], b"Obj\x01");
+/// assert_eq!(facet_avro::from_container::<Reading>(&file).unwrap(), readings);
+/// ```
+pub fn to_container<'a, T: Facet<'a>>(values: &[T], codec: Codec) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_container(values, &mut out, codec).unwrap();
+    out
+}
+
+/// Writes values to a writer as an Avro object container file.
+pub fn write_container<'a, T: Facet<'a>, W: Write>(
+    values: &[T],
+    writer: &mut W,
+    codec: Codec,
+) -> io::Result<()> {
+    let schema = Schema::from_shape(T::SHAPE).map_err(|e| io::Error::other(e.to_string()))?;
+    let names = schema.names();
+    let sync = sync_marker();
+
+    let mut header = Vec::new();
+    header.extend_from_slice(MAGIC);
+    write_long(&mut header, 2);
+    write_bytes(&mut header, b"avro.schema");
+    write_bytes(&mut header, schema.to_string().as_bytes());
+    write_bytes(&mut header, b"avro.codec");
+    write_bytes(&mut header, codec.name().as_bytes());
+    write_long(&mut header, 0);
+    header.extend_from_slice(&sync);
+    writer.write_all(&header)?;
+
+    let mut block = Vec::new();
+    let mut count = 0;
+    for value in values {
+        write_value(&mut block, Peek::new(value), &schema, &names)?;
+        count += 1;
+        if block.len() >= BLOCK_SIZE {
+            write_block(writer, &block, count, codec, &sync)?;
+            block.clear();
+            count = 0;
+        }
+    }
+    if count > 0 {
+        write_block(writer, &block, count, codec, &sync)?;
+    }
+    Ok(())
+}
+
+fn write_block<W: Write>(
+    writer: &mut W,
+    block: &[u8],
+    count: usize,
+    codec: Codec,
+    sync: &[u8; 16],
+) -> io::Result<()> {
+    trace!("Writing a block of {} values", count);
+    let data = match codec {
+        Codec::Null => Cow::Borrowed(block),
+        Codec::Deflate => Cow::Owned(miniz_oxide::deflate::compress_to_vec(block, 6)),
+    };
+    let mut out = Vec::new();
+    write_long(&mut out, count as i64);
+    write_bytes(&mut out, &data);
+    out.extend_from_slice(sync);
+    writer.write_all(&out)
+}
+
+/// A marker that is unlikely to occur in the data it separates blocks of
+fn sync_marker() -> [u8; 16] {
+    let state = RandomState::new();
+    let mut marker = [0; 16];
+    for (i, chunk) in marker.chunks_mut(8).enumerate() {
+        chunk.copy_from_slice(&state.hash_one(i).to_le_bytes());
+    }
+    marker
+}
+
+/// Reads the values of an Avro object container file, resolving the schema
+/// it was written with against their type as
+/// [`from_slice_with_schema`](crate::from_slice_with_schema) does.
+///
+/// Values are owned, as blocks compressed with DEFLATE can't be borrowed
+/// from.
+pub fn from_container<T: for<'facet> Facet<'facet>>(input: &[u8]) -> Result<Vec<T>, AvroError> {
+    from_container_with_limits(input, Limits::default())
+}
+
+/// Reads the values of an Avro object container file, refusing input that
+/// goes over the given [`Limits`].
+///
+/// Blocks are only inflated up to what [`Limits::max_alloc_bytes`] leaves,
+/// so a small file can't decompress into gigabytes, and the values count
+/// against [`Limits::max_collection_len`] before a block is read. Each value
+/// is then read with the limits too. Violations are reported as
+/// [`AvroError::LimitExceeded`].
+///
+/// # Example
+/// ```
+/// use facet_avro::{AvroError, Codec, LimitKind, Limits};
+///
+/// let file = facet_avro::to_container(&vec![0u8; 1 << 20], Codec::Deflate);
+/// let limits = Limits::default().max_alloc_bytes(1024);
+///
+/// let err = facet_avro::from_container_with_limits::<u8>(&file, limits).unwrap_err();
+/// assert!(matches!(
+///     err,
+///     AvroError::LimitExceeded { kind: LimitKind::Allocation, .. }
+/// ));
+/// ```
+pub fn from_container_with_limits<T: for<'facet> Facet<'facet>>(
+    input: &[u8],
+    limits: Limits,
+) -> Result<Vec<T>, AvroError> {
+    // The header is a map of bytes, which needs no names to read
+    let metadata_schema = Schema::Bytes;
+    let mut header = Decoder::new(input, &metadata_schema);
+    if header
+        .take(4)
+        .map_err(|_| AvroError::InvalidContainer("missing magic"))?
+        != MAGIC
+    {
+        return Err(AvroError::InvalidContainer("missing magic"));
+    }
+
+    let mut schema = None;
+    let mut codec = Codec::Null;
+    while let Some(len) = header.block_len(&metadata_schema)? {
+        for _ in 0..len {
+            let key = header.read_bytes()?;
+            let value = header.read_bytes()?;
+            match key {
+                b"avro.schema" => {
+                    let json = core::str::from_utf8(value).map_err(|_| AvroError::InvalidUtf8)?;
+                    schema = Some(Schema::parse(json)?);
+                }
+                b"avro.codec" => {
+                    codec = match value {
+                        b"null" => Codec::Null,
+                        b"deflate" => Codec::Deflate,
+                        _ => {
+                            let name = String::from_utf8_lossy(value).into_owned();
+                            return Err(AvroError::UnsupportedCodec(name));
+                        }
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+    let schema = schema.ok_or(AvroError::InvalidContainer("missing schema"))?;
+    let sync = header.take(16)?;
+    trace!(
+        "Reading a container of {} with the {} codec",
+        schema,
+        codec.name()
+    );
+
+    let mut values = Vec::new();
+    let mut inflated = 0usize;
+    while !header.is_empty() {
+        let count = usize::try_from(header.read_long()?).map_err(|_| AvroError::InvalidData)?;
+        let data = header.read_bytes()?;
+        if header.take(16)? != sync {
+            return Err(AvroError::InvalidContainer("sync marker mismatch"));
+        }
+        limits
+            .check(
+                LimitKind::CollectionLength,
+                values.len().saturating_add(count),
+            )
+            .map_err(|e| limit_exceeded(e, values.len()))?;
+        let data = match codec {
+            Codec::Null => Cow::Borrowed(data),
+            Codec::Deflate => {
+                let room = limits.max_alloc_bytes.saturating_sub(inflated);
+                let data = miniz_oxide::inflate::decompress_to_vec_with_limit(data, room).map_err(
+                    |e| match e.status {
+                        TINFLStatus::HasMoreOutput => limit_exceeded(
+                            ReflectError::LimitExceeded {
+                                kind: LimitKind::Allocation,
+                                max: limits.max_alloc_bytes,
+                            },
+                            values.len(),
+                        ),
+                        _ => AvroError::InvalidContainer("invalid deflate data"),
+                    },
+                )?;
+                inflated += data.len();
+                Cow::Owned(data)
+            }
+        };
+
+        let mut block = Decoder::new(&data, &schema);
+        for _ in 0..count {
+            let value = block
+                .read_value::<T>(&schema, limits)
+                .map_err(|e| match e {
+                    AvroError::ReflectError(e) => limit_exceeded(e, values.len()),
+                    e => e,
+                })?;
+            values.push(value);
+        }
+        if !block.is_empty() {
+            return Err(AvroError::InvalidData);
+        }
+    }
+    Ok(values)
+}
+
+/// Reports a limit exceeded while reading the value at `index`, passing
+/// other errors through.
+fn limit_exceeded(e: ReflectError, index: usize) -> AvroError {
+    match e {
+        ReflectError::LimitExceeded { kind, max } => AvroError::LimitExceeded {
+            kind,
+            max,
+            path: format!("$[{}]", index),
+        },
+        e => AvroError::ReflectError(e),
+    }
+}
@@ -0,0 +1,96 @@
+use core::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Errors that can occur while deriving schemas, or reading Avro data
+pub enum Error {
+    /// Not enough data available to decode a complete value
+    InsufficientData,
+    /// A varint is longer than ten bytes
+    VarintOverflow,
+    /// The data is malformed, e.g. a boolean that is neither 0 nor 1, or a
+    /// negative length
+    InvalidData,
+    /// A string isn't valid UTF-8
+    InvalidUtf8,
+    /// A union index past the branches of the union
+    InvalidUnionIndex(i64),
+    /// An enum index past the symbols of the enum
+    InvalidEnumIndex(i64),
+    /// Integer value is too large for the target type
+    IntegerOverflow,
+    /// A field of the reader that the writer doesn't have, and that has no
+    /// default
+    MissingField(String),
+    /// An enum symbol or union branch of the writer that no variant of the
+    /// reader matches
+    UnknownVariant(String),
+    /// The writer's schema can't be read as the reader's type
+    SchemaMismatch {
+        /// The writer's schema, as JSON
+        writer: String,
+        /// The reader's type
+        reader: String,
+    },
+    /// A schema that isn't valid JSON, or not a valid Avro schema
+    InvalidSchema(String),
+    /// An object container file that is malformed
+    InvalidContainer(&'static str),
+    /// An object container file compressed with a codec other than `null`
+    /// and `deflate`
+    UnsupportedCodec(String),
+    /// Shape is not supported by Avro
+    UnsupportedShape(String),
+    /// Reflection error
+    ReflectError(facet_reflect::ReflectError),
+    /// The input went over one of the configured [`Limits`](facet_reflect::Limits)
+    LimitExceeded {
+        /// Which limit was exceeded
+        kind: facet_reflect::LimitKind,
+        /// The configured maximum
+        max: usize,
+        /// The value that was being read, e.g. `$[3]`
+        path: String,
+    },
+}
+
+impl From<facet_reflect::ReflectError> for Error {
+    fn from(err: facet_reflect::ReflectError) -> Self {
+        Self::ReflectError(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InsufficientData => write!(f, "Insufficient data to decode"),
+            Error::VarintOverflow => write!(f, "Varint is too long"),
+            Error::InvalidData => write!(f, "Invalid Avro data"),
+            Error::InvalidUtf8 => write!(f, "Invalid UTF-8 in string"),
+            Error::InvalidUnionIndex(index) => write!(f, "Invalid union index: {}", index),
+            Error::InvalidEnumIndex(index) => write!(f, "Invalid enum index: {}", index),
+            Error::IntegerOverflow => write!(f, "Integer value too large for target type"),
+            Error::MissingField(field) => write!(f, "Missing required field: {}", field),
+            Error::UnknownVariant(name) => write!(f, "Unknown variant: {}", name),
+            Error::SchemaMismatch { writer, reader } => {
+                write!(f, "Cannot read {} as {}", writer, reader)
+            }
+            Error::InvalidSchema(reason) => write!(f, "Invalid schema: {}", reason),
+            Error::InvalidContainer(reason) => {
+                write!(f, "Invalid object container file: {}", reason)
+            }
+            Error::UnsupportedCodec(codec) => write!(f, "Unsupported codec: {}", codec),
+            Error::UnsupportedShape(shape) => {
+                write!(f, "Unsupported shape for Avro: {}", shape)
+            }
+            Error::ReflectError(err) => {
+                write!(f, "Reflection error: {}", err)
+            }
+            Error::LimitExceeded { kind, max, path } => {
+                write!(f, "Exceeded the {} limit of {} at {}", kind, max, path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
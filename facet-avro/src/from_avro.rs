@@ -0,0 +1,452 @@
+use std::borrow::Cow;
+
+use facet_core::{Def, Facet, Field, Shape};
+use facet_reflect::{Limits, Wip};
+use log::trace;
+
+use crate::schema::Names;
+use crate::{AvroError, RecordField, Schema, field_name, is_textual, unwrap, variant_name};
+
+/// Deserializes the Avro binary encoding of a value, written against the
+/// schema [`schema`](crate::schema()) derives from its type.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Click {
+///     x: i32,
+///     label: Option<String>,
+/// }
+///
+/// let click = Click { x: -2, label: None };
+/// let bytes = facet_avro::to_vec(&click);
+/// assert_eq!(facet_avro::from_slice::<Click>(&bytes).unwrap(), click);
+/// ```
+pub fn from_slice<'input: 'facet, 'facet, T: Facet<'facet>>(
+    input: &'input [u8],
+) -> Result<T, AvroError> {
+    let schema = Schema::from_shape(T::SHAPE)?;
+    from_slice_with_schema(input, &schema)
+}
+
+/// Deserializes the Avro binary encoding of a value written against
+/// another schema, such as one from an older version of the type.
+///
+/// The writer's schema is resolved against the type, following the Avro
+/// specification:
+///
+/// - Record fields are matched by name. Fields only the writer has are
+///   skipped, and fields only the reader has take their
+///   `#[facet(default)]`, or `None` for options.
+/// - Enum symbols and the records of unions are matched to variants by
+///   name.
+/// - Ints can be read as longs, floats or doubles, longs as floats or
+///   doubles, floats as doubles, and strings and bytes as each other.
+/// - Unions can be read as options or as one of their branches, and
+///   options can read values that aren't in a union.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct EventV1 {
+///     id: i32,
+///     legacy: String,
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct EventV2 {
+///     id: i64,
+///     #[facet(default)]
+///     tags: Vec<String>,
+/// }
+///
+/// let writer = facet_avro::schema::<EventV1>().unwrap();
+/// let bytes = facet_avro::to_vec(&EventV1 { id: 7, legacy: "x".to_string() });
+/// let event: EventV2 = facet_avro::from_slice_with_schema(&bytes, &writer).unwrap();
+/// assert_eq!(event, EventV2 { id: 7, tags: vec![] });
+/// ```
+pub fn from_slice_with_schema<'input: 'facet, 'facet, T: Facet<'facet>>(
+    input: &'input [u8],
+    writer: &Schema,
+) -> Result<T, AvroError> {
+    let mut decoder = Decoder::new(input, writer);
+    let value = decoder.read_value::<T>(writer, Limits::default())?;
+    if !decoder.is_empty() {
+        return Err(AvroError::InvalidData);
+    }
+    Ok(value)
+}
+
+/// Reads values against a writer's schema
+pub(crate) struct Decoder<'input, 's> {
+    input: &'input [u8],
+    offset: usize,
+    names: Names<'s>,
+}
+
+impl<'input, 's> Decoder<'input, 's> {
+    pub(crate) fn new(input: &'input [u8], writer: &'s Schema) -> Self {
+        Self {
+            input,
+            offset: 0,
+            names: writer.names(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.offset == self.input.len()
+    }
+
+    pub(crate) fn read_value<T: Facet<'input>>(
+        &mut self,
+        writer: &'s Schema,
+        limits: Limits,
+    ) -> Result<T, AvroError> {
+        let wip = self.decode(Wip::alloc::<T>()?.with_limits(limits)?, writer)?;
+        Ok(wip.build()?.materialize::<T>()?)
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'input [u8], AvroError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|&end| end <= self.input.len())
+            .ok_or(AvroError::InsufficientData)?;
+        let bytes = &self.input[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], AvroError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    /// Reads a long: a zigzag-encoded varint
+    pub(crate) fn read_long(&mut self) -> Result<i64, AvroError> {
+        let mut n = 0u64;
+        for i in 0..10 {
+            let byte = self.take(1)?[0];
+            n |= ((byte & 0x7f) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok((n >> 1) as i64 ^ -((n & 1) as i64));
+            }
+        }
+        Err(AvroError::VarintOverflow)
+    }
+
+    pub(crate) fn read_bytes(&mut self) -> Result<&'input [u8], AvroError> {
+        let len = usize::try_from(self.read_long()?).map_err(|_| AvroError::InvalidData)?;
+        self.take(len)
+    }
+
+    fn read_str(&mut self) -> Result<&'input str, AvroError> {
+        core::str::from_utf8(self.read_bytes()?).map_err(|_| AvroError::InvalidUtf8)
+    }
+
+    /// Reads the item count of the next block of an array or map, or `None`
+    /// for the empty block that ends it.
+    pub(crate) fn block_len(&mut self, item: &Schema) -> Result<Option<usize>, AvroError> {
+        let count = self.read_long()?;
+        if count == 0 {
+            return Ok(None);
+        }
+        if count < 0 {
+            // Negative counts are followed by the size of the block in bytes
+            self.read_long()?;
+        }
+        let count = usize::try_from(count.unsigned_abs()).map_err(|_| AvroError::InvalidData)?;
+        // Every item but nulls takes a byte, so a count past the end of the
+        // input is a lie, and not worth allocating for
+        if count > self.input.len() - self.offset && *item != Schema::Null {
+            return Err(AvroError::InsufficientData);
+        }
+        Ok(Some(count))
+    }
+
+    fn decode(&mut self, wip: Wip<'input>, writer: &'s Schema) -> Result<Wip<'input>, AvroError> {
+        let writer = writer.resolve(&self.names)?;
+        let Schema::Union(branches) = writer else {
+            return self.decode_resolved(wip, writer);
+        };
+        let index = self.read_long()?;
+        let branch = usize::try_from(index)
+            .ok()
+            .and_then(|index| branches.get(index))
+            .ok_or(AvroError::InvalidUnionIndex(index))?;
+        let branch = branch.resolve(&self.names)?;
+        self.decode_resolved(wip, branch)
+    }
+
+    /// Decodes a value whose writer's schema is known not to be a union or
+    /// a reference.
+    fn decode_resolved(
+        &mut self,
+        wip: Wip<'input>,
+        writer: &'s Schema,
+    ) -> Result<Wip<'input>, AvroError> {
+        let shape = wip.shape();
+        trace!("Deserializing {} from {}", shape, writer);
+
+        if let Def::Option(_) = shape.def {
+            if *writer == Schema::Null {
+                return Ok(wip.put_default()?);
+            }
+            let wip = wip.push_some()?;
+            let wip = self.decode_resolved(wip, writer)?;
+            return Ok(wip.pop()?);
+        }
+
+        // Wrappers can only be built from the primitive they wrap
+        let target = unwrap(shape);
+        if target != shape {
+            return self.decode_primitive(wip, writer, target);
+        }
+
+        match (writer, shape.def) {
+            (Schema::Record { fields, .. }, Def::Struct(sd)) => {
+                self.decode_fields(wip, fields, sd.fields)
+            }
+            (Schema::Record { name, fields, .. }, Def::Enum(ed)) => {
+                let simple_name = name.rsplit('.').next().unwrap_or(name);
+                let index = ed
+                    .variants
+                    .iter()
+                    .position(|v| variant_name(shape, v) == simple_name)
+                    .ok_or_else(|| AvroError::UnknownVariant(name.clone()))?;
+                let wip = wip.variant(index)?;
+                self.decode_fields(wip, fields, ed.variants[index].data.fields)
+            }
+            (Schema::Enum { symbols, .. }, Def::Enum(ed)) => {
+                let index = self.read_long()?;
+                let symbol = usize::try_from(index)
+                    .ok()
+                    .and_then(|index| symbols.get(index))
+                    .ok_or(AvroError::InvalidEnumIndex(index))?;
+                let variant = ed
+                    .variants
+                    .iter()
+                    .position(|v| v.name == symbol)
+                    .ok_or_else(|| AvroError::UnknownVariant(symbol.clone()))?;
+                let wip = wip.variant(variant)?;
+                self.decode_fields(wip, &[], ed.variants[variant].data.fields)
+            }
+            (Schema::Array(items), Def::List(_)) => {
+                let mut wip = wip.put_default()?;
+                while let Some(len) = self.block_len(items)? {
+                    for _ in 0..len {
+                        wip = wip.push()?;
+                        wip = self.decode(wip, items)?;
+                        wip = wip.pop()?;
+                    }
+                }
+                Ok(wip)
+            }
+            (Schema::Map(values), Def::Map(_)) => {
+                let mut wip = wip.put_default()?;
+                while let Some(len) = self.block_len(values)? {
+                    for _ in 0..len {
+                        wip = wip.push_map_key()?;
+                        let key_shape = unwrap(wip.shape());
+                        wip = self.decode_primitive(wip, &Schema::String, key_shape)?;
+                        wip = wip.push_map_value()?;
+                        wip = self.decode(wip, values)?;
+                        wip = wip.pop()?;
+                    }
+                }
+                Ok(wip)
+            }
+            _ => self.decode_primitive(wip, writer, shape),
+        }
+    }
+
+    /// Decodes the fields of a record into those of a struct or variant,
+    /// by name.
+    fn decode_fields(
+        &mut self,
+        mut wip: Wip<'input>,
+        writer_fields: &'s [RecordField],
+        fields: &'static [Field],
+    ) -> Result<Wip<'input>, AvroError> {
+        for writer_field in writer_fields {
+            match fields
+                .iter()
+                .position(|f| field_name(f) == writer_field.name.as_str())
+            {
+                Some(index) => {
+                    wip = wip.field(index)?;
+                    wip = self.decode(wip, &writer_field.schema)?;
+                    wip = wip.pop()?;
+                }
+                None => {
+                    trace!("Skipping field {}", writer_field.name);
+                    self.skip(&writer_field.schema)?;
+                }
+            }
+        }
+
+        for (index, field) in fields.iter().enumerate() {
+            if wip.is_field_set(index)? {
+                continue;
+            }
+            wip = wip.field(index)?;
+            wip = match field.maybe_default_fn() {
+                Some(Some(default_fn)) => wip.put_from_fn(default_fn)?,
+                Some(None) => wip.put_default()?,
+                None if matches!(field.shape().def, Def::Option(_)) => wip.put_default()?,
+                None => return Err(AvroError::MissingField(field.name.to_string())),
+            };
+            wip = wip.pop()?;
+        }
+        Ok(wip)
+    }
+
+    /// Decodes a primitive into the `target` shape, which is the current
+    /// frame's or the primitive a wrapper around it holds.
+    fn decode_primitive(
+        &mut self,
+        wip: Wip<'input>,
+        writer: &'s Schema,
+        target: &'static Shape,
+    ) -> Result<Wip<'input>, AvroError> {
+        let mismatch = || AvroError::SchemaMismatch {
+            writer: writer.to_string(),
+            reader: target.to_string(),
+        };
+
+        let value = match writer {
+            Schema::Null => Primitive::Null,
+            Schema::Boolean => match self.take(1)?[0] {
+                0 => Primitive::Boolean(false),
+                1 => Primitive::Boolean(true),
+                _ => return Err(AvroError::InvalidData),
+            },
+            Schema::Int | Schema::Long => Primitive::Long(self.read_long()?),
+            Schema::Float => Primitive::Float(f32::from_le_bytes(self.take_array()?)),
+            Schema::Double => Primitive::Double(f64::from_le_bytes(self.take_array()?)),
+            Schema::Bytes => Primitive::Bytes(self.read_bytes()?),
+            Schema::Fixed { size, .. } => Primitive::Bytes(self.take(*size)?),
+            Schema::String => Primitive::String(self.read_str()?),
+            _ => return Err(mismatch()),
+        };
+
+        macro_rules! integers {
+            ($($ty:ty),*) => {
+                $(
+                    if target.is_type::<$ty>() {
+                        let Primitive::Long(n) = value else {
+                            return Err(mismatch());
+                        };
+                        let n = <$ty>::try_from(n).map_err(|_| AvroError::IntegerOverflow)?;
+                        return Ok(wip.put(n)?);
+                    }
+                )*
+            };
+        }
+        integers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+        let wip = match value {
+            Primitive::Long(n) if target.is_type::<f32>() => wip.put(n as f32)?,
+            Primitive::Float(n) if target.is_type::<f32>() => wip.put(n)?,
+            Primitive::Long(n) if target.is_type::<f64>() => wip.put(n as f64)?,
+            Primitive::Float(n) if target.is_type::<f64>() => wip.put(n as f64)?,
+            Primitive::Double(n) if target.is_type::<f64>() => wip.put(n)?,
+            Primitive::Boolean(b) if target.is_type::<bool>() => wip.put(b)?,
+            Primitive::Null if target.is_type::<()>() => wip.put(())?,
+            Primitive::String(s) if target.is_type::<String>() => wip.put(s.to_string())?,
+            Primitive::String(s) if target.is_type::<&str>() => wip.put(s)?,
+            Primitive::String(s) if target.is_type::<Cow<'_, str>>() => {
+                wip.put(Cow::Borrowed(s))?
+            }
+            Primitive::Bytes(b) if target.is_type::<String>() => {
+                let s = core::str::from_utf8(b).map_err(|_| AvroError::InvalidUtf8)?;
+                wip.put(s.to_string())?
+            }
+            Primitive::String(s) if target.is_type::<char>() => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => wip.put(c)?,
+                    _ => return Err(AvroError::InvalidData),
+                }
+            }
+            Primitive::Bytes(b) if target.is_type::<Vec<u8>>() => wip.put(b.to_vec())?,
+            Primitive::String(s) if target.is_type::<Vec<u8>>() => {
+                wip.put(s.as_bytes().to_vec())?
+            }
+            Primitive::Bytes(b) if target.is_type::<&[u8]>() => wip.put(b)?,
+            Primitive::String(s) if is_textual(target) => wip.parse(s)?,
+            _ => return Err(mismatch()),
+        };
+        Ok(wip)
+    }
+
+    /// Reads past a value the reader has no use for.
+    fn skip(&mut self, schema: &'s Schema) -> Result<(), AvroError> {
+        match schema.resolve(&self.names)? {
+            Schema::Null => {}
+            Schema::Boolean => {
+                self.take(1)?;
+            }
+            Schema::Int | Schema::Long | Schema::Enum { .. } => {
+                self.read_long()?;
+            }
+            Schema::Float => {
+                self.take(4)?;
+            }
+            Schema::Double => {
+                self.take(8)?;
+            }
+            Schema::Bytes | Schema::String => {
+                self.read_bytes()?;
+            }
+            Schema::Fixed { size, .. } => {
+                self.take(*size)?;
+            }
+            Schema::Array(items) => {
+                while let Some(len) = self.block_len(items)? {
+                    for _ in 0..len {
+                        self.skip(items)?;
+                    }
+                }
+            }
+            Schema::Map(values) => {
+                while let Some(len) = self.block_len(values)? {
+                    for _ in 0..len {
+                        self.read_bytes()?;
+                        self.skip(values)?;
+                    }
+                }
+            }
+            Schema::Union(branches) => {
+                let index = self.read_long()?;
+                let branch = usize::try_from(index)
+                    .ok()
+                    .and_then(|index| branches.get(index))
+                    .ok_or(AvroError::InvalidUnionIndex(index))?;
+                self.skip(branch)?;
+            }
+            Schema::Record { fields, .. } => {
+                for field in fields {
+                    self.skip(&field.schema)?;
+                }
+            }
+            Schema::Ref(_) => unreachable!("references are resolved above"),
+        }
+        Ok(())
+    }
+}
+
+/// A primitive value, as written
+#[derive(Clone, Copy)]
+enum Primitive<'input> {
+    Null,
+    Boolean(bool),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Bytes(&'input [u8]),
+    String(&'input str),
+}
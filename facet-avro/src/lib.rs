@@ -0,0 +1,129 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+use std::borrow::Cow;
+
+use facet_core::{Def, Field, Shape, Variant};
+
+mod errors;
+pub use errors::Error as AvroError;
+
+pub use facet_reflect::{LimitKind, Limits};
+
+mod json;
+
+mod schema;
+pub use schema::*;
+
+mod to_avro;
+pub use to_avro::*;
+
+mod from_avro;
+pub use from_avro::*;
+
+mod container;
+pub use container::*;
+
+/// Follows transparent wrappers and smart pointers down to the type that
+/// decides the schema: an Avro primitive, an option, or a type of its own.
+fn unwrap(mut shape: &'static Shape) -> &'static Shape {
+    loop {
+        if matches!(shape.def, Def::Option(_)) || primitive(shape).is_some() {
+            return shape;
+        }
+        match shape.inner {
+            Some(inner) => shape = inner(),
+            None => return shape,
+        }
+    }
+}
+
+/// The Avro primitive type of a Rust scalar, for the scalars that have one
+fn primitive(shape: &'static Shape) -> Option<Schema> {
+    let schema = if shape.is_type::<bool>() {
+        Schema::Boolean
+    } else if shape.is_type::<i8>()
+        || shape.is_type::<i16>()
+        || shape.is_type::<i32>()
+        || shape.is_type::<u8>()
+        || shape.is_type::<u16>()
+    {
+        Schema::Int
+    } else if shape.is_type::<i64>()
+        || shape.is_type::<isize>()
+        || shape.is_type::<u32>()
+        || shape.is_type::<u64>()
+        || shape.is_type::<usize>()
+    {
+        // Unsigned 64-bit values above `i64::MAX` fail to encode
+        Schema::Long
+    } else if shape.is_type::<f32>() {
+        Schema::Float
+    } else if shape.is_type::<f64>() {
+        Schema::Double
+    } else if is_string(shape) || shape.is_type::<char>() {
+        Schema::String
+    } else if shape.is_type::<Vec<u8>>() || shape.is_type::<&[u8]>() {
+        Schema::Bytes
+    } else if shape.is_type::<()>() {
+        Schema::Null
+    } else {
+        return None;
+    };
+    Some(schema)
+}
+
+fn is_string(shape: &'static Shape) -> bool {
+    shape.is_type::<String>() || shape.is_type::<&str>() || shape.is_type::<Cow<'_, str>>()
+}
+
+/// Scalars without an Avro primitive type, such as UUIDs or timestamps, are
+/// strings, written with their `Display` implementation and read with their
+/// `FromStr` one
+fn is_textual(shape: &'static Shape) -> bool {
+    matches!(shape.def, Def::Scalar(_))
+        && shape.vtable.display.is_some()
+        && shape.vtable.parse.is_some()
+}
+
+/// The name of the record or enum a type is, which is its type name with
+/// generic parameters spelled out, as Avro names only allow letters, digits
+/// and underscores
+fn type_name(shape: &'static Shape) -> String {
+    let mut name = String::new();
+    for c in shape.to_string().chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c);
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name = name.trim_end_matches('_');
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// The name of the record the fields of an enum variant are written as
+fn variant_name(shape: &'static Shape, variant: &Variant) -> String {
+    format!("{}_{}", type_name(shape), variant.name)
+}
+
+/// The name of a field in a record: its own, or `_0`, `_1`... for the
+/// fields of tuples
+fn field_name(field: &'static Field) -> Cow<'static, str> {
+    if field.name.starts_with(|c: char| c.is_ascii_digit()) {
+        Cow::Owned(format!("_{}", field.name))
+    } else {
+        Cow::Borrowed(field.name)
+    }
+}
+
+/// Whether an enum only has unit variants, and is written as an Avro enum
+/// rather than a union of records
+fn is_plain_enum(variants: &[Variant]) -> bool {
+    variants.iter().all(|v| v.data.fields.is_empty())
+}
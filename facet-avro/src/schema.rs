@@ -0,0 +1,530 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use facet_core::{Def, Facet, Field, Shape};
+use facet_reflect::Wip;
+
+use crate::json::Json;
+use crate::to_avro::json_value;
+use crate::{
+    AvroError, field_name, is_plain_enum, is_textual, primitive, type_name, unwrap, variant_name,
+};
+
+/// An Avro schema
+///
+/// Records, enums and fixed types are defined where they first appear, and
+/// referred to by name afterwards, which is how recursive types are
+/// written.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Schema {
+    /// No value
+    Null,
+    /// A boolean
+    Boolean,
+    /// A 32-bit signed integer
+    Int,
+    /// A 64-bit signed integer
+    Long,
+    /// A single precision float
+    Float,
+    /// A double precision float
+    Double,
+    /// A sequence of bytes
+    Bytes,
+    /// A UTF-8 string
+    String,
+    /// A list of items of the same schema
+    Array(Box<Schema>),
+    /// A map from strings to values of the same schema
+    Map(Box<Schema>),
+    /// One of several schemas, such as `["null", "string"]`
+    Union(Vec<Schema>),
+    /// Named fields
+    Record {
+        /// The full name of the record
+        name: String,
+        /// The documentation of the record
+        doc: Option<String>,
+        /// The fields, in the order they're encoded
+        fields: Vec<RecordField>,
+    },
+    /// One of several symbols
+    Enum {
+        /// The full name of the enum
+        name: String,
+        /// The documentation of the enum
+        doc: Option<String>,
+        /// The symbols, in the order of their index
+        symbols: Vec<String>,
+    },
+    /// A fixed number of bytes
+    Fixed {
+        /// The full name of the fixed type
+        name: String,
+        /// The number of bytes
+        size: usize,
+    },
+    /// A record, enum or fixed type defined elsewhere in the schema
+    Ref(String),
+}
+
+/// A field of a [`Schema::Record`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordField {
+    /// The name of the field
+    pub name: String,
+    /// The documentation of the field
+    pub doc: Option<String>,
+    /// The schema of the field's values
+    pub schema: Schema,
+    default: Option<Json>,
+}
+
+impl RecordField {
+    /// The default value of the field, as JSON, if it has one
+    pub fn default(&self) -> Option<String> {
+        let default = self.default.as_ref()?;
+        let mut out = String::new();
+        default.write(&mut out);
+        Some(out)
+    }
+}
+
+/// The named types of a schema, by full name
+pub(crate) type Names<'s> = HashMap<&'s str, &'s Schema>;
+
+/// Derives the Avro schema of a type.
+///
+/// - Structs, tuples and tuple structs are records, whose fields are named
+///   `_0`, `_1`... for tuples.
+/// - Enums whose variants are all units are enums. Other enums are unions
+///   of records, one per variant, named after the enum and the variant.
+/// - `Option`s are unions of `"null"` and the schema of what they hold.
+/// - Lists and slices are arrays, except `Vec<u8>` and `&[u8]` which are
+///   bytes, and maps are maps, their keys written as strings.
+/// - Integers are ints up to 32 bits when signed and 16 bits when
+///   unsigned, and longs above.
+/// - Other scalars with `Display` and `FromStr` implementations, such as
+///   UUIDs, are strings.
+///
+/// Doc comments become `doc` attributes, `Option` fields default to `null`,
+/// and fields with `#[facet(default)]` get their default value.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// /// A page view
+/// struct View {
+///     url: String,
+///     referrer: Option<String>,
+/// }
+///
+/// let schema = facet_avro::schema::<View>().unwrap();
+/// assert_eq!(
+///     schema.to_string(),
+///     r#"{"type":"record","name":"View","doc":"A page view","fields":[{"name":"url","type":"string"},{"name":"referrer","type":["null","string"],"default":null}]}"#
+/// );
+/// ```
+pub fn schema<'a, T: Facet<'a>>() -> Result<Schema, AvroError> {
+    Schema::from_shape(T::SHAPE)
+}
+
+impl Schema {
+    /// Derives the schema of a shape, as [`schema`] does.
+    pub fn from_shape(shape: &'static Shape) -> Result<Schema, AvroError> {
+        Builder::default().schema(shape)
+    }
+
+    /// Parses a schema from its JSON form.
+    ///
+    /// Named types are identified by their full name, namespace included.
+    /// Logical types and other attributes this crate doesn't use are
+    /// ignored.
+    pub fn parse(json: &str) -> Result<Schema, AvroError> {
+        from_json(&Json::parse(json)?, "")
+    }
+
+    fn to_json(&self) -> Json {
+        let string = |s: &str| Json::String(s.to_string());
+        let object = |members: Vec<(&str, Json)>| {
+            Json::Object(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value))
+                    .collect(),
+            )
+        };
+        let doc = |doc: &Option<String>| doc.as_deref().map(|doc| ("doc", string(doc)));
+
+        match self {
+            Schema::Null => string("null"),
+            Schema::Boolean => string("boolean"),
+            Schema::Int => string("int"),
+            Schema::Long => string("long"),
+            Schema::Float => string("float"),
+            Schema::Double => string("double"),
+            Schema::Bytes => string("bytes"),
+            Schema::String => string("string"),
+            Schema::Array(items) => {
+                object(vec![("type", string("array")), ("items", items.to_json())])
+            }
+            Schema::Map(values) => {
+                object(vec![("type", string("map")), ("values", values.to_json())])
+            }
+            Schema::Union(branches) => Json::Array(branches.iter().map(Schema::to_json).collect()),
+            Schema::Record {
+                name,
+                doc: d,
+                fields,
+            } => {
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        let mut members = vec![("name", string(&field.name))];
+                        members.extend(doc(&field.doc));
+                        members.push(("type", field.schema.to_json()));
+                        if let Some(default) = &field.default {
+                            members.push(("default", default.clone()));
+                        }
+                        object(members)
+                    })
+                    .collect();
+                let mut members = vec![("type", string("record")), ("name", string(name))];
+                members.extend(doc(d));
+                members.push(("fields", Json::Array(fields)));
+                object(members)
+            }
+            Schema::Enum {
+                name,
+                doc: d,
+                symbols,
+            } => {
+                let mut members = vec![("type", string("enum")), ("name", string(name))];
+                members.extend(doc(d));
+                let symbols = symbols.iter().map(|symbol| string(symbol)).collect();
+                members.push(("symbols", Json::Array(symbols)));
+                object(members)
+            }
+            Schema::Fixed { name, size } => object(vec![
+                ("type", string("fixed")),
+                ("name", string(name)),
+                ("size", Json::Number(size.to_string())),
+            ]),
+            Schema::Ref(name) => string(name),
+        }
+    }
+
+    /// The name of a record, enum or fixed type, or of what a reference
+    /// refers to
+    pub(crate) fn name(&self) -> Option<&str> {
+        match self {
+            Schema::Record { name, .. }
+            | Schema::Enum { name, .. }
+            | Schema::Fixed { name, .. }
+            | Schema::Ref(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Collects the named types the schema defines.
+    pub(crate) fn names(&self) -> Names<'_> {
+        fn collect<'s>(schema: &'s Schema, names: &mut Names<'s>) {
+            match schema {
+                Schema::Array(inner) | Schema::Map(inner) => collect(inner, names),
+                Schema::Union(branches) => branches.iter().for_each(|b| collect(b, names)),
+                Schema::Record { name, fields, .. } => {
+                    names.insert(name, schema);
+                    fields.iter().for_each(|f| collect(&f.schema, names));
+                }
+                Schema::Enum { name, .. } | Schema::Fixed { name, .. } => {
+                    names.insert(name, schema);
+                }
+                _ => {}
+            }
+        }
+
+        let mut names = Names::new();
+        collect(self, &mut names);
+        names
+    }
+
+    /// Follows a reference to the type it names.
+    pub(crate) fn resolve<'s>(&'s self, names: &Names<'s>) -> Result<&'s Schema, AvroError> {
+        match self {
+            Schema::Ref(name) => names
+                .get(name.as_str())
+                .copied()
+                .ok_or_else(|| AvroError::InvalidSchema(format!("undefined name: {}", name))),
+            schema => Ok(schema),
+        }
+    }
+}
+
+impl fmt::Display for Schema {
+    /// Writes the schema as JSON, without whitespace.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        self.to_json().write(&mut out);
+        f.write_str(&out)
+    }
+}
+
+fn unsupported(shape: &'static Shape) -> AvroError {
+    AvroError::UnsupportedShape(shape.to_string())
+}
+
+/// Joins the lines of a doc comment, without the space after each `///`
+fn doc(lines: &'static [&'static str]) -> Option<String> {
+    if lines.is_empty() {
+        return None;
+    }
+    let lines: Vec<&str> = lines
+        .iter()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect();
+    Some(lines.join("\n"))
+}
+
+/// Derives schemas, defining each named type once
+#[derive(Default)]
+struct Builder {
+    defined: Vec<String>,
+}
+
+impl Builder {
+    fn schema(&mut self, shape: &'static Shape) -> Result<Schema, AvroError> {
+        let shape = unwrap(shape);
+        if let Some(schema) = primitive(shape) {
+            return Ok(schema);
+        }
+
+        match shape.def {
+            Def::Option(od) => {
+                let branches = match self.schema(od.t())? {
+                    Schema::Union(branches) => branches,
+                    schema => vec![schema],
+                };
+                // Unions can't hold unions, nor `null` twice
+                if branches.contains(&Schema::Null) {
+                    return Err(unsupported(shape));
+                }
+                Ok(Schema::Union(
+                    std::iter::once(Schema::Null).chain(branches).collect(),
+                ))
+            }
+            _ if is_textual(shape) => Ok(Schema::String),
+            Def::List(ld) => Ok(Schema::Array(Box::new(self.schema(ld.t())?))),
+            Def::Array(ad) => Ok(Schema::Array(Box::new(self.schema(ad.t())?))),
+            Def::Slice(sd) => Ok(Schema::Array(Box::new(self.schema(sd.t())?))),
+            Def::Map(md) => {
+                if self.schema(md.k)? != Schema::String {
+                    return Err(unsupported(shape));
+                }
+                Ok(Schema::Map(Box::new(self.schema(md.v)?)))
+            }
+            Def::Struct(sd) => self.record(type_name(shape), shape.doc, sd.fields),
+            Def::Enum(ed) if is_plain_enum(ed.variants) => {
+                let name = type_name(shape);
+                if let Some(schema) = self.reference(&name) {
+                    return Ok(schema);
+                }
+                Ok(Schema::Enum {
+                    name,
+                    doc: doc(shape.doc),
+                    symbols: ed.variants.iter().map(|v| v.name.to_string()).collect(),
+                })
+            }
+            Def::Enum(ed) => {
+                let branches = ed
+                    .variants
+                    .iter()
+                    .map(|v| self.record(variant_name(shape, v), v.doc, v.data.fields))
+                    .collect::<Result<_, _>>()?;
+                Ok(Schema::Union(branches))
+            }
+            _ => Err(unsupported(shape)),
+        }
+    }
+
+    /// A reference to a named type, if it's already defined; otherwise
+    /// marks it as defined.
+    fn reference(&mut self, name: &str) -> Option<Schema> {
+        if self.defined.iter().any(|defined| defined == name) {
+            return Some(Schema::Ref(name.to_string()));
+        }
+        self.defined.push(name.to_string());
+        None
+    }
+
+    fn record(
+        &mut self,
+        name: String,
+        lines: &'static [&'static str],
+        fields: &'static [Field],
+    ) -> Result<Schema, AvroError> {
+        if let Some(schema) = self.reference(&name) {
+            return Ok(schema);
+        }
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let schema = self.schema(field.shape())?;
+                Ok(RecordField {
+                    name: field_name(field).into_owned(),
+                    doc: doc(field.doc),
+                    default: default_value(field, &schema),
+                    schema,
+                })
+            })
+            .collect::<Result<_, AvroError>>()?;
+        Ok(Schema::Record {
+            name,
+            doc: doc(lines),
+            fields,
+        })
+    }
+}
+
+/// The default value of a field, as JSON: its `#[facet(default)]` if it has
+/// one, or `null` for options
+fn default_value(field: &'static Field, schema: &Schema) -> Option<Json> {
+    let default_fn = match field.maybe_default_fn() {
+        Some(default_fn) => default_fn,
+        None if matches!(field.shape().def, Def::Option(_)) => return Some(Json::Null),
+        None => return None,
+    };
+    let wip = Wip::alloc_shape(field.shape()).ok()?;
+    let wip = match default_fn {
+        Some(default_fn) => wip.put_from_fn(default_fn).ok()?,
+        None => wip.put_default().ok()?,
+    };
+    let value = wip.build().ok()?;
+    json_value(value.peek(), schema)
+}
+
+/// Parses a schema from JSON, in the namespace of the type around it.
+fn from_json(json: &Json, namespace: &str) -> Result<Schema, AvroError> {
+    let invalid = |reason: &str| AvroError::InvalidSchema(reason.to_string());
+    let qualify = |name: &str, namespace: &str| {
+        if namespace.is_empty() || name.contains('.') {
+            name.to_string()
+        } else {
+            format!("{}.{}", namespace, name)
+        }
+    };
+
+    let object = match json {
+        Json::String(name) => {
+            return Ok(match name.as_str() {
+                "null" => Schema::Null,
+                "boolean" => Schema::Boolean,
+                "int" => Schema::Int,
+                "long" => Schema::Long,
+                "float" => Schema::Float,
+                "double" => Schema::Double,
+                "bytes" => Schema::Bytes,
+                "string" => Schema::String,
+                name => Schema::Ref(qualify(name, namespace)),
+            });
+        }
+        Json::Array(branches) => {
+            let branches = branches
+                .iter()
+                .map(|branch| from_json(branch, namespace))
+                .collect::<Result<_, _>>()?;
+            return Ok(Schema::Union(branches));
+        }
+        Json::Object(_) => json,
+        _ => return Err(invalid("expected a string, array or object")),
+    };
+
+    let ty = object.get("type").ok_or_else(|| invalid("missing type"))?;
+    let Some(ty) = ty.as_str() else {
+        // A type spelled out in full, as in `{"type": {"type": "array", ...}}`
+        return from_json(ty, namespace);
+    };
+    let name = || {
+        let name = object
+            .get("name")
+            .and_then(Json::as_str)
+            .ok_or_else(|| invalid("missing name"))?;
+        let namespace = object
+            .get("namespace")
+            .and_then(Json::as_str)
+            .unwrap_or(namespace);
+        Ok::<_, AvroError>(qualify(name, namespace))
+    };
+    let doc = object.get("doc").and_then(Json::as_str).map(str::to_string);
+
+    match ty {
+        "record" | "error" => {
+            let name = name()?;
+            // Names inside the record are relative to its namespace
+            let inner = name.rsplit_once('.').map_or("", |(namespace, _)| namespace);
+            let Some(Json::Array(fields)) = object.get("fields") else {
+                return Err(invalid("missing fields"));
+            };
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    let name = field
+                        .get("name")
+                        .and_then(Json::as_str)
+                        .ok_or_else(|| invalid("missing field name"))?;
+                    let ty = field
+                        .get("type")
+                        .ok_or_else(|| invalid("missing field type"))?;
+                    Ok(RecordField {
+                        name: name.to_string(),
+                        doc: field.get("doc").and_then(Json::as_str).map(str::to_string),
+                        schema: from_json(ty, inner)?,
+                        default: field.get("default").cloned(),
+                    })
+                })
+                .collect::<Result<_, AvroError>>()?;
+            Ok(Schema::Record { name, doc, fields })
+        }
+        "enum" => {
+            let Some(Json::Array(symbols)) = object.get("symbols") else {
+                return Err(invalid("missing symbols"));
+            };
+            let symbols = symbols
+                .iter()
+                .map(|symbol| symbol.as_str().map(str::to_string))
+                .collect::<Option<_>>()
+                .ok_or_else(|| invalid("symbols must be strings"))?;
+            Ok(Schema::Enum {
+                name: name()?,
+                doc,
+                symbols,
+            })
+        }
+        "fixed" => {
+            let size = match object.get("size") {
+                Some(Json::Number(size)) => size.parse().ok(),
+                _ => None,
+            };
+            Ok(Schema::Fixed {
+                name: name()?,
+                size: size.ok_or_else(|| invalid("missing or invalid size"))?,
+            })
+        }
+        "array" => {
+            let items = object
+                .get("items")
+                .ok_or_else(|| invalid("missing items"))?;
+            Ok(Schema::Array(Box::new(from_json(items, namespace)?)))
+        }
+        "map" => {
+            let values = object
+                .get("values")
+                .ok_or_else(|| invalid("missing values"))?;
+            Ok(Schema::Map(Box::new(from_json(values, namespace)?)))
+        }
+        // Primitive types in object form, possibly with a logical type
+        primitive => from_json(&Json::String(primitive.to_string()), namespace),
+    }
+}
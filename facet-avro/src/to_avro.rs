@@ -0,0 +1,351 @@
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use facet_core::{Def, Facet, Field};
+use facet_reflect::Peek;
+use log::trace;
+
+use crate::json::Json;
+use crate::schema::Names;
+use crate::{Schema, field_name, is_string, primitive, variant_name};
+
+/// Serializes a value to the Avro binary encoding, against the schema
+/// [`schema`](crate::schema()) derives from its type.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Click {
+///     x: i32,
+///     label: Option<String>,
+/// }
+///
+/// let bytes = facet_avro::to_vec(&Click { x: -2, label: Some("ok".to_string()) });
+/// // -2 zigzag-encoded, then the second branch of the union and the string
+/// assert_eq!(bytes, [0x03, 0x02, 0x04, b'o', b'k']);
+/// ```
+pub fn to_vec<'a, T: Facet<'a>>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    to_writer(value, &mut out).unwrap();
+    out
+}
+
+/// Serializes a value to a writer in the Avro binary encoding, against the
+/// schema [`schema`](crate::schema()) derives from its type.
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    let schema = Schema::from_shape(T::SHAPE).map_err(other)?;
+    let mut out = Vec::new();
+    write_value(&mut out, Peek::new(value), &schema, &schema.names())?;
+    writer.write_all(&out)
+}
+
+fn other(e: impl core::fmt::Display) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+fn mismatch(peek: Peek<'_, '_>, schema: &Schema) -> io::Error {
+    io::Error::other(format!("Cannot write {} as {}", peek.shape(), schema))
+}
+
+/// Follows transparent wrappers and smart pointers, like
+/// [`unwrap`](crate::unwrap) does for shapes.
+fn unwrap<'mem, 'facet>(mut peek: Peek<'mem, 'facet>) -> Peek<'mem, 'facet> {
+    loop {
+        let shape = peek.shape();
+        if matches!(shape.def, Def::Option(_)) || primitive(shape).is_some() {
+            return peek;
+        }
+        match peek.borrow_inner() {
+            Some(inner) => peek = inner,
+            None => return peek,
+        }
+    }
+}
+
+/// Writes a long: zigzag-encoded, then as a varint
+pub(crate) fn write_long(out: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+pub(crate) fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(out, bytes.len() as i64);
+    out.extend_from_slice(bytes);
+}
+
+/// The value of an integer of any width, if it fits a long
+fn long_value(peek: Peek<'_, '_>) -> Option<i64> {
+    macro_rules! integers {
+        ($($ty:ty),*) => {
+            $(
+                if let Ok(value) = peek.get::<$ty>() {
+                    return i64::try_from(*value).ok();
+                }
+            )*
+        };
+    }
+    integers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+    None
+}
+
+/// The text of a string, a char, or a scalar written as a string
+fn string_value<'mem>(peek: Peek<'mem, '_>) -> Option<Cow<'mem, str>> {
+    let shape = peek.shape();
+    if is_string(shape) {
+        if let Ok(s) = peek.get::<String>() {
+            return Some(Cow::Borrowed(s));
+        }
+        if let Ok(s) = peek.get::<&str>() {
+            return Some(Cow::Borrowed(s));
+        }
+        if let Ok(s) = peek.get::<Cow<'_, str>>() {
+            return Some(Cow::Borrowed(s));
+        }
+    }
+    if let Ok(c) = peek.get::<char>() {
+        return Some(Cow::Owned(c.to_string()));
+    }
+    if shape.vtable.display.is_some() {
+        return Some(Cow::Owned(peek.to_string()));
+    }
+    None
+}
+
+fn bytes_value<'mem>(peek: Peek<'mem, '_>) -> Option<&'mem [u8]> {
+    if let Ok(bytes) = peek.get::<Vec<u8>>() {
+        return Some(bytes);
+    }
+    peek.get::<&[u8]>().ok().copied()
+}
+
+/// The items of a list, array or slice
+fn items<'mem, 'facet>(peek: Peek<'mem, 'facet>) -> Option<Vec<Peek<'mem, 'facet>>> {
+    match peek.shape().def {
+        Def::List(_) => Some(peek.into_list().ok()?.iter().collect()),
+        Def::Array(_) => Some(peek.into_array().ok()?.iter().collect()),
+        Def::Slice(_) => Some(peek.into_slice().ok()?.iter().collect()),
+        _ => None,
+    }
+}
+
+/// The fields of a struct, or of the active variant of an enum
+fn fields<'mem, 'facet>(
+    peek: Peek<'mem, 'facet>,
+) -> Option<Vec<(&'static Field, Peek<'mem, 'facet>)>> {
+    match peek.shape().def {
+        Def::Struct(_) => Some(peek.into_struct().ok()?.fields().collect()),
+        Def::Enum(_) => Some(peek.into_enum().ok()?.fields().collect()),
+        _ => None,
+    }
+}
+
+/// The union branch a value is written as: `null` for `None`, the record of
+/// its variant for an enum, and otherwise the only other branch
+fn branch<'mem, 'facet>(
+    peek: Peek<'mem, 'facet>,
+    branches: &[Schema],
+) -> Option<(usize, Peek<'mem, 'facet>)> {
+    let peek = unwrap(peek);
+    let shape = peek.shape();
+    match shape.def {
+        Def::Option(_) => match peek.into_option().ok()?.value() {
+            Some(value) => branch(value, branches),
+            None => Some((branches.iter().position(|b| *b == Schema::Null)?, peek)),
+        },
+        Def::Enum(_) => {
+            let pe = peek.into_enum().ok()?;
+            let name = variant_name(shape, pe.active_variant());
+            let index = branches
+                .iter()
+                .position(|b| b.name() == Some(name.as_str()))?;
+            Some((index, peek))
+        }
+        _ => Some((branches.iter().position(|b| *b != Schema::Null)?, peek)),
+    }
+}
+
+pub(crate) fn write_value(
+    out: &mut Vec<u8>,
+    peek: Peek<'_, '_>,
+    schema: &Schema,
+    names: &Names<'_>,
+) -> io::Result<()> {
+    let schema = schema.resolve(names).map_err(other)?;
+    let peek = unwrap(peek);
+    trace!("Serializing {} as {}", peek.shape(), schema);
+
+    match schema {
+        Schema::Null => Ok(()),
+        Schema::Boolean => {
+            let value = peek.get::<bool>().map_err(other)?;
+            out.push(*value as u8);
+            Ok(())
+        }
+        Schema::Int | Schema::Long => {
+            let value = long_value(peek).ok_or_else(|| mismatch(peek, schema))?;
+            write_long(out, value);
+            Ok(())
+        }
+        Schema::Float => {
+            let value = peek.get::<f32>().map_err(other)?;
+            out.extend_from_slice(&value.to_le_bytes());
+            Ok(())
+        }
+        Schema::Double => {
+            let value = peek.get::<f64>().map_err(other)?;
+            out.extend_from_slice(&value.to_le_bytes());
+            Ok(())
+        }
+        Schema::Bytes => {
+            let bytes = bytes_value(peek).ok_or_else(|| mismatch(peek, schema))?;
+            write_bytes(out, bytes);
+            Ok(())
+        }
+        Schema::Fixed { size, .. } => {
+            let bytes = bytes_value(peek)
+                .filter(|bytes| bytes.len() == *size)
+                .ok_or_else(|| mismatch(peek, schema))?;
+            out.extend_from_slice(bytes);
+            Ok(())
+        }
+        Schema::String => {
+            let s = string_value(peek).ok_or_else(|| mismatch(peek, schema))?;
+            write_bytes(out, s.as_bytes());
+            Ok(())
+        }
+        Schema::Array(item_schema) => {
+            let items = items(peek).ok_or_else(|| mismatch(peek, schema))?;
+            // A single block, then the empty block that ends the array
+            if !items.is_empty() {
+                write_long(out, items.len() as i64);
+                for item in items {
+                    write_value(out, item, item_schema, names)?;
+                }
+            }
+            write_long(out, 0);
+            Ok(())
+        }
+        Schema::Map(value_schema) => {
+            let map = peek.into_map().map_err(other)?;
+            if !map.is_empty() {
+                write_long(out, map.len() as i64);
+                for (key, value) in map.iter() {
+                    let key = string_value(unwrap(key)).ok_or_else(|| mismatch(key, schema))?;
+                    write_bytes(out, key.as_bytes());
+                    write_value(out, value, value_schema, names)?;
+                }
+            }
+            write_long(out, 0);
+            Ok(())
+        }
+        Schema::Union(branches) => {
+            let (index, value) = branch(peek, branches).ok_or_else(|| mismatch(peek, schema))?;
+            write_long(out, index as i64);
+            write_value(out, value, &branches[index], names)
+        }
+        Schema::Record {
+            fields: schema_fields,
+            ..
+        } => {
+            let fields = fields(peek).ok_or_else(|| mismatch(peek, schema))?;
+            for (field, value) in fields {
+                let name = field_name(field);
+                let Some(field_schema) = schema_fields.iter().find(|f| f.name == name) else {
+                    return Err(mismatch(peek, schema));
+                };
+                write_value(out, value, &field_schema.schema, names)?;
+            }
+            Ok(())
+        }
+        Schema::Enum { symbols, .. } => {
+            let pe = peek.into_enum().map_err(other)?;
+            let name = pe.active_variant().name;
+            let index = symbols
+                .iter()
+                .position(|symbol| symbol == name)
+                .ok_or_else(|| mismatch(peek, schema))?;
+            write_long(out, index as i64);
+            Ok(())
+        }
+        Schema::Ref(_) => unreachable!("references are resolved above"),
+    }
+}
+
+/// Converts a value to JSON, as Avro writes defaults in schemas. Returns
+/// `None` for values JSON can't hold, such as infinite floats, or defaults
+/// Avro can't express, such as a union default other than its first
+/// branch.
+pub(crate) fn json_value(peek: Peek<'_, '_>, schema: &Schema) -> Option<Json> {
+    let peek = unwrap(peek);
+    let json = match schema {
+        Schema::Null => Json::Null,
+        Schema::Boolean => Json::Bool(*peek.get::<bool>().ok()?),
+        Schema::Int | Schema::Long => Json::Number(long_value(peek)?.to_string()),
+        Schema::Float => {
+            let value = *peek.get::<f32>().ok()?;
+            if !value.is_finite() {
+                return None;
+            }
+            Json::Number(format!("{:?}", value))
+        }
+        Schema::Double => {
+            let value = *peek.get::<f64>().ok()?;
+            if !value.is_finite() {
+                return None;
+            }
+            Json::Number(format!("{:?}", value))
+        }
+        Schema::String => Json::String(string_value(peek)?.into_owned()),
+        // Bytes are strings of the code points 0 to 255
+        Schema::Bytes | Schema::Fixed { .. } => {
+            Json::String(bytes_value(peek)?.iter().map(|&b| b as char).collect())
+        }
+        Schema::Array(item_schema) => Json::Array(
+            items(peek)?
+                .into_iter()
+                .map(|item| json_value(item, item_schema))
+                .collect::<Option<_>>()?,
+        ),
+        Schema::Map(value_schema) => Json::Object(
+            peek.into_map()
+                .ok()?
+                .iter()
+                .map(|(key, value)| {
+                    let key = string_value(unwrap(key))?.into_owned();
+                    Some((key, json_value(value, value_schema)?))
+                })
+                .collect::<Option<_>>()?,
+        ),
+        Schema::Union(branches) => {
+            let (index, value) = branch(peek, branches)?;
+            if index != 0 {
+                return None;
+            }
+            json_value(value, &branches[0])?
+        }
+        Schema::Record {
+            fields: schema_fields,
+            ..
+        } => Json::Object(
+            fields(peek)?
+                .into_iter()
+                .zip(schema_fields)
+                .map(|((_, value), field)| {
+                    Some((field.name.clone(), json_value(value, &field.schema)?))
+                })
+                .collect::<Option<_>>()?,
+        ),
+        Schema::Enum { .. } => {
+            Json::String(peek.into_enum().ok()?.active_variant().name.to_string())
+        }
+        // Defaults of recursive types aren't worth spelling out
+        Schema::Ref(_) => return None,
+    };
+    Some(json)
+}
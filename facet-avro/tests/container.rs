@@ -0,0 +1,172 @@
+use eyre::Result;
+use facet::Facet;
+use facet_avro::{
+    AvroError, Codec, LimitKind, Limits, from_container, from_container_with_limits, to_container,
+    write_container,
+};
+
+#[derive(Debug, PartialEq, Facet)]
+struct Reading {
+    sensor: String,
+    celsius: f64,
+}
+
+fn readings(count: usize) -> Vec<Reading> {
+    (0..count)
+        .map(|i| Reading {
+            sensor: format!("sensor-{}", i % 7),
+            celsius: i as f64 / 4.0,
+        })
+        .collect()
+}
+
+#[test]
+fn null_codec() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let values = readings(3);
+    let file = to_container(&values, Codec::Null);
+    assert_eq!(&file[..4], b"Obj\x01");
+    assert_eq!(from_container::<Reading>(&file)?, values);
+    Ok(())
+}
+
+#[test]
+fn deflate_codec_across_blocks() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // Enough values to fill several 64 KiB blocks
+    let values = readings(20_000);
+    let null = to_container(&values, Codec::Null);
+    let deflate = to_container(&values, Codec::Deflate);
+    assert!(deflate.len() < null.len());
+    assert_eq!(from_container::<Reading>(&deflate)?, values);
+    Ok(())
+}
+
+#[test]
+fn empty() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let mut file = Vec::new();
+    write_container::<Reading, _>(&[], &mut file, Codec::Deflate)?;
+    assert!(from_container::<Reading>(&file)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn reader_with_a_newer_type() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct ReadingV2 {
+        sensor: String,
+        celsius: f64,
+        #[facet(default)]
+        humidity: f32,
+        location: Option<String>,
+    }
+
+    let file = to_container(&readings(2), Codec::Null);
+    assert_eq!(
+        from_container::<ReadingV2>(&file)?,
+        [
+            ReadingV2 {
+                sensor: "sensor-0".to_string(),
+                celsius: 0.0,
+                humidity: 0.0,
+                location: None,
+            },
+            ReadingV2 {
+                sensor: "sensor-1".to_string(),
+                celsius: 0.25,
+                humidity: 0.0,
+                location: None,
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_files() {
+    facet_testhelpers::setup();
+
+    assert!(matches!(
+        from_container::<Reading>(b"PK\x03\x04"),
+        Err(AvroError::InvalidContainer(_))
+    ));
+
+    // The last byte of the file is the last byte of the sync marker
+    let mut file = to_container(&readings(1), Codec::Null);
+    *file.last_mut().unwrap() ^= 0xff;
+    assert!(matches!(
+        from_container::<Reading>(&file),
+        Err(AvroError::InvalidContainer(_))
+    ));
+
+    let mut file = b"Obj\x01".to_vec();
+    // One metadata entry, then the end of the map
+    file.extend_from_slice(b"\x02\x14avro.codec\x0csnappy\x00");
+    assert!(matches!(
+        from_container::<Reading>(&file),
+        Err(AvroError::UnsupportedCodec(codec)) if codec == "snappy"
+    ));
+}
+
+#[test]
+fn limits() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // A megabyte of zeros deflates to a few kilobytes
+    let file = to_container(&vec![0u8; 1 << 20], Codec::Deflate);
+    assert!(file.len() < 16 * 1024);
+    let err = from_container_with_limits::<u8>(&file, Limits::default().max_alloc_bytes(1 << 16))
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            AvroError::LimitExceeded {
+                kind: LimitKind::Allocation,
+                max: 65536,
+                ..
+            }
+        ),
+        "{err}"
+    );
+
+    let file = to_container(&readings(10), Codec::Null);
+    let err = from_container_with_limits::<Reading>(&file, Limits::default().max_collection_len(5))
+        .unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            AvroError::LimitExceeded {
+                kind: LimitKind::CollectionLength,
+                max: 5,
+                path,
+            } if path == "$[0]"
+        ),
+        "{err}"
+    );
+
+    let err = from_container_with_limits::<Reading>(&file, Limits::default().max_string_len(4))
+        .unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            AvroError::LimitExceeded {
+                kind: LimitKind::StringLength,
+                path,
+                ..
+            } if path == "$[0]"
+        ),
+        "{err}"
+    );
+
+    assert_eq!(
+        from_container_with_limits::<Reading>(&file, Limits::default().max_collection_len(10))?,
+        readings(10)
+    );
+    Ok(())
+}
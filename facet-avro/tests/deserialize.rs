@@ -0,0 +1,282 @@
+use eyre::Result;
+use facet::Facet;
+use facet_avro::{AvroError, Schema, from_slice, from_slice_with_schema, schema, to_vec};
+use std::collections::HashMap;
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+
+    let data = [0x0a, b'A', b'l', b'i', b'c', b'e', 0x3c];
+    assert_eq!(
+        from_slice::<TestStruct>(&data)?,
+        TestStruct {
+            name: "Alice".to_string(),
+            age: 30,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Color {
+        Red,
+        Green,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Shape {
+        Circle { radius: f64 },
+        Square(u8),
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Drawing {
+        title: Option<String>,
+        colors: Vec<Color>,
+        shapes: Vec<Shape>,
+        layers: HashMap<String, Vec<i32>>,
+        thumbnail: Vec<u8>,
+        scale: f32,
+        visible: bool,
+    }
+
+    let drawing = Drawing {
+        title: Some("sketch".to_string()),
+        colors: vec![Color::Green, Color::Red],
+        shapes: vec![Shape::Circle { radius: 1.5 }, Shape::Square(4)],
+        layers: HashMap::from([("base".to_string(), vec![-1, 0, 1])]),
+        thumbnail: vec![0, 1, 2, 255],
+        scale: 0.5,
+        visible: true,
+    };
+    assert_eq!(from_slice::<Drawing>(&to_vec(&drawing))?, drawing);
+    Ok(())
+}
+
+#[test]
+fn recursive_types() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Tree {
+        value: i32,
+        children: Vec<Tree>,
+    }
+
+    let tree = Tree {
+        value: 1,
+        children: vec![Tree {
+            value: 2,
+            children: vec![],
+        }],
+    };
+    assert_eq!(from_slice::<Tree>(&to_vec(&tree))?, tree);
+    Ok(())
+}
+
+#[test]
+fn borrowed_strings() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Borrowed<'a> {
+        name: &'a str,
+    }
+
+    let data = [0x04, b'h', b'i'];
+    assert_eq!(from_slice::<Borrowed>(&data)?, Borrowed { name: "hi" });
+    Ok(())
+}
+
+#[test]
+fn blocks_with_sizes() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // A block of -2 items, 2 bytes long, then the empty block
+    let data = [0x03, 0x04, 0x02, 0x04, 0x00];
+    assert_eq!(from_slice::<Vec<i32>>(&data)?, [1, 2]);
+    Ok(())
+}
+
+#[test]
+fn errors() {
+    facet_testhelpers::setup();
+
+    assert!(matches!(
+        from_slice::<String>(&[0x0a, b'a']),
+        Err(AvroError::InsufficientData)
+    ));
+    assert!(matches!(
+        from_slice::<bool>(&[0x02]),
+        Err(AvroError::InvalidData)
+    ));
+    assert!(matches!(
+        from_slice::<i32>(&[0x02, 0x00]),
+        Err(AvroError::InvalidData)
+    ));
+    assert!(matches!(
+        from_slice::<Option<i32>>(&[0x04]),
+        Err(AvroError::InvalidUnionIndex(2))
+    ));
+    assert!(matches!(
+        from_slice::<u8>(&[0x80, 0x04]),
+        Err(AvroError::IntegerOverflow)
+    ));
+    assert!(matches!(
+        from_slice::<i64>(&[0xff; 11]),
+        Err(AvroError::VarintOverflow)
+    ));
+}
+
+#[test]
+fn added_fields_take_their_defaults() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct UserV1 {
+        name: String,
+        age: i32,
+    }
+
+    fn default_role() -> String {
+        "member".to_string()
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct UserV2 {
+        name: String,
+        // Promoted from int
+        age: i64,
+        email: Option<String>,
+        #[facet(default = "default_role")]
+        role: String,
+        #[facet(default)]
+        logins: u32,
+    }
+
+    let writer = schema::<UserV1>()?;
+    let bytes = to_vec(&UserV1 {
+        name: "Ada".to_string(),
+        age: 36,
+    });
+    assert_eq!(
+        from_slice_with_schema::<UserV2>(&bytes, &writer)?,
+        UserV2 {
+            name: "Ada".to_string(),
+            age: 36,
+            email: None,
+            role: "member".to_string(),
+            logins: 0,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn removed_fields_are_skipped() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct EventV1 {
+        id: i32,
+        tags: Vec<String>,
+        extra: HashMap<String, Option<f64>>,
+        kind: String,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct EventV2 {
+        id: i32,
+        kind: String,
+    }
+
+    let writer = schema::<EventV1>()?;
+    let bytes = to_vec(&EventV1 {
+        id: 1,
+        tags: vec!["a".to_string(), "b".to_string()],
+        extra: HashMap::from([("x".to_string(), Some(1.0)), ("y".to_string(), None)]),
+        kind: "click".to_string(),
+    });
+    assert_eq!(
+        from_slice_with_schema::<EventV2>(&bytes, &writer)?,
+        EventV2 {
+            id: 1,
+            kind: "click".to_string(),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn enum_symbols_are_matched_by_name() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Level {
+        Low,
+        Medium,
+        High,
+    }
+
+    let writer = Schema::parse(r#"{"type":"enum","name":"Level","symbols":["High","Low"]}"#)?;
+    assert_eq!(
+        from_slice_with_schema::<Level>(&[0x00], &writer)?,
+        Level::High
+    );
+
+    let writer = Schema::parse(r#"{"type":"enum","name":"Level","symbols":["Extreme"]}"#)?;
+    assert!(matches!(
+        from_slice_with_schema::<Level>(&[0x00], &writer),
+        Err(AvroError::UnknownVariant(name)) if name == "Extreme"
+    ));
+    Ok(())
+}
+
+#[test]
+fn missing_fields_without_defaults() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let writer =
+        Schema::parse(r#"{"type":"record","name":"Point","fields":[{"name":"x","type":"int"}]}"#)?;
+    assert!(matches!(
+        from_slice_with_schema::<Point>(&[0x02], &writer),
+        Err(AvroError::MissingField(name)) if name == "y"
+    ));
+    Ok(())
+}
+
+#[test]
+fn schema_mismatch() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert!(matches!(
+        from_slice_with_schema::<i32>(&[0x02, b'a'], &Schema::String),
+        Err(AvroError::SchemaMismatch { .. })
+    ));
+    // Values outside of a union can still be read as options
+    assert_eq!(
+        from_slice_with_schema::<Option<String>>(&[0x02, b'a'], &Schema::String)?,
+        Some("a".to_string())
+    );
+    Ok(())
+}
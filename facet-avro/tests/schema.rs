@@ -0,0 +1,168 @@
+use facet::Facet;
+use facet_avro::{AvroError, Schema, schema};
+use std::collections::HashMap;
+
+#[test]
+fn record_with_option_and_doc() {
+    facet_testhelpers::setup();
+
+    /// A user account
+    #[derive(Facet)]
+    struct User {
+        /// Display name
+        name: String,
+        age: u32,
+        email: Option<String>,
+    }
+
+    assert_eq!(
+        schema::<User>().unwrap().to_string(),
+        r#"{"type":"record","name":"User","doc":"A user account","fields":[{"name":"name","doc":"Display name","type":"string"},{"name":"age","type":"long"},{"name":"email","type":["null","string"],"default":null}]}"#
+    );
+}
+
+#[test]
+fn collections() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Collections {
+        tags: Vec<String>,
+        scores: HashMap<String, f64>,
+        blob: Vec<u8>,
+    }
+
+    assert_eq!(
+        schema::<Collections>().unwrap().to_string(),
+        r#"{"type":"record","name":"Collections","fields":[{"name":"tags","type":{"type":"array","items":"string"}},{"name":"scores","type":{"type":"map","values":"double"}},{"name":"blob","type":"bytes"}]}"#
+    );
+}
+
+#[test]
+fn enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Color {
+        Red,
+        Green,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Shape {
+        Circle { radius: f64 },
+        Square(u8),
+    }
+
+    assert_eq!(
+        schema::<Color>().unwrap().to_string(),
+        r#"{"type":"enum","name":"Color","symbols":["Red","Green"]}"#
+    );
+    assert_eq!(
+        schema::<Shape>().unwrap().to_string(),
+        r#"[{"type":"record","name":"Shape_Circle","fields":[{"name":"radius","type":"double"}]},{"type":"record","name":"Shape_Square","fields":[{"name":"_0","type":"int"}]}]"#
+    );
+}
+
+#[test]
+fn recursive_types_are_referenced_by_name() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Tree {
+        value: i32,
+        children: Vec<Tree>,
+    }
+
+    assert_eq!(
+        schema::<Tree>().unwrap().to_string(),
+        r#"{"type":"record","name":"Tree","fields":[{"name":"value","type":"int"},{"name":"children","type":{"type":"array","items":"Tree"}}]}"#
+    );
+}
+
+#[test]
+fn field_defaults() {
+    facet_testhelpers::setup();
+
+    fn default_port() -> u16 {
+        8080
+    }
+
+    #[derive(Facet)]
+    struct Server {
+        #[facet(default = "default_port")]
+        port: u16,
+        #[facet(default)]
+        hosts: Vec<String>,
+    }
+
+    let Schema::Record { fields, .. } = schema::<Server>().unwrap() else {
+        panic!("expected a record");
+    };
+    assert_eq!(fields[0].default().as_deref(), Some("8080"));
+    assert_eq!(fields[1].default().as_deref(), Some("[]"));
+}
+
+#[test]
+fn unsupported_map_keys() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Lookup {
+        by_id: HashMap<u32, String>,
+    }
+
+    assert!(matches!(
+        schema::<Lookup>(),
+        Err(AvroError::UnsupportedShape(_))
+    ));
+}
+
+#[test]
+fn parse_roundtrips() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"type":"record","name":"Tree","fields":[{"name":"value","type":"int"},{"name":"children","type":{"type":"array","items":"Tree"}},{"name":"label","type":["null","string"],"default":null}]}"#;
+    assert_eq!(Schema::parse(json).unwrap().to_string(), json);
+}
+
+#[test]
+fn parse_qualifies_names_with_namespaces() {
+    facet_testhelpers::setup();
+
+    let schema = Schema::parse(
+        r#"{
+            "type": "record",
+            "name": "Event",
+            "namespace": "com.example",
+            "fields": [
+                {"name": "kind", "type": {"type": "enum", "name": "Kind", "symbols": ["A", "B"]}},
+                {"name": "previous", "type": "Kind"},
+                {"name": "at", "type": {"type": "long", "logicalType": "timestamp-millis"}}
+            ]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        schema.to_string(),
+        r#"{"type":"record","name":"com.example.Event","fields":[{"name":"kind","type":{"type":"enum","name":"com.example.Kind","symbols":["A","B"]}},{"name":"previous","type":"com.example.Kind"},{"name":"at","type":"long"}]}"#
+    );
+}
+
+#[test]
+fn parse_errors() {
+    facet_testhelpers::setup();
+
+    assert!(matches!(
+        Schema::parse(r#"{"type":"record","name":"A"}"#),
+        Err(AvroError::InvalidSchema(_))
+    ));
+    assert!(matches!(
+        Schema::parse(r#"{"type":"#),
+        Err(AvroError::InvalidSchema(_))
+    ));
+}
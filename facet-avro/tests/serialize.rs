@@ -0,0 +1,103 @@
+use facet::Facet;
+use facet_avro::{to_vec, to_writer};
+use std::collections::BTreeMap;
+
+#[test]
+fn it_works() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+
+    let value = TestStruct {
+        name: "Alice".to_string(),
+        age: 30,
+    };
+    // Lengths and integers are zigzag-encoded varints
+    assert_eq!(to_vec(&value), [0x0a, b'A', b'l', b'i', b'c', b'e', 0x3c]);
+}
+
+#[test]
+fn scalars() {
+    facet_testhelpers::setup();
+
+    assert_eq!(to_vec(&true), [0x01]);
+    assert_eq!(to_vec(&-1i32), [0x01]);
+    assert_eq!(to_vec(&150i64), [0xac, 0x02]);
+    assert_eq!(to_vec(&1.0f32), [0x00, 0x00, 0x80, 0x3f]);
+    assert_eq!(to_vec(&vec![0xffu8, 0x00]), [0x04, 0xff, 0x00]);
+}
+
+#[test]
+fn options_are_unions_with_null() {
+    facet_testhelpers::setup();
+
+    assert_eq!(to_vec(&None::<i32>), [0x00]);
+    assert_eq!(to_vec(&Some(1i32)), [0x02, 0x02]);
+}
+
+#[test]
+fn arrays_and_maps_end_with_an_empty_block() {
+    facet_testhelpers::setup();
+
+    assert_eq!(to_vec(&vec![1i32, 2]), [0x04, 0x02, 0x04, 0x00]);
+    assert_eq!(to_vec(&Vec::<i32>::new()), [0x00]);
+
+    let mut map = BTreeMap::new();
+    map.insert("a".to_string(), 1i32);
+    assert_eq!(to_vec(&map), [0x02, 0x02, b'a', 0x02, 0x00]);
+}
+
+#[test]
+fn enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Color {
+        Red,
+        Green,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Shape {
+        Circle { radius: f64 },
+        Square(u8),
+    }
+
+    // Symbol index
+    assert_eq!(to_vec(&Color::Green), [0x02]);
+    // Union branch, then the fields of the variant's record
+    assert_eq!(to_vec(&Shape::Square(3)), [0x02, 0x06]);
+}
+
+#[test]
+fn transparent_wrappers() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[facet(transparent)]
+    struct UserId(u16);
+
+    #[derive(Facet)]
+    struct Session {
+        user: UserId,
+    }
+
+    assert_eq!(to_vec(&Session { user: UserId(2) }), [0x04]);
+}
+
+#[test]
+fn writer() {
+    facet_testhelpers::setup();
+
+    let mut out = Vec::new();
+    to_writer(&"hi", &mut out).unwrap();
+    assert_eq!(out, [0x04, b'h', b'i']);
+}
@@ -0,0 +1,78 @@
+use core::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Errors that can occur during decoding
+pub enum Error {
+    /// The input was written from another shape than the one it is decoded
+    /// into
+    FingerprintMismatch {
+        /// The fingerprint of the shape being decoded
+        expected: u64,
+        /// The fingerprint the input starts with
+        got: u64,
+    },
+    /// Not enough data available to decode a complete value
+    InsufficientData,
+    /// The data is malformed, e.g. a `bool` byte other than `0` or `1`
+    InvalidData,
+    /// A string isn't valid UTF-8
+    InvalidUtf8,
+    /// A varint is longer than any integer type
+    VarintOverflow,
+    /// Bytes were left over after the value
+    TrailingData,
+    /// An enum variant index that is out of range
+    UnknownVariant(usize),
+    /// Integer value is too large for the target type
+    IntegerOverflow,
+    /// Shape is not supported for deserialization
+    UnsupportedShape(String),
+    /// Reflection error
+    ReflectError(facet_reflect::ReflectError),
+    /// The input went over one of the configured [`Limits`](facet_reflect::Limits)
+    LimitExceeded {
+        /// Which limit was exceeded
+        kind: facet_reflect::LimitKind,
+        /// The configured maximum
+        max: usize,
+        /// Where in the value being built the limit was hit, e.g. `$.items[3]`
+        path: String,
+    },
+}
+
+impl From<facet_reflect::ReflectError> for Error {
+    fn from(err: facet_reflect::ReflectError) -> Self {
+        Self::ReflectError(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::FingerprintMismatch { expected, got } => write!(
+                f,
+                "Shape fingerprint mismatch: expected {:#018x}, got {:#018x}",
+                expected, got
+            ),
+            Error::InsufficientData => write!(f, "Insufficient data to decode"),
+            Error::InvalidData => write!(f, "Invalid data"),
+            Error::InvalidUtf8 => write!(f, "Invalid UTF-8 in string"),
+            Error::VarintOverflow => write!(f, "Varint is too long"),
+            Error::TrailingData => write!(f, "Trailing data after the value"),
+            Error::UnknownVariant(index) => write!(f, "Unknown enum variant index: {}", index),
+            Error::IntegerOverflow => write!(f, "Integer value too large for target type"),
+            Error::UnsupportedShape(shape) => {
+                write!(f, "Unsupported shape for deserialization: {}", shape)
+            }
+            Error::ReflectError(err) => {
+                write!(f, "Reflection error: {}", err)
+            }
+            Error::LimitExceeded { kind, max, path } => {
+                write!(f, "Exceeded the {} limit of {} at {}", kind, max, path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
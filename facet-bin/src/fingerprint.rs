@@ -0,0 +1,162 @@
+use facet_core::{Def, Field, Shape, StructKind};
+
+/// Bumped whenever the encoding changes, so that old data stops matching
+const FORMAT_VERSION: u8 = 1;
+
+/// Computes the fingerprint of a shape, which every encoded message starts
+/// with.
+///
+/// It covers everything the encoding depends on: the names of scalar types,
+/// the kind, field names and field types of structs, the names and fields of
+/// enum variants, and the item types of options, lists and maps.
+/// Transparent wrappers and smart pointers are their inner type, as on the
+/// wire. The names of user types are left out, so renaming or moving a type
+/// keeps its data readable, but renaming one of its fields doesn't.
+///
+/// The fingerprint only depends on the shape, so it is the same in every
+/// build and process.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct A {
+///     id: u32,
+/// }
+///
+/// #[derive(Facet)]
+/// struct B {
+///     id: u32,
+/// }
+///
+/// #[derive(Facet)]
+/// struct C {
+///     id: u64,
+/// }
+///
+/// assert_eq!(facet_bin::fingerprint(A::SHAPE), facet_bin::fingerprint(B::SHAPE));
+/// assert_ne!(facet_bin::fingerprint(A::SHAPE), facet_bin::fingerprint(C::SHAPE));
+/// ```
+pub fn fingerprint(shape: &'static Shape) -> u64 {
+    let mut hasher = Hasher {
+        hash: FNV_OFFSET_BASIS,
+        stack: Vec::new(),
+    };
+    hasher.write_u8(FORMAT_VERSION);
+    hasher.write_shape(shape);
+    hasher.hash
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// An FNV-1a hash of a walk over a shape
+struct Hasher {
+    hash: u64,
+    /// The shapes being walked, to cut recursive types short
+    stack: Vec<&'static Shape>,
+}
+
+impl Hasher {
+    fn write_u8(&mut self, byte: u8) {
+        self.hash ^= byte as u64;
+        self.hash = self.hash.wrapping_mul(FNV_PRIME);
+    }
+
+    fn write_len(&mut self, len: usize) {
+        for byte in (len as u64).to_le_bytes() {
+            self.write_u8(byte);
+        }
+    }
+
+    /// Strings are prefixed with their length, so that `("ab", "c")` and
+    /// `("a", "bc")` hash differently
+    fn write_str(&mut self, s: &str) {
+        self.write_len(s.len());
+        for &byte in s.as_bytes() {
+            self.write_u8(byte);
+        }
+    }
+
+    fn write_shape(&mut self, shape: &'static Shape) {
+        // A shape containing itself is written as how far up it is
+        if let Some(position) = self.stack.iter().rposition(|s| *s == shape) {
+            self.write_u8(b'^');
+            self.write_len(self.stack.len() - position);
+            return;
+        }
+        self.stack.push(shape);
+
+        match shape.def {
+            Def::Scalar(_) => {
+                self.write_u8(b's');
+                self.write_str(&shape.to_string());
+            }
+            Def::Option(od) => {
+                self.write_u8(b'?');
+                self.write_shape(od.t());
+            }
+            // Transparent wrappers and smart pointers are encoded as the
+            // value they hold
+            _ => match shape.inner {
+                Some(inner) => self.write_shape(inner()),
+                None => self.write_composite(shape),
+            },
+        }
+
+        self.stack.pop();
+    }
+
+    fn write_composite(&mut self, shape: &'static Shape) {
+        match shape.def {
+            Def::Struct(sd) => {
+                self.write_u8(b'{');
+                self.write_fields(sd.kind, sd.fields);
+            }
+            Def::Enum(ed) => {
+                self.write_u8(b'|');
+                self.write_len(ed.variants.len());
+                for variant in ed.variants {
+                    self.write_str(variant.name);
+                    self.write_fields(variant.data.kind, variant.data.fields);
+                }
+            }
+            // Lists and slices have the same encoding
+            Def::List(ld) => {
+                self.write_u8(b'[');
+                self.write_shape(ld.t());
+            }
+            Def::Slice(sd) => {
+                self.write_u8(b'[');
+                self.write_shape(sd.t());
+            }
+            Def::Map(md) => {
+                self.write_u8(b'<');
+                self.write_shape(md.k);
+                self.write_shape(md.v);
+            }
+            // Shapes that can't be encoded fail when they are, but still get
+            // a fingerprint of their own
+            _ => {
+                self.write_u8(b'!');
+                self.write_str(&shape.to_string());
+            }
+        }
+    }
+
+    fn write_fields(&mut self, kind: StructKind, fields: &'static [Field]) {
+        self.write_u8(match kind {
+            StructKind::Unit => 0,
+            StructKind::TupleStruct => 1,
+            StructKind::Struct => 2,
+            StructKind::Tuple => 3,
+            _ => 4,
+        });
+        self.write_len(fields.len());
+        for field in fields {
+            self.write_str(field.name);
+            self.write_shape(field.shape());
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use std::borrow::Cow;
+
+use crate::errors::Error as DecodeError;
+use crate::fingerprint::fingerprint;
+use crate::varint;
+
+use facet_core::{Def, Facet, ScalarAffinity, Shape, ShapeLayout};
+use facet_reflect::{HeapValue, LimitKind, Limits, ReflectError, Wip};
+use log::trace;
+
+/// Deserializes bytes written by [`to_vec`](crate::to_vec) into a type that
+/// implements `Facet`.
+///
+/// The input must have been written from a type with the same shape:
+/// otherwise this fails with [`DecodeError::FingerprintMismatch`].
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+/// use facet_bin::{DecodeError, from_slice, to_vec};
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct User {
+///     id: u64,
+///     username: String,
+/// }
+///
+/// let user = User { id: 42, username: "user123".to_string() };
+/// let bytes = to_vec(&user);
+/// assert_eq!(from_slice::<User>(&bytes).unwrap(), user);
+///
+/// // The fields of a tuple have other names
+/// let err = from_slice::<(u64, String)>(&bytes).unwrap_err();
+/// assert!(matches!(err, DecodeError::FingerprintMismatch { .. }));
+/// ```
+pub fn from_slice<'input: 'facet, 'facet, T: Facet<'facet>>(
+    input: &'input [u8],
+) -> Result<T, DecodeError> {
+    from_slice_value(Wip::alloc::<T>()?, input)?
+        .materialize::<T>()
+        .map_err(DecodeError::ReflectError)
+}
+
+/// Deserializes bytes written by [`to_vec`](crate::to_vec) into a type that
+/// implements `Facet`, refusing input that goes over the given [`Limits`].
+///
+/// Length prefixes are checked before anything is allocated, so a hostile
+/// one can't make the decoder reserve gigabytes. Violations are reported as
+/// [`DecodeError::LimitExceeded`].
+///
+/// # Example
+/// ```
+/// use facet_bin::{DecodeError, LimitKind, Limits, from_slice_with_limits, to_vec};
+///
+/// let bytes = to_vec(&vec![0u32; 2000]);
+/// let limits = Limits::default().max_collection_len(1000);
+///
+/// let err = from_slice_with_limits::<Vec<u32>>(&bytes, limits).unwrap_err();
+/// assert!(matches!(
+///     err,
+///     DecodeError::LimitExceeded { kind: LimitKind::CollectionLength, max: 1000, .. }
+/// ));
+/// ```
+pub fn from_slice_with_limits<'input: 'facet, 'facet, T: Facet<'facet>>(
+    input: &'input [u8],
+    limits: Limits,
+) -> Result<T, DecodeError> {
+    let wip = Wip::alloc::<T>()?.with_limits(limits)?;
+    decode(wip, input, limits)?
+        .materialize::<T>()
+        .map_err(DecodeError::ReflectError)
+}
+
+/// Deserializes bytes written by [`to_vec`](crate::to_vec) into a Facet
+/// value, checking them against the fingerprint of the `Wip`'s shape.
+#[allow(clippy::needless_lifetimes)]
+pub fn from_slice_value<'mem>(
+    wip: Wip<'mem>,
+    input: &'mem [u8],
+) -> Result<HeapValue<'mem>, DecodeError> {
+    let limits = *wip.limits();
+    decode(wip, input, limits)
+}
+
+fn decode<'mem>(
+    wip: Wip<'mem>,
+    input: &'mem [u8],
+    limits: Limits,
+) -> Result<HeapValue<'mem>, DecodeError> {
+    let mut decoder = Decoder::new(input, limits);
+
+    let expected = fingerprint(wip.shape());
+    let got = u64::from_le_bytes(decoder.take_array()?);
+    if got != expected {
+        return Err(DecodeError::FingerprintMismatch { expected, got });
+    }
+
+    let wip = decoder.deserialize_value(wip)?;
+    if decoder.offset != input.len() {
+        return Err(DecodeError::TrailingData);
+    }
+    wip.build().map_err(DecodeError::ReflectError)
+}
+
+/// Runs an operation on the `Wip`, reporting limit violations with the path
+/// they happened at.
+fn reflect<'mem>(
+    wip: Wip<'mem>,
+    op: impl FnOnce(Wip<'mem>) -> Result<Wip<'mem>, ReflectError>,
+) -> Result<Wip<'mem>, DecodeError> {
+    let path = wip.path();
+    op(wip).map_err(|e| reflect_error(e, || path))
+}
+
+/// Keeps limit violations typed and located; other reflection errors are
+/// passed through.
+fn reflect_error(e: ReflectError, path: impl FnOnce() -> String) -> DecodeError {
+    match e {
+        ReflectError::LimitExceeded { kind, max } => DecodeError::LimitExceeded {
+            kind,
+            max,
+            path: path(),
+        },
+        e => DecodeError::ReflectError(e),
+    }
+}
+
+/// Follows transparent wrappers and smart pointers down to the scalar they
+/// hold, if any.
+fn scalar_target(mut shape: &'static Shape) -> Option<&'static Shape> {
+    loop {
+        if let Def::Scalar(_) = shape.def {
+            return Some(shape);
+        }
+        shape = (shape.inner?)();
+    }
+}
+
+/// The fewest bytes a value of `shape` can take: zero-sized types may take
+/// none, everything else at least one.
+fn min_encoded_size(shape: &'static Shape) -> usize {
+    match shape.layout {
+        ShapeLayout::Sized(layout) if layout.size() == 0 => 0,
+        _ => 1,
+    }
+}
+
+struct Decoder<'input> {
+    input: &'input [u8],
+    offset: usize,
+    limits: Limits,
+}
+
+impl<'input> Decoder<'input> {
+    fn new(input: &'input [u8], limits: Limits) -> Self {
+        Decoder {
+            input,
+            offset: 0,
+            limits,
+        }
+    }
+
+    /// Checks `value` against one of the limits, before anything gets
+    /// allocated for it.
+    fn check_limit(&self, kind: LimitKind, value: usize, wip: &Wip) -> Result<(), DecodeError> {
+        self.limits
+            .check(kind, value)
+            .map_err(|e| reflect_error(e, || wip.path()))
+    }
+
+    /// Fails early if a length prefix promises more items than there are
+    /// bytes left: every item takes at least `min_item_size` bytes.
+    fn check_remaining(&self, len: usize, min_item_size: usize) -> Result<(), DecodeError> {
+        if len.saturating_mul(min_item_size) > self.input.len() - self.offset {
+            return Err(DecodeError::InsufficientData);
+        }
+        Ok(())
+    }
+
+    /// Consumes `len` bytes of input.
+    fn take(&mut self, len: usize) -> Result<&'input [u8], DecodeError> {
+        if len > self.input.len() - self.offset {
+            return Err(DecodeError::InsufficientData);
+        }
+        let bytes = &self.input[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    /// Consumes `N` bytes of input, e.g. to read a little-endian number.
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take_array::<1>()?[0])
+    }
+
+    /// Decodes a LEB128 varint of up to 128 bits.
+    fn read_varint(&mut self) -> Result<u128, DecodeError> {
+        let mut value = 0u128;
+        for i in 0..varint::MAX_LEN {
+            let byte = self.read_u8()?;
+            let bits = (byte & 0x7f) as u128;
+            // The last byte only has two of its seven bits left
+            if i == varint::MAX_LEN - 1 && bits > 0b11 {
+                return Err(DecodeError::VarintOverflow);
+            }
+            value |= bits << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::VarintOverflow)
+    }
+
+    /// Decodes a zigzag-encoded varint.
+    fn read_signed(&mut self) -> Result<i128, DecodeError> {
+        Ok(varint::unzigzag(self.read_varint()?))
+    }
+
+    /// Decodes a length prefix, checked against the `kind` limit and the
+    /// input that is left.
+    fn read_len(
+        &mut self,
+        kind: LimitKind,
+        min_item_size: usize,
+        wip: &Wip,
+    ) -> Result<usize, DecodeError> {
+        let len =
+            usize::try_from(self.read_varint()?).map_err(|_| DecodeError::InsufficientData)?;
+        self.check_limit(kind, len, wip)?;
+        self.check_remaining(len, min_item_size)?;
+        Ok(len)
+    }
+
+    /// Decodes length-prefixed bytes, borrowing them from the input.
+    fn read_bytes(&mut self, wip: &Wip) -> Result<&'input [u8], DecodeError> {
+        let len = self.read_len(LimitKind::StringLength, 1, wip)?;
+        self.take(len)
+    }
+
+    /// Decodes a length-prefixed UTF-8 string, borrowing it from the input.
+    fn read_str(&mut self, wip: &Wip) -> Result<&'input str, DecodeError> {
+        core::str::from_utf8(self.read_bytes(wip)?).map_err(|_| DecodeError::InvalidUtf8)
+    }
+
+    fn deserialize_value(&mut self, wip: Wip<'input>) -> Result<Wip<'input>, DecodeError> {
+        let shape = wip.shape();
+        trace!("Deserializing {:?}", shape);
+
+        let wip = match shape.def {
+            Def::Option(_) => {
+                trace!("Deserializing option");
+                match self.read_u8()? {
+                    0 => reflect(wip, Wip::put_default)?,
+                    1 => {
+                        let wip = reflect(wip, Wip::push_some)?;
+                        let wip = self.deserialize_value(wip)?;
+                        reflect(wip, Wip::pop)?
+                    }
+                    _ => return Err(DecodeError::InvalidData),
+                }
+            }
+            Def::Scalar(_) => self.deserialize_scalar(wip, shape)?,
+            // Transparent wrappers and smart pointers, which can be built
+            // from the scalar they hold
+            _ if shape.inner.is_some() => match scalar_target(shape) {
+                Some(target) => self.deserialize_scalar(wip, target)?,
+                None => return Err(DecodeError::UnsupportedShape(format!("{}", shape))),
+            },
+            Def::Struct(sd) => {
+                trace!("Deserializing struct");
+                let mut wip = wip;
+                for index in 0..sd.fields.len() {
+                    wip = reflect(wip, |wip| wip.field(index))?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::pop)?;
+                }
+                wip
+            }
+            Def::List(_) if shape.is_type::<Vec<u8>>() => {
+                trace!("Deserializing bytes");
+                let bytes = self.read_bytes(&wip)?.to_vec();
+                reflect(wip, |wip| wip.put(bytes))?
+            }
+            Def::Slice(_) if shape.is_type::<&[u8]>() => {
+                trace!("Deserializing borrowed bytes");
+                let bytes = self.read_bytes(&wip)?;
+                reflect(wip, |wip| wip.put(bytes))?
+            }
+            Def::List(ld) => {
+                trace!("Deserializing list");
+                let len =
+                    self.read_len(LimitKind::CollectionLength, min_encoded_size(ld.t()), &wip)?;
+
+                let mut wip = reflect(wip, Wip::put_default)?;
+                for _ in 0..len {
+                    wip = reflect(wip, Wip::push)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::pop)?;
+                }
+                wip
+            }
+            Def::Map(md) => {
+                trace!("Deserializing map");
+                let min_entry_size = min_encoded_size(md.k) + min_encoded_size(md.v);
+                let len = self.read_len(LimitKind::CollectionLength, min_entry_size, &wip)?;
+
+                let mut wip = reflect(wip, Wip::put_default)?;
+                for _ in 0..len {
+                    wip = reflect(wip, Wip::push_map_key)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::push_map_value)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::pop)?;
+                }
+                wip
+            }
+            Def::Enum(ed) => {
+                trace!("Deserializing enum");
+                let index = usize::try_from(self.read_varint()?).unwrap_or(usize::MAX);
+                if index >= ed.variants.len() {
+                    return Err(DecodeError::UnknownVariant(index));
+                }
+                let mut wip = reflect(wip, |wip| wip.variant(index))?;
+                for field in 0..ed.variants[index].data.fields.len() {
+                    wip = reflect(wip, |wip| wip.field(field))?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::pop)?;
+                }
+                wip
+            }
+            _ => {
+                return Err(DecodeError::UnsupportedShape(format!("{}", shape)));
+            }
+        };
+
+        Ok(wip)
+    }
+
+    /// Deserializes a scalar of the `target` shape into the current frame,
+    /// which is either that shape or a wrapper around it.
+    fn deserialize_scalar(
+        &mut self,
+        wip: Wip<'input>,
+        target: &'static Shape,
+    ) -> Result<Wip<'input>, DecodeError> {
+        let Def::Scalar(sd) = target.def else {
+            return Err(DecodeError::UnsupportedShape(format!("{}", target)));
+        };
+        trace!("Deserializing scalar {}", target);
+
+        macro_rules! unsigned {
+            ($($ty:ty),*) => {
+                $(
+                    if target.is_type::<$ty>() {
+                        let n = self.read_varint()?;
+                        let n = <$ty>::try_from(n).map_err(|_| DecodeError::IntegerOverflow)?;
+                        return reflect(wip, |wip| wip.put(n));
+                    }
+                )*
+            };
+        }
+        macro_rules! signed {
+            ($($ty:ty),*) => {
+                $(
+                    if target.is_type::<$ty>() {
+                        let n = self.read_signed()?;
+                        let n = <$ty>::try_from(n).map_err(|_| DecodeError::IntegerOverflow)?;
+                        return reflect(wip, |wip| wip.put(n));
+                    }
+                )*
+            };
+        }
+
+        if target.is_type::<u8>() {
+            let n = self.read_u8()?;
+            return reflect(wip, |wip| wip.put(n));
+        }
+        if target.is_type::<i8>() {
+            let n = i8::from_le_bytes(self.take_array()?);
+            return reflect(wip, |wip| wip.put(n));
+        }
+        unsigned!(u16, u32, u64, u128, usize);
+        signed!(i16, i32, i64, i128, isize);
+
+        if target.is_type::<f32>() {
+            let n = f32::from_le_bytes(self.take_array()?);
+            return reflect(wip, |wip| wip.put(n));
+        }
+        if target.is_type::<f64>() {
+            let n = f64::from_le_bytes(self.take_array()?);
+            return reflect(wip, |wip| wip.put(n));
+        }
+        if target.is_type::<bool>() {
+            let b = match self.read_u8()? {
+                0 => false,
+                1 => true,
+                _ => return Err(DecodeError::InvalidData),
+            };
+            return reflect(wip, |wip| wip.put(b));
+        }
+        if target.is_type::<char>() {
+            let c = u32::try_from(self.read_varint()?)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or(DecodeError::InvalidData)?;
+            return reflect(wip, |wip| wip.put(c));
+        }
+        if target.is_type::<String>() {
+            let s = self.read_str(&wip)?.to_string();
+            return reflect(wip, |wip| wip.put(s));
+        }
+        if target.is_type::<&str>() {
+            let s = self.read_str(&wip)?;
+            return reflect(wip, |wip| wip.put(s));
+        }
+        if target.is_type::<Cow<'_, str>>() {
+            let s = Cow::Borrowed(self.read_str(&wip)?);
+            return reflect(wip, |wip| wip.put(s));
+        }
+
+        match sd.affinity {
+            ScalarAffinity::Empty(_) => reflect(wip, Wip::put_default),
+            // Other scalars are written as text
+            _ => {
+                let text = self.read_str(&wip)?;
+                reflect(wip, |wip| wip.parse(text))
+            }
+        }
+    }
+}
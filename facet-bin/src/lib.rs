@@ -0,0 +1,19 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+mod errors;
+pub use errors::Error as DecodeError;
+
+pub use facet_reflect::{LimitKind, Limits};
+
+mod fingerprint;
+pub use fingerprint::fingerprint;
+
+mod varint;
+
+mod from_bin;
+pub use from_bin::*;
+
+mod to_bin;
+pub use to_bin::*;
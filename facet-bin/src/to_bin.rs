@@ -0,0 +1,203 @@
+use crate::fingerprint::fingerprint;
+use crate::varint;
+
+use facet_core::{Def, Facet, ScalarAffinity};
+use facet_reflect::Peek;
+use log::trace;
+use std::io::{self, Write};
+
+/// Serializes any Facet type to bytes, starting with the fingerprint of its
+/// shape
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Point {
+///     x: u16,
+///     y: i16,
+/// }
+///
+/// let bytes = facet_bin::to_vec(&Point { x: 300, y: -2 });
+/// // After the 8-byte fingerprint, 300 as a varint, then -2 zigzag-encoded
+/// assert_eq!(bytes[8..], [0xac, 0x02, 0x03]);
+/// ```
+pub fn to_vec<'a, T: Facet<'a>>(value: &T) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    to_writer(value, &mut buffer).unwrap();
+    buffer
+}
+
+/// Serializes any Facet type to a writer, starting with the fingerprint of
+/// its shape
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    writer.write_all(&fingerprint(T::SHAPE).to_le_bytes())?;
+    serialize(Peek::new(value), writer)
+}
+
+fn other(e: impl core::fmt::Display) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+fn unsupported(pv: Peek<'_, '_>) -> io::Error {
+    io::Error::other(format!("Unsupported type: {}", pv.shape()))
+}
+
+fn serialize<W: Write>(pv: Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
+    let shape = pv.shape();
+
+    // Options name the type they hold as their inner type, but have a tag
+    if let Def::Option(_) = shape.def {
+        trace!("Serializing option");
+        let po = pv.into_option().map_err(other)?;
+        return match po.value() {
+            Some(value) => {
+                writer.write_all(&[1])?;
+                serialize(value, writer)
+            }
+            None => writer.write_all(&[0]),
+        };
+    }
+
+    // Transparent wrappers are written as the value they wrap
+    if let Some(inner) = pv.borrow_inner() {
+        return serialize(inner, writer);
+    }
+
+    match shape.def {
+        Def::Scalar(_) => serialize_scalar(pv, writer),
+        Def::Struct(_) => {
+            trace!("Serializing struct");
+            let ps = pv.into_struct().map_err(other)?;
+            // Every field, even those skipped by self-describing formats,
+            // since there are no names to tell which ones are there
+            for index in 0..ps.field_count() {
+                serialize(ps.field(index).map_err(other)?, writer)?;
+            }
+            Ok(())
+        }
+        Def::List(_) if shape.is_type::<Vec<u8>>() => {
+            let bytes = pv.get::<Vec<u8>>().map_err(other)?;
+            write_bytes(writer, bytes)
+        }
+        Def::Slice(_) if shape.is_type::<&[u8]>() => {
+            let bytes = pv.get::<&[u8]>().map_err(other)?;
+            write_bytes(writer, bytes)
+        }
+        Def::List(_) => {
+            trace!("Serializing list");
+            let list = pv.into_list().map_err(other)?;
+            varint::write(writer, list.len() as u128)?;
+            for item in list.iter() {
+                serialize(item, writer)?;
+            }
+            Ok(())
+        }
+        Def::Slice(_) => {
+            trace!("Serializing slice");
+            let slice = pv.into_slice().map_err(other)?;
+            varint::write(writer, slice.len() as u128)?;
+            for item in slice.iter() {
+                serialize(item, writer)?;
+            }
+            Ok(())
+        }
+        Def::Map(_) => {
+            trace!("Serializing map");
+            let map = pv.into_map().map_err(other)?;
+            varint::write(writer, map.len() as u128)?;
+            for (key, value) in map.iter() {
+                serialize(key, writer)?;
+                serialize(value, writer)?;
+            }
+            Ok(())
+        }
+        Def::Enum(_) => {
+            trace!("Serializing enum");
+            let pe = pv.into_enum().map_err(other)?;
+            varint::write(writer, pe.variant_index() as u128)?;
+            for (_, value) in pe.fields() {
+                serialize(value, writer)?;
+            }
+            Ok(())
+        }
+        Def::SmartPointer(_) => {
+            trace!("Serializing smart pointer");
+            let pointer = pv.into_smart_pointer().map_err(other)?;
+            let pointee = pointer
+                .borrow_inner()
+                .ok_or_else(|| other(format!("Cannot borrow the pointee of {}", pv.shape())))?;
+            serialize(pointee, writer)
+        }
+        _ => Err(unsupported(pv)),
+    }
+}
+
+fn serialize_scalar<W: Write>(pv: Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
+    let shape = pv.shape();
+    let Def::Scalar(sd) = shape.def else {
+        unreachable!("serialize_scalar called on {}", shape)
+    };
+    trace!("Serializing scalar {}", shape);
+
+    macro_rules! unsigned {
+        ($($ty:ty),*) => {
+            $(
+                if let Ok(value) = pv.get::<$ty>() {
+                    return varint::write(writer, *value as u128);
+                }
+            )*
+        };
+    }
+    macro_rules! signed {
+        ($($ty:ty),*) => {
+            $(
+                if let Ok(value) = pv.get::<$ty>() {
+                    return varint::write(writer, varint::zigzag(*value as i128));
+                }
+            )*
+        };
+    }
+
+    // Single bytes gain nothing from being varints
+    if let Ok(value) = pv.get::<u8>() {
+        return writer.write_all(&[*value]);
+    }
+    if let Ok(value) = pv.get::<i8>() {
+        return writer.write_all(&value.to_le_bytes());
+    }
+    unsigned!(u16, u32, u64, u128, usize);
+    signed!(i16, i32, i64, i128, isize);
+
+    if let Ok(value) = pv.get::<f32>() {
+        writer.write_all(&value.to_le_bytes())
+    } else if let Ok(value) = pv.get::<f64>() {
+        writer.write_all(&value.to_le_bytes())
+    } else if let Ok(value) = pv.get::<bool>() {
+        writer.write_all(&[*value as u8])
+    } else if let Ok(value) = pv.get::<char>() {
+        varint::write(writer, *value as u128)
+    } else if let Ok(value) = pv.get::<String>() {
+        write_bytes(writer, value.as_bytes())
+    } else if let Ok(value) = pv.get::<&str>() {
+        write_bytes(writer, value.as_bytes())
+    } else if let Ok(value) = pv.get::<std::borrow::Cow<'_, str>>() {
+        write_bytes(writer, value.as_bytes())
+    } else if let ScalarAffinity::Empty(_) = sd.affinity {
+        Ok(())
+    } else if shape.vtable.display.is_some() {
+        // Other scalars, such as UUIDs or timestamps, are written as text,
+        // which their `FromStr` implementation reads back
+        write_bytes(writer, pv.to_string().as_bytes())
+    } else {
+        Err(unsupported(pv))
+    }
+}
+
+/// Writes a length-prefixed run of bytes, which is how strings are written
+/// too
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    varint::write(writer, bytes.len() as u128)?;
+    writer.write_all(bytes)
+}
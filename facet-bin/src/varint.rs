@@ -0,0 +1,34 @@
+//! LEB128 varints: seven bits per byte, least significant group first, with
+//! the high bit set on every byte but the last.
+
+use std::io::{self, Write};
+
+/// The most bytes a varint of up to 128 bits takes
+pub(crate) const MAX_LEN: usize = 19;
+
+pub(crate) fn write<W: Write>(writer: &mut W, mut value: u128) -> io::Result<()> {
+    let mut buf = [0u8; MAX_LEN];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buf[..len])
+}
+
+/// Maps signed integers to unsigned ones so that small magnitudes stay
+/// small: 0, -1, 1, -2, … become 0, 1, 2, 3, …
+pub(crate) fn zigzag(value: i128) -> u128 {
+    ((value << 1) ^ (value >> 127)) as u128
+}
+
+pub(crate) fn unzigzag(value: u128) -> i128 {
+    ((value >> 1) as i128) ^ -((value & 1) as i128)
+}
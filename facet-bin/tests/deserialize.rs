@@ -0,0 +1,315 @@
+use eyre::Result;
+use facet::Facet;
+use facet_bin::{
+    DecodeError, LimitKind, Limits, fingerprint, from_slice, from_slice_with_limits, to_vec,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Prefixes a payload with the fingerprint of `T`
+fn message<'a, T: Facet<'a>>(payload: &[u8]) -> Vec<u8> {
+    let mut bytes = fingerprint(T::SHAPE).to_le_bytes().to_vec();
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+
+    let data = message::<TestStruct>(&[0x05, b'A', b'l', b'i', b'c', b'e', 0x1e]);
+    assert_eq!(
+        from_slice::<TestStruct>(&data)?,
+        TestStruct {
+            name: "Alice".to_string(),
+            age: 30,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Status {
+        Active,
+        Suspended { reason: String, days: u16 },
+        Deleted(i64),
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Account<'a> {
+        id: u128,
+        handle: &'a str,
+        balance: f64,
+        ratio: f32,
+        delta: i8,
+        initial: char,
+        verified: bool,
+        email: Option<String>,
+        tags: Vec<String>,
+        avatar: Vec<u8>,
+        limits: HashMap<String, i32>,
+        history: Vec<Status>,
+        status: Status,
+        pair: (u8, Option<bool>),
+        shared: Arc<String>,
+        id_card: uuid::Uuid,
+    }
+
+    let mut limits = HashMap::new();
+    limits.insert("daily".to_string(), -500);
+    limits.insert("monthly".to_string(), 10_000);
+
+    let account = Account {
+        id: u128::MAX - 1,
+        handle: "ann",
+        balance: -12.75,
+        ratio: 0.5,
+        delta: i8::MIN,
+        initial: '🦀',
+        verified: true,
+        email: Some("ann@example.com".to_string()),
+        tags: vec!["a".to_string(), String::new()],
+        avatar: vec![0, 255, 7],
+        limits,
+        history: vec![
+            Status::Active,
+            Status::Suspended {
+                reason: "spam".to_string(),
+                days: 300,
+            },
+            Status::Deleted(i64::MIN),
+        ],
+        status: Status::Deleted(-1),
+        pair: (9, None),
+        shared: Arc::new("shared".to_string()),
+        id_card: uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap(),
+    };
+
+    let bytes = to_vec(&account);
+    assert_eq!(from_slice::<Account>(&bytes)?, account);
+
+    Ok(())
+}
+
+#[test]
+fn recursive_types() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Node {
+        value: u32,
+        children: Vec<Node>,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct OtherNode {
+        value: u32,
+        children: Vec<OtherNode>,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct WideNode {
+        value: u64,
+        children: Vec<WideNode>,
+    }
+
+    let tree = Node {
+        value: 1,
+        children: vec![
+            Node {
+                value: 2,
+                children: vec![],
+            },
+            Node {
+                value: 3,
+                children: vec![Node {
+                    value: 4,
+                    children: vec![],
+                }],
+            },
+        ],
+    };
+    let bytes = to_vec(&tree);
+    assert_eq!(from_slice::<Node>(&bytes)?, tree);
+
+    // Only the layout matters, not the names of types
+    assert_eq!(fingerprint(Node::SHAPE), fingerprint(OtherNode::SHAPE));
+    assert_ne!(fingerprint(Node::SHAPE), fingerprint(WideNode::SHAPE));
+
+    Ok(())
+}
+
+#[test]
+fn fingerprint_mismatch() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct V1 {
+        id: u32,
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Reordered {
+        name: String,
+        id: u32,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Renamed {
+        id: u32,
+        label: String,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Extended {
+        id: u32,
+        name: String,
+        email: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum E1 {
+        A,
+        B(u32),
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum E2 {
+        A,
+        B(u32),
+        C,
+    }
+
+    let bytes = to_vec(&V1 {
+        id: 1,
+        name: "x".to_string(),
+    });
+
+    let err = from_slice::<Reordered>(&bytes).unwrap_err();
+    assert!(matches!(
+        err,
+        DecodeError::FingerprintMismatch { expected, got }
+            if expected == fingerprint(Reordered::SHAPE) && got == fingerprint(V1::SHAPE)
+    ));
+    assert!(matches!(
+        from_slice::<Renamed>(&bytes).unwrap_err(),
+        DecodeError::FingerprintMismatch { .. }
+    ));
+    assert!(matches!(
+        from_slice::<Extended>(&bytes).unwrap_err(),
+        DecodeError::FingerprintMismatch { .. }
+    ));
+    assert!(matches!(
+        from_slice::<E2>(&to_vec(&E1::B(3))).unwrap_err(),
+        DecodeError::FingerprintMismatch { .. }
+    ));
+    assert!(matches!(
+        from_slice::<Vec<i32>>(&to_vec(&vec![1u32])).unwrap_err(),
+        DecodeError::FingerprintMismatch { .. }
+    ));
+}
+
+#[test]
+fn malformed_input() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Choice {
+        Yes,
+        No,
+    }
+
+    // Shorter than a fingerprint
+    assert!(matches!(
+        from_slice::<u32>(&[0x01, 0x02]).unwrap_err(),
+        DecodeError::InsufficientData
+    ));
+
+    // Truncated in the middle of a value
+    let bytes = to_vec(&"hello".to_string());
+    assert!(matches!(
+        from_slice::<String>(&bytes[..bytes.len() - 1]).unwrap_err(),
+        DecodeError::InsufficientData
+    ));
+    assert!(matches!(
+        from_slice::<u64>(&message::<u64>(&[0x80, 0x80])).unwrap_err(),
+        DecodeError::InsufficientData
+    ));
+
+    let mut bytes = to_vec(&7u32);
+    bytes.push(0);
+    assert!(matches!(
+        from_slice::<u32>(&bytes).unwrap_err(),
+        DecodeError::TrailingData
+    ));
+
+    assert!(matches!(
+        from_slice::<bool>(&message::<bool>(&[0x02])).unwrap_err(),
+        DecodeError::InvalidData
+    ));
+    assert!(matches!(
+        from_slice::<Option<u8>>(&message::<Option<u8>>(&[0x07, 0x00])).unwrap_err(),
+        DecodeError::InvalidData
+    ));
+    assert!(matches!(
+        from_slice::<char>(&message::<char>(&[0x80, 0xb0, 0x03])).unwrap_err(),
+        DecodeError::InvalidData
+    ));
+    assert!(matches!(
+        from_slice::<String>(&message::<String>(&[0x02, 0xc3, 0x28])).unwrap_err(),
+        DecodeError::InvalidUtf8
+    ));
+    assert!(matches!(
+        from_slice::<Choice>(&message::<Choice>(&[0x02])).unwrap_err(),
+        DecodeError::UnknownVariant(2)
+    ));
+
+    // Fits a varint, not a u16
+    assert!(matches!(
+        from_slice::<u16>(&message::<u16>(&[0x80, 0x80, 0x04])).unwrap_err(),
+        DecodeError::IntegerOverflow
+    ));
+    let mut too_long = vec![0xff; 19];
+    too_long.push(0x01);
+    assert!(matches!(
+        from_slice::<u128>(&message::<u128>(&too_long)).unwrap_err(),
+        DecodeError::VarintOverflow
+    ));
+}
+
+#[test]
+fn limits() {
+    facet_testhelpers::setup();
+
+    // A length prefix claiming far more items than there are bytes
+    let data = message::<Vec<u64>>(&[0xff, 0xff, 0xff, 0xff, 0x0f]);
+    assert!(matches!(
+        from_slice::<Vec<u64>>(&data).unwrap_err(),
+        DecodeError::InsufficientData
+    ));
+
+    let limits = Limits::default().max_string_len(4);
+    let bytes = to_vec(&vec!["abc".to_string(), "abcde".to_string()]);
+    let err = from_slice_with_limits::<Vec<String>>(&bytes, limits).unwrap_err();
+    assert!(matches!(
+        err,
+        DecodeError::LimitExceeded { kind: LimitKind::StringLength, max: 4, ref path }
+            if path.starts_with("$[")
+    ));
+}
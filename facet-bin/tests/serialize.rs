@@ -0,0 +1,152 @@
+use facet::Facet;
+use facet_bin::{fingerprint, to_vec, to_writer};
+use std::collections::BTreeMap;
+
+/// The encoding of a value, without its fingerprint
+fn payload<'a, T: Facet<'a>>(value: &T) -> Vec<u8> {
+    let bytes = to_vec(value);
+    assert_eq!(bytes[..8], fingerprint(T::SHAPE).to_le_bytes());
+    bytes[8..].to_vec()
+}
+
+#[test]
+fn it_works() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+
+    let value = TestStruct {
+        name: "Alice".to_string(),
+        age: 30,
+    };
+    assert_eq!(
+        payload(&value),
+        [
+            0x05, // Length 5
+            b'A', b'l', b'i', b'c', b'e', // "Alice"
+            0x1e, // 30
+        ]
+    );
+}
+
+#[test]
+fn integers() {
+    facet_testhelpers::setup();
+
+    assert_eq!(payload(&0u32), [0x00]);
+    assert_eq!(payload(&127u32), [0x7f]);
+    assert_eq!(payload(&128u32), [0x80, 0x01]);
+    assert_eq!(payload(&16_384u64), [0x80, 0x80, 0x01]);
+    assert_eq!(
+        payload(&u64::MAX),
+        [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]
+    );
+    assert_eq!(payload(&u128::MAX).len(), 19);
+
+    // Zigzag: 0, -1, 1, -2, …
+    assert_eq!(payload(&0i32), [0x00]);
+    assert_eq!(payload(&-1i32), [0x01]);
+    assert_eq!(payload(&1i32), [0x02]);
+    assert_eq!(payload(&-64i64), [0x7f]);
+    assert_eq!(payload(&64i64), [0x80, 0x01]);
+    assert_eq!(payload(&i16::MIN), [0xff, 0xff, 0x03]);
+
+    // Bytes are written as they are
+    assert_eq!(payload(&200u8), [0xc8]);
+    assert_eq!(payload(&-1i8), [0xff]);
+}
+
+#[test]
+fn other_scalars() {
+    facet_testhelpers::setup();
+
+    assert_eq!(payload(&true), [0x01]);
+    assert_eq!(payload(&false), [0x00]);
+    assert_eq!(payload(&1.5f32), 1.5f32.to_le_bytes());
+    assert_eq!(payload(&-0.25f64), (-0.25f64).to_le_bytes());
+    assert_eq!(payload(&'é'), [0xe9, 0x01]);
+    assert_eq!(payload(&"hé"), [0x03, b'h', 0xc3, 0xa9]);
+    assert!(payload(&()).is_empty());
+}
+
+#[test]
+fn containers() {
+    facet_testhelpers::setup();
+
+    assert_eq!(payload(&Some(3u16)), [0x01, 0x03]);
+    assert_eq!(payload(&None::<u16>), [0x00]);
+    assert_eq!(payload(&vec![1u32, 300]), [0x02, 0x01, 0xac, 0x02]);
+    assert_eq!(payload(&vec![0xabu8, 0xcd]), [0x02, 0xab, 0xcd]);
+    assert_eq!(payload(&Vec::<String>::new()), [0x00]);
+
+    let mut map = BTreeMap::new();
+    map.insert("a".to_string(), 1u8);
+    map.insert("b".to_string(), 2u8);
+    assert_eq!(payload(&map), [0x02, 0x01, b'a', 0x01, 0x01, b'b', 0x02]);
+
+    assert_eq!(payload(&(1u8, -1i32, true)), [0x01, 0x01, 0x01]);
+}
+
+#[test]
+fn enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Message {
+        Quit,
+        Move { x: i32, y: i32 },
+        Write(String),
+    }
+
+    // Variant index, then the variant's fields
+    assert_eq!(payload(&Message::Quit), [0x00]);
+    assert_eq!(payload(&Message::Move { x: 1, y: -1 }), [0x01, 0x02, 0x01]);
+    assert_eq!(
+        payload(&Message::Write("hi".to_string())),
+        [0x02, 0x02, b'h', b'i']
+    );
+}
+
+#[test]
+fn skipped_fields_are_written() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Cached {
+        key: u8,
+        #[facet(skip_serializing)]
+        hits: u8,
+    }
+
+    // There are no names to tell a field is missing, so all of them are there
+    assert_eq!(payload(&Cached { key: 1, hits: 2 }), [0x01, 0x02]);
+}
+
+#[test]
+fn transparent_wrappers() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[facet(transparent)]
+    struct Meters(u32);
+
+    assert_eq!(payload(&Meters(300)), payload(&300u32));
+    assert_eq!(fingerprint(Meters::SHAPE), fingerprint(u32::SHAPE));
+}
+
+#[test]
+fn writer() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let mut output = Vec::new();
+    to_writer(&vec!["a", "b"], &mut output)?;
+    assert_eq!(output, to_vec(&vec!["a", "b"]));
+
+    Ok(())
+}
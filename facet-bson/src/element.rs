@@ -0,0 +1,56 @@
+//! Element type bytes, as in the [BSON specification](https://bsonspec.org/spec.html).
+
+pub(crate) const DOUBLE: u8 = 0x01;
+pub(crate) const STRING: u8 = 0x02;
+pub(crate) const DOCUMENT: u8 = 0x03;
+pub(crate) const ARRAY: u8 = 0x04;
+pub(crate) const BINARY: u8 = 0x05;
+pub(crate) const UNDEFINED: u8 = 0x06;
+pub(crate) const OBJECT_ID: u8 = 0x07;
+pub(crate) const BOOLEAN: u8 = 0x08;
+pub(crate) const DATETIME: u8 = 0x09;
+pub(crate) const NULL: u8 = 0x0a;
+pub(crate) const REGEX: u8 = 0x0b;
+pub(crate) const DB_POINTER: u8 = 0x0c;
+pub(crate) const JAVASCRIPT: u8 = 0x0d;
+pub(crate) const SYMBOL: u8 = 0x0e;
+pub(crate) const JAVASCRIPT_WITH_SCOPE: u8 = 0x0f;
+pub(crate) const INT32: u8 = 0x10;
+pub(crate) const TIMESTAMP: u8 = 0x11;
+pub(crate) const INT64: u8 = 0x12;
+pub(crate) const DECIMAL128: u8 = 0x13;
+pub(crate) const MIN_KEY: u8 = 0xff;
+pub(crate) const MAX_KEY: u8 = 0x7f;
+
+/// Binary subtype of generic bytes
+pub(crate) const SUBTYPE_GENERIC: u8 = 0x00;
+/// Binary subtype of UUIDs, in their RFC 4122 byte order
+pub(crate) const SUBTYPE_UUID: u8 = 0x04;
+
+/// The name of an element type, for errors
+pub(crate) fn name(ty: u8) -> &'static str {
+    match ty {
+        DOUBLE => "double",
+        STRING => "string",
+        DOCUMENT => "document",
+        ARRAY => "array",
+        BINARY => "binary",
+        UNDEFINED => "undefined",
+        OBJECT_ID => "ObjectId",
+        BOOLEAN => "boolean",
+        DATETIME => "datetime",
+        NULL => "null",
+        REGEX => "regex",
+        DB_POINTER => "DBPointer",
+        JAVASCRIPT => "JavaScript code",
+        SYMBOL => "symbol",
+        JAVASCRIPT_WITH_SCOPE => "JavaScript code with scope",
+        INT32 => "int32",
+        TIMESTAMP => "timestamp",
+        INT64 => "int64",
+        DECIMAL128 => "decimal128",
+        MIN_KEY => "min key",
+        MAX_KEY => "max key",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,71 @@
+use core::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Errors that can occur while reading BSON
+pub enum Error {
+    /// Not enough data available to decode a complete value
+    InsufficientData,
+    /// A document or string whose length doesn't match its contents
+    InvalidLength,
+    /// A string or key isn't valid UTF-8
+    InvalidUtf8,
+    /// An element type byte that BSON doesn't define
+    UnknownElementType(u8),
+    /// The data is malformed, e.g. a boolean that is neither 0 nor 1
+    InvalidData(&'static str),
+    /// An element that can't be read as the type at its position
+    TypeMismatch {
+        /// The BSON type of the element
+        element: &'static str,
+        /// The type it was read as
+        shape: String,
+    },
+    /// Integer value is too large for the target type
+    IntegerOverflow,
+    /// A datetime outside of the years 0 to 9999, which can't be written as
+    /// RFC 3339 text for the target type to parse
+    InvalidDateTime(i64),
+    /// A required field is missing from a document
+    MissingField(String),
+    /// A field that isn't part of a struct with `#[facet(deny_unknown_fields)]`
+    UnknownField(String),
+    /// A variant name that the enum doesn't have
+    UnknownVariant(String),
+    /// Bytes after the end of the document
+    TrailingData,
+    /// Reflection error
+    ReflectError(facet_reflect::ReflectError),
+}
+
+impl From<facet_reflect::ReflectError> for Error {
+    fn from(err: facet_reflect::ReflectError) -> Self {
+        Self::ReflectError(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InsufficientData => write!(f, "Insufficient data to decode"),
+            Error::InvalidLength => write!(f, "Length doesn't match the contents"),
+            Error::InvalidUtf8 => write!(f, "Invalid UTF-8 in string"),
+            Error::UnknownElementType(ty) => write!(f, "Unknown element type: {:#04x}", ty),
+            Error::InvalidData(reason) => write!(f, "Invalid BSON data: {}", reason),
+            Error::TypeMismatch { element, shape } => {
+                write!(f, "Cannot read {} element as {}", element, shape)
+            }
+            Error::IntegerOverflow => write!(f, "Integer value too large for target type"),
+            Error::InvalidDateTime(millis) => write!(f, "Datetime out of range: {}", millis),
+            Error::MissingField(field) => write!(f, "Missing required field: {}", field),
+            Error::UnknownField(field) => write!(f, "Unknown field: {}", field),
+            Error::UnknownVariant(name) => write!(f, "Unknown variant: {}", name),
+            Error::TrailingData => write!(f, "Trailing data after the document"),
+            Error::ReflectError(err) => {
+                write!(f, "Reflection error: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
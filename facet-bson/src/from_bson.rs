@@ -0,0 +1,455 @@
+use std::borrow::Cow;
+
+use facet_core::{Def, Facet, Field, ScalarAffinity, Shape};
+use facet_reflect::Wip;
+use log::trace;
+
+use crate::element::*;
+use crate::{BsonError, ObjectId, text};
+
+/// Deserializes a BSON document into a struct, map or enum with fields.
+///
+/// Missing fields take their `#[facet(default)]`, or `None` for options,
+/// and fields the type doesn't have are skipped unless it has
+/// `#[facet(deny_unknown_fields)]`.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Point {
+///     x: i32,
+///     label: Option<String>,
+/// }
+///
+/// let bytes = [12, 0, 0, 0, 0x10, b'x', 0, 1, 0, 0, 0, 0];
+/// let point: Point = facet_bson::from_slice(&bytes).unwrap();
+/// assert_eq!(point, Point { x: 1, label: None });
+/// ```
+pub fn from_slice<'input: 'facet, 'facet, T: Facet<'facet>>(
+    input: &'input [u8],
+) -> Result<T, BsonError> {
+    let mut decoder = Decoder { input, offset: 0 };
+    let wip = decoder.decode_value(Wip::alloc::<T>()?, DOCUMENT)?;
+    if decoder.offset != input.len() {
+        return Err(BsonError::TrailingData);
+    }
+    Ok(wip.build()?.materialize::<T>()?)
+}
+
+/// A scalar element, as read
+enum Primitive<'input> {
+    Null,
+    Boolean(bool),
+    Integer(i64),
+    Double(f64),
+    String(&'input str),
+    Binary(u8, &'input [u8]),
+    ObjectId([u8; 12]),
+    DateTime(i64),
+}
+
+/// Follows transparent wrappers down to the type values are read as: a
+/// scalar, or an option. UUIDs and datetimes stop there, as they read the
+/// binary and datetime elements of BSON rather than the strings they wrap.
+fn unwrap(mut shape: &'static Shape) -> &'static Shape {
+    loop {
+        match shape.def {
+            Def::Option(_) => return shape,
+            Def::Scalar(sd)
+                if matches!(
+                    sd.affinity,
+                    ScalarAffinity::UUID(_) | ScalarAffinity::Time(_)
+                ) =>
+            {
+                return shape;
+            }
+            _ => {}
+        }
+        match shape.inner {
+            Some(inner) => shape = inner(),
+            None => return shape,
+        }
+    }
+}
+
+fn is_string(shape: &'static Shape) -> bool {
+    shape.is_type::<String>() || shape.is_type::<&str>() || shape.is_type::<Cow<'_, str>>()
+}
+
+struct Decoder<'input> {
+    input: &'input [u8],
+    offset: usize,
+}
+
+impl<'input> Decoder<'input> {
+    fn take(&mut self, len: usize) -> Result<&'input [u8], BsonError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|&end| end <= self.input.len())
+            .ok_or(BsonError::InsufficientData)?;
+        let bytes = &self.input[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], BsonError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    /// Reads the length that starts documents, strings and binaries
+    fn read_len(&mut self) -> Result<usize, BsonError> {
+        let len = i32::from_le_bytes(self.take_array()?);
+        usize::try_from(len).map_err(|_| BsonError::InvalidLength)
+    }
+
+    fn read_cstring(&mut self) -> Result<&'input str, BsonError> {
+        let rest = &self.input[self.offset..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(BsonError::InsufficientData)?;
+        let s = core::str::from_utf8(&rest[..len]).map_err(|_| BsonError::InvalidUtf8)?;
+        self.offset += len + 1;
+        Ok(s)
+    }
+
+    fn read_string(&mut self) -> Result<&'input str, BsonError> {
+        let len = self.read_len()?;
+        if len == 0 {
+            return Err(BsonError::InvalidLength);
+        }
+        let bytes = self.take(len)?;
+        let (last, bytes) = bytes.split_last().unwrap();
+        if *last != 0 {
+            return Err(BsonError::InvalidLength);
+        }
+        core::str::from_utf8(bytes).map_err(|_| BsonError::InvalidUtf8)
+    }
+
+    /// Reads the length of a document, and returns where it ends
+    fn document_start(&mut self) -> Result<usize, BsonError> {
+        let start = self.offset;
+        let len = self.read_len()?;
+        let end = start.checked_add(len).ok_or(BsonError::InvalidLength)?;
+        // The length counts itself and the final NUL byte
+        if len < 5 || end > self.input.len() {
+            return Err(BsonError::InvalidLength);
+        }
+        Ok(end)
+    }
+
+    /// Reads the type and key of the next element of the document ending at
+    /// `end`, or `None` past its last element.
+    fn next_element(&mut self, end: usize) -> Result<Option<(u8, &'input str)>, BsonError> {
+        if self.offset >= end {
+            return Err(BsonError::InvalidLength);
+        }
+        let ty = self.take(1)?[0];
+        if ty == 0 {
+            if self.offset != end {
+                return Err(BsonError::InvalidLength);
+            }
+            return Ok(None);
+        }
+        let key = self.read_cstring()?;
+        Ok(Some((ty, key)))
+    }
+
+    /// Decodes a value of element type `ty` into the `Wip`'s current frame
+    fn decode_value(&mut self, wip: Wip<'input>, ty: u8) -> Result<Wip<'input>, BsonError> {
+        let shape = wip.shape();
+        trace!("Deserializing {} from {}", shape, name(ty));
+
+        if let Def::Option(_) = shape.def {
+            if ty == NULL || ty == UNDEFINED {
+                return Ok(wip.put_default()?);
+            }
+            let wip = wip.push_some()?;
+            let wip = self.decode_value(wip, ty)?;
+            return Ok(wip.pop()?);
+        }
+
+        // Wrappers can only be built from the scalar they wrap
+        let target = unwrap(shape);
+        if target != shape {
+            return self.decode_scalar(wip, ty, target);
+        }
+
+        match (ty, shape.def) {
+            (DOCUMENT, Def::Struct(sd)) => {
+                let mut wip = wip;
+                let end = self.document_start()?;
+                let deny_unknown_fields = shape.has_deny_unknown_fields_attr();
+                while let Some((ty, key)) = self.next_element(end)? {
+                    match wip.field_index(key) {
+                        Some(index) => {
+                            wip = wip.field(index)?;
+                            wip = self.decode_value(wip, ty)?;
+                            wip = wip.pop()?;
+                        }
+                        None if deny_unknown_fields => {
+                            return Err(BsonError::UnknownField(key.to_string()));
+                        }
+                        None => {
+                            trace!("Skipping field {}", key);
+                            self.skip(ty)?;
+                        }
+                    }
+                }
+                fill_defaults(wip, sd.fields)
+            }
+            (ARRAY, Def::List(_)) => {
+                let mut wip = wip.put_default()?;
+                let end = self.document_start()?;
+                while let Some((ty, _)) = self.next_element(end)? {
+                    wip = wip.push()?;
+                    wip = self.decode_value(wip, ty)?;
+                    wip = wip.pop()?;
+                }
+                Ok(wip)
+            }
+            (DOCUMENT, Def::Map(md)) => {
+                let mut wip = wip.put_default()?;
+                let end = self.document_start()?;
+                while let Some((ty, key)) = self.next_element(end)? {
+                    wip = wip.push_map_key()?;
+                    // Keys are always strings, which other key types parse
+                    let key_shape = unwrap(md.k);
+                    wip = if is_string(key_shape) {
+                        put_primitive(wip, Primitive::String(key), key_shape, STRING)?
+                    } else {
+                        wip.parse(key)?
+                    };
+                    wip = wip.push_map_value()?;
+                    wip = self.decode_value(wip, ty)?;
+                    wip = wip.pop()?;
+                }
+                Ok(wip)
+            }
+            (STRING, Def::Enum(ed)) => {
+                let name = self.read_string()?;
+                let index = ed
+                    .variants
+                    .iter()
+                    .position(|v| v.name == name)
+                    .ok_or_else(|| BsonError::UnknownVariant(name.to_string()))?;
+                let wip = wip.variant(index)?;
+                fill_defaults(wip, ed.variants[index].data.fields)
+            }
+            (DOCUMENT, Def::Enum(ed)) => {
+                let end = self.document_start()?;
+                let Some((ty, name)) = self.next_element(end)? else {
+                    return Err(BsonError::InvalidData("empty enum document"));
+                };
+                let index = ed
+                    .variants
+                    .iter()
+                    .position(|v| v.name == name)
+                    .ok_or_else(|| BsonError::UnknownVariant(name.to_string()))?;
+                let fields = ed.variants[index].data.fields;
+                let mut wip = wip.variant(index)?;
+
+                // Newtype variants hold their value directly, others a
+                // document of their fields
+                if fields.len() == 1 && fields[0].name == "0" {
+                    wip = wip.field(0)?;
+                    wip = self.decode_value(wip, ty)?;
+                    wip = wip.pop()?;
+                } else {
+                    if ty != DOCUMENT {
+                        return Err(mismatch(ty, shape));
+                    }
+                    let fields_end = self.document_start()?;
+                    while let Some((ty, key)) = self.next_element(fields_end)? {
+                        match wip.field_index(key) {
+                            Some(index) => {
+                                wip = wip.field(index)?;
+                                wip = self.decode_value(wip, ty)?;
+                                wip = wip.pop()?;
+                            }
+                            None => self.skip(ty)?,
+                        }
+                    }
+                    wip = fill_defaults(wip, fields)?;
+                }
+
+                if self.next_element(end)?.is_some() {
+                    return Err(BsonError::InvalidData(
+                        "enum documents hold a single element",
+                    ));
+                }
+                Ok(wip)
+            }
+            _ => self.decode_scalar(wip, ty, shape),
+        }
+    }
+
+    /// Decodes a scalar element into the `target` shape, which is the
+    /// current frame's or the scalar a wrapper around it holds.
+    fn decode_scalar(
+        &mut self,
+        wip: Wip<'input>,
+        ty: u8,
+        target: &'static Shape,
+    ) -> Result<Wip<'input>, BsonError> {
+        let value = match ty {
+            NULL | UNDEFINED => Primitive::Null,
+            BOOLEAN => match self.take(1)?[0] {
+                0 => Primitive::Boolean(false),
+                1 => Primitive::Boolean(true),
+                _ => return Err(BsonError::InvalidData("boolean is neither 0 nor 1")),
+            },
+            INT32 => Primitive::Integer(i32::from_le_bytes(self.take_array()?) as i64),
+            INT64 => Primitive::Integer(i64::from_le_bytes(self.take_array()?)),
+            DOUBLE => Primitive::Double(f64::from_le_bytes(self.take_array()?)),
+            STRING | SYMBOL => Primitive::String(self.read_string()?),
+            BINARY => {
+                let len = self.read_len()?;
+                let subtype = self.take(1)?[0];
+                Primitive::Binary(subtype, self.take(len)?)
+            }
+            OBJECT_ID => Primitive::ObjectId(self.take_array()?),
+            DATETIME => Primitive::DateTime(i64::from_le_bytes(self.take_array()?)),
+            _ if name(ty) == "unknown" => return Err(BsonError::UnknownElementType(ty)),
+            _ => return Err(mismatch(ty, target)),
+        };
+        put_primitive(wip, value, target, ty)
+    }
+
+    /// Reads past an element the type has no field for
+    fn skip(&mut self, ty: u8) -> Result<(), BsonError> {
+        match ty {
+            NULL | UNDEFINED | MIN_KEY | MAX_KEY => {}
+            BOOLEAN => {
+                self.take(1)?;
+            }
+            INT32 => {
+                self.take(4)?;
+            }
+            DOUBLE | DATETIME | TIMESTAMP | INT64 => {
+                self.take(8)?;
+            }
+            OBJECT_ID => {
+                self.take(12)?;
+            }
+            DECIMAL128 => {
+                self.take(16)?;
+            }
+            STRING | SYMBOL | JAVASCRIPT => {
+                self.read_string()?;
+            }
+            DB_POINTER => {
+                self.read_string()?;
+                self.take(12)?;
+            }
+            REGEX => {
+                self.read_cstring()?;
+                self.read_cstring()?;
+            }
+            BINARY => {
+                let len = self.read_len()?;
+                self.take(len + 1)?;
+            }
+            // Lengths that count themselves
+            DOCUMENT | ARRAY | JAVASCRIPT_WITH_SCOPE => {
+                let end = self.document_start()?;
+                self.offset = end;
+            }
+            _ => return Err(BsonError::UnknownElementType(ty)),
+        }
+        Ok(())
+    }
+}
+
+fn mismatch(ty: u8, shape: &'static Shape) -> BsonError {
+    BsonError::TypeMismatch {
+        element: name(ty),
+        shape: shape.to_string(),
+    }
+}
+
+/// Fills the fields the document didn't have with their defaults
+fn fill_defaults<'input>(
+    mut wip: Wip<'input>,
+    fields: &'static [Field],
+) -> Result<Wip<'input>, BsonError> {
+    for (index, field) in fields.iter().enumerate() {
+        if wip.is_field_set(index)? {
+            continue;
+        }
+        wip = wip.field(index)?;
+        wip = match field.maybe_default_fn() {
+            Some(Some(default_fn)) => wip.put_from_fn(default_fn)?,
+            Some(None) => wip.put_default()?,
+            None if matches!(field.shape().def, Def::Option(_)) => wip.put_default()?,
+            None => return Err(BsonError::MissingField(field.name.to_string())),
+        };
+        wip = wip.pop()?;
+    }
+    Ok(wip)
+}
+
+/// Puts a scalar element into the `target` shape, converting it where
+/// BSON's types and Rust's differ
+fn put_primitive<'input>(
+    wip: Wip<'input>,
+    value: Primitive<'input>,
+    target: &'static Shape,
+    ty: u8,
+) -> Result<Wip<'input>, BsonError> {
+    macro_rules! integers {
+        ($($int:ty),*) => {
+            $(
+                if target.is_type::<$int>() {
+                    let Primitive::Integer(n) = value else {
+                        return Err(mismatch(ty, target));
+                    };
+                    let n = <$int>::try_from(n).map_err(|_| BsonError::IntegerOverflow)?;
+                    return Ok(wip.put(n)?);
+                }
+            )*
+        };
+    }
+    integers!(
+        i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+    );
+
+    let affinity = match target.def {
+        Def::Scalar(sd) => Some(sd.affinity),
+        _ => None,
+    };
+    let wip = match value {
+        Primitive::Double(n) if target.is_type::<f64>() => wip.put(n)?,
+        Primitive::Double(n) if target.is_type::<f32>() => wip.put(n as f32)?,
+        Primitive::Integer(n) if target.is_type::<f64>() => wip.put(n as f64)?,
+        Primitive::Integer(n) if target.is_type::<f32>() => wip.put(n as f32)?,
+        Primitive::Boolean(b) if target.is_type::<bool>() => wip.put(b)?,
+        Primitive::Null if target.is_type::<()>() => wip.put(())?,
+        Primitive::String(s) if target.is_type::<String>() => wip.put(s.to_string())?,
+        Primitive::String(s) if target.is_type::<&str>() => wip.put(s)?,
+        Primitive::String(s) if target.is_type::<Cow<'_, str>>() => wip.put(Cow::Borrowed(s))?,
+        Primitive::Binary(_, b) if target.is_type::<Vec<u8>>() => wip.put(b.to_vec())?,
+        Primitive::Binary(_, b) if target.is_type::<&[u8]>() => wip.put(b)?,
+        Primitive::ObjectId(id) if target.is_type::<ObjectId>() => {
+            wip.put(ObjectId::from_bytes(id))?
+        }
+        Primitive::Binary(_, b) if matches!(affinity, Some(ScalarAffinity::UUID(_))) => {
+            let bytes: &[u8; 16] = b
+                .try_into()
+                .map_err(|_| BsonError::InvalidData("UUIDs are 16 bytes"))?;
+            wip.parse(&text::format_uuid(bytes))?
+        }
+        Primitive::DateTime(millis) if matches!(affinity, Some(ScalarAffinity::Time(_))) => {
+            let text = text::format_rfc3339(millis).ok_or(BsonError::InvalidDateTime(millis))?;
+            wip.parse(&text)?
+        }
+        // Chars, and scalars such as UUIDs, paths or IP addresses, parse
+        // their text
+        Primitive::String(s) if target.vtable.parse.is_some() => wip.parse(s)?,
+        _ => return Err(mismatch(ty, target)),
+    };
+    Ok(wip)
+}
@@ -0,0 +1,18 @@
+#![warn(missing_docs)]
+#![deny(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+mod errors;
+pub use errors::Error as BsonError;
+
+mod element;
+mod text;
+
+mod object_id;
+pub use object_id::*;
+
+mod from_bson;
+pub use from_bson::*;
+
+mod to_bson;
+pub use to_bson::*;
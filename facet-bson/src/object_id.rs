@@ -0,0 +1,124 @@
+//! The 12-byte identifier MongoDB gives documents.
+
+use core::fmt;
+use core::str::FromStr;
+
+use facet_core::{
+    Def, Facet, PtrConst, PtrMut, PtrUninit, ScalarAffinity, ScalarDef, Shape, TryFromError,
+    value_vtable,
+};
+
+/// An ObjectId, written as BSON's ObjectId element rather than as binary.
+///
+/// Other formats see it as text: 24 lowercase hexadecimal digits.
+///
+/// ```
+/// use facet::Facet;
+/// use facet_bson::ObjectId;
+///
+/// #[derive(Debug, PartialEq, Facet)]
+/// struct Post {
+///     _id: ObjectId,
+///     title: String,
+/// }
+///
+/// let post = Post {
+///     _id: "65f1a2b3c4d5e6f708192a3b".parse().unwrap(),
+///     title: "Hello".to_string(),
+/// };
+/// let bytes = facet_bson::to_vec(&post);
+/// // The element type of `_id`, after the length of the document
+/// assert_eq!(bytes[4], 0x07);
+/// assert_eq!(facet_bson::from_slice::<Post>(&bytes).unwrap(), post);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObjectId([u8; 12]);
+
+impl ObjectId {
+    /// Wraps the 12 bytes of an ObjectId
+    pub const fn from_bytes(bytes: [u8; 12]) -> Self {
+        ObjectId(bytes)
+    }
+
+    /// The 12 bytes of the ObjectId
+    pub const fn bytes(&self) -> [u8; 12] {
+        self.0
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ObjectId {
+    type Err = InvalidObjectId;
+
+    /// Accepts 24 hexadecimal digits, in either case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 24 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(InvalidObjectId);
+        }
+        let mut bytes = [0; 12];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).map_err(|_| InvalidObjectId)?;
+        }
+        Ok(ObjectId(bytes))
+    }
+}
+
+/// Error returned when a string isn't 24 hexadecimal digits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidObjectId;
+
+impl fmt::Display for InvalidObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a valid ObjectId")
+    }
+}
+
+impl std::error::Error for InvalidObjectId {}
+
+#[allow(unsafe_code)]
+unsafe impl Facet<'_> for ObjectId {
+    const SHAPE: &'static Shape = &const {
+        // Formats without ObjectIds carry them as hexadecimal strings
+        unsafe fn try_from<'dst>(
+            src_ptr: PtrConst<'_>,
+            src_shape: &'static Shape,
+            dst: PtrUninit<'dst>,
+        ) -> Result<PtrMut<'dst>, TryFromError> {
+            if src_shape.id != <String as Facet>::SHAPE.id {
+                return Err(TryFromError::UnsupportedSourceShape {
+                    src_shape,
+                    expected: &[<String as Facet>::SHAPE],
+                });
+            }
+            // Take the string over: whoever put it gave it up
+            let s = unsafe { src_ptr.read::<String>() };
+            match s.parse::<ObjectId>() {
+                Ok(id) => Ok(unsafe { dst.put(id) }),
+                Err(_) => Err(TryFromError::Generic("not a valid ObjectId")),
+            }
+        }
+
+        Shape::builder_for_sized::<Self>()
+            .def(Def::Scalar(
+                ScalarDef::builder()
+                    .affinity(ScalarAffinity::other().build())
+                    .build(),
+            ))
+            .vtable(
+                &const {
+                    let mut vtable = value_vtable!(ObjectId, |f, _opts| write!(f, "ObjectId"));
+                    vtable.try_from = Some(try_from);
+                    vtable
+                },
+            )
+            .build()
+    };
+}
@@ -0,0 +1,315 @@
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use facet_core::{Def, Facet, NumberBits, ScalarAffinity, ScalarDef, Signedness};
+use facet_reflect::Peek;
+use log::trace;
+
+use crate::element::*;
+use crate::{ObjectId, text};
+
+/// Serializes a struct, map or enum with fields to a BSON document
+///
+/// # Panics
+/// If the value isn't written as a document, or holds a value BSON can't
+/// represent, such as a `u64` above `i64::MAX`.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Point {
+///     x: i32,
+/// }
+///
+/// let bytes = facet_bson::to_vec(&Point { x: 1 });
+/// // Length, then an int32 element named "x", then the end of the document
+/// assert_eq!(bytes, [12, 0, 0, 0, 0x10, b'x', 0, 1, 0, 0, 0, 0]);
+/// ```
+pub fn to_vec<'a, T: Facet<'a>>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    to_writer(value, &mut out).unwrap();
+    out
+}
+
+/// Serializes a struct, map or enum with fields to a writer as a BSON
+/// document
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    // Documents start with their length, so they're built in memory first
+    let mut out = Vec::new();
+    let ty = write_value(&mut out, Peek::new(value))?;
+    if ty != DOCUMENT {
+        return Err(io::Error::other(format!(
+            "{} is written as a {}, not a document",
+            T::SHAPE,
+            name(ty)
+        )));
+    }
+    writer.write_all(&out)
+}
+
+fn other(e: impl core::fmt::Display) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+fn unsupported(pv: Peek<'_, '_>) -> io::Error {
+    io::Error::other(format!("Unsupported type: {}", pv.shape()))
+}
+
+/// Writes a document, whose elements `body` writes, and returns its
+/// element type
+fn write_document(
+    out: &mut Vec<u8>,
+    body: impl FnOnce(&mut Vec<u8>) -> io::Result<()>,
+) -> io::Result<u8> {
+    let start = out.len();
+    out.extend_from_slice(&[0; 4]);
+    body(out)?;
+    out.push(0);
+    let len = i32::try_from(out.len() - start).map_err(|_| other("document too large"))?;
+    out[start..start + 4].copy_from_slice(&len.to_le_bytes());
+    Ok(DOCUMENT)
+}
+
+/// Writes an element: its type, its key, then the value `value` writes and
+/// returns the type of
+fn write_element(
+    out: &mut Vec<u8>,
+    key: &str,
+    value: impl FnOnce(&mut Vec<u8>) -> io::Result<u8>,
+) -> io::Result<()> {
+    let ty_pos = out.len();
+    out.push(0);
+    if key.contains('\0') {
+        return Err(other(format!("Key contains a NUL byte: {:?}", key)));
+    }
+    out.extend_from_slice(key.as_bytes());
+    out.push(0);
+    out[ty_pos] = value(out)?;
+    Ok(())
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) -> io::Result<u8> {
+    let len = i32::try_from(s.len() + 1).map_err(|_| other("string too large"))?;
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+    out.push(0);
+    Ok(STRING)
+}
+
+fn write_binary(out: &mut Vec<u8>, subtype: u8, bytes: &[u8]) -> io::Result<u8> {
+    let len = i32::try_from(bytes.len()).map_err(|_| other("binary too large"))?;
+    out.extend_from_slice(&len.to_le_bytes());
+    out.push(subtype);
+    out.extend_from_slice(bytes);
+    Ok(BINARY)
+}
+
+/// The text of a map key
+fn key_string<'mem>(pv: Peek<'mem, '_>) -> Option<Cow<'mem, str>> {
+    if let Some(inner) = pv.borrow_inner() {
+        return key_string(inner);
+    }
+    if let Ok(s) = pv.get::<String>() {
+        Some(Cow::Borrowed(s))
+    } else if let Ok(s) = pv.get::<&str>() {
+        Some(Cow::Borrowed(s))
+    } else if let Ok(s) = pv.get::<Cow<'_, str>>() {
+        Some(Cow::Borrowed(s))
+    } else if pv.shape().vtable.display.is_some() {
+        Some(Cow::Owned(pv.to_string()))
+    } else {
+        None
+    }
+}
+
+/// Writes a value, without its key, and returns its element type.
+fn write_value(out: &mut Vec<u8>, pv: Peek<'_, '_>) -> io::Result<u8> {
+    let shape = pv.shape();
+
+    if let Def::Option(_) = shape.def {
+        return match pv.into_option().map_err(other)?.value() {
+            Some(value) => write_value(out, value),
+            None => Ok(NULL),
+        };
+    }
+
+    // Transparent wrappers are written as the value they wrap
+    if let Some(inner) = pv.borrow_inner() {
+        return write_value(out, inner);
+    }
+
+    match shape.def {
+        Def::Scalar(sd) => write_scalar(out, pv, sd),
+        Def::Struct(_) => {
+            trace!("Serializing struct {}", shape);
+            let ps = pv.into_struct().map_err(other)?;
+            write_document(out, |out| {
+                for (field, value) in ps.fields_for_serialize() {
+                    write_element(out, field.name, |out| write_value(out, value))?;
+                }
+                Ok(())
+            })
+        }
+        Def::List(_) if shape.is_type::<Vec<u8>>() => {
+            let bytes = pv.get::<Vec<u8>>().map_err(other)?;
+            write_binary(out, SUBTYPE_GENERIC, bytes)
+        }
+        Def::Slice(_) if shape.is_type::<&[u8]>() => {
+            let bytes = pv.get::<&[u8]>().map_err(other)?;
+            write_binary(out, SUBTYPE_GENERIC, bytes)
+        }
+        Def::List(_) | Def::Array(_) | Def::Slice(_) => {
+            trace!("Serializing array {}", shape);
+            let items: Vec<_> = match shape.def {
+                Def::List(_) => pv.into_list().map_err(other)?.iter().collect(),
+                Def::Array(_) => pv.into_array().map_err(other)?.iter().collect(),
+                _ => pv.into_slice().map_err(other)?.iter().collect(),
+            };
+            // Arrays are documents whose keys are the indices
+            write_document(out, |out| {
+                for (index, item) in items.into_iter().enumerate() {
+                    write_element(out, &index.to_string(), |out| write_value(out, item))?;
+                }
+                Ok(())
+            })?;
+            Ok(ARRAY)
+        }
+        Def::Map(_) => {
+            trace!("Serializing map {}", shape);
+            let map = pv.into_map().map_err(other)?;
+            write_document(out, |out| {
+                for (key, value) in map.iter() {
+                    let key = key_string(key).ok_or_else(|| unsupported(key))?;
+                    write_element(out, &key, |out| write_value(out, value))?;
+                }
+                Ok(())
+            })
+        }
+        Def::Enum(_) => {
+            trace!("Serializing enum {}", shape);
+            let pe = pv.into_enum().map_err(other)?;
+            let variant = pe.active_variant();
+            let fields = variant.data.fields;
+            // Unit variants are their name, and others a document with a
+            // single element named after the variant
+            if fields.is_empty() {
+                return write_string(out, variant.name);
+            }
+            write_document(out, |out| {
+                write_element(out, variant.name, |out| {
+                    if fields.len() == 1 && fields[0].name == "0" {
+                        let value = pe.field(0).ok_or_else(|| unsupported(pv))?;
+                        return write_value(out, value);
+                    }
+                    write_document(out, |out| {
+                        for (field, value) in pe.fields_for_serialize() {
+                            write_element(out, field.name, |out| write_value(out, value))?;
+                        }
+                        Ok(())
+                    })
+                })
+            })
+        }
+        Def::SmartPointer(_) => {
+            let pointer = pv.into_smart_pointer().map_err(other)?;
+            let pointee = pointer
+                .borrow_inner()
+                .ok_or_else(|| other(format!("Cannot borrow the pointee of {}", shape)))?;
+            write_value(out, pointee)
+        }
+        _ => Err(unsupported(pv)),
+    }
+}
+
+fn write_scalar(out: &mut Vec<u8>, pv: Peek<'_, '_>, sd: ScalarDef) -> io::Result<u8> {
+    let shape = pv.shape();
+    trace!("Serializing scalar {}", shape);
+
+    if let Ok(id) = pv.get::<ObjectId>() {
+        out.extend_from_slice(&id.bytes());
+        return Ok(OBJECT_ID);
+    }
+
+    match sd.affinity {
+        ScalarAffinity::Number(na) => match na.bits {
+            NumberBits::Integer { bits, sign } => {
+                let value = integer_value(pv).ok_or_else(|| unsupported(pv))?;
+                // int32 holds every signed integer up to 32 bits, and
+                // unsigned ones below that
+                if bits < 32 || (bits == 32 && sign == Signedness::Signed) {
+                    out.extend_from_slice(&(value as i32).to_le_bytes());
+                    return Ok(INT32);
+                }
+                let value = i64::try_from(value)
+                    .map_err(|_| other(format!("{} doesn't fit in an int64", value)))?;
+                out.extend_from_slice(&value.to_le_bytes());
+                Ok(INT64)
+            }
+            NumberBits::Float { .. } => {
+                let value = if let Ok(value) = pv.get::<f32>() {
+                    *value as f64
+                } else {
+                    *pv.get::<f64>().map_err(other)?
+                };
+                out.extend_from_slice(&value.to_le_bytes());
+                Ok(DOUBLE)
+            }
+            _ => write_text(out, pv),
+        },
+        ScalarAffinity::Boolean(_) => {
+            let value = pv.get::<bool>().map_err(other)?;
+            out.push(*value as u8);
+            Ok(BOOLEAN)
+        }
+        ScalarAffinity::Empty(_) => Ok(NULL),
+        ScalarAffinity::UUID(_) if shape.vtable.display.is_some() => {
+            let text = pv.to_string();
+            let bytes = text::parse_uuid(&text)
+                .ok_or_else(|| other(format!("{:?} is not a UUID", text)))?;
+            write_binary(out, SUBTYPE_UUID, &bytes)
+        }
+        ScalarAffinity::Time(_) if shape.vtable.display.is_some() => {
+            let text = pv.to_string();
+            let millis = text::parse_rfc3339(&text)
+                .ok_or_else(|| other(format!("{:?} is not an RFC 3339 datetime", text)))?;
+            out.extend_from_slice(&millis.to_le_bytes());
+            Ok(DATETIME)
+        }
+        _ => write_text(out, pv),
+    }
+}
+
+/// Writes strings, chars, and other scalars such as paths or IP addresses
+/// as their text
+fn write_text(out: &mut Vec<u8>, pv: Peek<'_, '_>) -> io::Result<u8> {
+    if let Ok(value) = pv.get::<String>() {
+        write_string(out, value)
+    } else if let Ok(value) = pv.get::<&str>() {
+        write_string(out, value)
+    } else if let Ok(value) = pv.get::<Cow<'_, str>>() {
+        write_string(out, value)
+    } else if pv.shape().vtable.display.is_some() {
+        write_string(out, &pv.to_string())
+    } else {
+        Err(unsupported(pv))
+    }
+}
+
+/// The value of an integer of any width
+fn integer_value(pv: Peek<'_, '_>) -> Option<i128> {
+    macro_rules! integers {
+        ($($ty:ty),*) => {
+            $(
+                if let Ok(value) = pv.get::<$ty>() {
+                    return i128::try_from(*value).ok();
+                }
+            )*
+        };
+    }
+    integers!(
+        i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+    );
+    None
+}
@@ -0,0 +1,366 @@
+use core::fmt;
+use core::str::FromStr;
+
+use eyre::Result;
+use facet::{Def, Facet, ScalarAffinity, ScalarDef, Shape, value_vtable};
+use facet_bson::{BsonError, ObjectId, from_slice, to_vec};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Builds a document from the type, key and value of its elements
+fn document(elements: &[(u8, &str, &[u8])]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (ty, key, value) in elements {
+        body.push(*ty);
+        body.extend_from_slice(key.as_bytes());
+        body.push(0);
+        body.extend_from_slice(value);
+    }
+    let mut out = ((body.len() + 5) as i32).to_le_bytes().to_vec();
+    out.extend_from_slice(&body);
+    out.push(0);
+    out
+}
+
+fn string(s: &str) -> Vec<u8> {
+    let mut out = ((s.len() + 1) as i32).to_le_bytes().to_vec();
+    out.extend_from_slice(s.as_bytes());
+    out.push(0);
+    out
+}
+
+/// A datetime that only knows its RFC 3339 text, as datetime types display
+/// and parse theirs
+#[derive(Debug, PartialEq)]
+struct Timestamp(String);
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = core::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Timestamp(s.to_string()))
+    }
+}
+
+unsafe impl Facet<'_> for Timestamp {
+    const SHAPE: &'static Shape = &const {
+        Shape::builder_for_sized::<Self>()
+            .def(Def::Scalar(
+                ScalarDef::builder()
+                    .affinity(ScalarAffinity::time().build())
+                    .build(),
+            ))
+            .vtable(&const { value_vtable!(Timestamp, |f, _opts| write!(f, "Timestamp")) })
+            .build()
+    };
+}
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+
+    let data = document(&[
+        (0x02, "name", &string("Alice")),
+        (0x12, "age", &30i64.to_le_bytes()),
+    ]);
+    assert_eq!(
+        from_slice::<TestStruct>(&data)?,
+        TestStruct {
+            name: "Alice".to_string(),
+            age: 30,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn roundtrip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Status {
+        Pending,
+        Shipped { carrier: String },
+        Refunded(i64),
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Line {
+        sku: String,
+        quantity: u16,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Order {
+        _id: ObjectId,
+        request: Uuid,
+        placed_at: Timestamp,
+        lines: Vec<Line>,
+        statuses: Vec<Status>,
+        attributes: HashMap<String, f64>,
+        signature: Vec<u8>,
+        counts: HashMap<u32, bool>,
+        notes: Option<String>,
+        total: u64,
+        initial: char,
+    }
+
+    let order = Order {
+        _id: "65f1a2b3c4d5e6f708192a3b".parse()?,
+        request: Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8")?,
+        placed_at: Timestamp("2024-03-01T12:30:00.250Z".to_string()),
+        lines: vec![Line {
+            sku: "A-1".to_string(),
+            quantity: 3,
+        }],
+        statuses: vec![
+            Status::Pending,
+            Status::Shipped {
+                carrier: "post".to_string(),
+            },
+            Status::Refunded(-5),
+        ],
+        attributes: HashMap::from([("weight".to_string(), 1.5)]),
+        signature: vec![0, 255],
+        counts: HashMap::from([(7, true)]),
+        notes: None,
+        total: 1 << 40,
+        initial: 'é',
+    };
+    assert_eq!(from_slice::<Order>(&to_vec(&order))?, order);
+    Ok(())
+}
+
+#[test]
+fn datetimes() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Event {
+        at: Timestamp,
+    }
+
+    // Offsets are folded into UTC milliseconds
+    let event = Event {
+        at: Timestamp("1970-01-01T01:00:01+01:00".to_string()),
+    };
+    let bytes = to_vec(&event);
+    assert_eq!(bytes, document(&[(0x09, "at", &1000i64.to_le_bytes())]));
+    assert_eq!(
+        from_slice::<Event>(&bytes)?,
+        Event {
+            at: Timestamp("1970-01-01T00:00:01Z".to_string()),
+        }
+    );
+
+    let far_future = document(&[(0x09, "at", &i64::MAX.to_le_bytes())]);
+    assert!(matches!(
+        from_slice::<Event>(&far_future),
+        Err(BsonError::InvalidDateTime(i64::MAX))
+    ));
+    Ok(())
+}
+
+#[test]
+fn uuids_from_binary_or_strings() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Device {
+        id: Uuid,
+    }
+
+    let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8")?;
+    let mut binary = vec![16, 0, 0, 0, 0x04];
+    binary.extend_from_slice(uuid.as_bytes());
+    let data = document(&[(0x05, "id", &binary)]);
+    assert_eq!(from_slice::<Device>(&data)?, Device { id: uuid });
+
+    let data = document(&[(0x02, "id", &string(&uuid.to_string()))]);
+    assert_eq!(from_slice::<Device>(&data)?, Device { id: uuid });
+    Ok(())
+}
+
+#[test]
+fn integer_widening_and_overflow() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Sample {
+        count: i64,
+        ratio: f64,
+    }
+
+    // Documents written elsewhere may use int32 for small values, and
+    // integers for whole doubles
+    let data = document(&[
+        (0x10, "count", &5i32.to_le_bytes()),
+        (0x10, "ratio", &2i32.to_le_bytes()),
+    ]);
+    assert_eq!(
+        from_slice::<Sample>(&data)?,
+        Sample {
+            count: 5,
+            ratio: 2.0
+        }
+    );
+
+    #[derive(Debug, Facet)]
+    struct Small {
+        #[allow(dead_code)]
+        value: u8,
+    }
+
+    let data = document(&[(0x10, "value", &300i32.to_le_bytes())]);
+    assert!(matches!(
+        from_slice::<Small>(&data),
+        Err(BsonError::IntegerOverflow)
+    ));
+    Ok(())
+}
+
+#[test]
+fn missing_and_unknown_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    fn default_limit() -> u32 {
+        10
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Query {
+        name: String,
+        #[facet(default = "default_limit")]
+        limit: u32,
+        cursor: Option<String>,
+    }
+
+    // Elements the type has no field for are skipped, whatever their type
+    let data = document(&[
+        (0x13, "decimal", &[0; 16]),
+        (0x0b, "regex", b"^a\0i\0"),
+        (0x03, "nested", &document(&[(0x08, "flag", &[1])])),
+        (0x02, "name", &string("q")),
+        (0x11, "timestamp", &[0; 8]),
+    ]);
+    assert_eq!(
+        from_slice::<Query>(&data)?,
+        Query {
+            name: "q".to_string(),
+            limit: 10,
+            cursor: None,
+        }
+    );
+
+    let data = document(&[(0x10, "limit", &1i32.to_le_bytes())]);
+    assert!(matches!(
+        from_slice::<Query>(&data),
+        Err(BsonError::MissingField(field)) if field == "name"
+    ));
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        #[allow(dead_code)]
+        name: String,
+    }
+
+    let data = document(&[(0x02, "name", &string("q")), (0x0a, "extra", &[])]);
+    assert!(matches!(
+        from_slice::<Strict>(&data),
+        Err(BsonError::UnknownField(field)) if field == "extra"
+    ));
+    Ok(())
+}
+
+#[test]
+fn malformed_documents() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Flag {
+        #[allow(dead_code)]
+        on: bool,
+    }
+
+    let data = document(&[(0x08, "on", &[1])]);
+    assert!(from_slice::<Flag>(&data).is_ok());
+
+    let mut trailing = data.clone();
+    trailing.push(0);
+    assert!(matches!(
+        from_slice::<Flag>(&trailing),
+        Err(BsonError::TrailingData)
+    ));
+
+    let mut wrong_length = data.clone();
+    wrong_length[0] += 1;
+    assert!(matches!(
+        from_slice::<Flag>(&wrong_length),
+        Err(BsonError::InvalidLength)
+    ));
+
+    assert!(matches!(
+        from_slice::<Flag>(&data[..data.len() - 1]),
+        Err(BsonError::InvalidLength)
+    ));
+
+    let data = document(&[(0x08, "on", &[2])]);
+    assert!(matches!(
+        from_slice::<Flag>(&data),
+        Err(BsonError::InvalidData(_))
+    ));
+
+    let data = document(&[(0x10, "on", &1i32.to_le_bytes())]);
+    assert!(matches!(
+        from_slice::<Flag>(&data),
+        Err(BsonError::TypeMismatch {
+            element: "int32",
+            ..
+        })
+    ));
+
+    let data = document(&[(0x42, "on", &[])]);
+    assert!(matches!(
+        from_slice::<Flag>(&data),
+        Err(BsonError::UnknownElementType(0x42))
+    ));
+}
+
+#[test]
+fn unknown_variants() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Mode {
+        Fast,
+        Slow,
+    }
+
+    #[derive(Debug, Facet)]
+    struct Settings {
+        #[allow(dead_code)]
+        mode: Mode,
+    }
+
+    let data = document(&[(0x02, "mode", &string("Medium"))]);
+    assert!(matches!(
+        from_slice::<Settings>(&data),
+        Err(BsonError::UnknownVariant(name)) if name == "Medium"
+    ));
+}
@@ -0,0 +1,222 @@
+use facet::Facet;
+use facet_bson::{ObjectId, to_vec, to_writer};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+/// Builds a document from the type, key and value of its elements
+fn document(elements: &[(u8, &str, &[u8])]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (ty, key, value) in elements {
+        body.push(*ty);
+        body.extend_from_slice(key.as_bytes());
+        body.push(0);
+        body.extend_from_slice(value);
+    }
+    let mut out = ((body.len() + 5) as i32).to_le_bytes().to_vec();
+    out.extend_from_slice(&body);
+    out.push(0);
+    out
+}
+
+fn string(s: &str) -> Vec<u8> {
+    let mut out = ((s.len() + 1) as i32).to_le_bytes().to_vec();
+    out.extend_from_slice(s.as_bytes());
+    out.push(0);
+    out
+}
+
+#[test]
+fn it_works() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+
+    let value = TestStruct {
+        name: "Alice".to_string(),
+        age: 30,
+    };
+    assert_eq!(
+        to_vec(&value),
+        document(&[
+            (0x02, "name", &string("Alice")),
+            (0x12, "age", &30i64.to_le_bytes()),
+        ])
+    );
+}
+
+#[test]
+fn integer_widths() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Integers {
+        small: u8,
+        signed: i32,
+        unsigned: u32,
+        long: i64,
+        float: f32,
+    }
+
+    let value = Integers {
+        small: 1,
+        signed: -1,
+        unsigned: 2,
+        long: 3,
+        float: 0.5,
+    };
+    // int32 only holds unsigned integers narrower than 32 bits
+    assert_eq!(
+        to_vec(&value),
+        document(&[
+            (0x10, "small", &1i32.to_le_bytes()),
+            (0x10, "signed", &(-1i32).to_le_bytes()),
+            (0x12, "unsigned", &2i64.to_le_bytes()),
+            (0x12, "long", &3i64.to_le_bytes()),
+            (0x01, "float", &0.5f64.to_le_bytes()),
+        ])
+    );
+}
+
+#[test]
+fn binary_uuid_and_object_id() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Blob {
+        _id: ObjectId,
+        id: Uuid,
+        data: Vec<u8>,
+    }
+
+    let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    let value = Blob {
+        _id: ObjectId::from_bytes([7; 12]),
+        id: uuid,
+        data: vec![1, 2, 3],
+    };
+
+    let mut uuid_binary = vec![16, 0, 0, 0, 0x04];
+    uuid_binary.extend_from_slice(uuid.as_bytes());
+    assert_eq!(
+        to_vec(&value),
+        document(&[
+            (0x07, "_id", &[7; 12]),
+            (0x05, "id", &uuid_binary),
+            (0x05, "data", &[3, 0, 0, 0, 0x00, 1, 2, 3]),
+        ])
+    );
+}
+
+#[test]
+fn arrays_and_embedded_documents() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(Facet)]
+    struct Person {
+        tags: Vec<String>,
+        address: Address,
+        nickname: Option<String>,
+    }
+
+    let value = Person {
+        tags: vec!["a".to_string(), "b".to_string()],
+        address: Address {
+            city: "Lyon".to_string(),
+        },
+        nickname: None,
+    };
+    // Arrays are documents keyed by index
+    let tags = document(&[(0x02, "0", &string("a")), (0x02, "1", &string("b"))]);
+    let address = document(&[(0x02, "city", &string("Lyon"))]);
+    assert_eq!(
+        to_vec(&value),
+        document(&[
+            (0x04, "tags", &tags),
+            (0x03, "address", &address),
+            (0x0a, "nickname", &[]),
+        ])
+    );
+}
+
+#[test]
+fn maps() {
+    facet_testhelpers::setup();
+
+    let mut map = BTreeMap::new();
+    map.insert(1u16, true);
+    map.insert(2u16, false);
+    assert_eq!(
+        to_vec(&map),
+        document(&[(0x08, "1", &[1]), (0x08, "2", &[0])])
+    );
+}
+
+#[test]
+fn enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Event {
+        Started,
+        Renamed(String),
+        Moved { x: i32 },
+    }
+
+    #[derive(Facet)]
+    struct Log {
+        events: Vec<Event>,
+    }
+
+    let value = Log {
+        events: vec![
+            Event::Started,
+            Event::Renamed("b".to_string()),
+            Event::Moved { x: 4 },
+        ],
+    };
+    let renamed = document(&[(0x02, "Renamed", &string("b"))]);
+    let moved = document(&[(
+        0x03,
+        "Moved",
+        &document(&[(0x10, "x", &4i32.to_le_bytes())]),
+    )]);
+    let events = document(&[
+        (0x02, "0", &string("Started")),
+        (0x03, "1", &renamed),
+        (0x03, "2", &moved),
+    ]);
+    assert_eq!(to_vec(&value), document(&[(0x04, "events", &events)]));
+}
+
+#[test]
+fn only_documents_at_the_top_level() {
+    facet_testhelpers::setup();
+
+    let mut out = Vec::new();
+    assert!(to_writer(&5i32, &mut out).is_err());
+    assert!(to_writer(&vec![1i32], &mut out).is_err());
+}
+
+#[test]
+fn integers_beyond_int64() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Counter {
+        value: u64,
+    }
+
+    let mut out = Vec::new();
+    assert!(to_writer(&Counter { value: u64::MAX }, &mut out).is_err());
+}
@@ -0,0 +1,63 @@
+/// CBOR major types, the top three bits of every initial byte.
+/// Ref: <https://www.rfc-editor.org/rfc/rfc8949.html#section-3.1>
+/// Major type 0: an unsigned integer
+pub const CBOR_MAJOR_UNSIGNED: u8 = 0;
+/// Major type 1: a negative integer, -1 minus the argument
+pub const CBOR_MAJOR_NEGATIVE: u8 = 1;
+/// Major type 2: a byte string
+pub const CBOR_MAJOR_BYTES: u8 = 2;
+/// Major type 3: a UTF-8 text string
+pub const CBOR_MAJOR_TEXT: u8 = 3;
+/// Major type 4: an array of data items
+pub const CBOR_MAJOR_ARRAY: u8 = 4;
+/// Major type 5: a map of pairs of data items
+pub const CBOR_MAJOR_MAP: u8 = 5;
+/// Major type 6: a tagged data item
+pub const CBOR_MAJOR_TAG: u8 = 6;
+/// Major type 7: floats and simple values
+pub const CBOR_MAJOR_SIMPLE: u8 = 7;
+
+/// Additional information values, the low five bits of the initial byte.
+/// Ref: <https://www.rfc-editor.org/rfc/rfc8949.html#section-3>
+/// The argument follows in one byte
+pub const CBOR_INFO_U8: u8 = 24;
+/// The argument follows in two bytes
+pub const CBOR_INFO_U16: u8 = 25;
+/// The argument follows in four bytes
+pub const CBOR_INFO_U32: u8 = 26;
+/// The argument follows in eight bytes
+pub const CBOR_INFO_U64: u8 = 27;
+/// Indefinite length strings, arrays and maps, ended by [`CBOR_BREAK`]
+pub const CBOR_INFO_INDEFINITE: u8 = 31;
+
+/// Simple values and floats, as complete initial bytes.
+/// Ref: <https://www.rfc-editor.org/rfc/rfc8949.html#section-3.3>
+/// The simple value `false` (0xf4)
+pub const CBOR_FALSE: u8 = 0xf4;
+/// The simple value `true` (0xf5)
+pub const CBOR_TRUE: u8 = 0xf5;
+/// The simple value `null` (0xf6)
+pub const CBOR_NULL: u8 = 0xf6;
+/// The simple value `undefined` (0xf7)
+pub const CBOR_UNDEFINED: u8 = 0xf7;
+/// A half-precision float follows (0xf9)
+pub const CBOR_FLOAT16: u8 = 0xf9;
+/// A single-precision float follows (0xfa)
+pub const CBOR_FLOAT32: u8 = 0xfa;
+/// A double-precision float follows (0xfb)
+pub const CBOR_FLOAT64: u8 = 0xfb;
+/// Ends an indefinite-length item (0xff)
+pub const CBOR_BREAK: u8 = 0xff;
+
+/// Tags this crate reads or writes.
+/// Ref: <https://www.iana.org/assignments/cbor-tags/cbor-tags.xhtml>
+/// Tag 0: an RFC 3339 date/time string
+pub const CBOR_TAG_DATE_TIME: u64 = 0;
+/// Tag 1: seconds since the Unix epoch, as an integer or float
+pub const CBOR_TAG_EPOCH: u64 = 1;
+/// Tag 2: an unsigned bignum, as a big-endian byte string
+pub const CBOR_TAG_POSITIVE_BIGNUM: u64 = 2;
+/// Tag 3: a negative bignum, -1 minus the big-endian byte string
+pub const CBOR_TAG_NEGATIVE_BIGNUM: u64 = 3;
+/// Tag 37: a binary UUID, as a 16-byte byte string
+pub const CBOR_TAG_UUID: u64 = 37;
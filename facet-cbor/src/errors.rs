@@ -0,0 +1,84 @@
+use core::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Errors that can occur during CBOR decoding
+pub enum Error {
+    /// Encountered a CBOR type that doesn't match the expected type
+    UnexpectedType,
+    /// Not enough data available to decode a complete CBOR data item
+    InsufficientData,
+    /// The CBOR data is malformed or corrupted
+    InvalidData,
+    /// Bytes were left over after the data item
+    TrailingData,
+    /// Encountered a field name that isn't recognized, with `deny_unknown_fields`
+    UnknownField(String),
+    /// Required field is missing from the input
+    MissingField(String),
+    /// Encountered an enum variant name that isn't recognized
+    UnknownVariant(String),
+    /// An array with a different number of items than the tuple or
+    /// fixed-size array it is decoded into
+    LengthMismatch {
+        /// How many items the type has
+        expected: usize,
+        /// How many items the array has
+        got: usize,
+    },
+    /// Integer value is too large for the target type
+    IntegerOverflow,
+    /// Shape is not supported for deserialization
+    UnsupportedShape(String),
+    /// Type is not supported for deserialization
+    UnsupportedType(String),
+    /// Reflection error
+    ReflectError(facet_reflect::ReflectError),
+    /// The input went over one of the configured [`Limits`](facet_reflect::Limits)
+    LimitExceeded {
+        /// Which limit was exceeded
+        kind: facet_reflect::LimitKind,
+        /// The configured maximum
+        max: usize,
+        /// Where in the value being built the limit was hit, e.g. `$.items[3]`
+        path: String,
+    },
+}
+
+impl From<facet_reflect::ReflectError> for Error {
+    fn from(err: facet_reflect::ReflectError) -> Self {
+        Self::ReflectError(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnexpectedType => write!(f, "Unexpected CBOR type"),
+            Error::InsufficientData => write!(f, "Insufficient data to decode"),
+            Error::InvalidData => write!(f, "Invalid CBOR data"),
+            Error::TrailingData => write!(f, "Trailing data after the CBOR data item"),
+            Error::UnknownField(field) => write!(f, "Unknown field: {}", field),
+            Error::MissingField(field) => write!(f, "Missing required field: {}", field),
+            Error::UnknownVariant(variant) => write!(f, "Unknown enum variant: {}", variant),
+            Error::LengthMismatch { expected, got } => {
+                write!(f, "Expected an array of {} items, got {}", expected, got)
+            }
+            Error::IntegerOverflow => write!(f, "Integer value too large for target type"),
+            Error::UnsupportedShape(shape) => {
+                write!(f, "Unsupported shape for deserialization: {}", shape)
+            }
+            Error::UnsupportedType(typ) => {
+                write!(f, "Unsupported type for deserialization: {}", typ)
+            }
+            Error::ReflectError(err) => {
+                write!(f, "Reflection error: {}", err)
+            }
+            Error::LimitExceeded { kind, max, path } => {
+                write!(f, "Exceeded the {} limit of {} at {}", kind, max, path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,842 @@
+use std::borrow::Cow;
+
+use crate::constants::*;
+use crate::errors::Error as DecodeError;
+use crate::time::{epoch_to_rfc3339, split_epoch_float};
+
+use facet_core::{Def, Facet, Field, ScalarAffinity, Shape, StructKind};
+use facet_reflect::{HeapValue, LimitKind, Limits, ReflectError, Wip};
+use log::trace;
+
+/// Deserializes CBOR-encoded data into a type that implements `Facet`.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+/// use facet_cbor::from_slice;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct User {
+///     id: u64,
+///     username: String,
+/// }
+///
+/// // {"id": 42, "username": "user123"}
+/// let cbor_data = [
+///     0xa2, 0x62, 0x69, 0x64, 0x18, 0x2a, 0x68, 0x75,
+///     0x73, 0x65, 0x72, 0x6e, 0x61, 0x6d, 0x65, 0x67,
+///     0x75, 0x73, 0x65, 0x72, 0x31, 0x32, 0x33,
+/// ];
+///
+/// let user: User = from_slice(&cbor_data).unwrap();
+/// assert_eq!(user, User { id: 42, username: "user123".to_string() });
+/// ```
+pub fn from_slice<'input: 'facet, 'facet, T: Facet<'facet>>(
+    cbor: &'input [u8],
+) -> Result<T, DecodeError> {
+    from_slice_value(Wip::alloc::<T>()?, cbor)?
+        .materialize::<T>()
+        .map_err(|e| DecodeError::UnsupportedType(e.to_string()))
+}
+
+/// Deserializes CBOR-encoded data into a type that implements `Facet`,
+/// refusing input that goes over the given [`Limits`].
+///
+/// Length arguments are checked before anything is allocated, so a hostile
+/// array or string header can't make the decoder reserve gigabytes.
+/// Violations are reported as [`DecodeError::LimitExceeded`].
+///
+/// # Example
+/// ```
+/// use facet_cbor::{DecodeError, LimitKind, Limits, from_slice_with_limits};
+///
+/// // An array header claiming four billion items
+/// let data = [0x9a, 0xff, 0xff, 0xff, 0xff];
+/// let limits = Limits::default().max_collection_len(1000);
+///
+/// let err = from_slice_with_limits::<Vec<u64>>(&data, limits).unwrap_err();
+/// assert!(matches!(
+///     err,
+///     DecodeError::LimitExceeded { kind: LimitKind::CollectionLength, max: 1000, .. }
+/// ));
+/// ```
+pub fn from_slice_with_limits<'input: 'facet, 'facet, T: Facet<'facet>>(
+    cbor: &'input [u8],
+    limits: Limits,
+) -> Result<T, DecodeError> {
+    let wip = Wip::alloc::<T>()?.with_limits(limits)?;
+    decode(wip, cbor, limits)?
+        .materialize::<T>()
+        .map_err(|e| DecodeError::UnsupportedType(e.to_string()))
+}
+
+/// Deserializes CBOR-encoded data into a Facet value.
+///
+/// This function takes a CBOR byte array and populates a Wip object
+/// according to the shape description.
+///
+/// # CBOR Format
+/// This implementation follows RFC 8949:
+/// <https://www.rfc-editor.org/rfc/rfc8949.html>
+#[allow(clippy::needless_lifetimes)]
+pub fn from_slice_value<'mem>(
+    wip: Wip<'mem>,
+    cbor: &'mem [u8],
+) -> Result<HeapValue<'mem>, DecodeError> {
+    let limits = *wip.limits();
+    decode(wip, cbor, limits)
+}
+
+fn decode<'mem>(
+    wip: Wip<'mem>,
+    cbor: &'mem [u8],
+    limits: Limits,
+) -> Result<HeapValue<'mem>, DecodeError> {
+    let mut decoder = Decoder::new(cbor, limits);
+    let wip = decoder.deserialize_value(wip)?;
+    if decoder.offset != cbor.len() {
+        return Err(DecodeError::TrailingData);
+    }
+    wip.build()
+        .map_err(|e| DecodeError::UnsupportedType(e.to_string()))
+}
+
+/// Runs an operation on the `Wip`, reporting limit violations with the path
+/// they happened at.
+fn reflect<'mem>(
+    wip: Wip<'mem>,
+    op: impl FnOnce(Wip<'mem>) -> Result<Wip<'mem>, ReflectError>,
+) -> Result<Wip<'mem>, DecodeError> {
+    let path = wip.path();
+    op(wip).map_err(|e| reflect_error(e, || path))
+}
+
+/// Keeps limit violations typed and located; other reflection errors are
+/// passed through.
+fn reflect_error(e: ReflectError, path: impl FnOnce() -> String) -> DecodeError {
+    match e {
+        ReflectError::LimitExceeded { kind, max } => DecodeError::LimitExceeded {
+            kind,
+            max,
+            path: path(),
+        },
+        e => DecodeError::ReflectError(e),
+    }
+}
+
+/// Follows transparent wrappers and smart pointers down to the scalar they
+/// hold, if any.
+fn scalar_target(mut shape: &'static Shape) -> Option<&'static Shape> {
+    loop {
+        if let Def::Scalar(_) = shape.def {
+            return Some(shape);
+        }
+        shape = (shape.inner?)();
+    }
+}
+
+/// The initial byte of a data item, and its argument
+///
+/// Ref: <https://www.rfc-editor.org/rfc/rfc8949.html#section-3>
+#[derive(Debug, Clone, Copy)]
+struct Header {
+    major: u8,
+    info: u8,
+    argument: u64,
+}
+
+impl Header {
+    fn is_indefinite(&self) -> bool {
+        self.info == CBOR_INFO_INDEFINITE
+    }
+}
+
+struct Decoder<'input> {
+    input: &'input [u8],
+    offset: usize,
+    limits: Limits,
+}
+
+impl<'input> Decoder<'input> {
+    fn new(input: &'input [u8], limits: Limits) -> Self {
+        Decoder {
+            input,
+            offset: 0,
+            limits,
+        }
+    }
+
+    /// Checks `value` against one of the limits, before anything gets
+    /// allocated for it.
+    fn check_limit(&self, kind: LimitKind, value: usize, wip: &Wip) -> Result<(), DecodeError> {
+        self.limits
+            .check(kind, value)
+            .map_err(|e| reflect_error(e, || wip.path()))
+    }
+
+    /// Fails early if a length argument promises more items than there are
+    /// bytes left: every item takes at least `min_item_size` bytes.
+    fn check_remaining(&self, len: usize, min_item_size: usize) -> Result<(), DecodeError> {
+        if len.saturating_mul(min_item_size) > self.input.len() - self.offset {
+            return Err(DecodeError::InsufficientData);
+        }
+        Ok(())
+    }
+
+    /// Returns the next byte without consuming it.
+    fn peek_u8(&self) -> Result<u8, DecodeError> {
+        self.input
+            .get(self.offset)
+            .copied()
+            .ok_or(DecodeError::InsufficientData)
+    }
+
+    /// Returns the major type of the next data item without consuming it.
+    fn peek_major(&self) -> Result<u8, DecodeError> {
+        Ok(self.peek_u8()? >> 5)
+    }
+
+    /// Consumes `len` bytes of input.
+    fn take(&mut self, len: usize) -> Result<&'input [u8], DecodeError> {
+        if len > self.input.len() - self.offset {
+            return Err(DecodeError::InsufficientData);
+        }
+        let bytes = &self.input[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    /// Consumes `N` bytes of input, e.g. to read a big-endian number.
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    /// Decodes the initial byte of a data item and the argument that
+    /// follows it. Indefinite lengths have an argument of zero.
+    fn read_header(&mut self) -> Result<Header, DecodeError> {
+        let initial = self.take_array::<1>()?[0];
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        let argument = match info {
+            0..=23 => info as u64,
+            CBOR_INFO_U8 => self.take_array::<1>()?[0] as u64,
+            CBOR_INFO_U16 => u16::from_be_bytes(self.take_array()?) as u64,
+            CBOR_INFO_U32 => u32::from_be_bytes(self.take_array()?) as u64,
+            CBOR_INFO_U64 => u64::from_be_bytes(self.take_array()?),
+            CBOR_INFO_INDEFINITE => {
+                // Only strings, arrays and maps can be indefinite, and a
+                // lone "break" is only valid inside one of those
+                if !matches!(
+                    major,
+                    CBOR_MAJOR_BYTES | CBOR_MAJOR_TEXT | CBOR_MAJOR_ARRAY | CBOR_MAJOR_MAP
+                ) {
+                    return Err(DecodeError::InvalidData);
+                }
+                0
+            }
+            _ => return Err(DecodeError::InvalidData),
+        };
+
+        Ok(Header {
+            major,
+            info,
+            argument,
+        })
+    }
+
+    /// Converts a length argument to a `usize`.
+    fn length(argument: u64) -> Result<usize, DecodeError> {
+        usize::try_from(argument).map_err(|_| DecodeError::InsufficientData)
+    }
+
+    /// Consumes any tags in front of the next data item, returning the one
+    /// closest to it.
+    ///
+    /// Ref: <https://www.rfc-editor.org/rfc/rfc8949.html#section-3.4>
+    fn read_tags(&mut self) -> Result<Option<u64>, DecodeError> {
+        let mut tag = None;
+        while self.peek_major()? == CBOR_MAJOR_TAG {
+            tag = Some(self.read_header()?.argument);
+        }
+        Ok(tag)
+    }
+
+    /// Consumes a "break" if it's next, which ends indefinite-length items.
+    fn read_break(&mut self) -> Result<bool, DecodeError> {
+        if self.peek_u8()? == CBOR_BREAK {
+            self.offset += 1;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Tells whether an array or map of `len` items (`None` for indefinite)
+    /// has more after `index` of them were read.
+    fn has_next(&mut self, len: Option<usize>, index: usize) -> Result<bool, DecodeError> {
+        match len {
+            Some(len) => Ok(index < len),
+            None => Ok(!self.read_break()?),
+        }
+    }
+
+    /// Decodes the header of an array or map, returning its length, or
+    /// `None` if it is indefinite.
+    fn read_len(
+        &mut self,
+        major: u8,
+        min_item_size: usize,
+        wip: &Wip,
+    ) -> Result<Option<usize>, DecodeError> {
+        let header = self.read_header()?;
+        if header.major != major {
+            return Err(DecodeError::UnexpectedType);
+        }
+        if header.is_indefinite() {
+            return Ok(None);
+        }
+        let len = Self::length(header.argument)?;
+        self.check_limit(LimitKind::CollectionLength, len, wip)?;
+        self.check_remaining(len, min_item_size)?;
+        Ok(Some(len))
+    }
+
+    /// Decodes a byte or text string (per `major`), borrowing it from the
+    /// input unless it is split into indefinite-length chunks.
+    fn read_string_bytes(
+        &mut self,
+        major: u8,
+        wip: &Wip,
+    ) -> Result<Cow<'input, [u8]>, DecodeError> {
+        let header = self.read_header()?;
+        if header.major != major {
+            return Err(DecodeError::UnexpectedType);
+        }
+
+        if !header.is_indefinite() {
+            let len = Self::length(header.argument)?;
+            self.check_limit(LimitKind::StringLength, len, wip)?;
+            return Ok(Cow::Borrowed(self.take(len)?));
+        }
+
+        let mut joined = Vec::new();
+        while !self.read_break()? {
+            let chunk = self.read_header()?;
+            // Chunks are definite strings of the same type
+            if chunk.major != major || chunk.is_indefinite() {
+                return Err(DecodeError::InvalidData);
+            }
+            let len = Self::length(chunk.argument)?;
+            self.check_limit(
+                LimitKind::StringLength,
+                joined.len().saturating_add(len),
+                wip,
+            )?;
+            joined.extend_from_slice(self.take(len)?);
+        }
+        Ok(Cow::Owned(joined))
+    }
+
+    /// Decodes a CBOR byte string (major type 2).
+    fn read_bytes(&mut self, wip: &Wip) -> Result<Cow<'input, [u8]>, DecodeError> {
+        self.read_string_bytes(CBOR_MAJOR_BYTES, wip)
+    }
+
+    /// Decodes a CBOR text string (major type 3).
+    fn read_text(&mut self, wip: &Wip) -> Result<Cow<'input, str>, DecodeError> {
+        match self.read_string_bytes(CBOR_MAJOR_TEXT, wip)? {
+            Cow::Borrowed(bytes) => core::str::from_utf8(bytes)
+                .map(Cow::Borrowed)
+                .map_err(|_| DecodeError::InvalidData),
+            Cow::Owned(bytes) => String::from_utf8(bytes)
+                .map(Cow::Owned)
+                .map_err(|_| DecodeError::InvalidData),
+        }
+    }
+
+    /// Decodes an integer as its sign and argument: the value is the
+    /// argument for non-negative integers, and `-1 - argument` otherwise.
+    /// Bignums (tags 2 and 3) of up to 128 bits are accepted.
+    fn read_integer(&mut self, tag: Option<u64>, wip: &Wip) -> Result<(bool, u128), DecodeError> {
+        match tag {
+            Some(tag @ (CBOR_TAG_POSITIVE_BIGNUM | CBOR_TAG_NEGATIVE_BIGNUM)) => {
+                let bytes = self.read_bytes(wip)?;
+                let significant = bytes
+                    .iter()
+                    .position(|&b| b != 0)
+                    .map_or(&bytes[..0], |start| &bytes[start..]);
+                if significant.len() > 16 {
+                    return Err(DecodeError::IntegerOverflow);
+                }
+                let mut buf = [0u8; 16];
+                buf[16 - significant.len()..].copy_from_slice(significant);
+                Ok((tag == CBOR_TAG_NEGATIVE_BIGNUM, u128::from_be_bytes(buf)))
+            }
+            _ => {
+                let header = self.read_header()?;
+                match header.major {
+                    CBOR_MAJOR_UNSIGNED => Ok((false, header.argument as u128)),
+                    CBOR_MAJOR_NEGATIVE => Ok((true, header.argument as u128)),
+                    _ => Err(DecodeError::UnexpectedType),
+                }
+            }
+        }
+    }
+
+    /// Decodes an integer that fits an `i128`.
+    fn read_i128(&mut self, tag: Option<u64>, wip: &Wip) -> Result<i128, DecodeError> {
+        let (negative, argument) = self.read_integer(tag, wip)?;
+        let argument = i128::try_from(argument).map_err(|_| DecodeError::IntegerOverflow)?;
+        Ok(if negative { -1 - argument } else { argument })
+    }
+
+    /// Decodes a float of any precision, or an integer.
+    ///
+    /// Ref: <https://www.rfc-editor.org/rfc/rfc8949.html#section-3.3>
+    fn read_float(&mut self, tag: Option<u64>, wip: &Wip) -> Result<f64, DecodeError> {
+        match self.peek_u8()? {
+            CBOR_FLOAT16 => {
+                self.offset += 1;
+                Ok(f16_to_f64(u16::from_be_bytes(self.take_array()?)))
+            }
+            CBOR_FLOAT32 => {
+                self.offset += 1;
+                Ok(f32::from_be_bytes(self.take_array()?) as f64)
+            }
+            CBOR_FLOAT64 => {
+                self.offset += 1;
+                Ok(f64::from_be_bytes(self.take_array()?))
+            }
+            _ => Ok(self.read_i128(tag, wip)? as f64),
+        }
+    }
+
+    /// Decodes `false` or `true`.
+    fn read_bool(&mut self) -> Result<bool, DecodeError> {
+        match self.take_array::<1>()?[0] {
+            CBOR_FALSE => Ok(false),
+            CBOR_TRUE => Ok(true),
+            _ => Err(DecodeError::UnexpectedType),
+        }
+    }
+
+    /// Consumes `null` or `undefined` if it's next.
+    fn read_null(&mut self) -> Result<bool, DecodeError> {
+        if matches!(self.peek_u8()?, CBOR_NULL | CBOR_UNDEFINED) {
+            self.offset += 1;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Skips a CBOR data item of any type, tags included.
+    /// This is used when encountering unknown field names in a struct.
+    ///
+    /// `depth` is the nesting depth of the skipped value, which is checked
+    /// against the limits since skipping recurses too.
+    fn skip_value(&mut self, wip: &Wip, depth: usize) -> Result<(), DecodeError> {
+        self.check_limit(LimitKind::Depth, depth, wip)?;
+        let header = self.read_header()?;
+
+        match header.major {
+            CBOR_MAJOR_UNSIGNED | CBOR_MAJOR_NEGATIVE | CBOR_MAJOR_SIMPLE => Ok(()),
+            CBOR_MAJOR_BYTES | CBOR_MAJOR_TEXT => {
+                if header.is_indefinite() {
+                    while !self.read_break()? {
+                        self.skip_value(wip, depth + 1)?;
+                    }
+                } else {
+                    self.take(Self::length(header.argument)?)?;
+                }
+                Ok(())
+            }
+            CBOR_MAJOR_ARRAY | CBOR_MAJOR_MAP => {
+                let per_entry = if header.major == CBOR_MAJOR_MAP { 2 } else { 1 };
+                if header.is_indefinite() {
+                    while !self.read_break()? {
+                        for _ in 0..per_entry {
+                            self.skip_value(wip, depth + 1)?;
+                        }
+                    }
+                } else {
+                    let len = Self::length(header.argument)?;
+                    self.check_remaining(len, per_entry)?;
+                    for _ in 0..len * per_entry {
+                        self.skip_value(wip, depth + 1)?;
+                    }
+                }
+                Ok(())
+            }
+            // The tag's data item
+            _ => self.skip_value(wip, depth + 1),
+        }
+    }
+
+    fn deserialize_value(&mut self, wip: Wip<'input>) -> Result<Wip<'input>, DecodeError> {
+        let tag = self.read_tags()?;
+        self.deserialize_tagged(wip, tag)
+    }
+
+    /// Deserializes a data item whose tags were already read.
+    fn deserialize_tagged(
+        &mut self,
+        wip: Wip<'input>,
+        tag: Option<u64>,
+    ) -> Result<Wip<'input>, DecodeError> {
+        let shape = wip.shape();
+        trace!("Deserializing {:?}", shape);
+
+        let wip = match shape.def {
+            Def::Option(_) => {
+                trace!("Deserializing option");
+                if self.read_null()? {
+                    reflect(wip, Wip::put_default)?
+                } else {
+                    let wip = reflect(wip, Wip::push_some)?;
+                    let wip = self.deserialize_tagged(wip, tag)?;
+                    reflect(wip, Wip::pop)?
+                }
+            }
+            Def::Scalar(_) => self.deserialize_scalar(wip, shape, tag)?,
+            // Transparent wrappers and smart pointers, which can be built
+            // from the scalar they hold
+            _ if shape.inner.is_some() => match scalar_target(shape) {
+                Some(target) => self.deserialize_scalar(wip, target, tag)?,
+                None => return Err(DecodeError::UnsupportedShape(format!("{}", shape))),
+            },
+            Def::Struct(sd) if matches!(sd.kind, StructKind::Tuple | StructKind::TupleStruct) => {
+                trace!("Deserializing tuple");
+                self.deserialize_tuple(wip, sd.fields.len())?
+            }
+            Def::Struct(sd) => {
+                trace!("Deserializing struct");
+                self.deserialize_fields(wip, shape, sd.fields)?
+            }
+            Def::List(_)
+                if shape.is_type::<Vec<u8>>() && self.peek_major()? == CBOR_MAJOR_BYTES =>
+            {
+                trace!("Deserializing byte string");
+                let bytes = self.read_bytes(&wip)?.into_owned();
+                reflect(wip, |wip| wip.put(bytes))?
+            }
+            Def::Slice(_) if shape.is_type::<&[u8]>() => {
+                trace!("Deserializing borrowed byte string");
+                match self.read_bytes(&wip)? {
+                    Cow::Borrowed(bytes) => reflect(wip, |wip| wip.put(bytes))?,
+                    // Chunks of an indefinite-length string aren't contiguous
+                    Cow::Owned(_) => return Err(DecodeError::UnexpectedType),
+                }
+            }
+            Def::List(_) => {
+                trace!("Deserializing list");
+                let len = self.read_len(CBOR_MAJOR_ARRAY, 1, &wip)?;
+
+                let mut wip = reflect(wip, Wip::put_default)?;
+                let mut index = 0;
+                while self.has_next(len, index)? {
+                    index += 1;
+                    wip = reflect(wip, Wip::push)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::pop)?;
+                }
+                wip
+            }
+            Def::Map(_) => {
+                trace!("Deserializing map");
+                let len = self.read_len(CBOR_MAJOR_MAP, 2, &wip)?;
+
+                let mut wip = reflect(wip, Wip::put_default)?;
+                let mut index = 0;
+                while self.has_next(len, index)? {
+                    index += 1;
+                    wip = reflect(wip, Wip::push_map_key)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::push_map_value)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::pop)?;
+                }
+                wip
+            }
+            Def::Enum(_) => {
+                trace!("Deserializing enum");
+                self.deserialize_enum(wip)?
+            }
+            _ => {
+                return Err(DecodeError::UnsupportedShape(format!("{}", shape)));
+            }
+        };
+
+        Ok(wip)
+    }
+
+    /// Deserializes a scalar of the `target` shape into the current frame,
+    /// which is either that shape or a wrapper around it.
+    fn deserialize_scalar(
+        &mut self,
+        wip: Wip<'input>,
+        target: &'static Shape,
+        tag: Option<u64>,
+    ) -> Result<Wip<'input>, DecodeError> {
+        let Def::Scalar(sd) = target.def else {
+            return Err(DecodeError::UnsupportedShape(format!("{}", target)));
+        };
+        trace!("Deserializing scalar {}", target);
+
+        macro_rules! integers {
+            ($($ty:ty),*) => {
+                $(
+                    if target.is_type::<$ty>() {
+                        let n = self.read_i128(tag, &wip)?;
+                        let n = <$ty>::try_from(n).map_err(|_| DecodeError::IntegerOverflow)?;
+                        return reflect(wip, |wip| wip.put(n));
+                    }
+                )*
+            };
+        }
+        integers!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128, isize);
+
+        if target.is_type::<u128>() {
+            let (negative, n) = self.read_integer(tag, &wip)?;
+            if negative {
+                return Err(DecodeError::IntegerOverflow);
+            }
+            return reflect(wip, |wip| wip.put(n));
+        }
+        if target.is_type::<f64>() {
+            let n = self.read_float(tag, &wip)?;
+            return reflect(wip, |wip| wip.put(n));
+        }
+        if target.is_type::<f32>() {
+            let n = self.read_float(tag, &wip)? as f32;
+            return reflect(wip, |wip| wip.put(n));
+        }
+        if target.is_type::<bool>() {
+            let b = self.read_bool()?;
+            return reflect(wip, |wip| wip.put(b));
+        }
+        if target.is_type::<String>() {
+            let s = self.read_text(&wip)?.into_owned();
+            return reflect(wip, |wip| wip.put(s));
+        }
+        if target.is_type::<&str>() {
+            return match self.read_text(&wip)? {
+                Cow::Borrowed(s) => reflect(wip, |wip| wip.put(s)),
+                // Chunks of an indefinite-length string aren't contiguous
+                Cow::Owned(_) => Err(DecodeError::UnexpectedType),
+            };
+        }
+        if target.is_type::<Cow<'_, str>>() {
+            let s = self.read_text(&wip)?;
+            return reflect(wip, |wip| wip.put(s));
+        }
+        if target.is_type::<char>() {
+            let s = self.read_text(&wip)?;
+            let mut chars = s.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return Err(DecodeError::InvalidData);
+            };
+            return reflect(wip, |wip| wip.put(c));
+        }
+
+        match sd.affinity {
+            ScalarAffinity::Empty(_) => {
+                if !self.read_null()? {
+                    return Err(DecodeError::UnexpectedType);
+                }
+                reflect(wip, Wip::put_default)
+            }
+            ScalarAffinity::UUID(_) if self.peek_major()? == CBOR_MAJOR_BYTES => {
+                if tag.is_some_and(|tag| tag != CBOR_TAG_UUID) {
+                    return Err(DecodeError::UnexpectedType);
+                }
+                let bytes = self.read_bytes(&wip)?;
+                if bytes.len() != 16 {
+                    return Err(DecodeError::InvalidData);
+                }
+                let mut text = String::with_capacity(36);
+                for (i, byte) in bytes.iter().enumerate() {
+                    if matches!(i, 4 | 6 | 8 | 10) {
+                        text.push('-');
+                    }
+                    text.push_str(&format!("{:02x}", byte));
+                }
+                reflect(wip, |wip| wip.parse(&text))
+            }
+            ScalarAffinity::Time(_) if tag == Some(CBOR_TAG_EPOCH) => {
+                let text = match self.peek_major()? {
+                    CBOR_MAJOR_UNSIGNED | CBOR_MAJOR_NEGATIVE => {
+                        let secs = self.read_i128(None, &wip)?;
+                        let secs = i64::try_from(secs).map_err(|_| DecodeError::IntegerOverflow)?;
+                        epoch_to_rfc3339(secs, 0)
+                    }
+                    _ => {
+                        let value = self.read_float(None, &wip)?;
+                        let (secs, nanos) =
+                            split_epoch_float(value).ok_or(DecodeError::IntegerOverflow)?;
+                        epoch_to_rfc3339(secs, nanos)
+                    }
+                };
+                reflect(wip, |wip| wip.parse(&text))
+            }
+            // Numbers this crate doesn't know about, such as `NonZero`s, are
+            // parsed from their text form
+            ScalarAffinity::Number(_) if self.peek_major()? != CBOR_MAJOR_TEXT => {
+                let text = match self.peek_major()? {
+                    CBOR_MAJOR_SIMPLE => self.read_float(tag, &wip)?.to_string(),
+                    _ => match self.read_integer(tag, &wip)? {
+                        (false, n) => n.to_string(),
+                        (true, n) => {
+                            let magnitude = n.checked_add(1).ok_or(DecodeError::IntegerOverflow)?;
+                            format!("-{}", magnitude)
+                        }
+                    },
+                };
+                reflect(wip, |wip| wip.parse(&text))
+            }
+            _ => {
+                let text = self.read_text(&wip)?;
+                reflect(wip, |wip| wip.parse(&text))
+            }
+        }
+    }
+
+    /// Deserializes a map into the fields of a struct or struct variant.
+    fn deserialize_fields(
+        &mut self,
+        wip: Wip<'input>,
+        shape: &'static Shape,
+        fields: &'static [Field],
+    ) -> Result<Wip<'input>, DecodeError> {
+        // Each entry is at least a one-byte key and a one-byte value
+        let len = self.read_len(CBOR_MAJOR_MAP, 2, &wip)?;
+
+        let mut wip = wip;
+        let mut index = 0;
+        while self.has_next(len, index)? {
+            index += 1;
+            let key = self.read_text(&wip)?;
+            match wip.field_index(&key) {
+                Some(index) => {
+                    wip = reflect(wip, |wip| wip.field(index))?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::pop)?;
+                }
+                None if shape.has_deny_unknown_fields_attr() => {
+                    return Err(DecodeError::UnknownField(key.into_owned()));
+                }
+                None => {
+                    // Skip unknown field value
+                    self.skip_value(&wip, wip.frames_count() + 1)?;
+                    trace!("Skipping unknown field: {}", key);
+                }
+            }
+        }
+
+        for (index, field) in fields.iter().enumerate() {
+            if wip.is_field_set(index)? {
+                continue;
+            }
+            let Some(default_fn) = field.maybe_default_fn() else {
+                return Err(DecodeError::MissingField(field.name.to_string()));
+            };
+            wip = reflect(wip, |wip| wip.field(index))?;
+            wip = match default_fn {
+                Some(default_fn) => reflect(wip, |wip| wip.put_from_fn(default_fn))?,
+                None => reflect(wip, Wip::put_default)?,
+            };
+            wip = reflect(wip, Wip::pop)?;
+        }
+        Ok(wip)
+    }
+
+    /// Deserializes an array into the fields of a tuple, tuple struct or
+    /// tuple variant.
+    fn deserialize_tuple(
+        &mut self,
+        wip: Wip<'input>,
+        field_count: usize,
+    ) -> Result<Wip<'input>, DecodeError> {
+        let len = self.read_len(CBOR_MAJOR_ARRAY, 1, &wip)?;
+
+        let mut wip = wip;
+        let mut index = 0;
+        while self.has_next(len, index)? {
+            if index >= field_count {
+                return Err(DecodeError::LengthMismatch {
+                    expected: field_count,
+                    got: len.unwrap_or(index + 1),
+                });
+            }
+            wip = reflect(wip, |wip| wip.field(index))?;
+            wip = self.deserialize_value(wip)?;
+            wip = reflect(wip, Wip::pop)?;
+            index += 1;
+        }
+
+        if index != field_count {
+            return Err(DecodeError::LengthMismatch {
+                expected: field_count,
+                got: index,
+            });
+        }
+        Ok(wip)
+    }
+
+    /// Deserializes an enum: unit variants are their name, others a
+    /// single-entry map from their name to their fields.
+    fn deserialize_enum(&mut self, wip: Wip<'input>) -> Result<Wip<'input>, DecodeError> {
+        if self.peek_major()? == CBOR_MAJOR_TEXT {
+            let name = self.read_text(&wip)?;
+            let Some((index, _)) = wip.find_variant(&name) else {
+                return Err(DecodeError::UnknownVariant(name.into_owned()));
+            };
+            return reflect(wip, |wip| wip.variant(index));
+        }
+
+        let len = self.read_len(CBOR_MAJOR_MAP, 2, &wip)?;
+        if len.is_some_and(|len| len != 1) {
+            return Err(DecodeError::InvalidData);
+        }
+
+        let name = self.read_text(&wip)?;
+        let Some((index, variant)) = wip.find_variant(&name) else {
+            return Err(DecodeError::UnknownVariant(name.into_owned()));
+        };
+        let mut wip = reflect(wip, |wip| wip.variant(index))?;
+
+        let shape = wip.shape();
+        let fields = variant.data.fields;
+        wip = match variant.data.kind {
+            _ if fields.is_empty() => {
+                self.skip_value(&wip, wip.frames_count() + 1)?;
+                wip
+            }
+            StructKind::Struct => self.deserialize_fields(wip, shape, fields)?,
+            _ if fields.len() == 1 => {
+                let wip = reflect(wip, |wip| wip.field(0))?;
+                let wip = self.deserialize_value(wip)?;
+                reflect(wip, Wip::pop)?
+            }
+            _ => self.deserialize_tuple(wip, fields.len())?,
+        };
+
+        if len.is_none() && !self.read_break()? {
+            return Err(DecodeError::InvalidData);
+        }
+        Ok(wip)
+    }
+}
+
+/// Widens the bits of a half-precision float.
+fn f16_to_f64(half: u16) -> f64 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f64;
+
+    sign * match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1024.0 + mantissa) * 2f64.powi(exponent - 25),
+    }
+}
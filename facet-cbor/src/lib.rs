@@ -0,0 +1,19 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+mod errors;
+pub use errors::Error as DecodeError;
+
+pub use facet_reflect::{LimitKind, Limits};
+
+mod constants;
+pub use constants::*;
+
+mod from_cbor;
+pub use from_cbor::*;
+
+mod to_cbor;
+pub use to_cbor::*;
+
+mod time;
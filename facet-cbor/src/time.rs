@@ -0,0 +1,58 @@
+//! Conversions for the epoch-based date/time tag (tag 1), which is read into
+//! the same RFC 3339 text that tag 0 carries so that time types only need to
+//! know how to parse one representation.
+
+/// Formats seconds since the Unix epoch, plus a sub-second part in
+/// nanoseconds, as an RFC 3339 timestamp in UTC.
+pub(crate) fn epoch_to_rfc3339(secs: i64, nanos: u32) -> String {
+    let days = secs.div_euclid(86_400);
+    let secs_of_day = secs.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    let (hour, minute, second) = (
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60,
+        secs_of_day % 60,
+    );
+
+    let mut out = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year, month, day, hour, minute, second
+    );
+    if nanos != 0 {
+        let fraction = format!("{:09}", nanos);
+        out.push('.');
+        out.push_str(fraction.trim_end_matches('0'));
+    }
+    out.push('Z');
+    out
+}
+
+/// Splits a floating-point epoch timestamp into whole seconds and
+/// nanoseconds, or returns `None` if it doesn't fit an `i64`.
+pub(crate) fn split_epoch_float(value: f64) -> Option<(i64, u32)> {
+    if !value.is_finite() || value < i64::MIN as f64 || value >= i64::MAX as f64 {
+        return None;
+    }
+    let secs = value.floor();
+    let nanos = ((value - secs) * 1e9).round() as u32;
+    if nanos >= 1_000_000_000 {
+        return Some((secs as i64 + 1, 0));
+    }
+    Some((secs as i64, nanos))
+}
+
+/// Turns days since 1970-01-01 into a proleptic Gregorian (year, month, day).
+///
+/// Ref: <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
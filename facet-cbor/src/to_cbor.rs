@@ -0,0 +1,518 @@
+use crate::constants::*;
+
+use facet_core::{Def, Facet, ScalarAffinity, StructKind};
+use facet_reflect::Peek;
+use log::trace;
+use std::io::{self, Write};
+
+/// Options for [`to_vec_with_options`] and [`to_writer_with_options`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EncodeOptions {
+    deterministic: bool,
+}
+
+impl EncodeOptions {
+    /// Use the deterministic encoding of RFC 8949 §4.2: map entries are
+    /// sorted by the bytes of their encoded keys, and floats are written in
+    /// the shortest of the half, single and double precision forms that
+    /// keeps their value. Integers and lengths always take their shortest
+    /// form, deterministic or not.
+    ///
+    /// Ref: <https://www.rfc-editor.org/rfc/rfc8949.html#section-4.2>
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+}
+
+/// Serializes any Facet type to CBOR bytes
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Point {
+///     x: u8,
+///     y: i8,
+/// }
+///
+/// // {"x": 1, "y": -1}
+/// let bytes = facet_cbor::to_vec(&Point { x: 1, y: -1 });
+/// assert_eq!(bytes, [0xa2, 0x61, 0x78, 0x01, 0x61, 0x79, 0x20]);
+/// ```
+pub fn to_vec<'a, T: Facet<'a>>(value: &T) -> Vec<u8> {
+    to_vec_with_options(value, EncodeOptions::default())
+}
+
+/// Serializes any Facet type to CBOR bytes, with the given options
+///
+/// # Example
+/// ```
+/// use facet_cbor::{EncodeOptions, to_vec_with_options};
+///
+/// let options = EncodeOptions::default().deterministic(true);
+/// // 1.5 fits a half-precision float exactly
+/// assert_eq!(to_vec_with_options(&1.5f64, options), [0xf9, 0x3e, 0x00]);
+/// ```
+pub fn to_vec_with_options<'a, T: Facet<'a>>(value: &T, options: EncodeOptions) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let peek = Peek::new(value);
+    serialize(peek, &mut buffer, options).unwrap();
+    buffer
+}
+
+/// Serializes any Facet type to a writer in CBOR format
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    to_writer_with_options(value, writer, EncodeOptions::default())
+}
+
+/// Serializes any Facet type to a writer in CBOR format, with the given
+/// options
+pub fn to_writer_with_options<'a, T: Facet<'a>, W: Write>(
+    value: &T,
+    writer: &mut W,
+    options: EncodeOptions,
+) -> io::Result<()> {
+    serialize(Peek::new(value), writer, options)
+}
+
+fn serialize<W: Write>(pv: Peek<'_, '_>, writer: &mut W, options: EncodeOptions) -> io::Result<()> {
+    let shape = pv.shape();
+
+    // UUIDs name a string as their inner type, but have a binary encoding
+    if let Def::Scalar(sd) = shape.def {
+        if matches!(sd.affinity, ScalarAffinity::UUID(_)) {
+            return serialize_scalar(pv, writer);
+        }
+    }
+
+    // Transparent wrappers are written as the value they wrap
+    if let Some(inner) = pv.borrow_inner() {
+        return serialize(inner, writer, options);
+    }
+
+    match shape.def {
+        Def::Scalar(_) => {
+            trace!("Serializing scalar");
+            if let Ok(value) = pv.get::<f32>() {
+                write_float(writer, *value as f64, true, options)
+            } else if let Ok(value) = pv.get::<f64>() {
+                write_float(writer, *value, false, options)
+            } else {
+                serialize_scalar(pv, writer)
+            }
+        }
+        Def::Struct(sd) if matches!(sd.kind, StructKind::Tuple | StructKind::TupleStruct) => {
+            trace!("Serializing tuple");
+            let ps = pv.into_struct().map_err(other)?;
+            let items: Vec<_> = ps.fields_for_serialize().map(|(_, item)| item).collect();
+            write_header(writer, CBOR_MAJOR_ARRAY, items.len() as u64)?;
+            for item in items {
+                serialize(item, writer, options)?;
+            }
+            Ok(())
+        }
+        Def::Struct(_) => {
+            trace!("Serializing struct");
+            let ps = pv.into_struct().map_err(other)?;
+            let entries = ps
+                .fields_for_serialize()
+                .map(|(field, value)| {
+                    let name = field.get_rename_attr().unwrap_or(field.name);
+                    (MapKey::Name(name), value)
+                })
+                .collect();
+            write_map(writer, entries, options)
+        }
+        Def::List(_) if shape.is_type::<Vec<u8>>() => {
+            let bytes = pv.get::<Vec<u8>>().map_err(other)?;
+            write_bytes(writer, bytes)
+        }
+        Def::Slice(_) if shape.is_type::<&[u8]>() => {
+            let bytes = pv.get::<&[u8]>().map_err(other)?;
+            write_bytes(writer, bytes)
+        }
+        Def::List(_) => {
+            trace!("Serializing list");
+            let list = pv.into_list().map_err(other)?;
+            write_header(writer, CBOR_MAJOR_ARRAY, list.len() as u64)?;
+            for item in list.iter() {
+                serialize(item, writer, options)?;
+            }
+            Ok(())
+        }
+        Def::Array(_) => {
+            trace!("Serializing array");
+            let array = pv.into_array().map_err(other)?;
+            write_header(writer, CBOR_MAJOR_ARRAY, array.len() as u64)?;
+            for item in array.iter() {
+                serialize(item, writer, options)?;
+            }
+            Ok(())
+        }
+        Def::Slice(_) => {
+            trace!("Serializing slice");
+            let slice = pv.into_slice().map_err(other)?;
+            write_header(writer, CBOR_MAJOR_ARRAY, slice.len() as u64)?;
+            for item in slice.iter() {
+                serialize(item, writer, options)?;
+            }
+            Ok(())
+        }
+        Def::Map(_) => {
+            trace!("Serializing map");
+            let map = pv.into_map().map_err(other)?;
+            let entries = map
+                .iter()
+                .map(|(key, value)| (MapKey::Value(key), value))
+                .collect();
+            write_map(writer, entries, options)
+        }
+        Def::Enum(_) => {
+            trace!("Serializing enum");
+            let pe = pv.into_enum().map_err(other)?;
+            let variant = pe.active_variant();
+
+            // Unit variants are their name, others a single-entry map from
+            // the name to the variant's fields, like facet-json does
+            if variant.data.fields.is_empty() {
+                return write_text(writer, variant.name);
+            }
+            write_header(writer, CBOR_MAJOR_MAP, 1)?;
+            write_text(writer, variant.name)?;
+
+            match variant.data.kind {
+                StructKind::Struct => {
+                    let entries = pe
+                        .fields_for_serialize()
+                        .map(|(field, value)| {
+                            let name = field.get_rename_attr().unwrap_or(field.name);
+                            (MapKey::Name(name), value)
+                        })
+                        .collect();
+                    write_map(writer, entries, options)
+                }
+                _ if variant.data.fields.len() == 1 => {
+                    let value = pe
+                        .field(0)
+                        .ok_or_else(|| other("Failed to access enum field"))?;
+                    serialize(value, writer, options)
+                }
+                _ => {
+                    let items: Vec<_> = pe.fields_for_serialize().map(|(_, item)| item).collect();
+                    write_header(writer, CBOR_MAJOR_ARRAY, items.len() as u64)?;
+                    for item in items {
+                        serialize(item, writer, options)?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+        Def::Option(_) => {
+            trace!("Serializing option");
+            let po = pv.into_option().map_err(other)?;
+            match po.value() {
+                Some(value) => serialize(value, writer, options),
+                None => writer.write_all(&[CBOR_NULL]),
+            }
+        }
+        Def::SmartPointer(_) => {
+            trace!("Serializing smart pointer");
+            let pointer = pv.into_smart_pointer().map_err(other)?;
+            let pointee = pointer
+                .borrow_inner()
+                .ok_or_else(|| other(format!("Cannot borrow the pointee of {}", pv.shape())))?;
+            serialize(pointee, writer, options)
+        }
+        _ => Err(other(format!("Unsupported type: {}", shape))),
+    }
+}
+
+/// Serializes scalars other than floats, which depend on the options
+fn serialize_scalar<W: Write>(pv: Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
+    let shape = pv.shape();
+    let Def::Scalar(sd) = shape.def else {
+        unreachable!("serialize_scalar called on {}", shape)
+    };
+
+    if let Ok(value) = pv.get::<bool>() {
+        writer.write_all(&[if *value { CBOR_TRUE } else { CBOR_FALSE }])
+    } else if let Ok(value) = pv.get::<String>() {
+        write_text(writer, value)
+    } else if let Ok(value) = pv.get::<&str>() {
+        write_text(writer, value)
+    } else if let Ok(value) = pv.get::<std::borrow::Cow<'_, str>>() {
+        write_text(writer, value)
+    } else if let Ok(value) = pv.get::<char>() {
+        write_text(writer, value.encode_utf8(&mut [0; 4]))
+    } else if let Ok(value) = pv.get::<u128>() {
+        write_unsigned(writer, *value)
+    } else if let Some(value) = integer_value(pv) {
+        write_integer(writer, value)
+    } else {
+        match sd.affinity {
+            ScalarAffinity::Empty(_) => writer.write_all(&[CBOR_NULL]),
+            ScalarAffinity::UUID(_) if shape.vtable.display.is_some() => {
+                let bytes = uuid_bytes(&pv.to_string())
+                    .ok_or_else(|| other(format!("{} did not display as a UUID", shape)))?;
+                write_header(writer, CBOR_MAJOR_TAG, CBOR_TAG_UUID)?;
+                write_bytes(writer, &bytes)
+            }
+            ScalarAffinity::Time(_) if shape.vtable.display.is_some() => {
+                write_header(writer, CBOR_MAJOR_TAG, CBOR_TAG_DATE_TIME)?;
+                write_text(writer, &pv.to_string())
+            }
+            // Numbers this crate doesn't know about, such as `NonZero`s,
+            // are written from their text form
+            ScalarAffinity::Number(_) if shape.vtable.display.is_some() => {
+                let text = pv.to_string();
+                if let Ok(n) = text.parse::<i128>() {
+                    write_integer(writer, n)
+                } else if let Ok(n) = text.parse::<u128>() {
+                    write_unsigned(writer, n)
+                } else if let Ok(n) = text.parse::<f64>() {
+                    write_float(writer, n, false, EncodeOptions::default())
+                } else {
+                    Err(other(format!("{} did not display as a number", shape)))
+                }
+            }
+            _ if shape.vtable.display.is_some() => write_text(writer, &pv.to_string()),
+            _ => Err(other(format!("Unsupported scalar type: {}", shape))),
+        }
+    }
+}
+
+/// Reads any of the built-in integer types other than `u128`, which is the
+/// only one that doesn't fit an `i128`
+fn integer_value(pv: Peek<'_, '_>) -> Option<i128> {
+    macro_rules! try_integers {
+        ($($ty:ty),*) => {
+            $(
+                if let Ok(value) = pv.get::<$ty>() {
+                    return Some(*value as i128);
+                }
+            )*
+        };
+    }
+    try_integers!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128, isize);
+    None
+}
+
+/// Parses the hyphenated (or simple) text form of a UUID into its 16 bytes
+fn uuid_bytes(text: &str) -> Option<[u8; 16]> {
+    let hex: Vec<u8> = text.bytes().filter(|&b| b != b'-').collect();
+    if hex.len() != 32 {
+        return None;
+    }
+    let mut bytes = [0u8; 16];
+    for (byte, pair) in bytes.iter_mut().zip(hex.chunks(2)) {
+        let pair = std::str::from_utf8(pair).ok()?;
+        *byte = u8::from_str_radix(pair, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+/// A map key: either a field or variant name, or a value of a map's key type
+enum MapKey<'mem, 'facet> {
+    Name(&'static str),
+    Value(Peek<'mem, 'facet>),
+}
+
+impl MapKey<'_, '_> {
+    fn write<W: Write>(&self, writer: &mut W, options: EncodeOptions) -> io::Result<()> {
+        match self {
+            MapKey::Name(name) => write_text(writer, name),
+            MapKey::Value(key) => serialize(*key, writer, options),
+        }
+    }
+}
+
+/// Writes a map. In deterministic mode, entries are encoded first and then
+/// written in the order of their encoded keys.
+fn write_map<W: Write>(
+    writer: &mut W,
+    entries: Vec<(MapKey<'_, '_>, Peek<'_, '_>)>,
+    options: EncodeOptions,
+) -> io::Result<()> {
+    write_header(writer, CBOR_MAJOR_MAP, entries.len() as u64)?;
+
+    if !options.deterministic {
+        for (key, value) in entries {
+            key.write(writer, options)?;
+            serialize(value, writer, options)?;
+        }
+        return Ok(());
+    }
+
+    let mut encoded = Vec::with_capacity(entries.len());
+    for (key, value) in entries {
+        let mut key_bytes = Vec::new();
+        key.write(&mut key_bytes, options)?;
+        let mut value_bytes = Vec::new();
+        serialize(value, &mut value_bytes, options)?;
+        encoded.push((key_bytes, value_bytes));
+    }
+    encoded.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (key_bytes, value_bytes) in encoded {
+        writer.write_all(&key_bytes)?;
+        writer.write_all(&value_bytes)?;
+    }
+    Ok(())
+}
+
+/// Writes the initial byte of a data item, followed by its argument in the
+/// fewest bytes that hold it.
+///
+/// Ref: <https://www.rfc-editor.org/rfc/rfc8949.html#section-3>
+fn write_header<W: Write>(writer: &mut W, major: u8, argument: u64) -> io::Result<()> {
+    let major = major << 5;
+    match argument {
+        0..=23 => writer.write_all(&[major | argument as u8]),
+        24..=0xff => writer.write_all(&[major | CBOR_INFO_U8, argument as u8]),
+        0x100..=0xffff => {
+            writer.write_all(&[major | CBOR_INFO_U16])?;
+            writer.write_all(&(argument as u16).to_be_bytes())
+        }
+        0x1_0000..=0xffff_ffff => {
+            writer.write_all(&[major | CBOR_INFO_U32])?;
+            writer.write_all(&(argument as u32).to_be_bytes())
+        }
+        _ => {
+            writer.write_all(&[major | CBOR_INFO_U64])?;
+            writer.write_all(&argument.to_be_bytes())
+        }
+    }
+}
+
+fn write_text<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+    write_header(writer, CBOR_MAJOR_TEXT, s.len() as u64)?;
+    writer.write_all(s.as_bytes())
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    write_header(writer, CBOR_MAJOR_BYTES, bytes.len() as u64)?;
+    writer.write_all(bytes)
+}
+
+/// Writes an unsigned integer, as a bignum (tag 2) if it doesn't fit 64 bits
+fn write_unsigned<W: Write>(writer: &mut W, n: u128) -> io::Result<()> {
+    match u64::try_from(n) {
+        Ok(n) => write_header(writer, CBOR_MAJOR_UNSIGNED, n),
+        Err(_) => {
+            write_header(writer, CBOR_MAJOR_TAG, CBOR_TAG_POSITIVE_BIGNUM)?;
+            write_bignum_bytes(writer, n)
+        }
+    }
+}
+
+/// Writes a signed integer. Negative integers are stored as `-1 - n`, as a
+/// bignum (tag 3) if that doesn't fit 64 bits.
+fn write_integer<W: Write>(writer: &mut W, n: i128) -> io::Result<()> {
+    if n >= 0 {
+        return write_unsigned(writer, n as u128);
+    }
+    let argument = (-1 - n) as u128;
+    match u64::try_from(argument) {
+        Ok(argument) => write_header(writer, CBOR_MAJOR_NEGATIVE, argument),
+        Err(_) => {
+            write_header(writer, CBOR_MAJOR_TAG, CBOR_TAG_NEGATIVE_BIGNUM)?;
+            write_bignum_bytes(writer, argument)
+        }
+    }
+}
+
+/// Writes the big-endian bytes of a bignum, without leading zeroes
+fn write_bignum_bytes<W: Write>(writer: &mut W, n: u128) -> io::Result<()> {
+    let bytes = n.to_be_bytes();
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    write_bytes(writer, &bytes[start..])
+}
+
+/// Writes a float. `single` floats keep their precision; in deterministic
+/// mode every float takes its shortest exact form instead.
+fn write_float<W: Write>(
+    writer: &mut W,
+    value: f64,
+    single: bool,
+    options: EncodeOptions,
+) -> io::Result<()> {
+    if !options.deterministic {
+        return if single {
+            writer.write_all(&[CBOR_FLOAT32])?;
+            writer.write_all(&(value as f32).to_be_bytes())
+        } else {
+            writer.write_all(&[CBOR_FLOAT64])?;
+            writer.write_all(&value.to_be_bytes())
+        };
+    }
+
+    if value.is_nan() {
+        // The canonical quiet NaN
+        return writer.write_all(&[CBOR_FLOAT16, 0x7e, 0x00]);
+    }
+    let narrow = value as f32;
+    if narrow as f64 != value {
+        writer.write_all(&[CBOR_FLOAT64])?;
+        return writer.write_all(&value.to_be_bytes());
+    }
+    match f32_to_f16_exact(narrow) {
+        Some(half) => {
+            writer.write_all(&[CBOR_FLOAT16])?;
+            writer.write_all(&half.to_be_bytes())
+        }
+        None => {
+            writer.write_all(&[CBOR_FLOAT32])?;
+            writer.write_all(&narrow.to_be_bytes())
+        }
+    }
+}
+
+/// Converts a (non-NaN) `f32` to the bits of a half-precision float, if that
+/// can be done without losing anything.
+fn f32_to_f16_exact(value: f32) -> Option<u16> {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if value.is_infinite() {
+        return Some(sign | 0x7c00);
+    }
+    if value == 0.0 {
+        return Some(sign);
+    }
+    if exponent == 0 {
+        // f32 subnormals are far below the smallest half
+        return None;
+    }
+
+    let unbiased = exponent - 127;
+    match unbiased {
+        // Normal halves keep 10 of the 23 mantissa bits
+        -14..=15 => {
+            if mantissa & 0x1fff != 0 {
+                return None;
+            }
+            Some(sign | (((unbiased + 15) as u16) << 10) | (mantissa >> 13) as u16)
+        }
+        // Subnormal halves are multiples of 2^-24
+        -24..=-15 => {
+            let full = mantissa | 0x80_0000;
+            let shift = -(unbiased + 1);
+            if full & ((1 << shift) - 1) != 0 {
+                return None;
+            }
+            Some(sign | (full >> shift) as u16)
+        }
+        _ => None,
+    }
+}
+
+fn other<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::Other, error)
+}
@@ -0,0 +1,239 @@
+use eyre::Result;
+use facet::Facet;
+use facet_cbor::{DecodeError, EncodeOptions, from_slice, to_vec, to_vec_with_options};
+use std::collections::HashMap;
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct TestStruct {
+        name: String,
+        age: u64,
+    }
+
+    let data = [
+        0xa2, // Map with 2 entries
+        0x64, // Text string of length 4
+        0x6e, 0x61, 0x6d, 0x65, // "name"
+        0x65, // Text string of length 5
+        0x41, 0x6c, 0x69, 0x63, 0x65, // "Alice"
+        0x63, // Text string of length 3
+        0x61, 0x67, 0x65, // "age"
+        0x18, 0x1e, // 30, as a one-byte argument
+    ];
+
+    let result: TestStruct = from_slice(&data)?;
+    assert_eq!(
+        result,
+        TestStruct {
+            name: "Alice".to_string(),
+            age: 30,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn scalars() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(from_slice::<u32>(&[0x1a, 0x00, 0x0f, 0x42, 0x40])?, 1000000);
+    assert_eq!(from_slice::<i16>(&[0x39, 0x03, 0xe7])?, -1000);
+    assert_eq!(
+        from_slice::<u128>(&[0xc2, 0x49, 0x01, 0, 0, 0, 0, 0, 0, 0, 0])?,
+        18446744073709551616
+    );
+    assert_eq!(from_slice::<f64>(&[0xf9, 0x3e, 0x00])?, 1.5);
+    assert_eq!(
+        from_slice::<f32>(&[0xfa, 0x47, 0xc3, 0x50, 0x00])?,
+        100000.0
+    );
+    assert_eq!(from_slice::<f64>(&[0x18, 0x64])?, 100.0);
+    assert!(from_slice::<bool>(&[0xf5])?);
+    assert_eq!(from_slice::<char>(&[0x62, 0xc3, 0xbc])?, '\u{fc}');
+    assert_eq!(from_slice::<Option<u8>>(&[0xf6])?, None);
+    assert_eq!(from_slice::<Option<u8>>(&[0x07])?, Some(7));
+
+    // Strings and bytes are borrowed from the input
+    let data = [0x64, 0x49, 0x45, 0x54, 0x46];
+    assert_eq!(from_slice::<&str>(&data)?, "IETF");
+    let data = [0x44, 0x01, 0x02, 0x03, 0x04];
+    assert_eq!(from_slice::<&[u8]>(&data)?, [1, 2, 3, 4]);
+    assert_eq!(from_slice::<Vec<u8>>(&data)?, vec![1, 2, 3, 4]);
+
+    // Tags that don't change the value are looked through:
+    // 0("2013-03-21T20:04:00Z")
+    let mut data = vec![0xc0, 0x74];
+    data.extend_from_slice(b"2013-03-21T20:04:00Z");
+    assert_eq!(from_slice::<String>(&data)?, "2013-03-21T20:04:00Z");
+
+    Ok(())
+}
+
+#[test]
+fn integer_overflow() {
+    facet_testhelpers::setup();
+
+    assert!(matches!(
+        from_slice::<u8>(&[0x19, 0x01, 0x00]),
+        Err(DecodeError::IntegerOverflow)
+    ));
+    assert!(matches!(
+        from_slice::<u32>(&[0x20]),
+        Err(DecodeError::IntegerOverflow)
+    ));
+}
+
+#[test]
+fn indefinite_lengths() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // [_ 1, 2, 3]
+    let list: Vec<u8> = from_slice(&[0x9f, 0x01, 0x02, 0x03, 0xff])?;
+    assert_eq!(list, vec![1, 2, 3]);
+
+    // (_ "strea", "ming")
+    let data = [
+        0x7f, 0x65, 0x73, 0x74, 0x72, 0x65, 0x61, 0x64, 0x6d, 0x69, 0x6e, 0x67, 0xff,
+    ];
+    assert_eq!(from_slice::<String>(&data)?, "streaming");
+
+    // {_ "a": 1, "b": [_ 2, 3]}
+    #[derive(Debug, PartialEq, Facet)]
+    struct Nested {
+        a: u8,
+        b: Vec<u8>,
+    }
+    let data = [
+        0xbf, 0x61, 0x61, 0x01, 0x61, 0x62, 0x9f, 0x02, 0x03, 0xff, 0xff,
+    ];
+    assert_eq!(
+        from_slice::<Nested>(&data)?,
+        Nested {
+            a: 1,
+            b: vec![2, 3]
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn unknown_missing_and_default_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Config {
+        port: u16,
+        #[facet(default)]
+        verbose: bool,
+    }
+
+    // {"port": 80, "extra": [1, {"x": null}]}
+    let data = [
+        0xa2, 0x64, 0x70, 0x6f, 0x72, 0x74, 0x18, 0x50, 0x65, 0x65, 0x78, 0x74, 0x72, 0x61, 0x82,
+        0x01, 0xa1, 0x61, 0x78, 0xf6,
+    ];
+    assert_eq!(
+        from_slice::<Config>(&data)?,
+        Config {
+            port: 80,
+            verbose: false
+        }
+    );
+
+    // {}
+    match from_slice::<Config>(&[0xa0]) {
+        Err(DecodeError::MissingField(field)) => assert_eq!(field, "port"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        port: u16,
+    }
+    match from_slice::<Strict>(&data) {
+        Err(DecodeError::UnknownField(field)) => assert_eq!(field, "extra"),
+        other => panic!("unexpected result: {other:?}"),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn malformed_input() {
+    facet_testhelpers::setup();
+
+    assert!(matches!(
+        from_slice::<u32>(&[0x1a, 0x00]),
+        Err(DecodeError::InsufficientData)
+    ));
+    assert!(matches!(
+        from_slice::<u8>(&[0x01, 0x02]),
+        Err(DecodeError::TrailingData)
+    ));
+    assert!(matches!(
+        from_slice::<(u8, u8)>(&[0x83, 0x01, 0x02, 0x03]),
+        Err(DecodeError::LengthMismatch {
+            expected: 2,
+            got: 3
+        })
+    ));
+    // A "break" outside of an indefinite-length item
+    assert!(matches!(
+        from_slice::<u8>(&[0xff]),
+        Err(DecodeError::InvalidData)
+    ));
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Event {
+        Started,
+        Progress(u8),
+        Moved(i32, i32),
+        Failed { code: i64, reason: Option<String> },
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Log {
+        id: uuid::Uuid,
+        events: Vec<Event>,
+        counts: HashMap<String, u32>,
+        payload: Vec<u8>,
+        ratio: f32,
+        pair: (u8, String),
+    }
+
+    let log = Log {
+        id: uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap(),
+        events: vec![
+            Event::Started,
+            Event::Progress(50),
+            Event::Moved(-3, 4),
+            Event::Failed {
+                code: -1,
+                reason: Some("timeout".to_string()),
+            },
+        ],
+        counts: HashMap::from([("a".to_string(), 1), ("b".to_string(), 70000)]),
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        ratio: 0.25,
+        pair: (9, "nine".to_string()),
+    };
+
+    assert_eq!(from_slice::<Log>(&to_vec(&log))?, log);
+
+    let deterministic = to_vec_with_options(&log, EncodeOptions::default().deterministic(true));
+    assert_eq!(from_slice::<Log>(&deterministic)?, log);
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use facet::Facet;
+use facet_cbor::{EncodeOptions, to_vec, to_vec_with_options, to_writer};
+use std::collections::BTreeMap;
+
+// Byte vectors from RFC 8949, Appendix A
+
+#[test]
+fn integers() {
+    facet_testhelpers::setup();
+
+    assert_eq!(to_vec(&0u8), [0x00]);
+    assert_eq!(to_vec(&23u8), [0x17]);
+    assert_eq!(to_vec(&24u8), [0x18, 0x18]);
+    assert_eq!(to_vec(&1000u16), [0x19, 0x03, 0xe8]);
+    assert_eq!(to_vec(&1000000u32), [0x1a, 0x00, 0x0f, 0x42, 0x40]);
+    assert_eq!(
+        to_vec(&u64::MAX),
+        [0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]
+    );
+    assert_eq!(to_vec(&-1i8), [0x20]);
+    assert_eq!(to_vec(&-100i32), [0x38, 0x63]);
+    assert_eq!(to_vec(&-1000i64), [0x39, 0x03, 0xe7]);
+
+    // Past 64 bits, integers become bignums
+    assert_eq!(
+        to_vec(&18446744073709551616u128),
+        [
+            0xc2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        ]
+    );
+    assert_eq!(
+        to_vec(&-18446744073709551617i128),
+        [
+            0xc3, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+        ]
+    );
+}
+
+#[test]
+fn floats() {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        to_vec(&1.1f64),
+        [0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a]
+    );
+    assert_eq!(to_vec(&100000.0f32), [0xfa, 0x47, 0xc3, 0x50, 0x00]);
+
+    let deterministic = EncodeOptions::default().deterministic(true);
+    let shortest = |value: f64| to_vec_with_options(&value, deterministic);
+    assert_eq!(shortest(0.0), [0xf9, 0x00, 0x00]);
+    assert_eq!(shortest(-0.0), [0xf9, 0x80, 0x00]);
+    assert_eq!(shortest(1.5), [0xf9, 0x3e, 0x00]);
+    assert_eq!(shortest(65504.0), [0xf9, 0x7b, 0xff]);
+    assert_eq!(shortest(5.960464477539063e-8), [0xf9, 0x00, 0x01]);
+    assert_eq!(shortest(-4.0), [0xf9, 0xc4, 0x00]);
+    assert_eq!(shortest(f64::INFINITY), [0xf9, 0x7c, 0x00]);
+    assert_eq!(shortest(f64::NAN), [0xf9, 0x7e, 0x00]);
+    assert_eq!(shortest(100000.0), [0xfa, 0x47, 0xc3, 0x50, 0x00]);
+    assert_eq!(
+        shortest(1.0e300),
+        [0xfb, 0x7e, 0x37, 0xe4, 0x3c, 0x88, 0x00, 0x75, 0x9c]
+    );
+}
+
+#[test]
+fn strings_bytes_and_simple_values() {
+    facet_testhelpers::setup();
+
+    assert_eq!(to_vec(&"IETF".to_string()), [0x64, 0x49, 0x45, 0x54, 0x46]);
+    assert_eq!(to_vec(&"\u{fc}"), [0x62, 0xc3, 0xbc]);
+    assert_eq!(to_vec(&'a'), [0x61, 0x61]);
+    assert_eq!(to_vec(&true), [0xf5]);
+    assert_eq!(to_vec(&()), [0xf6]);
+    assert_eq!(to_vec(&None::<u8>), [0xf6]);
+    assert_eq!(to_vec(&Some(1u8)), [0x01]);
+
+    // Bytes are byte strings, other lists are arrays
+    assert_eq!(to_vec(&vec![1u8, 2, 3, 4]), [0x44, 0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(to_vec(&&[1u8, 2][..]), [0x42, 0x01, 0x02]);
+    assert_eq!(to_vec(&vec![1u16, 2, 3]), [0x83, 0x01, 0x02, 0x03]);
+    assert_eq!(to_vec(&(1u8, "a")), [0x82, 0x01, 0x61, 0x61]);
+}
+
+#[test]
+fn structs_and_enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Renamed {
+        #[facet(rename = "n")]
+        name: String,
+    }
+    assert_eq!(
+        to_vec(&Renamed {
+            name: "x".to_string()
+        }),
+        [0xa1, 0x61, 0x6e, 0x61, 0x78]
+    );
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Shape {
+        Empty,
+        Circle(u8),
+        Line(u8, u8),
+        Rect { w: u8 },
+    }
+    // "Empty"
+    assert_eq!(to_vec(&Shape::Empty), [0x65, 0x45, 0x6d, 0x70, 0x74, 0x79]);
+    // {"Circle": 2}
+    assert_eq!(
+        to_vec(&Shape::Circle(2)),
+        [0xa1, 0x66, 0x43, 0x69, 0x72, 0x63, 0x6c, 0x65, 0x02]
+    );
+    // {"Line": [1, 2]}
+    assert_eq!(
+        to_vec(&Shape::Line(1, 2)),
+        [0xa1, 0x64, 0x4c, 0x69, 0x6e, 0x65, 0x82, 0x01, 0x02]
+    );
+    // {"Rect": {"w": 3}}
+    assert_eq!(
+        to_vec(&Shape::Rect { w: 3 }),
+        [0xa1, 0x64, 0x52, 0x65, 0x63, 0x74, 0xa1, 0x61, 0x77, 0x03]
+    );
+}
+
+#[test]
+fn deterministic_map_order() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Unsorted {
+        aa: u8,
+        b: u8,
+        a: u8,
+    }
+    let value = Unsorted { aa: 1, b: 2, a: 3 };
+
+    // Declaration order by default
+    assert_eq!(
+        to_vec(&value),
+        [
+            0xa3, 0x62, 0x61, 0x61, 0x01, 0x61, 0x62, 0x02, 0x61, 0x61, 0x03
+        ]
+    );
+
+    // Keys sorted by their encoding, so shorter keys come first
+    let options = EncodeOptions::default().deterministic(true);
+    assert_eq!(
+        to_vec_with_options(&value, options),
+        [
+            0xa3, 0x61, 0x61, 0x03, 0x61, 0x62, 0x02, 0x62, 0x61, 0x61, 0x01
+        ]
+    );
+
+    let mut map = BTreeMap::new();
+    map.insert(-1i8, "neg");
+    map.insert(10, "ten");
+    // 10 (0x0a) sorts before -1 (0x20)
+    let bytes = to_vec_with_options(&map, options);
+    assert_eq!(&bytes[..2], [0xa2, 0x0a]);
+}
+
+#[test]
+fn uuid_uses_tag_37() {
+    facet_testhelpers::setup();
+
+    let id = uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    let mut bytes = Vec::new();
+    to_writer(&id, &mut bytes).unwrap();
+
+    let mut expected = vec![0xd8, 0x25, 0x50];
+    expected.extend_from_slice(id.as_bytes());
+    assert_eq!(bytes, expected);
+}
@@ -8,7 +8,7 @@ unsafe impl Facet<'_> for std::path::PathBuf {
                     .affinity(ScalarAffinity::path().build())
                     .build(),
             ))
-            .vtable(&const { value_vtable!((), |f, _opts| write!(f, "PathBuf")) })
+            .vtable(&const { value_vtable!(std::path::PathBuf, |f, _opts| write!(f, "PathBuf")) })
             .build()
     };
 }
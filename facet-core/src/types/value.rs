@@ -134,7 +134,7 @@ pub type ParseFnTyped<T> =
 
 /// Error returned by [`ParseFn`]
 #[non_exhaustive]
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum ParseError {
     /// Generic error message
     Generic(&'static str),
@@ -157,6 +157,10 @@ impl core::error::Error for ParseError {}
 /// The `target` parameter has the correct layout and alignment, but points to
 /// uninitialized memory. If this function succeeds, it should return `Ok` with the
 /// same pointer wrapped in an [`PtrMut`]. If conversion fails, it returns `Err` with an error.
+///
+/// Unless it returns [`TryFromError::UnsupportedSourceShape`], the function
+/// takes ownership of the value at `source`, which the caller must not drop
+/// afterwards.
 pub type TryFromFn = for<'src, 'mem> unsafe fn(
     source: PtrConst<'src>,
     source_shape: &'static Shape,
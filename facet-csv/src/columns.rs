@@ -0,0 +1,87 @@
+use facet_core::{Def, Field, Shape, StructKind};
+
+use crate::CsvErrorKind;
+
+/// A column of a CSV file: a field holding a single cell, possibly inside
+/// nested structs.
+pub(crate) struct Column {
+    /// The field's name, or its `rename`, prefixed with the names of the
+    /// structs it is nested in: `address.city`
+    pub header: String,
+    /// Field indices from the row struct down to the column's field
+    pub path: Vec<usize>,
+    /// The column's field
+    pub field: &'static Field,
+}
+
+/// Lists the columns of a row type, depth-first, so that the columns of a
+/// nested struct are next to each other.
+pub(crate) fn columns(shape: &'static Shape) -> Result<Vec<Column>, CsvErrorKind> {
+    if !is_record(shape) {
+        return Err(CsvErrorKind::UnsupportedShape(format!(
+            "{} is not a struct with named fields",
+            shape
+        )));
+    }
+    let mut columns = Vec::new();
+    collect(shape, "", &mut Vec::new(), &mut columns)?;
+    Ok(columns)
+}
+
+fn collect(
+    shape: &'static Shape,
+    prefix: &str,
+    path: &mut Vec<usize>,
+    columns: &mut Vec<Column>,
+) -> Result<(), CsvErrorKind> {
+    let Def::Struct(sd) = shape.def else {
+        unreachable!("only records are collected")
+    };
+
+    for (index, field) in sd.fields.iter().enumerate() {
+        let name = field.get_rename_attr().unwrap_or(field.name);
+        let header = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+
+        path.push(index);
+        if is_cell(field.shape()) {
+            columns.push(Column {
+                header,
+                path: path.clone(),
+                field,
+            });
+        } else if is_record(field.shape()) {
+            collect(field.shape(), &header, path, columns)?;
+        } else {
+            return Err(CsvErrorKind::UnsupportedShape(format!(
+                "field {} of type {} doesn't fit in a cell",
+                header,
+                field.shape()
+            )));
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+/// Structs with named fields are flattened into columns
+fn is_record(shape: &'static Shape) -> bool {
+    match shape.def {
+        Def::Struct(sd) => sd.kind == StructKind::Struct && shape.inner.is_none(),
+        _ => false,
+    }
+}
+
+/// Scalars, options of those, unit-only enums and transparent wrappers fit
+/// in a single cell
+fn is_cell(shape: &'static Shape) -> bool {
+    match shape.def {
+        Def::Scalar(_) => true,
+        Def::Option(od) => is_cell(od.t()),
+        Def::Enum(ed) => ed.variants.iter().all(|v| v.data.fields.is_empty()),
+        _ => shape.inner.is_some_and(|inner| is_cell(inner())),
+    }
+}
@@ -0,0 +1,140 @@
+use core::fmt;
+
+use facet_core::Shape;
+use facet_reflect::ReflectError;
+
+/// An error while reading CSV, with where it happened.
+#[derive(Debug)]
+pub struct CsvError {
+    /// What went wrong
+    pub kind: CsvErrorKind,
+    /// The 1-based line of the input the row starts on, the header being
+    /// line 1. `None` for errors that aren't about the input, such as a row
+    /// type that can't be a CSV record.
+    pub line: Option<usize>,
+    /// The 1-based column of the cell at fault, if the error is about one
+    pub column: Option<usize>,
+    /// The header of that column
+    pub header: Option<String>,
+}
+
+impl CsvError {
+    pub(crate) fn new(kind: CsvErrorKind) -> Self {
+        Self {
+            kind,
+            line: None,
+            column: None,
+            header: None,
+        }
+    }
+
+    pub(crate) fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub(crate) fn at_column(mut self, column: usize, header: &str) -> Self {
+        self.column = Some(column);
+        self.header = Some(header.to_string());
+        self
+    }
+}
+
+impl From<CsvErrorKind> for CsvError {
+    fn from(kind: CsvErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(line) = self.line {
+            write!(f, " at line {}", line)?;
+        }
+        if let Some(column) = self.column {
+            write!(f, ", column {}", column)?;
+        }
+        if let Some(header) = &self.header {
+            write!(f, " ({})", header)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            CsvErrorKind::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// The different kinds of [`CsvError`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CsvErrorKind {
+    /// Reading the input failed
+    Io(std::io::Error),
+    /// The input is not valid UTF-8
+    InvalidUtf8,
+    /// The input ended inside a quoted cell
+    UnterminatedQuote,
+    /// A closing quote was followed by something other than a delimiter or a
+    /// line break
+    InvalidQuote,
+    /// A row with a different number of cells than the header
+    WrongCellCount {
+        /// How many cells the header has
+        expected: usize,
+        /// How many cells the row has
+        got: usize,
+    },
+    /// The header has no column for a field that is required
+    MissingColumn(String),
+    /// The header has a column no field matches, with `deny_unknown_fields`
+    UnknownColumn(String),
+    /// A cell that names no variant of the field's enum
+    UnknownVariant(String),
+    /// A cell that can't be parsed as the field's type
+    InvalidValue {
+        /// The cell's contents
+        value: String,
+        /// The type it was parsed as
+        shape: &'static Shape,
+    },
+    /// The row type can't be read or written as CSV: rows must be structs
+    /// whose fields are scalars, options, unit-only enums or nested structs
+    UnsupportedShape(String),
+    /// Reflection error
+    ReflectError(ReflectError),
+}
+
+impl From<ReflectError> for CsvErrorKind {
+    fn from(err: ReflectError) -> Self {
+        Self::ReflectError(err)
+    }
+}
+
+impl fmt::Display for CsvErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvErrorKind::Io(err) => write!(f, "I/O error: {}", err),
+            CsvErrorKind::InvalidUtf8 => write!(f, "Invalid UTF-8"),
+            CsvErrorKind::UnterminatedQuote => write!(f, "Unterminated quoted cell"),
+            CsvErrorKind::InvalidQuote => write!(f, "Unexpected character after closing quote"),
+            CsvErrorKind::WrongCellCount { expected, got } => {
+                write!(f, "Expected {} cells, got {}", expected, got)
+            }
+            CsvErrorKind::MissingColumn(column) => write!(f, "Missing column: {}", column),
+            CsvErrorKind::UnknownColumn(column) => write!(f, "Unknown column: {}", column),
+            CsvErrorKind::UnknownVariant(variant) => write!(f, "Unknown variant: {}", variant),
+            CsvErrorKind::InvalidValue { value, shape } => {
+                write!(f, "Invalid value for {}: {:?}", shape, value)
+            }
+            CsvErrorKind::UnsupportedShape(shape) => write!(f, "Unsupported shape: {}", shape),
+            CsvErrorKind::ReflectError(err) => write!(f, "Reflection error: {}", err),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+mod columns;
+
+mod error;
+pub use error::{CsvError, CsvErrorKind};
+
+mod options;
+pub use options::{CsvOptions, QuoteStyle};
+
+mod read;
+pub use read::*;
+
+mod write;
+pub use write::*;
@@ -0,0 +1,85 @@
+/// When the writer puts cells in quotes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum QuoteStyle {
+    /// Only cells that contain the delimiter, the quote character or a line
+    /// break
+    #[default]
+    Necessary,
+    /// Every cell, headers included
+    Always,
+    /// No cell. Quote characters are also read as ordinary characters.
+    Never,
+}
+
+/// Options for reading and writing CSV
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+/// use facet_csv::{CsvOptions, from_str_with_options};
+///
+/// #[derive(Debug, Facet)]
+/// struct Row {
+///     name: String,
+///     score: u32,
+/// }
+///
+/// let options = CsvOptions::default().delimiter(b';');
+/// let rows: Vec<Row> = from_str_with_options("name;score\nann;3\n", options).unwrap();
+/// assert_eq!(rows[0].score, 3);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CsvOptions {
+    pub(crate) delimiter: u8,
+    pub(crate) quote: u8,
+    pub(crate) quote_style: QuoteStyle,
+    pub(crate) has_headers: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            quote_style: QuoteStyle::Necessary,
+            has_headers: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Sets the ASCII character that separates cells, `,` by default
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets the ASCII character that quotes cells, `"` by default. Inside a
+    /// quoted cell, it is escaped by doubling it.
+    pub fn quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Sets when cells are quoted, [`QuoteStyle::Necessary`] by default
+    pub fn quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    /// Sets whether the first row is a header, `true` by default. Without a
+    /// header, cells are matched to fields in declaration order.
+    pub fn has_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// The quote character, unless quoting is turned off
+    pub(crate) fn quote_char(&self) -> Option<char> {
+        match self.quote_style {
+            QuoteStyle::Never => None,
+            _ => Some(self.quote as char),
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use std::io::{self, BufRead};
+use std::marker::PhantomData;
+
+use facet_core::{Def, DefaultInPlaceFn, Facet};
+use facet_reflect::{ReflectError, Wip};
+use log::trace;
+
+use crate::columns::{Column, columns};
+use crate::{CsvError, CsvErrorKind, CsvOptions};
+
+/// Deserializes CSV text into a list of records.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Address {
+///     city: String,
+///     zip: String,
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Customer {
+///     name: String,
+///     address: Address,
+/// }
+///
+/// let csv = "name,address.city,address.zip\n\"Doe, Jane\",Lyon,69001\n";
+/// let customers: Vec<Customer> = facet_csv::from_str(csv).unwrap();
+/// assert_eq!(customers[0].name, "Doe, Jane");
+/// assert_eq!(customers[0].address.city, "Lyon");
+/// ```
+pub fn from_str<'facet, T: Facet<'facet>>(csv: &str) -> Result<Vec<T>, CsvError> {
+    from_str_with_options(csv, CsvOptions::default())
+}
+
+/// Deserializes CSV text into a list of records, with the given options.
+pub fn from_str_with_options<'facet, T: Facet<'facet>>(
+    csv: &str,
+    options: CsvOptions,
+) -> Result<Vec<T>, CsvError> {
+    Reader::with_options(csv.as_bytes(), options).collect()
+}
+
+/// Where the value of a column comes from
+enum Source {
+    /// The cell at this index of each row
+    Cell(usize),
+    /// The field's default, for a column the header doesn't have
+    Default(Option<DefaultInPlaceFn>),
+}
+
+/// Reads records one row at a time.
+///
+/// Errors about a single row, such as a cell that doesn't parse, are
+/// returned in its place and reading goes on with the next row. Errors that
+/// leave the rest of the input unreadable end the iteration.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+/// use facet_csv::Reader;
+///
+/// #[derive(Debug, Facet)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let input = "x,y\n1,2\n3,oops\n5,6\n";
+/// let rows: Vec<_> = Reader::<_, Point>::new(input.as_bytes()).collect();
+/// assert_eq!(rows[0].as_ref().unwrap().y, 2);
+///
+/// let err = rows[1].as_ref().unwrap_err();
+/// assert_eq!((err.line, err.column), (Some(3), Some(2)));
+/// assert_eq!(rows[2].as_ref().unwrap().x, 5);
+/// ```
+pub struct Reader<R, T> {
+    input: R,
+    options: CsvOptions,
+    columns: Vec<Column>,
+    /// Set once the header was read
+    sources: Option<Vec<Source>>,
+    /// The header of each cell, for error messages
+    headers: Vec<String>,
+    /// The number of lines consumed so far
+    line: usize,
+    done: bool,
+    buf: String,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'facet, R: BufRead, T: Facet<'facet>> Reader<R, T> {
+    /// Creates a reader with the default options
+    pub fn new(input: R) -> Self {
+        Self::with_options(input, CsvOptions::default())
+    }
+
+    /// Creates a reader with the given options
+    pub fn with_options(input: R, options: CsvOptions) -> Self {
+        Self {
+            input,
+            options,
+            columns: Vec::new(),
+            sources: None,
+            headers: Vec::new(),
+            line: 0,
+            done: false,
+            buf: String::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Works out the columns of `T`, and which cell each one is read from.
+    fn start(&mut self) -> Result<(), CsvError> {
+        self.columns = columns(T::SHAPE)?;
+
+        if !self.options.has_headers {
+            self.headers = self.columns.iter().map(|c| c.header.clone()).collect();
+            self.sources = Some((0..self.columns.len()).map(Source::Cell).collect());
+            return Ok(());
+        }
+
+        let Some((line, mut headers)) = self.read_record()? else {
+            // An empty input has no rows
+            self.sources = Some(Vec::new());
+            return Ok(());
+        };
+        if let Some(first) = headers.first_mut() {
+            if first.starts_with('\u{feff}') {
+                first.remove(0);
+            }
+        }
+
+        let mut sources = Vec::with_capacity(self.columns.len());
+        for column in &self.columns {
+            let source = match headers.iter().position(|h| *h == column.header) {
+                Some(index) => Source::Cell(index),
+                None => match column.field.maybe_default_fn() {
+                    Some(default_fn) => Source::Default(default_fn),
+                    None if matches!(column.field.shape().def, Def::Option(_)) => {
+                        Source::Default(None)
+                    }
+                    None => {
+                        return Err(CsvError::new(CsvErrorKind::MissingColumn(
+                            column.header.clone(),
+                        ))
+                        .at_line(line));
+                    }
+                },
+            };
+            sources.push(source);
+        }
+
+        if T::SHAPE.has_deny_unknown_fields_attr() {
+            for (index, header) in headers.iter().enumerate() {
+                if !self.columns.iter().any(|c| c.header == *header) {
+                    return Err(CsvError::new(CsvErrorKind::UnknownColumn(header.clone()))
+                        .at_line(line)
+                        .at_column(index + 1, header));
+                }
+            }
+        }
+
+        self.headers = headers;
+        self.sources = Some(sources);
+        Ok(())
+    }
+
+    /// Reads the cells of the next record, and the line it starts on. Blank
+    /// lines are skipped, and quoted cells may span several lines.
+    fn read_record(&mut self) -> Result<Option<(usize, Vec<String>)>, CsvError> {
+        let quote = self.options.quote_char();
+        let delimiter = self.options.delimiter as char;
+
+        let mut cells = Vec::new();
+        let mut cell = String::new();
+        let mut in_quotes = false;
+        let mut after_quote = false;
+        let mut start = None;
+
+        loop {
+            let line = self.line + 1;
+            self.buf.clear();
+            let read = self.input.read_line(&mut self.buf).map_err(|e| {
+                let kind = match e.kind() {
+                    io::ErrorKind::InvalidData => CsvErrorKind::InvalidUtf8,
+                    _ => CsvErrorKind::Io(e),
+                };
+                CsvError::new(kind).at_line(line)
+            })?;
+            if read == 0 {
+                return match start {
+                    Some(start) => {
+                        Err(CsvError::new(CsvErrorKind::UnterminatedQuote).at_line(start))
+                    }
+                    None => Ok(None),
+                };
+            }
+            self.line = line;
+
+            let first_line = match start {
+                Some(first_line) => first_line,
+                None if self.buf.trim_end_matches(['\r', '\n']).is_empty() => continue,
+                None => *start.insert(line),
+            };
+            self.split_cells(
+                delimiter,
+                quote,
+                &mut cells,
+                &mut cell,
+                &mut in_quotes,
+                &mut after_quote,
+            )
+            .map_err(|e| e.at_line(first_line))?;
+            if !in_quotes {
+                cells.push(cell);
+                return Ok(Some((first_line, cells)));
+            }
+        }
+    }
+
+    /// Splits the line in the buffer into cells, carrying the state of a
+    /// quoted cell over to the next line when the line ends inside one.
+    fn split_cells(
+        &self,
+        delimiter: char,
+        quote: Option<char>,
+        cells: &mut Vec<String>,
+        cell: &mut String,
+        in_quotes: &mut bool,
+        after_quote: &mut bool,
+    ) -> Result<(), CsvError> {
+        let mut chars = self.buf.chars().peekable();
+        while let Some(c) = chars.next() {
+            if *in_quotes {
+                if Some(c) != quote {
+                    cell.push(c);
+                } else if chars.peek() == Some(&c) {
+                    // A doubled quote is a literal one
+                    chars.next();
+                    cell.push(c);
+                } else {
+                    *in_quotes = false;
+                    *after_quote = true;
+                }
+            } else if c == delimiter {
+                cells.push(core::mem::take(cell));
+                *after_quote = false;
+            } else if c == '\n' || (c == '\r' && matches!(chars.peek(), Some('\n') | None)) {
+                break;
+            } else if *after_quote {
+                let column = cells.len() + 1;
+                return Err(CsvError {
+                    column: Some(column),
+                    ..CsvError::new(CsvErrorKind::InvalidQuote)
+                });
+            } else if Some(c) == quote && cell.is_empty() {
+                *in_quotes = true;
+            } else {
+                cell.push(c);
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds a record from the cells of a row.
+    fn build_row(&self, line: usize, cells: Vec<String>) -> Result<T, CsvError> {
+        trace!("Building a row from line {}", line);
+        let at_line = |e: ReflectError| CsvError::new(e.into()).at_line(line);
+
+        if cells.len() != self.headers.len() {
+            return Err(CsvError::new(CsvErrorKind::WrongCellCount {
+                expected: self.headers.len(),
+                got: cells.len(),
+            })
+            .at_line(line));
+        }
+        let sources = self.sources.as_deref().unwrap_or_default();
+
+        let mut wip = Wip::alloc::<T>().map_err(at_line)?;
+        // The nested structs currently entered, as field indices
+        let mut open: Vec<usize> = Vec::new();
+        for (column, source) in self.columns.iter().zip(sources) {
+            let (leaf, parents) = column.path.split_last().unwrap();
+            let shared = open.iter().zip(parents).take_while(|(a, b)| a == b).count();
+            while open.len() > shared {
+                wip = wip.pop().map_err(at_line)?;
+                open.pop();
+            }
+            for &index in &parents[shared..] {
+                wip = wip.field(index).map_err(at_line)?;
+                open.push(index);
+            }
+
+            wip = wip.field(*leaf).map_err(at_line)?;
+            wip = match source {
+                Source::Cell(index) => put_cell(wip, &cells[*index]).map_err(|kind| {
+                    CsvError::new(kind)
+                        .at_line(line)
+                        .at_column(index + 1, &self.headers[*index])
+                })?,
+                Source::Default(Some(default_fn)) => {
+                    wip.put_from_fn(*default_fn).map_err(at_line)?
+                }
+                Source::Default(None) => wip.put_default().map_err(at_line)?,
+            };
+            wip = wip.pop().map_err(at_line)?;
+        }
+        for _ in open {
+            wip = wip.pop().map_err(at_line)?;
+        }
+
+        wip.build()
+            .map_err(at_line)?
+            .materialize::<T>()
+            .map_err(at_line)
+    }
+}
+
+/// Parses a cell into the current frame: empty cells are `None` for
+/// options, enums are read by variant name, and everything else through
+/// its `FromStr` implementation.
+fn put_cell<'facet>(wip: Wip<'facet>, value: &str) -> Result<Wip<'facet>, CsvErrorKind> {
+    match wip.shape().def {
+        Def::Option(_) if value.is_empty() => Ok(wip.put_default()?),
+        Def::Option(_) => {
+            let wip = put_cell(wip.push_some()?, value)?;
+            Ok(wip.pop()?)
+        }
+        Def::Enum(_) => match wip.find_variant(value) {
+            Some((index, _)) => Ok(wip.variant(index)?),
+            None => Err(CsvErrorKind::UnknownVariant(value.to_string())),
+        },
+        _ => wip.parse(value).map_err(|e| match e {
+            ReflectError::ParseFailed { shape, .. } => CsvErrorKind::InvalidValue {
+                value: value.to_string(),
+                shape,
+            },
+            e => e.into(),
+        }),
+    }
+}
+
+impl<'facet, R: BufRead, T: Facet<'facet>> Iterator for Reader<R, T> {
+    type Item = Result<T, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.sources.is_none() {
+            if let Err(e) = self.start() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+
+        match self.read_record() {
+            Ok(Some((line, cells))) => Some(self.build_row(line, cells)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
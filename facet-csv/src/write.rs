@@ -0,0 +1,200 @@
+use std::io::{self, Write};
+use std::marker::PhantomData;
+
+use facet_core::{Def, Facet};
+use facet_reflect::Peek;
+
+use crate::columns::{Column, columns};
+use crate::{CsvOptions, QuoteStyle};
+
+/// Serializes records to CSV, with a header row
+///
+/// # Panics
+/// If the record type can't be written as CSV, see
+/// [`CsvErrorKind::UnsupportedShape`](crate::CsvErrorKind::UnsupportedShape).
+pub fn to_string<'a, T: Facet<'a>>(rows: &[T]) -> String {
+    to_string_with_options(rows, CsvOptions::default())
+}
+
+/// Serializes records to CSV, with the given options
+pub fn to_string_with_options<'a, T: Facet<'a>>(rows: &[T], options: CsvOptions) -> String {
+    let mut output = Vec::new();
+    to_writer_with_options(rows, &mut output, options).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// Serializes records to a writer in CSV format
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(rows: &[T], writer: &mut W) -> io::Result<()> {
+    to_writer_with_options(rows, writer, CsvOptions::default())
+}
+
+/// Serializes records to a writer in CSV format, with the given options
+pub fn to_writer_with_options<'a, T: Facet<'a>, W: Write>(
+    rows: &[T],
+    writer: &mut W,
+    options: CsvOptions,
+) -> io::Result<()> {
+    let mut csv = Writer::with_options(writer, options)?;
+    for row in rows {
+        csv.write_row(row)?;
+    }
+    csv.into_inner()?;
+    Ok(())
+}
+
+/// Writes records one row at a time.
+///
+/// The header is written along with the first row, or by
+/// [`Writer::into_inner`] if there were no rows.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+/// use facet_csv::Writer;
+///
+/// #[derive(Facet)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let mut writer = Writer::new(Vec::new()).unwrap();
+/// for i in 0..3 {
+///     writer.write_row(&Point { x: i, y: i * i }).unwrap();
+/// }
+/// let csv = writer.into_inner().unwrap();
+/// assert_eq!(csv, b"x,y\n0,0\n1,1\n2,4\n");
+/// ```
+pub struct Writer<W, T> {
+    writer: W,
+    options: CsvOptions,
+    columns: Vec<Column>,
+    header_written: bool,
+    marker: PhantomData<fn(&T)>,
+}
+
+impl<'a, W: Write, T: Facet<'a>> Writer<W, T> {
+    /// Creates a writer with the default options. Fails if the record type
+    /// can't be written as CSV.
+    pub fn new(writer: W) -> io::Result<Self> {
+        Self::with_options(writer, CsvOptions::default())
+    }
+
+    /// Creates a writer with the given options
+    pub fn with_options(writer: W, options: CsvOptions) -> io::Result<Self> {
+        let columns = columns(T::SHAPE).map_err(|e| io::Error::other(e.to_string()))?;
+        Ok(Self {
+            writer,
+            options,
+            columns,
+            header_written: false,
+            marker: PhantomData,
+        })
+    }
+
+    /// Writes a record as a row
+    pub fn write_row(&mut self, row: &T) -> io::Result<()> {
+        self.write_header()?;
+
+        let peek = Peek::new(row);
+        let mut cells = Vec::with_capacity(self.columns.len());
+        for column in &self.columns {
+            let mut value = peek;
+            for &index in &column.path {
+                value = value
+                    .into_struct()
+                    .map_err(|e| io::Error::other(e.to_string()))?
+                    .field(index)
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+            }
+            cells.push(cell_text(value)?);
+        }
+        self.write_record(&cells)
+    }
+
+    /// Writes the header if it wasn't yet, flushes, and returns the
+    /// underlying writer
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.write_header()?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        if self.header_written {
+            return Ok(());
+        }
+        self.header_written = true;
+        if !self.options.has_headers {
+            return Ok(());
+        }
+        let headers: Vec<String> = self.columns.iter().map(|c| c.header.clone()).collect();
+        self.write_record(&headers)
+    }
+
+    fn write_record(&mut self, cells: &[String]) -> io::Result<()> {
+        let delimiter = self.options.delimiter as char;
+        let quote = self.options.quote as char;
+
+        let mut line = String::new();
+        for (index, cell) in cells.iter().enumerate() {
+            if index > 0 {
+                line.push(delimiter);
+            }
+            let quoted = match self.options.quote_style {
+                QuoteStyle::Always => true,
+                QuoteStyle::Never => false,
+                // A lone empty cell would make a blank line, which readers skip
+                QuoteStyle::Necessary => {
+                    (cells.len() == 1 && cell.is_empty())
+                        || cell
+                            .chars()
+                            .any(|c| c == delimiter || c == quote || c == '\r' || c == '\n')
+                }
+            };
+            if quoted {
+                line.push(quote);
+                for c in cell.chars() {
+                    if c == quote {
+                        line.push(quote);
+                    }
+                    line.push(c);
+                }
+                line.push(quote);
+            } else {
+                line.push_str(cell);
+            }
+        }
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())
+    }
+}
+
+/// The text of a cell: `None` is empty, and enums are written as the name of
+/// their variant
+fn cell_text(peek: Peek<'_, '_>) -> io::Result<String> {
+    match peek.shape().def {
+        Def::Option(_) => match peek
+            .into_option()
+            .map_err(|e| io::Error::other(e.to_string()))?
+            .value()
+        {
+            Some(value) => cell_text(value),
+            None => Ok(String::new()),
+        },
+        Def::Enum(_) => Ok(peek
+            .into_enum()
+            .map_err(|e| io::Error::other(e.to_string()))?
+            .active_variant()
+            .name
+            .to_string()),
+        Def::Scalar(_) if peek.shape().vtable.display.is_some() => Ok(peek.to_string()),
+        _ => match peek.borrow_inner() {
+            Some(inner) => cell_text(inner),
+            None => Err(io::Error::other(format!(
+                "{} can't be written as a cell",
+                peek.shape()
+            ))),
+        },
+    }
+}
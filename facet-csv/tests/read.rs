@@ -0,0 +1,322 @@
+use eyre::Result;
+use facet::Facet;
+use facet_csv::{CsvErrorKind, CsvOptions, QuoteStyle, Reader, from_str, from_str_with_options};
+
+#[derive(Debug, PartialEq, Facet)]
+struct City {
+    name: String,
+    population: u64,
+}
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let cities: Vec<City> = from_str("name,population\nParis,2102650\nLyon,522250\n")?;
+    assert_eq!(
+        cities,
+        vec![
+            City {
+                name: "Paris".to_string(),
+                population: 2102650,
+            },
+            City {
+                name: "Lyon".to_string(),
+                population: 522250,
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn columns_in_any_order() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // Also without a trailing newline, with CRLF line endings and a BOM
+    let cities: Vec<City> = from_str("\u{feff}population,name\r\n3,Nowhere")?;
+    assert_eq!(cities[0].name, "Nowhere");
+    assert_eq!(cities[0].population, 3);
+
+    Ok(())
+}
+
+#[test]
+fn renamed_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Row {
+        #[facet(rename = "Full Name")]
+        name: String,
+        age: u8,
+    }
+
+    let rows: Vec<Row> = from_str("Full Name,age\nAnn,31\n")?;
+    assert_eq!(rows[0].name, "Ann");
+
+    Ok(())
+}
+
+#[test]
+fn optional_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Row {
+        id: u32,
+        score: Option<f64>,
+        comment: Option<String>,
+    }
+
+    let rows: Vec<Row> = from_str("id,score,comment\n1,,\n2,4.5,ok\n")?;
+    assert_eq!(
+        rows,
+        vec![
+            Row {
+                id: 1,
+                score: None,
+                comment: None,
+            },
+            Row {
+                id: 2,
+                score: Some(4.5),
+                comment: Some("ok".to_string()),
+            },
+        ]
+    );
+
+    // Missing optional columns are `None`
+    let rows: Vec<Row> = from_str("id\n7\n")?;
+    assert_eq!(rows[0].score, None);
+    assert_eq!(rows[0].comment, None);
+
+    Ok(())
+}
+
+#[test]
+fn default_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Row {
+        id: u32,
+        #[facet(default)]
+        tags: String,
+    }
+
+    let rows: Vec<Row> = from_str("id\n7\n")?;
+    assert_eq!(rows[0].tags, "");
+
+    Ok(())
+}
+
+#[test]
+fn nested_structs() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Segment {
+        name: String,
+        from: Point,
+        to: Point,
+        #[facet(rename = "len")]
+        length: f32,
+    }
+
+    let csv = "name,from.x,from.y,to.x,to.y,len\ndiag,0,0,3,4,5\n";
+    let segments: Vec<Segment> = from_str(csv)?;
+    assert_eq!(
+        segments,
+        vec![Segment {
+            name: "diag".to_string(),
+            from: Point { x: 0, y: 0 },
+            to: Point { x: 3, y: 4 },
+            length: 5.0,
+        }]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn enums_by_variant_name() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Status {
+        Active,
+        Retired,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Row {
+        id: u32,
+        status: Status,
+    }
+
+    let rows: Vec<Row> = from_str("id,status\n1,Active\n2,Retired\n")?;
+    assert_eq!(rows[1].status, Status::Retired);
+
+    let err = from_str::<Row>("id,status\n1,Gone\n").unwrap_err();
+    assert!(matches!(err.kind, CsvErrorKind::UnknownVariant(ref v) if v == "Gone"));
+
+    Ok(())
+}
+
+#[test]
+fn quoted_cells() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let csv = "name,population\n\"Paris, France\",1\n\"The \"\"Big\"\" One\",2\n\"Two\nLines\",3\n\"\",4\n";
+    let cities: Vec<City> = from_str(csv)?;
+    let names: Vec<&str> = cities.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["Paris, France", "The \"Big\" One", "Two\nLines", ""]
+    );
+
+    // A quote that doesn't open the cell is an ordinary character
+    let cities: Vec<City> = from_str("name,population\n5\" disk,1\n")?;
+    assert_eq!(cities[0].name, "5\" disk");
+
+    Ok(())
+}
+
+#[test]
+fn options() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = CsvOptions::default().delimiter(b'\t').quote(b'\'');
+    let cities: Vec<City> = from_str_with_options("name\tpopulation\n'Tab\there'\t1\n", options)?;
+    assert_eq!(cities[0].name, "Tab\there");
+
+    let options = CsvOptions::default().quote_style(QuoteStyle::Never);
+    let cities: Vec<City> = from_str_with_options("name,population\n\"Quoted\",1\n", options)?;
+    assert_eq!(cities[0].name, "\"Quoted\"");
+
+    // Without a header, cells are read in field order
+    let options = CsvOptions::default().has_headers(false);
+    let cities: Vec<City> = from_str_with_options("Paris,1\nLyon,2\n", options)?;
+    assert_eq!(cities[1].name, "Lyon");
+
+    Ok(())
+}
+
+#[test]
+fn empty_input() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert!(from_str::<City>("")?.is_empty());
+    assert!(from_str::<City>("name,population\n\n")?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn errors_point_at_the_cell() {
+    facet_testhelpers::setup();
+
+    let err = from_str::<City>("name,population\nParis,1\nLyon,many\n").unwrap_err();
+    assert!(matches!(
+        err.kind,
+        CsvErrorKind::InvalidValue { ref value, .. } if value == "many"
+    ));
+    assert_eq!(err.line, Some(3));
+    assert_eq!(err.column, Some(2));
+    assert_eq!(err.header.as_deref(), Some("population"));
+    assert_eq!(
+        err.to_string(),
+        "Invalid value for u64: \"many\" at line 3, column 2 (population)"
+    );
+
+    // Lines are counted from the start of the row, across quoted line breaks
+    let err = from_str::<City>("name,population\n\"A\nB\",1\nC,x\n").unwrap_err();
+    assert_eq!(err.line, Some(4));
+}
+
+#[test]
+fn malformed_input() {
+    facet_testhelpers::setup();
+
+    let err = from_str::<City>("name,population\nParis\n").unwrap_err();
+    assert!(matches!(
+        err.kind,
+        CsvErrorKind::WrongCellCount {
+            expected: 2,
+            got: 1
+        }
+    ));
+    assert_eq!(err.line, Some(2));
+
+    let err = from_str::<City>("name\nParis\n").unwrap_err();
+    assert!(matches!(err.kind, CsvErrorKind::MissingColumn(ref c) if c == "population"));
+    assert_eq!(err.line, Some(1));
+
+    let err = from_str::<City>("name,population\n\"Paris,1\n").unwrap_err();
+    assert!(matches!(err.kind, CsvErrorKind::UnterminatedQuote));
+    assert_eq!(err.line, Some(2));
+
+    let err = from_str::<City>("name,population\n\"Paris\"x,1\n").unwrap_err();
+    assert!(matches!(err.kind, CsvErrorKind::InvalidQuote));
+    assert_eq!(err.column, Some(1));
+}
+
+#[test]
+fn unknown_columns() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        name: String,
+    }
+
+    // Extra columns are ignored by default
+    let cities: Vec<City> = from_str("name,country,population\nParis,FR,1\n")?;
+    assert_eq!(cities[0].population, 1);
+
+    let err = from_str::<Strict>("name,country\nParis,FR\n").unwrap_err();
+    assert!(matches!(err.kind, CsvErrorKind::UnknownColumn(ref c) if c == "country"));
+    assert_eq!(err.column, Some(2));
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_shapes() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Row {
+        tags: Vec<String>,
+    }
+
+    let err = from_str::<Row>("tags\n\n").unwrap_err();
+    assert!(matches!(err.kind, CsvErrorKind::UnsupportedShape(_)));
+    assert_eq!(err.line, None);
+}
+
+#[test]
+fn streaming() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let input = "name,population\nParis,1\nLyon,?\nNice,3\n";
+    let mut reader = Reader::<_, City>::new(std::io::Cursor::new(input));
+
+    assert_eq!(reader.next().unwrap()?.name, "Paris");
+    // A bad row doesn't stop the reader
+    assert!(reader.next().unwrap().is_err());
+    assert_eq!(reader.next().unwrap()?.name, "Nice");
+    assert!(reader.next().is_none());
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use eyre::Result;
+use facet::Facet;
+use facet_csv::{
+    CsvOptions, QuoteStyle, Writer, from_str, to_string, to_string_with_options, to_writer,
+};
+
+#[derive(Debug, PartialEq, Facet)]
+struct City {
+    name: String,
+    population: u64,
+    #[facet(rename = "area_km2")]
+    area: Option<f64>,
+}
+
+fn cities() -> Vec<City> {
+    vec![
+        City {
+            name: "Paris".to_string(),
+            population: 2102650,
+            area: Some(105.4),
+        },
+        City {
+            name: "Lyon".to_string(),
+            population: 522250,
+            area: None,
+        },
+    ]
+}
+
+#[test]
+fn it_works() {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        to_string(&cities()),
+        "name,population,area_km2\nParis,2102650,105.4\nLyon,522250,\n"
+    );
+}
+
+#[test]
+fn header_without_rows() {
+    facet_testhelpers::setup();
+
+    assert_eq!(to_string::<City>(&[]), "name,population,area_km2\n");
+
+    let options = CsvOptions::default().has_headers(false);
+    assert_eq!(to_string_with_options::<City>(&[], options), "");
+    assert_eq!(
+        to_string_with_options(&cities()[..1], options),
+        "Paris,2102650,105.4\n"
+    );
+}
+
+#[test]
+fn quoting() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Note {
+        text: String,
+    }
+
+    let notes = vec![
+        Note {
+            text: "a, b".to_string(),
+        },
+        Note {
+            text: "say \"hi\"".to_string(),
+        },
+        Note {
+            text: "two\nlines".to_string(),
+        },
+        Note {
+            text: String::new(),
+        },
+        Note {
+            text: "plain".to_string(),
+        },
+    ];
+
+    let csv = to_string(&notes);
+    assert_eq!(
+        csv,
+        "text\n\"a, b\"\n\"say \"\"hi\"\"\"\n\"two\nlines\"\n\"\"\nplain\n"
+    );
+    assert_eq!(from_str::<Note>(&csv).unwrap(), notes);
+
+    let options = CsvOptions::default().quote_style(QuoteStyle::Always);
+    assert_eq!(
+        to_string_with_options(&notes[4..], options),
+        "\"text\"\n\"plain\"\n"
+    );
+
+    let options = CsvOptions::default().quote_style(QuoteStyle::Never);
+    assert_eq!(to_string_with_options(&notes[..1], options), "text\na, b\n");
+
+    let options = CsvOptions::default().delimiter(b';');
+    assert_eq!(to_string_with_options(&notes[..1], options), "text\na, b\n");
+}
+
+#[test]
+fn nested_structs_and_enums() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Unit {
+        Metric,
+        Imperial,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Size {
+        width: u32,
+        height: u32,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Item {
+        sku: String,
+        size: Size,
+        unit: Unit,
+    }
+
+    let items = vec![
+        Item {
+            sku: "A-1".to_string(),
+            size: Size {
+                width: 2,
+                height: 3,
+            },
+            unit: Unit::Metric,
+        },
+        Item {
+            sku: "B-2".to_string(),
+            size: Size {
+                width: 5,
+                height: 8,
+            },
+            unit: Unit::Imperial,
+        },
+    ];
+
+    let csv = to_string(&items);
+    assert_eq!(
+        csv,
+        "sku,size.width,size.height,unit\nA-1,2,3,Metric\nB-2,5,8,Imperial\n"
+    );
+    assert_eq!(from_str::<Item>(&csv)?, items);
+
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let csv = to_string(&cities());
+    assert_eq!(from_str::<City>(&csv)?, cities());
+
+    Ok(())
+}
+
+#[test]
+fn streaming() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let mut writer = Writer::new(Vec::new())?;
+    for city in cities() {
+        writer.write_row(&city)?;
+    }
+    let output = writer.into_inner()?;
+
+    let mut expected = Vec::new();
+    to_writer(&cities(), &mut expected)?;
+    assert_eq!(output, expected);
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_shapes() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Row {
+        tags: Vec<String>,
+    }
+
+    assert!(Writer::<_, Row>::new(Vec::new()).is_err());
+}
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+
+use facet_core::{Def, Facet, Field, Shape};
+use facet_reflect::{ReflectError, Wip};
+use log::trace;
+
+use crate::{EnvError, SEPARATOR, env_name, join, matches_name, parse_dotenv};
+
+/// Builds a value from the variables of the process environment whose name
+/// starts with `prefix` followed by `__`.
+///
+/// The rest of each name is a path to a field, with `__` between the
+/// segments: `APP__DB__HOST` sets the `host` field of the `db` field. See
+/// [`from_iter`] for how values are read.
+///
+/// An empty `prefix` takes every variable, with no prefix stripped.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Config {
+///     path: String,
+/// }
+///
+/// # unsafe { std::env::set_var("DOC__PATH", "/usr/bin") };
+/// let config: Config = facet_env::from_env("DOC").unwrap();
+/// assert_eq!(config.path, "/usr/bin");
+/// ```
+pub fn from_env<'facet, T: Facet<'facet>>(prefix: &str) -> Result<T, EnvError> {
+    let vars = std::env::vars_os().filter_map(|(key, value)| {
+        // Variables that aren't valid UTF-8 can't be meant for us
+        let key = key.into_string().ok()?;
+        let value = value.into_string().ok()?;
+        Some((key, value))
+    });
+    deserialize(prefix, strip_prefix(prefix, vars))
+}
+
+/// Builds a value from the variables of a `.env` file whose name starts
+/// with `prefix` followed by `__`, like [`from_env`] does from the process
+/// environment. See [`parse_dotenv`] for the file format.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Config {
+///     name: String,
+///     workers: u8,
+/// }
+///
+/// let dotenv = "
+/// # Deployed on Fridays
+/// APP__NAME='release bot'
+/// APP__WORKERS=4
+/// ";
+/// let config: Config = facet_env::from_dotenv(dotenv, "APP").unwrap();
+/// assert_eq!(config, Config { name: "release bot".to_string(), workers: 4 });
+/// ```
+pub fn from_dotenv<'facet, T: Facet<'facet>>(contents: &str, prefix: &str) -> Result<T, EnvError> {
+    let vars = parse_dotenv(contents)?;
+    deserialize(prefix, strip_prefix(prefix, vars))
+}
+
+/// Builds a value from `(name, value)` pairs, as [`from_env`] does once the
+/// prefix is stripped. This is handy to test configurations without
+/// touching the process environment.
+///
+/// - Segments of names, separated by `__`, match field names ignoring case
+///   and treating `-` as `_`. Names are the ones the fields are renamed to
+///   by `rename` or `rename_all`.
+/// - Values are parsed with the type's `parse` function, so anything that
+///   implements `FromStr` can be read.
+/// - `Option` fields are `None` when no variable sets them. Other fields
+///   without a variable take their `#[facet(default)]` if they have one.
+/// - Lists are either comma-separated, as in `PORTS=80,443`, or indexed,
+///   as in `PORTS__0=80` and `PORTS__1=443`. Indexed items can be structs,
+///   as in `SERVERS__0__HOST=a`, and are taken in index order. Indices
+///   start at 0 and can't repeat or skip a number.
+/// - Maps take their keys from the next segment, as in `LABELS__TEAM=core`.
+/// - Enums are set by variant name, with the fields of the variant nested
+///   under it, as in `STORAGE=s3` and `STORAGE__BUCKET=assets`.
+///
+/// Variables that match no field are ignored, unless the struct has
+/// `#[facet(deny_unknown_fields)]`.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Db {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Config {
+///     db: Db,
+///     replicas: Vec<String>,
+///     timeout: Option<u32>,
+/// }
+///
+/// let config: Config = facet_env::from_iter([
+///     ("DB__HOST", "localhost"),
+///     ("DB__PORT", "5432"),
+///     ("REPLICAS", "a.internal,b.internal"),
+/// ])
+/// .unwrap();
+/// assert_eq!(
+///     config,
+///     Config {
+///         db: Db { host: "localhost".to_string(), port: 5432 },
+///         replicas: vec!["a.internal".to_string(), "b.internal".to_string()],
+///         timeout: None,
+///     }
+/// );
+/// ```
+pub fn from_iter<'facet, T, I, K, V>(vars: I) -> Result<T, EnvError>
+where
+    T: Facet<'facet>,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    deserialize("", vars)
+}
+
+/// Keeps the variables that start with `prefix` and `__`, without them.
+fn strip_prefix(
+    prefix: &str,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> impl Iterator<Item = (String, String)> {
+    vars.into_iter().filter_map(move |(key, value)| {
+        if prefix.is_empty() {
+            return Some((key, value));
+        }
+        let name = key.strip_prefix(prefix)?.strip_prefix(SEPARATOR)?;
+        Some((name.to_string(), value))
+    })
+}
+
+fn deserialize<'facet, T, K, V>(
+    prefix: &str,
+    vars: impl IntoIterator<Item = (K, V)>,
+) -> Result<T, EnvError>
+where
+    T: Facet<'facet>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut root = Node::new(prefix.to_string());
+    for (name, value) in vars {
+        root.insert(name.as_ref(), value.as_ref());
+    }
+
+    let wip = Wip::alloc::<T>()?;
+    let wip = deserialize_node(wip, &root)?;
+    Ok(wip.build()?.materialize::<T>()?)
+}
+
+/// The variables under a path, as a tree of path segments
+struct Node {
+    /// The name of the variable that sets this node, prefix included
+    key: String,
+    value: Option<String>,
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn new(key: String) -> Self {
+        Self {
+            key,
+            value: None,
+            children: BTreeMap::new(),
+        }
+    }
+
+    fn leaf(key: String, value: &str) -> Self {
+        Self {
+            value: Some(value.to_string()),
+            ..Self::new(key)
+        }
+    }
+
+    fn insert(&mut self, name: &str, value: &str) {
+        let mut node = self;
+        for segment in name.split(SEPARATOR) {
+            let key = join(&node.key, segment);
+            node = node
+                .children
+                .entry(segment.to_string())
+                .or_insert_with(|| Node::new(key));
+        }
+        node.value = Some(value.to_string());
+    }
+}
+
+fn deserialize_node<'facet>(wip: Wip<'facet>, node: &Node) -> Result<Wip<'facet>, EnvError> {
+    let shape = wip.shape();
+    trace!("Deserializing {} from {}", shape, node.key);
+
+    match shape.def {
+        Def::Option(_) => {
+            let wip = wip.push_some()?;
+            let wip = deserialize_node(wip, node)?;
+            Ok(wip.pop()?)
+        }
+        // Transparent wrappers are parsed from a value like what they wrap
+        Def::Struct(sd) if shape.inner.is_none() || node.value.is_none() => {
+            if node.value.is_some() {
+                return Err(unexpected_value(node, shape));
+            }
+            deserialize_fields(wip, node, shape, sd.fields)
+        }
+        Def::Enum(ed) => {
+            let Some(value) = &node.value else {
+                return Err(EnvError::MissingVariable(node.key.clone()));
+            };
+            let Some(index) = ed.variants.iter().position(|v| matches_name(value, v.name)) else {
+                return Err(EnvError::UnknownVariant {
+                    key: node.key.clone(),
+                    value: value.clone(),
+                });
+            };
+            let wip = wip.variant(index)?;
+            deserialize_fields(wip, node, shape, ed.variants[index].data.fields)
+        }
+        Def::List(_) => {
+            let mut wip = wip.put_default()?;
+            if !node.children.is_empty() {
+                if node.value.is_some() {
+                    return Err(unexpected_value(node, shape));
+                }
+                let mut items = Vec::with_capacity(node.children.len());
+                for (segment, child) in &node.children {
+                    let index: usize = segment
+                        .parse()
+                        .map_err(|_| EnvError::InvalidIndex(child.key.clone()))?;
+                    items.push((index, child));
+                }
+                items.sort_by_key(|&(index, _)| index);
+                // `PORTS__0` and `PORTS__00` both set the first item, and
+                // `PORTS__5` after `PORTS__0` leaves four items unset
+                for (expected, &(index, child)) in items.iter().enumerate() {
+                    if index < expected {
+                        return Err(EnvError::DuplicateVariable(child.key.clone()));
+                    }
+                    if index > expected {
+                        return Err(EnvError::InvalidIndex(child.key.clone()));
+                    }
+                }
+                for (_, child) in items {
+                    wip = wip.push()?;
+                    wip = deserialize_node(wip, child)?;
+                    wip = wip.pop()?;
+                }
+            } else if let Some(value) = node.value.as_deref() {
+                // An empty value is an empty list, not a list of one empty
+                // item
+                let items = match value.trim() {
+                    "" => None,
+                    _ => Some(value.split(',')),
+                };
+                for item in items.into_iter().flatten() {
+                    wip = wip.push()?;
+                    wip = deserialize_node(wip, &Node::leaf(node.key.clone(), item.trim()))?;
+                    wip = wip.pop()?;
+                }
+            }
+            Ok(wip)
+        }
+        Def::Map(_) => {
+            if node.value.is_some() {
+                return Err(unexpected_value(node, shape));
+            }
+            let mut wip = wip.put_default()?;
+            for (segment, child) in &node.children {
+                wip = wip.push_map_key()?;
+                wip = parse(wip, &child.key, segment)?;
+                wip = wip.push_map_value()?;
+                wip = deserialize_node(wip, child)?;
+                wip = wip.pop()?;
+            }
+            Ok(wip)
+        }
+        _ => match &node.value {
+            Some(value) => parse(wip, &node.key, value),
+            None => Err(EnvError::MissingVariable(node.key.clone())),
+        },
+    }
+}
+
+/// Deserializes the fields of a struct or enum variant from the children
+/// of a node.
+fn deserialize_fields<'facet>(
+    mut wip: Wip<'facet>,
+    node: &Node,
+    shape: &'static Shape,
+    fields: &'static [Field],
+) -> Result<Wip<'facet>, EnvError> {
+    for (segment, child) in &node.children {
+        match fields.iter().position(|f| matches_name(segment, f.name)) {
+            Some(index) => {
+                if wip.is_field_set(index)? {
+                    return Err(EnvError::DuplicateVariable(child.key.clone()));
+                }
+                wip = wip.field(index)?;
+                wip = deserialize_node(wip, child)?;
+                wip = wip.pop()?;
+            }
+            None if shape.has_deny_unknown_fields_attr() => {
+                return Err(EnvError::UnknownVariable(child.key.clone()));
+            }
+            None => trace!("Ignoring unknown variable: {}", child.key),
+        }
+    }
+
+    for (index, field) in fields.iter().enumerate() {
+        if wip.is_field_set(index)? {
+            continue;
+        }
+        wip = wip.field(index)?;
+        wip = match field.maybe_default_fn() {
+            Some(Some(default_fn)) => wip.put_from_fn(default_fn)?,
+            Some(None) => wip.put_default()?,
+            None => match field.shape().def {
+                Def::Option(_) => wip.put_default()?,
+                // Nested structs may be made entirely of optional and
+                // defaulted fields, and otherwise report the field that's
+                // missing rather than the struct
+                Def::Struct(_) if field.shape().inner.is_none() => {
+                    let key = join(&node.key, &env_name(field.name));
+                    deserialize_node(wip, &Node::new(key))?
+                }
+                _ => {
+                    let key = join(&node.key, &env_name(field.name));
+                    return Err(EnvError::MissingVariable(key));
+                }
+            },
+        };
+        wip = wip.pop()?;
+    }
+    Ok(wip)
+}
+
+/// Parses a value with the `parse` function of the current frame's shape.
+fn parse<'facet>(wip: Wip<'facet>, key: &str, value: &str) -> Result<Wip<'facet>, EnvError> {
+    wip.parse(value).map_err(|e| match e {
+        ReflectError::ParseFailed { shape, .. } => EnvError::InvalidValue {
+            key: key.to_string(),
+            value: value.to_string(),
+            shape,
+        },
+        e => e.into(),
+    })
+}
+
+fn unexpected_value(node: &Node, shape: &'static Shape) -> EnvError {
+    EnvError::UnexpectedValue {
+        key: node.key.clone(),
+        shape,
+    }
+}
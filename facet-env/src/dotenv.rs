@@ -0,0 +1,139 @@
+use crate::EnvError;
+
+/// Parses the contents of a `.env` file into `(name, value)` pairs, in the
+/// order they appear.
+///
+/// Each line is a `NAME=value` assignment, optionally preceded by `export`.
+/// Blank lines and lines starting with `#` are skipped. Values are:
+///
+/// - unquoted, trimmed and ending at a ` #` comment,
+/// - in single quotes, taken literally,
+/// - or in double quotes, where `\n`, `\r`, `\t`, `\\` and `\"` are
+///   escapes and line breaks are part of the value.
+///
+/// Variables aren't expanded: `${HOME}` is read as is.
+///
+/// # Example
+/// ```
+/// let pairs = facet_env::parse_dotenv(
+///     r#"
+/// export APP__NAME=bot # the default
+/// APP__GREETING="hello\nworld"
+/// "#,
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     pairs,
+///     [
+///         ("APP__NAME".to_string(), "bot".to_string()),
+///         ("APP__GREETING".to_string(), "hello\nworld".to_string()),
+///     ]
+/// );
+///
+/// let err = facet_env::parse_dotenv("A=1\nB").unwrap_err();
+/// assert_eq!(err.to_string(), "Invalid .env file at line 2: expected `NAME=value`");
+/// ```
+pub fn parse_dotenv(contents: &str) -> Result<Vec<(String, String)>, EnvError> {
+    let mut pairs = Vec::new();
+    let mut lines = contents.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line_number = index + 1;
+        let invalid = |message| EnvError::InvalidDotenv {
+            line: line_number,
+            message,
+        };
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").map_or(line, str::trim_start);
+        let Some((name, rest)) = line.split_once('=') else {
+            return Err(invalid("expected `NAME=value`"));
+        };
+        let name = name.trim_end();
+        if !is_name(name) {
+            return Err(invalid("invalid variable name"));
+        }
+
+        let rest = rest.trim_start();
+        let value = if let Some(quoted) = rest.strip_prefix('\'') {
+            let Some((value, after)) = quoted.split_once('\'') else {
+                return Err(invalid("unclosed single quote"));
+            };
+            if !is_blank(after) {
+                return Err(invalid("unexpected characters after the closing quote"));
+            }
+            value.to_string()
+        } else if let Some(mut quoted) = rest.strip_prefix('"') {
+            // Double-quoted values may span lines, up to the closing quote
+            let mut value = String::new();
+            loop {
+                match unescape(quoted, &mut value) {
+                    Some(after) => {
+                        if !is_blank(after) {
+                            return Err(invalid("unexpected characters after the closing quote"));
+                        }
+                        break;
+                    }
+                    None => {
+                        value.push('\n');
+                        quoted = match lines.next() {
+                            Some((_, line)) => line,
+                            None => return Err(invalid("unclosed double quote")),
+                        };
+                    }
+                }
+            }
+            value
+        } else {
+            let end = if rest.starts_with('#') {
+                0
+            } else {
+                rest.find(" #").unwrap_or(rest.len())
+            };
+            rest[..end].trim_end().to_string()
+        };
+        pairs.push((name.to_string(), value));
+    }
+    Ok(pairs)
+}
+
+/// Unescapes the part of a double-quoted value on one line into `value`,
+/// returning what follows the closing quote, or `None` if the line doesn't
+/// have it.
+fn unescape<'a>(quoted: &'a str, value: &mut String) -> Option<&'a str> {
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some(&quoted[i + 1..]),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => value.push('\n'),
+                Some((_, 'r')) => value.push('\r'),
+                Some((_, 't')) => value.push('\t'),
+                Some((_, c @ ('\\' | '"'))) => value.push(c),
+                // Other backslashes are kept, as in Windows paths
+                Some((_, c)) => {
+                    value.push('\\');
+                    value.push(c);
+                }
+                None => value.push('\\'),
+            },
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.'))
+}
+
+/// Whether what follows a closing quote is only whitespace and a comment
+fn is_blank(after: &str) -> bool {
+    let after = after.trim_start();
+    after.is_empty() || after.starts_with('#')
+}
@@ -0,0 +1,93 @@
+use core::fmt;
+
+use facet_core::Shape;
+use facet_reflect::ReflectError;
+
+/// Errors that can occur while building a value from environment variables.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum EnvError {
+    /// No variable sets a field that has no default
+    MissingVariable(String),
+    /// A variable that matches no field, with `deny_unknown_fields`
+    UnknownVariable(String),
+    /// Two variables that set the same field, such as `DB__HOST` and `db__host`
+    DuplicateVariable(String),
+    /// A value that can't be parsed as the type of its field
+    InvalidValue {
+        /// The variable
+        key: String,
+        /// Its value
+        value: String,
+        /// The type it was parsed as
+        shape: &'static Shape,
+    },
+    /// A value that names no unit variant of the enum
+    UnknownVariant {
+        /// The variable
+        key: String,
+        /// Its value
+        value: String,
+    },
+    /// A variable that sets a struct or map, which can only be set through
+    /// nested variables
+    UnexpectedValue {
+        /// The variable
+        key: String,
+        /// The type it sets
+        shape: &'static Shape,
+    },
+    /// An indexed list variable whose index isn't a number, or skips over
+    /// an index no variable sets
+    InvalidIndex(String),
+    /// A line of a `.env` file that can't be parsed
+    InvalidDotenv {
+        /// The 1-based line
+        line: usize,
+        /// What's wrong with it
+        message: &'static str,
+    },
+    /// The shape is not supported for deserialization
+    UnsupportedShape(String),
+    /// Reflection error
+    ReflectError(ReflectError),
+}
+
+impl From<ReflectError> for EnvError {
+    fn from(err: ReflectError) -> Self {
+        EnvError::ReflectError(err)
+    }
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::MissingVariable(key) => write!(f, "Missing variable: {}", key),
+            EnvError::UnknownVariable(key) => write!(f, "Unknown variable: {}", key),
+            EnvError::DuplicateVariable(key) => {
+                write!(f, "Variable sets a field that is already set: {}", key)
+            }
+            EnvError::InvalidValue { key, value, shape } => {
+                write!(f, "Invalid value for {} ({}): {:?}", key, shape, value)
+            }
+            EnvError::UnknownVariant { key, value } => {
+                write!(f, "Unknown variant for {}: {:?}", key, value)
+            }
+            EnvError::UnexpectedValue { key, shape } => write!(
+                f,
+                "Variable {} can't be set directly, {} is set through nested variables",
+                key, shape
+            ),
+            EnvError::InvalidIndex(key) => write!(f, "Invalid list index in variable: {}", key),
+            EnvError::InvalidDotenv { line, message } => {
+                write!(f, "Invalid .env file at line {}: {}", line, message)
+            }
+            EnvError::UnsupportedShape(shape) => {
+                write!(f, "Unsupported shape for deserialization: {}", shape)
+            }
+            EnvError::ReflectError(err) => write!(f, "Reflection error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for EnvError {}
@@ -0,0 +1,47 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+mod error;
+pub use error::EnvError;
+
+mod dotenv;
+pub use dotenv::parse_dotenv;
+
+mod deserialize;
+pub use deserialize::*;
+
+/// What separates the prefix and the path segments of a variable name, as in
+/// `APP__DB__HOST`
+const SEPARATOR: &str = "__";
+
+/// The name of the variable that sets `name` under `parent`.
+fn join(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}{}{}", parent, SEPARATOR, name)
+    }
+}
+
+/// The conventional spelling of a field or variant name in a variable name:
+/// upper case, with `-` spelled `_`.
+fn env_name(name: &str) -> String {
+    name.chars().map(normalize).collect()
+}
+
+/// Whether a segment of a variable name stands for a field or variant
+/// name, ignoring case and whether words are separated by `-` or `_`.
+fn matches_name(segment: &str, name: &str) -> bool {
+    segment
+        .chars()
+        .map(normalize)
+        .eq(name.chars().map(normalize))
+}
+
+fn normalize(c: char) -> char {
+    match c {
+        '-' => '_',
+        c => c.to_ascii_uppercase(),
+    }
+}
@@ -0,0 +1,279 @@
+use eyre::Result;
+use facet::Facet;
+use facet_env::{EnvError, from_dotenv, from_env, from_iter};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Facet)]
+struct Db {
+    host: String,
+    port: u16,
+    #[facet(default = "pool_size")]
+    pool_size: u32,
+}
+
+fn pool_size() -> u32 {
+    8
+}
+
+#[derive(Debug, PartialEq, Facet)]
+struct Server {
+    name: String,
+    weight: Option<u8>,
+}
+
+#[derive(Debug, PartialEq, Facet)]
+#[repr(u8)]
+enum Storage {
+    Disk,
+    S3 { bucket: String },
+}
+
+#[derive(Debug, PartialEq, Facet)]
+struct Config {
+    db: Db,
+    peers: Vec<String>,
+    servers: Vec<Server>,
+    labels: HashMap<String, String>,
+    storage: Storage,
+    debug: bool,
+    timeout: Option<u32>,
+}
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let config: Config = from_iter([
+        ("DB__HOST", "localhost"),
+        ("DB__PORT", "5432"),
+        ("PEERS", "a, b,c"),
+        ("SERVERS__1__NAME", "second"),
+        ("SERVERS__0__NAME", "first"),
+        ("SERVERS__0__WEIGHT", "3"),
+        ("LABELS__team", "core"),
+        ("STORAGE", "s3"),
+        ("STORAGE__BUCKET", "assets"),
+        ("DEBUG", "true"),
+        ("UNRELATED", "ignored"),
+    ])?;
+    assert_eq!(
+        config,
+        Config {
+            db: Db {
+                host: "localhost".to_string(),
+                port: 5432,
+                pool_size: 8,
+            },
+            peers: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            servers: vec![
+                Server {
+                    name: "first".to_string(),
+                    weight: Some(3),
+                },
+                Server {
+                    name: "second".to_string(),
+                    weight: None,
+                },
+            ],
+            labels: HashMap::from([("team".to_string(), "core".to_string())]),
+            storage: Storage::S3 {
+                bucket: "assets".to_string()
+            },
+            debug: true,
+            timeout: None,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn lists() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Lists {
+        ports: Vec<u16>,
+        storages: Vec<Storage>,
+        empty: Vec<u16>,
+    }
+
+    let lists: Lists = from_iter([
+        ("PORTS__1", "443"),
+        ("PORTS__0", "80"),
+        ("STORAGES", "disk,Disk"),
+        ("EMPTY", ""),
+    ])?;
+    assert_eq!(
+        lists,
+        Lists {
+            ports: vec![80, 443],
+            storages: vec![Storage::Disk, Storage::Disk],
+            empty: vec![],
+        }
+    );
+
+    let err = from_iter::<Lists, _, _, _>([("PORTS__first", "80")]).unwrap_err();
+    assert!(matches!(err, EnvError::InvalidIndex(ref key) if key == "PORTS__first"));
+
+    let err = from_iter::<Lists, _, _, _>([("PORTS__0", "80"), ("PORTS__00", "81")]).unwrap_err();
+    assert!(matches!(err, EnvError::DuplicateVariable(_)));
+
+    let err = from_iter::<Lists, _, _, _>([("PORTS__0", "80"), ("PORTS__5", "81")]).unwrap_err();
+    assert!(matches!(err, EnvError::InvalidIndex(ref key) if key == "PORTS__5"));
+
+    let err = from_iter::<Lists, _, _, _>([("PORTS__1", "80")]).unwrap_err();
+    assert!(matches!(err, EnvError::InvalidIndex(ref key) if key == "PORTS__1"));
+    Ok(())
+}
+
+#[test]
+fn renamed_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(rename_all = "kebab-case")]
+    struct Limits {
+        max_conns: u32,
+        #[facet(rename = "burst")]
+        max_burst: u32,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(rename_all = "camelCase")]
+    struct Window {
+        idle_secs: u32,
+    }
+
+    let limits: Limits = from_iter([("MAX_CONNS", "10"), ("BURST", "20")])?;
+    assert_eq!(
+        limits,
+        Limits {
+            max_conns: 10,
+            max_burst: 20,
+        }
+    );
+
+    let window: Window = from_iter([("idleSecs", "30")])?;
+    assert_eq!(window, Window { idle_secs: 30 });
+    let window: Window = from_iter([("IDLESECS", "30")])?;
+    assert_eq!(window, Window { idle_secs: 30 });
+    Ok(())
+}
+
+#[test]
+fn optional_structs() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Tls {
+        cert: String,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Listener {
+        tls: Option<Tls>,
+        server: Server,
+    }
+
+    let listener: Listener = from_iter([("SERVER__NAME", "edge")])?;
+    assert_eq!(listener.tls, None);
+
+    let listener: Listener = from_iter([("SERVER__NAME", "edge"), ("TLS__CERT", "a.pem")])?;
+    assert_eq!(
+        listener.tls,
+        Some(Tls {
+            cert: "a.pem".to_string()
+        })
+    );
+
+    // Nested structs report the field that's missing
+    let err = from_iter::<Listener, _, _, _>([("TLS__CERT", "a.pem")]).unwrap_err();
+    assert!(matches!(err, EnvError::MissingVariable(ref key) if key == "SERVER__NAME"));
+    Ok(())
+}
+
+#[test]
+fn errors() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        port: u16,
+    }
+
+    let err = from_iter::<Strict, _, _, _>([("PORT", "80"), ("PROT", "81")]).unwrap_err();
+    assert!(matches!(err, EnvError::UnknownVariable(ref key) if key == "PROT"));
+
+    let err = from_iter::<Strict, _, _, _>([("PORT", "http")]).unwrap_err();
+    assert!(matches!(
+        err,
+        EnvError::InvalidValue { ref key, ref value, .. } if key == "PORT" && value == "http"
+    ));
+
+    let err = from_iter::<Strict, _, _, _>([("PORT", "80"), ("port", "81")]).unwrap_err();
+    assert!(matches!(err, EnvError::DuplicateVariable(_)));
+
+    let err = from_iter::<Strict, _, _, _>([("PORTS", "80")]).unwrap_err();
+    assert!(matches!(err, EnvError::UnknownVariable(_)));
+
+    let err = from_iter::<Config, _, _, _>([("DB", "localhost")]).unwrap_err();
+    assert!(matches!(err, EnvError::UnexpectedValue { ref key, .. } if key == "DB"));
+
+    let err = from_iter::<Config, _, _, _>([
+        ("DB__HOST", "localhost"),
+        ("DB__PORT", "5432"),
+        ("PEERS", ""),
+        ("SERVERS", ""),
+        ("STORAGE", "tape"),
+    ])
+    .unwrap_err();
+    assert!(
+        matches!(err, EnvError::UnknownVariant { ref key, ref value } if key == "STORAGE" && value == "tape")
+    );
+}
+
+#[test]
+fn prefixes() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Service {
+        name: String,
+        replicas: Vec<u8>,
+    }
+
+    let dotenv = r#"
+# Overridden in production
+export SVC__NAME="api"
+SVC__REPLICAS__0=1
+SVC__REPLICAS__1=2
+SVCX__NAME=other
+"#;
+    let service: Service = from_dotenv(dotenv, "SVC")?;
+    assert_eq!(
+        service,
+        Service {
+            name: "api".to_string(),
+            replicas: vec![1, 2],
+        }
+    );
+
+    let err = from_dotenv::<Service>(dotenv, "OTHER").unwrap_err();
+    assert!(matches!(err, EnvError::MissingVariable(ref key) if key == "OTHER__NAME"));
+
+    // SAFETY: no other test reads or writes these variables
+    unsafe {
+        std::env::set_var("FACET_ENV_TEST__NAME", "from-env");
+        std::env::set_var("FACET_ENV_TEST__REPLICAS", "3");
+    }
+    let service: Service = from_env("FACET_ENV_TEST")?;
+    assert_eq!(
+        service,
+        Service {
+            name: "from-env".to_string(),
+            replicas: vec![3],
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use eyre::Result;
+use facet_env::{EnvError, parse_dotenv};
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let pairs = parse_dotenv(
+        r#"
+# A comment
+  EMPTY=
+COMMENT_ONLY= # nothing
+UNQUOTED = some value  # trailing comment
+HASH=a#b
+export EXPORTED=1
+SINGLE='no \n escapes # here'
+DOUBLE="tab\there \"quoted\" C:\dir" # comment
+MULTI="first
+second"
+dotted.name=ok
+"#,
+    )?;
+    let pairs: Vec<(&str, &str)> = pairs
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    assert_eq!(
+        pairs,
+        [
+            ("EMPTY", ""),
+            ("COMMENT_ONLY", ""),
+            ("UNQUOTED", "some value"),
+            ("HASH", "a#b"),
+            ("EXPORTED", "1"),
+            ("SINGLE", r"no \n escapes # here"),
+            ("DOUBLE", "tab\there \"quoted\" C:\\dir"),
+            ("MULTI", "first\nsecond"),
+            ("dotted.name", "ok"),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn errors_have_line_numbers() {
+    facet_testhelpers::setup();
+
+    let cases = [
+        ("A=1\n\nno equals sign", 3, "expected `NAME=value`"),
+        ("1A=1", 1, "invalid variable name"),
+        ("A=1\nB='open", 2, "unclosed single quote"),
+        ("A=\"open\nstill open", 1, "unclosed double quote"),
+        (
+            "A='x' y",
+            1,
+            "unexpected characters after the closing quote",
+        ),
+    ];
+    for (contents, expected_line, expected_message) in cases {
+        let err = parse_dotenv(contents).unwrap_err();
+        assert!(
+            matches!(err, EnvError::InvalidDotenv { line, message } if line == expected_line && message == expected_message),
+            "{contents:?}: {err}"
+        );
+    }
+}
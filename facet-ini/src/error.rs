@@ -0,0 +1,117 @@
+use core::fmt;
+
+use facet_core::Shape;
+use facet_reflect::ReflectError;
+
+/// An error while reading an INI file, with the line it's about.
+#[derive(Debug)]
+pub struct IniError {
+    /// What went wrong
+    pub kind: IniErrorKind,
+    /// The 1-based line at fault. For a missing key, that's the header of
+    /// its section. `None` for errors that aren't about a line of the input,
+    /// such as a missing section or a type that can't be read from INI.
+    pub line: Option<usize>,
+}
+
+impl IniError {
+    pub(crate) fn new(kind: IniErrorKind) -> Self {
+        Self { kind, line: None }
+    }
+
+    pub(crate) fn at_line(mut self, line: Option<usize>) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl From<IniErrorKind> for IniError {
+    fn from(kind: IniErrorKind) -> Self {
+        Self::new(kind)
+    }
+}
+
+impl From<ReflectError> for IniError {
+    fn from(err: ReflectError) -> Self {
+        Self::new(IniErrorKind::ReflectError(err))
+    }
+}
+
+impl fmt::Display for IniError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(line) = self.line {
+            write!(f, " at line {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for IniError {}
+
+/// The different kinds of [`IniError`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum IniErrorKind {
+    /// A line that is neither a section header, a `key=value` pair nor a
+    /// comment
+    Syntax(&'static str),
+    /// A section that appears twice
+    DuplicateSection(String),
+    /// A key that appears twice in the same section
+    DuplicateKey(String),
+    /// A section that matches no field, with `deny_unknown_fields`
+    UnknownSection(String),
+    /// A key that matches no field, with `deny_unknown_fields`
+    UnknownKey(String),
+    /// A field with no key and no default, as `section.key`
+    MissingKey(String),
+    /// A section header for a field that is a value
+    NotASection(String),
+    /// A key for a field that is a section
+    NotAValue(String),
+    /// A value that names no variant of the field's enum
+    UnknownVariant(String),
+    /// A value that can't be parsed as the field's type
+    InvalidValue {
+        /// The value, unquoted
+        value: String,
+        /// The type it was parsed as
+        shape: &'static Shape,
+    },
+    /// The type can't be read or written as INI: it must be a struct whose
+    /// fields are values or sections, sections being structs or maps of
+    /// values
+    UnsupportedShape(String),
+    /// Reflection error
+    ReflectError(ReflectError),
+}
+
+impl From<ReflectError> for IniErrorKind {
+    fn from(err: ReflectError) -> Self {
+        Self::ReflectError(err)
+    }
+}
+
+impl fmt::Display for IniErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IniErrorKind::Syntax(message) => write!(f, "Syntax error: {}", message),
+            IniErrorKind::DuplicateSection(name) => write!(f, "Duplicate section: {}", name),
+            IniErrorKind::DuplicateKey(key) => write!(f, "Duplicate key: {}", key),
+            IniErrorKind::UnknownSection(name) => write!(f, "Unknown section: {}", name),
+            IniErrorKind::UnknownKey(key) => write!(f, "Unknown key: {}", key),
+            IniErrorKind::MissingKey(key) => write!(f, "Missing key: {}", key),
+            IniErrorKind::NotASection(name) => {
+                write!(f, "{} is a value, not a section", name)
+            }
+            IniErrorKind::NotAValue(key) => write!(f, "{} is a section, not a value", key),
+            IniErrorKind::UnknownVariant(variant) => write!(f, "Unknown variant: {}", variant),
+            IniErrorKind::InvalidValue { value, shape } => {
+                write!(f, "Invalid value for {}: {:?}", shape, value)
+            }
+            IniErrorKind::UnsupportedShape(shape) => write!(f, "Unsupported shape: {}", shape),
+            IniErrorKind::ReflectError(err) => write!(f, "Reflection error: {}", err),
+        }
+    }
+}
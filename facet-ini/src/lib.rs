@@ -0,0 +1,30 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+mod error;
+pub use error::{IniError, IniErrorKind};
+
+mod options;
+pub use options::{IniOptions, QuoteStyle};
+
+mod parse;
+
+mod read;
+pub use read::*;
+
+mod write;
+pub use write::*;
+
+use facet_core::{Def, Shape};
+
+/// Whether a field is written as a `[section]`: a struct, a map, or an
+/// option of one. Transparent wrappers are values, like what they wrap.
+fn is_section(shape: &'static Shape) -> bool {
+    match shape.def {
+        Def::Option(od) => is_section(od.t()),
+        Def::Struct(_) => shape.inner.is_none(),
+        Def::Map(_) => true,
+        _ => false,
+    }
+}
@@ -0,0 +1,110 @@
+/// When the writer puts values in quotes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum QuoteStyle {
+    /// Only values that other readers could take differently: those with
+    /// leading or trailing whitespace or line breaks, or that start with the
+    /// quote character or the comment character
+    #[default]
+    Necessary,
+    /// Every value
+    Always,
+    /// No value. Quote characters are also read as ordinary characters, and
+    /// values with line breaks can't be written.
+    Never,
+}
+
+/// Options for reading and writing INI files
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+/// use facet_ini::{IniOptions, from_str_with_options, to_string_with_options};
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Server {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Config {
+///     server: Server,
+/// }
+///
+/// let options = IniOptions::default().delimiter(':').spaced_delimiter(true);
+/// let ini = "[server]\nhost: example.org\nport: 80\n";
+/// let config: Config = from_str_with_options(ini, options).unwrap();
+/// assert_eq!(config.server.port, 80);
+/// assert_eq!(
+///     to_string_with_options(&config, options),
+///     "[server]\nhost : example.org\nport : 80\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct IniOptions {
+    pub(crate) delimiter: char,
+    pub(crate) spaced_delimiter: bool,
+    pub(crate) quote: char,
+    pub(crate) quote_style: QuoteStyle,
+    pub(crate) comment: char,
+}
+
+impl Default for IniOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: '=',
+            spaced_delimiter: false,
+            quote: '"',
+            quote_style: QuoteStyle::Necessary,
+            comment: ';',
+        }
+    }
+}
+
+impl IniOptions {
+    /// Sets the character between keys and values, `=` by default. Keys end
+    /// at its first occurrence, so values may contain it.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets whether the writer puts spaces around the delimiter, as in
+    /// `key = value`, `false` by default. Spaces around keys and values are
+    /// ignored when reading either way.
+    pub fn spaced_delimiter(mut self, spaced_delimiter: bool) -> Self {
+        self.spaced_delimiter = spaced_delimiter;
+        self
+    }
+
+    /// Sets the character that quotes values, `"` by default. Inside quotes,
+    /// `\\`, `\n`, `\r`, `\t` and a backslash before the quote character are
+    /// escapes.
+    pub fn quote(mut self, quote: char) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Sets when values are quoted, [`QuoteStyle::Necessary`] by default
+    pub fn quote_style(mut self, quote_style: QuoteStyle) -> Self {
+        self.quote_style = quote_style;
+        self
+    }
+
+    /// Sets the character comments start with, `;` by default. Lines starting
+    /// with it are skipped when reading, and doc comments are written with
+    /// it.
+    pub fn comment(mut self, comment: char) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    /// The quote character, unless quoting is turned off
+    pub(crate) fn quote_char(&self) -> Option<char> {
+        match self.quote_style {
+            QuoteStyle::Never => None,
+            _ => Some(self.quote),
+        }
+    }
+}
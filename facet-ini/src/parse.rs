@@ -0,0 +1,124 @@
+use crate::{IniError, IniErrorKind, IniOptions};
+
+/// A `key=value` pair, with its value unquoted
+pub(crate) struct Entry {
+    pub(crate) key: String,
+    pub(crate) value: String,
+    pub(crate) line: usize,
+}
+
+/// A `[section]` and the pairs under it
+pub(crate) struct Section {
+    pub(crate) name: String,
+    pub(crate) line: usize,
+    pub(crate) entries: Vec<Entry>,
+}
+
+/// An INI file: the pairs before the first header, then the sections
+#[derive(Default)]
+pub(crate) struct Document {
+    pub(crate) global: Vec<Entry>,
+    pub(crate) sections: Vec<Section>,
+}
+
+/// Splits an INI file into sections and pairs, without looking at what
+/// they're read into.
+pub(crate) fn parse(ini: &str, options: &IniOptions) -> Result<Document, IniError> {
+    let mut document = Document::default();
+    let ini = ini.strip_prefix('\u{feff}').unwrap_or(ini);
+    for (index, line) in ini.lines().enumerate() {
+        let line_number = index + 1;
+        let syntax =
+            |message| IniError::new(IniErrorKind::Syntax(message)).at_line(Some(line_number));
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(options.comment) {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let Some((name, after)) = header.split_once(']') else {
+                return Err(syntax("expected `]` at the end of the section header"));
+            };
+            if !is_blank(after, options.comment) {
+                return Err(syntax("unexpected characters after the section header"));
+            }
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(syntax("empty section name"));
+            }
+            document.sections.push(Section {
+                name: name.to_string(),
+                line: line_number,
+                entries: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once(options.delimiter) else {
+            return Err(syntax("expected a key, the delimiter, then a value"));
+        };
+        let key = key.trim_end();
+        if key.is_empty() {
+            return Err(syntax("empty key"));
+        }
+        let value = value.trim_start();
+        let value = match options.quote_char() {
+            Some(quote) if value.starts_with(quote) => {
+                let Some((value, after)) = unquote(&value[quote.len_utf8()..], quote) else {
+                    return Err(syntax("unclosed quote"));
+                };
+                if !is_blank(after, options.comment) {
+                    return Err(syntax("unexpected characters after the closing quote"));
+                }
+                value
+            }
+            _ => value.to_string(),
+        };
+
+        let entry = Entry {
+            key: key.to_string(),
+            value,
+            line: line_number,
+        };
+        match document.sections.last_mut() {
+            Some(section) => section.entries.push(entry),
+            None => document.global.push(entry),
+        }
+    }
+    Ok(document)
+}
+
+/// Unescapes a quoted value, returning it and what follows the closing
+/// quote, or `None` if there isn't one.
+fn unquote(quoted: &str, quote: char) -> Option<(String, &str)> {
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c == quote => return Some((value, &quoted[i + c.len_utf8()..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => value.push('\n'),
+                Some((_, 'r')) => value.push('\r'),
+                Some((_, 't')) => value.push('\t'),
+                Some((_, '\\')) => value.push('\\'),
+                Some((_, c)) if c == quote => value.push(c),
+                // Other backslashes are kept, as in Windows paths
+                Some((_, c)) => {
+                    value.push('\\');
+                    value.push(c);
+                }
+                None => value.push('\\'),
+            },
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+/// Whether what follows a closing bracket or quote is only whitespace and a
+/// comment
+fn is_blank(after: &str, comment: char) -> bool {
+    let after = after.trim_start();
+    after.is_empty() || after.starts_with(comment)
+}
@@ -0,0 +1,279 @@
+use std::collections::HashSet;
+
+use facet_core::{Def, Facet, Field, Shape};
+use facet_reflect::{ReflectError, Wip};
+use log::trace;
+
+use crate::parse::{Document, Entry, parse};
+use crate::{IniError, IniErrorKind, IniOptions, is_section};
+
+/// Deserializes an INI file into a struct.
+///
+/// - Fields that are structs or maps, or options of them, are read from the
+///   `[section]` of the same name. Other fields are read from the keys
+///   before the first section. Names are the ones the fields are renamed to
+///   by `rename` or `rename_all`.
+/// - Values are parsed with the type's `parse` function, so anything that
+///   implements `FromStr` can be read, and enums by variant name.
+/// - `Option` fields are `None` when their key or section is missing. Other
+///   fields without a key take their `#[facet(default)]` if they have one,
+///   and missing sections are read as empty ones.
+/// - Values in quotes are unquoted, see [`IniOptions::quote`]. Other values
+///   are taken as is, up to the end of the line: comments take whole lines.
+///
+/// Keys and sections that match no field are ignored, unless the struct has
+/// `#[facet(deny_unknown_fields)]`.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Database {
+///     host: String,
+///     port: u16,
+///     user: Option<String>,
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Config {
+///     name: String,
+///     database: Database,
+/// }
+///
+/// let ini = r#"
+/// ; Deployed on Fridays
+/// name = "release bot"
+///
+/// [database]
+/// host = localhost
+/// port = 5432
+/// "#;
+/// let config: Config = facet_ini::from_str(ini).unwrap();
+/// assert_eq!(config.name, "release bot");
+/// assert_eq!(config.database.port, 5432);
+/// assert_eq!(config.database.user, None);
+///
+/// let ini = "name = bot\n[database]\nhost = localhost\nport = many\n";
+/// let err = facet_ini::from_str::<Config>(ini).unwrap_err();
+/// assert_eq!(err.to_string(), r#"Invalid value for u16: "many" at line 4"#);
+/// ```
+pub fn from_str<'facet, T: Facet<'facet>>(ini: &str) -> Result<T, IniError> {
+    from_str_with_options(ini, IniOptions::default())
+}
+
+/// Deserializes an INI file into a struct, with the given options.
+pub fn from_str_with_options<'facet, T: Facet<'facet>>(
+    ini: &str,
+    options: IniOptions,
+) -> Result<T, IniError> {
+    let document = parse(ini, &options)?;
+
+    let wip = Wip::alloc::<T>()?;
+    let shape = wip.shape();
+    let fields = match shape.def {
+        Def::Struct(sd) if shape.inner.is_none() => sd.fields,
+        _ => return Err(IniErrorKind::UnsupportedShape(shape.to_string()).into()),
+    };
+    let wip = read_document(wip, &document, shape, fields)?;
+    Ok(wip.build()?.materialize::<T>()?)
+}
+
+fn read_document<'facet>(
+    mut wip: Wip<'facet>,
+    document: &Document,
+    shape: &'static Shape,
+    fields: &'static [Field],
+) -> Result<Wip<'facet>, IniError> {
+    let deny_unknown_fields = shape.has_deny_unknown_fields_attr();
+
+    for entry in &document.global {
+        let at_line = |kind| IniError::new(kind).at_line(Some(entry.line));
+        match wip.field_index(&entry.key) {
+            Some(index) if is_section(fields[index].shape()) => {
+                return Err(at_line(IniErrorKind::NotAValue(entry.key.clone())));
+            }
+            Some(index) => wip = read_entry(wip, index, entry, None)?,
+            None if deny_unknown_fields => {
+                return Err(at_line(IniErrorKind::UnknownKey(entry.key.clone())));
+            }
+            None => trace!("Ignoring unknown key: {}", entry.key),
+        }
+    }
+
+    for section in &document.sections {
+        let at_line = |kind| IniError::new(kind).at_line(Some(section.line));
+        match wip.field_index(&section.name) {
+            Some(index) if !is_section(fields[index].shape()) => {
+                return Err(at_line(IniErrorKind::NotASection(section.name.clone())));
+            }
+            Some(index) => {
+                if wip.is_field_set(index)? {
+                    return Err(at_line(IniErrorKind::DuplicateSection(
+                        section.name.clone(),
+                    )));
+                }
+                wip = wip.field(index)?;
+                wip = read_section(wip, &section.name, Some(section.line), &section.entries)?;
+                wip = wip.pop()?;
+            }
+            None if deny_unknown_fields => {
+                return Err(at_line(IniErrorKind::UnknownSection(section.name.clone())));
+            }
+            None => trace!("Ignoring unknown section: {}", section.name),
+        }
+    }
+
+    fill_defaults(wip, fields, None, None)
+}
+
+/// Reads a section into the current frame, a struct, a map or an option of
+/// one. `line` is the line of its header, if it has one.
+fn read_section<'facet>(
+    wip: Wip<'facet>,
+    name: &str,
+    line: Option<usize>,
+    entries: &[Entry],
+) -> Result<Wip<'facet>, IniError> {
+    let shape = wip.shape();
+    trace!("Deserializing {} from section {}", shape, name);
+
+    match shape.def {
+        Def::Option(_) => {
+            let wip = wip.push_some()?;
+            let wip = read_section(wip, name, line, entries)?;
+            Ok(wip.pop()?)
+        }
+        Def::Struct(sd) => {
+            let mut wip = wip;
+            let deny_unknown_fields = shape.has_deny_unknown_fields_attr();
+            for entry in entries {
+                match wip.field_index(&entry.key) {
+                    Some(index) => wip = read_entry(wip, index, entry, Some(name))?,
+                    None if deny_unknown_fields => {
+                        return Err(IniError::new(IniErrorKind::UnknownKey(path(
+                            Some(name),
+                            &entry.key,
+                        )))
+                        .at_line(Some(entry.line)));
+                    }
+                    None => trace!("Ignoring unknown key: {}", path(Some(name), &entry.key)),
+                }
+            }
+            fill_defaults(wip, sd.fields, Some(name), line)
+        }
+        Def::Map(_) => {
+            let mut wip = wip.put_default()?;
+            let mut seen = HashSet::new();
+            for entry in entries {
+                let at_line = |kind| IniError::new(kind).at_line(Some(entry.line));
+                if !seen.insert(entry.key.as_str()) {
+                    return Err(at_line(IniErrorKind::DuplicateKey(path(
+                        Some(name),
+                        &entry.key,
+                    ))));
+                }
+                wip = wip.push_map_key()?;
+                wip = put_value(wip, &entry.key).map_err(at_line)?;
+                wip = wip.push_map_value()?;
+                wip = put_value(wip, &entry.value).map_err(at_line)?;
+                wip = wip.pop()?;
+            }
+            Ok(wip)
+        }
+        _ => Err(IniErrorKind::UnsupportedShape(shape.to_string()).into()),
+    }
+}
+
+/// Reads a `key=value` pair into the field at `index` of the current frame
+fn read_entry<'facet>(
+    wip: Wip<'facet>,
+    index: usize,
+    entry: &Entry,
+    section: Option<&str>,
+) -> Result<Wip<'facet>, IniError> {
+    let at_line = |kind| IniError::new(kind).at_line(Some(entry.line));
+    if wip.is_field_set(index)? {
+        return Err(at_line(IniErrorKind::DuplicateKey(path(
+            section, &entry.key,
+        ))));
+    }
+    let wip = wip.field(index)?;
+    let wip = put_value(wip, &entry.value).map_err(at_line)?;
+    Ok(wip.pop()?)
+}
+
+/// Sets the fields no key set: to their default, to `None` for options,
+/// or, for sections, to what an empty section reads as. Missing keys are
+/// reported at `line`, the header of their section.
+fn fill_defaults<'facet>(
+    mut wip: Wip<'facet>,
+    fields: &'static [Field],
+    section: Option<&str>,
+    line: Option<usize>,
+) -> Result<Wip<'facet>, IniError> {
+    for (index, field) in fields.iter().enumerate() {
+        if wip.is_field_set(index)? {
+            continue;
+        }
+        wip = wip.field(index)?;
+        wip = match field.maybe_default_fn() {
+            Some(Some(default_fn)) => wip.put_from_fn(default_fn)?,
+            Some(None) => wip.put_default()?,
+            None => match field.shape().def {
+                Def::Option(_) => wip.put_default()?,
+                // Sections made only of optional and defaulted keys can be
+                // left out, and otherwise report the key that's missing
+                _ if section.is_none() && is_section(field.shape()) => {
+                    read_section(wip, field.name, None, &[])?
+                }
+                _ => {
+                    return Err(
+                        IniError::new(IniErrorKind::MissingKey(path(section, field.name)))
+                            .at_line(line),
+                    );
+                }
+            },
+        };
+        wip = wip.pop()?;
+    }
+    Ok(wip)
+}
+
+/// Parses a value into the current frame: enums are read by variant name,
+/// and everything else through its `FromStr` implementation.
+fn put_value<'facet>(wip: Wip<'facet>, value: &str) -> Result<Wip<'facet>, IniErrorKind> {
+    let shape = wip.shape();
+    match shape.def {
+        Def::Option(_) => {
+            let wip = put_value(wip.push_some()?, value)?;
+            Ok(wip.pop()?)
+        }
+        Def::Enum(_) => match wip.find_variant(value) {
+            Some((index, _)) => Ok(wip.variant(index)?),
+            None => Err(IniErrorKind::UnknownVariant(value.to_string())),
+        },
+        // Sections don't nest
+        Def::Struct(_) | Def::Map(_) | Def::List(_) | Def::Array(_) | Def::Slice(_)
+            if shape.inner.is_none() =>
+        {
+            Err(IniErrorKind::UnsupportedShape(shape.to_string()))
+        }
+        _ => wip.parse(value).map_err(|e| match e {
+            ReflectError::ParseFailed { shape, .. } => IniErrorKind::InvalidValue {
+                value: value.to_string(),
+                shape,
+            },
+            e => e.into(),
+        }),
+    }
+}
+
+/// How a key is named in errors: `section.key`, or `key` before the first
+/// section
+fn path(section: Option<&str>, key: &str) -> String {
+    match section {
+        Some(section) => format!("{}.{}", section, key),
+        None => key.to_string(),
+    }
+}
@@ -0,0 +1,264 @@
+use std::io::{self, Write};
+
+use facet_core::{Def, Facet};
+use facet_reflect::Peek;
+
+use crate::{IniOptions, QuoteStyle, is_section};
+
+/// Serializes a struct to an INI file.
+///
+/// Fields that are values come first, then one `[section]` per field that
+/// is a struct or a map. Doc comments on fields are written as comments
+/// above their key or section, and `None` fields are left out.
+///
+/// # Panics
+/// If the type can't be written as INI, see
+/// [`IniErrorKind::UnsupportedShape`](crate::IniErrorKind::UnsupportedShape),
+/// or if a key or value can't be written, such as a value with a line
+/// break with [`QuoteStyle::Never`].
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Database {
+///     /// Where the database listens
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Facet)]
+/// struct Config {
+///     name: String,
+///     /// The primary
+///     database: Database,
+/// }
+///
+/// let config = Config {
+///     name: " release bot ".to_string(),
+///     database: Database { host: "localhost".to_string(), port: 5432 },
+/// };
+/// assert_eq!(
+///     facet_ini::to_string(&config),
+///     r#"name=" release bot "
+///
+/// ; The primary
+/// [database]
+/// ; Where the database listens
+/// host=localhost
+/// port=5432
+/// "#
+/// );
+/// ```
+pub fn to_string<'a, T: Facet<'a>>(value: &T) -> String {
+    to_string_with_options(value, IniOptions::default())
+}
+
+/// Serializes a struct to an INI file, with the given options
+pub fn to_string_with_options<'a, T: Facet<'a>>(value: &T, options: IniOptions) -> String {
+    let mut output = Vec::new();
+    to_writer_with_options(value, &mut output, options).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// Serializes a struct to a writer as an INI file
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    to_writer_with_options(value, writer, IniOptions::default())
+}
+
+/// Serializes a struct to a writer as an INI file, with the given options
+pub fn to_writer_with_options<'a, T: Facet<'a>, W: Write>(
+    value: &T,
+    writer: &mut W,
+    options: IniOptions,
+) -> io::Result<()> {
+    let peek = Peek::new(value);
+    let shape = peek.shape();
+    if !matches!(shape.def, Def::Struct(_)) || shape.inner.is_some() {
+        return Err(unsupported(peek));
+    }
+    let ps = peek.into_struct().map_err(other)?;
+
+    let mut out = String::new();
+    // Values come first, as keys after a header belong to its section
+    for (field, value) in ps.fields_for_serialize() {
+        if !is_section(field.shape()) {
+            write_entry(&mut out, field.doc, field.name, value, &options)?;
+        }
+    }
+    for (field, value) in ps.fields_for_serialize() {
+        if !is_section(field.shape()) {
+            continue;
+        }
+        let Some(value) = unwrap_option(value)? else {
+            continue;
+        };
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        write_comment(&mut out, field.doc, &options);
+        if field.name.is_empty() || field.name.contains([']', '\n', '\r']) {
+            return Err(other(format!("Invalid section name: {:?}", field.name)));
+        }
+        out.push('[');
+        out.push_str(field.name);
+        out.push_str("]\n");
+        write_section(&mut out, field.name, value, &options)?;
+    }
+    writer.write_all(out.as_bytes())
+}
+
+fn other(e: impl core::fmt::Display) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+fn unsupported(peek: Peek<'_, '_>) -> io::Error {
+    io::Error::other(format!("Unsupported shape: {}", peek.shape()))
+}
+
+/// The value of an option, or the value itself if it isn't one
+fn unwrap_option<'mem, 'facet>(peek: Peek<'mem, 'facet>) -> io::Result<Option<Peek<'mem, 'facet>>> {
+    match peek.shape().def {
+        Def::Option(_) => match peek.into_option().map_err(other)?.value() {
+            Some(value) => unwrap_option(value),
+            None => Ok(None),
+        },
+        _ => Ok(Some(peek)),
+    }
+}
+
+/// Writes the keys of a section, a struct or a map
+fn write_section(
+    out: &mut String,
+    name: &str,
+    peek: Peek<'_, '_>,
+    options: &IniOptions,
+) -> io::Result<()> {
+    match peek.shape().def {
+        Def::Struct(_) => {
+            let ps = peek.into_struct().map_err(other)?;
+            for (field, value) in ps.fields_for_serialize() {
+                write_entry(out, field.doc, field.name, value, options)?;
+            }
+            Ok(())
+        }
+        Def::Map(_) => {
+            let map = peek.into_map().map_err(other)?;
+            for (key, value) in map.iter() {
+                let Some(key) = value_text(key)? else {
+                    return Err(other(format!("Key of section {} is None", name)));
+                };
+                write_entry(out, &[], &key, value, options)?;
+            }
+            Ok(())
+        }
+        _ => Err(unsupported(peek)),
+    }
+}
+
+/// Writes a `key=value` line, after its doc comment. `None` values aren't
+/// written at all.
+fn write_entry(
+    out: &mut String,
+    doc: &[&str],
+    key: &str,
+    peek: Peek<'_, '_>,
+    options: &IniOptions,
+) -> io::Result<()> {
+    let Some(text) = value_text(peek)? else {
+        return Ok(());
+    };
+    if key.is_empty()
+        || key.trim() != key
+        || key.contains([options.delimiter, '\n', '\r'])
+        || key.starts_with(['[', options.comment])
+    {
+        return Err(other(format!("Invalid key: {:?}", key)));
+    }
+
+    write_comment(out, doc, options);
+    out.push_str(key);
+    if options.spaced_delimiter {
+        out.push(' ');
+        out.push(options.delimiter);
+        out.push(' ');
+    } else {
+        out.push(options.delimiter);
+    }
+
+    let quoted = match options.quote_style {
+        QuoteStyle::Always => true,
+        QuoteStyle::Necessary => {
+            text.trim() != text
+                || text.starts_with([options.quote, options.comment])
+                || text.contains(['\n', '\r'])
+        }
+        QuoteStyle::Never => {
+            if text.contains(['\n', '\r']) {
+                return Err(other(format!(
+                    "Value of {} has a line break and can't be written unquoted",
+                    key
+                )));
+            }
+            false
+        }
+    };
+    if quoted {
+        let quote = options.quote;
+        out.push(quote);
+        for c in text.chars() {
+            match c {
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if c == '\\' || c == quote => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                c => out.push(c),
+            }
+        }
+        out.push(quote);
+    } else {
+        out.push_str(&text);
+    }
+    out.push('\n');
+    Ok(())
+}
+
+/// Writes doc comment lines as comments
+fn write_comment(out: &mut String, doc: &[&str], options: &IniOptions) {
+    for line in doc {
+        let line = line.strip_prefix(' ').unwrap_or(line);
+        out.push(options.comment);
+        if !line.is_empty() {
+            out.push(' ');
+            out.push_str(line);
+        }
+        out.push('\n');
+    }
+}
+
+/// The text of a value, or `None` for `None`. Enums are written as the
+/// name of their variant.
+fn value_text(peek: Peek<'_, '_>) -> io::Result<Option<String>> {
+    match peek.shape().def {
+        Def::Option(_) => match peek.into_option().map_err(other)?.value() {
+            Some(value) => value_text(value),
+            None => Ok(None),
+        },
+        Def::Enum(_) => Ok(Some(
+            peek.into_enum()
+                .map_err(other)?
+                .active_variant()
+                .name
+                .to_string(),
+        )),
+        Def::Scalar(_) if peek.shape().vtable.display.is_some() => Ok(Some(peek.to_string())),
+        _ => match peek.borrow_inner() {
+            Some(inner) => value_text(inner),
+            None => Err(unsupported(peek)),
+        },
+    }
+}
@@ -0,0 +1,344 @@
+use eyre::Result;
+use facet::Facet;
+use facet_ini::{IniErrorKind, IniOptions, QuoteStyle, from_str, from_str_with_options};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Facet)]
+struct Database {
+    host: String,
+    port: u16,
+    #[facet(default = "pool_size")]
+    pool_size: u32,
+    user: Option<String>,
+}
+
+fn pool_size() -> u32 {
+    8
+}
+
+#[derive(Debug, PartialEq, Facet)]
+#[repr(u8)]
+enum Level {
+    Warn,
+    Info,
+}
+
+#[derive(Debug, PartialEq, Facet)]
+struct Config {
+    name: String,
+    level: Level,
+    database: Database,
+    labels: BTreeMap<String, String>,
+    cache: Option<Database>,
+}
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let ini = r#"
+; Written by the legacy tool
+;; and edited by hand
+name = inventory
+level = Info
+unrelated = ignored
+
+[database]
+host=db.internal
+port=5432
+user = " admin "
+
+[labels]
+team = core
+url = https://example.org/?a=b
+
+[unrelated]
+key = value
+"#;
+    let config: Config = from_str(ini)?;
+    assert_eq!(
+        config,
+        Config {
+            name: "inventory".to_string(),
+            level: Level::Info,
+            database: Database {
+                host: "db.internal".to_string(),
+                port: 5432,
+                pool_size: 8,
+                user: Some(" admin ".to_string()),
+            },
+            labels: BTreeMap::from([
+                ("team".to_string(), "core".to_string()),
+                ("url".to_string(), "https://example.org/?a=b".to_string()),
+            ]),
+            cache: None,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn missing_sections() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Logging {
+        #[facet(default)]
+        verbose: bool,
+        file: Option<String>,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Settings {
+        logging: Logging,
+        database: Option<Database>,
+    }
+
+    // Sections of optional and defaulted keys can be left out
+    let settings: Settings = from_str("")?;
+    assert_eq!(
+        settings,
+        Settings {
+            logging: Logging {
+                verbose: false,
+                file: None,
+            },
+            database: None,
+        }
+    );
+
+    // Others report what's missing, at their header if they have one
+    let err = from_str::<Config>("name = a\nlevel = Warn\n").unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::MissingKey(key) if key == "database.host"));
+    assert_eq!(err.line, None);
+
+    let err = from_str::<Config>("name = a\nlevel = Warn\n\n[database]\nhost = h\n").unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::MissingKey(key) if key == "database.port"));
+    assert_eq!(err.line, Some(4));
+
+    Ok(())
+}
+
+#[test]
+fn renamed_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(rename_all = "kebab-case")]
+    struct Server {
+        listen_address: String,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Settings {
+        #[facet(rename = "Main Server")]
+        server: Server,
+    }
+
+    let settings: Settings = from_str("[Main Server]\nlisten-address = 0.0.0.0:80\n")?;
+    assert_eq!(settings.server.listen_address, "0.0.0.0:80");
+
+    Ok(())
+}
+
+#[test]
+fn quoting() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Paths {
+        home: String,
+        motd: String,
+        title: String,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Settings {
+        paths: Paths,
+    }
+
+    // Backslashes that escape nothing are kept, as in Windows paths
+    let ini = r#"
+[paths]
+home = "C:\Users\ann"
+motd = "hello\n\t\"world\"" ; greeting
+title = 'single'
+"#;
+    let settings: Settings = from_str(ini)?;
+    assert_eq!(settings.paths.home, r"C:\Users\ann");
+    assert_eq!(settings.paths.motd, "hello\n\t\"world\"");
+    assert_eq!(settings.paths.title, "'single'");
+
+    let options = IniOptions::default().quote('\'');
+    let settings: Settings = from_str_with_options(ini, options)?;
+    assert_eq!(settings.paths.home, r#""C:\Users\ann""#);
+    assert_eq!(settings.paths.title, "single");
+
+    let options = IniOptions::default().quote_style(QuoteStyle::Never);
+    let settings: Settings = from_str_with_options(ini, options)?;
+    assert_eq!(settings.paths.motd, r#""hello\n\t\"world\"" ; greeting"#);
+
+    Ok(())
+}
+
+#[test]
+fn delimiters() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Settings {
+        url: String,
+        port: u16,
+    }
+
+    let options = IniOptions::default().delimiter(':');
+    let settings: Settings = from_str_with_options("url: a=b:c\nport :80\n", options)?;
+    assert_eq!(
+        settings,
+        Settings {
+            url: "a=b:c".to_string(),
+            port: 80,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn comments() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Settings {
+        url: String,
+        port: u16,
+    }
+
+    let ini = "# Written by hand\nurl = \"a;b\" # the upstream\nport = 80\n";
+    let options = IniOptions::default().comment('#');
+    let settings: Settings = from_str_with_options(ini, options)?;
+    assert_eq!(settings.url, "a;b");
+
+    // Only the configured comment character starts a comment
+    let err = from_str::<Settings>(ini).unwrap_err();
+    assert!(matches!(err.kind, IniErrorKind::Syntax(_)));
+    assert_eq!(err.line, Some(1));
+
+    let err =
+        from_str_with_options::<Settings>("; note\nurl = a\nport = 80\n", options).unwrap_err();
+    assert_eq!(err.line, Some(1));
+
+    Ok(())
+}
+
+#[test]
+fn errors_report_lines() {
+    facet_testhelpers::setup();
+
+    let cases: &[(&str, usize, &str)] = &[
+        (
+            "name = a\nlevel\n",
+            2,
+            "expected a key, the delimiter, then a value",
+        ),
+        (
+            "name = a\n[database\n",
+            2,
+            "expected `]` at the end of the section header",
+        ),
+        (
+            "[database] x\n",
+            1,
+            "unexpected characters after the section header",
+        ),
+        ("[ ]\n", 1, "empty section name"),
+        ("\n\n = a\n", 3, "empty key"),
+        ("name = \"a\n", 1, "unclosed quote"),
+        (
+            "name = \"a\" b\n",
+            1,
+            "unexpected characters after the closing quote",
+        ),
+    ];
+    for (ini, line, message) in cases {
+        let err = from_str::<Config>(ini).unwrap_err();
+        assert!(
+            matches!(err.kind, IniErrorKind::Syntax(m) if m == *message),
+            "{:?}",
+            err
+        );
+        assert_eq!(err.line, Some(*line), "{:?}", err);
+    }
+
+    let ini = "name = a\nlevel = Trace\n";
+    let err = from_str::<Config>(ini).unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::UnknownVariant(v) if v == "Trace"));
+    assert_eq!(err.to_string(), "Unknown variant: Trace at line 2");
+
+    let ini = "name = a\nlevel = Info\n[database]\nhost = h\nport = -1\n";
+    let err = from_str::<Config>(ini).unwrap_err();
+    assert!(matches!(
+        &err.kind,
+        IniErrorKind::InvalidValue { value, .. } if value == "-1"
+    ));
+    assert_eq!(err.line, Some(5));
+
+    let ini = "[database]\nhost = h\nhost = i\n";
+    let err = from_str::<Config>(ini).unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::DuplicateKey(k) if k == "database.host"));
+    assert_eq!(err.line, Some(3));
+
+    let ini = "[labels]\na = 1\n[labels]\n";
+    let err = from_str::<Config>(ini).unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::DuplicateSection(s) if s == "labels"));
+    assert_eq!(err.line, Some(3));
+
+    let ini = "database = here\n";
+    let err = from_str::<Config>(ini).unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::NotAValue(k) if k == "database"));
+    assert_eq!(err.line, Some(1));
+
+    let ini = "\n[name]\n";
+    let err = from_str::<Config>(ini).unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::NotASection(s) if s == "name"));
+    assert_eq!(err.line, Some(2));
+}
+
+#[test]
+fn deny_unknown_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Server {
+        #[allow(dead_code)]
+        host: String,
+    }
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Settings {
+        #[allow(dead_code)]
+        server: Option<Server>,
+    }
+
+    let err = from_str::<Settings>("extra = 1\n").unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::UnknownKey(k) if k == "extra"));
+    assert_eq!(err.line, Some(1));
+
+    let err = from_str::<Settings>("[client]\n").unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::UnknownSection(s) if s == "client"));
+
+    let err = from_str::<Settings>("[server]\nhost = a\nport = 1\n").unwrap_err();
+    assert!(matches!(&err.kind, IniErrorKind::UnknownKey(k) if k == "server.port"));
+    assert_eq!(err.line, Some(3));
+}
+
+#[test]
+fn only_structs_at_the_top_level() {
+    facet_testhelpers::setup();
+
+    let err = from_str::<BTreeMap<String, String>>("a = 1\n").unwrap_err();
+    assert!(matches!(err.kind, IniErrorKind::UnsupportedShape(_)));
+}
@@ -0,0 +1,234 @@
+use eyre::Result;
+use facet::Facet;
+use facet_ini::{
+    IniOptions, QuoteStyle, from_str, from_str_with_options, to_string, to_string_with_options,
+    to_writer, to_writer_with_options,
+};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Facet)]
+#[repr(u8)]
+enum Level {
+    Warn,
+    Info,
+}
+
+#[derive(Debug, PartialEq, Facet)]
+struct Database {
+    /// Host name or address
+    host: String,
+    port: u16,
+    user: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Facet)]
+struct Config {
+    /// Shown in the title bar
+    name: String,
+    level: Level,
+    /// The primary database.
+    ///
+    /// Replicas are configured elsewhere.
+    database: Database,
+    labels: BTreeMap<String, String>,
+    cache: Option<Database>,
+    timeout: Option<u32>,
+}
+
+fn config() -> Config {
+    Config {
+        name: "inventory".to_string(),
+        level: Level::Warn,
+        database: Database {
+            host: "db.internal".to_string(),
+            port: 5432,
+            user: None,
+        },
+        labels: BTreeMap::from([("team".to_string(), "core".to_string())]),
+        cache: Some(Database {
+            host: "cache.internal".to_string(),
+            port: 6379,
+            user: Some("ann".to_string()),
+        }),
+        timeout: None,
+    }
+}
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let ini = to_string(&config());
+    assert_eq!(
+        ini,
+        "\
+; Shown in the title bar
+name=inventory
+level=Warn
+
+; The primary database.
+;
+; Replicas are configured elsewhere.
+[database]
+; Host name or address
+host=db.internal
+port=5432
+
+[labels]
+team=core
+
+[cache]
+; Host name or address
+host=cache.internal
+port=6379
+user=ann
+"
+    );
+    assert_eq!(from_str::<Config>(&ini)?, config());
+
+    Ok(())
+}
+
+#[test]
+fn sections_only() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Settings {
+        database: Database,
+    }
+
+    let settings = Settings {
+        database: Database {
+            host: "h".to_string(),
+            port: 1,
+            user: None,
+        },
+    };
+    assert_eq!(
+        to_string(&settings),
+        "[database]\n; Host name or address\nhost=h\nport=1\n"
+    );
+}
+
+#[test]
+fn quoting() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Texts {
+        plain: String,
+        path: String,
+        padded: String,
+        quoted: String,
+        comment: String,
+        multiline: String,
+        empty: String,
+    }
+
+    let texts = Texts {
+        plain: "a = b".to_string(),
+        path: r"C:\Users\ann".to_string(),
+        padded: " x\t".to_string(),
+        quoted: "\"hi\"".to_string(),
+        comment: "; not a comment".to_string(),
+        multiline: "one\ntwo".to_string(),
+        empty: String::new(),
+    };
+
+    let ini = to_string(&texts);
+    assert_eq!(
+        ini,
+        r#"plain=a = b
+path=C:\Users\ann
+padded=" x\t"
+quoted="\"hi\""
+comment="; not a comment"
+multiline="one\ntwo"
+empty=
+"#
+    );
+    assert_eq!(from_str::<Texts>(&ini)?, texts);
+
+    let options = IniOptions::default()
+        .quote('\'')
+        .quote_style(QuoteStyle::Always);
+    let ini = to_string_with_options(&texts, options);
+    assert_eq!(
+        ini,
+        r#"plain='a = b'
+path='C:\\Users\\ann'
+padded=' x\t'
+quoted='"hi"'
+comment='; not a comment'
+multiline='one\ntwo'
+empty=''
+"#
+    );
+    assert_eq!(from_str_with_options::<Texts>(&ini, options)?, texts);
+
+    // Without quotes, line breaks can't be written
+    let options = IniOptions::default().quote_style(QuoteStyle::Never);
+    let mut out = Vec::new();
+    assert!(to_writer_with_options(&texts, &mut out, options).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn delimiters_and_comments() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let options = IniOptions::default()
+        .delimiter(':')
+        .spaced_delimiter(true)
+        .comment('#');
+    let ini = to_string_with_options(&config(), options);
+    assert!(ini.starts_with("# Shown in the title bar\nname : inventory\nlevel : Warn\n"));
+    assert_eq!(from_str_with_options::<Config>(&ini, options)?, config());
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_shapes() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Inner {
+        value: u8,
+    }
+
+    #[derive(Facet)]
+    struct Outer {
+        inner: Inner,
+    }
+
+    #[derive(Facet)]
+    struct Nested {
+        outer: Outer,
+    }
+
+    // Sections don't nest
+    let mut out = Vec::new();
+    let nested = Nested {
+        outer: Outer {
+            inner: Inner { value: 1 },
+        },
+    };
+    assert!(to_writer(&nested, &mut out).is_err());
+
+    // Nor do values go anywhere but in a struct
+    assert!(to_writer(&5u8, &mut out).is_err());
+
+    #[derive(Facet)]
+    struct Keys {
+        labels: BTreeMap<String, u8>,
+    }
+
+    // Keys that would read back differently
+    let keys = Keys {
+        labels: BTreeMap::from([("a=b".to_string(), 1)]),
+    };
+    assert!(to_writer(&keys, &mut out).is_err());
+}
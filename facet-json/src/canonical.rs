@@ -0,0 +1,204 @@
+//! Canonical JSON, as specified by RFC 8785 (JSON Canonicalization Scheme).
+
+use core::fmt::Write as _;
+use facet_core::Facet;
+use facet_reflect::Peek;
+use std::io::{self, Write};
+
+use crate::value::JsonValue;
+
+/// Serializes a value to canonical JSON (RFC 8785), suitable for hashing
+/// and signing.
+///
+/// Object members are sorted by the UTF-16 code units of their names,
+/// numbers are written the way ECMAScript's `Number::toString` writes them,
+/// strings use the minimal set of escapes and no whitespace is emitted. The
+/// output is the same for equal values no matter the iteration order of
+/// their maps.
+///
+/// As the RFC requires, every number goes through an IEEE 754 double, so
+/// integers beyond 2^53 (large `u64`, `i64` and `u128` values, or a
+/// [`RawNumber`](crate::RawNumber) with more digits than a double holds)
+/// are rounded: `9007199254740993` is written as `9007199254740992`. Values
+/// that must survive exactly are better serialized as strings.
+///
+/// # Panics
+///
+/// Panics if the value contains a non-finite float, which has no JSON
+/// representation. Use [`to_canonical_writer`] to get an error instead.
+pub fn to_canonical_string<'a, T: Facet<'a>>(value: &T) -> String {
+    let mut output = Vec::new();
+    to_canonical_writer(value, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// Serializes a value to a writer as canonical JSON (RFC 8785). See
+/// [`to_canonical_string`].
+pub fn to_canonical_writer<'a, T: Facet<'a>, W: Write>(
+    value: &T,
+    writer: &mut W,
+) -> io::Result<()> {
+    peek_to_canonical_writer(&Peek::new(value), writer)
+}
+
+/// Serializes a Peek instance to a writer as canonical JSON (RFC 8785). See
+/// [`to_canonical_string`].
+pub fn peek_to_canonical_writer<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
+    // Go through the regular serializer, then normalize the document: that
+    // keeps a single definition of how each shape maps to JSON.
+    let json = crate::peek_to_string(peek);
+    let mut doc = JsonValue::parse(json.as_bytes()).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("value has no canonical JSON form: {}", e.message()),
+        )
+    })?;
+    sort_members(&mut doc);
+
+    let mut out = String::with_capacity(json.len());
+    write_canonical(&doc, &mut out)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "non-finite number"))?;
+    writer.write_all(out.as_bytes())
+}
+
+/// Recursively sorts object members by the UTF-16 code units of their names.
+fn sort_members(value: &mut JsonValue) {
+    match value {
+        JsonValue::Array(items) => items.iter_mut().for_each(sort_members),
+        JsonValue::Object(entries) => {
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            entries.iter_mut().for_each(|(_, v)| sort_members(v));
+        }
+        _ => {}
+    }
+}
+
+fn write_canonical(value: &JsonValue, out: &mut String) -> core::fmt::Result {
+    match value {
+        JsonValue::Null => out.push_str("null"),
+        JsonValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        // Numbers are IEEE 754 doubles in JCS, however they were written
+        JsonValue::Number(n) => {
+            let n = n.parse::<f64>().map_err(|_| core::fmt::Error)?;
+            out.push_str(&format_f64(n).ok_or(core::fmt::Error)?);
+        }
+        JsonValue::String(s) => write_canonical_string(s, out),
+        JsonValue::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(']');
+        }
+        JsonValue::Object(entries) => {
+            out.push('{');
+            for (i, (key, value)) in entries.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_string(key, out);
+                out.push(':');
+                write_canonical(value, out)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+/// Writes a string with only the escapes RFC 8785 allows: `"`, `\`, the
+/// short forms for the usual control characters, and `\u00XX` for the rest
+/// of the C0 range. Everything else is written as-is.
+fn write_canonical_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{09}' => out.push_str("\\t"),
+            '\u{0A}' => out.push_str("\\n"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\u{0D}' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Formats an `f64` like ECMAScript's `Number::toString`, or returns `None`
+/// for NaN and infinities.
+pub(crate) fn format_f64(n: f64) -> Option<String> {
+    if !n.is_finite() {
+        return None;
+    }
+    if n == 0.0 {
+        // Covers -0 too
+        return Some("0".into());
+    }
+    Some(ecmascript_layout(&format!("{n:e}")))
+}
+
+/// Formats an `f32` like ECMAScript's `Number::toString`, starting from the
+/// shortest digits that round-trip through `f32` rather than `f64`.
+pub(crate) fn format_f32(n: f32) -> Option<String> {
+    if !n.is_finite() {
+        return None;
+    }
+    if n == 0.0 {
+        return Some("0".into());
+    }
+    Some(ecmascript_layout(&format!("{n:e}")))
+}
+
+/// Lays out the digits of a number given in Rust's shortest `{:e}` form
+/// (e.g. `-1.2345e3`) following steps 6 to 10 of ECMAScript's
+/// `Number::toString`.
+fn ecmascript_layout(scientific: &str) -> String {
+    let (negative, scientific) = match scientific.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, scientific),
+    };
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("`{:e}` output always has an exponent");
+    let exponent: i32 = exponent.parse().expect("`{:e}` exponent is an integer");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+
+    // value = 0.digits * 10^n
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    let mut out = String::new();
+    if negative {
+        out.push('-');
+    }
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(core::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(core::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n - 1 >= 0 { '+' } else { '-' });
+        let _ = write!(out, "{}", (n - 1).abs());
+    }
+    out
+}
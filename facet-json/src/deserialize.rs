@@ -1,16 +1,26 @@
+use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec;
-use facet_core::{Characteristic, Def, Facet, ScalarAffinity, StructKind};
-use facet_reflect::{HeapValue, ReflectError, Wip};
+use alloc::vec::Vec;
+use facet_core::{Characteristic, Def, Facet, ScalarAffinity, Shape, StructKind};
+use facet_reflect::{FrameMode, HeapValue, Limits, ReflectError, Wip};
 use log::trace;
 use owo_colors::OwoColorize;
 
+use crate::RawNumber;
+
 mod tokenizer;
 pub use tokenizer::*;
 
 mod error;
 pub use error::*;
 
+mod subtree;
+pub use subtree::*;
+
+mod validate;
+pub use validate::*;
+
 /// Deserializes a JSON string into a value of type `T` that implements `Facet`.
 ///
 /// This function takes a JSON string representation and converts it into a Rust
@@ -40,7 +50,7 @@ where
 {
     let wip = Wip::alloc::<T>().map_err(|e| {
         JsonError::new(
-            JsonErrorKind::ReflectError(e),
+            JsonErrorKind::from(e),
             json,
             Span::new(0, json.len()),
             "$".to_string(),
@@ -50,6 +60,89 @@ where
     Ok(heap_value.materialize::<T>().unwrap())
 }
 
+/// Deserializes a JSON string into a value of type `T`, refusing input that
+/// goes over the given [`Limits`].
+///
+/// Use this for untrusted input: nesting too deep, too many items in an array
+/// or object, strings too long, or too much memory overall fails with
+/// [`JsonErrorKind::LimitExceeded`], whose path points at where the limit was
+/// hit.
+pub fn from_str_with_limits<'input, 'facet, T>(
+    json: &'input str,
+    limits: Limits,
+) -> Result<T, JsonError<'input>>
+where
+    T: Facet<'facet>,
+    'input: 'facet,
+{
+    from_slice_with_limits(json.as_bytes(), limits)
+}
+
+/// Deserializes a JSON slice, refusing input that goes over the given
+/// [`Limits`]. See [`from_str_with_limits`].
+pub fn from_slice_with_limits<'input, 'facet, T>(
+    json: &'input [u8],
+    limits: Limits,
+) -> Result<T, JsonError<'input>>
+where
+    T: Facet<'facet>,
+    'input: 'facet,
+{
+    let wip = Wip::alloc::<T>()
+        .and_then(|wip| wip.with_limits(limits))
+        .map_err(|e| {
+            JsonError::new(
+                JsonErrorKind::from(e),
+                json,
+                Span::new(0, json.len()),
+                "$".to_string(),
+            )
+        })?;
+    let heap_value = from_slice_wip(wip, json)?;
+    Ok(heap_value.materialize::<T>().unwrap())
+}
+
+/// Deserializes a JSON string, reporting every recoverable error instead of
+/// stopping at the first one.
+///
+/// Type mismatches, unknown fields (with `deny_unknown_fields`), unknown enum
+/// variants and missing fields are recorded and the offending value is
+/// skipped, so that a single run surfaces all of them. Syntax errors still
+/// stop parsing, since nothing after them can be trusted.
+pub fn from_str_all_errors<'input, 'facet, T>(json: &'input str) -> Result<T, JsonErrors<'input>>
+where
+    T: Facet<'facet>,
+    'input: 'facet,
+{
+    from_slice_all_errors(json.as_bytes())
+}
+
+/// Deserializes a JSON slice, reporting every recoverable error instead of
+/// stopping at the first one. See [`from_str_all_errors`].
+pub fn from_slice_all_errors<'input, 'facet, T>(json: &'input [u8]) -> Result<T, JsonErrors<'input>>
+where
+    T: Facet<'facet>,
+    'input: 'facet,
+{
+    let mut errors = Vec::new();
+    let wip = Wip::alloc::<T>().map_err(|e| JsonErrors {
+        errors: vec![JsonError::new(
+            JsonErrorKind::from(e),
+            json,
+            Span::new(0, json.len()),
+            "$".to_string(),
+        )],
+    })?;
+    match deserialize_wip(wip, json, Some(&mut errors)) {
+        Ok(Some(heap_value)) if errors.is_empty() => Ok(heap_value.materialize::<T>().unwrap()),
+        Ok(_) => Err(JsonErrors { errors }),
+        Err(fatal) => {
+            errors.push(fatal);
+            Err(JsonErrors { errors })
+        }
+    }
+}
+
 /// Represents the next expected token or structure while parsing.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Instruction {
@@ -81,13 +174,122 @@ enum PopReason {
 ///
 /// A result containing the updated `Wip` or a `JsonParseErrorWithContext`.
 pub fn from_slice_wip<'input: 'facet, 'facet>(
-    mut wip: Wip<'facet>,
+    wip: Wip<'facet>,
     input: &'input [u8],
 ) -> Result<HeapValue<'facet>, JsonError<'input>> {
+    deserialize_wip(wip, input, None).map(|hv| hv.expect("errors are only collected on request"))
+}
+
+/// Whether `path` is `prefix` itself or lies below it.
+fn path_is_within(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with('['))
+}
+
+/// The error for a number that a 128-bit integer can't be parsed from:
+/// a type mismatch, unless it's an integer and only too large.
+fn wide_integer_error(token: Token, lexeme: &str) -> JsonErrorKind {
+    if lexeme.bytes().all(|b| b == b'-' || b.is_ascii_digit()) {
+        JsonErrorKind::NumberOutOfRange(lexeme.parse().unwrap_or(f64::INFINITY))
+    } else {
+        JsonErrorKind::UnexpectedToken {
+            got: token,
+            wanted: "integer",
+        }
+    }
+}
+
+/// Throwaway `Wip`s to try values on, so that a value that doesn't fit is
+/// found out without consuming the real `Wip`. One probe is kept per shape
+/// and reused, each put replacing the value the previous one left.
+#[derive(Default)]
+pub(crate) struct Probes<'facet> {
+    probes: Vec<Wip<'facet>>,
+}
+
+impl<'facet> Probes<'facet> {
+    /// Checks whether `value` can be put into the current frame of `wip`.
+    fn check_put<T: Facet<'facet>>(
+        &mut self,
+        wip: &Wip<'facet>,
+        value: T,
+    ) -> Result<(), ReflectError> {
+        let shape = wip.shape();
+        // A value of the frame's own type always goes in
+        if shape == T::SHAPE {
+            return Ok(());
+        }
+        // Structs and enums take it in their first unset field of its type,
+        // which a fresh probe wouldn't know about: look for one instead
+        if shape.inner.is_none() && shape.vtable.try_from.is_none() {
+            let fields = match shape.def {
+                Def::Struct(sd) => Some(sd.fields),
+                Def::Enum(_) => Some(wip.selected_variant().map_or(&[][..], |v| v.data.fields)),
+                _ => None,
+            };
+            if let Some(fields) = fields {
+                let fits = fields.iter().enumerate().any(|(index, field)| {
+                    field.shape() == T::SHAPE && wip.is_field_set(index) == Ok(false)
+                });
+                return if fits {
+                    Ok(())
+                } else {
+                    Err(ReflectError::WrongShape {
+                        expected: shape,
+                        actual: T::SHAPE,
+                    })
+                };
+            }
+        }
+        self.check(shape, value)
+    }
+
+    /// Checks whether `value` can be put into a frame of the given shape.
+    fn check<T: Facet<'facet>>(
+        &mut self,
+        shape: &'static Shape,
+        value: T,
+    ) -> Result<(), ReflectError> {
+        let probe = match self.probes.iter().position(|probe| probe.shape() == shape) {
+            Some(index) => self.probes.swap_remove(index),
+            None => match Wip::alloc_shape(shape) {
+                Ok(probe) => probe,
+                // Nothing to try it on: the real put will tell.
+                Err(_) => return Ok(()),
+            },
+        };
+        // A failed put takes the probe with it; the next check makes another.
+        self.probes.push(probe.put(value)?);
+        Ok(())
+    }
+
+    /// Checks whether `s` parses into a value of the given shape.
+    fn parse(&mut self, shape: &'static Shape, s: &str) -> Result<(), ReflectError> {
+        let probe = match self.probes.iter().position(|probe| probe.shape() == shape) {
+            Some(index) => self.probes.swap_remove(index),
+            None => match Wip::alloc_shape(shape) {
+                Ok(probe) => probe,
+                Err(_) => return Ok(()),
+            },
+        };
+        self.probes.push(probe.parse(s)?);
+        Ok(())
+    }
+}
+
+/// The deserializer proper. When `diagnostics` is given, recoverable errors
+/// are pushed there and parsing carries on; `Ok(None)` is then returned if
+/// any were recorded.
+fn deserialize_wip<'input: 'facet, 'facet>(
+    mut wip: Wip<'facet>,
+    input: &'input [u8],
+    mut diagnostics: Option<&mut Vec<JsonError<'input>>>,
+) -> Result<Option<HeapValue<'facet>>, JsonError<'input>> {
     let mut stack = vec![Instruction::Pop(PopReason::TopLevel), Instruction::Value];
-    let mut tokenizer = Tokenizer::new(input);
+    let mut tokenizer = Tokenizer::new(input).with_max_string_len(wip.limits().max_string_len);
     let mut last_span = Span { start: 0, len: 0 };
     let mut unread_token: Option<Spanned<Token>> = None;
+    let mut probes = Probes::default();
 
     macro_rules! bail {
         ($kind:expr) => {
@@ -95,6 +297,34 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
         };
     }
 
+    // Records a recoverable error when collecting diagnostics (then runs the
+    // recovery code), or bails otherwise.
+    macro_rules! recoverable {
+        ($kind:expr, $recover:block) => {
+            match diagnostics.as_deref_mut() {
+                Some(diagnostics) => {
+                    diagnostics.push(JsonError::new($kind, input, last_span, wip.path()));
+                    $recover
+                }
+                None => bail!($kind),
+            }
+        };
+    }
+
+    // Skips the rest of a value whose opening token was already read.
+    macro_rules! skip_rest {
+        ($node:expr) => {
+            let first = Spanned {
+                node: $node,
+                span: last_span,
+            };
+            if let Err(e) = tokenizer.skip_value(&first) {
+                last_span = e.span;
+                bail!(JsonErrorKind::from(e.kind));
+            }
+        };
+    }
+
     macro_rules! read_token {
         () => {
             if let Some(token) = unread_token.take() {
@@ -108,7 +338,7 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                     }
                     Err(e) => {
                         last_span = e.span;
-                        bail!(JsonErrorKind::SyntaxError(e.kind));
+                        bail!(JsonErrorKind::from(e.kind));
                     }
                 }
             }
@@ -132,7 +362,7 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                 Ok(wip) => wip,
                 Err(e) => {
                     return Err(JsonError::new(
-                        JsonErrorKind::ReflectError(e),
+                        JsonErrorKind::from(e),
                         input,
                         last_span,
                         path,
@@ -142,6 +372,20 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
         };
     }
 
+    // Puts a scalar, trying it out first when collecting diagnostics so that a
+    // mismatch doesn't cost us the `Wip`.
+    macro_rules! put_scalar {
+        ($value:expr) => {{
+            let value = $value;
+            if diagnostics.is_some() {
+                if let Err(e) = probes.check_put(&wip, value.clone()) {
+                    recoverable!(JsonErrorKind::from(e), { continue });
+                }
+            }
+            reflect!(put(value));
+        }};
+    }
+
     loop {
         let frame_count = wip.frames_count();
         let insn = match stack.pop() {
@@ -164,7 +408,7 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                             let is_set = wip.is_field_set(index).map_err(|err| {
                                 trace!("Error checking field set status: {:?}", err);
                                 JsonError::new(
-                                    JsonErrorKind::ReflectError(err),
+                                    JsonErrorKind::from(err),
                                     input,
                                     last_span,
                                     wip.path(),
@@ -208,6 +452,23 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                         field.blue()
                                     );
                                     has_unset = true;
+
+                                    if let Some(diagnostics) = diagnostics.as_deref_mut() {
+                                        // Don't report fields we already reported an error within
+                                        let field_path = format!("{}.{}", wip.path(), field.name);
+                                        let already_reported = diagnostics
+                                            .iter()
+                                            .any(|d| path_is_within(&d.path, &field_path));
+                                        if !already_reported && !container_shape.has_default_attr()
+                                        {
+                                            diagnostics.push(JsonError::new(
+                                                JsonErrorKind::MissingField(field.name),
+                                                input,
+                                                last_span,
+                                                wip.path(),
+                                            ));
+                                        }
+                                    }
                                 }
                             }
                         }
@@ -217,7 +478,7 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                             let default_val = Wip::<'facet>::alloc_shape(container_shape)
                                 .map_err(|e| {
                                     JsonError::new(
-                                        JsonErrorKind::ReflectError(e),
+                                        JsonErrorKind::from(e),
                                         input,
                                         last_span,
                                         wip.path(),
@@ -226,7 +487,7 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                 .put_default()
                                 .map_err(|e| {
                                     JsonError::new(
-                                        JsonErrorKind::ReflectError(e),
+                                        JsonErrorKind::from(e),
                                         input,
                                         last_span,
                                         wip.path(),
@@ -235,7 +496,7 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                 .build()
                                 .map_err(|e| {
                                     JsonError::new(
-                                        JsonErrorKind::ReflectError(e),
+                                        JsonErrorKind::from(e),
                                         input,
                                         last_span,
                                         wip.path(),
@@ -247,7 +508,7 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                 let is_set = wip.is_field_set(index).map_err(|err| {
                                     trace!("Error checking field set status: {:?}", err);
                                     JsonError::new(
-                                        JsonErrorKind::ReflectError(err),
+                                        JsonErrorKind::from(err),
                                         input,
                                         last_span,
                                         wip.path(),
@@ -276,19 +537,32 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                     }
                 }
 
+                if let Some(diagnostics) = diagnostics.as_deref() {
+                    if reason == PopReason::TopLevel && !diagnostics.is_empty() {
+                        return Ok(None);
+                    }
+
+                    // A map value that failed can't be inserted: drop the entry,
+                    // key and all, and carry on with the rest of the document.
+                    if matches!(wip.mode(), FrameMode::MapValue { .. }) && !wip.is_initialized() {
+                        reflect!(discard_map_entry());
+                        continue;
+                    }
+                }
+
                 if reason == PopReason::TopLevel {
                     let path = wip.path();
-                    return Ok(match wip.build() {
+                    return Ok(Some(match wip.build() {
                         Ok(hv) => hv,
                         Err(e) => {
                             return Err(JsonError::new(
-                                JsonErrorKind::ReflectError(e),
+                                JsonErrorKind::from(e),
                                 input,
                                 last_span,
                                 path,
                             ));
                         }
-                    });
+                    }));
                 } else {
                     reflect!(pop());
                 }
@@ -296,40 +570,18 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
             Instruction::SkipValue => {
                 let token = read_token!();
                 match token.node {
-                    Token::LBrace | Token::LBracket => {
-                        // Skip a compound value by tracking nesting depth
-                        let mut depth = 1;
-                        while depth > 0 {
-                            let token = read_token!();
-                            match token.node {
-                                Token::LBrace | Token::LBracket => {
-                                    depth += 1;
-                                }
-                                Token::RBrace | Token::RBracket => {
-                                    depth -= 1;
-                                }
-                                _ => {
-                                    // primitives, commas, colons, strings, numbers, etc.
-                                }
-                            }
-                        }
-                    }
-                    Token::String(_)
-                    | Token::F64(_)
-                    | Token::I64(_)
-                    | Token::U64(_)
-                    | Token::True
-                    | Token::False
-                    | Token::Null => {
-                        // Primitive value; nothing more to skip
-                    }
-                    other => {
-                        // Unexpected token when skipping a value
+                    Token::RBrace | Token::RBracket | Token::Colon | Token::Comma | Token::EOF => {
                         bail!(JsonErrorKind::UnexpectedToken {
-                            got: other,
+                            got: token.node,
                             wanted: "value"
                         });
                     }
+                    _ => {
+                        if let Err(e) = tokenizer.skip_value(&token) {
+                            last_span = e.span;
+                            bail!(JsonErrorKind::from(e.kind));
+                        }
+                    }
                 }
             }
             Instruction::Value => {
@@ -371,10 +623,16 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                         // nothing to do here
                                     }
                                     _ => {
-                                        bail!(JsonErrorKind::UnsupportedType {
-                                            got: wip.innermost_shape(),
-                                            wanted: "map, enum, or struct"
-                                        });
+                                        recoverable!(
+                                            JsonErrorKind::UnsupportedType {
+                                                got: wip.innermost_shape(),
+                                                wanted: "map, enum, or struct"
+                                            },
+                                            {
+                                                skip_rest!(Token::LBrace);
+                                                continue;
+                                            }
+                                        );
                                     }
                                 }
 
@@ -425,10 +683,16 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                                 reflect!(put_default());
                                             }
                                         } else {
-                                            bail!(JsonErrorKind::UnsupportedType {
-                                                got: wip.shape(),
-                                                wanted: "array, list, tuple, or slice"
-                                            });
+                                            recoverable!(
+                                                JsonErrorKind::UnsupportedType {
+                                                    got: wip.shape(),
+                                                    wanted: "array, list, tuple, or slice"
+                                                },
+                                                {
+                                                    skip_rest!(Token::LBracket);
+                                                    continue;
+                                                }
+                                            );
                                         }
                                     }
                                     Def::Scalar(s)
@@ -452,10 +716,16 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                         }
                                     }
                                     _ => {
-                                        bail!(JsonErrorKind::UnsupportedType {
-                                            got: wip.innermost_shape(),
-                                            wanted: "array, list, tuple, or slice"
-                                        });
+                                        recoverable!(
+                                            JsonErrorKind::UnsupportedType {
+                                                got: wip.innermost_shape(),
+                                                wanted: "array, list, tuple, or slice"
+                                            },
+                                            {
+                                                skip_rest!(Token::LBracket);
+                                                continue;
+                                            }
+                                        );
                                     }
                                 }
 
@@ -471,51 +741,101 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                             }
                             Token::String(s) => match wip.innermost_shape().def {
                                 Def::Scalar(_sd) => {
-                                    reflect!(put::<String>(s));
+                                    put_scalar!(s);
                                 }
                                 Def::Enum(_ed) => {
                                     if wip.selected_variant().is_some() {
                                         trace!("Have variant selected arleady, just putting");
 
                                         // just put, then — if it's a tuple field it'll work
-                                        reflect!(put::<String>(s));
+                                        put_scalar!(s);
                                     } else {
                                         match wip.find_variant(&s) {
                                             Some((variant_index, _)) => {
                                                 reflect!(variant(variant_index));
                                             }
                                             None => {
-                                                bail!(JsonErrorKind::NoSuchVariant {
-                                                    name: s.to_string(),
-                                                    enum_shape: wip.shape()
-                                                });
+                                                recoverable!(
+                                                    JsonErrorKind::NoSuchVariant {
+                                                        name: s.to_string(),
+                                                        enum_shape: wip.shape()
+                                                    },
+                                                    {}
+                                                );
                                             }
                                         }
                                     }
                                 }
-                                _ => bail!(JsonErrorKind::UnsupportedType {
-                                    got: wip.innermost_shape(),
-                                    wanted: "enum or string"
-                                }),
+                                _ => recoverable!(
+                                    JsonErrorKind::UnsupportedType {
+                                        got: wip.innermost_shape(),
+                                        wanted: "enum or string"
+                                    },
+                                    {}
+                                ),
                             },
+                            // Targets that a 64-bit token can't represent
+                            // faithfully are read from the digits themselves
+                            Token::F64(_) | Token::I64(_) | Token::U64(_)
+                                if wip.shape().is_type::<RawNumber>() =>
+                            {
+                                let lexeme = tokenizer.lexeme(token.span);
+                                match lexeme.parse::<RawNumber>() {
+                                    Ok(n) => {
+                                        put_scalar!(n);
+                                    }
+                                    Err(_) => recoverable!(
+                                        JsonErrorKind::InvalidNumber(lexeme.to_string()),
+                                        {}
+                                    ),
+                                }
+                            }
+                            Token::F64(_) | Token::I64(_) | Token::U64(_)
+                                if wip.innermost_shape().is_type::<u128>() =>
+                            {
+                                let lexeme = tokenizer.lexeme(token.span);
+                                match lexeme.parse::<u128>() {
+                                    Ok(n) => {
+                                        put_scalar!(n);
+                                    }
+                                    Err(_) => recoverable!(
+                                        wide_integer_error(token.node.clone(), lexeme),
+                                        {}
+                                    ),
+                                }
+                            }
+                            Token::F64(_) | Token::I64(_) | Token::U64(_)
+                                if wip.innermost_shape().is_type::<i128>() =>
+                            {
+                                let lexeme = tokenizer.lexeme(token.span);
+                                match lexeme.parse::<i128>() {
+                                    Ok(n) => {
+                                        put_scalar!(n);
+                                    }
+                                    Err(_) => recoverable!(
+                                        wide_integer_error(token.node.clone(), lexeme),
+                                        {}
+                                    ),
+                                }
+                            }
                             Token::F64(n) => {
                                 if wip.innermost_shape() == <f32 as Facet>::SHAPE {
-                                    reflect!(put(n as f32));
+                                    put_scalar!(n as f32);
                                 } else {
-                                    reflect!(put(n));
+                                    put_scalar!(n);
                                 }
                             }
                             Token::U64(n) => {
-                                reflect!(put(n));
+                                put_scalar!(n);
                             }
                             Token::I64(n) => {
-                                reflect!(put(n));
+                                put_scalar!(n);
                             }
                             Token::True => {
-                                reflect!(put::<bool>(true));
+                                put_scalar!(true);
                             }
                             Token::False => {
-                                reflect!(put::<bool>(false));
+                                put_scalar!(false);
                             }
                             Token::EOF => {
                                 bail!(JsonErrorKind::UnexpectedEof("in value"));
@@ -545,10 +865,15 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                             "It's not a struct field AND we're denying unknown fields"
                                         );
                                         // well, it all depends.
-                                        bail!(JsonErrorKind::UnknownField {
-                                            field_name: key.to_string(),
-                                            shape: wip.shape(),
-                                        })
+                                        recoverable!(
+                                            JsonErrorKind::UnknownField {
+                                                field_name: key.to_string(),
+                                                shape: wip.shape(),
+                                            },
+                                            {
+                                                ignore = true;
+                                            }
+                                        )
                                     } else {
                                         trace!(
                                             "It's not a struct field and we're ignoring unknown fields"
@@ -579,10 +904,15 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                             trace!(
                                                 "Unknown field in variant and denying unknown fields"
                                             );
-                                            bail!(JsonErrorKind::UnknownField {
-                                                field_name: key.to_string(),
-                                                shape: wip.shape(),
-                                            });
+                                            recoverable!(
+                                                JsonErrorKind::UnknownField {
+                                                    field_name: key.to_string(),
+                                                    shape: wip.shape(),
+                                                },
+                                                {
+                                                    ignore = true;
+                                                }
+                                            );
                                         } else {
                                             trace!("Ignoring unknown field in variant");
                                             // Mark to ignore this field below
@@ -590,16 +920,27 @@ pub fn from_slice_wip<'input: 'facet, 'facet>(
                                             // Instead we handle ignoring in the calling code as needed
                                         }
                                     } else {
-                                        bail!(JsonErrorKind::NoSuchVariant {
-                                            name: key.to_string(),
-                                            enum_shape: wip.shape()
-                                        });
+                                        recoverable!(
+                                            JsonErrorKind::NoSuchVariant {
+                                                name: key.to_string(),
+                                                enum_shape: wip.shape()
+                                            },
+                                            {
+                                                ignore = true;
+                                            }
+                                        );
                                     }
                                 }
                             },
-                            Def::Map(_) => {
+                            Def::Map(md) => {
                                 reflect!(push_map_key());
-                                reflect!(put(key));
+                                if md.k.is_type::<String>() {
+                                    reflect!(put(key));
+                                } else {
+                                    // Object keys are always strings: non-string
+                                    // keys (`u64`, newtypes, ...) are parsed back
+                                    reflect!(parse(&key));
+                                }
                                 reflect!(push_map_value());
                             }
                             _ => {
@@ -5,7 +5,7 @@ use alloc::string::String;
 
 #[cfg(feature = "rich-diagnostics")]
 use ariadne::{Color, Config, IndexType, Label, Report, ReportKind, Source};
-use facet_reflect::ReflectError;
+use facet_reflect::{LimitKind, ReflectError};
 use owo_colors::OwoColorize;
 
 use super::{Token, TokenErrorKind, tokenizer::Span};
@@ -44,6 +44,16 @@ impl<'input> JsonError<'input> {
         }
     }
 
+    /// Detaches the error from the input it was parsed from, copying the input.
+    pub fn into_owned(self) -> JsonError<'static> {
+        JsonError {
+            input: alloc::borrow::Cow::Owned(self.input.into_owned()),
+            span: self.span,
+            path: self.path,
+            kind: self.kind,
+        }
+    }
+
     /// Returns a wrapper type that displays a human-readable error message for this JSON error.
     pub fn message(&self) -> JsonErrorMessage<'_> {
         JsonErrorMessage(self)
@@ -69,6 +79,9 @@ impl core::fmt::Display for JsonErrorMessage<'_> {
             JsonErrorKind::NumberOutOfRange(n) => {
                 write!(f, "Number out of range: {}", n.red())
             }
+            JsonErrorKind::InvalidNumber(n) => {
+                write!(f, "Invalid number: {}", n.red())
+            }
             JsonErrorKind::StringAsNumber(s) => {
                 write!(f, "Expected a string but got number: {}", s.red())
             }
@@ -123,6 +136,23 @@ impl core::fmt::Display for JsonErrorMessage<'_> {
                     )
                 }
             },
+            JsonErrorKind::InvalidPointer(p) => {
+                write!(f, "Invalid JSON Pointer: {}", p.red())
+            }
+            JsonErrorKind::PointerNotFound(token) => {
+                write!(f, "JSON Pointer does not resolve: no {} here", token.red())
+            }
+            JsonErrorKind::LimitExceeded { kind, max } => {
+                write!(f, "Input exceeds the {} limit of {}", kind, max.red())
+            }
+            JsonErrorKind::WrongLength { expected, got } => {
+                write!(
+                    f,
+                    "Wrong number of items: got {}, wanted {}",
+                    got.red(),
+                    expected.green()
+                )
+            }
         }
     }
 }
@@ -144,6 +174,8 @@ pub enum JsonErrorKind {
     },
     /// A number is out of range.
     NumberOutOfRange(f64),
+    /// A number that doesn't follow the JSON number grammar, e.g. `01` or `1.`
+    InvalidNumber(String),
     /// An unexpected String was encountered in the input.
     StringAsNumber(String),
     /// An unexpected field name was encountered in the input.
@@ -177,11 +209,46 @@ pub enum JsonErrorKind {
         /// The enum shape definition where the variant was looked up
         enum_shape: &'static Shape,
     },
+    /// A JSON Pointer that is neither empty nor starts with `/`.
+    InvalidPointer(String),
+    /// A JSON Pointer reference token that has no match in the document.
+    PointerNotFound(String),
+    /// The input went over one of the configured
+    /// [`Limits`](facet_reflect::Limits).
+    LimitExceeded {
+        /// Which limit was exceeded
+        kind: LimitKind,
+        /// The configured maximum
+        max: usize,
+    },
+    /// An array with a different number of items than the fixed-size array
+    /// or tuple it is checked against.
+    WrongLength {
+        /// How many items the type has
+        expected: usize,
+        /// How many items the array has
+        got: usize,
+    },
+}
+
+impl From<TokenErrorKind> for JsonErrorKind {
+    fn from(kind: TokenErrorKind) -> Self {
+        match kind {
+            TokenErrorKind::StringTooLong(max) => JsonErrorKind::LimitExceeded {
+                kind: LimitKind::StringLength,
+                max,
+            },
+            kind => JsonErrorKind::SyntaxError(kind),
+        }
+    }
 }
 
 impl From<ReflectError> for JsonErrorKind {
     fn from(err: ReflectError) -> Self {
-        JsonErrorKind::ReflectError(err)
+        match err {
+            ReflectError::LimitExceeded { kind, max } => JsonErrorKind::LimitExceeded { kind, max },
+            err => JsonErrorKind::ReflectError(err),
+        }
     }
 }
 
@@ -243,3 +310,78 @@ impl core::fmt::Debug for JsonError<'_> {
 }
 
 impl core::error::Error for JsonError<'_> {}
+
+/// Every error found while deserializing in collect-all-errors mode, see
+/// [`from_str_all_errors`](crate::from_str_all_errors), or while validating,
+/// see [`validate_str`](crate::validate_str).
+pub struct JsonErrors<'input> {
+    /// The errors, in the order they were encountered. Never empty.
+    pub errors: alloc::vec::Vec<JsonError<'input>>,
+}
+
+#[cfg(not(feature = "rich-diagnostics"))]
+impl core::fmt::Display for JsonErrors<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rich-diagnostics")]
+impl core::fmt::Display for JsonErrors<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let Some(first) = self.errors.first() else {
+            return Ok(());
+        };
+        let Ok(input_str) = core::str::from_utf8(&first.input[..]) else {
+            return write!(f, "(JSON input was invalid UTF-8)");
+        };
+
+        let source_id = "json";
+        let mut report = Report::build(
+            ReportKind::Error,
+            (source_id, first.span.start()..first.span.end()),
+        )
+        .with_message(format!(
+            "{} error{} in JSON input",
+            self.errors.len(),
+            if self.errors.len() == 1 { "" } else { "s" }
+        ))
+        .with_config(Config::new().with_index_type(IndexType::Byte));
+
+        for error in &self.errors {
+            let label = Label::new((source_id, error.span.start()..error.span.end()))
+                .with_message(format!("{}: {}", error.path.yellow(), error.message()))
+                .with_color(Color::Red);
+            report = report.with_label(label);
+        }
+
+        let source = Source::from(input_str);
+
+        let mut writer = Vec::new();
+        let cache = (source_id, &source);
+
+        if report.finish().write(cache, &mut writer).is_err() {
+            return write!(f, "Error formatting with ariadne");
+        }
+
+        if let Ok(output) = String::from_utf8(writer) {
+            write!(f, "{}", output)
+        } else {
+            write!(f, "Error converting ariadne output to string")
+        }
+    }
+}
+
+impl core::fmt::Debug for JsonErrors<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+impl core::error::Error for JsonErrors<'_> {}
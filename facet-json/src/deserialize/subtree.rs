@@ -0,0 +1,212 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use facet_core::Facet;
+use facet_reflect::Wip;
+
+use super::{JsonError, JsonErrorKind, Span, Spanned, Token, Tokenizer, from_slice_wip};
+use crate::pointer::{parse_index, parse_pointer};
+
+/// Deserializes the value found at an RFC 6901 JSON Pointer inside a JSON
+/// string, e.g. `from_str_at::<Vec<Item>>(json, "/data/items")`.
+///
+/// Everything outside the pointed-to value is skipped over without being
+/// built, and isn't checked past the end of that value. Error paths are
+/// relative to the document root, not to the subtree.
+pub fn from_str_at<'input, 'facet, T>(
+    json: &'input str,
+    pointer: &str,
+) -> Result<T, JsonError<'input>>
+where
+    T: Facet<'facet>,
+    'input: 'facet,
+{
+    from_slice_at(json.as_bytes(), pointer)
+}
+
+/// Deserializes the value found at an RFC 6901 JSON Pointer inside a JSON
+/// byte slice. See [`from_str_at`].
+pub fn from_slice_at<'input, 'facet, T>(
+    json: &'input [u8],
+    pointer: &str,
+) -> Result<T, JsonError<'input>>
+where
+    T: Facet<'facet>,
+    'input: 'facet,
+{
+    let (span, root_path) = locate(json, pointer)?;
+    let offset = span.start;
+
+    let wip = Wip::alloc::<T>().map_err(|e| {
+        JsonError::new(
+            JsonErrorKind::ReflectError(e),
+            json,
+            span,
+            root_path.clone(),
+        )
+    })?;
+    let heap_value = from_slice_wip(wip, &json[offset..span.end()]).map_err(|err| {
+        // Errors from the subtree are relative to it: move them back into
+        // the whole document.
+        JsonError {
+            input: Cow::Borrowed(json),
+            span: Span::new(err.span.start + offset, err.span.len),
+            path: format!(
+                "{}{}",
+                root_path,
+                err.path.strip_prefix('$').unwrap_or(&err.path)
+            ),
+            kind: err.kind,
+        }
+    })?;
+    Ok(heap_value.materialize::<T>().unwrap())
+}
+
+/// Walks the document along `pointer`, returning the span of the pointed-to
+/// value and its path in `$.a.b[0]` form.
+fn locate<'input>(input: &'input [u8], pointer: &str) -> Result<(Span, String), JsonError<'input>> {
+    let mut path = String::from("$");
+    let Some(reference_tokens) = parse_pointer(pointer) else {
+        return Err(JsonError::new(
+            JsonErrorKind::InvalidPointer(pointer.to_string()),
+            input,
+            Span::new(0, 0),
+            path,
+        ));
+    };
+
+    let mut tokenizer = Tokenizer::new(input);
+
+    macro_rules! next {
+        () => {
+            match tokenizer.next_token() {
+                Ok(token) => token,
+                Err(e) => {
+                    return Err(JsonError::new(
+                        JsonErrorKind::SyntaxError(e.kind),
+                        input,
+                        e.span,
+                        path,
+                    ));
+                }
+            }
+        };
+    }
+
+    macro_rules! skip {
+        ($token:expr) => {
+            match tokenizer.skip_value(&$token) {
+                Ok(span) => span,
+                Err(e) => {
+                    return Err(JsonError::new(
+                        JsonErrorKind::SyntaxError(e.kind),
+                        input,
+                        e.span,
+                        path,
+                    ));
+                }
+            }
+        };
+    }
+
+    macro_rules! unexpected {
+        ($token:expr, $wanted:expr) => {{
+            let token: Spanned<Token> = $token;
+            return Err(JsonError::new(
+                JsonErrorKind::UnexpectedToken {
+                    got: token.node,
+                    wanted: $wanted,
+                },
+                input,
+                token.span,
+                path,
+            ));
+        }};
+    }
+
+    macro_rules! not_found {
+        ($reference:expr, $span:expr) => {
+            return Err(JsonError::new(
+                JsonErrorKind::PointerNotFound($reference.to_string()),
+                input,
+                $span,
+                path,
+            ))
+        };
+    }
+
+    let mut current = next!();
+    for reference in &reference_tokens {
+        let container_start = current.span.start;
+        match current.node {
+            Token::LBrace => {
+                let mut token = next!();
+                current = loop {
+                    let key = match token.node {
+                        Token::String(ref key) => key.clone(),
+                        Token::RBrace => not_found!(
+                            reference,
+                            Span::new(container_start, token.span.end() - container_start)
+                        ),
+                        _ => unexpected!(token, "object key or closing brace"),
+                    };
+                    let colon = next!();
+                    if colon.node != Token::Colon {
+                        unexpected!(colon, "colon");
+                    }
+                    let value = next!();
+                    if key == *reference {
+                        break value;
+                    }
+                    skip!(value);
+                    let separator = next!();
+                    match separator.node {
+                        Token::Comma => token = next!(),
+                        Token::RBrace => not_found!(
+                            reference,
+                            Span::new(container_start, separator.span.end() - container_start)
+                        ),
+                        _ => unexpected!(separator, "comma or closing brace"),
+                    }
+                };
+                path.push('.');
+                path.push_str(reference);
+            }
+            Token::LBracket => {
+                let Some(index) = parse_index(reference) else {
+                    not_found!(reference, current.span);
+                };
+                let mut item = next!();
+                for _ in 0..index {
+                    if item.node == Token::RBracket {
+                        break;
+                    }
+                    skip!(item);
+                    let separator = next!();
+                    match separator.node {
+                        Token::Comma => item = next!(),
+                        Token::RBracket => {
+                            item = separator;
+                            break;
+                        }
+                        _ => unexpected!(separator, "comma or closing bracket"),
+                    }
+                }
+                if item.node == Token::RBracket {
+                    not_found!(
+                        reference,
+                        Span::new(container_start, item.span.end() - container_start)
+                    );
+                }
+                path.push_str(&format!("[{index}]"));
+                current = item;
+            }
+            _ => not_found!(reference, current.span),
+        }
+    }
+
+    // Only the value itself is handed to the deserializer, whatever follows
+    let span = skip!(current);
+    Ok((span, path))
+}
@@ -67,6 +67,8 @@ pub enum TokenErrorKind {
     InvalidUtf8(String),
     /// Number is out of range
     NumberOutOfRange(f64),
+    /// String is longer than the tokenizer was told to allow, in bytes
+    StringTooLong(usize),
 }
 
 impl Display for TokenErrorKind {
@@ -76,6 +78,7 @@ impl Display for TokenErrorKind {
             TokenErrorKind::UnexpectedEof(context) => write!(f, "unexpected EOF {}", context),
             TokenErrorKind::InvalidUtf8(detail) => write!(f, "invalid UTF-8: {}", detail),
             TokenErrorKind::NumberOutOfRange(n) => write!(f, "number out of range: {}", n),
+            TokenErrorKind::StringTooLong(max) => write!(f, "string longer than {} bytes", max),
         }
     }
 }
@@ -143,12 +146,24 @@ impl Display for Token {
 pub struct Tokenizer<'input> {
     input: &'input [u8],
     pos: Pos,
+    max_string_len: usize,
 }
 
 impl<'input> Tokenizer<'input> {
     /// Create a new tokenizer for the given input slice.
     pub fn new(input: &'input [u8]) -> Self {
-        Tokenizer { input, pos: 0 }
+        Tokenizer {
+            input,
+            pos: 0,
+            max_string_len: usize::MAX,
+        }
+    }
+
+    /// Refuses strings longer than `max` bytes once decoded, before more
+    /// than that is allocated for them.
+    pub fn with_max_string_len(mut self, max: usize) -> Self {
+        self.max_string_len = max;
+        self
     }
 
     /// Current cursor position in the input
@@ -156,6 +171,18 @@ impl<'input> Tokenizer<'input> {
         self.pos
     }
 
+    /// The input covered by `span`, exactly as written.
+    ///
+    /// Number tokens are converted to `f64`, `i64` or `u64`, which can't hold
+    /// every JSON number; their lexeme still has all the original digits.
+    /// Spans that don't fall on UTF-8 boundaries give an empty string.
+    pub fn lexeme(&self, span: Span) -> &'input str {
+        self.input
+            .get(span.start..span.end())
+            .and_then(|bytes| str::from_utf8(bytes).ok())
+            .unwrap_or_default()
+    }
+
     /// Return the next spanned token or a TokenizeError
     pub fn next_token(&mut self) -> TokenizeResult {
         self.skip_whitespace();
@@ -229,6 +256,57 @@ impl<'input> Tokenizer<'input> {
         Ok(sp)
     }
 
+    /// Skips over the rest of a value whose first token is `first`, without
+    /// building anything. Returns the span covering the whole value.
+    pub fn skip_value(&mut self, first: &Spanned<Token>) -> Result<Span, TokenError> {
+        let start = first.span.start;
+        let mut depth: usize = match first.node {
+            Token::LBrace | Token::LBracket => 1,
+            Token::String(_)
+            | Token::F64(_)
+            | Token::I64(_)
+            | Token::U64(_)
+            | Token::True
+            | Token::False
+            | Token::Null => return Ok(first.span),
+            Token::EOF => {
+                return Err(TokenError {
+                    kind: TokenErrorKind::UnexpectedEof("in value"),
+                    span: first.span,
+                });
+            }
+            Token::RBrace | Token::RBracket | Token::Colon | Token::Comma => {
+                return Err(TokenError {
+                    kind: TokenErrorKind::UnexpectedCharacter(self.input[start] as char),
+                    span: first.span,
+                });
+            }
+        };
+
+        // Compound value: track nesting depth until it closes
+        loop {
+            let token = self.next_token()?;
+            match token.node {
+                Token::LBrace | Token::LBracket => depth += 1,
+                Token::RBrace | Token::RBracket => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(Span::new(start, token.span.end() - start));
+                    }
+                }
+                Token::EOF => {
+                    return Err(TokenError {
+                        kind: TokenErrorKind::UnexpectedEof("in value"),
+                        span: token.span,
+                    });
+                }
+                _ => {
+                    // primitives, commas, colons, strings, numbers, etc.
+                }
+            }
+        }
+    }
+
     /// Skip whitespace characters
     fn skip_whitespace(&mut self) {
         while let Some(&b) = self.input.get(self.pos) {
@@ -261,7 +339,12 @@ impl<'input> Tokenizer<'input> {
                             b'n' => buf.push(b'\n'),   // line feed
                             b'r' => buf.push(b'\r'),   // carriage return
                             b't' => buf.push(b'\t'),   // tab
-                            _ => buf.push(esc), // other escapes (should handle \uXXXX properly)
+                            b'u' => {
+                                let c = self.parse_unicode_escape()?;
+                                let mut utf8 = [0u8; 4];
+                                buf.extend_from_slice(c.encode_utf8(&mut utf8).as_bytes());
+                            }
+                            _ => buf.push(esc), // other escapes
                         }
                         self.pos += 1;
                     } else {
@@ -276,6 +359,12 @@ impl<'input> Tokenizer<'input> {
                     self.pos += 1;
                 }
             }
+            if buf.len() > self.max_string_len {
+                return Err(TokenError {
+                    kind: TokenErrorKind::StringTooLong(self.max_string_len),
+                    span: Span::new(start, self.pos - start),
+                });
+            }
         }
 
         // Check if we reached the end without finding a closing quote
@@ -306,6 +395,47 @@ impl<'input> Tokenizer<'input> {
         })
     }
 
+    /// Decodes the `XXXX` of a `\uXXXX` escape, with `self.pos` on the `u`,
+    /// combining UTF-16 surrogate pairs. Leaves `self.pos` on the last hex
+    /// digit consumed. Unpaired surrogates decode to U+FFFD.
+    fn parse_unicode_escape(&mut self) -> Result<char, TokenError> {
+        let high = self.parse_hex4(self.pos + 1)?;
+        self.pos += 4;
+
+        if (0xD800..0xDC00).contains(&high)
+            && self.input.get(self.pos + 1..self.pos + 3) == Some(&b"\\u"[..])
+        {
+            let low = self.parse_hex4(self.pos + 3)?;
+            if (0xDC00..0xE000).contains(&low) {
+                self.pos += 6;
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                return Ok(char::from_u32(code).unwrap_or('\u{FFFD}'));
+            }
+        }
+        Ok(char::from_u32(high).unwrap_or('\u{FFFD}'))
+    }
+
+    /// Reads four hex digits starting at `at`.
+    fn parse_hex4(&self, at: Pos) -> Result<u32, TokenError> {
+        let Some(hex) = self.input.get(at..at + 4) else {
+            return Err(TokenError {
+                kind: TokenErrorKind::UnexpectedEof("in unicode escape"),
+                span: Span::new(at, self.input.len().saturating_sub(at)),
+            });
+        };
+        let mut code = 0;
+        for (i, &b) in hex.iter().enumerate() {
+            let Some(digit) = (b as char).to_digit(16) else {
+                return Err(TokenError {
+                    kind: TokenErrorKind::UnexpectedCharacter(b as char),
+                    span: Span::new(at + i, 1),
+                });
+            };
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
     fn parse_number(&mut self, start: Pos) -> TokenizeResult {
         let mut end = self.pos;
         if self.input[end] == b'-' {
@@ -344,40 +474,20 @@ impl<'input> Tokenizer<'input> {
 
         let token = if text.contains('.') || text.contains('e') || text.contains('E') {
             // If the number contains a decimal point or exponent, parse as f64
-            match text.parse::<f64>() {
-                Ok(n) => Token::F64(n),
-                Err(_) => {
-                    return Err(TokenError {
-                        kind: TokenErrorKind::NumberOutOfRange(0.0),
-                        span,
-                    });
-                }
-            }
+            Token::F64(self.parse_float(text, end)?)
         } else if text.starts_with('-') {
             // If the number starts with a negative sign, parse as i64
             match text.parse::<i64>() {
                 Ok(n) => Token::I64(n),
-                Err(_) => {
-                    // If i64 parsing fails, try to parse as f64 for error reporting
-                    let num = text.parse::<f64>().unwrap_or(0.0);
-                    return Err(TokenError {
-                        kind: TokenErrorKind::NumberOutOfRange(num),
-                        span,
-                    });
-                }
+                // Too large for an i64: it's still a valid JSON number
+                Err(_) => Token::F64(self.parse_float(text, end)?),
             }
         } else {
             // Otherwise, parse as u64
             match text.parse::<u64>() {
                 Ok(n) => Token::U64(n),
-                Err(_) => {
-                    // If u64 parsing fails, try to parse as f64 for error reporting
-                    let num = text.parse::<f64>().unwrap_or(0.0);
-                    return Err(TokenError {
-                        kind: TokenErrorKind::NumberOutOfRange(num),
-                        span,
-                    });
-                }
+                // Too large for a u64: it's still a valid JSON number
+                Err(_) => Token::F64(self.parse_float(text, end)?),
             }
         };
 
@@ -385,6 +495,22 @@ impl<'input> Tokenizer<'input> {
         Ok(Spanned { node: token, span })
     }
 
+    /// Parses the number `text`, which ends at `end`, as an `f64`. Any
+    /// number parses, if only to an infinity: this only fails when the
+    /// number is cut short, as in `-` or `1e`, and reports what follows it.
+    fn parse_float(&self, text: &str, end: Pos) -> Result<f64, TokenError> {
+        text.parse::<f64>().map_err(|_| match self.input.get(end) {
+            Some(&b) => TokenError {
+                kind: TokenErrorKind::UnexpectedCharacter(b as char),
+                span: Span::new(end, 1),
+            },
+            None => TokenError {
+                kind: TokenErrorKind::UnexpectedEof("in number"),
+                span: Span::new(end, 0),
+            },
+        })
+    }
+
     fn parse_literal<F>(&mut self, start: Pos, pat: &[u8], ctor: F) -> TokenizeResult
     where
         F: FnOnce() -> Token,
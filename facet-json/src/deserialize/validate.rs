@@ -0,0 +1,693 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use facet_core::{
+    Def, EnumDef, Facet, Field, NumberAffinity, NumberBits, ScalarAffinity, ScalarDef, Shape,
+    Signedness, StructKind,
+};
+use facet_reflect::{LimitKind, Limits};
+
+use super::{JsonError, JsonErrorKind, JsonErrors, Probes, Span, Spanned, Token, Tokenizer};
+use crate::RawNumber;
+
+/// Checks a JSON string against the shape of `T` without deserializing it,
+/// reporting every violation found.
+///
+/// The rules are the ones `facet-json` deserializes by, and that
+/// `facet-jsonschema` describes: missing required fields, values of the
+/// wrong type, unknown enum variants, arrays of the wrong length and, with
+/// `deny_unknown_fields`, unknown keys are all reported with their span and
+/// path. Fields with a default, or in a container with one, may be left out,
+/// and `null` stands for the default of any type that has one. Syntax errors
+/// stop validation, since nothing after them can be trusted, and so do values
+/// nested more than 128 levels deep.
+pub fn validate_str<'input, 'facet, T>(json: &'input str) -> Result<(), JsonErrors<'input>>
+where
+    T: Facet<'facet>,
+{
+    validate_shape(T::SHAPE, json.as_bytes())
+}
+
+/// Checks a JSON slice against the shape of `T`. See [`validate_str`].
+pub fn validate_slice<'input, 'facet, T>(json: &'input [u8]) -> Result<(), JsonErrors<'input>>
+where
+    T: Facet<'facet>,
+{
+    validate_shape(T::SHAPE, json)
+}
+
+/// Checks a JSON string against the shape of `T`, refusing input that goes
+/// over the given [`Limits`] the way [`from_str_with_limits`] would.
+///
+/// Nesting too deep, too many items in an array or object, and strings too
+/// long stop validation with [`JsonErrorKind::LimitExceeded`]. The memory
+/// limit isn't checked, since nothing is allocated.
+///
+/// [`from_str_with_limits`]: crate::from_str_with_limits
+pub fn validate_str_with_limits<'input, 'facet, T>(
+    json: &'input str,
+    limits: Limits,
+) -> Result<(), JsonErrors<'input>>
+where
+    T: Facet<'facet>,
+{
+    validate(T::SHAPE, json.as_bytes(), limits)
+}
+
+/// Checks a JSON slice against the shape of `T`, refusing input that goes
+/// over the given [`Limits`]. See [`validate_str_with_limits`].
+pub fn validate_slice_with_limits<'input, 'facet, T>(
+    json: &'input [u8],
+    limits: Limits,
+) -> Result<(), JsonErrors<'input>>
+where
+    T: Facet<'facet>,
+{
+    validate(T::SHAPE, json, limits)
+}
+
+/// Checks a JSON slice against `shape`. See [`validate_str`].
+pub fn validate_shape<'input>(
+    shape: &'static Shape,
+    json: &'input [u8],
+) -> Result<(), JsonErrors<'input>> {
+    validate(shape, json, Limits::default())
+}
+
+/// How deep the validator goes, whatever the limits say: unlike the
+/// deserializer, it recurses.
+const MAX_DEPTH: usize = 128;
+
+fn validate<'input>(
+    shape: &'static Shape,
+    json: &'input [u8],
+    limits: Limits,
+) -> Result<(), JsonErrors<'input>> {
+    let mut validator = Validator {
+        input: json,
+        tokenizer: Tokenizer::new(json).with_max_string_len(limits.max_string_len),
+        errors: Vec::new(),
+        probes: Probes::default(),
+        limits: limits.max_depth(limits.max_depth.min(MAX_DEPTH)),
+        depth: 1,
+    };
+    if let Err(fatal) = validator.document(shape) {
+        validator.errors.push(fatal);
+    }
+    if validator.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(JsonErrors {
+            errors: validator.errors,
+        })
+    }
+}
+
+struct Validator<'input> {
+    input: &'input [u8],
+    tokenizer: Tokenizer<'input>,
+    /// Violations found so far. Syntax errors are returned instead.
+    errors: Vec<JsonError<'input>>,
+    /// For trying out values only their type can check.
+    probes: Probes<'static>,
+    limits: Limits,
+    /// How deep the value being checked is, the root being at 1
+    depth: usize,
+}
+
+impl<'input> Validator<'input> {
+    fn document(&mut self, shape: &'static Shape) -> Result<(), JsonError<'input>> {
+        let token = self.next("$")?;
+        self.check(shape, token, "$")?;
+        let token = self.next("$")?;
+        if token.node != Token::EOF {
+            return Err(self.error(
+                JsonErrorKind::UnexpectedToken {
+                    got: token.node,
+                    wanted: "end of input",
+                },
+                token.span,
+                "$",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks the value starting with `token` against `shape`.
+    fn check(
+        &mut self,
+        shape: &'static Shape,
+        token: Spanned<Token>,
+        path: &str,
+    ) -> Result<(), JsonError<'input>> {
+        // The deserializer puts the default value for `null`
+        if token.node == Token::Null && shape.vtable.default_in_place.is_some() {
+            return Ok(());
+        }
+
+        match shape.def {
+            Def::Option(option_def) => {
+                if token.node == Token::Null {
+                    Ok(())
+                } else {
+                    self.check(option_def.t(), token, path)
+                }
+            }
+            // Transparent wrappers and smart pointers look like what they
+            // hold
+            Def::Struct(_) | Def::SmartPointer(_) if shape.inner.is_some() => {
+                let inner = (shape.inner.unwrap())();
+                self.check(inner, token, path)
+            }
+            Def::SmartPointer(smart_pointer_def) => match smart_pointer_def.pointee {
+                Some(pointee) => self.check(pointee, token, path),
+                None => self.skip(&token, path).map(|_| ()),
+            },
+            Def::Scalar(scalar_def) => self.check_scalar(shape, scalar_def, token, path),
+            Def::Struct(struct_def) => match struct_def.kind {
+                StructKind::Tuple | StructKind::TupleStruct => {
+                    self.check_tuple(struct_def.fields, token, path)
+                }
+                _ => self.check_object(
+                    shape,
+                    struct_def.fields,
+                    shape.has_default_attr(),
+                    token,
+                    path,
+                ),
+            },
+            Def::Map(map_def) => self.check_map(map_def.k, map_def.v, token, path),
+            Def::List(list_def) => self.check_array(list_def.t(), None, token, path),
+            Def::Slice(slice_def) => self.check_array(slice_def.t(), None, token, path),
+            Def::Array(array_def) => {
+                self.check_array(array_def.t(), Some(array_def.n), token, path)
+            }
+            Def::Enum(enum_def) => self.check_enum(shape, enum_def, token, path),
+            // Nothing we know how to check: anything goes
+            _ => self.skip(&token, path).map(|_| ()),
+        }
+    }
+
+    fn check_scalar(
+        &mut self,
+        shape: &'static Shape,
+        scalar_def: ScalarDef,
+        token: Spanned<Token>,
+        path: &str,
+    ) -> Result<(), JsonError<'input>> {
+        if shape.is_type::<RawNumber>() {
+            return match token.node {
+                Token::F64(_) | Token::I64(_) | Token::U64(_) => Ok(()),
+                _ => self.mismatch(token, "number", path),
+            };
+        }
+
+        match scalar_def.affinity {
+            ScalarAffinity::Number(number_affinity) => match token.node {
+                Token::F64(_) | Token::I64(_) | Token::U64(_) => {
+                    if let NumberBits::Integer { bits, sign } = number_affinity.bits {
+                        let lexeme = self.tokenizer.lexeme(token.span);
+                        self.check_integer(lexeme, bits, sign, token, path);
+                    }
+                    Ok(())
+                }
+                _ => match number_affinity.bits {
+                    NumberBits::Integer { .. } => self.mismatch(token, "integer", path),
+                    _ => self.mismatch(token, "number", path),
+                },
+            },
+            ScalarAffinity::Boolean(_) => match token.node {
+                Token::True | Token::False => Ok(()),
+                _ => self.mismatch(token, "boolean", path),
+            },
+            ScalarAffinity::Empty(_) => match token.node {
+                Token::Null => Ok(()),
+                // The deserializer also takes `[]` for the unit type
+                Token::LBracket => {
+                    let span = self.skip(&token, path)?;
+                    let lexeme = self.tokenizer.lexeme(span);
+                    if !lexeme[1..lexeme.len() - 1].trim().is_empty() {
+                        self.push(
+                            JsonErrorKind::UnexpectedToken {
+                                got: token.node,
+                                wanted: "null or empty array",
+                            },
+                            span,
+                            path,
+                        );
+                    }
+                    Ok(())
+                }
+                _ => self.mismatch(token, "null", path),
+            },
+            ScalarAffinity::Opaque(_) | ScalarAffinity::Other(_) => {
+                self.skip(&token, path).map(|_| ())
+            }
+            ScalarAffinity::String(_) => match token.node {
+                Token::String(_) => Ok(()),
+                _ => self.mismatch(token, "string", path),
+            },
+            ScalarAffinity::Char(_) => match token.node {
+                Token::String(ref s) if s.chars().count() == 1 => Ok(()),
+                _ => self.mismatch(token, "single-character string", path),
+            },
+            // Addresses, ids, times and paths are strings in a format only
+            // the type itself knows: ask it.
+            _ => match token.node {
+                Token::String(s) => {
+                    if let Err(e) = self.probes.check(shape, s) {
+                        self.push(JsonErrorKind::from(e), token.span, path);
+                    }
+                    Ok(())
+                }
+                _ => self.mismatch(token, "string", path),
+            },
+        }
+    }
+
+    /// Checks that `lexeme`, from `token`, is an integer that fits in `bits`.
+    fn check_integer(
+        &mut self,
+        lexeme: &str,
+        bits: usize,
+        sign: Signedness,
+        token: Spanned<Token>,
+        path: &str,
+    ) {
+        let value = match lexeme.parse::<f64>() {
+            Ok(value) if !lexeme.contains(['.', 'e', 'E']) => value,
+            _ => {
+                self.push(
+                    JsonErrorKind::UnexpectedToken {
+                        got: token.node,
+                        wanted: "integer",
+                    },
+                    token.span,
+                    path,
+                );
+                return;
+            }
+        };
+
+        let fits = match sign {
+            Signedness::Unsigned => lexeme
+                .parse::<u128>()
+                .is_ok_and(|n| bits >= 128 || n >> bits == 0),
+            Signedness::Signed => lexeme.parse::<i128>().is_ok_and(|n| {
+                bits >= 128 || (-(1i128 << (bits - 1))..(1i128 << (bits - 1))).contains(&n)
+            }),
+        };
+        if !fits {
+            self.push(JsonErrorKind::NumberOutOfRange(value), token.span, path);
+        }
+    }
+
+    /// Checks an object with one key per field. Unknown keys are only
+    /// reported when the container denies them.
+    fn check_object(
+        &mut self,
+        shape: &'static Shape,
+        fields: &'static [Field],
+        container_default: bool,
+        token: Spanned<Token>,
+        path: &str,
+    ) -> Result<(), JsonError<'input>> {
+        if token.node != Token::LBrace {
+            return self.mismatch(token, "object", path);
+        }
+
+        let start = token.span.start;
+        let mut seen = vec![false; fields.len()];
+        let end = self.entries(path, |validator, key, key_span, value| {
+            match fields
+                .iter()
+                .position(|f| f.get_rename_attr().unwrap_or(f.name) == key)
+            {
+                Some(index) => {
+                    seen[index] = true;
+                    let path = format!("{path}.{key}");
+                    validator.check(fields[index].shape(), value, &path)
+                }
+                None => {
+                    validator.skip(&value, path)?;
+                    if shape.has_deny_unknown_fields_attr() {
+                        validator.push(
+                            JsonErrorKind::UnknownField {
+                                field_name: key.to_string(),
+                                shape,
+                            },
+                            key_span,
+                            path,
+                        );
+                    }
+                    Ok(())
+                }
+            }
+        })?;
+
+        if !container_default {
+            let span = Span::new(start, end - start);
+            for (field, seen) in fields.iter().zip(seen) {
+                if !seen && field.maybe_default_fn().is_none() {
+                    let name = field.get_rename_attr().unwrap_or(field.name);
+                    self.push(JsonErrorKind::MissingField(name), span, path);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Tuples are arrays with one item per field.
+    fn check_tuple(
+        &mut self,
+        fields: &'static [Field],
+        token: Spanned<Token>,
+        path: &str,
+    ) -> Result<(), JsonError<'input>> {
+        if token.node != Token::LBracket {
+            return self.mismatch(token, "array", path);
+        }
+
+        let start = token.span.start;
+        let mut count = 0;
+        let end = self.items(path, |validator, index, item| {
+            count += 1;
+            let path = format!("{path}[{index}]");
+            match fields.get(index) {
+                Some(field) => validator.check(field.shape(), item, &path),
+                None => validator.skip(&item, &path).map(|_| ()),
+            }
+        })?;
+        self.check_length(fields.len(), count, Span::new(start, end - start), path);
+        Ok(())
+    }
+
+    /// Checks an array of `item`s, of exactly `len` of them if given.
+    fn check_array(
+        &mut self,
+        item_shape: &'static Shape,
+        len: Option<usize>,
+        token: Spanned<Token>,
+        path: &str,
+    ) -> Result<(), JsonError<'input>> {
+        if token.node != Token::LBracket {
+            return self.mismatch(token, "array", path);
+        }
+
+        let start = token.span.start;
+        let mut count = 0;
+        let end = self.items(path, |validator, index, item| {
+            count += 1;
+            validator.check(item_shape, item, &format!("{path}[{index}]"))
+        })?;
+        if let Some(len) = len {
+            self.check_length(len, count, Span::new(start, end - start), path);
+        }
+        Ok(())
+    }
+
+    /// Maps are objects. Keys that aren't strings are written with their
+    /// `Display` impl, so they must parse back.
+    fn check_map(
+        &mut self,
+        key_shape: &'static Shape,
+        value_shape: &'static Shape,
+        token: Spanned<Token>,
+        path: &str,
+    ) -> Result<(), JsonError<'input>> {
+        if token.node != Token::LBrace {
+            return self.mismatch(token, "object", path);
+        }
+
+        self.entries(path, |validator, key, key_span, value| {
+            let path = format!("{path}.{key}");
+            match key_shape.def {
+                Def::Scalar(ScalarDef {
+                    affinity:
+                        ScalarAffinity::Number(NumberAffinity {
+                            bits: NumberBits::Integer { bits, sign },
+                            ..
+                        }),
+                    ..
+                }) => {
+                    let key_token = Spanned {
+                        node: Token::String(key.to_string()),
+                        span: key_span,
+                    };
+                    validator.check_integer(key, bits, sign, key_token, &path);
+                }
+                _ if key_shape.is_type::<String>() => {}
+                _ => {
+                    if let Err(e) = validator.probes.parse(key_shape, key) {
+                        validator.push(JsonErrorKind::from(e), key_span, &path);
+                    }
+                }
+            }
+            validator.check(value_shape, value, &path)
+        })?;
+        Ok(())
+    }
+
+    /// Enums follow the serializer: unit variants are strings holding their
+    /// name, and other variants are objects with their name as the only key.
+    fn check_enum(
+        &mut self,
+        shape: &'static Shape,
+        enum_def: EnumDef,
+        token: Spanned<Token>,
+        path: &str,
+    ) -> Result<(), JsonError<'input>> {
+        let find = |name: &str| enum_def.variants.iter().find(|v| v.name == name);
+        match token.node {
+            Token::String(ref name) => {
+                match find(name) {
+                    Some(variant) if variant.data.fields.is_empty() => {}
+                    Some(_) => self.push(
+                        JsonErrorKind::UnexpectedToken {
+                            got: token.node.clone(),
+                            wanted: "object holding the variant's fields",
+                        },
+                        token.span,
+                        path,
+                    ),
+                    None => self.push(
+                        JsonErrorKind::NoSuchVariant {
+                            name: name.clone(),
+                            enum_shape: shape,
+                        },
+                        token.span,
+                        path,
+                    ),
+                }
+                Ok(())
+            }
+            Token::LBrace => {
+                let start = token.span.start;
+                let mut count = 0;
+                let end = self.entries(path, |validator, key, key_span, value| {
+                    count += 1;
+                    let path = format!("{path}.{key}");
+                    let Some(variant) = find(key) else {
+                        validator.skip(&value, &path)?;
+                        validator.push(
+                            JsonErrorKind::NoSuchVariant {
+                                name: key.to_string(),
+                                enum_shape: shape,
+                            },
+                            key_span,
+                            &path,
+                        );
+                        return Ok(());
+                    };
+                    let fields = variant.data.fields;
+                    match variant.data.kind {
+                        StructKind::Tuple | StructKind::TupleStruct if fields.len() == 1 => {
+                            validator.check(fields[0].shape(), value, &path)
+                        }
+                        StructKind::Tuple | StructKind::TupleStruct => {
+                            validator.check_tuple(fields, value, &path)
+                        }
+                        _ => validator.check_object(shape, fields, false, value, &path),
+                    }
+                })?;
+                if count != 1 {
+                    self.push(
+                        JsonErrorKind::UnexpectedToken {
+                            got: Token::LBrace,
+                            wanted: "object with exactly one variant",
+                        },
+                        Span::new(start, end - start),
+                        path,
+                    );
+                }
+                Ok(())
+            }
+            _ => self.mismatch(token, "string or object", path),
+        }
+    }
+
+    /// Walks the entries of an object whose `{` was already read, calling
+    /// `entry` with each key, its span and the first token of its value.
+    /// Returns where the object ends.
+    fn entries(
+        &mut self,
+        path: &str,
+        mut entry: impl FnMut(&mut Self, &str, Span, Spanned<Token>) -> Result<(), JsonError<'input>>,
+    ) -> Result<usize, JsonError<'input>> {
+        let mut token = self.next(path)?;
+        if token.node == Token::RBrace {
+            return Ok(token.span.end());
+        }
+        let mut count = 0;
+        loop {
+            let key = match token.node {
+                Token::String(key) => key,
+                node => {
+                    let token = Spanned {
+                        node,
+                        span: token.span,
+                    };
+                    return Err(self.unexpected(token, "object key", path));
+                }
+            };
+            let colon = self.next(path)?;
+            if colon.node != Token::Colon {
+                return Err(self.unexpected(colon, "colon", path));
+            }
+            count += 1;
+            self.limit(LimitKind::CollectionLength, count, token.span, path)?;
+            let value = self.next(path)?;
+            self.descend(value.span, path, |validator| {
+                entry(validator, &key, token.span, value)
+            })?;
+
+            let separator = self.next(path)?;
+            match separator.node {
+                Token::Comma => token = self.next(path)?,
+                Token::RBrace => return Ok(separator.span.end()),
+                _ => return Err(self.unexpected(separator, "comma or closing brace", path)),
+            }
+        }
+    }
+
+    /// Walks the items of an array whose `[` was already read, calling
+    /// `item` with each index and the first token of its value. Returns
+    /// where the array ends.
+    fn items(
+        &mut self,
+        path: &str,
+        mut item: impl FnMut(&mut Self, usize, Spanned<Token>) -> Result<(), JsonError<'input>>,
+    ) -> Result<usize, JsonError<'input>> {
+        let mut token = self.next(path)?;
+        if token.node == Token::RBracket {
+            return Ok(token.span.end());
+        }
+        let mut index = 0;
+        loop {
+            self.limit(LimitKind::CollectionLength, index + 1, token.span, path)?;
+            self.descend(token.span, path, |validator| item(validator, index, token))?;
+            index += 1;
+
+            let separator = self.next(path)?;
+            match separator.node {
+                Token::Comma => token = self.next(path)?,
+                Token::RBracket => return Ok(separator.span.end()),
+                _ => return Err(self.unexpected(separator, "comma or closing bracket", path)),
+            }
+        }
+    }
+
+    /// Checks a value inside the one being checked, unless that goes deeper
+    /// than the limits allow.
+    fn descend(
+        &mut self,
+        span: Span,
+        path: &str,
+        check: impl FnOnce(&mut Self) -> Result<(), JsonError<'input>>,
+    ) -> Result<(), JsonError<'input>> {
+        self.limit(LimitKind::Depth, self.depth + 1, span, path)?;
+        self.depth += 1;
+        let result = check(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Fails when `value` goes over the limit of the given kind.
+    fn limit(
+        &self,
+        kind: LimitKind,
+        value: usize,
+        span: Span,
+        path: &str,
+    ) -> Result<(), JsonError<'input>> {
+        self.limits
+            .check(kind, value)
+            .map_err(|e| self.error(JsonErrorKind::from(e), span, path))
+    }
+
+    fn check_length(&mut self, expected: usize, got: usize, span: Span, path: &str) {
+        if expected != got {
+            self.push(JsonErrorKind::WrongLength { expected, got }, span, path);
+        }
+    }
+
+    /// Records that the value starting with `token` isn't what was
+    /// `wanted`, and skips over it.
+    fn mismatch(
+        &mut self,
+        token: Spanned<Token>,
+        wanted: &'static str,
+        path: &str,
+    ) -> Result<(), JsonError<'input>> {
+        let span = self.skip(&token, path)?;
+        self.push(
+            JsonErrorKind::UnexpectedToken {
+                got: token.node,
+                wanted,
+            },
+            span,
+            path,
+        );
+        Ok(())
+    }
+
+    /// Skips the rest of the value starting with `token`, returning its span.
+    fn skip(&mut self, token: &Spanned<Token>, path: &str) -> Result<Span, JsonError<'input>> {
+        self.tokenizer
+            .skip_value(token)
+            .map_err(|e| self.error(JsonErrorKind::from(e.kind), e.span, path))
+    }
+
+    fn next(&mut self, path: &str) -> Result<Spanned<Token>, JsonError<'input>> {
+        self.tokenizer
+            .next_token()
+            .map_err(|e| self.error(JsonErrorKind::from(e.kind), e.span, path))
+    }
+
+    fn push(&mut self, kind: JsonErrorKind, span: Span, path: &str) {
+        let error = self.error(kind, span, path);
+        self.errors.push(error);
+    }
+
+    fn unexpected(
+        &self,
+        token: Spanned<Token>,
+        wanted: &'static str,
+        path: &str,
+    ) -> JsonError<'input> {
+        self.error(
+            JsonErrorKind::UnexpectedToken {
+                got: token.node,
+                wanted,
+            },
+            token.span,
+            path,
+        )
+    }
+
+    fn error(&self, kind: JsonErrorKind, span: Span, path: &str) -> JsonError<'input> {
+        JsonError::new(kind, self.input, span, path.to_string())
+    }
+}
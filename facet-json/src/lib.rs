@@ -14,11 +14,29 @@ compile_error!("feature `alloc` is required");
 mod deserialize;
 pub use deserialize::*;
 
+pub use facet_reflect::{LimitKind, Limits};
+
+mod raw_number;
+pub use raw_number::*;
+
 #[cfg(feature = "std")]
 mod serialize;
 #[cfg(feature = "std")]
 pub use serialize::*;
 
+#[cfg(feature = "std")]
+mod canonical;
+#[cfg(feature = "std")]
+pub use canonical::*;
+
+#[cfg(feature = "std")]
+mod patch;
+#[cfg(feature = "std")]
+pub use patch::*;
+
+mod pointer;
+mod value;
+
 fn variant_is_transparent(variant: &facet_core::Variant) -> bool {
     variant.data.kind == facet_core::StructKind::Tuple && variant.data.fields.len() == 1
 }
@@ -0,0 +1,659 @@
+//! Applying RFC 6902 JSON Patch and RFC 7396 JSON Merge Patch documents to
+//! typed values.
+//!
+//! The target is serialized to an untyped document, the operations are
+//! applied to that document while checking every location against the
+//! target's [`Shape`], and the result is deserialized back into the target.
+//! The target is only overwritten once the whole patch applied cleanly.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use facet_core::{Def, Facet, Field, Shape, StructKind, Variant};
+use owo_colors::OwoColorize;
+
+use crate::pointer::{format_pointer, parse_index, parse_pointer};
+use crate::value::JsonValue;
+use crate::{JsonError, from_str, to_string, validate_shape};
+
+/// Applies an RFC 6902 JSON Patch document to `target`.
+///
+/// The patch is an array of `add`, `remove`, `replace`, `move`, `copy` and
+/// `test` operations. Operations are applied in order; if any of them fails,
+/// `target` is left untouched.
+///
+/// Besides the rules of the RFC, every location must make sense for the
+/// target's shape: unknown struct fields can't be added, only `Option` fields
+/// (set to `None`) and fields with a default may be removed, and fixed-length
+/// arrays and tuples can't grow or shrink.
+pub fn apply_patch<T>(target: &mut T, patch: &str) -> Result<(), PatchError>
+where
+    T: for<'f> Facet<'f>,
+{
+    let patch = JsonValue::parse(patch.as_bytes())
+        .map_err(|e| PatchError::new("", PatchErrorKind::InvalidJson(e.into_owned())))?;
+    let JsonValue::Array(operations) = patch else {
+        return Err(PatchError::new(
+            "",
+            PatchErrorKind::InvalidPatch("a JSON Patch document must be an array".into()),
+        ));
+    };
+
+    let mut doc = to_document(target)?;
+    for operation in operations {
+        apply_operation(&mut doc, T::SHAPE, operation)?;
+    }
+    *target = from_document(&doc)?;
+    Ok(())
+}
+
+/// Applies an RFC 7396 JSON Merge Patch document to `target`.
+///
+/// Objects in the patch are merged member by member, `null` members remove
+/// the corresponding member (setting `Option` fields to `None`), and any other
+/// value replaces the target value wholesale. If the merge fails, `target` is
+/// left untouched.
+pub fn apply_merge_patch<T>(target: &mut T, patch: &str) -> Result<(), PatchError>
+where
+    T: for<'f> Facet<'f>,
+{
+    let patch = JsonValue::parse(patch.as_bytes())
+        .map_err(|e| PatchError::new("", PatchErrorKind::InvalidJson(e.into_owned())))?;
+
+    let mut doc = to_document(target)?;
+    let mut path = Vec::new();
+    merge(&mut doc, patch, Loc::Shape(T::SHAPE), &mut path)?;
+    *target = from_document(&doc)?;
+    Ok(())
+}
+
+/// An error that occurred while applying a JSON Patch or JSON Merge Patch.
+#[derive(Debug)]
+pub struct PatchError {
+    /// JSON Pointer (RFC 6901) to the location the failing operation targeted.
+    /// The empty string designates the whole document.
+    pub pointer: String,
+
+    /// What went wrong.
+    pub kind: PatchErrorKind,
+}
+
+impl PatchError {
+    fn new(pointer: impl Into<String>, kind: PatchErrorKind) -> Self {
+        Self {
+            pointer: pointer.into(),
+            kind,
+        }
+    }
+}
+
+/// The kind of a [`PatchError`].
+#[derive(Debug)]
+pub enum PatchErrorKind {
+    /// The patch document is not valid JSON.
+    InvalidJson(JsonError<'static>),
+    /// The patch document is valid JSON, but not a well-formed patch.
+    InvalidPatch(String),
+    /// The pointer does not refer to an existing value.
+    PathNotFound,
+    /// The pointer refers to a location the target type has no room for,
+    /// such as an unknown struct field or a non-numeric list index.
+    NoSuchLocation {
+        /// The reference token that could not be resolved
+        token: String,
+        /// The shape it was resolved against
+        shape: &'static Shape,
+    },
+    /// The value can't be removed, because the type requires it to be present.
+    NotRemovable {
+        /// The shape of the value that was to be removed
+        shape: &'static Shape,
+    },
+    /// Elements can't be added to or removed from a fixed-length container.
+    FixedLength {
+        /// The shape of the fixed-length container
+        shape: &'static Shape,
+    },
+    /// A `test` operation found a different value than expected.
+    TestFailed {
+        /// The value the patch expected, as JSON
+        expected: String,
+        /// The value that was actually there, as JSON
+        actual: String,
+    },
+    /// The patched value does not fit the target type.
+    InvalidValue(JsonError<'static>),
+}
+
+impl fmt::Display for PatchErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchErrorKind::InvalidJson(e) => write!(f, "Patch is not valid JSON: {}", e.message()),
+            PatchErrorKind::InvalidPatch(msg) => write!(f, "Invalid patch: {msg}"),
+            PatchErrorKind::PathNotFound => write!(f, "No value at this location"),
+            PatchErrorKind::NoSuchLocation { token, shape } => {
+                write!(f, "{} does not exist in {}", token.red(), shape.yellow())
+            }
+            PatchErrorKind::NotRemovable { shape } => {
+                write!(
+                    f,
+                    "Cannot remove a required value of type {}",
+                    shape.yellow()
+                )
+            }
+            PatchErrorKind::FixedLength { shape } => {
+                write!(f, "Cannot change the length of {}", shape.yellow())
+            }
+            PatchErrorKind::TestFailed { expected, actual } => {
+                write!(
+                    f,
+                    "Test failed: expected {}, found {}",
+                    expected.green(),
+                    actual.red()
+                )
+            }
+            PatchErrorKind::InvalidValue(e) => {
+                write!(f, "Value does not fit the target type: {}", e.message())
+            }
+        }
+    }
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at JSON Pointer \"{}\")", self.kind, self.pointer)
+    }
+}
+
+impl core::error::Error for PatchError {}
+
+fn to_document<'a, T: Facet<'a>>(target: &T) -> Result<JsonValue, PatchError> {
+    let json = to_string(target);
+    JsonValue::parse(json.as_bytes())
+        .map_err(|e| PatchError::new("", PatchErrorKind::InvalidValue(e.into_owned())))
+}
+
+fn from_document<T>(doc: &JsonValue) -> Result<T, PatchError>
+where
+    T: for<'f> Facet<'f>,
+{
+    let json = doc.to_json_string();
+    from_str::<T>(&json)
+        .map_err(|e| PatchError::new("", PatchErrorKind::InvalidValue(e.into_owned())))
+}
+
+/// Validates `value` against the given shape, to report type mismatches at
+/// the pointer of the operation that introduced them. Only the first
+/// violation is kept.
+fn check_value(pointer: &str, shape: &'static Shape, value: &JsonValue) -> Result<(), PatchError> {
+    let json = value.to_json_string();
+    validate_shape(shape, json.as_bytes()).map_err(|mut e| {
+        PatchError::new(
+            pointer,
+            PatchErrorKind::InvalidValue(e.errors.remove(0).into_owned()),
+        )
+    })
+}
+
+/// A location in the target type, as reached by following reference tokens.
+#[derive(Clone, Copy)]
+enum Loc {
+    /// A value of the given shape
+    Shape(&'static Shape),
+    /// The payload of a struct or tuple enum variant, which is not a value on
+    /// its own: it's spelled `{"Variant": {...}}` or `{"Variant": [...]}`
+    Variant(&'static Shape, &'static Variant),
+}
+
+/// How the parent of a location stores its children.
+enum Container {
+    /// A struct field or struct-variant field
+    Field {
+        owner: &'static Shape,
+        field: &'static Field,
+    },
+    /// An element of a tuple, array or tuple variant
+    FixedItem { owner: &'static Shape },
+    /// An element of a growable list
+    ListItem,
+    /// A map entry
+    MapEntry,
+    /// The variant key of an enum object
+    Variant { owner: &'static Shape },
+}
+
+/// Looks through wrappers that serialize as their contents.
+fn peel(mut shape: &'static Shape) -> &'static Shape {
+    loop {
+        shape = match shape.def {
+            Def::Option(od) => od.t(),
+            Def::SmartPointer(sp) => match sp.pointee {
+                Some(pointee) => pointee,
+                None => return shape,
+            },
+            _ => match shape.inner {
+                Some(inner) => inner(),
+                None => return shape,
+            },
+        };
+    }
+}
+
+fn find_field(fields: &'static [Field], name: &str) -> Option<&'static Field> {
+    fields.iter().find(|f| f.name == name)
+}
+
+/// Resolves one reference token below `loc`, returning how the parent stores
+/// it and the location of the child.
+fn step(loc: Loc, token: &str) -> Result<(Container, Loc), PatchErrorKind> {
+    let no_such = |shape| PatchErrorKind::NoSuchLocation {
+        token: token.to_owned(),
+        shape,
+    };
+
+    let (owner, kind, fields) = match loc {
+        Loc::Variant(owner, variant) => (owner, variant.data.kind, variant.data.fields),
+        Loc::Shape(shape) => {
+            let shape = peel(shape);
+            match shape.def {
+                Def::Struct(sd) => (shape, sd.kind, sd.fields),
+                Def::List(ld) => {
+                    if token != "-" && parse_index(token).is_none() {
+                        return Err(no_such(shape));
+                    }
+                    return Ok((Container::ListItem, Loc::Shape(ld.t())));
+                }
+                Def::Array(ad) => {
+                    return match parse_index(token) {
+                        Some(i) if i < ad.n => {
+                            Ok((Container::FixedItem { owner: shape }, Loc::Shape(ad.t())))
+                        }
+                        _ => Err(no_such(shape)),
+                    };
+                }
+                Def::Slice(sd) => {
+                    if parse_index(token).is_none() {
+                        return Err(no_such(shape));
+                    }
+                    return Ok((Container::FixedItem { owner: shape }, Loc::Shape(sd.t())));
+                }
+                Def::Map(md) => return Ok((Container::MapEntry, Loc::Shape(md.v))),
+                Def::Enum(ed) => {
+                    let Some(variant) = ed.variants.iter().find(|v| v.name == token) else {
+                        return Err(no_such(shape));
+                    };
+                    let child = if crate::variant_is_transparent(variant) {
+                        Loc::Shape(variant.data.fields[0].shape())
+                    } else {
+                        Loc::Variant(shape, variant)
+                    };
+                    return Ok((Container::Variant { owner: shape }, child));
+                }
+                _ => return Err(no_such(shape)),
+            }
+        }
+    };
+
+    match kind {
+        StructKind::Struct => match find_field(fields, token) {
+            Some(field) => Ok((Container::Field { owner, field }, Loc::Shape(field.shape()))),
+            None => Err(no_such(owner)),
+        },
+        StructKind::Tuple | StructKind::TupleStruct => match parse_index(token) {
+            Some(i) if i < fields.len() => Ok((
+                Container::FixedItem { owner },
+                Loc::Shape(fields[i].shape()),
+            )),
+            _ => Err(no_such(owner)),
+        },
+        _ => Err(no_such(owner)),
+    }
+}
+
+/// Resolves a whole pointer against the root shape.
+fn resolve(
+    root: &'static Shape,
+    tokens: &[String],
+) -> Result<(Option<Container>, Loc), PatchError> {
+    let mut loc = Loc::Shape(root);
+    let mut container = None;
+    for (depth, token) in tokens.iter().enumerate() {
+        let (c, child) = step(loc, token)
+            .map_err(|kind| PatchError::new(format_pointer(&tokens[..=depth]), kind))?;
+        container = Some(c);
+        loc = child;
+    }
+    Ok((container, loc))
+}
+
+fn lookup<'a>(doc: &'a JsonValue, tokens: &[String]) -> Option<&'a JsonValue> {
+    tokens.iter().try_fold(doc, |value, token| match value {
+        JsonValue::Object(_) => value.get(token),
+        JsonValue::Array(items) => items.get(parse_index(token)?),
+        _ => None,
+    })
+}
+
+fn lookup_mut<'a>(doc: &'a mut JsonValue, tokens: &[String]) -> Option<&'a mut JsonValue> {
+    tokens.iter().try_fold(doc, |value, token| match value {
+        JsonValue::Object(_) => value.get_mut(token),
+        JsonValue::Array(items) => items.get_mut(parse_index(token)?),
+        _ => None,
+    })
+}
+
+/// Reads a string member of a patch operation.
+fn member<'a>(op: &'a JsonValue, name: &str) -> Result<&'a str, PatchError> {
+    match op.get(name) {
+        Some(JsonValue::String(s)) => Ok(s),
+        Some(other) => Err(PatchError::new(
+            "",
+            PatchErrorKind::InvalidPatch(format!(
+                "`{name}` must be a string, got {}",
+                other.kind_name()
+            )),
+        )),
+        None => Err(PatchError::new(
+            "",
+            PatchErrorKind::InvalidPatch(format!("operation is missing `{name}`")),
+        )),
+    }
+}
+
+fn pointer_tokens(pointer: &str) -> Result<Vec<String>, PatchError> {
+    parse_pointer(pointer).ok_or_else(|| {
+        PatchError::new(
+            pointer,
+            PatchErrorKind::InvalidPatch("JSON Pointers must be empty or start with `/`".into()),
+        )
+    })
+}
+
+fn apply_operation(
+    doc: &mut JsonValue,
+    root: &'static Shape,
+    op: JsonValue,
+) -> Result<(), PatchError> {
+    let name = member(&op, "op")?.to_owned();
+    let path = member(&op, "path")?.to_owned();
+    let tokens = pointer_tokens(&path)?;
+    let value = || {
+        op.get("value").cloned().ok_or_else(|| {
+            PatchError::new(
+                path.as_str(),
+                PatchErrorKind::InvalidPatch(format!("`{name}` operation is missing `value`")),
+            )
+        })
+    };
+
+    match name.as_str() {
+        "add" => add(doc, root, &tokens, value()?),
+        "remove" => remove(doc, root, &tokens).map(drop),
+        "replace" => {
+            let value = value()?;
+            if lookup(doc, &tokens).is_none() {
+                return Err(PatchError::new(path, PatchErrorKind::PathNotFound));
+            }
+            if let (_, Loc::Shape(shape)) = resolve(root, &tokens)? {
+                check_value(&path, shape, &value)?;
+            }
+            *lookup_mut(doc, &tokens).expect("looked up above") = value;
+            Ok(())
+        }
+        "move" => {
+            let from = member(&op, "from")?;
+            let from_tokens = pointer_tokens(from)?;
+            if tokens.len() > from_tokens.len() && tokens.starts_with(&from_tokens) {
+                return Err(PatchError::new(
+                    path,
+                    PatchErrorKind::InvalidPatch(format!(
+                        "cannot move {from} into one of its own children"
+                    )),
+                ));
+            }
+            if from_tokens == tokens {
+                return match lookup(doc, &tokens) {
+                    Some(_) => Ok(()),
+                    None => Err(PatchError::new(path, PatchErrorKind::PathNotFound)),
+                };
+            }
+            let moved = remove(doc, root, &from_tokens)?;
+            add(doc, root, &tokens, moved)
+        }
+        "copy" => {
+            let from = member(&op, "from")?;
+            let from_tokens = pointer_tokens(from)?;
+            let copied = lookup(doc, &from_tokens)
+                .cloned()
+                .ok_or_else(|| PatchError::new(from, PatchErrorKind::PathNotFound))?;
+            add(doc, root, &tokens, copied)
+        }
+        "test" => {
+            let expected = value()?;
+            let actual = lookup(doc, &tokens)
+                .ok_or_else(|| PatchError::new(path.as_str(), PatchErrorKind::PathNotFound))?;
+            if *actual != expected {
+                return Err(PatchError::new(
+                    path,
+                    PatchErrorKind::TestFailed {
+                        expected: expected.to_json_string(),
+                        actual: actual.to_json_string(),
+                    },
+                ));
+            }
+            Ok(())
+        }
+        other => Err(PatchError::new(
+            path,
+            PatchErrorKind::InvalidPatch(format!("unknown operation `{other}`")),
+        )),
+    }
+}
+
+fn add(
+    doc: &mut JsonValue,
+    root: &'static Shape,
+    tokens: &[String],
+    value: JsonValue,
+) -> Result<(), PatchError> {
+    let pointer = format_pointer(tokens);
+    let (container, loc) = resolve(root, tokens)?;
+    if let Loc::Shape(shape) = loc {
+        check_value(&pointer, shape, &value)?;
+    }
+
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        *doc = value;
+        return Ok(());
+    };
+    let parent = lookup_mut(doc, parent_tokens).ok_or_else(|| {
+        PatchError::new(format_pointer(parent_tokens), PatchErrorKind::PathNotFound)
+    })?;
+
+    match (container.expect("non-empty pointer"), parent) {
+        (Container::ListItem, JsonValue::Array(items)) => {
+            let index = if last == "-" {
+                items.len()
+            } else {
+                parse_index(last)
+                    .filter(|i| *i <= items.len())
+                    .ok_or_else(|| {
+                        PatchError::new(pointer.as_str(), PatchErrorKind::PathNotFound)
+                    })?
+            };
+            items.insert(index, value);
+        }
+        (Container::FixedItem { owner }, _) => {
+            return Err(PatchError::new(
+                pointer,
+                PatchErrorKind::FixedLength { shape: owner },
+            ));
+        }
+        (Container::Variant { owner }, _) => {
+            return Err(PatchError::new(
+                pointer,
+                PatchErrorKind::InvalidPatch(format!(
+                    "the variant of {owner} can only be changed by replacing the whole value"
+                )),
+            ));
+        }
+        (Container::Field { .. } | Container::MapEntry, parent @ JsonValue::Object(_)) => {
+            match parent.get_mut(last) {
+                Some(slot) => *slot = value,
+                None => {
+                    let JsonValue::Object(entries) = parent else {
+                        unreachable!()
+                    };
+                    entries.push((last.clone(), value));
+                }
+            }
+        }
+        // `None` options serialize as `null`: adding below them starts from scratch
+        (Container::Field { .. } | Container::MapEntry, parent @ JsonValue::Null) => {
+            *parent = JsonValue::Object(alloc::vec![(last.clone(), value)]);
+        }
+        _ => return Err(PatchError::new(pointer, PatchErrorKind::PathNotFound)),
+    }
+    Ok(())
+}
+
+fn remove(
+    doc: &mut JsonValue,
+    root: &'static Shape,
+    tokens: &[String],
+) -> Result<JsonValue, PatchError> {
+    let pointer = format_pointer(tokens);
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        return Err(PatchError::new(
+            pointer,
+            PatchErrorKind::NotRemovable { shape: root },
+        ));
+    };
+    let (container, _) = resolve(root, tokens)?;
+    let parent = lookup_mut(doc, parent_tokens)
+        .ok_or_else(|| PatchError::new(pointer.as_str(), PatchErrorKind::PathNotFound))?;
+
+    match (container.expect("non-empty pointer"), parent) {
+        (Container::ListItem, JsonValue::Array(items)) => {
+            match parse_index(last).filter(|i| *i < items.len()) {
+                Some(index) => Ok(items.remove(index)),
+                None => Err(PatchError::new(pointer, PatchErrorKind::PathNotFound)),
+            }
+        }
+        (Container::FixedItem { owner }, _) => Err(PatchError::new(
+            pointer,
+            PatchErrorKind::FixedLength { shape: owner },
+        )),
+        (Container::Variant { owner }, _) => Err(PatchError::new(
+            pointer,
+            PatchErrorKind::NotRemovable { shape: owner },
+        )),
+        (Container::MapEntry, JsonValue::Object(entries)) => {
+            match entries.iter().position(|(k, _)| k == last) {
+                Some(index) => Ok(entries.remove(index).1),
+                None => Err(PatchError::new(pointer, PatchErrorKind::PathNotFound)),
+            }
+        }
+        (Container::Field { owner, field }, JsonValue::Object(entries)) => {
+            let Some(index) = entries.iter().position(|(k, _)| k == last) else {
+                return Err(PatchError::new(pointer, PatchErrorKind::PathNotFound));
+            };
+            remove_field(&pointer, owner, field, entries, index)
+        }
+        _ => Err(PatchError::new(pointer, PatchErrorKind::PathNotFound)),
+    }
+}
+
+/// Removes a struct field from its serialized object. Fields with a default
+/// are dropped and filled back in on deserialization; `Option` fields become
+/// `null`. Anything else is required and can't be removed.
+fn remove_field(
+    pointer: &str,
+    owner: &'static Shape,
+    field: &'static Field,
+    entries: &mut Vec<(String, JsonValue)>,
+    index: usize,
+) -> Result<JsonValue, PatchError> {
+    if field.maybe_default_fn().is_some() || owner.has_default_attr() {
+        Ok(entries.remove(index).1)
+    } else if matches!(field.shape().def, Def::Option(_)) {
+        Ok(core::mem::replace(&mut entries[index].1, JsonValue::Null))
+    } else {
+        Err(PatchError::new(
+            pointer,
+            PatchErrorKind::NotRemovable {
+                shape: field.shape(),
+            },
+        ))
+    }
+}
+
+fn merge(
+    target: &mut JsonValue,
+    patch: JsonValue,
+    loc: Loc,
+    path: &mut Vec<String>,
+) -> Result<(), PatchError> {
+    let JsonValue::Object(members) = patch else {
+        if let Loc::Shape(shape) = loc {
+            check_value(&format_pointer(path), shape, &patch)?;
+        }
+        *target = patch;
+        return Ok(());
+    };
+
+    if !matches!(target, JsonValue::Object(_)) {
+        *target = JsonValue::Object(Vec::new());
+    }
+
+    for (key, value) in members {
+        path.push(key);
+        let key = path.last().expect("just pushed");
+        let (container, child) =
+            step(loc, key).map_err(|kind| PatchError::new(format_pointer(path), kind))?;
+        if let Container::ListItem | Container::FixedItem { .. } = container {
+            return Err(PatchError::new(
+                format_pointer(&path[..path.len() - 1]),
+                PatchErrorKind::InvalidPatch(
+                    "arrays can only be replaced as a whole by a merge patch".into(),
+                ),
+            ));
+        }
+
+        let JsonValue::Object(entries) = &mut *target else {
+            unreachable!()
+        };
+        let existing = entries.iter().position(|(k, _)| k == key);
+
+        if let JsonValue::Null = value {
+            if let Some(index) = existing {
+                let pointer = format_pointer(path);
+                match container {
+                    Container::Field { owner, field } => {
+                        remove_field(&pointer, owner, field, entries, index)?;
+                    }
+                    Container::MapEntry => {
+                        entries.remove(index);
+                    }
+                    Container::Variant { owner } => {
+                        return Err(PatchError::new(
+                            pointer,
+                            PatchErrorKind::NotRemovable { shape: owner },
+                        ));
+                    }
+                    Container::ListItem | Container::FixedItem { .. } => unreachable!(),
+                }
+            }
+        } else {
+            let index = existing.unwrap_or_else(|| {
+                entries.push((key.clone(), JsonValue::Null));
+                entries.len() - 1
+            });
+            merge(&mut entries[index].1, value, child, path)?;
+        }
+        path.pop();
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! RFC 6901 JSON Pointer helpers.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+///
+/// Returns `None` if the pointer is neither empty nor starts with `/`.
+pub(crate) fn parse_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    let rest = pointer.strip_prefix('/')?;
+    Some(
+        rest.split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+/// Joins reference tokens back into a JSON Pointer, escaping `~` and `/`.
+pub(crate) fn format_pointer<S: AsRef<str>>(tokens: &[S]) -> String {
+    let mut out = String::new();
+    for token in tokens {
+        out.push('/');
+        for c in token.as_ref().chars() {
+            match c {
+                '~' => out.push_str("~0"),
+                '/' => out.push_str("~1"),
+                c => out.push(c),
+            }
+        }
+    }
+    out
+}
+
+/// Interprets a reference token as an array index, per RFC 6901: no sign,
+/// no leading zeros.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || (token.len() > 1 && token.starts_with('0'))
+        || !token.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    token.parse().ok()
+}
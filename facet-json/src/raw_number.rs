@@ -0,0 +1,163 @@
+//! A number type that keeps the digits of the JSON input.
+
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use facet_core::{
+    Def, Facet, PtrConst, PtrMut, PtrUninit, ScalarAffinity, ScalarDef, Shape, TryFromError,
+    value_vtable,
+};
+
+/// A JSON number, kept exactly as it was written.
+///
+/// Numbers normally go through `f64`, `i64` or `u64`, which rounds long
+/// decimals and can't represent every integer. A `RawNumber` field instead
+/// keeps the original digits: `19.90` stays `19.90`, and serializing writes
+/// it back out verbatim. Convert it with [`RawNumber::parse`] to whichever
+/// numeric or decimal type fits the value.
+///
+/// ```
+/// use facet::Facet;
+/// use facet_json::RawNumber;
+///
+/// #[derive(Facet)]
+/// struct Invoice {
+///     total: RawNumber,
+/// }
+///
+/// let invoice: Invoice = facet_json::from_str(r#"{"total":19.90}"#).unwrap();
+/// assert_eq!(invoice.total.as_str(), "19.90");
+/// assert_eq!(invoice.total.parse::<f64>().unwrap(), 19.9);
+/// assert_eq!(facet_json::to_string(&invoice), r#"{"total":19.90}"#);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RawNumber(String);
+
+impl RawNumber {
+    /// The number exactly as it was written
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the digits as a `String`
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Parses the digits into another type, e.g. `u128` or a decimal type
+    pub fn parse<T: FromStr>(&self) -> Result<T, T::Err> {
+        self.0.parse()
+    }
+}
+
+impl fmt::Display for RawNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for RawNumber {
+    type Err = InvalidNumber;
+
+    /// Accepts anything the JSON grammar calls a number, and nothing else
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if is_json_number(s.as_bytes()) {
+            Ok(RawNumber(s.into()))
+        } else {
+            Err(InvalidNumber)
+        }
+    }
+}
+
+/// Error returned when a string isn't a valid JSON number
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidNumber;
+
+impl fmt::Display for InvalidNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not a valid JSON number")
+    }
+}
+
+impl core::error::Error for InvalidNumber {}
+
+/// Checks `s` against RFC 8259's `number` production.
+fn is_json_number(s: &[u8]) -> bool {
+    fn digits(s: &[u8], mut i: usize) -> usize {
+        while s.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        i
+    }
+
+    let mut i = 0;
+    if s.first() == Some(&b'-') {
+        i += 1;
+    }
+    i = match s.get(i) {
+        Some(b'0') => i + 1,
+        Some(b'1'..=b'9') => digits(s, i + 1),
+        _ => return false,
+    };
+    if s.get(i) == Some(&b'.') {
+        let end = digits(s, i + 1);
+        if end == i + 1 {
+            return false;
+        }
+        i = end;
+    }
+    if matches!(s.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(s.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        let end = digits(s, i);
+        if end == i {
+            return false;
+        }
+        i = end;
+    }
+    i == s.len()
+}
+
+#[allow(unsafe_code)]
+unsafe impl Facet<'_> for RawNumber {
+    const SHAPE: &'static Shape = &const {
+        // Strings holding a number are accepted too, for formats and
+        // callers that carry numbers as text
+        unsafe fn try_from<'dst>(
+            src_ptr: PtrConst<'_>,
+            src_shape: &'static Shape,
+            dst: PtrUninit<'dst>,
+        ) -> Result<PtrMut<'dst>, TryFromError> {
+            if src_shape.id != <String as Facet>::SHAPE.id {
+                return Err(TryFromError::UnsupportedSourceShape {
+                    src_shape,
+                    expected: &[<String as Facet>::SHAPE],
+                });
+            }
+            // Take the string over: whoever put it gave it up
+            let s = unsafe { src_ptr.read::<String>() };
+            match s.parse::<RawNumber>() {
+                Ok(n) => Ok(unsafe { dst.put(n) }),
+                Err(_) => Err(TryFromError::Generic("not a valid JSON number")),
+            }
+        }
+
+        Shape::builder_for_sized::<Self>()
+            .def(Def::Scalar(
+                ScalarDef::builder()
+                    .affinity(ScalarAffinity::other().build())
+                    .build(),
+            ))
+            .vtable(
+                &const {
+                    let mut vtable = value_vtable!(RawNumber, |f, _opts| write!(f, "RawNumber"));
+                    vtable.try_from = Some(try_from);
+                    vtable
+                },
+            )
+            .build()
+    };
+}
@@ -3,7 +3,7 @@ use facet_core::{Def, Facet, FieldAttribute, StructKind};
 use facet_reflect::Peek;
 use std::io::{self, Write};
 
-use crate::First;
+use crate::{First, RawNumber};
 
 /// Serializes a value to JSON
 pub fn to_string<'a, T: Facet<'a>>(value: &T) -> String {
@@ -38,6 +38,17 @@ fn serialize<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
         StructKind::{Tuple, TupleStruct},
     };
 
+    // Smart pointers are written as their pointee. This comes first since
+    // `Arc` and `Rc` are also transparent wrappers around it.
+    if let Def::SmartPointer(_) = peek.shape().def {
+        return serialize_smart_pointer(peek, writer);
+    }
+
+    // Transparent wrappers are written as the value they wrap
+    if let Some(inner) = peek.borrow_inner() {
+        return serialize(&inner, writer);
+    }
+
     match peek.shape().def {
         Def::Scalar(_) => serialize_scalar(peek, writer),
         Def::Struct(StructDef {
@@ -46,6 +57,8 @@ fn serialize<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
         }) => serialize_tuple(peek, writer),
         Def::Struct(_) => serialize_struct(peek, writer),
         Def::List(_) => serialize_list(peek, writer),
+        Def::Array(_) => serialize_array(peek, writer),
+        Def::Slice(_) => serialize_slice(peek, writer),
         Def::Map(_) => serialize_map(peek, writer),
         Def::Enum(_) => serialize_enum(peek, writer),
         Def::Option(_) => serialize_option(peek, writer),
@@ -85,6 +98,9 @@ fn serialize_scalar<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result
     } else if peek.shape().is_type::<u64>() {
         let value = peek.get::<u64>().unwrap();
         write!(writer, "{}", value)
+    } else if peek.shape().is_type::<u128>() {
+        let value = peek.get::<u128>().unwrap();
+        write!(writer, "{}", value)
     } else if peek.shape().is_type::<usize>() {
         let value = peek.get::<usize>().unwrap();
         write!(writer, "{}", value)
@@ -100,6 +116,9 @@ fn serialize_scalar<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result
     } else if peek.shape().is_type::<i64>() {
         let value = peek.get::<i64>().unwrap();
         write!(writer, "{}", value)
+    } else if peek.shape().is_type::<i128>() {
+        let value = peek.get::<i128>().unwrap();
+        write!(writer, "{}", value)
     } else if peek.shape().is_type::<isize>() {
         let value = peek.get::<isize>().unwrap();
         write!(writer, "{}", value)
@@ -117,6 +136,9 @@ fn serialize_scalar<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result
     } else if peek.shape().is_type::<NonZero<u64>>() {
         let value = peek.get::<NonZero<u64>>().unwrap();
         write!(writer, "{}", value)
+    } else if peek.shape().is_type::<NonZero<u128>>() {
+        let value = peek.get::<NonZero<u128>>().unwrap();
+        write!(writer, "{}", value)
     } else if peek.shape().is_type::<NonZero<usize>>() {
         let value = peek.get::<NonZero<usize>>().unwrap();
         write!(writer, "{}", value)
@@ -132,17 +154,32 @@ fn serialize_scalar<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result
     } else if peek.shape().is_type::<NonZero<i64>>() {
         let value = peek.get::<NonZero<i64>>().unwrap();
         write!(writer, "{}", value)
+    } else if peek.shape().is_type::<NonZero<i128>>() {
+        let value = peek.get::<NonZero<i128>>().unwrap();
+        write!(writer, "{}", value)
     } else if peek.shape().is_type::<NonZero<isize>>() {
         let value = peek.get::<NonZero<isize>>().unwrap();
         write!(writer, "{}", value)
     }
-    // Float types
+    // Float types, laid out like ECMAScript does so that very large and very
+    // small magnitudes use an exponent instead of dozens of digits
     else if peek.shape().is_type::<f32>() {
         let value = peek.get::<f32>().unwrap();
-        write!(writer, "{}", value)
+        match crate::canonical::format_f32(*value) {
+            Some(formatted) => write!(writer, "{}", formatted),
+            None => write!(writer, "{}", value),
+        }
     } else if peek.shape().is_type::<f64>() {
         let value = peek.get::<f64>().unwrap();
-        write!(writer, "{}", value)
+        match crate::canonical::format_f64(*value) {
+            Some(formatted) => write!(writer, "{}", formatted),
+            None => write!(writer, "{}", value),
+        }
+    }
+    // Numbers kept as written
+    else if peek.shape().is_type::<RawNumber>() {
+        let value = peek.get::<RawNumber>().unwrap();
+        write!(writer, "{}", value.as_str())
     } else {
         Err(io::Error::new(
             io::ErrorKind::Other,
@@ -211,6 +248,63 @@ fn serialize_list<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<(
     Ok(())
 }
 
+/// Serializes a fixed-size array to JSON
+fn serialize_array<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
+    let array_peek = peek
+        .into_array()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Not an array: {}", e)))?;
+
+    write!(writer, "[")?;
+
+    for (first, item_peek) in array_peek.iter().with_first() {
+        if !first {
+            write!(writer, ",")?;
+        }
+
+        serialize(&item_peek, writer)?;
+    }
+
+    write!(writer, "]")?;
+
+    Ok(())
+}
+
+/// Serializes a slice to JSON
+fn serialize_slice<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
+    let slice_peek = peek
+        .into_slice()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Not a slice: {}", e)))?;
+
+    write!(writer, "[")?;
+
+    for (first, item_peek) in slice_peek.iter().with_first() {
+        if !first {
+            write!(writer, ",")?;
+        }
+
+        serialize(&item_peek, writer)?;
+    }
+
+    write!(writer, "]")?;
+
+    Ok(())
+}
+
+/// Serializes a smart pointer (`Box`, `Arc`, `Rc`, ...) as its pointee
+fn serialize_smart_pointer<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
+    let pointer_peek = peek
+        .into_smart_pointer()
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Not a smart pointer: {}", e)))?;
+
+    let pointee = pointer_peek.borrow_inner().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Other,
+            format!("Cannot borrow the pointee of {}", peek.shape()),
+        )
+    })?;
+    serialize(&pointee, writer)
+}
+
 /// Serializes a tuple (struct) to JSON
 fn serialize_tuple<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
     let struct_peek = peek
@@ -245,26 +339,7 @@ fn serialize_map<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()
             write!(writer, ",")?;
         }
 
-        // For map, keys must be converted to strings
-        match key.shape().def {
-            Def::Scalar(_) => {
-                // Try to convert key to string
-                if key.shape().is_type::<String>() {
-                    let key_str = key.get::<String>().unwrap();
-                    write_json_string(writer, key_str)?;
-                } else {
-                    // For other scalar types, use their Display implementation
-                    write!(writer, "\"{}\"", key)?;
-                }
-            }
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Map keys must be scalar types, got: {}", key.shape()),
-                ));
-            }
-        }
-
+        serialize_map_key(&key, writer)?;
         write!(writer, ":")?;
 
         // Write map value
@@ -276,6 +351,39 @@ fn serialize_map<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()
     Ok(())
 }
 
+/// Serializes a map key. JSON object keys are always strings, so scalars
+/// other than strings (integers, booleans, chars, ...) are written through
+/// their `Display` implementation, and transparent wrappers as the value they
+/// wrap. The deserializer parses them back with the key type's `FromStr`.
+fn serialize_map_key<W: Write>(key: &Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
+    if let Some(inner) = key.borrow_inner() {
+        return serialize_map_key(&inner, writer);
+    }
+
+    let shape = key.shape();
+    if !matches!(shape.def, Def::Scalar(_)) {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("Map keys must be scalar types, got: {}", shape),
+        ));
+    }
+
+    if shape.is_type::<String>() {
+        write_json_string(writer, key.get::<String>().unwrap())
+    } else if shape.is_type::<&str>() {
+        write_json_string(writer, key.get::<&str>().unwrap())
+    } else if shape.is_type::<alloc::borrow::Cow<'_, str>>() {
+        write_json_string(writer, key.get::<alloc::borrow::Cow<'_, str>>().unwrap())
+    } else if shape.vtable.display.is_some() {
+        write_json_string(writer, &key.to_string())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("Map key type {} cannot be written as a string", shape),
+        ))
+    }
+}
+
 /// Serializes an enum to JSON
 fn serialize_enum<W: Write>(peek: &Peek<'_, '_>, writer: &mut W) -> io::Result<()> {
     let enum_peek = peek
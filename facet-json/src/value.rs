@@ -0,0 +1,296 @@
+//! A small, order-preserving JSON document tree, for operations that need to
+//! look at a whole document at once rather than stream it into a `Wip`.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::{JsonError, JsonErrorKind, Span, Spanned, Token, Tokenizer};
+
+/// An untyped JSON value.
+#[derive(Debug, Clone)]
+pub(crate) enum JsonValue {
+    Null,
+    Bool(bool),
+    /// A number, as it was written: its digits are kept, so that no
+    /// precision is lost on values a `u64` or an `f64` can't hold.
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /// Parses a complete JSON document.
+    pub(crate) fn parse(input: &[u8]) -> Result<JsonValue, JsonError<'_>> {
+        enum Frame {
+            Array(Vec<JsonValue>),
+            Object(Vec<(String, JsonValue)>, String),
+        }
+
+        let mut parser = Parser {
+            input,
+            tokenizer: Tokenizer::new(input),
+            peeked: None,
+            last_span: Span::new(0, 0),
+        };
+        let mut stack: Vec<Frame> = Vec::new();
+
+        'value: loop {
+            let token = parser.next()?;
+            let mut value = match token.node {
+                Token::LBrace => {
+                    let token = parser.next()?;
+                    match token.node {
+                        Token::RBrace => JsonValue::Object(Vec::new()),
+                        Token::String(key) => {
+                            parser.expect_colon()?;
+                            stack.push(Frame::Object(Vec::new(), key));
+                            continue 'value;
+                        }
+                        other => {
+                            return Err(parser.unexpected(other, "object key or closing brace"));
+                        }
+                    }
+                }
+                Token::LBracket => {
+                    let token = parser.next()?;
+                    if token.node == Token::RBracket {
+                        JsonValue::Array(Vec::new())
+                    } else {
+                        parser.peeked = Some(token);
+                        stack.push(Frame::Array(Vec::new()));
+                        continue 'value;
+                    }
+                }
+                Token::String(s) => JsonValue::String(s),
+                Token::F64(_) | Token::I64(_) | Token::U64(_) => {
+                    JsonValue::Number(parser.tokenizer.lexeme(token.span).into())
+                }
+                Token::True => JsonValue::Bool(true),
+                Token::False => JsonValue::Bool(false),
+                Token::Null => JsonValue::Null,
+                Token::EOF => return Err(parser.error(JsonErrorKind::UnexpectedEof("in value"))),
+                other => return Err(parser.unexpected(other, "value")),
+            };
+
+            // Attach the finished value to its parent, closing as many
+            // containers as the input tells us to.
+            loop {
+                match stack.last_mut() {
+                    None => {
+                        let token = parser.next()?;
+                        if token.node != Token::EOF {
+                            return Err(parser.unexpected(token.node, "end of input"));
+                        }
+                        return Ok(value);
+                    }
+                    Some(Frame::Array(items)) => {
+                        items.push(value);
+                        let token = parser.next()?;
+                        match token.node {
+                            Token::Comma => continue 'value,
+                            Token::RBracket => {
+                                let Some(Frame::Array(items)) = stack.pop() else {
+                                    unreachable!()
+                                };
+                                value = JsonValue::Array(items);
+                            }
+                            other => {
+                                return Err(parser.unexpected(other, "comma or closing bracket"));
+                            }
+                        }
+                    }
+                    Some(Frame::Object(entries, key)) => {
+                        entries.push((core::mem::take(key), value));
+                        let token = parser.next()?;
+                        match token.node {
+                            Token::Comma => {
+                                let token = parser.next()?;
+                                let Token::String(next_key) = token.node else {
+                                    return Err(parser.unexpected(token.node, "object key"));
+                                };
+                                parser.expect_colon()?;
+                                *key = next_key;
+                                continue 'value;
+                            }
+                            Token::RBrace => {
+                                let Some(Frame::Object(entries, _)) = stack.pop() else {
+                                    unreachable!()
+                                };
+                                value = JsonValue::Object(entries);
+                            }
+                            other => return Err(parser.unexpected(other, "comma or closing brace")),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns a short description of the kind of value, for error messages.
+    pub(crate) fn kind_name(&self) -> &'static str {
+        match self {
+            JsonValue::Null => "null",
+            JsonValue::Bool(_) => "boolean",
+            JsonValue::Number(_) => "number",
+            JsonValue::String(_) => "string",
+            JsonValue::Array(_) => "array",
+            JsonValue::Object(_) => "object",
+        }
+    }
+
+    /// Looks up a key in an object value.
+    pub(crate) fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Looks up a key in an object value, mutably.
+    pub(crate) fn get_mut(&mut self, key: &str) -> Option<&mut JsonValue> {
+        match self {
+            JsonValue::Object(entries) => {
+                entries.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Writes the value as compact JSON.
+    pub(crate) fn write<W: Write>(&self, w: &mut W) -> fmt::Result {
+        match self {
+            JsonValue::Null => w.write_str("null"),
+            JsonValue::Bool(b) => w.write_str(if *b { "true" } else { "false" }),
+            JsonValue::Number(n) => w.write_str(n),
+            JsonValue::String(s) => write_escaped(w, s),
+            JsonValue::Array(items) => {
+                w.write_char('[')?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        w.write_char(',')?;
+                    }
+                    item.write(w)?;
+                }
+                w.write_char(']')
+            }
+            JsonValue::Object(entries) => {
+                w.write_char('{')?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        w.write_char(',')?;
+                    }
+                    write_escaped(w, key)?;
+                    w.write_char(':')?;
+                    value.write(w)?;
+                }
+                w.write_char('}')
+            }
+        }
+    }
+
+    /// Renders the value as a compact JSON string.
+    pub(crate) fn to_json_string(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out)
+            .expect("writing to a String cannot fail");
+        out
+    }
+}
+
+/// Structural equality, as defined by RFC 6902's `test` operation: numbers
+/// compare by value regardless of how they were written, and object member
+/// order does not matter.
+impl PartialEq for JsonValue {
+    fn eq(&self, other: &Self) -> bool {
+        use JsonValue::*;
+        match (self, other) {
+            (Null, Null) => true,
+            (Bool(a), Bool(b)) => a == b,
+            (String(a), String(b)) => a == b,
+            (Number(a), Number(b)) => numbers_equal(a, b),
+            (Array(a), Array(b)) => a == b,
+            (Object(a), Object(b)) => {
+                a.len() == b.len() && a.iter().all(|(k, v)| other.get(k) == Some(v))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Compares two number lexemes by value: integers exactly, as long as they
+/// fit in an `i128`, and anything else as `f64`s.
+fn numbers_equal(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    match (a.parse::<i128>(), b.parse::<i128>()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        },
+    }
+}
+
+/// Writes a JSON string literal with the escapes required by RFC 8259.
+pub(crate) fn write_escaped<W: Write>(w: &mut W, s: &str) -> fmt::Result {
+    w.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            '\u{08}' => w.write_str("\\b")?,
+            '\u{0C}' => w.write_str("\\f")?,
+            c if c.is_control() => write!(w, "\\u{:04x}", c as u32)?,
+            c => w.write_char(c)?,
+        }
+    }
+    w.write_char('"')
+}
+
+struct Parser<'input> {
+    input: &'input [u8],
+    tokenizer: Tokenizer<'input>,
+    peeked: Option<Spanned<Token>>,
+    last_span: Span,
+}
+
+impl<'input> Parser<'input> {
+    fn next(&mut self) -> Result<Spanned<Token>, JsonError<'input>> {
+        if let Some(token) = self.peeked.take() {
+            self.last_span = token.span;
+            return Ok(token);
+        }
+        match self.tokenizer.next_token() {
+            Ok(token) => {
+                self.last_span = token.span;
+                Ok(token)
+            }
+            Err(e) => {
+                self.last_span = e.span;
+                Err(self.error(JsonErrorKind::SyntaxError(e.kind)))
+            }
+        }
+    }
+
+    fn expect_colon(&mut self) -> Result<(), JsonError<'input>> {
+        let token = self.next()?;
+        if token.node != Token::Colon {
+            return Err(self.unexpected(token.node, "colon"));
+        }
+        Ok(())
+    }
+
+    fn error(&self, kind: JsonErrorKind) -> JsonError<'input> {
+        JsonError::new(kind, self.input, self.last_span, "$".into())
+    }
+
+    fn unexpected(&self, got: Token, wanted: &'static str) -> JsonError<'input> {
+        self.error(JsonErrorKind::UnexpectedToken { got, wanted })
+    }
+}
@@ -1,4 +1,5 @@
 mod err;
+mod patch;
 mod read;
 mod transparent;
 mod write;
@@ -0,0 +1,239 @@
+#![cfg(feature = "std")]
+
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_json::{PatchErrorKind, RawNumber, apply_merge_patch, apply_patch};
+
+#[derive(Facet, Debug, PartialEq, Clone)]
+struct Address {
+    city: String,
+    zip: Option<String>,
+}
+
+#[derive(Facet, Debug, PartialEq, Clone)]
+struct User {
+    name: String,
+    age: u32,
+    nickname: Option<String>,
+    tags: Vec<String>,
+    scores: HashMap<String, u32>,
+    address: Address,
+}
+
+fn alice() -> User {
+    User {
+        name: "Alice".to_string(),
+        age: 30,
+        nickname: Some("Al".to_string()),
+        tags: vec!["admin".to_string(), "ops".to_string()],
+        scores: HashMap::from([("math".to_string(), 90)]),
+        address: Address {
+            city: "Lyon".to_string(),
+            zip: None,
+        },
+    }
+}
+
+#[test]
+fn patch_replace_add_remove() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let mut user = alice();
+    apply_patch(
+        &mut user,
+        r#"[
+            {"op": "replace", "path": "/age", "value": 31},
+            {"op": "add", "path": "/tags/1", "value": "dev"},
+            {"op": "add", "path": "/tags/-", "value": "oncall"},
+            {"op": "remove", "path": "/tags/0"},
+            {"op": "add", "path": "/scores/art", "value": 75},
+            {"op": "remove", "path": "/scores/math"},
+            {"op": "remove", "path": "/nickname"},
+            {"op": "add", "path": "/address/zip", "value": "69001"}
+        ]"#,
+    )?;
+
+    assert_eq!(user.age, 31);
+    assert_eq!(user.tags, vec!["dev", "ops", "oncall"]);
+    assert_eq!(user.scores, HashMap::from([("art".to_string(), 75)]));
+    assert_eq!(user.nickname, None);
+    assert_eq!(user.address.zip.as_deref(), Some("69001"));
+    Ok(())
+}
+
+#[test]
+fn patch_move_copy_test() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let mut user = alice();
+    apply_patch(
+        &mut user,
+        r#"[
+            {"op": "test", "path": "/address/city", "value": "Lyon"},
+            {"op": "copy", "from": "/address/city", "path": "/tags/0"},
+            {"op": "move", "from": "/nickname", "path": "/address/zip"},
+            {"op": "replace", "path": "/name", "value": "Alicia"}
+        ]"#,
+    )?;
+
+    assert_eq!(user.tags, vec!["Lyon", "admin", "ops"]);
+    assert_eq!(user.nickname, None);
+    assert_eq!(user.address.zip.as_deref(), Some("Al"));
+    assert_eq!(user.name, "Alicia");
+    Ok(())
+}
+
+#[test]
+fn patch_keeps_numbers_exact() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct Invoice {
+        id: u128,
+        total: RawNumber,
+        note: String,
+    }
+
+    let mut invoice = Invoice {
+        id: u128::MAX,
+        total: "19.90".parse()?,
+        note: String::new(),
+    };
+    apply_patch(
+        &mut invoice,
+        r#"[
+            {"op": "test", "path": "/total", "value": 19.9},
+            {"op": "replace", "path": "/note", "value": "paid"}
+        ]"#,
+    )?;
+
+    assert_eq!(invoice.id, u128::MAX);
+    assert_eq!(invoice.total.as_str(), "19.90");
+    assert_eq!(invoice.note, "paid");
+    Ok(())
+}
+
+#[test]
+fn patch_failed_test_leaves_target_untouched() {
+    facet_testhelpers::setup();
+
+    let mut user = alice();
+    let err = apply_patch(
+        &mut user,
+        r#"[
+            {"op": "replace", "path": "/age", "value": 99},
+            {"op": "test", "path": "/tags/1", "value": "dev"}
+        ]"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(err.pointer, "/tags/1");
+    assert!(matches!(err.kind, PatchErrorKind::TestFailed { .. }));
+    assert_eq!(user, alice());
+}
+
+#[test]
+fn patch_rejects_removing_required_field() {
+    facet_testhelpers::setup();
+
+    let mut user = alice();
+    let err = apply_patch(&mut user, r#"[{"op": "remove", "path": "/address/city"}]"#).unwrap_err();
+
+    assert_eq!(err.pointer, "/address/city");
+    assert!(matches!(err.kind, PatchErrorKind::NotRemovable { .. }));
+}
+
+#[test]
+fn patch_rejects_unknown_field() {
+    facet_testhelpers::setup();
+
+    let mut user = alice();
+    let err = apply_patch(
+        &mut user,
+        r#"[{"op": "add", "path": "/email", "value": "a@b.c"}]"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(err.pointer, "/email");
+    assert!(matches!(err.kind, PatchErrorKind::NoSuchLocation { .. }));
+}
+
+#[test]
+fn patch_rejects_mistyped_value() {
+    facet_testhelpers::setup();
+
+    let mut user = alice();
+    let err = apply_patch(
+        &mut user,
+        r#"[{"op": "replace", "path": "/age", "value": "old"}]"#,
+    )
+    .unwrap_err();
+
+    assert_eq!(err.pointer, "/age");
+    assert!(matches!(err.kind, PatchErrorKind::InvalidValue(_)));
+}
+
+#[test]
+fn patch_reports_missing_path() {
+    facet_testhelpers::setup();
+
+    let mut user = alice();
+    let err = apply_patch(&mut user, r#"[{"op": "remove", "path": "/tags/5"}]"#).unwrap_err();
+
+    assert_eq!(err.pointer, "/tags/5");
+    assert!(matches!(err.kind, PatchErrorKind::PathNotFound));
+}
+
+#[test]
+fn patch_rejects_resizing_fixed_length() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct Rgb {
+        channels: [u8; 3],
+    }
+
+    let mut rgb = Rgb {
+        channels: [1, 2, 3],
+    };
+    let err = apply_patch(&mut rgb, r#"[{"op": "remove", "path": "/channels/0"}]"#).unwrap_err();
+    assert!(matches!(err.kind, PatchErrorKind::FixedLength { .. }));
+}
+
+#[test]
+fn merge_patch() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let mut user = alice();
+    apply_merge_patch(
+        &mut user,
+        r#"{
+            "age": 32,
+            "nickname": null,
+            "tags": ["x"],
+            "scores": {"math": null, "art": 12},
+            "address": {"zip": "69002"}
+        }"#,
+    )?;
+
+    assert_eq!(user.age, 32);
+    assert_eq!(user.nickname, None);
+    assert_eq!(user.tags, vec!["x"]);
+    assert_eq!(user.scores, HashMap::from([("art".to_string(), 12)]));
+    assert_eq!(user.address.city, "Lyon");
+    assert_eq!(user.address.zip.as_deref(), Some("69002"));
+    Ok(())
+}
+
+#[test]
+fn merge_patch_rejects_removing_required_field() {
+    facet_testhelpers::setup();
+
+    let mut user = alice();
+    let err = apply_merge_patch(&mut user, r#"{"address": {"city": null}}"#).unwrap_err();
+
+    assert_eq!(err.pointer, "/address/city");
+    assert!(matches!(err.kind, PatchErrorKind::NotRemovable { .. }));
+    assert_eq!(user, alice());
+}
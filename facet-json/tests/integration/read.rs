@@ -1,15 +1,19 @@
+mod all_errors;
 mod bool;
 mod deny_unknown_and_default;
 mod diagnostics;
 mod enums;
 mod eof_and_nulls;
 mod hashmap;
+mod limits;
 mod nonzero;
 mod numbers;
 mod option;
+mod pointer;
 mod primitives;
 mod rename;
 mod skip_unknown_fields;
 mod structs;
 mod tuple;
+mod validate;
 mod vec;
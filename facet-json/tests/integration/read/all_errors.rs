@@ -0,0 +1,157 @@
+use facet::Facet;
+use facet_json::{JsonErrorKind, from_str_all_errors};
+
+#[derive(Facet, Debug)]
+#[facet(deny_unknown_fields)]
+struct Server {
+    host: String,
+    port: u16,
+    tls: bool,
+}
+
+#[derive(Facet, Debug)]
+struct Config {
+    name: String,
+    servers: Vec<Server>,
+    retries: u32,
+}
+
+#[test]
+fn collects_every_error() {
+    facet_testhelpers::setup();
+
+    let json = r#"{
+        "name": "prod",
+        "servers": [
+            {"host": "a", "port": "eighty", "tls": true},
+            {"host": "b", "port": 443, "tls": true, "colour": "blue"},
+            {"host": "c", "port": 8080}
+        ],
+        "retries": [3]
+    }"#;
+
+    let err = from_str_all_errors::<Config>(json).unwrap_err();
+    let found: Vec<_> = err
+        .errors
+        .iter()
+        .map(|e| (e.path.as_str(), &e.kind))
+        .collect();
+    assert_eq!(found.len(), 4, "{found:#?}");
+
+    assert_eq!(found[0].0, "$.servers[0].port");
+    assert!(matches!(found[0].1, JsonErrorKind::ReflectError(_)));
+    assert_eq!(
+        &json[err.errors[0].span.start..err.errors[0].span.end()],
+        r#""eighty""#
+    );
+
+    assert_eq!(found[1].0, "$.servers[1]");
+    assert!(
+        matches!(found[1].1, JsonErrorKind::UnknownField { field_name, .. } if field_name == "colour")
+    );
+
+    assert_eq!(found[2].0, "$.servers[2]");
+    assert_eq!(*found[2].1, JsonErrorKind::MissingField("tls"));
+
+    assert_eq!(found[3].0, "$.retries");
+    assert!(matches!(found[3].1, JsonErrorKind::UnsupportedType { .. }));
+}
+
+#[test]
+fn bad_field_is_not_also_reported_missing() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"host": 12, "port": 1, "tls": false}"#;
+    let err = from_str_all_errors::<Server>(json).unwrap_err();
+    assert_eq!(err.errors.len(), 1, "{:#?}", err.errors);
+    assert_eq!(err.errors[0].path, "$.host");
+}
+
+#[test]
+fn syntax_error_stops_collection() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"host": 12, "port": 1 "tls": false}"#;
+    let err = from_str_all_errors::<Server>(json).unwrap_err();
+    assert_eq!(err.errors.len(), 2, "{:#?}", err.errors);
+    assert!(matches!(
+        err.errors[1].kind,
+        JsonErrorKind::UnexpectedToken { .. }
+    ));
+}
+
+#[test]
+fn valid_input_deserializes() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let json = r#"{"host": "localhost", "port": 80, "tls": false}"#;
+    let server = from_str_all_errors::<Server>(json)?;
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 80);
+    Ok(())
+}
+
+#[test]
+fn values_in_enum_variants_are_not_misreported() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    enum Target {
+        Host(String),
+        Port(u64),
+    }
+
+    #[derive(Facet, Debug)]
+    struct Route {
+        from: Target,
+        to: Target,
+        weight: u8,
+    }
+
+    let json = r#"{"from": {"Host": "a"}, "to": {"Port": 8080}, "weight": 300}"#;
+    let err = from_str_all_errors::<Route>(json).unwrap_err();
+    assert_eq!(err.errors.len(), 1, "{:#?}", err.errors);
+    assert_eq!(err.errors[0].path, "$.weight");
+
+    let json = r#"{"from": {"Host": "a"}, "to": {"Port": 8080}, "weight": 3}"#;
+    let route = from_str_all_errors::<Route>(json).unwrap();
+    assert_eq!(route.from, Target::Host("a".to_string()));
+    assert_eq!(route.to, Target::Port(8080));
+}
+
+#[test]
+fn bad_map_values_do_not_stop_collection() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    #[facet(deny_unknown_fields)]
+    struct Weights {
+        weights: std::collections::HashMap<String, u8>,
+        name: String,
+    }
+
+    let json = r#"{
+        "weights": {"a": 1, "b": "heavy", "c": 2, "d": 900},
+        "name": "w",
+        "colour": "blue"
+    }"#;
+    let err = from_str_all_errors::<Weights>(json).unwrap_err();
+    assert_eq!(err.errors.len(), 3, "{:#?}", err.errors);
+
+    assert!(err.errors[0].path.starts_with("$.weights"));
+    assert_eq!(
+        &json[err.errors[0].span.start..err.errors[0].span.end()],
+        r#""heavy""#
+    );
+    assert!(err.errors[1].path.starts_with("$.weights"));
+    assert_eq!(
+        &json[err.errors[1].span.start..err.errors[1].span.end()],
+        "900"
+    );
+    assert_eq!(err.errors[2].path, "$");
+    assert!(matches!(
+        &err.errors[2].kind,
+        JsonErrorKind::UnknownField { field_name, .. } if field_name == "colour"
+    ));
+}
@@ -14,3 +14,18 @@ fn json_read_hashmap() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn json_read_hashmap_with_integer_keys() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let json = r#"{"1": "one", "-2": "minus two"}"#;
+
+    let m: std::collections::HashMap<i32, String> = from_str(json)?;
+    assert_eq!(m.get(&1).unwrap(), "one");
+    assert_eq!(m.get(&-2).unwrap(), "minus two");
+
+    assert!(from_str::<std::collections::HashMap<u8, String>>(r#"{"300": "x"}"#).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_json::{JsonErrorKind, LimitKind, Limits, from_str, from_str_with_limits};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Node {
+    name: String,
+    children: Vec<Node>,
+}
+
+#[derive(Facet, Debug, PartialEq)]
+struct Batch {
+    ids: Vec<u32>,
+}
+
+#[test]
+fn depth_limit_reports_path() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"name": "a", "children": [{"name": "b", "children": [{"name": "c", "children": []}]}]}"#;
+    assert!(from_str::<Node>(json).is_ok());
+
+    let err = from_str_with_limits::<Node>(json, Limits::default().max_depth(4)).unwrap_err();
+    assert!(
+        matches!(
+            err.kind,
+            JsonErrorKind::LimitExceeded {
+                kind: LimitKind::Depth,
+                max: 4
+            }
+        ),
+        "{:?}",
+        err.kind
+    );
+    assert_eq!(err.path, "$.children[0].children");
+}
+
+#[test]
+fn collection_length_limit() {
+    facet_testhelpers::setup();
+
+    let limits = Limits::default().max_collection_len(3);
+    let batch = from_str_with_limits::<Batch>(r#"{"ids": [1, 2, 3]}"#, limits).unwrap();
+    assert_eq!(batch.ids, vec![1, 2, 3]);
+
+    let err = from_str_with_limits::<Batch>(r#"{"ids": [1, 2, 3, 4]}"#, limits).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        JsonErrorKind::LimitExceeded {
+            kind: LimitKind::CollectionLength,
+            max: 3
+        }
+    ));
+    assert_eq!(err.path, "$.ids");
+}
+
+#[test]
+fn string_length_limit() {
+    facet_testhelpers::setup();
+
+    let limits = Limits::default().max_string_len(8);
+    let json = r#"{"name": "much too long", "children": []}"#;
+    let err = from_str_with_limits::<Node>(json, limits).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        JsonErrorKind::LimitExceeded {
+            kind: LimitKind::StringLength,
+            max: 8
+        }
+    ));
+    assert_eq!(err.path, "$.name");
+}
+
+#[test]
+fn string_length_limit_applies_to_keys() {
+    facet_testhelpers::setup();
+
+    let limits = Limits::default().max_string_len(8);
+    let json = r#"{"much too long": 1}"#;
+    let err = from_str_with_limits::<HashMap<String, u32>>(json, limits).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        JsonErrorKind::LimitExceeded {
+            kind: LimitKind::StringLength,
+            max: 8
+        }
+    ));
+}
+
+#[test]
+fn allocation_limit() {
+    facet_testhelpers::setup();
+
+    let json = format!("[{}]", vec!["\"abcdefgh\""; 100].join(","));
+    assert_eq!(from_str::<Vec<String>>(&json).unwrap().len(), 100);
+
+    let err = from_str_with_limits::<Vec<String>>(&json, Limits::default().max_alloc_bytes(1024))
+        .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        JsonErrorKind::LimitExceeded {
+            kind: LimitKind::Allocation,
+            ..
+        }
+    ));
+}
@@ -1,6 +1,6 @@
 use eyre::Result;
 use facet::Facet;
-use facet_json::from_str;
+use facet_json::{JsonErrorKind, RawNumber, TokenErrorKind, from_str};
 
 #[test]
 fn json_read_more_types() -> Result<()> {
@@ -48,3 +48,131 @@ fn json_read_more_types() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn json_read_integers_beyond_64_bits_as_floats() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let n: f64 = from_str("18446744073709551616")?;
+    assert_eq!(n, 18446744073709551616.0);
+    let n: f64 = from_str("-9223372036854775809")?;
+    assert_eq!(n, -9223372036854775808.0);
+
+    // Numbers that are cut short are syntax errors, reported where they end
+    let err = from_str::<Vec<f64>>("[1e]").unwrap_err();
+    assert_eq!(
+        err.kind,
+        JsonErrorKind::SyntaxError(TokenErrorKind::UnexpectedCharacter(']'))
+    );
+    let err = from_str::<f64>("-").unwrap_err();
+    assert_eq!(
+        err.kind,
+        JsonErrorKind::SyntaxError(TokenErrorKind::UnexpectedEof("in number"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn json_read_128_bit_integers_exactly() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Wide {
+        id: u128,
+        delta: i128,
+        small: u128,
+    }
+
+    let json = r#"{
+        "id": 340282366920938463463374607431768211455,
+        "delta": -170141183460469231731687303715884105728,
+        "small": 7
+    }"#;
+
+    let wide: Wide = from_str(json)?;
+    assert_eq!(wide.id, u128::MAX);
+    assert_eq!(wide.delta, i128::MIN);
+    assert_eq!(wide.small, 7);
+
+    Ok(())
+}
+
+#[test]
+fn json_read_128_bit_out_of_range() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Wide {
+        id: u128,
+    }
+
+    let err = from_str::<Wide>(r#"{"id": 340282366920938463463374607431768211456}"#).unwrap_err();
+    assert!(matches!(err.kind, JsonErrorKind::NumberOutOfRange(_)));
+    assert_eq!(err.path, "$.id");
+
+    let err = from_str::<Wide>(r#"{"id": -1}"#).unwrap_err();
+    assert!(matches!(err.kind, JsonErrorKind::NumberOutOfRange(_)));
+
+    let err = from_str::<Wide>(r#"{"id": 1.5}"#).unwrap_err();
+    assert!(matches!(
+        err.kind,
+        JsonErrorKind::UnexpectedToken {
+            wanted: "integer",
+            ..
+        }
+    ));
+}
+
+#[test]
+fn json_read_raw_number_keeps_digits() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Invoice {
+        total: RawNumber,
+        rate: RawNumber,
+        reference: RawNumber,
+        quoted: RawNumber,
+    }
+
+    let json = r#"{
+        "total": 19.90,
+        "rate": 1.5E-3,
+        "reference": 123456789012345678901234567890,
+        "quoted": "0.10"
+    }"#;
+
+    let invoice: Invoice = from_str(json)?;
+    assert_eq!(invoice.total.as_str(), "19.90");
+    assert_eq!(invoice.rate.as_str(), "1.5E-3");
+    assert_eq!(
+        invoice.reference.parse::<u128>()?,
+        123456789012345678901234567890
+    );
+    assert_eq!(invoice.quoted.as_str(), "0.10");
+
+    assert_eq!(
+        facet_json::to_string(&invoice),
+        r#"{"total":19.90,"rate":1.5E-3,"reference":123456789012345678901234567890,"quoted":0.10}"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn json_read_raw_number_rejects_invalid_numbers() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Invoice {
+        total: RawNumber,
+    }
+
+    let err = from_str::<Invoice>(r#"{"total": 019.90}"#).unwrap_err();
+    assert_eq!(err.kind, JsonErrorKind::InvalidNumber("019.90".to_string()));
+
+    assert!(from_str::<Invoice>(r#"{"total": "twelve"}"#).is_err());
+    assert!("1.".parse::<RawNumber>().is_err());
+    assert!("-0.5e+10".parse::<RawNumber>().is_ok());
+}
@@ -0,0 +1,114 @@
+use eyre::Result;
+use facet::Facet;
+use facet_json::{JsonErrorKind, from_str_at};
+
+#[derive(Facet, Debug, PartialEq)]
+struct Item {
+    id: u64,
+    name: String,
+}
+
+const ENVELOPE: &str = r#"{
+    "meta": {"page": 1, "cursor": [1, {"deep": [true, null]}]},
+    "data": {
+        "total": 2,
+        "items": [
+            {"id": 1, "name": "first"},
+            {"id": 2, "name": "second"}
+        ]
+    },
+    "trailer": "ignored"
+}"#;
+
+#[test]
+fn read_subtree_at_pointer() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let items: Vec<Item> = from_str_at(ENVELOPE, "/data/items")?;
+    assert_eq!(
+        items,
+        vec![
+            Item {
+                id: 1,
+                name: "first".to_string()
+            },
+            Item {
+                id: 2,
+                name: "second".to_string()
+            },
+        ]
+    );
+
+    let second: Item = from_str_at(ENVELOPE, "/data/items/1")?;
+    assert_eq!(second.name, "second");
+
+    let total: u64 = from_str_at(ENVELOPE, "/data/total")?;
+    assert_eq!(total, 2);
+
+    Ok(())
+}
+
+#[test]
+fn read_subtree_at_empty_pointer_is_whole_document() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let n: u32 = from_str_at("  42 ", "")?;
+    assert_eq!(n, 42);
+    Ok(())
+}
+
+#[test]
+fn read_subtree_stops_at_the_end_of_the_value() -> Result<()> {
+    facet_testhelpers::setup();
+
+    // What follows the pointed-to value isn't read
+    let total: u64 = from_str_at(r#"{"total": 2, oops"#, "/total")?;
+    assert_eq!(total, 2);
+
+    let items: Vec<u64> = from_str_at(r#"{"items": [1, 2]]"#, "/items")?;
+    assert_eq!(items, vec![1, 2]);
+    Ok(())
+}
+
+#[test]
+fn read_subtree_escaped_pointer() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let json = r#"{"a/b": {"c~d": "found"}}"#;
+    let s: String = from_str_at(json, "/a~1b/c~0d")?;
+    assert_eq!(s, "found");
+    Ok(())
+}
+
+#[test]
+fn read_subtree_pointer_not_found() {
+    facet_testhelpers::setup();
+
+    let err = from_str_at::<Vec<Item>>(ENVELOPE, "/data/things").unwrap_err();
+    assert_eq!(
+        err.kind,
+        JsonErrorKind::PointerNotFound("things".to_string())
+    );
+    assert_eq!(err.path, "$.data");
+
+    let err = from_str_at::<Item>(ENVELOPE, "/data/items/7").unwrap_err();
+    assert_eq!(err.kind, JsonErrorKind::PointerNotFound("7".to_string()));
+    assert_eq!(err.path, "$.data.items");
+
+    let err = from_str_at::<Item>(ENVELOPE, "data").unwrap_err();
+    assert_eq!(err.kind, JsonErrorKind::InvalidPointer("data".to_string()));
+}
+
+#[test]
+fn read_subtree_error_path_is_root_relative() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"data": {"items": [{"id": 1, "name": "ok"}, {"id": "two", "name": "bad"}]}}"#;
+    let err = from_str_at::<Vec<Item>>(json, "/data/items").unwrap_err();
+    assert!(
+        err.path.starts_with("$.data.items[1]"),
+        "path was {}",
+        err.path
+    );
+    assert_eq!(&json[err.span.start..err.span.end()], r#""two""#);
+}
@@ -41,3 +41,17 @@ fn test_str_escaped() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn json_read_unicode_escapes() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let s: String = from_str(r#""caf\u00e9 \u20AC \ud83d\ude00 \u0007""#)?;
+    assert_eq!(s, "café € 😀 \u{7}");
+
+    // A lone surrogate can't be represented: it becomes the replacement character
+    let s: String = from_str(r#""\ud83d!""#)?;
+    assert_eq!(s, "\u{fffd}!");
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use eyre::Result;
 use facet::Facet;
-use facet_json::from_str;
+use facet_json::{JsonErrorKind, Token, from_str};
 use std::fmt::Debug;
 
 #[test]
@@ -102,3 +102,24 @@ fn test_skip_over_complex_numbers() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_skip_over_missing_value() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    struct StructWithUnknownField {
+        #[allow(dead_code)]
+        known: String,
+    }
+
+    let json = r#"{"unknown": , "known": "value"}"#;
+    let err = from_str::<StructWithUnknownField>(json).unwrap_err();
+    assert_eq!(
+        err.kind,
+        JsonErrorKind::UnexpectedToken {
+            got: Token::Comma,
+            wanted: "value"
+        }
+    );
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use facet::Facet;
+use facet_json::{JsonErrorKind, LimitKind, Limits, validate_str, validate_str_with_limits};
+
+#[derive(Facet, Debug)]
+#[facet(deny_unknown_fields)]
+struct Server {
+    host: String,
+    port: u16,
+    tls: bool,
+}
+
+#[derive(Facet, Debug)]
+struct Config {
+    name: String,
+    servers: Vec<Server>,
+    #[facet(default)]
+    retries: u32,
+    backup: Option<Server>,
+}
+
+#[test]
+fn reports_every_violation() {
+    facet_testhelpers::setup();
+
+    let json = r#"{
+        "name": "prod",
+        "servers": [
+            {"host": "a", "port": "eighty", "tls": true},
+            {"host": "b", "port": 443, "tls": true, "colour": "blue"},
+            {"host": "c", "port": 80800}
+        ],
+        "backup": null,
+        "unknown": "ignored without deny_unknown_fields"
+    }"#;
+
+    let err = validate_str::<Config>(json).unwrap_err();
+    let found: Vec<_> = err
+        .errors
+        .iter()
+        .map(|e| (e.path.as_str(), &e.kind, &json[e.span.start..e.span.end()]))
+        .collect();
+    assert_eq!(found.len(), 4, "{found:#?}");
+
+    assert_eq!(found[0].0, "$.servers[0].port");
+    assert!(matches!(
+        found[0].1,
+        JsonErrorKind::UnexpectedToken {
+            wanted: "integer",
+            ..
+        }
+    ));
+    assert_eq!(found[0].2, r#""eighty""#);
+
+    assert_eq!(found[1].0, "$.servers[1]");
+    assert!(
+        matches!(found[1].1, JsonErrorKind::UnknownField { field_name, .. } if field_name == "colour")
+    );
+    assert_eq!(found[1].2, r#""colour""#);
+
+    assert_eq!(found[2].0, "$.servers[2].port");
+    assert!(matches!(found[2].1, JsonErrorKind::NumberOutOfRange(_)));
+    assert_eq!(found[2].2, "80800");
+
+    assert_eq!(found[3].0, "$.servers[2]");
+    assert_eq!(*found[3].1, JsonErrorKind::MissingField("tls"));
+    assert_eq!(found[3].2, r#"{"host": "c", "port": 80800}"#);
+}
+
+#[test]
+fn defaults_may_be_left_out() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, Default)]
+    #[facet(default)]
+    struct Defaults {
+        a: u32,
+        b: String,
+    }
+
+    assert!(validate_str::<Defaults>("{}").is_ok());
+    assert!(validate_str::<Config>(r#"{"name": "x", "servers": [], "backup": null}"#).is_ok());
+
+    let err = validate_str::<Config>(r#"{"servers": []}"#).unwrap_err();
+    let missing: Vec<_> = err.errors.iter().map(|e| e.kind.clone()).collect();
+    assert_eq!(
+        missing,
+        vec![
+            JsonErrorKind::MissingField("name"),
+            JsonErrorKind::MissingField("backup"),
+        ]
+    );
+}
+
+#[test]
+fn enums_tuples_and_arrays() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Figure {
+        Empty,
+        Circle(f64),
+        Rect { w: f64, h: f64 },
+    }
+
+    #[derive(Facet, Debug)]
+    struct Scene {
+        shapes: Vec<Figure>,
+        origin: (i32, i32),
+        rgb: [u8; 3],
+    }
+
+    let json = r#"{
+        "shapes": ["Empty", {"Circle": 1.5}, {"Rect": {"w": 1}}, "Triangle", {"Circle": "big"}],
+        "origin": [0, 0],
+        "rgb": [255, 255, 255]
+    }"#;
+    let err = validate_str::<Scene>(json).unwrap_err();
+    let found: Vec<_> = err
+        .errors
+        .iter()
+        .map(|e| (e.path.as_str(), &e.kind))
+        .collect();
+    assert_eq!(found.len(), 3, "{found:#?}");
+    assert_eq!(found[0].0, "$.shapes[2].Rect");
+    assert_eq!(*found[0].1, JsonErrorKind::MissingField("h"));
+    assert_eq!(found[1].0, "$.shapes[3]");
+    assert!(matches!(found[1].1, JsonErrorKind::NoSuchVariant { name, .. } if name == "Triangle"));
+    assert_eq!(found[2].0, "$.shapes[4].Circle");
+
+    let err =
+        validate_str::<Scene>(r#"{"shapes": [], "origin": [0], "rgb": [1, 2, 3, 4]}"#).unwrap_err();
+    let found: Vec<_> = err.errors.iter().map(|e| e.kind.clone()).collect();
+    assert_eq!(
+        found,
+        vec![
+            JsonErrorKind::WrongLength {
+                expected: 2,
+                got: 1
+            },
+            JsonErrorKind::WrongLength {
+                expected: 3,
+                got: 4
+            },
+        ]
+    );
+}
+
+#[test]
+fn syntax_errors_stop_validation() {
+    facet_testhelpers::setup();
+
+    let err = validate_str::<Config>(r#"{"name": 1, "servers": [}"#).unwrap_err();
+    assert_eq!(err.errors.len(), 2);
+    assert!(matches!(
+        err.errors[0].kind,
+        JsonErrorKind::UnexpectedToken { .. }
+    ));
+    assert!(matches!(
+        err.errors[1].kind,
+        JsonErrorKind::SyntaxError(_) | JsonErrorKind::UnexpectedToken { .. }
+    ));
+}
+
+#[test]
+fn null_stands_for_defaults() {
+    facet_testhelpers::setup();
+
+    assert!(
+        validate_str::<Config>(
+            r#"{"name": null, "servers": null, "retries": null, "backup": null}"#
+        )
+        .is_ok()
+    );
+
+    let err =
+        validate_str::<Config>(r#"{"name": "x", "servers": [null], "backup": null}"#).unwrap_err();
+    assert_eq!(err.errors.len(), 1);
+    assert_eq!(err.errors[0].path, "$.servers[0]");
+}
+
+#[test]
+fn map_keys_must_parse() {
+    facet_testhelpers::setup();
+
+    let json = r#"{"127.0.0.1": 1, "localhost": 2}"#;
+    let err = validate_str::<HashMap<IpAddr, u32>>(json).unwrap_err();
+    assert_eq!(err.errors.len(), 1);
+    assert_eq!(err.errors[0].path, "$.localhost");
+    assert_eq!(
+        &json[err.errors[0].span.start..err.errors[0].span.end()],
+        r#""localhost""#
+    );
+
+    let err = validate_str::<HashMap<u8, u32>>(r#"{"300": 1}"#).unwrap_err();
+    assert_eq!(err.errors[0].kind, JsonErrorKind::NumberOutOfRange(300.0));
+}
+
+#[test]
+fn limits_stop_validation() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    #[allow(dead_code)]
+    struct Node {
+        name: String,
+        children: Vec<Node>,
+    }
+
+    let json = r#"{"name": "a", "children": [{"name": "b", "children": [{"name": "c", "children": []}]}]}"#;
+    assert!(validate_str::<Node>(json).is_ok());
+
+    let err = validate_str_with_limits::<Node>(json, Limits::default().max_depth(4)).unwrap_err();
+    assert_eq!(
+        err.errors[0].kind,
+        JsonErrorKind::LimitExceeded {
+            kind: LimitKind::Depth,
+            max: 4
+        }
+    );
+    assert_eq!(err.errors[0].path, "$.children[0].children");
+
+    let err =
+        validate_str_with_limits::<Vec<u32>>("[1, 2, 3]", Limits::default().max_collection_len(2))
+            .unwrap_err();
+    assert_eq!(
+        err.errors[0].kind,
+        JsonErrorKind::LimitExceeded {
+            kind: LimitKind::CollectionLength,
+            max: 2
+        }
+    );
+
+    let err =
+        validate_str_with_limits::<Vec<String>>(r#"["abcd"]"#, Limits::default().max_string_len(3))
+            .unwrap_err();
+    assert_eq!(
+        err.errors[0].kind,
+        JsonErrorKind::LimitExceeded {
+            kind: LimitKind::StringLength,
+            max: 3
+        }
+    );
+
+    // The validator recurses, so it never goes arbitrarily deep
+    let deep = format!(
+        r#"{}{{"name": "x", "children": []}}{}"#,
+        r#"{"name": "x", "children": ["#.repeat(200),
+        "]}".repeat(200),
+    );
+    let err = validate_str::<Node>(&deep).unwrap_err();
+    assert!(matches!(
+        err.errors[0].kind,
+        JsonErrorKind::LimitExceeded {
+            kind: LimitKind::Depth,
+            ..
+        }
+    ));
+}
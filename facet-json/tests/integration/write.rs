@@ -1,3 +1,5 @@
+mod canonical;
+mod containers;
 mod enums;
 mod json;
 mod map;
@@ -0,0 +1,148 @@
+#![cfg(feature = "std")]
+
+use std::collections::HashMap;
+
+use facet::Facet;
+use facet_json::{to_canonical_string, to_canonical_writer};
+
+#[test]
+fn canonical_sorts_struct_fields_and_strips_whitespace() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Inner {
+        z: bool,
+        a: Option<u8>,
+    }
+
+    #[derive(Facet)]
+    struct Outer {
+        zeta: String,
+        alpha: Vec<i32>,
+        middle: Inner,
+    }
+
+    let value = Outer {
+        zeta: "last".to_string(),
+        alpha: vec![-1, 2],
+        middle: Inner { z: true, a: None },
+    };
+    assert_eq!(
+        to_canonical_string(&value),
+        r#"{"alpha":[-1,2],"middle":{"a":null,"z":true},"zeta":"last"}"#
+    );
+}
+
+#[test]
+fn canonical_key_order_is_utf16() {
+    facet_testhelpers::setup();
+
+    // The sorting example from RFC 8785 section 3.2.3
+    let mut map = HashMap::new();
+    for (i, key) in [
+        "\u{20ac}",
+        "\r",
+        "\u{fb33}",
+        "1",
+        "\u{1f600}",
+        "\u{80}",
+        "\u{f6}",
+    ]
+    .iter()
+    .enumerate()
+    {
+        map.insert(key.to_string(), i as u32);
+    }
+
+    assert_eq!(
+        to_canonical_string(&map),
+        "{\"\\r\":1,\"1\":3,\"\u{80}\":5,\"\u{f6}\":6,\"\u{20ac}\":0,\"\u{1f600}\":4,\"\u{fb33}\":2}"
+    );
+}
+
+#[test]
+fn canonical_hashmap_is_stable() {
+    facet_testhelpers::setup();
+
+    let keys: Vec<String> = (0..64).map(|i| format!("key{i}")).collect();
+
+    let mut forward = HashMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        forward.insert(key.clone(), i);
+    }
+    let mut backward = HashMap::new();
+    for (i, key) in keys.iter().enumerate().rev() {
+        backward.insert(key.clone(), i);
+    }
+
+    assert_eq!(
+        to_canonical_string(&forward),
+        to_canonical_string(&backward)
+    );
+}
+
+#[test]
+fn canonical_numbers_follow_ecmascript() {
+    facet_testhelpers::setup();
+
+    let cases: &[(f64, &str)] = &[
+        (0.0, "0"),
+        (-0.0, "0"),
+        (1.0, "1"),
+        (-1.5, "-1.5"),
+        (4.5, "4.5"),
+        (0.002, "0.002"),
+        (1e-7, "1e-7"),
+        (0.000001, "0.000001"),
+        (333333333.3333333, "333333333.3333333"),
+        (1e20, "100000000000000000000"),
+        (1e21, "1e+21"),
+        (1e30, "1e+30"),
+        (1.5e300, "1.5e+300"),
+        (5e-324, "5e-324"),
+        (f64::MAX, "1.7976931348623157e+308"),
+    ];
+    for (value, expected) in cases {
+        assert_eq!(
+            &to_canonical_string(value),
+            expected,
+            "formatting {value:e}"
+        );
+    }
+
+    // f32 values use their own shortest digits
+    assert_eq!(to_canonical_string(&1.1f32), "1.1");
+    assert_eq!(to_canonical_string(&3e-10f32), "3e-10");
+
+    // Integers are doubles too, so they're rounded beyond 2^53
+    assert_eq!(
+        to_canonical_string(&9007199254740993u64),
+        "9007199254740992"
+    );
+    assert_eq!(
+        to_canonical_string(&-9007199254740993i64),
+        "-9007199254740992"
+    );
+    assert_eq!(to_canonical_string(&u64::MAX), "18446744073709552000");
+    assert_eq!(to_canonical_string(&(1u128 << 64)), "18446744073709552000");
+}
+
+#[test]
+fn canonical_string_escapes_are_minimal() {
+    facet_testhelpers::setup();
+
+    let s = "quote\" backslash\\ nl\n tab\t bell\u{7} del\u{7f} é €".to_string();
+    assert_eq!(
+        to_canonical_string(&s),
+        "\"quote\\\" backslash\\\\ nl\\n tab\\t bell\\u0007 del\u{7f} é €\""
+    );
+}
+
+#[test]
+fn canonical_rejects_non_finite() {
+    facet_testhelpers::setup();
+
+    let mut out = Vec::new();
+    assert!(to_canonical_writer(&f64::NAN, &mut out).is_err());
+    assert!(to_canonical_writer(&f64::INFINITY, &mut out).is_err());
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "std")]
+
+use std::sync::Arc;
+
+use facet::Facet;
+use facet_json::to_string;
+
+#[test]
+fn test_array() {
+    facet_testhelpers::setup();
+
+    assert_eq!(to_string(&[1u8, 2, 3, 4]), "[1,2,3,4]");
+    assert_eq!(to_string(&[[true], [false]]), "[[true],[false]]");
+
+    let empty: [u32; 0] = [];
+    assert_eq!(to_string(&empty), "[]");
+}
+
+#[test]
+fn test_slice() {
+    facet_testhelpers::setup();
+
+    let names = vec!["a".to_string(), "b\"c".to_string()];
+    let slice: &[String] = &names;
+    assert_eq!(to_string(&slice), r#"["a","b\"c"]"#);
+}
+
+#[test]
+fn test_smart_pointers_and_transparent_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[facet(transparent)]
+    struct Celsius(f64);
+
+    #[derive(Facet)]
+    struct Reading<'a> {
+        sensor: Arc<String>,
+        samples: &'a [u16],
+        calibration: [i8; 2],
+        temperature: Celsius,
+    }
+
+    let samples = vec![7, 8];
+    let reading = Reading {
+        sensor: Arc::new("probe".to_string()),
+        samples: &samples,
+        calibration: [-1, 1],
+        temperature: Celsius(21.5),
+    };
+    assert_eq!(
+        to_string(&reading),
+        r#"{"sensor":"probe","samples":[7,8],"calibration":[-1,1],"temperature":21.5}"#
+    );
+}
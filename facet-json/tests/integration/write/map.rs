@@ -54,3 +54,48 @@ fn test_hashmap_to_json() {
     let json = String::from_utf8(buffer).unwrap();
     assert_eq!(json, expected_json);
 }
+
+#[test]
+fn test_map_with_non_string_keys_round_trips() -> eyre::Result<()> {
+    use facet::Facet;
+    use std::collections::{BTreeMap, HashMap};
+
+    facet_testhelpers::setup();
+
+    let mut by_id = BTreeMap::new();
+    by_id.insert(3u64, "three".to_string());
+    by_id.insert(10u64, "ten".to_string());
+    let json = to_string(&by_id);
+    assert_eq!(json, r#"{"3":"three","10":"ten"}"#);
+    assert_eq!(facet_json::from_str::<BTreeMap<u64, String>>(&json)?, by_id);
+
+    let mut flags = HashMap::new();
+    flags.insert(true, 1i8);
+    let json = to_string(&flags);
+    assert_eq!(json, r#"{"true":1}"#);
+    assert_eq!(facet_json::from_str::<HashMap<bool, i8>>(&json)?, flags);
+
+    #[derive(Facet, Debug, PartialEq, Eq, Hash)]
+    #[facet(transparent)]
+    struct UserId(u64);
+
+    let mut users = HashMap::new();
+    users.insert(UserId(42), "ferris".to_string());
+    let json = to_string(&users);
+    assert_eq!(json, r#"{"42":"ferris"}"#);
+    assert_eq!(
+        facet_json::from_str::<HashMap<UserId, String>>(&json)?,
+        users
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_map_key_display_is_escaped() {
+    facet_testhelpers::setup();
+
+    let mut map = std::collections::HashMap::new();
+    map.insert('"', 1u8);
+    assert_eq!(to_string(&map), r#"{"\"":1}"#);
+}
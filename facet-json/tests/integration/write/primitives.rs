@@ -107,6 +107,29 @@ fn test_nonzero_types_serialization() {
     assert!(json.contains(r#""isize_val":54321"#));
 }
 
+#[test]
+fn test_128_bit_integer_serialization() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Wide {
+        u128_val: u128,
+        i128_val: i128,
+        nonzero_val: NonZero<u128>,
+    }
+
+    let test_struct = Wide {
+        u128_val: u128::MAX,
+        i128_val: i128::MIN,
+        nonzero_val: NonZero::new(1 << 100).unwrap(),
+    };
+
+    assert_eq!(
+        to_string(&test_struct),
+        r#"{"u128_val":340282366920938463463374607431768211455,"i128_val":-170141183460469231731687303715884105728,"nonzero_val":1267650600228229401496703205376}"#
+    );
+}
+
 #[test]
 fn test_f64_serialization() {
     facet_testhelpers::setup();
@@ -5,11 +5,18 @@
 #![doc = include_str!("../README.md")]
 
 extern crate facet_core as facet;
-use facet_core::{Def, Facet, ScalarDef, Shape};
+use facet_core::{
+    Def, EnumDef, Facet, Field, FieldAttribute, NumberBits, ScalarAffinity, ScalarDef, Shape,
+    Signedness, StructDef, StructKind,
+};
 
 use std::io::Write;
 
 /// Convert a `Facet` type to a JSON schema string.
+///
+/// The schema describes the JSON that `facet-json` reads and writes for the
+/// type. Types that contain themselves are written once under `$defs` and
+/// referred to with `$ref`.
 pub fn to_string<'a, T: Facet<'a>>() -> String {
     let mut buffer = Vec::new();
     write!(buffer, "{{").unwrap();
@@ -46,147 +53,524 @@ pub fn to_string<'a, T: Facet<'a>>() -> String {
         }
     }
 
-    serialize(T::SHAPE, &[], &mut buffer).unwrap();
+    let mut schema = Schema::new(T::SHAPE);
+    schema.serialize(T::SHAPE, &[], &mut buffer).unwrap();
+    schema.serialize_defs(&mut buffer).unwrap();
     write!(buffer, "}}").unwrap();
     String::from_utf8(buffer).unwrap()
 }
 
-fn serialize<W: Write>(shape: &'static Shape, doc: &[&str], writer: &mut W) -> std::io::Result<()> {
-    serialize_doc(&[shape.doc, doc].concat(), writer)?;
+/// State for writing one schema
+struct Schema {
+    /// Shapes that contain themselves. They're written once under `$defs`
+    /// and referred to everywhere else.
+    recursive: Vec<&'static Shape>,
+    /// The names given to shapes under `$defs`
+    names: Vec<(&'static Shape, String)>,
+    /// The definitions written so far, by name
+    defs: Vec<(String, Vec<u8>)>,
+}
 
-    match shape.def {
-        Def::Scalar(ref scalar_def) => serialize_scalar(scalar_def, writer)?,
-        Def::Struct(ref struct_def) => serialize_struct(struct_def, writer)?,
-        Def::Map(_map_def) => todo!("Map"),
-        Def::List(list_def) => serialize_list(list_def, writer)?,
-        Def::Slice(slice_def) => serialize_slice(slice_def, writer)?,
-        Def::Array(array_def) => serialize_array(array_def, writer)?,
-        Def::Enum(_enum_def) => todo!("Enum"),
-        Def::Option(option_def) => serialize_option(option_def, writer)?,
-        Def::SmartPointer(_smart_pointer_def) => todo!("SmartPointer"),
-        _ => todo!("{:#?}", shape.def),
+impl Schema {
+    fn new(root: &'static Shape) -> Self {
+        let mut recursive = Vec::new();
+        find_recursive(root, &mut Vec::new(), &mut Vec::new(), &mut recursive);
+        Self {
+            recursive,
+            names: Vec::new(),
+            defs: Vec::new(),
+        }
     }
 
-    Ok(())
-}
+    /// The key under `$defs` for a shape. Types that share a name, from
+    /// different modules, get a number after it.
+    fn def_name(&mut self, shape: &'static Shape) -> String {
+        if let Some((_, name)) = self.names.iter().find(|(s, _)| *s == shape) {
+            return name.clone();
+        }
+        let base = def_name(shape);
+        let mut name = base.clone();
+        let mut n = 1;
+        while self.names.iter().any(|(_, taken)| *taken == name) {
+            n += 1;
+            name = format!("{base}_{n}");
+        }
+        self.names.push((shape, name.clone()));
+        name
+    }
 
-fn serialize_doc<W: Write>(doc: &[&str], writer: &mut W) -> Result<(), std::io::Error> {
-    if !doc.is_empty() {
-        let doc = doc.join("\n");
-        write!(writer, "\"description\": \"{}\",", doc.trim())?;
+    /// Writes the schema for `shape`, described by `doc` on top of its own
+    /// doc comment.
+    fn serialize<W: Write>(
+        &mut self,
+        shape: &'static Shape,
+        doc: &[&str],
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        if !self.recursive.contains(&shape) {
+            return self.serialize_inline(shape, doc, writer);
+        }
+
+        let name = self.def_name(shape);
+        if !self.defs.iter().any(|(n, _)| *n == name) {
+            // Claim the name before writing the body, which refers to it
+            self.defs.push((name.clone(), Vec::new()));
+            let mut body = Vec::new();
+            self.serialize_inline(shape, &[], &mut body)?;
+            if let Some((_, def)) = self.defs.iter_mut().find(|(n, _)| *n == name) {
+                *def = body;
+            }
+        }
+        serialize_doc(doc, writer)?;
+        write!(writer, "\"$ref\": \"#/$defs/{name}\"")?;
+        Ok(())
     }
-    Ok(())
-}
 
-/// Serialize a scalar definition to JSON schema format.
-fn serialize_scalar<W: Write>(scalar_def: &ScalarDef, writer: &mut W) -> std::io::Result<()> {
-    match scalar_def.affinity {
-        facet_core::ScalarAffinity::Number(number_affinity) => {
-            match number_affinity.bits {
-                facet_core::NumberBits::Integer { bits, sign } => {
-                    write!(writer, "\"type\": \"integer\"")?;
-                    match sign {
-                        facet_core::Signedness::Unsigned => {
-                            write!(writer, ", \"format\": \"uint{bits}\"")?;
-                            write!(writer, ", \"minimum\": 0")?;
-                        }
-                        facet_core::Signedness::Signed => {
-                            write!(writer, ", \"format\": \"int{bits}\"")?;
-                        }
-                    }
-                }
-                facet_core::NumberBits::Float { .. } => {
-                    write!(writer, "\"type\": \"number\"")?;
-                    write!(writer, ", \"format\": \"double\"")?;
+    fn serialize_inline<W: Write>(
+        &mut self,
+        shape: &'static Shape,
+        doc: &[&str],
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        let doc = [shape.doc, doc].concat();
+
+        match shape.def {
+            Def::Scalar(ref scalar_def) => match scalar_schema(shape, scalar_def) {
+                Some(schema) => {
+                    serialize_doc(&doc, writer)?;
+                    write!(writer, "{schema}")?
                 }
-                _ => unimplemented!(),
+                // Opaque scalars, and others with no JSON form we know of,
+                // could be anything
+                None => serialize_doc_only(&doc, writer)?,
+            },
+            Def::Option(option_def) => {
+                serialize_doc(&doc, writer)?;
+                self.serialize_option(option_def, writer)?
+            }
+            // Transparent wrappers and smart pointers look like what they
+            // hold
+            Def::Struct(_) | Def::SmartPointer(_) if shape.inner.is_some() => {
+                let inner = (shape.inner.unwrap())();
+                self.serialize(inner, &doc, writer)?
+            }
+            Def::SmartPointer(smart_pointer_def) => match smart_pointer_def.pointee {
+                Some(pointee) => self.serialize(pointee, &doc, writer)?,
+                // An opaque pointee could be anything
+                None => serialize_doc_only(&doc, writer)?,
+            },
+            Def::Struct(ref struct_def) => {
+                serialize_doc(&doc, writer)?;
+                self.serialize_struct(shape, struct_def, writer)?
+            }
+            Def::Map(map_def) => {
+                serialize_doc(&doc, writer)?;
+                self.serialize_map(map_def, writer)?
+            }
+            Def::List(list_def) => {
+                serialize_doc(&doc, writer)?;
+                self.serialize_list(list_def, writer)?
             }
-            Ok(())
+            Def::Slice(slice_def) => {
+                serialize_doc(&doc, writer)?;
+                self.serialize_slice(slice_def, writer)?
+            }
+            Def::Array(array_def) => {
+                serialize_doc(&doc, writer)?;
+                self.serialize_array(array_def, writer)?
+            }
+            Def::Enum(enum_def) => {
+                serialize_doc(&doc, writer)?;
+                self.serialize_enum(enum_def, writer)?
+            }
+            // Anything else has no JSON representation we can describe, so
+            // the schema accepts any value
+            _ => serialize_doc_only(&doc, writer)?,
         }
-        facet_core::ScalarAffinity::String(_) => {
-            write!(writer, "\"type\": \"string\"")?;
-            Ok(())
+
+        Ok(())
+    }
+
+    /// Writes `$defs`, if anything needed one
+    fn serialize_defs<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        if self.defs.is_empty() {
+            return Ok(());
         }
-        facet_core::ScalarAffinity::Boolean(_) => {
-            write!(writer, "\"type\": \"boolean\"")?;
-            Ok(())
+        write!(writer, ",\"$defs\": {{")?;
+        for (i, (name, body)) in self.defs.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ",")?;
+            }
+            write!(writer, "\"{name}\": {{")?;
+            writer.write_all(body)?;
+            write!(writer, "}}")?;
         }
-        _ => Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("Unsupported scalar type: {scalar_def:#?}"),
-        )),
+        write!(writer, "}}")?;
+        Ok(())
     }
-}
 
-fn serialize_struct<W: Write>(
-    struct_def: &facet_core::StructDef,
-    writer: &mut W,
-) -> std::io::Result<()> {
-    write!(writer, "\"type\": \"object\",")?;
-    let required = struct_def
-        .fields
-        .iter()
-        .map(|f| format!("\"{}\"", f.name))
-        .collect::<Vec<_>>()
-        .join(",");
-    write!(writer, "\"required\": [{required}],")?;
-    write!(writer, "\"properties\": {{")?;
-    let mut first = true;
-    for field in struct_def.fields {
-        if !first {
-            write!(writer, ",")?;
-        }
-        first = false;
-        write!(writer, "\"{}\": {{", field.name)?;
-        serialize(field.shape(), field.doc, writer)?;
+    fn serialize_struct<W: Write>(
+        &mut self,
+        shape: &'static Shape,
+        struct_def: &StructDef,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        match struct_def.kind {
+            StructKind::Tuple | StructKind::TupleStruct => {
+                self.serialize_tuple(struct_def.fields, writer)
+            }
+            _ => self.serialize_fields(
+                struct_def.fields,
+                shape.has_default_attr(),
+                shape.has_deny_unknown_fields_attr(),
+                writer,
+            ),
+        }
+    }
+
+    /// Writes an object with one property per field. Fields are required
+    /// unless they or their container have a default.
+    fn serialize_fields<W: Write>(
+        &mut self,
+        fields: &'static [Field],
+        container_default: bool,
+        deny_unknown_fields: bool,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write!(writer, "\"type\": \"object\",")?;
+        let required = fields
+            .iter()
+            .filter(|f| !container_default && f.maybe_default_fn().is_none())
+            .map(|f| json_string(field_name(f)))
+            .collect::<Vec<_>>()
+            .join(",");
+        write!(writer, "\"required\": [{required}],")?;
+        write!(writer, "\"properties\": {{")?;
+        let mut first = true;
+        for field in fields {
+            if !first {
+                write!(writer, ",")?;
+            }
+            first = false;
+            write!(writer, "{}: {{", json_string(field_name(field)))?;
+            self.serialize(field.shape(), field.doc, writer)?;
+            write!(writer, "}}")?;
+        }
         write!(writer, "}}")?;
+        if deny_unknown_fields {
+            write!(writer, ",\"additionalProperties\": false")?;
+        }
+        Ok(())
+    }
+
+    /// Tuples are written as arrays with one item per field.
+    fn serialize_tuple<W: Write>(
+        &mut self,
+        fields: &'static [Field],
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write!(writer, "\"type\": \"array\",")?;
+        write!(writer, "\"minItems\": {},", fields.len())?;
+        write!(writer, "\"maxItems\": {},", fields.len())?;
+        write!(writer, "\"prefixItems\": [")?;
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ",")?;
+            }
+            write!(writer, "{{")?;
+            self.serialize(field.shape(), field.doc, writer)?;
+            write!(writer, "}}")?;
+        }
+        write!(writer, "]")?;
+        Ok(())
+    }
+
+    /// Maps are objects whose values all have the same schema. Keys that
+    /// aren't strings are written with their `Display` impl, so integer keys
+    /// are constrained to digits.
+    fn serialize_map<W: Write>(
+        &mut self,
+        map_def: facet_core::MapDef,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write!(writer, "\"type\": \"object\",")?;
+        if let Def::Scalar(ScalarDef {
+            affinity: ScalarAffinity::Number(number_affinity),
+            ..
+        }) = map_def.k.def
+        {
+            if let NumberBits::Integer { sign, .. } = number_affinity.bits {
+                let pattern = match sign {
+                    Signedness::Unsigned => "^[0-9]+$",
+                    Signedness::Signed => "^-?[0-9]+$",
+                };
+                write!(writer, "\"propertyNames\": {{\"pattern\": \"{pattern}\"}},")?;
+            }
+        }
+        write!(writer, "\"additionalProperties\": {{")?;
+        self.serialize(map_def.v, &[], writer)?;
+        write!(writer, "}}")?;
+        Ok(())
+    }
+
+    /// Serialize a list definition to JSON schema format.
+    fn serialize_list<W: Write>(
+        &mut self,
+        list_def: facet_core::ListDef,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write!(writer, "\"type\": \"array\",")?;
+        write!(writer, "\"items\": {{")?;
+        self.serialize(list_def.t(), &[], writer)?;
+        write!(writer, "}}")?;
+        Ok(())
+    }
+
+    /// Serialize a slice definition to JSON schema format.
+    fn serialize_slice<W: Write>(
+        &mut self,
+        slice_def: facet_core::SliceDef,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write!(writer, "\"type\": \"array\",")?;
+        write!(writer, "\"items\": {{")?;
+        self.serialize(slice_def.t(), &[], writer)?;
+        write!(writer, "}}")?;
+        Ok(())
+    }
+
+    /// Serialize an array definition to JSON schema format.
+    fn serialize_array<W: Write>(
+        &mut self,
+        array_def: facet_core::ArrayDef,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write!(writer, "\"type\": \"array\",")?;
+        write!(writer, "\"minItems\": {},", array_def.n)?;
+        write!(writer, "\"maxItems\": {},", array_def.n)?;
+        write!(writer, "\"items\": {{")?;
+        self.serialize(array_def.t(), &[], writer)?;
+        write!(writer, "}}")?;
+        Ok(())
+    }
+
+    /// `None` is written as `null`, and `Some` as the value it holds.
+    fn serialize_option<W: Write>(
+        &mut self,
+        option_def: facet_core::OptionDef,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        write!(writer, "\"anyOf\": [{{")?;
+        self.serialize(option_def.t(), &[], writer)?;
+        write!(writer, "}},{{\"type\": \"null\"}}]")?;
+        Ok(())
+    }
+
+    /// Enums follow facet-json: unit variants are strings holding their
+    /// name, and other variants are objects with their name as the only key.
+    fn serialize_enum<W: Write>(
+        &mut self,
+        enum_def: EnumDef,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        let variants = enum_def.variants;
+        if variants.iter().all(|v| v.data.fields.is_empty()) {
+            let names = variants
+                .iter()
+                .map(|v| json_string(v.name))
+                .collect::<Vec<_>>()
+                .join(",");
+            write!(writer, "\"type\": \"string\",\"enum\": [{names}]")?;
+            return Ok(());
+        }
+
+        write!(writer, "\"oneOf\": [")?;
+        for (i, variant) in variants.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ",")?;
+            }
+            write!(writer, "{{")?;
+            serialize_doc(variant.doc, writer)?;
+            if variant.data.fields.is_empty() {
+                write!(writer, "\"const\": {}", json_string(variant.name))?;
+            } else {
+                let name = json_string(variant.name);
+                write!(writer, "\"type\": \"object\",")?;
+                write!(writer, "\"required\": [{name}],")?;
+                write!(writer, "\"properties\": {{{name}: {{")?;
+                match variant.data.kind {
+                    StructKind::Tuple | StructKind::TupleStruct
+                        if variant.data.fields.len() == 1 =>
+                    {
+                        let field = &variant.data.fields[0];
+                        self.serialize(field.shape(), field.doc, writer)?;
+                    }
+                    StructKind::Tuple | StructKind::TupleStruct => {
+                        self.serialize_tuple(variant.data.fields, writer)?
+                    }
+                    _ => self.serialize_fields(variant.data.fields, false, false, writer)?,
+                }
+                write!(writer, "}}}},\"additionalProperties\": false")?;
+            }
+            write!(writer, "}}")?;
+        }
+        write!(writer, "]")?;
+        Ok(())
     }
-    write!(writer, "}}")?;
-    Ok(())
 }
 
-/// Serialize a list definition to JSON schema format.
-fn serialize_list<W: Write>(list_def: facet_core::ListDef, writer: &mut W) -> std::io::Result<()> {
-    write!(writer, "\"type\": \"array\",")?;
-    write!(writer, "\"items\": {{")?;
-    serialize(list_def.t(), &[], writer)?;
-    write!(writer, "}}")?;
-    Ok(())
+/// Collects the shapes reachable from `shape` that contain themselves.
+/// `stack` holds the shapes being visited, and `seen` every shape visited.
+fn find_recursive(
+    shape: &'static Shape,
+    stack: &mut Vec<&'static Shape>,
+    seen: &mut Vec<&'static Shape>,
+    recursive: &mut Vec<&'static Shape>,
+) {
+    if stack.contains(&shape) {
+        if !recursive.contains(&shape) {
+            recursive.push(shape);
+        }
+        return;
+    }
+    if seen.contains(&shape) {
+        return;
+    }
+    seen.push(shape);
+    stack.push(shape);
+
+    let mut children = Vec::new();
+    match shape.def {
+        Def::Struct(struct_def) => children.extend(struct_def.fields.iter().map(|f| f.shape())),
+        Def::Enum(enum_def) => children.extend(
+            enum_def
+                .variants
+                .iter()
+                .flat_map(|v| v.data.fields.iter().map(|f| f.shape())),
+        ),
+        Def::Map(map_def) => children.extend([map_def.k, map_def.v]),
+        Def::List(list_def) => children.push(list_def.t()),
+        Def::Slice(slice_def) => children.push(slice_def.t()),
+        Def::Array(array_def) => children.push(array_def.t()),
+        Def::Option(option_def) => children.push(option_def.t()),
+        Def::SmartPointer(smart_pointer_def) => children.extend(smart_pointer_def.pointee),
+        _ => {}
+    }
+    if let Some(inner) = shape.inner {
+        children.push(inner());
+    }
+    for child in children {
+        find_recursive(child, stack, seen, recursive);
+    }
+
+    stack.pop();
 }
 
-/// Serialize a slice definition to JSON schema format.
-fn serialize_slice<W: Write>(
-    slice_def: facet_core::SliceDef,
-    writer: &mut W,
-) -> std::io::Result<()> {
-    write!(writer, "\"type\": \"array\",")?;
-    write!(writer, "\"items\": {{")?;
-    serialize(slice_def.t(), &[], writer)?;
-    write!(writer, "}}")?;
+/// The key under `$defs` for a shape: its type name, with anything that
+/// would need escaping in a JSON pointer replaced
+fn def_name(shape: &'static Shape) -> String {
+    shape
+        .to_string()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn field_name(field: &Field) -> &'static str {
+    field
+        .attributes
+        .iter()
+        .find_map(|attr| match attr {
+            FieldAttribute::Rename(name) => Some(*name),
+            _ => None,
+        })
+        .unwrap_or(field.name)
+}
+
+fn serialize_doc<W: Write>(doc: &[&str], writer: &mut W) -> Result<(), std::io::Error> {
+    if !doc.is_empty() {
+        let doc = doc.join("\n");
+        write!(writer, "\"description\": {},", json_string(doc.trim()))?;
+    }
     Ok(())
 }
 
-/// Serialize an array definition to JSON schema format.
-fn serialize_array<W: Write>(
-    array_def: facet_core::ArrayDef,
-    writer: &mut W,
-) -> std::io::Result<()> {
-    write!(writer, "\"type\": \"array\",")?;
-    write!(writer, "\"minItems\": {},", array_def.n)?;
-    write!(writer, "\"maxItems\": {},", array_def.n)?;
-    write!(writer, "\"items\": {{")?;
-    serialize(array_def.t(), &[], writer)?;
-    write!(writer, "}}")?;
+/// Writes just a description, for schemas that accept anything
+fn serialize_doc_only<W: Write>(doc: &[&str], writer: &mut W) -> Result<(), std::io::Error> {
+    if !doc.is_empty() {
+        let doc = doc.join("\n");
+        write!(writer, "\"description\": {}", json_string(doc.trim()))?;
+    }
     Ok(())
 }
 
-/// Serialize an option definition to JSON schema format.
-fn serialize_option<W: Write>(
-    _option_def: facet_core::OptionDef,
-    writer: &mut W,
-) -> std::io::Result<()> {
-    write!(writer, "\"type\": \"[]\",")?;
-    unimplemented!("serialize_option");
+/// Quotes and escapes a string for JSON
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The JSON schema keywords for a scalar, or `None` if it has no JSON form
+/// we know of.
+fn scalar_schema(shape: &'static Shape, scalar_def: &ScalarDef) -> Option<String> {
+    let schema = match scalar_def.affinity {
+        ScalarAffinity::Number(number_affinity) => match number_affinity.bits {
+            NumberBits::Integer { bits, sign } => match sign {
+                Signedness::Unsigned => {
+                    format!("\"type\": \"integer\", \"format\": \"uint{bits}\", \"minimum\": 0")
+                }
+                Signedness::Signed => format!("\"type\": \"integer\", \"format\": \"int{bits}\""),
+            },
+            NumberBits::Float { mantissa_bits, .. } => {
+                if mantissa_bits <= 23 {
+                    "\"type\": \"number\", \"format\": \"float\"".to_string()
+                } else {
+                    "\"type\": \"number\", \"format\": \"double\"".to_string()
+                }
+            }
+            _ => "\"type\": \"number\"".to_string(),
+        },
+        // JSON Schema has no format for paths or socket addresses
+        ScalarAffinity::String(_) | ScalarAffinity::Path(_) | ScalarAffinity::SocketAddr(_) => {
+            "\"type\": \"string\"".to_string()
+        }
+        ScalarAffinity::Boolean(_) => "\"type\": \"boolean\"".to_string(),
+        ScalarAffinity::Char(_) => {
+            "\"type\": \"string\",\"minLength\": 1,\"maxLength\": 1".to_string()
+        }
+        ScalarAffinity::UUID(_) => "\"type\": \"string\",\"format\": \"uuid\"".to_string(),
+        ScalarAffinity::ULID(_) => {
+            "\"type\": \"string\",\"pattern\": \"^[0-9A-HJKMNP-TV-Za-hjkmnp-tv-z]{26}$\""
+                .to_string()
+        }
+        ScalarAffinity::Time(_) => "\"type\": \"string\",\"format\": \"date-time\"".to_string(),
+        ScalarAffinity::IpAddr(_) => {
+            if shape.is_type::<core::net::Ipv4Addr>() {
+                "\"type\": \"string\",\"format\": \"ipv4\"".to_string()
+            } else if shape.is_type::<core::net::Ipv6Addr>() {
+                "\"type\": \"string\",\"format\": \"ipv6\"".to_string()
+            } else {
+                "\"type\": \"string\",\"anyOf\": [{\"format\": \"ipv4\"},{\"format\": \"ipv6\"}]"
+                    .to_string()
+            }
+        }
+        ScalarAffinity::Empty(_) => "\"type\": \"null\"".to_string(),
+        _ => return None,
+    };
+    Some(schema)
 }
 
 #[cfg(test)]
@@ -213,4 +597,152 @@ mod tests {
         let schema = to_string::<TestStruct>();
         assert_snapshot!(schema);
     }
+
+    #[test]
+    fn test_enums() {
+        #[derive(Facet)]
+        #[repr(u8)]
+        #[allow(dead_code)]
+        enum Color {
+            Red,
+            Green,
+        }
+
+        #[derive(Facet)]
+        #[repr(u8)]
+        #[allow(dead_code)]
+        enum Message {
+            /// Stop everything
+            Quit,
+            Move {
+                x: i32,
+                y: i32,
+            },
+            Write(String),
+            Point(i32, i32),
+        }
+
+        let schema = to_string::<Color>();
+        assert!(schema.contains(r#""type": "string","enum": ["Red","Green"]"#));
+
+        let schema = to_string::<Message>();
+        assert!(schema.contains(r#"{"description": "Stop everything","const": "Quit"}"#));
+        assert!(schema.contains(
+            r#""required": ["Move"],"properties": {"Move": {"type": "object","required": ["x","y"]"#
+        ));
+        assert!(schema.contains(r#""properties": {"Write": {"type": "string"}}"#));
+        assert!(
+            schema.contains(
+                r#""Point": {"type": "array","minItems": 2,"maxItems": 2,"prefixItems": ["#
+            )
+        );
+    }
+
+    #[test]
+    fn test_maps_options_and_wrappers() {
+        use std::collections::HashMap;
+        use std::sync::Arc;
+
+        #[derive(Facet)]
+        #[facet(transparent)]
+        struct Meters(f32);
+
+        #[derive(Facet)]
+        #[facet(deny_unknown_fields)]
+        struct Config {
+            labels: HashMap<String, u8>,
+            by_id: HashMap<u64, bool>,
+            nickname: Option<String>,
+            shared: Arc<bool>,
+            initial: char,
+            height: Meters,
+            #[facet(rename = "renamed")]
+            original: bool,
+            #[facet(default)]
+            retries: u32,
+        }
+
+        let schema = to_string::<Config>();
+        assert!(schema.contains(
+            r#""labels": {"type": "object","additionalProperties": {"type": "integer", "format": "uint8", "minimum": 0}}"#
+        ));
+        assert!(schema.contains(r#""propertyNames": {"pattern": "^[0-9]+$"}"#));
+        assert!(schema.contains(r#""nickname": {"anyOf": [{"type": "string"},{"type": "null"}]}"#));
+        assert!(schema.contains(r#""shared": {"type": "boolean"}"#));
+        assert!(schema.contains(r#""initial": {"type": "string","minLength": 1,"maxLength": 1}"#));
+        assert!(schema.contains(r#""height": {"type": "number", "format": "float"}"#));
+        assert!(schema.contains(r#""renamed": {"type": "boolean"}"#));
+        assert!(schema.contains(
+            r#""required": ["labels","by_id","nickname","shared","initial","height","renamed"]"#
+        ));
+        assert!(schema.ends_with(r#","additionalProperties": false}"#));
+    }
+
+    #[test]
+    fn test_formats() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        #[derive(Facet)]
+        struct Host {
+            v4: Ipv4Addr,
+            v6: Ipv6Addr,
+            any: IpAddr,
+            path: std::path::PathBuf,
+        }
+
+        let schema = to_string::<Host>();
+        assert!(schema.contains(r#""v4": {"type": "string","format": "ipv4"}"#));
+        assert!(schema.contains(r#""v6": {"type": "string","format": "ipv6"}"#));
+        assert!(schema.contains(
+            r#""any": {"type": "string","anyOf": [{"format": "ipv4"},{"format": "ipv6"}]}"#
+        ));
+        assert!(schema.contains(r#""path": {"type": "string"}"#));
+    }
+
+    #[test]
+    fn test_recursive() {
+        /// A node in a tree
+        #[derive(Facet)]
+        struct Node {
+            value: u32,
+            children: Vec<Node>,
+        }
+
+        let schema = to_string::<Node>();
+        assert!(schema.starts_with(
+            r##"{"$schema": "https://json-schema.org/draft/2020-12/schema","$ref": "#/$defs/Node","$defs": {"Node": {"description": "A node in a tree","type": "object""##
+        ));
+        assert!(
+            schema.contains(r##""children": {"type": "array","items": {"$ref": "#/$defs/Node"}}"##)
+        );
+    }
+
+    #[test]
+    fn test_recursive_types_with_the_same_name() {
+        mod a {
+            #[derive(facet_derive::Facet)]
+            pub struct Node {
+                pub children: Vec<Node>,
+            }
+        }
+
+        mod b {
+            #[derive(facet_derive::Facet)]
+            pub struct Node {
+                pub next: Option<Box<Node>>,
+            }
+        }
+
+        #[derive(Facet)]
+        struct Forest {
+            tree: a::Node,
+            list: b::Node,
+        }
+
+        let schema = to_string::<Forest>();
+        assert!(schema.contains(r##""tree": {"$ref": "#/$defs/Node"}"##));
+        assert!(schema.contains(r##""list": {"$ref": "#/$defs/Node_2"}"##));
+        assert!(schema.contains(r##""items": {"$ref": "#/$defs/Node"}"##));
+        assert!(schema.contains(r##""next": {"anyOf": [{"$ref": "#/$defs/Node_2"}"##));
+    }
 }
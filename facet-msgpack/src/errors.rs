@@ -22,6 +22,15 @@ pub enum Error {
     UnsupportedType(String),
     /// Reflection error
     ReflectError(facet_reflect::ReflectError),
+    /// The input went over one of the configured [`Limits`](facet_reflect::Limits)
+    LimitExceeded {
+        /// Which limit was exceeded
+        kind: facet_reflect::LimitKind,
+        /// The configured maximum
+        max: usize,
+        /// Where in the value being built the limit was hit, e.g. `$.items[3]`
+        path: String,
+    },
 }
 
 impl From<facet_reflect::ReflectError> for Error {
@@ -48,6 +57,9 @@ impl fmt::Display for Error {
             Error::ReflectError(err) => {
                 write!(f, "Reflection error: {}", err)
             }
+            Error::LimitExceeded { kind, max, path } => {
+                write!(f, "Exceeded the {} limit of {} at {}", kind, max, path)
+            }
         }
     }
 }
@@ -2,7 +2,7 @@ use crate::constants::*;
 use crate::errors::Error as DecodeError;
 
 use facet_core::{Def, Facet};
-use facet_reflect::{HeapValue, Wip};
+use facet_reflect::{HeapValue, LimitKind, Limits, ReflectError, Wip};
 use log::trace;
 
 /// Deserializes MessagePack-encoded data into a type that implements `Facet`.
@@ -36,6 +36,37 @@ pub fn from_slice<'input: 'facet, 'facet, T: Facet<'facet>>(
         .map_err(|e| DecodeError::UnsupportedType(e.to_string()))
 }
 
+/// Deserializes MessagePack-encoded data into a type that implements `Facet`,
+/// refusing input that goes over the given [`Limits`].
+///
+/// Length prefixes are checked before anything is allocated, so a hostile
+/// `array32`/`map32`/`str32` header can't make the decoder reserve gigabytes.
+/// Violations are reported as [`DecodeError::LimitExceeded`].
+///
+/// # Example
+/// ```
+/// use facet_msgpack::{DecodeError, LimitKind, Limits, from_slice_with_limits};
+///
+/// // An array32 header claiming four billion items
+/// let data = [0xdd, 0xff, 0xff, 0xff, 0xff];
+/// let limits = Limits::default().max_collection_len(1000);
+///
+/// let err = from_slice_with_limits::<Vec<u64>>(&data, limits).unwrap_err();
+/// assert!(matches!(
+///     err,
+///     DecodeError::LimitExceeded { kind: LimitKind::CollectionLength, max: 1000, .. }
+/// ));
+/// ```
+pub fn from_slice_with_limits<'input: 'facet, 'facet, T: Facet<'facet>>(
+    msgpack: &'input [u8],
+    limits: Limits,
+) -> Result<T, DecodeError> {
+    let wip = Wip::alloc::<T>()?.with_limits(limits)?;
+    decode(wip, msgpack, limits)?
+        .materialize::<T>()
+        .map_err(|e| DecodeError::UnsupportedType(e.to_string()))
+}
+
 /// Alias for from_slice for backward compatibility
 #[deprecated(since = "0.1.0", note = "Use from_slice instead")]
 pub fn from_str<'input: 'facet, 'facet, T: Facet<'facet>>(
@@ -88,21 +119,75 @@ pub fn from_slice_value<'mem>(
     wip: Wip<'mem>,
     msgpack: &'mem [u8],
 ) -> Result<HeapValue<'mem>, DecodeError> {
-    let mut decoder = Decoder::new(msgpack);
+    let limits = *wip.limits();
+    decode(wip, msgpack, limits)
+}
+
+fn decode<'mem>(
+    wip: Wip<'mem>,
+    msgpack: &'mem [u8],
+    limits: Limits,
+) -> Result<HeapValue<'mem>, DecodeError> {
+    let mut decoder = Decoder::new(msgpack, limits);
     decoder
         .deserialize_value(wip)?
         .build()
         .map_err(|e| DecodeError::UnsupportedType(e.to_string()))
 }
 
+/// Runs an operation on the `Wip`, reporting limit violations with the path
+/// they happened at.
+fn reflect<'mem>(
+    wip: Wip<'mem>,
+    op: impl FnOnce(Wip<'mem>) -> Result<Wip<'mem>, ReflectError>,
+) -> Result<Wip<'mem>, DecodeError> {
+    let path = wip.path();
+    op(wip).map_err(|e| reflect_error(e, || path))
+}
+
+/// Keeps limit violations typed and located; other reflection errors are
+/// passed through.
+fn reflect_error(e: ReflectError, path: impl FnOnce() -> String) -> DecodeError {
+    match e {
+        ReflectError::LimitExceeded { kind, max } => DecodeError::LimitExceeded {
+            kind,
+            max,
+            path: path(),
+        },
+        e => DecodeError::ReflectError(e),
+    }
+}
+
 struct Decoder<'input> {
     input: &'input [u8],
     offset: usize,
+    limits: Limits,
 }
 
 impl<'input> Decoder<'input> {
-    fn new(input: &'input [u8]) -> Self {
-        Decoder { input, offset: 0 }
+    fn new(input: &'input [u8], limits: Limits) -> Self {
+        Decoder {
+            input,
+            offset: 0,
+            limits,
+        }
+    }
+
+    /// Checks `value` against one of the limits, before anything gets
+    /// allocated for it.
+    fn check_limit(&self, kind: LimitKind, value: usize, wip: &Wip) -> Result<(), DecodeError> {
+        self.limits
+            .check(kind, value)
+            .map_err(|e| reflect_error(e, || wip.path()))
+    }
+
+    /// Fails early if a length prefix promises more items than there are
+    /// bytes left: every item takes at least `min_item_size` bytes.
+    fn check_remaining(&self, len: usize, min_item_size: usize) -> Result<(), DecodeError> {
+        if len.saturating_mul(min_item_size) > self.input.len() - self.offset {
+            return Err(DecodeError::InsufficientData);
+        }
+        Ok(())
     }
 
     /// Decodes a single byte from the input.
@@ -177,7 +262,7 @@ impl<'input> Decoder<'input> {
     /// - str32 (0xdb): string up to 4294967295 bytes
     ///
     /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#formats-str>
-    fn decode_string(&mut self) -> Result<String, DecodeError> {
+    fn decode_string(&mut self, wip: &Wip) -> Result<String, DecodeError> {
         let prefix = self.decode_u8()?;
 
         let len = match prefix {
@@ -188,6 +273,7 @@ impl<'input> Decoder<'input> {
             _ => return Err(DecodeError::UnexpectedType),
         };
 
+        self.check_limit(LimitKind::StringLength, len, wip)?;
         if self.offset + len > self.input.len() {
             return Err(DecodeError::InsufficientData);
         }
@@ -223,7 +309,6 @@ impl<'input> Decoder<'input> {
     /// - array32 (0xdd): array with up to 4294967295 elements
     ///
     /// Ref: <https://github.com/msgpack/msgpack/blob/master/spec.md#formats-array>
-    #[allow(dead_code)]
     fn decode_array_len(&mut self) -> Result<usize, DecodeError> {
         let prefix = self.decode_u8()?;
 
@@ -274,7 +359,11 @@ impl<'input> Decoder<'input> {
 
     /// Skips a MessagePack value of any type.
     /// This is used when encountering unknown field names in a struct.
-    fn skip_value(&mut self) -> Result<(), DecodeError> {
+    ///
+    /// `depth` is the nesting depth of the skipped value, which is checked
+    /// against the limits since skipping recurses too.
+    fn skip_value(&mut self, wip: &Wip, depth: usize) -> Result<(), DecodeError> {
+        self.check_limit(LimitKind::Depth, depth, wip)?;
         let prefix = self.decode_u8()?;
 
         match prefix {
@@ -354,24 +443,24 @@ impl<'input> Decoder<'input> {
             prefix @ MSGPACK_FIXMAP_MIN..=MSGPACK_FIXMAP_MAX => {
                 let len = (prefix & 0x0f) as usize;
                 for _ in 0..len {
-                    self.skip_value()?; // Skip key
-                    self.skip_value()?; // Skip value
+                    self.skip_value(wip, depth + 1)?; // Skip key
+                    self.skip_value(wip, depth + 1)?; // Skip value
                 }
                 Ok(())
             }
             MSGPACK_MAP16 => {
                 let len = self.decode_u16()? as usize;
                 for _ in 0..len {
-                    self.skip_value()?; // Skip key
-                    self.skip_value()?; // Skip value
+                    self.skip_value(wip, depth + 1)?; // Skip key
+                    self.skip_value(wip, depth + 1)?; // Skip value
                 }
                 Ok(())
             }
             MSGPACK_MAP32 => {
                 let len = self.decode_u32()? as usize;
                 for _ in 0..len {
-                    self.skip_value()?; // Skip key
-                    self.skip_value()?; // Skip value
+                    self.skip_value(wip, depth + 1)?; // Skip key
+                    self.skip_value(wip, depth + 1)?; // Skip value
                 }
                 Ok(())
             }
@@ -380,21 +469,21 @@ impl<'input> Decoder<'input> {
             prefix @ MSGPACK_FIXARRAY_MIN..=MSGPACK_FIXARRAY_MAX => {
                 let len = (prefix & 0x0f) as usize;
                 for _ in 0..len {
-                    self.skip_value()?;
+                    self.skip_value(wip, depth + 1)?;
                 }
                 Ok(())
             }
             MSGPACK_ARRAY16 => {
                 let len = self.decode_u16()? as usize;
                 for _ in 0..len {
-                    self.skip_value()?;
+                    self.skip_value(wip, depth + 1)?;
                 }
                 Ok(())
             }
             MSGPACK_ARRAY32 => {
                 let len = self.decode_u32()? as usize;
                 for _ in 0..len {
-                    self.skip_value()?;
+                    self.skip_value(wip, depth + 1)?;
                 }
                 Ok(())
             }
@@ -411,45 +500,45 @@ impl<'input> Decoder<'input> {
             Def::Scalar(_) => {
                 trace!("Deserializing scalar");
                 if shape.is_type::<String>() {
-                    let s = self.decode_string()?;
-                    wip.put(s).unwrap()
+                    let s = self.decode_string(&wip)?;
+                    reflect(wip, |wip| wip.put(s))?
                 } else if shape.is_type::<u64>() {
                     let n = self.decode_u64()?;
-                    wip.put(n).unwrap()
+                    reflect(wip, |wip| wip.put(n))?
                 } else if shape.is_type::<u32>() {
                     let n = self.decode_u64()?;
                     if n > u32::MAX as u64 {
                         return Err(DecodeError::IntegerOverflow);
                     }
-                    wip.put(n as u32).unwrap()
+                    reflect(wip, |wip| wip.put(n as u32))?
                 } else if shape.is_type::<u16>() {
                     let n = self.decode_u64()?;
                     if n > u16::MAX as u64 {
                         return Err(DecodeError::IntegerOverflow);
                     }
-                    wip.put(n as u16).unwrap()
+                    reflect(wip, |wip| wip.put(n as u16))?
                 } else if shape.is_type::<u8>() {
                     let n = self.decode_u64()?;
                     if n > u8::MAX as u64 {
                         return Err(DecodeError::IntegerOverflow);
                     }
-                    wip.put(n as u8).unwrap()
+                    reflect(wip, |wip| wip.put(n as u8))?
                 } else if shape.is_type::<i64>() {
                     // This is a simplification - need to implement proper int decoding
                     let n = self.decode_u64()?;
                     if n > i64::MAX as u64 {
                         return Err(DecodeError::IntegerOverflow);
                     }
-                    wip.put(n as i64).unwrap()
+                    reflect(wip, |wip| wip.put(n as i64))?
                 } else if shape.is_type::<i32>() {
                     let n = self.decode_u64()?;
                     if n > i32::MAX as u64 {
                         return Err(DecodeError::IntegerOverflow);
                     }
-                    wip.put(n as i32).unwrap()
+                    reflect(wip, |wip| wip.put(n as i32))?
                 } else if shape.is_type::<bool>() {
                     let b = self.decode_bool()?;
-                    wip.put(b).unwrap()
+                    reflect(wip, |wip| wip.put(b))?
                 } else {
                     return Err(DecodeError::UnsupportedType(format!("{}", shape)));
                 }
@@ -457,26 +546,57 @@ impl<'input> Decoder<'input> {
             Def::Struct(_) => {
                 trace!("Deserializing struct");
                 let map_len = self.decode_map_len()?;
+                // Each entry is at least a one-byte key and a one-byte value
+                self.check_remaining(map_len, 2)?;
 
                 let mut wip = wip;
                 for _ in 0..map_len {
-                    let key = self.decode_string()?;
+                    let key = self.decode_string(&wip)?;
                     match wip.field_index(&key) {
                         Some(index) => {
-                            wip = self
-                                .deserialize_value(wip.field(index).unwrap())?
-                                .pop()
-                                .unwrap();
+                            wip = reflect(wip, |wip| wip.field(index))?;
+                            wip = self.deserialize_value(wip)?;
+                            wip = reflect(wip, Wip::pop)?;
                         }
                         None => {
                             // Skip unknown field value
-                            self.skip_value()?;
+                            self.skip_value(&wip, wip.frames_count() + 1)?;
                             trace!("Skipping unknown field: {}", key);
                         }
                     }
                 }
                 wip
             }
+            Def::List(_) => {
+                trace!("Deserializing list");
+                let len = self.decode_array_len()?;
+                self.check_limit(LimitKind::CollectionLength, len, &wip)?;
+                self.check_remaining(len, 1)?;
+
+                let mut wip = reflect(wip, Wip::put_default)?;
+                for _ in 0..len {
+                    wip = reflect(wip, Wip::push)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::pop)?;
+                }
+                wip
+            }
+            Def::Map(_) => {
+                trace!("Deserializing map");
+                let len = self.decode_map_len()?;
+                self.check_limit(LimitKind::CollectionLength, len, &wip)?;
+                self.check_remaining(len, 2)?;
+
+                let mut wip = reflect(wip, Wip::put_default)?;
+                for _ in 0..len {
+                    wip = reflect(wip, Wip::push_map_key)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::push_map_value)?;
+                    wip = self.deserialize_value(wip)?;
+                    wip = reflect(wip, Wip::pop)?;
+                }
+                wip
+            }
             _ => {
                 return Err(DecodeError::UnsupportedShape(format!("{:?}", shape)));
             }
@@ -5,6 +5,8 @@
 mod errors;
 pub use errors::Error as DecodeError;
 
+pub use facet_reflect::{LimitKind, Limits};
+
 mod constants;
 pub use constants::*;
 
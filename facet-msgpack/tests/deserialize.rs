@@ -1,5 +1,6 @@
 use eyre::Result;
 use facet::Facet;
+use std::collections::HashMap;
 
 #[test]
 fn it_works() -> Result<()> {
@@ -34,3 +35,31 @@ fn it_works() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn lists_and_maps() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Tagged {
+        tags: Vec<String>,
+    }
+
+    let data = [
+        0x81, // Fixmap with 1 element
+        0xa4, 0x74, 0x61, 0x67, 0x73, // "tags"
+        0x92, // Fixarray with 2 elements
+        0xa1, 0x61, // "a"
+        0xa2, 0x62, 0x63, // "bc"
+    ];
+    let tagged: Tagged = facet_msgpack::from_slice(&data)?;
+    assert_eq!(tagged.tags, vec!["a".to_string(), "bc".to_string()]);
+
+    // {"x": 1, "y": 2}
+    let data = [0x82, 0xa1, 0x78, 0x01, 0xa1, 0x79, 0x02];
+    let map: HashMap<String, u8> = facet_msgpack::from_slice(&data)?;
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["y"], 2);
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use facet::Facet;
+use facet_msgpack::{DecodeError, LimitKind, Limits, from_slice, from_slice_with_limits};
+
+#[derive(Debug, PartialEq, Facet)]
+struct Tagged {
+    tags: Vec<String>,
+}
+
+// {"tags": ["a", "bc"]}
+const TAGGED: [u8; 12] = [
+    0x81, // Fixmap with 1 element
+    0xa4, 0x74, 0x61, 0x67, 0x73, // "tags"
+    0x92, // Fixarray with 2 elements
+    0xa1, 0x61, // "a"
+    0xa2, 0x62, 0x63, // "bc"
+];
+
+#[test]
+fn collection_length_limit() {
+    facet_testhelpers::setup();
+
+    let err = from_slice_with_limits::<Tagged>(&TAGGED, Limits::default().max_collection_len(1))
+        .unwrap_err();
+    match err {
+        DecodeError::LimitExceeded { kind, max, path } => {
+            assert_eq!(kind, LimitKind::CollectionLength);
+            assert_eq!(max, 1);
+            assert_eq!(path, "$.tags");
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn string_length_limit() {
+    facet_testhelpers::setup();
+
+    let err =
+        from_slice_with_limits::<Tagged>(&TAGGED, Limits::default().max_string_len(1)).unwrap_err();
+    assert!(matches!(
+        err,
+        DecodeError::LimitExceeded {
+            kind: LimitKind::StringLength,
+            max: 1,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn huge_length_prefix_is_rejected_before_allocating() {
+    facet_testhelpers::setup();
+
+    // An array32 header claiming four billion items, and nothing after it
+    let data = [0xdd, 0xff, 0xff, 0xff, 0xff];
+    assert!(matches!(
+        from_slice::<Vec<u64>>(&data),
+        Err(DecodeError::InsufficientData)
+    ));
+}
+
+#[test]
+fn depth_limit_applies_to_skipped_values() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Empty {}
+
+    // {"junk": [[[[1]]]]}
+    let data = [
+        0x81, 0xa4, 0x6a, 0x75, 0x6e, 0x6b, 0x91, 0x91, 0x91, 0x91, 0x01,
+    ];
+    assert!(from_slice::<Empty>(&data).is_ok());
+
+    let err = from_slice_with_limits::<Empty>(&data, Limits::default().max_depth(3)).unwrap_err();
+    assert!(matches!(
+        err,
+        DecodeError::LimitExceeded {
+            kind: LimitKind::Depth,
+            ..
+        }
+    ));
+}
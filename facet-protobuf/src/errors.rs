@@ -0,0 +1,67 @@
+use core::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+/// Errors that can occur during protobuf decoding
+pub enum Error {
+    /// Not enough data available to decode a complete record
+    InsufficientData,
+    /// A varint is longer than ten bytes
+    VarintOverflow,
+    /// The data is malformed, e.g. a record for field number 0
+    InvalidData,
+    /// A record has a wire type that doesn't exist, is a deprecated group, or
+    /// doesn't match its field's type
+    InvalidWireType {
+        /// The field number of the record
+        field: u32,
+        /// The wire type of the record
+        wire_type: u8,
+    },
+    /// A string isn't valid UTF-8
+    InvalidUtf8,
+    /// A field number no field claims, with `deny_unknown_fields`
+    UnknownField(u32),
+    /// A message field that has no default is missing from the input
+    MissingField(String),
+    /// An enum value that matches none of the variants' discriminants
+    UnknownEnumValue(i64),
+    /// Integer value is too large for the target type
+    IntegerOverflow,
+    /// Shape is not supported for deserialization
+    UnsupportedShape(String),
+    /// Reflection error
+    ReflectError(facet_reflect::ReflectError),
+}
+
+impl From<facet_reflect::ReflectError> for Error {
+    fn from(err: facet_reflect::ReflectError) -> Self {
+        Self::ReflectError(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InsufficientData => write!(f, "Insufficient data to decode"),
+            Error::VarintOverflow => write!(f, "Varint is too long"),
+            Error::InvalidData => write!(f, "Invalid protobuf data"),
+            Error::InvalidWireType { field, wire_type } => {
+                write!(f, "Invalid wire type {} for field {}", wire_type, field)
+            }
+            Error::InvalidUtf8 => write!(f, "Invalid UTF-8 in string"),
+            Error::UnknownField(field) => write!(f, "Unknown field number: {}", field),
+            Error::MissingField(field) => write!(f, "Missing required field: {}", field),
+            Error::UnknownEnumValue(value) => write!(f, "Unknown enum value: {}", value),
+            Error::IntegerOverflow => write!(f, "Integer value too large for target type"),
+            Error::UnsupportedShape(shape) => {
+                write!(f, "Unsupported shape for deserialization: {}", shape)
+            }
+            Error::ReflectError(err) => {
+                write!(f, "Reflection error: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
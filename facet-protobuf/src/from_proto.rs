@@ -0,0 +1,459 @@
+use std::borrow::Cow;
+
+use crate::errors::Error as DecodeError;
+use crate::types::{FieldType, ScalarType, ValueType, field_type, is_oneof, value_type};
+use crate::wire::{Reader, Record, WireType, WireValue, records, unzigzag};
+use crate::{attr_value, field_numbers};
+
+use facet_core::{Def, Facet, Field, Shape, StructKind};
+use facet_reflect::{HeapValue, Wip};
+use log::trace;
+
+/// Deserializes a protobuf message into a struct, or an enum with data.
+///
+/// Fields are matched by number, so they can come in any order, and the
+/// last record wins when a non-repeated field has several. Fields the type
+/// doesn't know are skipped, unless it has a field marked
+/// `#[facet(proto_unknown)]` to keep them in, or is marked
+/// `#[facet(deny_unknown_fields)]`.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct SearchRequest {
+///     #[facet(proto = 1)]
+///     query: String,
+///     #[facet(proto = 3)]
+///     page: u32,
+///     #[facet(proto = 4)]
+///     tags: Vec<String>,
+/// }
+///
+/// let request: SearchRequest =
+///     facet_protobuf::from_slice(&[0x18, 0x96, 0x01, 0x0a, 0x02, b'h', b'i']).unwrap();
+/// assert_eq!(
+///     request,
+///     SearchRequest { query: "hi".to_string(), page: 150, tags: vec![] }
+/// );
+/// ```
+pub fn from_slice<'input: 'facet, 'facet, T: Facet<'facet>>(
+    input: &'input [u8],
+) -> Result<T, DecodeError> {
+    from_slice_value(Wip::alloc::<T>()?, input)?
+        .materialize::<T>()
+        .map_err(DecodeError::ReflectError)
+}
+
+/// Deserializes a protobuf message into a Facet value.
+#[allow(clippy::needless_lifetimes)]
+pub fn from_slice_value<'mem>(
+    wip: Wip<'mem>,
+    input: &'mem [u8],
+) -> Result<HeapValue<'mem>, DecodeError> {
+    let shape = wip.shape();
+    let wip = match shape.def {
+        Def::Struct(sd) => deserialize_message(wip, shape, sd.fields, input)?,
+        // A message with nothing but the oneof
+        Def::Enum(ed) if is_oneof(shape) => {
+            let records = records(input)?;
+            let found = records
+                .iter()
+                .rev()
+                .find(|r| (r.number as usize).wrapping_sub(1) < ed.variants.len());
+            match found {
+                Some(record) => deserialize_oneof(wip, 1, record)?,
+                None => return Err(DecodeError::MissingField(shape.to_string())),
+            }
+        }
+        _ => return Err(DecodeError::UnsupportedShape(shape.to_string())),
+    };
+    wip.build().map_err(DecodeError::ReflectError)
+}
+
+/// Deserializes the records of a message into the fields of a struct or
+/// variant.
+fn deserialize_message<'input>(
+    mut wip: Wip<'input>,
+    shape: &'static Shape,
+    fields: &'static [Field],
+    input: &'input [u8],
+) -> Result<Wip<'input>, DecodeError> {
+    let records = records(input)?;
+    let mut used = vec![false; records.len()];
+    let mut unknown_field = None;
+
+    let numbers = field_numbers(fields).map_err(DecodeError::UnsupportedShape)?;
+    for (index, (field, number)) in fields.iter().zip(numbers).enumerate() {
+        let Some(number) = number else {
+            unknown_field = Some(index);
+            continue;
+        };
+        let field_type = field_type(field).map_err(DecodeError::UnsupportedShape)?;
+        trace!("Deserializing field {} from {}", field.name, number);
+
+        // Oneofs take one number per variant
+        let span = match field_type {
+            FieldType::Oneof(shape) => match shape.def {
+                Def::Enum(ed) => ed.variants.len() as u32,
+                _ => 1,
+            },
+            _ => 1,
+        };
+        let mut matching = Vec::new();
+        for (record, used) in records.iter().zip(&mut used) {
+            if record.number >= number && record.number - number < span {
+                *used = true;
+                matching.push(*record);
+            }
+        }
+
+        let Some(last) = matching.last() else {
+            if !matches!(field_type, FieldType::Repeated(_) | FieldType::Map(..)) {
+                wip = deserialize_missing(wip, index, field, field_type)?;
+                continue;
+            }
+            // Empty lists and maps
+            wip = wip.field(index)?.put_default()?.pop()?;
+            continue;
+        };
+
+        wip = wip.field(index)?;
+        wip = match field_type {
+            FieldType::Singular(vt) => deserialize_value(wip, vt, last)?,
+            FieldType::Optional(vt) => {
+                let wip = wip.push_some()?;
+                deserialize_value(wip, vt, last)?.pop()?
+            }
+            FieldType::Repeated(vt) => deserialize_repeated(wip, vt, &matching)?,
+            FieldType::Map(kt, vt) => deserialize_map(wip, kt, vt, &matching)?,
+            FieldType::Oneof(_) => {
+                if let Def::Option(_) = wip.shape().def {
+                    let wip = wip.push_some()?;
+                    deserialize_oneof(wip, number, last)?.pop()?
+                } else {
+                    deserialize_oneof(wip, number, last)?
+                }
+            }
+        };
+        wip = wip.pop()?;
+    }
+
+    let mut unknown = records.iter().zip(&used).filter(|(_, used)| !**used);
+    if shape.has_deny_unknown_fields_attr() {
+        if let Some((record, _)) = unknown.next() {
+            return Err(DecodeError::UnknownField(record.number));
+        }
+    }
+    if let Some(index) = unknown_field {
+        let bytes: Vec<u8> = unknown
+            .flat_map(|(record, _)| record.raw)
+            .copied()
+            .collect();
+        wip = wip.field(index)?.put(bytes)?.pop()?;
+    }
+
+    Ok(wip)
+}
+
+/// Fills in a field that has no record: with its `default`, if it has one,
+/// or else the default of its type, which is what proto3 means by a missing
+/// field. For enums, that's the variant whose discriminant is 0. Messages
+/// without a default can't be missing.
+fn deserialize_missing<'input>(
+    wip: Wip<'input>,
+    index: usize,
+    field: &'static Field,
+    field_type: FieldType,
+) -> Result<Wip<'input>, DecodeError> {
+    let wip = wip.field(index)?;
+    let zero = match (field_type, field.shape().def) {
+        (FieldType::Singular(ValueType::Enum(_)), Def::Enum(ed)) => {
+            ed.variants.iter().position(|v| v.discriminant == 0)
+        }
+        _ => None,
+    };
+    let wip = match field.maybe_default_fn() {
+        Some(Some(default_fn)) => wip.put_from_fn(default_fn)?,
+        Some(None) => wip.put_default()?,
+        None if field.shape().vtable.default_in_place.is_some() => wip.put_default()?,
+        None => match zero {
+            Some(zero) => wip.variant(zero)?,
+            None => return Err(DecodeError::MissingField(field.name.to_string())),
+        },
+    };
+    Ok(wip.pop()?)
+}
+
+fn deserialize_repeated<'input>(
+    wip: Wip<'input>,
+    vt: ValueType,
+    records: &[Record<'input>],
+) -> Result<Wip<'input>, DecodeError> {
+    let mut wip = wip.put_default()?;
+    for record in records {
+        match record.value {
+            // Packed numbers, which parsers must accept even for fields
+            // they expect unpacked
+            WireValue::Len(bytes) if vt.is_packable() => {
+                let mut reader = Reader::new(bytes);
+                while !reader.is_empty() {
+                    let value = match vt.wire_type() {
+                        WireType::I32 => WireValue::I32(reader.take_array()?),
+                        WireType::I64 => WireValue::I64(reader.take_array()?),
+                        _ => WireValue::Varint(reader.read_varint()?),
+                    };
+                    let item = Record { value, ..*record };
+                    wip = wip.push()?;
+                    wip = deserialize_value(wip, vt, &item)?;
+                    wip = wip.pop()?;
+                }
+            }
+            _ => {
+                wip = wip.push()?;
+                wip = deserialize_value(wip, vt, record)?;
+                wip = wip.pop()?;
+            }
+        }
+    }
+    Ok(wip)
+}
+
+/// Deserializes map entries, which are messages with the key as field 1
+/// and the value as field 2, either of which may be missing
+fn deserialize_map<'input>(
+    wip: Wip<'input>,
+    kt: ValueType,
+    vt: ValueType,
+    records: &[Record<'input>],
+) -> Result<Wip<'input>, DecodeError> {
+    let mut wip = wip.put_default()?;
+    for record in records {
+        let entry = records_of(record)?;
+        let key = entry.iter().rev().find(|r| r.number == 1);
+        let value = entry.iter().rev().find(|r| r.number == 2);
+
+        wip = wip.push_map_key()?;
+        wip = match key {
+            Some(key) => deserialize_value(wip, kt, key)?,
+            None => wip.put_default()?,
+        };
+        wip = wip.push_map_value()?;
+        wip = match value {
+            Some(value) => deserialize_value(wip, vt, value)?,
+            None => wip.put_default()?,
+        };
+        wip = wip.pop()?;
+    }
+    Ok(wip)
+}
+
+/// Selects the variant of a oneof that the record's number is for.
+fn deserialize_oneof<'input>(
+    wip: Wip<'input>,
+    base: u32,
+    record: &Record<'input>,
+) -> Result<Wip<'input>, DecodeError> {
+    let shape = wip.shape();
+    let Def::Enum(ed) = shape.def else {
+        return Err(DecodeError::UnsupportedShape(shape.to_string()));
+    };
+    let index = (record.number - base) as usize;
+    let variant = &ed.variants[index];
+    let wip = wip.variant(index)?;
+
+    let fields = variant.data.fields;
+    match variant.data.kind {
+        _ if fields.is_empty() => Ok(wip),
+        StructKind::TupleStruct | StructKind::Tuple if fields.len() == 1 => {
+            let field = &fields[0];
+            let vt = value_type(field.shape(), attr_value(field, "proto_type"))
+                .map_err(DecodeError::UnsupportedShape)?;
+            let wip = wip.field(0)?;
+            Ok(deserialize_value(wip, vt, record)?.pop()?)
+        }
+        _ => {
+            let WireValue::Len(bytes) = record.value else {
+                return Err(invalid_wire_type(record));
+            };
+            deserialize_message(wip, shape, fields, bytes)
+        }
+    }
+}
+
+fn invalid_wire_type(record: &Record) -> DecodeError {
+    DecodeError::InvalidWireType {
+        field: record.number,
+        wire_type: record.value.wire_type() as u8,
+    }
+}
+
+/// The records of an embedded message
+fn records_of<'input>(record: &Record<'input>) -> Result<Vec<Record<'input>>, DecodeError> {
+    match record.value {
+        WireValue::Len(bytes) => records(bytes),
+        _ => Err(invalid_wire_type(record)),
+    }
+}
+
+/// A scalar, as read from the wire
+enum Scalar<'input> {
+    Integer(i128),
+    Float(f64),
+    Bool(bool),
+    Str(&'input str),
+    Bytes(&'input [u8]),
+}
+
+/// Deserializes the value of a record into the current frame.
+fn deserialize_value<'input>(
+    wip: Wip<'input>,
+    vt: ValueType,
+    record: &Record<'input>,
+) -> Result<Wip<'input>, DecodeError> {
+    match vt {
+        ValueType::Message(_) => {
+            let WireValue::Len(bytes) = record.value else {
+                return Err(invalid_wire_type(record));
+            };
+            let shape = wip.shape();
+            let Def::Struct(sd) = shape.def else {
+                return Err(DecodeError::UnsupportedShape(shape.to_string()));
+            };
+            deserialize_message(wip, shape, sd.fields, bytes)
+        }
+        // Enums are int32s
+        ValueType::Enum(_) => {
+            let WireValue::Varint(n) = record.value else {
+                return Err(invalid_wire_type(record));
+            };
+            let discriminant = n as i32 as i64;
+            let shape = wip.shape();
+            let Def::Enum(ed) = shape.def else {
+                return Err(DecodeError::UnsupportedShape(shape.to_string()));
+            };
+            let index = ed
+                .variants
+                .iter()
+                .position(|v| v.discriminant == discriminant)
+                .ok_or(DecodeError::UnknownEnumValue(discriminant))?;
+            Ok(wip.variant(index)?)
+        }
+        ValueType::Scalar(st) => {
+            let scalar = match (st, record.value) {
+                (ScalarType::Bool, WireValue::Varint(n)) => Scalar::Bool(n != 0),
+                (ScalarType::Int32, WireValue::Varint(n)) => Scalar::Integer(n as i32 as i128),
+                (ScalarType::Int64, WireValue::Varint(n)) => Scalar::Integer(n as i64 as i128),
+                (ScalarType::Uint32, WireValue::Varint(n)) => Scalar::Integer(n as u32 as i128),
+                (ScalarType::Uint64, WireValue::Varint(n)) => Scalar::Integer(n as i128),
+                (ScalarType::Sint32, WireValue::Varint(n)) => {
+                    Scalar::Integer(unzigzag(n as u32 as u64) as i128)
+                }
+                (ScalarType::Sint64, WireValue::Varint(n)) => Scalar::Integer(unzigzag(n) as i128),
+                (ScalarType::Fixed32, WireValue::I32(b)) => {
+                    Scalar::Integer(u32::from_le_bytes(b) as i128)
+                }
+                (ScalarType::Sfixed32, WireValue::I32(b)) => {
+                    Scalar::Integer(i32::from_le_bytes(b) as i128)
+                }
+                (ScalarType::Fixed64, WireValue::I64(b)) => {
+                    Scalar::Integer(u64::from_le_bytes(b) as i128)
+                }
+                (ScalarType::Sfixed64, WireValue::I64(b)) => {
+                    Scalar::Integer(i64::from_le_bytes(b) as i128)
+                }
+                (ScalarType::Float, WireValue::I32(b)) => {
+                    Scalar::Float(f32::from_le_bytes(b) as f64)
+                }
+                (ScalarType::Double, WireValue::I64(b)) => Scalar::Float(f64::from_le_bytes(b)),
+                (ScalarType::String, WireValue::Len(b)) => {
+                    Scalar::Str(core::str::from_utf8(b).map_err(|_| DecodeError::InvalidUtf8)?)
+                }
+                (ScalarType::Bytes, WireValue::Len(b)) => Scalar::Bytes(b),
+                _ => return Err(invalid_wire_type(record)),
+            };
+            put_scalar(wip, scalar)
+        }
+    }
+}
+
+/// Follows transparent wrappers and smart pointers down to the scalar they
+/// hold, if any.
+fn scalar_target(mut shape: &'static Shape) -> &'static Shape {
+    loop {
+        if let Def::Scalar(_) = shape.def {
+            return shape;
+        }
+        match shape.inner {
+            Some(inner) => shape = inner(),
+            None => return shape,
+        }
+    }
+}
+
+/// Puts a scalar into the current frame, which is either a scalar of the
+/// type it was read as or a wrapper around one.
+fn put_scalar<'input>(
+    wip: Wip<'input>,
+    scalar: Scalar<'input>,
+) -> Result<Wip<'input>, DecodeError> {
+    let target = scalar_target(wip.shape());
+    trace!("Deserializing scalar {}", target);
+
+    match scalar {
+        Scalar::Integer(n) => {
+            macro_rules! integers {
+                ($($ty:ty),*) => {
+                    $(
+                        if target.is_type::<$ty>() {
+                            let n = <$ty>::try_from(n).map_err(|_| DecodeError::IntegerOverflow)?;
+                            return Ok(wip.put(n)?);
+                        }
+                    )*
+                };
+            }
+            integers!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+            // Integers this crate doesn't know about, such as `NonZero`s,
+            // are parsed from their text form
+            Ok(wip.parse(&n.to_string())?)
+        }
+        Scalar::Float(f) => {
+            if target.is_type::<f32>() {
+                Ok(wip.put(f as f32)?)
+            } else if target.is_type::<f64>() {
+                Ok(wip.put(f)?)
+            } else {
+                Ok(wip.parse(&f.to_string())?)
+            }
+        }
+        Scalar::Bool(b) => Ok(wip.put(b)?),
+        Scalar::Str(s) => {
+            if target.is_type::<String>() {
+                Ok(wip.put(s.to_string())?)
+            } else if target.is_type::<&str>() {
+                Ok(wip.put(s)?)
+            } else if target.is_type::<Cow<'_, str>>() {
+                Ok(wip.put(Cow::Borrowed(s))?)
+            } else if target.is_type::<char>() {
+                let mut chars = s.chars();
+                let (Some(c), None) = (chars.next(), chars.next()) else {
+                    return Err(DecodeError::InvalidData);
+                };
+                Ok(wip.put(c)?)
+            } else {
+                Ok(wip.parse(s)?)
+            }
+        }
+        Scalar::Bytes(b) => {
+            if target.is_type::<Vec<u8>>() {
+                Ok(wip.put(b.to_vec())?)
+            } else if target.is_type::<&[u8]>() {
+                Ok(wip.put(b)?)
+            } else {
+                Err(DecodeError::UnsupportedShape(target.to_string()))
+            }
+        }
+    }
+}
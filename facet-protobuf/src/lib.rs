@@ -0,0 +1,124 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+use core::ops::RangeInclusive;
+
+use facet_core::{Def, Field, FieldAttribute};
+
+use crate::types::{FieldType, field_type};
+
+mod errors;
+pub use errors::Error as DecodeError;
+
+mod types;
+
+mod wire;
+
+mod from_proto;
+pub use from_proto::*;
+
+mod to_proto;
+pub use to_proto::*;
+
+mod proto_file;
+pub use proto_file::*;
+
+/// The highest field number protobuf allows
+const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+/// Field numbers protobuf keeps for its own implementation
+const RESERVED_FIELD_NUMBERS: RangeInclusive<u32> = 19000..=19999;
+
+/// The value of a `#[facet(key = value)]` attribute on a field
+fn attr_value(field: &Field, key: &str) -> Option<&'static str> {
+    field.attributes.iter().find_map(|attr| match attr {
+        FieldAttribute::Arbitrary(attr) => {
+            let (k, value) = attr.split_once('=')?;
+            (k.trim() == key).then(|| value.trim().trim_matches('"'))
+        }
+        _ => None,
+    })
+}
+
+/// The field numbers of the fields of a struct or variant, in order, with
+/// `None` for the field that keeps unknown fields.
+///
+/// A field's number is the one given with `#[facet(proto = 3)]`, or else
+/// its position, counting from 1. A oneof takes one number per variant,
+/// from its own, and moves the positions of the fields after it up by as
+/// many as it takes beyond the first. Numbers out of range, in the range
+/// protobuf reserves, or that two fields share are errors.
+fn field_numbers(
+    fields: impl IntoIterator<Item = &'static Field>,
+) -> Result<Vec<Option<u32>>, String> {
+    let mut numbers = Vec::new();
+    // The first and last number of each field so far
+    let mut taken: Vec<(u32, u32, &str)> = Vec::new();
+    let mut shift = 0;
+    for (index, field) in fields.into_iter().enumerate() {
+        if is_unknown_fields(field) {
+            numbers.push(None);
+            continue;
+        }
+        let span = match field_type(field)? {
+            FieldType::Oneof(shape) => match shape.def {
+                Def::Enum(ed) => (ed.variants.len() as u32).max(1),
+                _ => 1,
+            },
+            _ => 1,
+        };
+        let first = match attr_value(field, "proto") {
+            Some(number) => number
+                .parse()
+                .map_err(|_| format!("invalid field number for {}: {}", field.name, number))?,
+            None => (index as u32).saturating_add(shift).saturating_add(1),
+        };
+        shift += span - 1;
+
+        let last = first.saturating_add(span - 1);
+        if first == 0 || last > MAX_FIELD_NUMBER {
+            let number = if first == 0 { first } else { last };
+            return Err(format!(
+                "field number of {} out of range: {}",
+                field.name, number
+            ));
+        }
+        if first <= *RESERVED_FIELD_NUMBERS.end() && last >= *RESERVED_FIELD_NUMBERS.start() {
+            return Err(format!(
+                "field number of {} is reserved by protobuf: {}",
+                field.name,
+                first.max(*RESERVED_FIELD_NUMBERS.start())
+            ));
+        }
+        if let Some((other_first, _, other)) = taken
+            .iter()
+            .find(|(other_first, other_last, _)| first <= *other_last && *other_first <= last)
+        {
+            return Err(format!(
+                "{} and {} share field number {}",
+                other,
+                field.name,
+                first.max(*other_first)
+            ));
+        }
+        taken.push((first, last, field.name));
+        numbers.push(Some(first));
+    }
+    Ok(numbers)
+}
+
+/// A field marked `#[facet(proto_unknown)]`, of type `Vec<u8>`, keeps the
+/// fields of the message that no other field claimed, so that they are
+/// written back as they were
+fn is_unknown_fields(field: &Field) -> bool {
+    field
+        .attributes
+        .iter()
+        .any(|attr| matches!(attr, FieldAttribute::Arbitrary(a) if a.trim() == "proto_unknown"))
+}
+
+/// The name of a field in `.proto` files
+fn field_name(field: &'static Field) -> &'static str {
+    field.get_rename_attr().unwrap_or(field.name)
+}
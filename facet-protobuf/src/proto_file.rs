@@ -0,0 +1,280 @@
+use core::fmt::Write;
+
+use crate::types::{FieldType, ValueType, field_type, is_oneof, unwrap, value_type};
+use crate::{attr_value, field_name, field_numbers};
+
+use facet_core::{Def, Facet, Field, Shape, StructKind};
+
+/// Writes a proto3 `.proto` file describing the messages that
+/// [`to_vec`](crate::to_vec) writes and [`from_slice`](crate::from_slice)
+/// reads for a type, so that services built with other protobuf libraries
+/// can talk to it.
+///
+/// Structs are messages named after the type, enums of unit variants are
+/// protobuf enums, and enums with data are oneofs whose variants are
+/// messages named after the enum and the variant. A oneof at the root is a
+/// message holding nothing but the oneof. Each type is written once, so
+/// types that contain themselves are fine.
+///
+/// proto3 wants enums to start with a value of 0, so the variant with the
+/// discriminant 0 comes first.
+///
+/// Fails if the type, or a type it contains, can't be encoded as protobuf,
+/// as [`to_vec`](crate::to_vec) would.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// /// A search query
+/// #[derive(Facet)]
+/// struct SearchRequest {
+///     #[facet(proto = 1)]
+///     query: String,
+///     #[facet(proto = 3)]
+///     page: Option<u32>,
+/// }
+///
+/// assert_eq!(
+///     facet_protobuf::to_proto_file::<SearchRequest>(Some("search.v1")).unwrap(),
+///     r#"syntax = "proto3";
+///
+/// package search.v1;
+///
+/// // A search query
+/// message SearchRequest {
+///   string query = 1;
+///   optional uint32 page = 3;
+/// }
+/// "#
+/// );
+/// ```
+pub fn to_proto_file<'a, T: Facet<'a>>(package: Option<&str>) -> Result<String, String> {
+    let mut out = String::from("syntax = \"proto3\";\n");
+    if let Some(package) = package {
+        writeln!(out, "\npackage {};", package).unwrap();
+    }
+
+    let root = unwrap(T::SHAPE);
+    let mut file = ProtoFile {
+        definitions: vec![match root.def {
+            Def::Struct(_) => Definition::Message(root),
+            Def::Enum(_) if is_oneof(root) => Definition::OneofMessage(root),
+            _ => return Err(format!("{} can't be a protobuf message", root)),
+        }],
+    };
+
+    // Definitions add the ones they refer to as they're written
+    let mut index = 0;
+    while index < file.definitions.len() {
+        out.push('\n');
+        file.write_definition(file.definitions[index], &mut out)?;
+        index += 1;
+    }
+    Ok(out)
+}
+
+/// Something that gets a top-level definition in the file
+#[derive(Clone, Copy, PartialEq)]
+enum Definition {
+    /// A struct
+    Message(&'static Shape),
+    /// An enum of unit variants
+    Enum(&'static Shape),
+    /// A message holding nothing but a oneof, for enums with data at the
+    /// root
+    OneofMessage(&'static Shape),
+    /// The message of a oneof variant that isn't a newtype
+    Variant(&'static Shape, usize),
+}
+
+struct ProtoFile {
+    /// All definitions the file needs, in the order they're written
+    definitions: Vec<Definition>,
+}
+
+impl ProtoFile {
+    /// Returns the name of a definition, adding it to those to write if it
+    /// isn't already there.
+    fn refer(&mut self, definition: Definition) -> String {
+        if !self.definitions.contains(&definition) {
+            self.definitions.push(definition);
+        }
+        match definition {
+            Definition::Message(shape)
+            | Definition::Enum(shape)
+            | Definition::OneofMessage(shape) => type_name(shape),
+            Definition::Variant(shape, index) => {
+                format!("{}{}", type_name(shape), variants(shape)[index].name)
+            }
+        }
+    }
+
+    fn value_type_name(&mut self, vt: ValueType) -> String {
+        match vt {
+            ValueType::Scalar(scalar) => scalar.name().to_string(),
+            ValueType::Message(shape) => self.refer(Definition::Message(shape)),
+            ValueType::Enum(shape) => self.refer(Definition::Enum(shape)),
+        }
+    }
+
+    fn write_definition(&mut self, definition: Definition, out: &mut String) -> Result<(), String> {
+        let name = self.refer(definition);
+        match definition {
+            Definition::Message(shape) => {
+                write_doc(shape.doc, "", out);
+                writeln!(out, "message {} {{", name).unwrap();
+                let Def::Struct(sd) = shape.def else {
+                    unreachable!()
+                };
+                self.write_fields(sd.fields, out)?;
+                out.push_str("}\n");
+            }
+            Definition::Enum(shape) => {
+                write_doc(shape.doc, "", out);
+                writeln!(out, "enum {} {{", name).unwrap();
+                let prefix = screaming_snake_case(&name);
+                let mut values: Vec<_> = variants(shape).iter().collect();
+                values.sort_by_key(|v| v.discriminant != 0);
+                for variant in values {
+                    write_doc(variant.doc, "  ", out);
+                    writeln!(
+                        out,
+                        "  {}_{} = {};",
+                        prefix,
+                        screaming_snake_case(variant.name),
+                        variant.discriminant
+                    )
+                    .unwrap();
+                }
+                out.push_str("}\n");
+            }
+            Definition::OneofMessage(shape) => {
+                write_doc(shape.doc, "", out);
+                writeln!(out, "message {} {{", name).unwrap();
+                self.write_oneof(shape, "value", 1, "  ", out)?;
+                out.push_str("}\n");
+            }
+            Definition::Variant(shape, index) => {
+                let variant = &variants(shape)[index];
+                write_doc(variant.doc, "", out);
+                writeln!(out, "message {} {{", name).unwrap();
+                self.write_fields(variant.data.fields, out)?;
+                out.push_str("}\n");
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the fields of a struct or variant
+    fn write_fields(&mut self, fields: &'static [Field], out: &mut String) -> Result<(), String> {
+        let numbers = field_numbers(fields)?;
+        for (field, number) in fields.iter().zip(numbers) {
+            // Unknown fields aren't part of the schema
+            let Some(number) = number else {
+                continue;
+            };
+            let name = field_name(field);
+            write_doc(field.doc, "  ", out);
+
+            let line = match field_type(field)? {
+                FieldType::Singular(vt) => self.value_type_name(vt),
+                FieldType::Optional(vt) => format!("optional {}", self.value_type_name(vt)),
+                FieldType::Repeated(vt) => format!("repeated {}", self.value_type_name(vt)),
+                FieldType::Map(kt, vt) => format!(
+                    "map<{}, {}>",
+                    self.value_type_name(kt),
+                    self.value_type_name(vt)
+                ),
+                FieldType::Oneof(shape) => {
+                    self.write_oneof(shape, name, number, "  ", out)?;
+                    continue;
+                }
+            };
+            writeln!(out, "  {} {} = {};", line, name, number).unwrap();
+        }
+        Ok(())
+    }
+
+    /// Writes a oneof, whose variants are numbered from `base`
+    fn write_oneof(
+        &mut self,
+        shape: &'static Shape,
+        name: &str,
+        base: u32,
+        indent: &str,
+        out: &mut String,
+    ) -> Result<(), String> {
+        writeln!(out, "{}oneof {} {{", indent, name).unwrap();
+        for (index, variant) in variants(shape).iter().enumerate() {
+            let fields = variant.data.fields;
+            let newtype = matches!(
+                variant.data.kind,
+                StructKind::TupleStruct | StructKind::Tuple
+            ) && fields.len() == 1;
+
+            let type_name = if newtype {
+                let field = &fields[0];
+                let vt = value_type(field.shape(), attr_value(field, "proto_type"))?;
+                self.value_type_name(vt)
+            } else {
+                self.refer(Definition::Variant(shape, index))
+            };
+            write_doc(variant.doc, &format!("{}  ", indent), out);
+            writeln!(
+                out,
+                "{}  {} {} = {};",
+                indent,
+                type_name,
+                snake_case(variant.name),
+                base + index as u32
+            )
+            .unwrap();
+        }
+        writeln!(out, "{}}}", indent).unwrap();
+        Ok(())
+    }
+}
+
+fn variants(shape: &'static Shape) -> &'static [facet_core::Variant] {
+    match shape.def {
+        Def::Enum(ed) => ed.variants,
+        _ => &[],
+    }
+}
+
+/// The name of a message or enum: the type's name, with the punctuation of
+/// generic parameters replaced
+fn type_name(shape: &'static Shape) -> String {
+    let name: String = shape
+        .to_string()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    name.trim_end_matches('_').to_string()
+}
+
+fn write_doc(doc: &[&str], indent: &str, out: &mut String) {
+    for line in doc {
+        writeln!(out, "{}//{}", indent, line).unwrap();
+    }
+}
+
+/// `SomeName` to `some_name`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev = None;
+    for c in name.chars() {
+        if c.is_uppercase() && prev.is_some_and(|p: char| p.is_lowercase() || p.is_ascii_digit()) {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
+        prev = Some(c);
+    }
+    snake
+}
+
+/// `SomeName` to `SOME_NAME`
+fn screaming_snake_case(name: &str) -> String {
+    snake_case(name).to_uppercase()
+}
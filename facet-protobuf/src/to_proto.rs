@@ -0,0 +1,368 @@
+use crate::types::{FieldType, ScalarType, ValueType, field_type, is_oneof, value_type};
+use crate::wire::{write_key, write_len, write_varint, zigzag};
+use crate::{MAX_FIELD_NUMBER, attr_value, field_numbers};
+
+use facet_core::{Def, Facet, Field, StructKind};
+use facet_reflect::{Peek, PeekEnum};
+use log::trace;
+use std::io::{self, Write};
+
+/// Serializes a struct, or an enum with data, to a protobuf message
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct SearchRequest {
+///     #[facet(proto = 1)]
+///     query: String,
+///     #[facet(proto = 3)]
+///     page: u32,
+/// }
+///
+/// let request = SearchRequest { query: "hi".to_string(), page: 150 };
+/// assert_eq!(
+///     facet_protobuf::to_vec(&request),
+///     [0x0a, 0x02, b'h', b'i', 0x18, 0x96, 0x01]
+/// );
+/// ```
+pub fn to_vec<'a, T: Facet<'a>>(value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_root(Peek::new(value), &mut out).unwrap();
+    out
+}
+
+/// Serializes a struct, or an enum with data, to a writer as a protobuf
+/// message
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    let mut out = Vec::new();
+    write_root(Peek::new(value), &mut out)?;
+    writer.write_all(&out)
+}
+
+fn other(e: impl core::fmt::Display) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+fn unsupported(peek: Peek<'_, '_>) -> io::Error {
+    io::Error::other(format!("Unsupported type: {}", peek.shape()))
+}
+
+/// Looks through transparent wrappers and smart pointers
+fn unwrap<'mem, 'facet>(mut peek: Peek<'mem, 'facet>) -> Peek<'mem, 'facet> {
+    loop {
+        if let Def::Scalar(_) | Def::Option(_) = peek.shape().def {
+            return peek;
+        }
+        match peek.borrow_inner() {
+            Some(inner) => peek = inner,
+            None => return peek,
+        }
+    }
+}
+
+fn write_root(peek: Peek<'_, '_>, out: &mut Vec<u8>) -> io::Result<()> {
+    let peek = unwrap(peek);
+    match peek.shape().def {
+        Def::Struct(_) => {
+            let ps = peek.into_struct().map_err(other)?;
+            write_fields(ps.fields(), out)
+        }
+        // A message with nothing but the oneof
+        Def::Enum(_) if is_oneof(peek.shape()) => {
+            write_oneof(peek.into_enum().map_err(other)?, 1, out)
+        }
+        _ => Err(unsupported(peek)),
+    }
+}
+
+/// Writes the fields of a struct or variant as the records of a message
+fn write_fields<'mem, 'facet>(
+    fields: impl Iterator<Item = (&'static Field, Peek<'mem, 'facet>)>,
+    out: &mut Vec<u8>,
+) -> io::Result<()> {
+    let fields: Vec<_> = fields.collect();
+    let numbers =
+        field_numbers(fields.iter().map(|(field, _)| *field)).map_err(io::Error::other)?;
+    let mut unknown = None;
+
+    for ((field, value), number) in fields.into_iter().zip(numbers) {
+        let Some(number) = number else {
+            unknown = Some(value);
+            continue;
+        };
+        trace!("Serializing field {} as {}", field.name, number);
+
+        match field_type(field).map_err(io::Error::other)? {
+            FieldType::Singular(vt) => write_value(out, number, vt, value, true)?,
+            FieldType::Optional(vt) => {
+                if let Some(value) = option_value(value)? {
+                    write_value(out, number, vt, value, false)?;
+                }
+            }
+            FieldType::Repeated(vt) => {
+                let items = list_items(value)?;
+                if vt.is_packable() {
+                    if !items.is_empty() {
+                        let mut packed = Vec::new();
+                        for item in items {
+                            payload(vt, item)?.write(&mut packed);
+                        }
+                        write_len(out, number, &packed);
+                    }
+                } else {
+                    for item in items {
+                        write_value(out, number, vt, item, false)?;
+                    }
+                }
+            }
+            FieldType::Map(kt, vt) => {
+                let map = unwrap(value).into_map().map_err(other)?;
+                for (key, value) in map.iter() {
+                    let mut entry = Vec::new();
+                    write_value(&mut entry, 1, kt, key, false)?;
+                    write_value(&mut entry, 2, vt, value, false)?;
+                    write_len(out, number, &entry);
+                }
+            }
+            FieldType::Oneof(_) => {
+                let value = match unwrap(value).shape().def {
+                    Def::Option(_) => option_value(value)?,
+                    _ => Some(value),
+                };
+                if let Some(value) = value {
+                    write_oneof(unwrap(value).into_enum().map_err(other)?, number, out)?;
+                }
+            }
+        }
+    }
+
+    // Fields this version of the message doesn't know, as they were read
+    if let Some(unknown) = unknown {
+        let unknown = unwrap(unknown);
+        out.extend_from_slice(unknown.get::<Vec<u8>>().map_err(other)?);
+    }
+    Ok(())
+}
+
+/// Writes the active variant of a oneof, as the field numbered `base` plus
+/// the variant's index. Unit variants are empty messages, newtype variants
+/// their value, and others a message of their fields.
+fn write_oneof(pe: PeekEnum<'_, '_>, base: u32, out: &mut Vec<u8>) -> io::Result<()> {
+    let variant = pe.active_variant();
+    let number = base + pe.variant_index() as u32;
+    if number > MAX_FIELD_NUMBER {
+        return Err(io::Error::other(format!(
+            "field number of {} out of range: {}",
+            variant.name, number
+        )));
+    }
+
+    let fields = variant.data.fields;
+    match variant.data.kind {
+        _ if fields.is_empty() => write_len(out, number, &[]),
+        StructKind::TupleStruct | StructKind::Tuple if fields.len() == 1 => {
+            let field = &fields[0];
+            let vt = value_type(field.shape(), attr_value(field, "proto_type"))
+                .map_err(io::Error::other)?;
+            let value = pe
+                .field(0)
+                .ok_or_else(|| other("Failed to access enum field"))?;
+            write_value(out, number, vt, value, false)?;
+        }
+        _ => {
+            let mut message = Vec::new();
+            write_fields(pe.fields(), &mut message)?;
+            write_len(out, number, &message);
+        }
+    }
+    Ok(())
+}
+
+fn option_value<'mem, 'facet>(peek: Peek<'mem, 'facet>) -> io::Result<Option<Peek<'mem, 'facet>>> {
+    Ok(unwrap(peek).into_option().map_err(other)?.value())
+}
+
+fn list_items<'mem, 'facet>(peek: Peek<'mem, 'facet>) -> io::Result<Vec<Peek<'mem, 'facet>>> {
+    let peek = unwrap(peek);
+    match peek.shape().def {
+        Def::List(_) => Ok(peek.into_list().map_err(other)?.iter().collect()),
+        Def::Slice(_) => Ok(peek.into_slice().map_err(other)?.iter().collect()),
+        _ => Err(unsupported(peek)),
+    }
+}
+
+/// Writes a record, unless `skip_default` is set and it holds a scalar's
+/// default value, which proto3 leaves out
+fn write_value(
+    out: &mut Vec<u8>,
+    number: u32,
+    vt: ValueType,
+    value: Peek<'_, '_>,
+    skip_default: bool,
+) -> io::Result<()> {
+    let payload = payload(vt, value)?;
+    if skip_default && !matches!(vt, ValueType::Message(_)) && payload.is_default() {
+        return Ok(());
+    }
+    write_key(out, number, vt.wire_type());
+    payload.write(out);
+    Ok(())
+}
+
+/// The value of a record
+enum Payload {
+    Varint(u64),
+    I32([u8; 4]),
+    I64([u8; 8]),
+    Len(Vec<u8>),
+}
+
+impl Payload {
+    fn is_default(&self) -> bool {
+        match self {
+            Payload::Varint(n) => *n == 0,
+            Payload::I32(bytes) => bytes.iter().all(|&b| b == 0),
+            Payload::I64(bytes) => bytes.iter().all(|&b| b == 0),
+            Payload::Len(bytes) => bytes.is_empty(),
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Payload::Varint(n) => write_varint(out, *n),
+            Payload::I32(bytes) => out.extend_from_slice(bytes),
+            Payload::I64(bytes) => out.extend_from_slice(bytes),
+            Payload::Len(bytes) => {
+                write_varint(out, bytes.len() as u64);
+                out.extend_from_slice(bytes);
+            }
+        }
+    }
+}
+
+fn payload(vt: ValueType, value: Peek<'_, '_>) -> io::Result<Payload> {
+    let value = unwrap(value);
+    match vt {
+        ValueType::Message(_) => {
+            let ps = value.into_struct().map_err(other)?;
+            let mut message = Vec::new();
+            write_fields(ps.fields(), &mut message)?;
+            Ok(Payload::Len(message))
+        }
+        // Enums are int32s, which are sign-extended to 64 bits
+        ValueType::Enum(_) => {
+            let variant = value.into_enum().map_err(other)?.active_variant();
+            Ok(Payload::Varint(variant.discriminant as u64))
+        }
+        ValueType::Scalar(st) => scalar_payload(st, value),
+    }
+}
+
+fn scalar_payload(st: ScalarType, value: Peek<'_, '_>) -> io::Result<Payload> {
+    let out_of_range =
+        |n: i128| io::Error::other(format!("{} is out of range for {}", n, st.name()));
+
+    Ok(match st {
+        ScalarType::Bool => Payload::Varint(*value.get::<bool>().map_err(other)? as u64),
+        ScalarType::String => Payload::Len(text(value)?.into_bytes()),
+        ScalarType::Bytes => Payload::Len(bytes(value)?),
+        ScalarType::Float => Payload::I32((float(value)? as f32).to_le_bytes()),
+        ScalarType::Double => Payload::I64(float(value)?.to_le_bytes()),
+        _ => {
+            let n = integer(value)?;
+            match st {
+                ScalarType::Int32 => {
+                    Payload::Varint(i32::try_from(n).map_err(|_| out_of_range(n))? as i64 as u64)
+                }
+                ScalarType::Int64 => {
+                    Payload::Varint(i64::try_from(n).map_err(|_| out_of_range(n))? as u64)
+                }
+                ScalarType::Uint32 => {
+                    Payload::Varint(u32::try_from(n).map_err(|_| out_of_range(n))? as u64)
+                }
+                ScalarType::Uint64 => {
+                    Payload::Varint(u64::try_from(n).map_err(|_| out_of_range(n))?)
+                }
+                ScalarType::Sint32 => {
+                    Payload::Varint(zigzag(i32::try_from(n).map_err(|_| out_of_range(n))? as i64))
+                }
+                ScalarType::Sint64 => {
+                    Payload::Varint(zigzag(i64::try_from(n).map_err(|_| out_of_range(n))?))
+                }
+                ScalarType::Fixed32 => {
+                    Payload::I32(u32::try_from(n).map_err(|_| out_of_range(n))?.to_le_bytes())
+                }
+                ScalarType::Sfixed32 => {
+                    Payload::I32(i32::try_from(n).map_err(|_| out_of_range(n))?.to_le_bytes())
+                }
+                ScalarType::Fixed64 => {
+                    Payload::I64(u64::try_from(n).map_err(|_| out_of_range(n))?.to_le_bytes())
+                }
+                ScalarType::Sfixed64 => {
+                    Payload::I64(i64::try_from(n).map_err(|_| out_of_range(n))?.to_le_bytes())
+                }
+                _ => unreachable!("{} is not an integer type", st.name()),
+            }
+        }
+    })
+}
+
+/// The value of an integer. Integers this crate doesn't know about, such as
+/// `NonZero`s, are read from their text form.
+fn integer(value: Peek<'_, '_>) -> io::Result<i128> {
+    macro_rules! integers {
+        ($($ty:ty),*) => {
+            $(
+                if let Ok(n) = value.get::<$ty>() {
+                    return Ok(*n as i128);
+                }
+            )*
+        };
+    }
+    integers!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+    if value.shape().vtable.display.is_some() {
+        if let Ok(n) = value.to_string().parse() {
+            return Ok(n);
+        }
+    }
+    Err(unsupported(value))
+}
+
+fn float(value: Peek<'_, '_>) -> io::Result<f64> {
+    if let Ok(f) = value.get::<f64>() {
+        Ok(*f)
+    } else if let Ok(f) = value.get::<f32>() {
+        Ok(*f as f64)
+    } else {
+        Err(unsupported(value))
+    }
+}
+
+/// The text of strings, characters, and other scalars through their
+/// `Display` implementation
+fn text(value: Peek<'_, '_>) -> io::Result<String> {
+    if let Ok(s) = value.get::<String>() {
+        Ok(s.clone())
+    } else if let Ok(s) = value.get::<&str>() {
+        Ok(s.to_string())
+    } else if let Ok(s) = value.get::<std::borrow::Cow<'_, str>>() {
+        Ok(s.to_string())
+    } else if value.shape().vtable.display.is_some() {
+        Ok(value.to_string())
+    } else {
+        Err(unsupported(value))
+    }
+}
+
+fn bytes(value: Peek<'_, '_>) -> io::Result<Vec<u8>> {
+    if let Ok(bytes) = value.get::<Vec<u8>>() {
+        Ok(bytes.clone())
+    } else if let Ok(bytes) = value.get::<&[u8]>() {
+        Ok(bytes.to_vec())
+    } else {
+        Err(unsupported(value))
+    }
+}
@@ -0,0 +1,262 @@
+use facet_core::{Def, Field, NumberBits, ScalarAffinity, ScalarDef, Shape, Signedness};
+
+use crate::attr_value;
+use crate::wire::WireType;
+
+/// The scalar value types of protobuf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScalarType {
+    Double,
+    Float,
+    Int32,
+    Int64,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+    Fixed32,
+    Fixed64,
+    Sfixed32,
+    Sfixed64,
+    Bool,
+    String,
+    Bytes,
+}
+
+impl ScalarType {
+    const ALL: [ScalarType; 15] = [
+        ScalarType::Double,
+        ScalarType::Float,
+        ScalarType::Int32,
+        ScalarType::Int64,
+        ScalarType::Uint32,
+        ScalarType::Uint64,
+        ScalarType::Sint32,
+        ScalarType::Sint64,
+        ScalarType::Fixed32,
+        ScalarType::Fixed64,
+        ScalarType::Sfixed32,
+        ScalarType::Sfixed64,
+        ScalarType::Bool,
+        ScalarType::String,
+        ScalarType::Bytes,
+    ];
+
+    /// The name of the type in `.proto` files
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ScalarType::Double => "double",
+            ScalarType::Float => "float",
+            ScalarType::Int32 => "int32",
+            ScalarType::Int64 => "int64",
+            ScalarType::Uint32 => "uint32",
+            ScalarType::Uint64 => "uint64",
+            ScalarType::Sint32 => "sint32",
+            ScalarType::Sint64 => "sint64",
+            ScalarType::Fixed32 => "fixed32",
+            ScalarType::Fixed64 => "fixed64",
+            ScalarType::Sfixed32 => "sfixed32",
+            ScalarType::Sfixed64 => "sfixed64",
+            ScalarType::Bool => "bool",
+            ScalarType::String => "string",
+            ScalarType::Bytes => "bytes",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.name() == name)
+    }
+
+    pub(crate) fn wire_type(self) -> WireType {
+        match self {
+            ScalarType::Double | ScalarType::Fixed64 | ScalarType::Sfixed64 => WireType::I64,
+            ScalarType::Float | ScalarType::Fixed32 | ScalarType::Sfixed32 => WireType::I32,
+            ScalarType::String | ScalarType::Bytes => WireType::Len,
+            _ => WireType::Varint,
+        }
+    }
+
+    /// Whether repeated values of this type are packed into a single
+    /// length-delimited record
+    pub(crate) fn is_packable(self) -> bool {
+        self.wire_type() != WireType::Len
+    }
+
+    fn is_integer(self) -> bool {
+        !matches!(
+            self,
+            ScalarType::Double
+                | ScalarType::Float
+                | ScalarType::Bool
+                | ScalarType::String
+                | ScalarType::Bytes
+        )
+    }
+
+    fn is_float(self) -> bool {
+        matches!(self, ScalarType::Double | ScalarType::Float)
+    }
+}
+
+/// What a field holds, once options, lists and maps are looked through
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ValueType {
+    Scalar(ScalarType),
+    /// A struct, as an embedded message
+    Message(&'static Shape),
+    /// An enum of unit variants, as its discriminant
+    Enum(&'static Shape),
+}
+
+impl ValueType {
+    pub(crate) fn wire_type(self) -> WireType {
+        match self {
+            ValueType::Scalar(scalar) => scalar.wire_type(),
+            ValueType::Message(_) => WireType::Len,
+            ValueType::Enum(_) => WireType::Varint,
+        }
+    }
+
+    pub(crate) fn is_packable(self) -> bool {
+        self.wire_type() != WireType::Len
+    }
+}
+
+/// How a field of a message is encoded
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum FieldType {
+    /// A plain field, left out when it has its default value
+    Singular(ValueType),
+    /// An `Option`, left out when `None`
+    Optional(ValueType),
+    /// A list, packed when it holds numbers
+    Repeated(ValueType),
+    /// A map, as repeated entry messages with the key as field 1 and the
+    /// value as field 2
+    Map(ValueType, ValueType),
+    /// An enum with data, or an option of one, whose variants take the
+    /// field numbers from the field's own onward
+    Oneof(&'static Shape),
+}
+
+/// Looks through transparent wrappers and smart pointers
+pub(crate) fn unwrap(mut shape: &'static Shape) -> &'static Shape {
+    loop {
+        if let Def::Scalar(_) | Def::Option(_) = shape.def {
+            return shape;
+        }
+        match shape.inner {
+            Some(inner) => shape = inner(),
+            None => return shape,
+        }
+    }
+}
+
+fn is_bytes(shape: &'static Shape) -> bool {
+    shape.is_type::<Vec<u8>>() || shape.is_type::<&[u8]>()
+}
+
+/// Enums with data are oneofs, the others protobuf enums
+pub(crate) fn is_oneof(shape: &'static Shape) -> bool {
+    match shape.def {
+        Def::Enum(ed) => ed.variants.iter().any(|v| !v.data.fields.is_empty()),
+        _ => false,
+    }
+}
+
+/// How a field is encoded. `#[facet(proto_type = "fixed64")]` picks
+/// another scalar type than the default one for its numbers.
+pub(crate) fn field_type(field: &'static Field) -> Result<FieldType, String> {
+    let over = attr_value(field, "proto_type");
+    let shape = unwrap(field.shape());
+
+    if is_bytes(shape) {
+        return Ok(FieldType::Singular(ValueType::Scalar(ScalarType::Bytes)));
+    }
+    Ok(match shape.def {
+        Def::Option(od) => {
+            let inner = unwrap(od.t());
+            if is_oneof(inner) {
+                FieldType::Oneof(inner)
+            } else {
+                FieldType::Optional(value_type(inner, over)?)
+            }
+        }
+        Def::List(ld) => FieldType::Repeated(value_type(ld.t(), over)?),
+        Def::Slice(sd) => FieldType::Repeated(value_type(sd.t(), over)?),
+        Def::Map(md) => FieldType::Map(map_key_type(md.k)?, value_type(md.v, over)?),
+        Def::Enum(_) if is_oneof(shape) => FieldType::Oneof(shape),
+        _ => FieldType::Singular(value_type(shape, over)?),
+    })
+}
+
+/// The value type of a field, or of the items of a repeated field
+pub(crate) fn value_type(shape: &'static Shape, over: Option<&str>) -> Result<ValueType, String> {
+    let shape = unwrap(shape);
+    if is_bytes(shape) {
+        return Ok(ValueType::Scalar(ScalarType::Bytes));
+    }
+    match shape.def {
+        Def::Scalar(sd) => Ok(ValueType::Scalar(scalar_type(shape, sd, over)?)),
+        Def::Struct(_) => Ok(ValueType::Message(shape)),
+        Def::Enum(_) if !is_oneof(shape) => Ok(ValueType::Enum(shape)),
+        _ => Err(format!("{} can't be a protobuf value", shape)),
+    }
+}
+
+/// Map keys can be integers, booleans or strings
+fn map_key_type(shape: &'static Shape) -> Result<ValueType, String> {
+    match value_type(shape, None)? {
+        ValueType::Scalar(scalar) if !scalar.is_float() && scalar != ScalarType::Bytes => {
+            Ok(ValueType::Scalar(scalar))
+        }
+        _ => Err(format!("{} can't be a protobuf map key", shape)),
+    }
+}
+
+/// The scalar type of numbers follows their `NumberAffinity`: unsigned
+/// integers are `uint32` or `uint64`, signed ones `sint32` or `sint64`
+/// (zigzag-encoded), and floats `float` or `double`. Anything else that
+/// isn't a boolean is text.
+fn scalar_type(
+    shape: &'static Shape,
+    sd: ScalarDef,
+    over: Option<&str>,
+) -> Result<ScalarType, String> {
+    let default = match sd.affinity {
+        ScalarAffinity::Number(na) => match na.bits {
+            NumberBits::Integer { bits, sign } if bits <= 64 => match (bits <= 32, sign) {
+                (true, Signedness::Unsigned) => ScalarType::Uint32,
+                (true, Signedness::Signed) => ScalarType::Sint32,
+                (false, Signedness::Unsigned) => ScalarType::Uint64,
+                (false, Signedness::Signed) => ScalarType::Sint64,
+            },
+            NumberBits::Float {
+                sign_bits,
+                exponent_bits,
+                mantissa_bits,
+                ..
+            } if sign_bits + exponent_bits + mantissa_bits <= 32 => ScalarType::Float,
+            NumberBits::Float { .. } => ScalarType::Double,
+            _ => return Err(format!("{} has no protobuf number type", shape)),
+        },
+        ScalarAffinity::Boolean(_) => ScalarType::Bool,
+        ScalarAffinity::Empty(_) | ScalarAffinity::Opaque(_) => {
+            return Err(format!("{} can't be a protobuf value", shape));
+        }
+        _ => ScalarType::String,
+    };
+
+    let Some(name) = over else {
+        return Ok(default);
+    };
+    let over = ScalarType::from_name(name)
+        .ok_or_else(|| format!("unknown protobuf scalar type: {}", name))?;
+    let compatible = over == default
+        || (over.is_integer() && default.is_integer())
+        || (over.is_float() && default.is_float());
+    if !compatible {
+        return Err(format!("{} can't be encoded as {}", shape, name));
+    }
+    Ok(over)
+}
@@ -0,0 +1,158 @@
+//! The protobuf wire format: records made of a key, which is a varint of the
+//! field number and wire type, and a value whose size the wire type tells.
+//!
+//! Ref: <https://protobuf.dev/programming-guides/encoding/>
+
+use crate::errors::Error as DecodeError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WireType {
+    Varint = 0,
+    I64 = 1,
+    Len = 2,
+    I32 = 5,
+}
+
+pub(crate) fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+pub(crate) fn write_key(out: &mut Vec<u8>, number: u32, wire_type: WireType) {
+    write_varint(out, ((number as u64) << 3) | wire_type as u64);
+}
+
+/// Writes a length-delimited record
+pub(crate) fn write_len(out: &mut Vec<u8>, number: u32, bytes: &[u8]) {
+    write_key(out, number, WireType::Len);
+    write_varint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+pub(crate) fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub(crate) fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// The value of a record, as the wire type lays it out
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum WireValue<'input> {
+    Varint(u64),
+    I64([u8; 8]),
+    Len(&'input [u8]),
+    I32([u8; 4]),
+}
+
+impl WireValue<'_> {
+    pub(crate) fn wire_type(&self) -> WireType {
+        match self {
+            WireValue::Varint(_) => WireType::Varint,
+            WireValue::I64(_) => WireType::I64,
+            WireValue::Len(_) => WireType::Len,
+            WireValue::I32(_) => WireType::I32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Record<'input> {
+    pub(crate) number: u32,
+    pub(crate) value: WireValue<'input>,
+    /// The whole record, key included, to keep unknown fields as they were
+    pub(crate) raw: &'input [u8],
+}
+
+pub(crate) struct Reader<'input> {
+    input: &'input [u8],
+    offset: usize,
+}
+
+impl<'input> Reader<'input> {
+    pub(crate) fn new(input: &'input [u8]) -> Self {
+        Reader { input, offset: 0 }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.offset == self.input.len()
+    }
+
+    /// Consumes `len` bytes of input.
+    fn take(&mut self, len: usize) -> Result<&'input [u8], DecodeError> {
+        if len > self.input.len() - self.offset {
+            return Err(DecodeError::InsufficientData);
+        }
+        let bytes = &self.input[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    pub(crate) fn take_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    /// Decodes a varint of up to ten bytes.
+    pub(crate) fn read_varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value = 0u64;
+        for i in 0..10 {
+            let byte = self.take_array::<1>()?[0];
+            // The tenth byte only has one of its seven bits left
+            if i == 9 && byte & 0x7f > 1 {
+                return Err(DecodeError::VarintOverflow);
+            }
+            value |= ((byte & 0x7f) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(DecodeError::VarintOverflow)
+    }
+
+    pub(crate) fn read_record(&mut self) -> Result<Record<'input>, DecodeError> {
+        let start = self.offset;
+        let key = self.read_varint()?;
+        let number = u32::try_from(key >> 3)
+            .ok()
+            .filter(|&number| number != 0)
+            .ok_or(DecodeError::InvalidData)?;
+
+        let value = match key & 0x7 {
+            0 => WireValue::Varint(self.read_varint()?),
+            1 => WireValue::I64(self.take_array()?),
+            2 => {
+                let len = self.read_varint()?;
+                let len = usize::try_from(len).map_err(|_| DecodeError::InsufficientData)?;
+                WireValue::Len(self.take(len)?)
+            }
+            5 => WireValue::I32(self.take_array()?),
+            // Groups (3 and 4) are deprecated, and the others don't exist
+            wire_type => {
+                return Err(DecodeError::InvalidWireType {
+                    field: number,
+                    wire_type: wire_type as u8,
+                });
+            }
+        };
+
+        Ok(Record {
+            number,
+            value,
+            raw: &self.input[start..self.offset],
+        })
+    }
+}
+
+/// Splits a message into its records.
+pub(crate) fn records(input: &[u8]) -> Result<Vec<Record<'_>>, DecodeError> {
+    let mut reader = Reader::new(input);
+    let mut records = Vec::new();
+    while !reader.is_empty() {
+        records.push(reader.read_record()?);
+    }
+    Ok(records)
+}
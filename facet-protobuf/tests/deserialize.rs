@@ -0,0 +1,299 @@
+use eyre::Result;
+use facet::Facet;
+use facet_protobuf::{DecodeError, from_slice, to_vec};
+use std::collections::HashMap;
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct SearchRequest {
+        #[facet(proto = 1)]
+        query: String,
+        #[facet(proto = 3)]
+        page: u32,
+    }
+
+    let request: SearchRequest = from_slice(&[0x0a, 0x02, b'h', b'i', 0x18, 0x96, 0x01])?;
+    assert_eq!(
+        request,
+        SearchRequest {
+            query: "hi".to_string(),
+            page: 150,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_fields_are_defaults() -> Result<()> {
+    facet_testhelpers::setup();
+
+    fn seven() -> u32 {
+        7
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Defaults {
+        count: u32,
+        name: String,
+        maybe: Option<u32>,
+        list: Vec<u32>,
+        #[facet(default = "seven")]
+        seven: u32,
+    }
+
+    let defaults: Defaults = from_slice(&[])?;
+    assert_eq!(
+        defaults,
+        Defaults {
+            count: 0,
+            name: String::new(),
+            maybe: None,
+            list: vec![],
+            seven: 7,
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn last_record_wins() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Single {
+        value: u32,
+    }
+
+    let single: Single = from_slice(&[0x08, 0x01, 0x08, 0x02])?;
+    assert_eq!(single, Single { value: 2 });
+    Ok(())
+}
+
+#[test]
+fn packed_and_unpacked_repeated() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Lists {
+        numbers: Vec<i32>,
+    }
+
+    // Packed, then one more unpacked, as parsers must accept both
+    let lists: Lists = from_slice(&[0x0a, 0x02, 0x02, 0x03, 0x08, 0x04])?;
+    assert_eq!(
+        lists,
+        Lists {
+            numbers: vec![1, -2, 2]
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Status {
+        Unknown = 0,
+        Active = 1,
+        Closed = 5,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    #[repr(u8)]
+    enum Figure {
+        Point,
+        Circle(f64),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Child {
+        name: String,
+    }
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Everything {
+        #[facet(proto = 1)]
+        id: u64,
+        #[facet(proto = 2)]
+        delta: i64,
+        #[facet(proto = 3, proto_type = "fixed32")]
+        hash: u32,
+        #[facet(proto = 4)]
+        ratio: f32,
+        #[facet(proto = 5)]
+        tags: Vec<String>,
+        #[facet(proto = 6)]
+        scores: Vec<f64>,
+        #[facet(proto = 7)]
+        counts: HashMap<String, u32>,
+        #[facet(proto = 8)]
+        children: Vec<Child>,
+        #[facet(proto = 9)]
+        status: Status,
+        #[facet(proto = 10)]
+        statuses: Vec<Status>,
+        #[facet(proto = 11)]
+        figure: Figure,
+        #[facet(proto = 14)]
+        other: Option<Figure>,
+        #[facet(proto = 17)]
+        data: Vec<u8>,
+        #[facet(proto = 18)]
+        note: Option<String>,
+    }
+
+    let mut counts = HashMap::new();
+    counts.insert("a".to_string(), 1);
+    counts.insert("b".to_string(), 0);
+    let everything = Everything {
+        id: u64::MAX,
+        delta: i64::MIN,
+        hash: 0xdeadbeef,
+        ratio: 0.25,
+        tags: vec!["x".to_string(), String::new()],
+        scores: vec![1.5, -0.5],
+        counts,
+        children: vec![
+            Child {
+                name: "c".to_string(),
+            },
+            Child {
+                name: String::new(),
+            },
+        ],
+        status: Status::Unknown,
+        statuses: vec![Status::Closed, Status::Active],
+        figure: Figure::Rect {
+            width: 2,
+            height: 0,
+        },
+        other: Some(Figure::Point),
+        data: vec![0, 1, 2],
+        note: Some(String::new()),
+    };
+
+    let back: Everything = from_slice(&to_vec(&everything))?;
+    assert_eq!(back, everything);
+
+    for figure in [Figure::Point, Figure::Circle(0.5)] {
+        let back: Figure = from_slice(&to_vec(&figure))?;
+        assert_eq!(back, figure);
+    }
+    Ok(())
+}
+
+#[test]
+fn unknown_fields_are_skipped() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Old {
+        #[facet(proto = 1)]
+        value: u32,
+    }
+
+    // Field 2 as a varint, field 3 as a string
+    let input = [0x08, 0x01, 0x10, 0x05, 0x1a, 0x01, b'x'];
+    let old: Old = from_slice(&input)?;
+    assert_eq!(old, Old { value: 1 });
+    Ok(())
+}
+
+#[test]
+fn unknown_fields_are_preserved() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet, PartialEq)]
+    struct Old {
+        #[facet(proto = 1)]
+        value: u32,
+        #[facet(proto_unknown)]
+        unknown: Vec<u8>,
+    }
+
+    let input = [0x10, 0x05, 0x08, 0x01, 0x1a, 0x01, b'x'];
+    let old: Old = from_slice(&input)?;
+    assert_eq!(
+        old,
+        Old {
+            value: 1,
+            unknown: vec![0x10, 0x05, 0x1a, 0x01, b'x'],
+        }
+    );
+
+    // They're written back after the known fields
+    assert_eq!(to_vec(&old), [0x08, 0x01, 0x10, 0x05, 0x1a, 0x01, b'x']);
+    Ok(())
+}
+
+#[test]
+fn deny_unknown_fields() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        #[facet(proto = 1)]
+        value: u32,
+    }
+
+    let result = from_slice::<Strict>(&[0x08, 0x01, 0x10, 0x05]);
+    assert!(matches!(result, Err(DecodeError::UnknownField(2))));
+}
+
+#[test]
+fn errors() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Small {
+        value: u8,
+    }
+
+    #[derive(Debug, Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Status {
+        Active = 1,
+    }
+
+    #[derive(Debug, Facet)]
+    struct WithStatus {
+        status: Status,
+    }
+
+    assert!(matches!(
+        from_slice::<Small>(&[0x08, 0xac, 0x02]),
+        Err(DecodeError::IntegerOverflow)
+    ));
+    assert!(matches!(
+        from_slice::<Small>(&[0x0d, 0x01, 0x00, 0x00, 0x00]),
+        Err(DecodeError::InvalidWireType {
+            field: 1,
+            wire_type: 5
+        })
+    ));
+    assert!(matches!(
+        from_slice::<Small>(&[0x08]),
+        Err(DecodeError::InsufficientData)
+    ));
+    assert!(matches!(
+        from_slice::<Small>(&[0x00, 0x01]),
+        Err(DecodeError::InvalidData)
+    ));
+    assert!(matches!(
+        from_slice::<WithStatus>(&[0x08, 0x02]),
+        Err(DecodeError::UnknownEnumValue(2))
+    ));
+    assert!(matches!(
+        from_slice::<WithStatus>(&[]),
+        Err(DecodeError::MissingField(_))
+    ));
+}
@@ -0,0 +1,417 @@
+use facet::Facet;
+use facet_protobuf::{DecodeError, from_slice, to_proto_file, to_vec, to_writer};
+use std::collections::BTreeMap;
+
+#[test]
+fn it_works() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct SearchRequest {
+        #[facet(proto = 1)]
+        query: String,
+        #[facet(proto = 3)]
+        page: u32,
+    }
+
+    let request = SearchRequest {
+        query: "hi".to_string(),
+        page: 150,
+    };
+    assert_eq!(
+        to_vec(&request),
+        [
+            0x0a, // Field 1, length-delimited
+            0x02, b'h', b'i', // "hi"
+            0x18, // Field 3, varint
+            0x96, 0x01, // 150
+        ]
+    );
+
+    let mut out = Vec::new();
+    to_writer(&request, &mut out).unwrap();
+    assert_eq!(out, to_vec(&request));
+}
+
+#[test]
+fn numbers_follow_their_affinity() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Numbers {
+        unsigned: u64,
+        signed: i32,
+        float: f32,
+        double: f64,
+        #[facet(proto_type = "int32")]
+        plain: i32,
+        #[facet(proto_type = "sfixed32")]
+        fixed: i32,
+    }
+
+    let numbers = Numbers {
+        unsigned: 300,
+        signed: -2,
+        float: 1.0,
+        double: -2.0,
+        plain: -1,
+        fixed: -1,
+    };
+    assert_eq!(
+        to_vec(&numbers),
+        [
+            0x08, 0xac, 0x02, // 300
+            0x10, 0x03, // -2, zigzagged
+            0x1d, 0x00, 0x00, 0x80, 0x3f, // 1.0f32
+            0x21, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc0, // -2.0f64
+            // -1, sign-extended to ten bytes
+            0x28, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, //
+            0x35, 0xff, 0xff, 0xff, 0xff, // -1 as sfixed32
+        ]
+    );
+}
+
+#[test]
+fn defaults_are_left_out() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Defaults {
+        count: u32,
+        name: String,
+        flag: bool,
+        maybe: Option<u32>,
+    }
+
+    let defaults = Defaults {
+        count: 0,
+        name: String::new(),
+        flag: false,
+        maybe: None,
+    };
+    assert!(to_vec(&defaults).is_empty());
+
+    // Optional fields are written when they're set, even to their default
+    let set = Defaults {
+        maybe: Some(0),
+        ..defaults
+    };
+    assert_eq!(to_vec(&set), [0x20, 0x00]);
+}
+
+#[test]
+fn repeated_numbers_are_packed() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Lists {
+        numbers: Vec<u32>,
+        names: Vec<String>,
+    }
+
+    let lists = Lists {
+        numbers: vec![1, 150],
+        names: vec!["a".to_string(), "b".to_string()],
+    };
+    assert_eq!(
+        to_vec(&lists),
+        [
+            0x0a, 0x03, 0x01, 0x96, 0x01, // Packed 1 and 150
+            0x12, 0x01, b'a', // One record per string
+            0x12, 0x01, b'b',
+        ]
+    );
+}
+
+#[test]
+fn maps_are_entry_messages() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Scores {
+        scores: BTreeMap<String, u32>,
+    }
+
+    let mut scores = BTreeMap::new();
+    scores.insert("a".to_string(), 1);
+    scores.insert("b".to_string(), 2);
+    assert_eq!(
+        to_vec(&Scores { scores }),
+        [
+            0x0a, 0x05, 0x0a, 0x01, b'a', 0x10, 0x01, // a: 1
+            0x0a, 0x05, 0x0a, 0x01, b'b', 0x10, 0x02, // b: 2
+        ]
+    );
+}
+
+#[test]
+fn nested_messages() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Inner {
+        value: u32,
+    }
+
+    #[derive(Facet)]
+    struct Outer {
+        inner: Inner,
+    }
+
+    assert_eq!(
+        to_vec(&Outer {
+            inner: Inner { value: 1 }
+        }),
+        [0x0a, 0x02, 0x08, 0x01]
+    );
+    // Messages are written even when empty
+    assert_eq!(
+        to_vec(&Outer {
+            inner: Inner { value: 0 }
+        }),
+        [0x0a, 0x00]
+    );
+}
+
+#[test]
+fn enums() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Status {
+        Unknown = 0,
+        Active = 1,
+        Closed = 5,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Figure {
+        Point,
+        Circle(f32),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[derive(Facet)]
+    struct Item {
+        #[facet(proto = 1)]
+        status: Status,
+        #[facet(proto = 2)]
+        shape: Figure,
+    }
+
+    assert_eq!(
+        to_vec(&Item {
+            status: Status::Closed,
+            shape: Figure::Point,
+        }),
+        [0x08, 0x05, 0x12, 0x00]
+    );
+    assert_eq!(
+        to_vec(&Item {
+            status: Status::Unknown,
+            shape: Figure::Circle(1.0),
+        }),
+        // Field 2 + 1
+        [0x1d, 0x00, 0x00, 0x80, 0x3f]
+    );
+    assert_eq!(
+        to_vec(&Item {
+            status: Status::Active,
+            shape: Figure::Rect {
+                width: 2,
+                height: 3
+            },
+        }),
+        [0x08, 0x01, 0x22, 0x04, 0x08, 0x02, 0x10, 0x03]
+    );
+
+    // An enum with data at the root is a message with just the oneof
+    assert_eq!(to_vec(&Figure::Circle(1.0)), [0x15, 0x00, 0x00, 0x80, 0x3f]);
+}
+
+#[test]
+fn out_of_range_field_number() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct TooBig {
+        #[facet(proto = 536870912)]
+        value: u32,
+    }
+
+    assert!(to_writer(&TooBig { value: 1 }, &mut Vec::new()).is_err());
+}
+
+#[test]
+fn oneofs_move_implicit_numbers_up() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Figure {
+        Point,
+        Circle(f32),
+        Rect { width: u32, height: u32 },
+    }
+
+    #[derive(Facet, Debug, PartialEq)]
+    struct Drawing {
+        kind: Figure,
+        name: String,
+    }
+
+    // `kind` takes 1 to 3, so `name` is 4
+    let drawing = Drawing {
+        kind: Figure::Circle(1.0),
+        name: "x".to_string(),
+    };
+    let bytes = to_vec(&drawing);
+    assert_eq!(bytes, [0x15, 0x00, 0x00, 0x80, 0x3f, 0x22, 0x01, b'x']);
+    assert_eq!(from_slice::<Drawing>(&bytes).unwrap(), drawing);
+    assert_eq!(
+        to_proto_file::<Drawing>(None).unwrap(),
+        r#"syntax = "proto3";
+
+message Drawing {
+  oneof kind {
+    FigurePoint point = 1;
+    float circle = 2;
+    FigureRect rect = 3;
+  }
+  string name = 4;
+}
+
+message FigurePoint {
+}
+
+message FigureRect {
+  uint32 width = 1;
+  uint32 height = 2;
+}
+"#
+    );
+}
+
+#[test]
+fn conflicting_field_numbers() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Figure {
+        Point,
+        Circle(f32),
+    }
+
+    #[derive(Facet, Debug)]
+    struct Overlapping {
+        #[facet(proto = 2)]
+        kind: Figure,
+        #[facet(proto = 3)]
+        size: u32,
+    }
+
+    let overlapping = Overlapping {
+        kind: Figure::Point,
+        size: 1,
+    };
+    assert!(to_writer(&overlapping, &mut Vec::new()).is_err());
+    assert!(matches!(
+        from_slice::<Overlapping>(&[]),
+        Err(DecodeError::UnsupportedShape(_))
+    ));
+    assert!(to_proto_file::<Overlapping>(None).is_err());
+
+    #[derive(Facet, Debug)]
+    struct Reserved {
+        #[facet(proto = 19500)]
+        value: u32,
+    }
+
+    assert!(to_writer(&Reserved { value: 1 }, &mut Vec::new()).is_err());
+    assert!(to_proto_file::<Reserved>(None).is_err());
+}
+
+#[test]
+fn proto_file() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Status {
+        Active = 1,
+        Unknown = 0,
+    }
+
+    #[derive(Facet)]
+    #[repr(u8)]
+    #[allow(dead_code)]
+    enum Payload {
+        Empty,
+        Text(String),
+        Pair { left: u32, right: u32 },
+    }
+
+    /// A node in a tree
+    #[derive(Facet)]
+    struct Node {
+        /// What the node is called
+        #[facet(proto = 1)]
+        name: String,
+        #[facet(proto = 2)]
+        status: Status,
+        #[facet(proto = 3)]
+        children: Vec<Node>,
+        #[facet(proto = 4)]
+        weights: BTreeMap<String, f64>,
+        #[facet(proto = 5)]
+        parent_id: Option<u64>,
+        #[facet(proto = 6)]
+        payload: Payload,
+        #[facet(proto_unknown)]
+        unknown: Vec<u8>,
+    }
+
+    assert_eq!(
+        to_proto_file::<Node>(Some("tree")).unwrap(),
+        r#"syntax = "proto3";
+
+package tree;
+
+// A node in a tree
+message Node {
+  // What the node is called
+  string name = 1;
+  Status status = 2;
+  repeated Node children = 3;
+  map<string, double> weights = 4;
+  optional uint64 parent_id = 5;
+  oneof payload {
+    PayloadEmpty empty = 6;
+    string text = 7;
+    PayloadPair pair = 8;
+  }
+}
+
+enum Status {
+  STATUS_UNKNOWN = 0;
+  STATUS_ACTIVE = 1;
+}
+
+message PayloadEmpty {
+}
+
+message PayloadPair {
+  uint32 left = 1;
+  uint32 right = 2;
+}
+"#
+    );
+}
@@ -1,4 +1,6 @@
-use facet_core::{Characteristic, EnumDef, Field, FieldError, Shape, TryFromError};
+use facet_core::{Characteristic, EnumDef, Field, FieldError, ParseError, Shape, TryFromError};
+
+use crate::LimitKind;
 use owo_colors::OwoColorize;
 
 /// Errors that can occur when reflecting on types.
@@ -110,6 +112,15 @@ pub enum ReflectError {
         inner: TryFromError,
     },
 
+    /// The shape's `parse` function rejected the input
+    ParseFailed {
+        /// The shape of the value being parsed.
+        shape: &'static Shape,
+
+        /// The inner error
+        inner: ParseError,
+    },
+
     /// A shape has a `default` attribute, but no implementation of the `Default` trait.
     DefaultAttrButNoDefaultImpl {
         /// The shape of the value that has a `default` attribute but no default implementation.
@@ -121,6 +132,14 @@ pub enum ReflectError {
         /// The shape for the type that is unsized
         shape: &'static Shape,
     },
+
+    /// One of the [`Limits`](crate::Limits) set on a `Wip` was exceeded
+    LimitExceeded {
+        /// Which limit was exceeded
+        kind: LimitKind,
+        /// The configured maximum
+        max: usize,
+    },
 }
 
 impl core::fmt::Display for ReflectError {
@@ -206,12 +225,23 @@ impl core::fmt::Display for ReflectError {
                     inner.red()
                 )
             }
+            ReflectError::ParseFailed { shape, inner } => {
+                write!(
+                    f,
+                    "While trying to parse a {}: {}",
+                    shape.blue(),
+                    inner.red()
+                )
+            }
             ReflectError::DefaultAttrButNoDefaultImpl { shape } => write!(
                 f,
                 "Shape '{}' has a `default` attribute but no default implementation",
                 shape
             ),
             ReflectError::Unsized { shape } => write!(f, "Shape '{}' is unsized", shape),
+            ReflectError::LimitExceeded { kind, max } => {
+                write!(f, "Exceeded the {} limit of {}", kind, max.red())
+            }
         }
     }
 }
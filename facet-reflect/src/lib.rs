@@ -10,6 +10,9 @@ extern crate alloc;
 mod error;
 pub use error::*;
 
+mod limits;
+pub use limits::*;
+
 #[cfg(feature = "alloc")]
 mod wip;
 #[cfg(feature = "alloc")]
@@ -0,0 +1,115 @@
+/// Bounds on how much a [`Wip`](crate::Wip) is allowed to build.
+///
+/// Deserializers accept a `Limits` so that hostile input (deeply nested
+/// documents, huge length prefixes, giant strings) is rejected with
+/// [`ReflectError::LimitExceeded`](crate::ReflectError::LimitExceeded) instead
+/// of exhausting the stack or memory.
+///
+/// The default is unlimited; set the bounds that make sense for your input:
+///
+/// ```
+/// use facet_reflect::Limits;
+///
+/// let limits = Limits::default()
+///     .max_depth(64)
+///     .max_collection_len(10_000)
+///     .max_string_len(1 << 16)
+///     .max_alloc_bytes(16 << 20);
+/// assert_eq!(limits.max_depth, 64);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Limits {
+    /// How many values may be nested inside each other, counting the root.
+    pub max_depth: usize,
+
+    /// How many items a single list, or entries a single map, may hold.
+    pub max_collection_len: usize,
+
+    /// How many bytes a single string may hold.
+    pub max_string_len: usize,
+
+    /// How many bytes may be allocated in total to build the value,
+    /// including string contents.
+    pub max_alloc_bytes: usize,
+}
+
+impl Limits {
+    /// No limits at all.
+    pub const UNLIMITED: Self = Self {
+        max_depth: usize::MAX,
+        max_collection_len: usize::MAX,
+        max_string_len: usize::MAX,
+        max_alloc_bytes: usize::MAX,
+    };
+
+    /// Sets the maximum nesting depth
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the maximum number of items in a list or entries in a map
+    pub const fn max_collection_len(mut self, max_collection_len: usize) -> Self {
+        self.max_collection_len = max_collection_len;
+        self
+    }
+
+    /// Sets the maximum length of a string, in bytes
+    pub const fn max_string_len(mut self, max_string_len: usize) -> Self {
+        self.max_string_len = max_string_len;
+        self
+    }
+
+    /// Sets the maximum number of bytes allocated overall
+    pub const fn max_alloc_bytes(mut self, max_alloc_bytes: usize) -> Self {
+        self.max_alloc_bytes = max_alloc_bytes;
+        self
+    }
+
+    /// Returns an error if `value` goes over the bound for `kind`.
+    pub fn check(&self, kind: LimitKind, value: usize) -> Result<(), crate::ReflectError> {
+        let max = match kind {
+            LimitKind::Depth => self.max_depth,
+            LimitKind::CollectionLength => self.max_collection_len,
+            LimitKind::StringLength => self.max_string_len,
+            LimitKind::Allocation => self.max_alloc_bytes,
+        };
+        if value > max {
+            Err(crate::ReflectError::LimitExceeded { kind, max })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::UNLIMITED
+    }
+}
+
+/// Which of the [`Limits`] was exceeded
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LimitKind {
+    /// [`Limits::max_depth`]
+    Depth,
+    /// [`Limits::max_collection_len`]
+    CollectionLength,
+    /// [`Limits::max_string_len`]
+    StringLength,
+    /// [`Limits::max_alloc_bytes`]
+    Allocation,
+}
+
+impl core::fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LimitKind::Depth => write!(f, "nesting depth"),
+            LimitKind::CollectionLength => write!(f, "collection length"),
+            LimitKind::StringLength => write!(f, "string length"),
+            LimitKind::Allocation => write!(f, "total allocation"),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use super::Peek;
+use facet_core::ArrayDef;
+
+/// Iterator over a `PeekArray`
+pub struct PeekArrayIter<'mem, 'facet_lifetime> {
+    array: PeekArray<'mem, 'facet_lifetime>,
+    index: usize,
+}
+
+impl<'mem, 'facet_lifetime> Iterator for PeekArrayIter<'mem, 'facet_lifetime> {
+    type Item = Peek<'mem, 'facet_lifetime>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.array.get(self.index)?;
+        self.index += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.array.len().saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PeekArrayIter<'_, '_> {}
+
+impl<'mem, 'facet_lifetime> IntoIterator for &'mem PeekArray<'mem, 'facet_lifetime> {
+    type Item = Peek<'mem, 'facet_lifetime>;
+    type IntoIter = PeekArrayIter<'mem, 'facet_lifetime>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Lets you read from a fixed-size array (implements read-only [`facet_core::ArrayVTable`] proxies)
+#[derive(Clone, Copy)]
+pub struct PeekArray<'mem, 'facet_lifetime> {
+    pub(crate) value: Peek<'mem, 'facet_lifetime>,
+    pub(crate) def: ArrayDef,
+}
+
+impl<'mem, 'facet_lifetime> PeekArray<'mem, 'facet_lifetime> {
+    /// Creates a new peek array
+    pub fn new(value: Peek<'mem, 'facet_lifetime>, def: ArrayDef) -> Self {
+        Self { value, def }
+    }
+
+    /// Get the length of the array
+    pub fn len(&self) -> usize {
+        self.def.n
+    }
+
+    /// Returns true if the array is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get an item from the array at the specified index, or `None` if it
+    /// is out of bounds
+    pub fn get(&self, index: usize) -> Option<Peek<'mem, 'facet_lifetime>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let item_ptr = unsafe { (self.def.vtable.get_item_ptr)(self.value.data(), index) };
+        Some(unsafe { Peek::unchecked_new(item_ptr, self.def.t()) })
+    }
+
+    /// Returns an iterator over the array
+    pub fn iter(self) -> PeekArrayIter<'mem, 'facet_lifetime> {
+        PeekArrayIter {
+            array: self,
+            index: 0,
+        }
+    }
+
+    /// Def getter
+    pub fn def(&self) -> ArrayDef {
+        self.def
+    }
+}
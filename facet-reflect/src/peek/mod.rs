@@ -12,6 +12,12 @@ pub use enum_::*;
 mod list;
 pub use list::*;
 
+mod array;
+pub use array::*;
+
+mod slice;
+pub use slice::*;
+
 mod map;
 pub use map::*;
 
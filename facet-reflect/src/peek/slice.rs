@@ -0,0 +1,82 @@
+use super::Peek;
+use facet_core::SliceDef;
+
+/// Iterator over a `PeekSlice`
+pub struct PeekSliceIter<'mem, 'facet_lifetime> {
+    slice: PeekSlice<'mem, 'facet_lifetime>,
+    index: usize,
+}
+
+impl<'mem, 'facet_lifetime> Iterator for PeekSliceIter<'mem, 'facet_lifetime> {
+    type Item = Peek<'mem, 'facet_lifetime>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.slice.get(self.index)?;
+        self.index += 1;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.slice.len().saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for PeekSliceIter<'_, '_> {}
+
+impl<'mem, 'facet_lifetime> IntoIterator for &'mem PeekSlice<'mem, 'facet_lifetime> {
+    type Item = Peek<'mem, 'facet_lifetime>;
+    type IntoIter = PeekSliceIter<'mem, 'facet_lifetime>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Lets you read from a slice (implements read-only [`facet_core::SliceVTable`] proxies)
+#[derive(Clone, Copy)]
+pub struct PeekSlice<'mem, 'facet_lifetime> {
+    pub(crate) value: Peek<'mem, 'facet_lifetime>,
+    pub(crate) def: SliceDef,
+}
+
+impl<'mem, 'facet_lifetime> PeekSlice<'mem, 'facet_lifetime> {
+    /// Creates a new peek slice
+    pub fn new(value: Peek<'mem, 'facet_lifetime>, def: SliceDef) -> Self {
+        Self { value, def }
+    }
+
+    /// Get the length of the slice
+    pub fn len(&self) -> usize {
+        unsafe { (self.def.vtable.len)(self.value.data()) }
+    }
+
+    /// Returns true if the slice is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get an item from the slice at the specified index, or `None` if it
+    /// is out of bounds
+    pub fn get(&self, index: usize) -> Option<Peek<'mem, 'facet_lifetime>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let item_ptr = unsafe { (self.def.vtable.get_item_ptr)(self.value.data(), index) };
+        Some(unsafe { Peek::unchecked_new(item_ptr, self.def.t()) })
+    }
+
+    /// Returns an iterator over the slice
+    pub fn iter(self) -> PeekSliceIter<'mem, 'facet_lifetime> {
+        PeekSliceIter {
+            slice: self,
+            index: 0,
+        }
+    }
+
+    /// Def getter
+    pub fn def(&self) -> SliceDef {
+        self.def
+    }
+}
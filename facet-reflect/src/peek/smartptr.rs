@@ -7,17 +7,28 @@ use super::Peek;
 /// This struct holds the value being pointed to and the definition of the smart pointer type.
 pub struct PeekSmartPointer<'mem, 'facet_lifetime> {
     /// The value being pointed to by this smart pointer.
-    #[expect(dead_code)]
     pub(crate) value: Peek<'mem, 'facet_lifetime>,
 
     /// The definition of this smart pointer type.
     pub(crate) def: SmartPointerDef,
 }
 
-impl PeekSmartPointer<'_, '_> {
+impl<'mem, 'facet_lifetime> PeekSmartPointer<'mem, 'facet_lifetime> {
     /// Returns a reference to the smart pointer definition.
     #[must_use]
     pub fn def(&self) -> &SmartPointerDef {
         &self.def
     }
+
+    /// Borrows the pointee, if the smart pointer knows how to hand out a
+    /// reference to it and its shape is not opaque.
+    ///
+    /// Returns `None` for pointers that can't be borrowed through directly,
+    /// such as locks.
+    pub fn borrow_inner(&self) -> Option<Peek<'mem, 'facet_lifetime>> {
+        let borrow_fn = self.def.vtable.borrow_fn?;
+        let pointee = self.def.pointee?;
+        let inner = unsafe { borrow_fn(self.value.data()) };
+        Some(unsafe { Peek::unchecked_new(inner, pointee) })
+    }
 }
@@ -3,7 +3,7 @@ use facet_core::{Def, Facet, PtrConst, PtrMut, Shape, TypeNameOpts, ValueVTable}
 
 use crate::{ReflectError, ScalarType};
 
-use super::{PeekEnum, PeekList, PeekMap, PeekSmartPointer, PeekStruct};
+use super::{PeekArray, PeekEnum, PeekList, PeekMap, PeekSlice, PeekSmartPointer, PeekStruct};
 
 /// A unique identifier for a peek value
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -186,6 +186,20 @@ impl<'mem, 'facet_lifetime> Peek<'mem, 'facet_lifetime> {
         }
     }
 
+    /// Borrows the value wrapped by a transparent type (such as a
+    /// `#[facet(transparent)]` newtype), or returns `None` if this is not a
+    /// wrapper or it can't lend out its inner value.
+    ///
+    /// This relies on the shape's `inner` and its vtable's
+    /// `try_borrow_inner` agreeing: whatever `try_borrow_inner` lends out
+    /// must be a value of the shape `inner` returns, as it's read as one.
+    pub fn borrow_inner(&self) -> Option<Peek<'mem, 'facet_lifetime>> {
+        let inner_shape = (self.shape.inner?)();
+        let borrow_fn = self.vtable().try_borrow_inner?;
+        let inner = unsafe { borrow_fn(self.data) }.ok()?;
+        Some(unsafe { Peek::unchecked_new(inner, inner_shape) })
+    }
+
     /// Tries to identify this value as a struct
     pub fn into_struct(self) -> Result<PeekStruct<'mem, 'facet_lifetime>, ReflectError> {
         if let Def::Struct(def) = self.shape.def {
@@ -234,6 +248,30 @@ impl<'mem, 'facet_lifetime> Peek<'mem, 'facet_lifetime> {
         }
     }
 
+    /// Tries to identify this value as a fixed-size array
+    pub fn into_array(self) -> Result<PeekArray<'mem, 'facet_lifetime>, ReflectError> {
+        if let Def::Array(def) = self.shape.def {
+            Ok(PeekArray { value: self, def })
+        } else {
+            Err(ReflectError::WasNotA {
+                expected: "array",
+                actual: self.shape,
+            })
+        }
+    }
+
+    /// Tries to identify this value as a slice
+    pub fn into_slice(self) -> Result<PeekSlice<'mem, 'facet_lifetime>, ReflectError> {
+        if let Def::Slice(def) = self.shape.def {
+            Ok(PeekSlice { value: self, def })
+        } else {
+            Err(ReflectError::WasNotA {
+                expected: "slice",
+                actual: self.shape,
+            })
+        }
+    }
+
     /// Tries to identify this value as a smart pointer
     pub fn into_smart_pointer(
         self,
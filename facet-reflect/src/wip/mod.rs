@@ -1,4 +1,4 @@
-use crate::{ReflectError, ValueId};
+use crate::{LimitKind, Limits, ReflectError, ValueId};
 use crate::{debug, trace};
 #[cfg(feature = "log")]
 use alloc::string::ToString;
@@ -11,7 +11,7 @@ use bitflags::bitflags;
 use core::{fmt, marker::PhantomData};
 use facet_core::{
     Def, DefaultInPlaceFn, Facet, FieldError, PtrConst, PtrMut, PtrUninit, ScalarAffinity, Shape,
-    Variant,
+    TryFromError, Variant,
 };
 use flat_map::FlatMap;
 
@@ -167,6 +167,61 @@ impl Frame {
         self.dealloc_if_needed();
     }
 
+    /// Drops whatever part of the value is initialized, and resets the
+    /// initialization state so the frame can be written to afresh.
+    // Safety: the initialization state must match the frame's data
+    unsafe fn deinit(&mut self) {
+        if self.istate.variant.is_some() || self.istate.fields.is_any_set() {
+            debug!("De-initializing partially initialized {:?}", self.yellow());
+
+            match self.shape.def {
+                Def::Struct(sd) => {
+                    for (i, field) in sd.fields.iter().enumerate() {
+                        if self.istate.fields.has(i) {
+                            if let Some(drop_fn) = field.shape().vtable.drop_in_place {
+                                unsafe {
+                                    let field_ptr = self.data.as_mut_byte_ptr().add(field.offset);
+                                    drop_fn(PtrMut::new(field_ptr));
+                                }
+                            }
+                        }
+                    }
+                }
+                Def::Enum(_) => {
+                    if let Some(variant) = &self.istate.variant {
+                        for (i, field) in variant.data.fields.iter().enumerate() {
+                            if self.istate.fields.has(i) {
+                                if let Some(drop_fn) = field.shape().vtable.drop_in_place {
+                                    unsafe {
+                                        let field_ptr =
+                                            self.data.as_mut_byte_ptr().add(field.offset);
+                                        drop_fn(PtrMut::new(field_ptr));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    // For scalar types and other non-struct/enum, attempt to drop the field in place if initialized
+                    if self.istate.fields.is_any_set() {
+                        debug!("Scalar type was set...");
+                        if let Some(drop_fn) = self.shape.vtable.drop_in_place {
+                            debug!("And it has a drop fn, dropping now!");
+                            unsafe {
+                                drop_fn(self.data.assume_init());
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Reset initialization state
+            self.istate.variant = None;
+            ISet::clear(&mut self.istate.fields);
+        }
+    }
+
     /// Marks the frame as fully initialized
     unsafe fn mark_fully_initialized(&mut self) {
         match self.shape.def {
@@ -289,6 +344,12 @@ pub struct Wip<'facet_lifetime> {
     /// keeps track of initialization of out-of-tree frames
     istates: FlatMap<ValueId, IState>,
 
+    /// bounds on what we're allowed to build
+    limits: Limits,
+
+    /// bytes allocated so far, checked against `limits`
+    allocated: usize,
+
     invariant: PhantomData<fn(&'facet_lifetime ()) -> &'facet_lifetime ()>,
 }
 
@@ -319,10 +380,51 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
                 istate: IState::new(0, FrameMode::Root, FrameFlags::ALLOCATED),
             }],
             istates: Default::default(),
+            limits: Limits::UNLIMITED,
+            allocated: shape_size(shape),
             invariant: PhantomData,
         })
     }
 
+    /// Sets the [`Limits`] enforced while building this value.
+    ///
+    /// Pushing a frame past `max_depth`, a list item or map entry past
+    /// `max_collection_len`, putting or parsing a string longer than
+    /// `max_string_len`, or allocating more than `max_alloc_bytes` in total
+    /// fails with [`ReflectError::LimitExceeded`].
+    pub fn with_limits(mut self, limits: Limits) -> Result<Self, ReflectError> {
+        limits.check(LimitKind::Depth, self.frames.len())?;
+        limits.check(LimitKind::Allocation, self.allocated)?;
+        self.limits = limits;
+        Ok(self)
+    }
+
+    /// Returns the limits enforced while building this value
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// Accounts for `bytes` more being allocated
+    fn charge(&mut self, bytes: usize) -> Result<(), ReflectError> {
+        self.allocated = self.allocated.saturating_add(bytes);
+        self.limits.check(LimitKind::Allocation, self.allocated)
+    }
+
+    /// Pushes a frame onto the stack, enforcing the depth and allocation
+    /// limits. The frame's memory is released if it doesn't fit.
+    fn push_frame(&mut self, mut frame: Frame) -> Result<(), ReflectError> {
+        let mut res = self.limits.check(LimitKind::Depth, self.frames.len() + 1);
+        if res.is_ok() && frame.istate.flags.contains(FrameFlags::ALLOCATED) {
+            res = self.charge(shape_size(frame.shape));
+        }
+        if let Err(e) = res {
+            frame.dealloc_if_needed();
+            return Err(e);
+        }
+        self.frames.push(frame);
+        Ok(())
+    }
+
     /// Allocates a new value of type `S`
     pub fn alloc<S: Facet<'facet_lifetime>>() -> Result<Self, ReflectError> {
         Self::alloc_shape(S::SHAPE)
@@ -482,6 +584,13 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
         self.frames.last().unwrap().istate.mode
     }
 
+    /// Returns true if the current frame holds a fully initialized value
+    pub fn is_initialized(&self) -> bool {
+        self.frames
+            .last()
+            .is_some_and(|frame| frame.is_fully_initialized())
+    }
+
     /// Asserts everything is initialized and that invariants are upheld (if any)
     pub fn build(mut self) -> Result<HeapValue<'facet_lifetime>, ReflectError> {
         debug!("[{}] ⚒️ It's BUILD time", self.frames.len());
@@ -793,7 +902,7 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
                 shape.blue(),
             );
         }
-        self.frames.push(frame);
+        self.push_frame(frame)?;
         Ok(self)
     }
 
@@ -907,6 +1016,12 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
         src: PtrConst<'_>,
         src_shape: &'static Shape,
     ) -> Result<Wip<'facet_lifetime>, ReflectError> {
+        if src_shape.is_type::<String>() {
+            let len = unsafe { src.get::<String>() }.len();
+            self.limits.check(LimitKind::StringLength, len)?;
+            self.charge(len)?;
+        }
+
         let Some(frame) = self.frames.last_mut() else {
             return Err(ReflectError::OperationFailed {
                 shape: src_shape,
@@ -931,6 +1046,10 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
                 if src_shape == inner_shape {
                     // Look for a try_from_inner function in the vtable
                     if let Some(try_from_fn) = frame.shape.vtable.try_from {
+                        // Whatever we're replacing has to go first
+                        unsafe {
+                            frame.deinit();
+                        }
                         match unsafe { (try_from_fn)(src, src_shape, frame.data) } {
                             Ok(_) => {
                                 unsafe {
@@ -971,6 +1090,10 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
 
             // Maybe there's a `TryFrom` impl?
             if let Some(try_from) = frame.shape.vtable.try_from {
+                // Whatever we're replacing has to go first
+                unsafe {
+                    frame.deinit();
+                }
                 match unsafe { try_from(src, src_shape, frame.data) } {
                     Ok(_) => {
                         unsafe {
@@ -1130,54 +1253,8 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
         }
 
         // de-initialize partially initialized fields, if any
-        if frame.istate.variant.is_some() || frame.istate.fields.is_any_set() {
-            debug!("De-initializing partially initialized {:?}", frame.yellow());
-
-            match frame.shape.def {
-                Def::Struct(sd) => {
-                    for (i, field) in sd.fields.iter().enumerate() {
-                        if frame.istate.fields.has(i) {
-                            if let Some(drop_fn) = field.shape().vtable.drop_in_place {
-                                unsafe {
-                                    let field_ptr = frame.data.as_mut_byte_ptr().add(field.offset);
-                                    drop_fn(PtrMut::new(field_ptr));
-                                }
-                            }
-                        }
-                    }
-                }
-                Def::Enum(_) => {
-                    if let Some(variant) = &frame.istate.variant {
-                        for (i, field) in variant.data.fields.iter().enumerate() {
-                            if frame.istate.fields.has(i) {
-                                if let Some(drop_fn) = field.shape().vtable.drop_in_place {
-                                    unsafe {
-                                        let field_ptr =
-                                            frame.data.as_mut_byte_ptr().add(field.offset);
-                                        drop_fn(PtrMut::new(field_ptr));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                _ => {
-                    // For scalar types and other non-struct/enum, attempt to drop the field in place if initialized
-                    if frame.istate.fields.is_any_set() {
-                        debug!("Scalar type was set...");
-                        if let Some(drop_fn) = frame.shape.vtable.drop_in_place {
-                            debug!("And it has a drop fn, dropping now!");
-                            unsafe {
-                                drop_fn(frame.data.assume_init());
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Reset initialization state
-            frame.istate.variant = None;
-            ISet::clear(&mut frame.istate.fields);
+        unsafe {
+            frame.deinit();
         }
 
         unsafe {
@@ -1202,6 +1279,8 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
 
     /// Tries to parse the current frame's value from a string
     pub fn parse(mut self, s: &str) -> Result<Self, ReflectError> {
+        self.limits.check(LimitKind::StringLength, s.len())?;
+
         let Some(frame) = self.frames.last_mut() else {
             return Err(ReflectError::OperationFailed {
                 shape: <()>::SHAPE,
@@ -1213,11 +1292,44 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
         let index = frame.field_index_in_parent;
 
         let Some(parse_fn) = frame.shape.vtable.parse else {
+            // Transparent wrappers parse like the type they wrap
+            if let Some(inner_fn) = shape.inner {
+                let inner = Wip::alloc_shape(inner_fn())?
+                    .with_limits(self.limits)?
+                    .parse(s)?;
+                // What the inner value took counts against our own budget
+                self.charge(inner.allocated)?;
+                let mut inner = inner.build()?;
+                let guard = inner.guard.take().unwrap();
+                // `put_shape` moves the value out when it succeeds, and so
+                // does a `try_from` that accepted it, even if it then failed:
+                // then only the memory is left to free
+                let res = self.put_shape(PtrConst::new(guard.ptr), inner.shape);
+                let moved_out = match &res {
+                    Ok(_) => true,
+                    Err(ReflectError::TryFromError { inner: e, .. }) => {
+                        !matches!(e, TryFromError::UnsupportedSourceShape { .. })
+                    }
+                    Err(_) => false,
+                };
+                if !moved_out {
+                    if let Some(drop_fn) = inner.shape.vtable.drop_in_place {
+                        unsafe { drop_fn(PtrMut::new(guard.ptr)) };
+                    }
+                }
+                drop(guard);
+                return res;
+            }
+
             return Err(ReflectError::OperationFailed {
-                shape: frame.shape,
+                shape,
                 operation: "type does not implement Parse",
             });
         };
+        // Whatever we're replacing has to go first
+        unsafe {
+            frame.deinit();
+        }
         match unsafe { (parse_fn)(s, frame.data) } {
             Ok(_res) => {
                 unsafe {
@@ -1229,10 +1341,7 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
 
                 Ok(self)
             }
-            Err(_) => Err(ReflectError::OperationFailed {
-                shape,
-                operation: "parsing",
-            }),
+            Err(inner) => Err(ReflectError::ParseFailed { shape, inner }),
         }
     }
 
@@ -1560,6 +1669,12 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
                 // List is initialized, get element shape (requires immutable self)
                 // Drop mutable borrow of frame before calling immutable method
                 let shape = self.element_shape()?;
+                if let Def::List(list_def) = seq_shape.def {
+                    let frame = self.frames.last().unwrap();
+                    let len =
+                        unsafe { (list_def.vtable.len)(PtrConst::new(frame.data.as_byte_ptr())) };
+                    self.limits.check(LimitKind::CollectionLength, len + 1)?;
+                }
                 (shape, "list")
             }
 
@@ -1658,7 +1773,7 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
         );
         let _ = context_str;
 
-        self.frames.push(element_frame);
+        self.push_frame(element_frame)?;
         Ok(self)
     }
 
@@ -1704,7 +1819,7 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
             option_shape.blue(),
         );
 
-        self.frames.push(inner_frame);
+        self.push_frame(inner_frame)?;
         Ok(self)
     }
 
@@ -1815,6 +1930,12 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
             self = self.begin_map_insert()?;
         }
 
+        if let Def::Map(map_def) = map_shape.def {
+            let frame = self.frames.last().unwrap();
+            let len = unsafe { (map_def.vtable.len_fn)(PtrConst::new(frame.data.as_byte_ptr())) };
+            self.limits.check(LimitKind::CollectionLength, len + 1)?;
+        }
+
         // Get the key type
         let key_shape = self.key_shape()?;
 
@@ -1838,7 +1959,7 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
             map_shape.blue(),
         );
 
-        self.frames.push(key_frame);
+        self.push_frame(key_frame)?;
         Ok(self)
     }
 
@@ -1925,7 +2046,7 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
             key_frame.shape.yellow(),
         );
 
-        self.frames.push(value_frame);
+        self.push_frame(value_frame)?;
         Ok(self)
     }
 
@@ -1944,6 +2065,46 @@ impl<'facet_lifetime> Wip<'facet_lifetime> {
         Ok(self)
     }
 
+    /// Abandons the map entry whose value is the current frame: drops
+    /// whatever part of its key and value was initialized, and goes back to
+    /// the map, leaving it as it was before [`Wip::push_map_key`].
+    ///
+    /// This is for values that can't be finished, which [`Wip::pop`] would
+    /// leave the key of behind.
+    pub fn discard_map_entry(mut self) -> Result<Self, ReflectError> {
+        let Some(FrameMode::MapValue {
+            index: key_frame_index,
+        }) = self.frames.last().map(|frame| frame.istate.mode)
+        else {
+            return Err(ReflectError::OperationFailed {
+                shape: self.frames.last().map_or(<()>::SHAPE, |frame| frame.shape),
+                operation: "tried to discard a map entry, but the current frame is not a map value",
+            });
+        };
+
+        let value_frame = self.frames.pop().unwrap();
+        let mut value_frame = self.evict_tree(value_frame);
+        let mut key_frame = self.frames.remove(key_frame_index);
+        trace!(
+            "[{}] Discarding map entry with key {} and value {}",
+            self.frames.len(),
+            key_frame.shape.yellow(),
+            value_frame.shape.green(),
+        );
+        // Safety: the initialization states match the frames' data, and
+        // `push_map_value` only takes fully initialized keys
+        unsafe {
+            value_frame.deinit();
+            value_frame.dealloc_if_needed();
+            if key_frame.is_fully_initialized() {
+                key_frame.drop_and_dealloc_if_needed();
+            } else {
+                key_frame.dealloc_if_needed();
+            }
+        }
+        Ok(self)
+    }
+
     fn pop_inner(&mut self) -> Result<Option<Frame>, ReflectError> {
         let mut frame = match self.frames.pop() {
             Some(f) => f,
@@ -2602,3 +2763,11 @@ impl Drop for Wip<'_> {
         }
     }
 }
+
+/// Size of a value of the given shape, or 0 if it's unsized
+fn shape_size(shape: &Shape) -> usize {
+    shape
+        .layout
+        .sized_layout()
+        .map_or(0, |layout| layout.size())
+}
@@ -0,0 +1,45 @@
+use facet_reflect::Peek;
+
+#[test]
+fn peek_array() -> Result<(), Box<dyn std::error::Error>> {
+    facet_testhelpers::setup();
+
+    let test_array = [10u16, 20, 30];
+    let peek_array = Peek::new(&test_array).into_array()?;
+
+    assert_eq!(peek_array.len(), 3);
+    assert_eq!(*peek_array.get(1).unwrap().get::<u16>()?, 20);
+    assert!(peek_array.get(3).is_none());
+
+    let items: Vec<u16> = peek_array
+        .iter()
+        .map(|item| *item.get::<u16>().unwrap())
+        .collect();
+    assert_eq!(items, vec![10, 20, 30]);
+
+    Ok(())
+}
+
+#[test]
+fn peek_slice() -> Result<(), Box<dyn std::error::Error>> {
+    facet_testhelpers::setup();
+
+    let backing = vec!["a".to_string(), "b".to_string()];
+    let test_slice: &[String] = &backing;
+    let peek_slice = Peek::new(&test_slice).into_slice()?;
+
+    assert_eq!(peek_slice.len(), 2);
+    assert_eq!(peek_slice.get(0).unwrap().get::<String>()?, "a");
+    assert!(peek_slice.get(2).is_none());
+    assert_eq!(peek_slice.iter().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn peek_array_is_not_a_slice() {
+    facet_testhelpers::setup();
+
+    let test_array = [1u8; 4];
+    assert!(Peek::new(&test_array).into_slice().is_err());
+}
@@ -1,3 +1,4 @@
+mod array;
 mod enum_;
 #[cfg(feature = "std")]
 mod facts;
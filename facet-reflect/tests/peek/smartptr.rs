@@ -139,3 +139,15 @@ fn test_peek_rc_with_string() {
     // Verify the inner type is correct
     assert_eq!(def.pointee, Some(String::SHAPE));
 }
+
+#[test]
+fn test_peek_arc_borrow_inner() {
+    facet_testhelpers::setup();
+
+    let source = Arc::new("shared".to_string());
+    let peek_smart_pointer = Peek::new(&source).into_smart_pointer().unwrap();
+
+    let inner = peek_smart_pointer.borrow_inner().unwrap();
+    assert_eq!(inner.shape(), String::SHAPE);
+    assert_eq!(inner.get::<String>().unwrap(), "shared");
+}
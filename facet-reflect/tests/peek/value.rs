@@ -36,3 +36,18 @@ fn test_peek_value_twostrings() {
     assert_eq!(av.to_string(), "⟨Option<i32>⟩");
     assert_eq!(format!("{a:?}"), format!("{av:?}"));
 }
+
+#[test]
+fn peek_borrow_inner_of_transparent() {
+    facet_testhelpers::setup();
+
+    #[derive(facet::Facet)]
+    #[facet(transparent)]
+    struct UserId(u64);
+
+    let id = UserId(7);
+    let inner = Peek::new(&id).borrow_inner().unwrap();
+    assert_eq!(*inner.get::<u64>().unwrap(), 7);
+
+    assert!(Peek::new(&7u64).borrow_inner().is_none());
+}
@@ -0,0 +1,155 @@
+use facet::Facet;
+use facet_reflect::{LimitKind, Limits, ReflectError, Wip};
+use std::collections::HashMap;
+
+fn limit_of(err: ReflectError) -> LimitKind {
+    match err {
+        ReflectError::LimitExceeded { kind, .. } => kind,
+        other => panic!("expected a limit error, got {other}"),
+    }
+}
+
+#[test]
+fn depth_limit() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Inner {
+        x: u32,
+    }
+
+    #[derive(Facet)]
+    struct Outer {
+        inner: Inner,
+    }
+
+    let limits = Limits::default().max_depth(2);
+    let wip = Wip::alloc::<Outer>()?
+        .with_limits(limits)?
+        .field_named("inner")?;
+    let err = wip.field_named("x").map(|_| ()).unwrap_err();
+    assert_eq!(limit_of(err), LimitKind::Depth);
+
+    // The same shape builds fine with one more level allowed
+    Wip::alloc::<Outer>()?
+        .with_limits(limits.max_depth(3))?
+        .field_named("inner")?
+        .field_named("x")?
+        .put(1u32)?
+        .pop()?
+        .pop()?
+        .build()?;
+    Ok(())
+}
+
+#[test]
+fn collection_length_limit() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let limits = Limits::default().max_collection_len(2);
+    let mut wip = Wip::alloc::<Vec<u8>>()?.with_limits(limits)?;
+    for i in 0..2u8 {
+        wip = wip.push()?.put(i)?.pop()?;
+    }
+    let err = wip.push().map(|_| ()).unwrap_err();
+    assert_eq!(limit_of(err), LimitKind::CollectionLength);
+
+    let mut wip = Wip::alloc::<HashMap<String, u8>>()?.with_limits(limits)?;
+    for key in ["a", "b"] {
+        wip = wip
+            .push_map_key()?
+            .put(key.to_string())?
+            .push_map_value()?
+            .put(0u8)?
+            .pop()?;
+    }
+    let err = wip.push_map_key().map(|_| ()).unwrap_err();
+    assert_eq!(limit_of(err), LimitKind::CollectionLength);
+    Ok(())
+}
+
+#[test]
+fn string_length_limit() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    let limits = Limits::default().max_string_len(4);
+    Wip::alloc::<String>()?
+        .with_limits(limits)?
+        .put("four".to_string())?
+        .build()?;
+
+    let err = Wip::alloc::<String>()?
+        .with_limits(limits)?
+        .put("fives".to_string())
+        .map(|_| ())
+        .unwrap_err();
+    assert_eq!(limit_of(err), LimitKind::StringLength);
+
+    let err = Wip::alloc::<u64>()?
+        .with_limits(limits)?
+        .parse("12345")
+        .map(|_| ())
+        .unwrap_err();
+    assert_eq!(limit_of(err), LimitKind::StringLength);
+    Ok(())
+}
+
+#[test]
+fn allocation_limit() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    // The list itself, then 8 bytes per pushed item
+    let budget = size_of::<Vec<u64>>() + 3 * size_of::<u64>();
+    let limits = Limits::default().max_alloc_bytes(budget);
+
+    let mut wip = Wip::alloc::<Vec<u64>>()?.with_limits(limits)?;
+    for i in 0..3u64 {
+        wip = wip.push()?.put(i)?.pop()?;
+    }
+    let err = wip.push().map(|_| ()).unwrap_err();
+    assert_eq!(limit_of(err), LimitKind::Allocation);
+
+    // String contents count too
+    let limits = Limits::default().max_alloc_bytes(size_of::<String>() + 3);
+    let err = Wip::alloc::<String>()?
+        .with_limits(limits)?
+        .put("four".to_string())
+        .map(|_| ())
+        .unwrap_err();
+    assert_eq!(limit_of(err), LimitKind::Allocation);
+    Ok(())
+}
+
+#[test]
+fn allocation_limit_through_transparent_parse() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(transparent)]
+    struct Name(String);
+
+    // The wrapper, the string parsed on the side, then its contents
+    let budget = size_of::<Name>() + size_of::<String>() + "ferris".len();
+    let name = Wip::alloc::<Name>()?
+        .with_limits(Limits::default().max_alloc_bytes(budget))?
+        .parse("ferris")?
+        .build()?
+        .materialize::<Name>()?;
+    assert_eq!(name, Name("ferris".to_string()));
+
+    let err = Wip::alloc::<Name>()?
+        .with_limits(Limits::default().max_alloc_bytes(budget - 1))?
+        .parse("ferris")
+        .map(|_| ())
+        .unwrap_err();
+    assert_eq!(limit_of(err), LimitKind::Allocation);
+
+    // Without the side allocation, the contents alone would have fit
+    let err = Wip::alloc::<Name>()?
+        .with_limits(Limits::default().max_alloc_bytes(size_of::<Name>() + "ferris".len()))?
+        .parse("ferris")
+        .map(|_| ())
+        .unwrap_err();
+    assert_eq!(limit_of(err), LimitKind::Allocation);
+    Ok(())
+}
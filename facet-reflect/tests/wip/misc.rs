@@ -840,3 +840,25 @@ fn gh_354_leak_2() -> Result<(), ReflectError> {
     leak2().unwrap_err();
     Ok(())
 }
+
+#[test]
+fn parse_into_transparent_wrapper() -> eyre::Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(transparent)]
+    struct Name(String);
+
+    let name = Wip::alloc::<Name>()?
+        .parse("ferris")?
+        .build()?
+        .materialize::<Name>()?;
+    assert_eq!(name, Name("ferris".to_string()));
+
+    #[derive(Facet, Debug, PartialEq)]
+    #[facet(transparent)]
+    struct Port(u16);
+
+    assert!(Wip::alloc::<Port>()?.parse("not a port").is_err());
+    Ok(())
+}
@@ -0,0 +1,533 @@
+use std::borrow::Cow;
+
+use facet_core::{Def, Facet, Field, ScalarAffinity, Shape, StructDef, StructKind};
+use facet_reflect::{ReflectError, Wip};
+use log::trace;
+
+use crate::parse::{Number, Parser};
+use crate::{RonError, RonErrorKind, Span, scalar_target, struct_name};
+
+/// Deserializes a RON document into a value of type `T`.
+///
+/// Struct names are optional, but must match the type's when they're
+/// there. Missing `Option` fields are `None`, and the `implicit_some`
+/// extension lets `Some(...)` be left out, as in
+/// `#![enable(implicit_some)]`.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// #[repr(u8)]
+/// enum Shape {
+///     Circle(f32),
+///     Rect { w: f32, h: f32 },
+/// }
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Sprite {
+///     name: String,
+///     shape: Shape,
+///     tint: Option<(u8, u8, u8)>,
+/// }
+///
+/// let sprite: Sprite = facet_ron::from_str(
+///     r#"Sprite(name: "ball", shape: Circle(2.0), tint: Some((255, 0, 0)))"#,
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     sprite,
+///     Sprite { name: "ball".to_string(), shape: Shape::Circle(2.0), tint: Some((255, 0, 0)) }
+/// );
+///
+/// let err = facet_ron::from_str::<Sprite>("(\n  name: 3,\n)").unwrap_err();
+/// assert_eq!((err.line, err.column), (2, 9));
+/// ```
+pub fn from_str<'input: 'facet, 'facet, T: Facet<'facet>>(ron: &'input str) -> Result<T, RonError> {
+    let mut deserializer = Deserializer::new(ron)?;
+    let whole = Span::new(0, ron.len());
+    let reflect = |e: ReflectError| deserializer.parser.error(e.into(), whole);
+
+    let wip = Wip::alloc::<T>().map_err(reflect)?;
+    let wip = deserializer.deserialize_value(wip)?;
+    if !deserializer.parser.is_at_end()? {
+        let span = Span::new(deserializer.parser.pos, ron.len());
+        return Err(deserializer
+            .parser
+            .error(RonErrorKind::TrailingCharacters, span));
+    }
+
+    let reflect = |e: ReflectError| deserializer.parser.error(e.into(), whole);
+    wip.build()
+        .map_err(reflect)?
+        .materialize::<T>()
+        .map_err(reflect)
+}
+
+struct Deserializer<'input> {
+    parser: Parser<'input>,
+    /// Whether values of options may be written without `Some(...)`
+    implicit_some: bool,
+}
+
+impl<'input> Deserializer<'input> {
+    fn new(ron: &'input str) -> Result<Self, RonError> {
+        let mut parser = Parser::new(ron);
+        let mut implicit_some = false;
+        for (extension, span) in parser.extensions()? {
+            match extension {
+                "implicit_some" => implicit_some = true,
+                _ => {
+                    let kind = RonErrorKind::UnsupportedExtension(extension.to_string());
+                    return Err(parser.error(kind, span));
+                }
+            }
+        }
+        Ok(Self {
+            parser,
+            implicit_some,
+        })
+    }
+
+    fn error(&self, kind: RonErrorKind, span: Span) -> RonError {
+        self.parser.error(kind, span)
+    }
+
+    /// Turns a reflection error into one about the input from `start` to
+    /// the current position.
+    fn reflect<T>(&self, start: usize, result: Result<T, ReflectError>) -> Result<T, RonError> {
+        result.map_err(|e| self.error(e.into(), Span::new(start, self.parser.pos)))
+    }
+
+    fn deserialize_value(&mut self, wip: Wip<'input>) -> Result<Wip<'input>, RonError> {
+        self.parser.skip_whitespace()?;
+        let start = self.parser.pos;
+        let shape = wip.shape();
+        trace!("Deserializing {}", shape);
+
+        match shape.def {
+            Def::Option(_) => self.deserialize_option(wip, start),
+            Def::Scalar(_) => self.deserialize_scalar(wip, shape, start),
+            // Transparent wrappers and smart pointers, which can be built
+            // from the scalar they hold
+            _ if shape.inner.is_some() => match scalar_target(shape) {
+                Some(target) => self.deserialize_scalar(wip, target, start),
+                None => Err(self.unsupported(shape, start)),
+            },
+            Def::Struct(sd) => self.deserialize_struct(wip, shape, sd, start),
+            Def::Enum(_) => self.deserialize_enum(wip, shape, start),
+            Def::List(_) => {
+                self.parser.expect('[', "`[`")?;
+                let mut wip = self.reflect(start, wip.put_default())?;
+                if !self.parser.eat(']')? {
+                    loop {
+                        wip = self.reflect(start, wip.push())?;
+                        wip = self.deserialize_value(wip)?;
+                        wip = self.reflect(start, wip.pop())?;
+                        if self.parser.item_end(']', "`,` or `]`")? {
+                            break;
+                        }
+                    }
+                }
+                Ok(wip)
+            }
+            Def::Map(_) => {
+                self.parser.expect('{', "`{`")?;
+                let mut wip = self.reflect(start, wip.put_default())?;
+                if !self.parser.eat('}')? {
+                    loop {
+                        wip = self.reflect(start, wip.push_map_key())?;
+                        wip = self.deserialize_value(wip)?;
+                        self.parser.expect(':', "`:`")?;
+                        wip = self.reflect(start, wip.push_map_value())?;
+                        wip = self.deserialize_value(wip)?;
+                        wip = self.reflect(start, wip.pop())?;
+                        if self.parser.item_end('}', "`,` or `}`")? {
+                            break;
+                        }
+                    }
+                }
+                Ok(wip)
+            }
+            _ => Err(self.unsupported(shape, start)),
+        }
+    }
+
+    fn unsupported(&self, shape: &'static Shape, start: usize) -> RonError {
+        let kind = RonErrorKind::UnsupportedShape(shape.to_string());
+        self.error(kind, Span::new(start, start))
+    }
+
+    /// Deserializes `None`, `Some(...)`, or with `implicit_some`, the value
+    /// itself.
+    fn deserialize_option(
+        &mut self,
+        wip: Wip<'input>,
+        start: usize,
+    ) -> Result<Wip<'input>, RonError> {
+        match self.parser.peek_ident()? {
+            Some("None") => {
+                self.parser.ident()?;
+                self.reflect(start, wip.put_default())
+            }
+            Some("Some") => {
+                self.parser.ident()?;
+                self.parser.expect('(', "`(`")?;
+                let wip = self.reflect(start, wip.push_some())?;
+                let wip = self.deserialize_value(wip)?;
+                self.parser.eat(',')?;
+                self.parser.expect(')', "`)`")?;
+                self.reflect(start, wip.pop())
+            }
+            _ if self.implicit_some => {
+                let wip = self.reflect(start, wip.push_some())?;
+                let wip = self.deserialize_value(wip)?;
+                self.reflect(start, wip.pop())
+            }
+            _ => Err(self.parser.unexpected("`Some` or `None`")),
+        }
+    }
+
+    fn deserialize_struct(
+        &mut self,
+        wip: Wip<'input>,
+        shape: &'static Shape,
+        sd: StructDef,
+        start: usize,
+    ) -> Result<Wip<'input>, RonError> {
+        match sd.kind {
+            StructKind::Tuple => {
+                self.parser.expect('(', "`(`")?;
+                self.deserialize_tuple(wip, sd.fields.len(), start)
+            }
+            // `Name` or `()`
+            StructKind::Unit => {
+                if !self.struct_name(shape)? {
+                    self.parser.expect('(', "`(`")?;
+                    self.parser.expect(')', "`)`")?;
+                }
+                self.reflect(start, wip.put_default())
+            }
+            StructKind::TupleStruct => {
+                self.struct_name(shape)?;
+                self.parser.expect('(', "`(`")?;
+                self.deserialize_tuple(wip, sd.fields.len(), start)
+            }
+            StructKind::Struct => {
+                self.struct_name(shape)?;
+                self.parser.expect('(', "`(`")?;
+                self.deserialize_fields(wip, shape, sd.fields, start)
+            }
+            _ => Err(self.unsupported(shape, start)),
+        }
+    }
+
+    /// Consumes the name a struct is written with, if there is one, and
+    /// checks that it is the type's.
+    fn struct_name(&mut self, shape: &'static Shape) -> Result<bool, RonError> {
+        let Some((name, span)) = self.parser.ident()? else {
+            return Ok(false);
+        };
+        let expected = struct_name(shape);
+        if name != expected {
+            let kind = RonErrorKind::StructNameMismatch {
+                expected,
+                got: name.to_string(),
+            };
+            return Err(self.error(kind, span));
+        }
+        Ok(true)
+    }
+
+    /// Deserializes `name: value` pairs into the fields of a struct or
+    /// struct variant, after the opening parenthesis.
+    fn deserialize_fields(
+        &mut self,
+        mut wip: Wip<'input>,
+        shape: &'static Shape,
+        fields: &'static [Field],
+        start: usize,
+    ) -> Result<Wip<'input>, RonError> {
+        if !self.parser.eat(')')? {
+            loop {
+                let Some((name, span)) = self.parser.ident()? else {
+                    return Err(self.parser.unexpected("a field name"));
+                };
+                self.parser.expect(':', "`:`")?;
+
+                match wip.field_index(name) {
+                    Some(index) => {
+                        if self.reflect(start, wip.is_field_set(index))? {
+                            let kind = RonErrorKind::DuplicateField(name.to_string());
+                            return Err(self.error(kind, span));
+                        }
+                        wip = self.reflect(start, wip.field(index))?;
+                        wip = self.deserialize_value(wip)?;
+                        wip = self.reflect(start, wip.pop())?;
+                    }
+                    None if shape.has_deny_unknown_fields_attr() => {
+                        let kind = RonErrorKind::UnknownField(name.to_string());
+                        return Err(self.error(kind, span));
+                    }
+                    None => {
+                        trace!("Skipping unknown field: {}", name);
+                        self.parser.skip_value()?;
+                    }
+                }
+                if self.parser.item_end(')', "`,` or `)`")? {
+                    break;
+                }
+            }
+        }
+
+        for (index, field) in fields.iter().enumerate() {
+            if self.reflect(start, wip.is_field_set(index))? {
+                continue;
+            }
+            wip = self.reflect(start, wip.field(index))?;
+            wip = match field.maybe_default_fn() {
+                Some(Some(default_fn)) => self.reflect(start, wip.put_from_fn(default_fn))?,
+                Some(None) => self.reflect(start, wip.put_default())?,
+                None if matches!(field.shape().def, Def::Option(_)) => {
+                    self.reflect(start, wip.put_default())?
+                }
+                None => {
+                    let kind = RonErrorKind::MissingField(field.name.to_string());
+                    return Err(self.error(kind, Span::new(start, self.parser.pos)));
+                }
+            };
+            wip = self.reflect(start, wip.pop())?;
+        }
+        Ok(wip)
+    }
+
+    /// Deserializes the items of a tuple, tuple struct or tuple variant,
+    /// after the opening parenthesis.
+    fn deserialize_tuple(
+        &mut self,
+        mut wip: Wip<'input>,
+        field_count: usize,
+        start: usize,
+    ) -> Result<Wip<'input>, RonError> {
+        let mut index = 0;
+        if !self.parser.eat(')')? {
+            loop {
+                if index == field_count {
+                    // Count the extra items for the error
+                    let mut got = index;
+                    loop {
+                        self.parser.skip_value()?;
+                        got += 1;
+                        if self.parser.item_end(')', "`,` or `)`")? {
+                            break;
+                        }
+                    }
+                    let kind = RonErrorKind::WrongLength {
+                        expected: field_count,
+                        got,
+                    };
+                    return Err(self.error(kind, Span::new(start, self.parser.pos)));
+                }
+                wip = self.reflect(start, wip.field(index))?;
+                wip = self.deserialize_value(wip)?;
+                wip = self.reflect(start, wip.pop())?;
+                index += 1;
+                if self.parser.item_end(')', "`,` or `)`")? {
+                    break;
+                }
+            }
+        }
+
+        if index != field_count {
+            let kind = RonErrorKind::WrongLength {
+                expected: field_count,
+                got: index,
+            };
+            return Err(self.error(kind, Span::new(start, self.parser.pos)));
+        }
+        Ok(wip)
+    }
+
+    /// Deserializes an enum variant: its name, followed by its fields in
+    /// parentheses unless it is a unit variant.
+    fn deserialize_enum(
+        &mut self,
+        wip: Wip<'input>,
+        shape: &'static Shape,
+        start: usize,
+    ) -> Result<Wip<'input>, RonError> {
+        let Some((name, span)) = self.parser.ident()? else {
+            return Err(self.parser.unexpected("a variant name"));
+        };
+        let Some((index, variant)) = wip.find_variant(name) else {
+            let kind = RonErrorKind::UnknownVariant(name.to_string());
+            return Err(self.error(kind, span));
+        };
+        let wip = self.reflect(start, wip.variant(index))?;
+
+        let fields = variant.data.fields;
+        if fields.is_empty() {
+            return Ok(wip);
+        }
+        self.parser.expect('(', "`(`")?;
+        match variant.data.kind {
+            StructKind::Struct => self.deserialize_fields(wip, shape, fields, start),
+            _ => self.deserialize_tuple(wip, fields.len(), start),
+        }
+    }
+
+    /// Deserializes a scalar of the `target` shape into the current frame,
+    /// which is either that shape or a wrapper around it.
+    fn deserialize_scalar(
+        &mut self,
+        wip: Wip<'input>,
+        target: &'static Shape,
+        start: usize,
+    ) -> Result<Wip<'input>, RonError> {
+        let Def::Scalar(sd) = target.def else {
+            return Err(self.unsupported(target, start));
+        };
+        trace!("Deserializing scalar {}", target);
+
+        macro_rules! integers {
+            ($($ty:ty),*) => {
+                $(
+                    if target.is_type::<$ty>() {
+                        let (n, span) = self.integer()?;
+                        let n = n
+                            .and_then(|n| <$ty>::try_from(n).ok())
+                            .ok_or_else(|| self.out_of_range(span))?;
+                        return self.reflect(start, wip.put(n));
+                    }
+                )*
+            };
+        }
+        integers!(u8, u16, u32, u64, usize, i8, i16, i32, i64, i128, isize);
+
+        if target.is_type::<u128>() {
+            let (number, span) = self.parser.number()?;
+            let n = match number {
+                Number::Integer {
+                    negative,
+                    magnitude,
+                } if !negative || magnitude == 0 => magnitude,
+                Number::Integer { .. } => return Err(self.out_of_range(span)),
+                Number::Float(_) => return Err(self.not_an_integer(span)),
+            };
+            return self.reflect(start, wip.put(n));
+        }
+        if target.is_type::<f64>() {
+            let n = self.float()?;
+            return self.reflect(start, wip.put(n));
+        }
+        if target.is_type::<f32>() {
+            let n = self.float()? as f32;
+            return self.reflect(start, wip.put(n));
+        }
+        if target.is_type::<bool>() {
+            let b = match self.parser.peek_ident()? {
+                Some("true") => true,
+                Some("false") => false,
+                _ => return Err(self.parser.unexpected("`true` or `false`")),
+            };
+            self.parser.ident()?;
+            return self.reflect(start, wip.put(b));
+        }
+        if target.is_type::<String>() {
+            let (s, _) = self.parser.string()?;
+            return self.reflect(start, wip.put(s.into_owned()));
+        }
+        if target.is_type::<&str>() {
+            return match self.parser.string()? {
+                (Cow::Borrowed(s), _) => self.reflect(start, wip.put(s)),
+                (Cow::Owned(s), span) => {
+                    let kind = RonErrorKind::UnexpectedToken {
+                        expected: "a string without escapes",
+                        got: s,
+                    };
+                    Err(self.error(kind, span))
+                }
+            };
+        }
+        if target.is_type::<Cow<'_, str>>() {
+            let (s, _) = self.parser.string()?;
+            return self.reflect(start, wip.put(s));
+        }
+        if target.is_type::<char>() {
+            let (c, _) = self.parser.char()?;
+            return self.reflect(start, wip.put(c));
+        }
+
+        let (text, span) = match sd.affinity {
+            ScalarAffinity::Empty(_) => {
+                self.parser.expect('(', "`()`")?;
+                self.parser.expect(')', "`)`")?;
+                return self.reflect(start, wip.put_default());
+            }
+            // Numbers this crate doesn't know about, such as `NonZero`s, are
+            // parsed from their text form
+            ScalarAffinity::Number(_) if self.parser.is_number()? => {
+                let (number, span) = self.parser.number()?;
+                let text = match number {
+                    Number::Integer {
+                        negative: true,
+                        magnitude,
+                    } => format!("-{}", magnitude),
+                    Number::Integer { magnitude, .. } => magnitude.to_string(),
+                    Number::Float(f) => f.to_string(),
+                };
+                (Cow::Owned(text), span)
+            }
+            _ => self.parser.string()?,
+        };
+        wip.parse(&text).map_err(|_| {
+            let kind = RonErrorKind::InvalidValue {
+                value: text.to_string(),
+                shape: target,
+            };
+            self.error(kind, span)
+        })
+    }
+
+    /// Reads an integer, which is `None` if it doesn't fit an `i128`.
+    fn integer(&mut self) -> Result<(Option<i128>, Span), RonError> {
+        let (number, span) = self.parser.number()?;
+        match number {
+            Number::Integer {
+                negative: true,
+                magnitude,
+            } => Ok((0i128.checked_sub_unsigned(magnitude), span)),
+            Number::Integer { magnitude, .. } => Ok((i128::try_from(magnitude).ok(), span)),
+            Number::Float(_) => Err(self.not_an_integer(span)),
+        }
+    }
+
+    /// Reads a float, or an integer as a float.
+    fn float(&mut self) -> Result<f64, RonError> {
+        let (number, _) = self.parser.number()?;
+        Ok(match number {
+            Number::Integer {
+                negative,
+                magnitude,
+            } => {
+                let n = magnitude as f64;
+                if negative { -n } else { n }
+            }
+            Number::Float(f) => f,
+        })
+    }
+
+    fn out_of_range(&self, span: Span) -> RonError {
+        let number = self.parser.text(span).to_string();
+        self.error(RonErrorKind::NumberOutOfRange(number), span)
+    }
+
+    fn not_an_integer(&self, span: Span) -> RonError {
+        let kind = RonErrorKind::UnexpectedToken {
+            expected: "an integer",
+            got: self.parser.text(span).to_string(),
+        };
+        self.error(kind, span)
+    }
+}
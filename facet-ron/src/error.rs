@@ -0,0 +1,148 @@
+use core::fmt;
+
+use facet_core::Shape;
+use facet_reflect::ReflectError;
+
+/// A range of bytes in the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// The offset of the first byte
+    pub start: usize,
+    /// The offset just past the last byte
+    pub end: usize,
+}
+
+impl Span {
+    pub(crate) fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
+/// An error while reading RON, with the part of the input it is about.
+#[derive(Debug)]
+pub struct RonError {
+    /// What went wrong
+    pub kind: RonErrorKind,
+    /// The bytes of the input the error is about
+    pub span: Span,
+    /// The 1-based line where the span starts
+    pub line: usize,
+    /// The 1-based column where the span starts, in characters
+    pub column: usize,
+}
+
+impl fmt::Display for RonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for RonError {}
+
+/// The different kinds of [`RonError`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RonErrorKind {
+    /// The input ended in the middle of a value
+    UnexpectedEof,
+    /// A token that doesn't fit here
+    UnexpectedToken {
+        /// What was expected instead
+        expected: &'static str,
+        /// The token
+        got: String,
+    },
+    /// A `/* ... */` comment that is never closed
+    UnclosedComment,
+    /// A backslash escape that doesn't exist, or names no character
+    InvalidEscape(String),
+    /// A number that doesn't follow the RON grammar
+    InvalidNumber(String),
+    /// A number that doesn't fit the type it is read as
+    NumberOutOfRange(String),
+    /// A struct written with another name than its type's
+    StructNameMismatch {
+        /// The name of the type
+        expected: String,
+        /// The name in the input
+        got: String,
+    },
+    /// A field no field of the struct matches, with `deny_unknown_fields`
+    UnknownField(String),
+    /// A field that appears twice in the same struct
+    DuplicateField(String),
+    /// A field without a default is missing
+    MissingField(String),
+    /// A name that matches no variant of the enum
+    UnknownVariant(String),
+    /// A tuple with a different number of items than its type
+    WrongLength {
+        /// How many items the type has
+        expected: usize,
+        /// How many items the input has
+        got: usize,
+    },
+    /// Something else than whitespace and comments after the value
+    TrailingCharacters,
+    /// A `#![enable(...)]` extension this crate doesn't implement
+    UnsupportedExtension(String),
+    /// A string that can't be parsed as the scalar type it is read as
+    InvalidValue {
+        /// The string
+        value: String,
+        /// The type it was parsed as
+        shape: &'static Shape,
+    },
+    /// Shape is not supported for deserialization
+    UnsupportedShape(String),
+    /// Reflection error
+    ReflectError(ReflectError),
+}
+
+impl From<ReflectError> for RonErrorKind {
+    fn from(err: ReflectError) -> Self {
+        Self::ReflectError(err)
+    }
+}
+
+impl fmt::Display for RonErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RonErrorKind::UnexpectedEof => write!(f, "Unexpected end of input"),
+            RonErrorKind::UnexpectedToken { expected, got } => {
+                write!(f, "Expected {}, got `{}`", expected, got)
+            }
+            RonErrorKind::UnclosedComment => write!(f, "Unclosed block comment"),
+            RonErrorKind::InvalidEscape(escape) => write!(f, "Invalid escape: {}", escape),
+            RonErrorKind::InvalidNumber(number) => write!(f, "Invalid number: {}", number),
+            RonErrorKind::NumberOutOfRange(number) => {
+                write!(f, "Number out of range for its type: {}", number)
+            }
+            RonErrorKind::StructNameMismatch { expected, got } => {
+                write!(f, "Expected struct {}, got {}", expected, got)
+            }
+            RonErrorKind::UnknownField(name) => write!(f, "Unknown field: {}", name),
+            RonErrorKind::DuplicateField(name) => write!(f, "Duplicate field: {}", name),
+            RonErrorKind::MissingField(name) => write!(f, "Missing required field: {}", name),
+            RonErrorKind::UnknownVariant(name) => write!(f, "Unknown variant: {}", name),
+            RonErrorKind::WrongLength { expected, got } => {
+                write!(f, "Expected {} items, got {}", expected, got)
+            }
+            RonErrorKind::TrailingCharacters => write!(f, "Trailing characters after the value"),
+            RonErrorKind::UnsupportedExtension(name) => {
+                write!(f, "Unsupported extension: {}", name)
+            }
+            RonErrorKind::InvalidValue { value, shape } => {
+                write!(f, "Invalid value for {}: {:?}", shape, value)
+            }
+            RonErrorKind::UnsupportedShape(shape) => {
+                write!(f, "Unsupported shape for deserialization: {}", shape)
+            }
+            RonErrorKind::ReflectError(err) => write!(f, "Reflection error: {}", err),
+        }
+    }
+}
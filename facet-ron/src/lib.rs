@@ -0,0 +1,51 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+use facet_core::{Def, Shape};
+
+mod error;
+pub use error::{RonError, RonErrorKind, Span};
+
+mod parse;
+
+mod deserialize;
+pub use deserialize::*;
+
+mod serialize;
+pub use serialize::*;
+
+/// The name a struct is written with: its type name, without generic
+/// parameters
+fn struct_name(shape: &'static Shape) -> String {
+    let name = shape.to_string();
+    match name.find('<') {
+        Some(end) => name[..end].to_string(),
+        None => name,
+    }
+}
+
+/// Follows transparent wrappers and smart pointers down to the scalar they
+/// hold, if any.
+fn scalar_target(mut shape: &'static Shape) -> Option<&'static Shape> {
+    loop {
+        if let Def::Scalar(_) = shape.def {
+            return Some(shape);
+        }
+        shape = (shape.inner?)();
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Characters raw identifiers (`r#...`) may have besides those of plain
+/// ones, so that renamed fields such as `content-type` can be written
+fn is_raw_ident_char(c: char) -> bool {
+    is_ident_char(c) || matches!(c, '-' | '.' | '+')
+}
@@ -0,0 +1,493 @@
+//! The tokens of RON, read on demand by the deserializer, which knows from
+//! the shape what comes next.
+//!
+//! Ref: <https://github.com/ron-rs/ron/blob/master/docs/grammar.md>
+
+use std::borrow::Cow;
+
+use crate::{RonError, RonErrorKind, Span, is_ident_char, is_ident_start, is_raw_ident_char};
+
+/// A number as written, before it is converted to its type
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Number {
+    Integer { negative: bool, magnitude: u128 },
+    Float(f64),
+}
+
+pub(crate) struct Parser<'input> {
+    input: &'input str,
+    pub(crate) pos: usize,
+}
+
+impl<'input> Parser<'input> {
+    pub(crate) fn new(input: &'input str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn rest(&self) -> &'input str {
+        &self.input[self.pos..]
+    }
+
+    pub(crate) fn text(&self, span: Span) -> &'input str {
+        &self.input[span.start..span.end]
+    }
+
+    pub(crate) fn error(&self, kind: RonErrorKind, span: Span) -> RonError {
+        let before = &self.input[..span.start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        RonError {
+            kind,
+            span,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Reports that the next token isn't what was expected.
+    pub(crate) fn unexpected(&mut self, expected: &'static str) -> RonError {
+        if let Err(err) = self.skip_whitespace() {
+            return err;
+        }
+        let span = self.token_span();
+        if span.start == span.end {
+            return self.error(RonErrorKind::UnexpectedEof, span);
+        }
+        let got = self.input[span.start..span.end].to_string();
+        self.error(RonErrorKind::UnexpectedToken { expected, got }, span)
+    }
+
+    /// The span of the next token, for errors: a word or number, or else a
+    /// single character
+    fn token_span(&self) -> Span {
+        let rest = self.rest();
+        let len = match rest.chars().next() {
+            None => 0,
+            Some(c) if is_ident_char(c) => rest.find(|c| !is_ident_char(c)).unwrap_or(rest.len()),
+            Some(c) => c.len_utf8(),
+        };
+        Span::new(self.pos, self.pos + len)
+    }
+
+    /// Skips whitespace and comments. Block comments nest.
+    pub(crate) fn skip_whitespace(&mut self) -> Result<(), RonError> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                let start = self.pos;
+                let mut depth = 0;
+                loop {
+                    let rest = self.rest();
+                    if rest.starts_with("/*") {
+                        depth += 1;
+                        self.pos += 2;
+                    } else if rest.starts_with("*/") {
+                        depth -= 1;
+                        self.pos += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        let Some(c) = rest.chars().next() else {
+                            let span = Span::new(start, start + 2);
+                            return Err(self.error(RonErrorKind::UnclosedComment, span));
+                        };
+                        self.pos += c.len_utf8();
+                    }
+                }
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Whether only whitespace and comments are left
+    pub(crate) fn is_at_end(&mut self) -> Result<bool, RonError> {
+        self.skip_whitespace()?;
+        Ok(self.rest().is_empty())
+    }
+
+    /// The next character, after whitespace and comments
+    pub(crate) fn peek(&mut self) -> Result<Option<char>, RonError> {
+        self.skip_whitespace()?;
+        Ok(self.rest().chars().next())
+    }
+
+    /// Consumes `c` if it's next.
+    pub(crate) fn eat(&mut self, c: char) -> Result<bool, RonError> {
+        if self.peek()? == Some(c) {
+            self.pos += c.len_utf8();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    pub(crate) fn expect(&mut self, c: char, expected: &'static str) -> Result<(), RonError> {
+        if self.eat(c)? {
+            return Ok(());
+        }
+        Err(self.unexpected(expected))
+    }
+
+    /// After an item of a list, map, tuple or struct: consumes the comma,
+    /// and returns whether the `close` character follows.
+    pub(crate) fn item_end(
+        &mut self,
+        close: char,
+        expected: &'static str,
+    ) -> Result<bool, RonError> {
+        if self.eat(',')? {
+            return self.eat(close);
+        }
+        self.expect(close, expected)?;
+        Ok(true)
+    }
+
+    /// Whether a raw string, rather than an identifier starting with `r`,
+    /// is next
+    fn is_raw_string(&self) -> bool {
+        self.rest()
+            .strip_prefix('r')
+            .is_some_and(|rest| rest.trim_start_matches('#').starts_with('"'))
+    }
+
+    /// Consumes an identifier, raw (`r#...`) or not, if one is next.
+    pub(crate) fn ident(&mut self) -> Result<Option<(&'input str, Span)>, RonError> {
+        self.skip_whitespace()?;
+        if self.is_raw_string() {
+            return Ok(None);
+        }
+        let start = self.pos;
+        let rest = self.rest();
+
+        let name = if let Some(raw) = rest.strip_prefix("r#") {
+            let len = raw.find(|c| !is_raw_ident_char(c)).unwrap_or(raw.len());
+            self.pos += 2;
+            &raw[..len]
+        } else if rest.starts_with(is_ident_start) {
+            let len = rest.find(|c| !is_ident_char(c)).unwrap_or(rest.len());
+            &rest[..len]
+        } else {
+            return Ok(None);
+        };
+        if name.is_empty() {
+            self.pos = start;
+            return Ok(None);
+        }
+        self.pos += name.len();
+        Ok(Some((name, Span::new(start, self.pos))))
+    }
+
+    /// The identifier that is next, if any, without consuming it
+    pub(crate) fn peek_ident(&mut self) -> Result<Option<&'input str>, RonError> {
+        let pos = self.pos;
+        let ident = self.ident()?;
+        self.pos = pos;
+        Ok(ident.map(|(name, _)| name))
+    }
+
+    /// Reads a string, raw (`r#"..."#`) or not. It is borrowed from the input
+    /// unless it has escapes.
+    pub(crate) fn string(&mut self) -> Result<(Cow<'input, str>, Span), RonError> {
+        self.skip_whitespace()?;
+        let start = self.pos;
+
+        if self.is_raw_string() {
+            let rest = &self.rest()[1..];
+            let hashes = rest.len() - rest.trim_start_matches('#').len();
+            let body = &rest[hashes + 1..];
+            let close = format!("\"{}", "#".repeat(hashes));
+            let Some(len) = body.find(&close) else {
+                let span = Span::new(start, self.input.len());
+                return Err(self.error(RonErrorKind::UnexpectedEof, span));
+            };
+            self.pos += 1 + hashes + 1 + len + close.len();
+            return Ok((Cow::Borrowed(&body[..len]), Span::new(start, self.pos)));
+        }
+
+        if !self.rest().starts_with('"') {
+            return Err(self.unexpected("a string"));
+        }
+        self.pos += 1;
+        let body_start = self.pos;
+        let mut owned: Option<String> = None;
+        loop {
+            let Some(c) = self.rest().chars().next() else {
+                let span = Span::new(start, self.pos);
+                return Err(self.error(RonErrorKind::UnexpectedEof, span));
+            };
+            match c {
+                '"' => {
+                    let text = match owned {
+                        Some(text) => Cow::Owned(text),
+                        None => Cow::Borrowed(&self.input[body_start..self.pos]),
+                    };
+                    self.pos += 1;
+                    return Ok((text, Span::new(start, self.pos)));
+                }
+                '\\' => {
+                    let text = owned.get_or_insert_with(|| self.input[body_start..self.pos].into());
+                    text.push(self.escape()?);
+                }
+                c => {
+                    self.pos += c.len_utf8();
+                    if let Some(text) = &mut owned {
+                        text.push(c);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reads a character literal, such as `'a'` or `'\n'`.
+    pub(crate) fn char(&mut self) -> Result<(char, Span), RonError> {
+        if !self.eat('\'')? {
+            return Err(self.unexpected("a character"));
+        }
+        let start = self.pos - 1;
+        let c = match self.rest().chars().next() {
+            Some('\\') => self.escape()?,
+            Some(c) if c != '\'' => {
+                self.pos += c.len_utf8();
+                c
+            }
+            _ => return Err(self.unexpected("a character")),
+        };
+        if !self.rest().starts_with('\'') {
+            return Err(self.unexpected("`'`"));
+        }
+        self.pos += 1;
+        Ok((c, Span::new(start, self.pos)))
+    }
+
+    /// Reads the escape the backslash at the current position starts.
+    fn escape(&mut self) -> Result<char, RonError> {
+        let start = self.pos;
+        self.pos += 1;
+        let Some(c) = self.rest().chars().next() else {
+            let span = Span::new(start, self.pos);
+            return Err(self.error(RonErrorKind::UnexpectedEof, span));
+        };
+        self.pos += c.len_utf8();
+
+        let escaped = match c {
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            't' => Some('\t'),
+            '0' => Some('\0'),
+            '\\' | '"' | '\'' => Some(c),
+            'x' => {
+                let digits = self.rest().get(..2).unwrap_or_default();
+                self.pos += digits.len();
+                u8::from_str_radix(digits, 16)
+                    .ok()
+                    .filter(|b| b.is_ascii())
+                    .map(char::from)
+            }
+            'u' => {
+                let rest = self.rest();
+                let code = rest
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.split_once('}'))
+                    .map(|(digits, _)| digits);
+                match code {
+                    Some(digits) => {
+                        self.pos += digits.len() + 2;
+                        u32::from_str_radix(digits, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                    }
+                    None => None,
+                }
+            }
+            _ => None,
+        };
+        escaped.ok_or_else(|| {
+            let span = Span::new(start, self.pos);
+            let escape = self.input[start..self.pos].to_string();
+            self.error(RonErrorKind::InvalidEscape(escape), span)
+        })
+    }
+
+    /// Reads a number: an integer, decimal or with a `0x`, `0o` or `0b`
+    /// prefix, or a float, `inf` and `NaN` included. Digits may be
+    /// separated with underscores.
+    pub(crate) fn number(&mut self) -> Result<(Number, Span), RonError> {
+        self.skip_whitespace()?;
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '+' | '-')))
+            .unwrap_or(rest.len());
+        // A sign only belongs to the number at its start, or after an
+        // exponent
+        let len = rest[..len]
+            .char_indices()
+            .skip(1)
+            .find(|&(i, c)| {
+                matches!(c, '+' | '-') && !matches!(rest.as_bytes()[i - 1], b'e' | b'E')
+            })
+            .map_or(len, |(i, _)| i);
+        if len == 0 {
+            return Err(self.unexpected("a number"));
+        }
+
+        let text = &rest[..len];
+        let span = Span::new(self.pos, self.pos + len);
+        let invalid = |kind: fn(String) -> RonErrorKind| self.error(kind(text.to_string()), span);
+
+        let (negative, unsigned) = match text.as_bytes()[0] {
+            b'-' => (true, &text[1..]),
+            b'+' => (false, &text[1..]),
+            _ => (false, text),
+        };
+        let number = match unsigned {
+            "inf" if negative => Number::Float(f64::NEG_INFINITY),
+            "inf" => Number::Float(f64::INFINITY),
+            "NaN" => Number::Float(f64::NAN),
+            _ if !unsigned.starts_with(|c: char| c.is_ascii_digit()) => {
+                return Err(invalid(RonErrorKind::InvalidNumber));
+            }
+            _ => {
+                let digits = unsigned.replace('_', "");
+                let (radix, digits) = match digits.get(..2) {
+                    Some("0x") => (16, &digits[2..]),
+                    Some("0o") => (8, &digits[2..]),
+                    Some("0b") => (2, &digits[2..]),
+                    _ => (10, &digits[..]),
+                };
+                if radix == 10 && digits.contains(['.', 'e', 'E']) {
+                    let value: f64 = digits
+                        .parse()
+                        .map_err(|_| invalid(RonErrorKind::InvalidNumber))?;
+                    Number::Float(if negative { -value } else { value })
+                } else if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+                    return Err(invalid(RonErrorKind::InvalidNumber));
+                } else {
+                    let magnitude = u128::from_str_radix(digits, radix)
+                        .map_err(|_| invalid(RonErrorKind::NumberOutOfRange))?;
+                    Number::Integer {
+                        negative,
+                        magnitude,
+                    }
+                }
+            }
+        };
+        self.pos += len;
+        Ok((number, span))
+    }
+
+    /// Whether a number is next
+    pub(crate) fn is_number(&mut self) -> Result<bool, RonError> {
+        if self
+            .peek()?
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+'))
+        {
+            return Ok(true);
+        }
+        Ok(matches!(self.peek_ident()?, Some("inf" | "NaN")))
+    }
+
+    /// Skips a value of any type, for fields the type doesn't know.
+    pub(crate) fn skip_value(&mut self) -> Result<(), RonError> {
+        match self.peek()? {
+            Some('"') => {
+                self.string()?;
+            }
+            Some('r') if self.is_raw_string() => {
+                self.string()?;
+            }
+            Some('\'') => {
+                self.char()?;
+            }
+            Some('[') => {
+                self.pos += 1;
+                if !self.eat(']')? {
+                    loop {
+                        self.skip_value()?;
+                        if self.item_end(']', "`,` or `]`")? {
+                            break;
+                        }
+                    }
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                if !self.eat('}')? {
+                    loop {
+                        self.skip_value()?;
+                        self.expect(':', "`:`")?;
+                        self.skip_value()?;
+                        if self.item_end('}', "`,` or `}`")? {
+                            break;
+                        }
+                    }
+                }
+            }
+            Some('(') => self.skip_parenthesized()?,
+            _ if self.is_number()? => {
+                self.number()?;
+            }
+            _ => {
+                if self.ident()?.is_none() {
+                    return Err(self.unexpected("a value"));
+                }
+                // Named structs and variants with data
+                if self.peek()? == Some('(') {
+                    self.skip_parenthesized()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips the items of a tuple, or the fields of a struct.
+    fn skip_parenthesized(&mut self) -> Result<(), RonError> {
+        self.expect('(', "`(`")?;
+        if self.eat(')')? {
+            return Ok(());
+        }
+        loop {
+            let pos = self.pos;
+            let is_field = self.ident()?.is_some() && self.eat(':')?;
+            if !is_field {
+                self.pos = pos;
+            }
+            self.skip_value()?;
+            if self.item_end(')', "`,` or `)`")? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Reads the `#![enable(...)]` attributes at the start of a document,
+    /// and returns the extensions they name.
+    pub(crate) fn extensions(&mut self) -> Result<Vec<(&'input str, Span)>, RonError> {
+        let mut extensions = Vec::new();
+        while self.eat('#')? {
+            self.expect('!', "`!`")?;
+            self.expect('[', "`[`")?;
+            match self.ident()? {
+                Some(("enable", _)) => {}
+                _ => return Err(self.unexpected("`enable`")),
+            }
+            self.expect('(', "`(`")?;
+            if !self.eat(')')? {
+                loop {
+                    let Some(extension) = self.ident()? else {
+                        return Err(self.unexpected("an extension name"));
+                    };
+                    extensions.push(extension);
+                    if self.item_end(')', "`,` or `)`")? {
+                        break;
+                    }
+                }
+            }
+            self.expect(']', "`]`")?;
+        }
+        Ok(extensions)
+    }
+}
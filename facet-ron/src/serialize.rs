@@ -0,0 +1,466 @@
+use std::io::{self, Write};
+
+use facet_core::{Def, Facet, Field, ScalarAffinity, StructKind};
+use facet_reflect::Peek;
+
+use crate::{is_ident_char, is_ident_start, is_raw_ident_char, struct_name};
+
+/// Options for [`to_string_pretty`] and [`to_writer_pretty`]
+///
+/// # Example
+/// ```
+/// use facet_ron::{PrettyConfig, to_string_pretty};
+///
+/// let config = PrettyConfig::default().indent("  ").compact_lists(true);
+/// assert_eq!(
+///     to_string_pretty(&vec![(1, 2), (3, 4)], config),
+///     "[(1, 2), (3, 4)]"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrettyConfig {
+    indent: String,
+    struct_names: bool,
+    compact_lists: bool,
+    separate_tuple_members: bool,
+    depth_limit: usize,
+}
+
+impl Default for PrettyConfig {
+    fn default() -> Self {
+        Self {
+            indent: "    ".to_string(),
+            struct_names: true,
+            compact_lists: false,
+            separate_tuple_members: false,
+            depth_limit: usize::MAX,
+        }
+    }
+}
+
+impl PrettyConfig {
+    /// Sets the indentation of each level, four spaces by default
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    /// Sets whether structs are written with their name, as in
+    /// `Point(x: 1, y: 2)` rather than `(x: 1, y: 2)`. `true` by default.
+    pub fn struct_names(mut self, struct_names: bool) -> Self {
+        self.struct_names = struct_names;
+        self
+    }
+
+    /// Sets whether lists are written on a single line, `false` by default
+    pub fn compact_lists(mut self, compact_lists: bool) -> Self {
+        self.compact_lists = compact_lists;
+        self
+    }
+
+    /// Sets whether the items of tuples, tuple structs and tuple variants
+    /// get a line each, like the fields of structs do. `false` by default.
+    pub fn separate_tuple_members(mut self, separate_tuple_members: bool) -> Self {
+        self.separate_tuple_members = separate_tuple_members;
+        self
+    }
+
+    /// Sets the nesting depth past which values are written on a single
+    /// line. Unlimited by default.
+    pub fn depth_limit(mut self, depth_limit: usize) -> Self {
+        self.depth_limit = depth_limit;
+        self
+    }
+}
+
+/// Serializes a value to RON, on a single line and without spaces.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// #[repr(u8)]
+/// enum Shape {
+///     Circle(f32),
+///     Rect { w: f32, h: f32 },
+/// }
+///
+/// #[derive(Facet)]
+/// struct Sprite {
+///     name: String,
+///     shapes: Vec<Shape>,
+///     tint: Option<(u8, u8, u8)>,
+/// }
+///
+/// let sprite = Sprite {
+///     name: "ball".to_string(),
+///     shapes: vec![Shape::Circle(2.0), Shape::Rect { w: 1.0, h: 0.5 }],
+///     tint: None,
+/// };
+/// assert_eq!(
+///     facet_ron::to_string(&sprite),
+///     r#"Sprite(name:"ball",shapes:[Circle(2.0),Rect(w:1.0,h:0.5)],tint:None)"#
+/// );
+/// ```
+pub fn to_string<'a, T: Facet<'a>>(value: &T) -> String {
+    let mut output = Vec::new();
+    to_writer(value, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// Serializes a value to RON, pretty-printed with the given options.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+/// use facet_ron::PrettyConfig;
+///
+/// #[derive(Facet)]
+/// struct Level {
+///     name: String,
+///     spawn: (f32, f32),
+///     enemies: Vec<String>,
+/// }
+///
+/// let level = Level {
+///     name: "caves".to_string(),
+///     spawn: (1.0, -2.5),
+///     enemies: vec!["bat".to_string()],
+/// };
+/// assert_eq!(
+///     facet_ron::to_string_pretty(&level, PrettyConfig::default()),
+///     r#"Level(
+///     name: "caves",
+///     spawn: (1.0, -2.5),
+///     enemies: [
+///         "bat",
+///     ],
+/// )"#
+/// );
+/// ```
+pub fn to_string_pretty<'a, T: Facet<'a>>(value: &T, config: PrettyConfig) -> String {
+    let mut output = Vec::new();
+    to_writer_pretty(value, &mut output, config).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// Serializes a value to a writer in RON format, on a single line and
+/// without spaces
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    let mut serializer = Serializer {
+        writer,
+        pretty: None,
+        depth: 0,
+    };
+    serializer.write_value(Peek::new(value))
+}
+
+/// Serializes a value to a writer in RON format, pretty-printed with the
+/// given options
+pub fn to_writer_pretty<'a, T: Facet<'a>, W: Write>(
+    value: &T,
+    writer: &mut W,
+    config: PrettyConfig,
+) -> io::Result<()> {
+    let mut serializer = Serializer {
+        writer,
+        pretty: Some(config),
+        depth: 0,
+    };
+    serializer.write_value(Peek::new(value))
+}
+
+fn other(e: impl core::fmt::Display) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+fn unsupported(peek: Peek<'_, '_>) -> io::Error {
+    io::Error::other(format!(
+        "Unsupported shape for serialization: {}",
+        peek.shape()
+    ))
+}
+
+/// How the items of a list, map, tuple or struct are laid out when
+/// pretty-printing
+#[derive(Clone, Copy, PartialEq)]
+enum Layout {
+    /// A line each
+    Block,
+    /// All on the line of the brackets
+    Inline,
+}
+
+/// A list, map, tuple or struct being written
+struct Seq {
+    multiline: bool,
+    empty: bool,
+}
+
+struct Serializer<'w, W> {
+    writer: &'w mut W,
+    /// `None` for the compact form
+    pretty: Option<PrettyConfig>,
+    depth: usize,
+}
+
+impl<W: Write> Serializer<'_, W> {
+    fn begin(&mut self, open: char, layout: Layout) -> io::Result<Seq> {
+        let multiline = self.pretty.as_ref().is_some_and(|config| {
+            (layout == Layout::Block || config.separate_tuple_members)
+                && self.depth < config.depth_limit
+        });
+        self.depth += 1;
+        write!(self.writer, "{}", open)?;
+        Ok(Seq {
+            multiline,
+            empty: true,
+        })
+    }
+
+    /// Starts an item.
+    fn item(&mut self, seq: &mut Seq) -> io::Result<()> {
+        if !seq.empty {
+            self.writer.write_all(b",")?;
+        }
+        if seq.multiline {
+            self.new_line(self.depth)?;
+        } else if !seq.empty && self.pretty.is_some() {
+            self.writer.write_all(b" ")?;
+        }
+        seq.empty = false;
+        Ok(())
+    }
+
+    /// Ends the sequence, with a trailing comma when its items have a line
+    /// each.
+    fn end(&mut self, seq: Seq, close: char) -> io::Result<()> {
+        self.depth -= 1;
+        if seq.multiline && !seq.empty {
+            self.writer.write_all(b",")?;
+            self.new_line(self.depth)?;
+        }
+        write!(self.writer, "{}", close)
+    }
+
+    fn new_line(&mut self, depth: usize) -> io::Result<()> {
+        self.writer.write_all(b"\n")?;
+        if let Some(config) = &self.pretty {
+            for _ in 0..depth {
+                self.writer.write_all(config.indent.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the separator between a field name or map key and its value.
+    fn colon(&mut self) -> io::Result<()> {
+        match self.pretty {
+            Some(_) => self.writer.write_all(b": "),
+            None => self.writer.write_all(b":"),
+        }
+    }
+
+    fn struct_names(&self) -> bool {
+        self.pretty
+            .as_ref()
+            .is_none_or(|config| config.struct_names)
+    }
+
+    fn write_value(&mut self, peek: Peek<'_, '_>) -> io::Result<()> {
+        let shape = peek.shape();
+        match shape.def {
+            Def::Option(_) => match peek.into_option().map_err(other)?.value() {
+                Some(value) => {
+                    self.writer.write_all(b"Some(")?;
+                    self.write_value(value)?;
+                    self.writer.write_all(b")")
+                }
+                None => self.writer.write_all(b"None"),
+            },
+            Def::Scalar(sd) => self.write_scalar(peek, sd.affinity),
+            // Transparent wrappers and smart pointers are written as the
+            // value they hold
+            _ if shape.inner.is_some() => match peek.borrow_inner() {
+                Some(inner) => self.write_value(inner),
+                None => Err(unsupported(peek)),
+            },
+            Def::Struct(sd) => {
+                let ps = peek.into_struct().map_err(other)?;
+                let name = struct_name(shape);
+                match sd.kind {
+                    StructKind::Unit if self.struct_names() => self.write_ident(&name),
+                    StructKind::Unit => self.writer.write_all(b"()"),
+                    StructKind::Tuple => self.write_tuple(ps.fields().map(|(_, value)| value)),
+                    StructKind::TupleStruct => {
+                        if self.struct_names() {
+                            self.write_ident(&name)?;
+                        }
+                        self.write_tuple(ps.fields_for_serialize().map(|(_, value)| value))
+                    }
+                    StructKind::Struct => {
+                        if self.struct_names() {
+                            self.write_ident(&name)?;
+                        }
+                        self.write_fields(ps.fields_for_serialize())
+                    }
+                    _ => Err(unsupported(peek)),
+                }
+            }
+            Def::Enum(_) => {
+                let pe = peek.into_enum().map_err(other)?;
+                let variant = pe.active_variant();
+                self.write_ident(variant.name)?;
+                match variant.data.kind {
+                    _ if variant.data.fields.is_empty() => Ok(()),
+                    StructKind::Struct => self.write_fields(pe.fields_for_serialize()),
+                    _ => self.write_tuple(pe.fields_for_serialize().map(|(_, value)| value)),
+                }
+            }
+            Def::List(_) => self.write_list(peek.into_list().map_err(other)?.iter()),
+            Def::Array(_) => self.write_list(peek.into_array().map_err(other)?.iter()),
+            Def::Slice(_) => self.write_list(peek.into_slice().map_err(other)?.iter()),
+            Def::Map(_) => {
+                let mut seq = self.begin('{', Layout::Block)?;
+                for (key, value) in peek.into_map().map_err(other)?.iter() {
+                    self.item(&mut seq)?;
+                    self.write_value(key)?;
+                    self.colon()?;
+                    self.write_value(value)?;
+                }
+                self.end(seq, '}')
+            }
+            _ => Err(unsupported(peek)),
+        }
+    }
+
+    fn write_list<'mem, 'facet>(
+        &mut self,
+        items: impl Iterator<Item = Peek<'mem, 'facet>>,
+    ) -> io::Result<()> {
+        let compact = self
+            .pretty
+            .as_ref()
+            .is_some_and(|config| config.compact_lists);
+        let layout = if compact {
+            Layout::Inline
+        } else {
+            Layout::Block
+        };
+        let mut seq = self.begin('[', layout)?;
+        for item in items {
+            self.item(&mut seq)?;
+            self.write_value(item)?;
+        }
+        self.end(seq, ']')
+    }
+
+    fn write_tuple<'mem, 'facet>(
+        &mut self,
+        items: impl Iterator<Item = Peek<'mem, 'facet>>,
+    ) -> io::Result<()> {
+        let mut seq = self.begin('(', Layout::Inline)?;
+        for item in items {
+            self.item(&mut seq)?;
+            self.write_value(item)?;
+        }
+        self.end(seq, ')')
+    }
+
+    fn write_fields<'mem, 'facet>(
+        &mut self,
+        fields: impl Iterator<Item = (&'static Field, Peek<'mem, 'facet>)>,
+    ) -> io::Result<()> {
+        let mut seq = self.begin('(', Layout::Block)?;
+        for (field, value) in fields {
+            self.item(&mut seq)?;
+            self.write_ident(field.name)?;
+            self.colon()?;
+            self.write_value(value)?;
+        }
+        self.end(seq, ')')
+    }
+
+    /// Writes a name, as a raw identifier if it isn't a plain one.
+    fn write_ident(&mut self, name: &str) -> io::Result<()> {
+        let plain = name.starts_with(is_ident_start) && name.chars().all(is_ident_char);
+        if !plain && name.chars().all(is_raw_ident_char) {
+            self.writer.write_all(b"r#")?;
+        }
+        self.writer.write_all(name.as_bytes())
+    }
+
+    fn write_scalar(&mut self, peek: Peek<'_, '_>, affinity: ScalarAffinity) -> io::Result<()> {
+        let shape = peek.shape();
+        if let Ok(b) = peek.get::<bool>() {
+            return write!(self.writer, "{}", b);
+        }
+        if let Ok(s) = peek.get::<String>() {
+            return self.write_string(s);
+        }
+        if let Ok(s) = peek.get::<&str>() {
+            return self.write_string(s);
+        }
+        if let Ok(s) = peek.get::<std::borrow::Cow<'_, str>>() {
+            return self.write_string(s);
+        }
+        if let Ok(c) = peek.get::<char>() {
+            return self.write_char(*c);
+        }
+        if let Ok(n) = peek.get::<f32>() {
+            return self.write_float(&n.to_string());
+        }
+        if let Ok(n) = peek.get::<f64>() {
+            return self.write_float(&n.to_string());
+        }
+
+        match affinity {
+            ScalarAffinity::Empty(_) => self.writer.write_all(b"()"),
+            // Other numbers, integers included, are written as they display
+            ScalarAffinity::Number(_) if shape.vtable.display.is_some() => {
+                write!(self.writer, "{}", peek)
+            }
+            _ if shape.vtable.display.is_some() => self.write_string(&peek.to_string()),
+            _ => Err(unsupported(peek)),
+        }
+    }
+
+    /// Writes a float so that it reads back as one, with a decimal point
+    /// even when it's whole. `inf`, `-inf` and `NaN` are the same in Rust
+    /// and RON.
+    fn write_float(&mut self, text: &str) -> io::Result<()> {
+        self.writer.write_all(text.as_bytes())?;
+        if text.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+            self.writer.write_all(b".0")?;
+        }
+        Ok(())
+    }
+
+    fn write_string(&mut self, s: &str) -> io::Result<()> {
+        self.writer.write_all(b"\"")?;
+        for c in s.chars() {
+            self.write_escaped(c, '"')?;
+        }
+        self.writer.write_all(b"\"")
+    }
+
+    fn write_char(&mut self, c: char) -> io::Result<()> {
+        self.writer.write_all(b"'")?;
+        self.write_escaped(c, '\'')?;
+        self.writer.write_all(b"'")
+    }
+
+    fn write_escaped(&mut self, c: char, quote: char) -> io::Result<()> {
+        match c {
+            '\\' => self.writer.write_all(b"\\\\"),
+            '\n' => self.writer.write_all(b"\\n"),
+            '\r' => self.writer.write_all(b"\\r"),
+            '\t' => self.writer.write_all(b"\\t"),
+            '\0' => self.writer.write_all(b"\\0"),
+            c if c == quote => write!(self.writer, "\\{}", c),
+            c if c.is_control() => write!(self.writer, "\\u{{{:x}}}", c as u32),
+            c => write!(self.writer, "{}", c),
+        }
+    }
+}
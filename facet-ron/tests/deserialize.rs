@@ -0,0 +1,240 @@
+use eyre::Result;
+use facet::Facet;
+use facet_ron::{PrettyConfig, RonErrorKind, Span, from_str, to_string, to_string_pretty};
+use std::collections::HashMap;
+
+#[derive(Debug, PartialEq, Facet)]
+#[repr(u8)]
+enum Shape {
+    Point,
+    Circle(f64),
+    Rect { w: u32, h: u32 },
+}
+
+#[derive(Debug, PartialEq, Facet)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Facet)]
+struct Meters(f32);
+
+#[derive(Debug, PartialEq, Facet)]
+struct Scene {
+    name: String,
+    shapes: Vec<Shape>,
+    offset: (i32, i32),
+    scale: Meters,
+    marker: Unit,
+    tags: HashMap<String, u8>,
+    parent: Option<u32>,
+}
+
+fn scene() -> Scene {
+    let mut tags = HashMap::new();
+    tags.insert("a".to_string(), 1);
+    Scene {
+        name: "level".to_string(),
+        shapes: vec![Shape::Point, Shape::Circle(1.0), Shape::Rect { w: 2, h: 3 }],
+        offset: (-1, 2),
+        scale: Meters(0.5),
+        marker: Unit,
+        tags,
+        parent: Some(7),
+    }
+}
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let ron = r#"
+        // A scene
+        Scene(
+            name: "level",
+            shapes: [Point, Circle(1.0), Rect(w: 2, h: 3)],
+            offset: (-1, 2),
+            scale: Meters(0.5),
+            marker: Unit,
+            /* tags, /* nested */ by name */
+            tags: { "a": 1 },
+            parent: Some(7),
+        )
+    "#;
+    assert_eq!(from_str::<Scene>(ron)?, scene());
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(from_str::<Scene>(&to_string(&scene()))?, scene());
+    let pretty = to_string_pretty(&scene(), PrettyConfig::default());
+    assert_eq!(from_str::<Scene>(&pretty)?, scene());
+    let nameless = to_string_pretty(&scene(), PrettyConfig::default().struct_names(false));
+    assert_eq!(from_str::<Scene>(&nameless)?, scene());
+    Ok(())
+}
+
+#[test]
+fn scalars() -> Result<()> {
+    facet_testhelpers::setup();
+
+    assert_eq!(from_str::<u32>("0x_ff")?, 255);
+    assert_eq!(from_str::<i8>("-0b1000_0000")?, -128);
+    assert_eq!(from_str::<u16>("0o17")?, 15);
+    assert_eq!(from_str::<i64>("+1_000")?, 1000);
+    assert_eq!(from_str::<u128>(&u128::MAX.to_string())?, u128::MAX);
+    assert_eq!(from_str::<f64>("1e3")?, 1000.0);
+    assert_eq!(from_str::<f32>("-inf")?, f32::NEG_INFINITY);
+    assert!(from_str::<f64>("NaN")?.is_nan());
+    assert_eq!(from_str::<f64>("3")?, 3.0);
+    assert_eq!(from_str::<char>(r"'\n'")?, '\n');
+    assert_eq!(from_str::<char>(r"'\u{e9}'")?, 'é');
+    assert_eq!(from_str::<String>(r#""a\"b\x41\u{1F600}""#)?, "a\"bA😀");
+    assert_eq!(from_str::<String>(r###"r##"a "# b"##"###)?, "a \"# b");
+    assert_eq!(from_str::<&str>(r#""borrowed""#)?, "borrowed");
+    assert_eq!(from_str::<bool>("true")?, true);
+    from_str::<()>("()")?;
+    assert_eq!(
+        from_str::<core::num::NonZeroU8>("3")?,
+        core::num::NonZeroU8::new(3).unwrap()
+    );
+    Ok(())
+}
+
+#[test]
+fn options() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Config {
+        name: Option<String>,
+        level: Option<u8>,
+    }
+
+    assert_eq!(
+        from_str::<Config>(r#"(name: Some("x"))"#)?,
+        Config {
+            name: Some("x".to_string()),
+            level: None,
+        }
+    );
+    assert_eq!(
+        from_str::<Config>("#![enable(implicit_some)]\n(name: \"x\", level: None)")?,
+        Config {
+            name: Some("x".to_string()),
+            level: None,
+        }
+    );
+
+    let err = from_str::<Config>(r#"(name: "x")"#).unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::UnexpectedToken { .. }));
+
+    let err = from_str::<Config>("#![enable(unwrap_newtypes)]\n()").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::UnsupportedExtension(_)));
+    Ok(())
+}
+
+#[test]
+fn raw_identifiers() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Header {
+        #[facet(rename = "content-type")]
+        content_type: String,
+    }
+
+    let header = Header {
+        content_type: "text/plain".to_string(),
+    };
+    assert_eq!(from_str::<Header>(&to_string(&header))?, header);
+    Ok(())
+}
+
+#[test]
+fn unknown_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Lenient {
+        a: u8,
+    }
+
+    #[derive(Debug, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        a: u8,
+    }
+
+    let ron = r#"(b: Thing(x: [1, (2, "3")], y: {'c': r"d"}), a: 1, c: None)"#;
+    assert_eq!(from_str::<Lenient>(ron)?, Lenient { a: 1 });
+
+    let err = from_str::<Strict>(ron).unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::UnknownField(ref name) if name == "b"));
+    assert_eq!(err.span, Span { start: 1, end: 2 });
+    Ok(())
+}
+
+#[test]
+fn errors_have_spans() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Point {
+        x: u8,
+        y: u8,
+    }
+
+    let err = from_str::<Point>("Point(x: 1, y: 300)").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::NumberOutOfRange(ref n) if n == "300"));
+    assert_eq!(err.span, Span { start: 15, end: 18 });
+
+    let err = from_str::<Point>("Pointe(x: 1, y: 2)").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::StructNameMismatch { .. }));
+    assert_eq!(err.span, Span { start: 0, end: 6 });
+
+    let err = from_str::<Point>("(x: 1,\n x: 2)").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::DuplicateField(_)));
+    assert_eq!((err.line, err.column), (2, 2));
+
+    let err = from_str::<Point>("(x: 1)").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::MissingField(ref name) if name == "y"));
+
+    let err = from_str::<Point>("(x: 1, y: 2) extra").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::TrailingCharacters));
+
+    let err = from_str::<Point>("(x: 1, y: 2").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::UnexpectedEof));
+
+    let err = from_str::<Point>("(x: 1.5, y: 2)").unwrap_err();
+    assert!(matches!(
+        err.kind,
+        RonErrorKind::UnexpectedToken {
+            expected: "an integer",
+            ..
+        }
+    ));
+
+    let err = from_str::<(u8, u8)>("(1, 2, 3)").unwrap_err();
+    assert!(matches!(
+        err.kind,
+        RonErrorKind::WrongLength {
+            expected: 2,
+            got: 3
+        }
+    ));
+
+    let err = from_str::<Shape>("Square(1)").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::UnknownVariant(ref name) if name == "Square"));
+
+    let err = from_str::<String>(r#""\q""#).unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::InvalidEscape(_)));
+    assert_eq!(err.span, Span { start: 1, end: 3 });
+
+    let err = from_str::<u8>("/* never closed").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::UnclosedComment));
+
+    let err = from_str::<u8>("12x").unwrap_err();
+    assert!(matches!(err.kind, RonErrorKind::InvalidNumber(_)));
+}
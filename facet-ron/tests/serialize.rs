@@ -0,0 +1,161 @@
+use facet::Facet;
+use facet_ron::{PrettyConfig, to_string, to_string_pretty, to_writer};
+use std::collections::BTreeMap;
+
+#[derive(Facet)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Shape {
+    Point,
+    Circle(f64),
+    Rect { w: u32, h: u32 },
+}
+
+#[derive(Facet)]
+struct Unit;
+
+#[derive(Facet)]
+struct Meters(f32);
+
+#[derive(Facet)]
+struct Scene {
+    name: String,
+    shapes: Vec<Shape>,
+    offset: (i32, i32),
+    scale: Meters,
+    marker: Unit,
+    tags: BTreeMap<String, u8>,
+    parent: Option<u32>,
+}
+
+fn scene() -> Scene {
+    let mut tags = BTreeMap::new();
+    tags.insert("a".to_string(), 1);
+    Scene {
+        name: "level".to_string(),
+        shapes: vec![Shape::Point, Shape::Circle(1.0), Shape::Rect { w: 2, h: 3 }],
+        offset: (-1, 2),
+        scale: Meters(0.5),
+        marker: Unit,
+        tags,
+        parent: Some(7),
+    }
+}
+
+#[test]
+fn it_works() {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        to_string(&scene()),
+        r#"Scene(name:"level",shapes:[Point,Circle(1.0),Rect(w:2,h:3)],offset:(-1,2),scale:Meters(0.5),marker:Unit,tags:{"a":1},parent:Some(7))"#
+    );
+
+    let mut out = Vec::new();
+    to_writer(&scene(), &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), to_string(&scene()));
+}
+
+#[test]
+fn pretty() {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        to_string_pretty(&scene(), PrettyConfig::default()),
+        r#"Scene(
+    name: "level",
+    shapes: [
+        Point,
+        Circle(1.0),
+        Rect(
+            w: 2,
+            h: 3,
+        ),
+    ],
+    offset: (-1, 2),
+    scale: Meters(0.5),
+    marker: Unit,
+    tags: {
+        "a": 1,
+    },
+    parent: Some(7),
+)"#
+    );
+}
+
+#[test]
+fn pretty_options() {
+    facet_testhelpers::setup();
+
+    let config = PrettyConfig::default()
+        .indent("\t")
+        .struct_names(false)
+        .compact_lists(true)
+        .depth_limit(1);
+    assert_eq!(
+        to_string_pretty(&scene(), config),
+        "(\n\tname: \"level\",\n\tshapes: [Point, Circle(1.0), Rect(w: 2, h: 3)],\n\toffset: (-1, 2),\n\tscale: (0.5),\n\tmarker: (),\n\ttags: {\"a\": 1},\n\tparent: Some(7),\n)"
+    );
+
+    let config = PrettyConfig::default().separate_tuple_members(true);
+    assert_eq!(to_string_pretty(&(1, 2), config), "(\n    1,\n    2,\n)");
+}
+
+#[test]
+fn scalars() {
+    facet_testhelpers::setup();
+
+    assert_eq!(to_string(&1.0f32), "1.0");
+    assert_eq!(to_string(&-2.5f64), "-2.5");
+    assert_eq!(to_string(&f64::NAN), "NaN");
+    assert_eq!(to_string(&f64::NEG_INFINITY), "-inf");
+    assert_eq!(to_string(&u128::MAX), u128::MAX.to_string());
+    assert_eq!(to_string(&'\''), r"'\''");
+    assert_eq!(to_string(&"a\"b\\c\n\u{1b}"), r#""a\"b\\c\n\u{1b}""#);
+    assert_eq!(to_string(&()), "()");
+    assert_eq!(to_string(&true), "true");
+    assert_eq!(to_string(&core::num::NonZeroU8::new(3).unwrap()), "3");
+}
+
+#[test]
+fn renamed_fields_are_raw_identifiers() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Header {
+        #[facet(rename = "content-type")]
+        content_type: String,
+    }
+
+    assert_eq!(
+        to_string(&Header {
+            content_type: "text/plain".to_string()
+        }),
+        r#"Header(r#content-type:"text/plain")"#
+    );
+}
+
+#[test]
+fn empty_collections() {
+    facet_testhelpers::setup();
+
+    #[derive(Facet)]
+    struct Empty {}
+
+    #[derive(Facet)]
+    struct Lists {
+        items: Vec<u8>,
+        map: BTreeMap<u8, u8>,
+        empty: Empty,
+    }
+
+    let lists = Lists {
+        items: vec![],
+        map: BTreeMap::new(),
+        empty: Empty {},
+    };
+    assert_eq!(
+        to_string_pretty(&lists, PrettyConfig::default()),
+        "Lists(\n    items: [],\n    map: {},\n    empty: Empty(),\n)"
+    );
+}
@@ -0,0 +1,340 @@
+use facet_core::{Def, Facet, Field, Shape, StructKind, Variant};
+use facet_reflect::{ReflectError, Wip};
+use log::trace;
+
+use crate::parse::{Element, parse};
+use crate::{XmlError, XmlErrorKind, element_namespace, field_name, is_attribute, is_text};
+
+/// Deserializes an XML document into a value of type `T`.
+///
+/// The name of the root element isn't checked, except for enums, whose
+/// variant it names.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Debug, Facet, PartialEq)]
+/// struct Point {
+///     #[facet(xml_attribute)]
+///     x: i32,
+///     #[facet(xml_attribute)]
+///     y: i32,
+///     label: Option<String>,
+/// }
+///
+/// let point: Point = facet_xml::from_str(r#"<Point x="1" y="2"/>"#).unwrap();
+/// assert_eq!(point, Point { x: 1, y: 2, label: None });
+///
+/// let err = facet_xml::from_str::<Point>("<Point x=\"1\"\n  y=\"two\"/>").unwrap_err();
+/// assert_eq!((err.line, err.column), (2, 3));
+/// ```
+pub fn from_str<'facet, T: Facet<'facet>>(xml: &str) -> Result<T, XmlError> {
+    let root = parse(xml)?;
+    let reflect = |e: ReflectError| root.error(e.into());
+
+    let wip = Wip::alloc::<T>().map_err(reflect)?;
+    let wip = deserialize_root(wip, &root)?;
+    wip.build()
+        .map_err(reflect)?
+        .materialize::<T>()
+        .map_err(reflect)
+}
+
+fn deserialize_root<'facet>(wip: Wip<'facet>, root: &Element) -> Result<Wip<'facet>, XmlError> {
+    let shape = wip.shape();
+    let namespace = element_namespace(shape, None);
+    if namespace.is_some() && root.name.namespace.as_deref() != namespace {
+        return Err(root.error(XmlErrorKind::WrongNamespace {
+            expected: namespace.map(str::to_string),
+            got: root.name.namespace.clone(),
+        }));
+    }
+
+    match shape.def {
+        Def::Enum(_) => {
+            let Some((index, variant)) = wip.find_variant(&root.name.local) else {
+                return Err(root.error(XmlErrorKind::UnknownVariant(root.name.local.clone())));
+            };
+            let wip = wip.variant(index).map_err(|e| root.error(e.into()))?;
+            deserialize_variant(wip, variant, root, namespace)
+        }
+        _ => deserialize_value(wip, root, namespace),
+    }
+}
+
+/// Deserializes the content of an element into the current frame.
+fn deserialize_value<'facet>(
+    wip: Wip<'facet>,
+    element: &Element,
+    namespace: Option<&'static str>,
+) -> Result<Wip<'facet>, XmlError> {
+    let shape = wip.shape();
+    trace!("Deserializing {} from <{}>", shape, element.name.local);
+    let reflect = |e: ReflectError| element.error(e.into());
+
+    match shape.def {
+        Def::Option(_) => {
+            let wip = wip.push_some().map_err(reflect)?;
+            let wip = deserialize_value(wip, element, namespace)?;
+            wip.pop().map_err(reflect)
+        }
+        Def::Struct(sd) => match sd.kind {
+            StructKind::Struct | StructKind::Unit => {
+                deserialize_fields(wip, element, shape, sd.fields, namespace)
+            }
+            _ if sd.fields.len() == 1 => {
+                let wip = wip.field(0).map_err(reflect)?;
+                let wip = deserialize_value(wip, element, namespace)?;
+                wip.pop().map_err(reflect)
+            }
+            _ => Err(element.error(XmlErrorKind::UnsupportedShape(format!("{}", shape)))),
+        },
+        // Each child element is an item, whatever its name
+        Def::List(_) => {
+            let mut wip = wip.put_default().map_err(reflect)?;
+            for child in &element.children {
+                wip = wip.push().map_err(|e| child.error(e.into()))?;
+                wip = deserialize_value(wip, child, namespace)?;
+                wip = wip.pop().map_err(|e| child.error(e.into()))?;
+            }
+            Ok(wip)
+        }
+        // Each child element is an entry, keyed by its name
+        Def::Map(_) => {
+            let mut wip = wip.put_default().map_err(reflect)?;
+            for child in &element.children {
+                let reflect = |e: ReflectError| child.error(e.into());
+                wip = wip.push_map_key().map_err(reflect)?;
+                wip = put_text(wip, &child.name.local, child.line, child.column)?;
+                wip = wip.push_map_value().map_err(reflect)?;
+                wip = deserialize_value(wip, child, namespace)?;
+                wip = wip.pop().map_err(reflect)?;
+            }
+            Ok(wip)
+        }
+        Def::Enum(_) => deserialize_enum(wip, element, namespace),
+        _ => put_text(wip, &element.text, element.line, element.column),
+    }
+}
+
+/// Deserializes an enum: unit variants are the element's text, others its
+/// single child element, named after the variant.
+fn deserialize_enum<'facet>(
+    wip: Wip<'facet>,
+    element: &Element,
+    namespace: Option<&'static str>,
+) -> Result<Wip<'facet>, XmlError> {
+    match element.children.as_slice() {
+        [] => {
+            let name = element.text.trim();
+            let Some((index, variant)) = wip.find_variant(name) else {
+                return Err(element.error(XmlErrorKind::UnknownVariant(name.to_string())));
+            };
+            if let Some(field) = variant.data.fields.first() {
+                return Err(
+                    element.error(XmlErrorKind::MissingField(field_name(field).to_string()))
+                );
+            }
+            wip.variant(index).map_err(|e| element.error(e.into()))
+        }
+        [child] => {
+            let Some((index, variant)) = wip.find_variant(&child.name.local) else {
+                return Err(child.error(XmlErrorKind::UnknownVariant(child.name.local.clone())));
+            };
+            let wip = wip.variant(index).map_err(|e| child.error(e.into()))?;
+            deserialize_variant(wip, variant, child, namespace)
+        }
+        [_, extra, ..] => Err(extra.error(XmlErrorKind::UnknownElement(extra.name.local.clone()))),
+    }
+}
+
+/// Deserializes the element named after a variant into its fields.
+fn deserialize_variant<'facet>(
+    wip: Wip<'facet>,
+    variant: Variant,
+    element: &Element,
+    namespace: Option<&'static str>,
+) -> Result<Wip<'facet>, XmlError> {
+    let fields = variant.data.fields;
+    match variant.data.kind {
+        _ if fields.is_empty() => Ok(wip),
+        StructKind::Struct => {
+            let shape = wip.shape();
+            deserialize_fields(wip, element, shape, fields, namespace)
+        }
+        _ if fields.len() == 1 => {
+            let reflect = |e: ReflectError| element.error(e.into());
+            let wip = wip.field(0).map_err(reflect)?;
+            let wip = deserialize_value(wip, element, namespace)?;
+            wip.pop().map_err(reflect)
+        }
+        _ => Err(element.error(XmlErrorKind::UnsupportedShape(format!(
+            "tuple variant {}",
+            variant.name
+        )))),
+    }
+}
+
+/// Deserializes the attributes, text and child elements of an element into
+/// the fields of a struct or struct variant.
+fn deserialize_fields<'facet>(
+    mut wip: Wip<'facet>,
+    element: &Element,
+    shape: &'static Shape,
+    fields: &'static [Field],
+    namespace: Option<&'static str>,
+) -> Result<Wip<'facet>, XmlError> {
+    let reflect = |e: ReflectError| element.error(e.into());
+    let mut used_attributes = vec![false; element.attributes.len()];
+    let mut used_children = vec![false; element.children.len()];
+
+    for (index, field) in fields.iter().enumerate() {
+        let name = field_name(field);
+
+        if is_attribute(field) {
+            let found = element
+                .attributes
+                .iter()
+                .position(|a| a.name.namespace.is_none() && a.name.local == name);
+            if let Some(position) = found {
+                used_attributes[position] = true;
+                let attribute = &element.attributes[position];
+                wip = wip.field(index).map_err(reflect)?;
+                wip = put_text(wip, &attribute.value, attribute.line, attribute.column)?;
+                wip = wip.pop().map_err(reflect)?;
+                continue;
+            }
+        } else if is_text(field) {
+            wip = wip.field(index).map_err(reflect)?;
+            wip = put_text(wip, &element.text, element.line, element.column)?;
+            wip = wip.pop().map_err(reflect)?;
+            continue;
+        } else {
+            let child_namespace = element_namespace(field.shape(), namespace);
+            let matches: Vec<usize> = element
+                .children
+                .iter()
+                .enumerate()
+                .filter(|(_, child)| {
+                    child.name.local == name
+                        && (child_namespace.is_none()
+                            || child.name.namespace.as_deref() == child_namespace)
+                })
+                .map(|(position, _)| position)
+                .collect();
+            for &position in &matches {
+                used_children[position] = true;
+            }
+
+            // Lists are repeated elements, possibly none
+            if let Def::List(_) = field.shape().def {
+                wip = wip.field(index).map_err(reflect)?;
+                wip = wip.put_default().map_err(reflect)?;
+                for &position in &matches {
+                    let child = &element.children[position];
+                    wip = wip.push().map_err(|e| child.error(e.into()))?;
+                    wip = deserialize_value(wip, child, child_namespace)?;
+                    wip = wip.pop().map_err(|e| child.error(e.into()))?;
+                }
+                wip = wip.pop().map_err(reflect)?;
+                continue;
+            }
+
+            match matches.as_slice() {
+                [] => {}
+                [position] => {
+                    let child = &element.children[*position];
+                    wip = wip.field(index).map_err(reflect)?;
+                    wip = deserialize_value(wip, child, child_namespace)?;
+                    wip = wip.pop().map_err(reflect)?;
+                    continue;
+                }
+                [_, position, ..] => {
+                    let child = &element.children[*position];
+                    return Err(child.error(XmlErrorKind::DuplicateElement(name.to_string())));
+                }
+            }
+        }
+
+        // The field is missing: options are `None`, and fields marked
+        // `default` get their default
+        let default_fn = match field.maybe_default_fn() {
+            Some(default_fn) => default_fn,
+            None if matches!(field.shape().def, Def::Option(_)) => None,
+            None => return Err(element.error(XmlErrorKind::MissingField(name.to_string()))),
+        };
+        wip = wip.field(index).map_err(reflect)?;
+        wip = match default_fn {
+            Some(default_fn) => wip.put_from_fn(default_fn).map_err(reflect)?,
+            None => wip.put_default().map_err(reflect)?,
+        };
+        wip = wip.pop().map_err(reflect)?;
+    }
+
+    if shape.has_deny_unknown_fields_attr() {
+        if let Some(position) = used_children.iter().position(|used| !used) {
+            let child = &element.children[position];
+            return Err(child.error(XmlErrorKind::UnknownElement(child.name.local.clone())));
+        }
+        // Attributes in a namespace, such as `xsi:schemaLocation`, belong
+        // to other vocabularies
+        let unknown = element
+            .attributes
+            .iter()
+            .zip(&used_attributes)
+            .find(|(attribute, used)| !**used && attribute.name.namespace.is_none());
+        if let Some((attribute, _)) = unknown {
+            return Err(XmlError::new(
+                XmlErrorKind::UnknownAttribute(attribute.name.local.clone()),
+                attribute.line,
+                attribute.column,
+            ));
+        }
+    }
+
+    Ok(wip)
+}
+
+/// Parses text, from an attribute or an element, into the current frame.
+/// Enums are read by variant name, and everything else through its
+/// `FromStr` implementation.
+fn put_text<'facet>(
+    wip: Wip<'facet>,
+    text: &str,
+    line: usize,
+    column: usize,
+) -> Result<Wip<'facet>, XmlError> {
+    let shape = wip.shape();
+    let error = |kind| XmlError::new(kind, line, column);
+
+    match shape.def {
+        Def::Option(_) => {
+            let wip = wip.push_some().map_err(|e| error(e.into()))?;
+            let wip = put_text(wip, text, line, column)?;
+            wip.pop().map_err(|e| error(e.into()))
+        }
+        Def::Enum(_) => {
+            let name = text.trim();
+            let Some((index, _)) = wip.find_variant(name) else {
+                return Err(error(XmlErrorKind::UnknownVariant(name.to_string())));
+            };
+            wip.variant(index).map_err(|e| error(e.into()))
+        }
+        _ => {
+            // Whitespace around the text only matters for strings
+            let value = if shape.is_type::<String>() {
+                text
+            } else {
+                text.trim()
+            };
+            wip.parse(value).map_err(|e| match e {
+                ReflectError::ParseFailed { shape, .. } => error(XmlErrorKind::InvalidValue {
+                    value: value.to_string(),
+                    shape,
+                }),
+                e => error(e.into()),
+            })
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use core::fmt;
+
+use facet_core::Shape;
+use facet_reflect::ReflectError;
+
+/// An error while reading XML, with the position it was found at.
+#[derive(Debug)]
+pub struct XmlError {
+    /// What went wrong
+    pub kind: XmlErrorKind,
+    /// The 1-based line of the input
+    pub line: usize,
+    /// The 1-based column of the input, in characters
+    pub column: usize,
+}
+
+impl XmlError {
+    pub(crate) fn new(kind: XmlErrorKind, line: usize, column: usize) -> Self {
+        Self { kind, line, column }
+    }
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for XmlError {}
+
+/// The different kinds of [`XmlError`]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum XmlErrorKind {
+    /// The input ended before the root element was closed
+    UnexpectedEof,
+    /// A character that isn't allowed here
+    UnexpectedChar(char),
+    /// A closing tag that doesn't match the open element
+    MismatchedTag {
+        /// The name of the open element
+        expected: String,
+        /// The name in the closing tag
+        got: String,
+    },
+    /// An entity reference other than the predefined ones and character
+    /// references
+    InvalidEntity(String),
+    /// A name whose prefix has no namespace declaration in scope
+    UnboundPrefix(String),
+    /// An attribute that appears twice on the same element
+    DuplicateAttribute(String),
+    /// A field's element or attribute is missing
+    MissingField(String),
+    /// A field with a single value has several elements
+    DuplicateElement(String),
+    /// An element no field matches, with `deny_unknown_fields`
+    UnknownElement(String),
+    /// An attribute no field matches, with `deny_unknown_fields`
+    UnknownAttribute(String),
+    /// An element in another namespace than the one expected
+    WrongNamespace {
+        /// The namespace the element should be in
+        expected: Option<String>,
+        /// The namespace it is in
+        got: Option<String>,
+    },
+    /// An element or text that names no variant of the enum
+    UnknownVariant(String),
+    /// Text that can't be parsed as the field's type
+    InvalidValue {
+        /// The text
+        value: String,
+        /// The type it was parsed as
+        shape: &'static Shape,
+    },
+    /// Shape is not supported for deserialization
+    UnsupportedShape(String),
+    /// Reflection error
+    ReflectError(ReflectError),
+}
+
+impl From<ReflectError> for XmlErrorKind {
+    fn from(err: ReflectError) -> Self {
+        Self::ReflectError(err)
+    }
+}
+
+impl fmt::Display for XmlErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XmlErrorKind::UnexpectedEof => write!(f, "Unexpected end of input"),
+            XmlErrorKind::UnexpectedChar(c) => write!(f, "Unexpected character {:?}", c),
+            XmlErrorKind::MismatchedTag { expected, got } => {
+                write!(f, "Expected </{}>, got </{}>", expected, got)
+            }
+            XmlErrorKind::InvalidEntity(entity) => write!(f, "Invalid entity: &{};", entity),
+            XmlErrorKind::UnboundPrefix(prefix) => {
+                write!(f, "Undeclared namespace prefix: {}", prefix)
+            }
+            XmlErrorKind::DuplicateAttribute(name) => {
+                write!(f, "Duplicate attribute: {}", name)
+            }
+            XmlErrorKind::MissingField(name) => write!(f, "Missing required field: {}", name),
+            XmlErrorKind::DuplicateElement(name) => {
+                write!(f, "Element <{}> appears more than once", name)
+            }
+            XmlErrorKind::UnknownElement(name) => write!(f, "Unknown element: <{}>", name),
+            XmlErrorKind::UnknownAttribute(name) => write!(f, "Unknown attribute: {}", name),
+            XmlErrorKind::WrongNamespace { expected, got } => write!(
+                f,
+                "Expected namespace {}, got {}",
+                expected.as_deref().unwrap_or("(none)"),
+                got.as_deref().unwrap_or("(none)")
+            ),
+            XmlErrorKind::UnknownVariant(variant) => {
+                write!(f, "Unknown enum variant: {}", variant)
+            }
+            XmlErrorKind::InvalidValue { value, shape } => {
+                write!(f, "Invalid value for {}: {:?}", shape, value)
+            }
+            XmlErrorKind::UnsupportedShape(shape) => {
+                write!(f, "Unsupported shape for deserialization: {}", shape)
+            }
+            XmlErrorKind::ReflectError(err) => write!(f, "Reflection error: {}", err),
+        }
+    }
+}
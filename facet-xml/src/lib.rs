@@ -0,0 +1,83 @@
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+#![doc = include_str!("../README.md")]
+
+use facet_core::{Def, Field, FieldAttribute, Shape, ShapeAttribute};
+
+mod error;
+pub use error::{XmlError, XmlErrorKind};
+
+mod parse;
+
+mod deserialize;
+pub use deserialize::*;
+
+mod serialize;
+pub use serialize::*;
+
+/// Whether a field is marked with a bare attribute such as `xml_text`
+fn has_marker(field: &Field, marker: &str) -> bool {
+    field
+        .attributes
+        .iter()
+        .any(|attr| matches!(attr, FieldAttribute::Arbitrary(a) if a.trim() == marker))
+}
+
+/// Fields marked `#[facet(xml_attribute)]` are attributes of their
+/// struct's element
+fn is_attribute(field: &Field) -> bool {
+    has_marker(field, "xml_attribute")
+}
+
+/// A field marked `#[facet(xml_text)]` is the text of its struct's element
+fn is_text(field: &Field) -> bool {
+    has_marker(field, "xml_text")
+}
+
+/// The name of a field's element or attribute
+fn field_name(field: &'static Field) -> &'static str {
+    field.get_rename_attr().unwrap_or(field.name)
+}
+
+/// The namespace a shape declares with `#[facet(xml_namespace = "...")]`
+fn declared_namespace(shape: &Shape) -> Option<&'static str> {
+    shape.attributes.iter().find_map(|attr| match attr {
+        ShapeAttribute::Arbitrary(attr) => {
+            let (key, value) = attr.split_once('=')?;
+            (key.trim() == "xml_namespace").then(|| value.trim().trim_matches('"'))
+        }
+        _ => None,
+    })
+}
+
+/// The namespace of the elements holding values of `shape`: the one the
+/// shape, or the type it wraps or collects, declares, or else the one of
+/// the parent element.
+fn element_namespace(
+    mut shape: &'static Shape,
+    parent: Option<&'static str>,
+) -> Option<&'static str> {
+    loop {
+        if let Some(namespace) = declared_namespace(shape) {
+            return Some(namespace);
+        }
+        shape = match shape.def {
+            Def::Option(od) => od.t(),
+            Def::List(ld) => ld.t(),
+            _ => match shape.inner {
+                Some(inner) => inner(),
+                None => return parent,
+            },
+        };
+    }
+}
+
+/// The name of the root element for a value of `shape`: its type name,
+/// without generic parameters
+fn root_name(shape: &'static Shape) -> String {
+    let name = shape.to_string();
+    match name.find('<') {
+        Some(end) => name[..end].to_string(),
+        None => name,
+    }
+}
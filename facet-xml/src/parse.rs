@@ -0,0 +1,491 @@
+use crate::{XmlError, XmlErrorKind};
+
+/// The namespace the `xml` prefix is always bound to
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// A name resolved against the namespace declarations in scope
+#[derive(Debug)]
+pub(crate) struct Name {
+    pub namespace: Option<String>,
+    pub local: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct Attribute {
+    pub name: Name,
+    pub value: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct Element {
+    pub name: Name,
+    /// Attributes other than namespace declarations
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Element>,
+    /// The text directly inside the element, CDATA sections included
+    pub text: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Element {
+    pub(crate) fn error(&self, kind: XmlErrorKind) -> XmlError {
+        XmlError::new(kind, self.line, self.column)
+    }
+}
+
+/// Parses a document into its root element. Comments, processing
+/// instructions and the document type declaration are skipped.
+pub(crate) fn parse(input: &str) -> Result<Element, XmlError> {
+    Parser {
+        input,
+        pos: 0,
+        line: 1,
+        line_start: 0,
+        scopes: Vec::new(),
+    }
+    .document()
+}
+
+/// An element whose start tag was read, with its name as written, to match
+/// the end tag against
+struct Open {
+    qname: String,
+    element: Element,
+}
+
+struct Parser<'input> {
+    input: &'input str,
+    pos: usize,
+    line: usize,
+    /// Where the current line starts, to compute columns
+    line_start: usize,
+    /// The prefixes declared by each open element, `""` for the default
+    /// namespace
+    scopes: Vec<Vec<(String, String)>>,
+}
+
+impl<'input> Parser<'input> {
+    fn rest(&self) -> &'input str {
+        &self.input[self.pos..]
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        self.rest().starts_with(s)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.line_start = self.pos;
+        }
+        Some(c)
+    }
+
+    /// Skips a token known not to contain line breaks
+    fn advance(&mut self, len: usize) {
+        self.pos += len;
+    }
+
+    fn location(&self) -> (usize, usize) {
+        let column = self.input[self.line_start..self.pos].chars().count() + 1;
+        (self.line, column)
+    }
+
+    fn error(&self, kind: XmlErrorKind) -> XmlError {
+        let (line, column) = self.location();
+        XmlError::new(kind, line, column)
+    }
+
+    fn unexpected(&self) -> XmlError {
+        match self.peek() {
+            Some(c) => self.error(XmlErrorKind::UnexpectedChar(c)),
+            None => self.error(XmlErrorKind::UnexpectedEof),
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), XmlError> {
+        if !self.starts_with(token) {
+            return Err(self.unexpected());
+        }
+        self.advance(token.len());
+        Ok(())
+    }
+
+    /// Skips whitespace, and returns whether there was any
+    fn skip_whitespace(&mut self) -> bool {
+        let start = self.pos;
+        while matches!(self.peek(), Some(' ' | '\t' | '\r' | '\n')) {
+            self.bump();
+        }
+        self.pos > start
+    }
+
+    /// Skips past the next `end`, and returns what came before it
+    fn skip_until(&mut self, end: &str) -> Result<&'input str, XmlError> {
+        let Some(len) = self.rest().find(end) else {
+            while self.bump().is_some() {}
+            return Err(self.error(XmlErrorKind::UnexpectedEof));
+        };
+        let skipped = &self.rest()[..len];
+        for (i, b) in skipped.bytes().enumerate() {
+            if b == b'\n' {
+                self.line += 1;
+                self.line_start = self.pos + i + 1;
+            }
+        }
+        self.pos += len + end.len();
+        Ok(skipped)
+    }
+
+    /// Skips comments and processing instructions, and a document type
+    /// declaration when `doctype` is set
+    fn skip_misc(&mut self, doctype: bool) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("<!--") {
+                self.skip_until("-->")?;
+            } else if self.starts_with("<?") {
+                self.skip_until("?>")?;
+            } else if doctype && self.starts_with("<!DOCTYPE") {
+                self.skip_doctype()?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skips a document type declaration, internal subset included
+    fn skip_doctype(&mut self) -> Result<(), XmlError> {
+        let mut depth = 0;
+        loop {
+            match self.bump() {
+                Some('[') => depth += 1,
+                Some(']') => depth -= 1,
+                Some('>') if depth == 0 => return Ok(()),
+                Some(_) => {}
+                None => return Err(self.error(XmlErrorKind::UnexpectedEof)),
+            }
+        }
+    }
+
+    fn document(mut self) -> Result<Element, XmlError> {
+        if self.starts_with("\u{feff}") {
+            self.advance('\u{feff}'.len_utf8());
+        }
+        self.skip_misc(true)?;
+        if !self.starts_with("<") {
+            return Err(self.unexpected());
+        }
+        let root = self.element()?;
+        self.skip_misc(false)?;
+        if self.pos < self.input.len() {
+            return Err(self.unexpected());
+        }
+        Ok(root)
+    }
+
+    /// Reads an element and its descendants. Open elements are kept on a
+    /// stack rather than recursing, so deep documents can't overflow.
+    fn element(&mut self) -> Result<Element, XmlError> {
+        let mut stack: Vec<Open> = Vec::new();
+        loop {
+            let (open, empty) = self.start_tag()?;
+            let mut finished = if empty {
+                self.scopes.pop();
+                Some(open.element)
+            } else {
+                stack.push(open);
+                None
+            };
+
+            // Reads content up to the next start tag, closing elements
+            loop {
+                if let Some(element) = finished.take() {
+                    match stack.last_mut() {
+                        Some(parent) => parent.element.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                let Some(parent) = stack.last_mut() else {
+                    unreachable!("there is an open element until the root is closed")
+                };
+
+                if self.starts_with("</") {
+                    self.end_tag(&parent.qname)?;
+                    self.scopes.pop();
+                    finished = stack.pop().map(|open| open.element);
+                } else if self.starts_with("<!--") {
+                    self.skip_until("-->")?;
+                } else if self.starts_with("<![CDATA[") {
+                    self.advance("<![CDATA[".len());
+                    let text = self.skip_until("]]>")?;
+                    parent.element.text.push_str(text);
+                } else if self.starts_with("<?") {
+                    self.skip_until("?>")?;
+                } else if self.starts_with("<") {
+                    break;
+                } else if self.peek().is_none() {
+                    return Err(self.error(XmlErrorKind::UnexpectedEof));
+                } else {
+                    self.text(&mut parent.element.text)?;
+                }
+            }
+        }
+    }
+
+    /// Reads a start tag, and returns whether it is an empty-element tag
+    fn start_tag(&mut self) -> Result<(Open, bool), XmlError> {
+        let (line, column) = self.location();
+        self.expect("<")?;
+        let qname = self.name()?;
+
+        let mut raw_attributes: Vec<(&str, String, usize, usize)> = Vec::new();
+        let empty = loop {
+            let spaced = self.skip_whitespace();
+            if self.starts_with("/>") {
+                self.advance(2);
+                break true;
+            }
+            if self.starts_with(">") {
+                self.advance(1);
+                break false;
+            }
+            if !spaced {
+                return Err(self.unexpected());
+            }
+
+            let (line, column) = self.location();
+            let name = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let value = self.attribute_value()?;
+            if raw_attributes.iter().any(|(n, ..)| *n == name) {
+                return Err(XmlError::new(
+                    XmlErrorKind::DuplicateAttribute(name.to_string()),
+                    line,
+                    column,
+                ));
+            }
+            raw_attributes.push((name, value, line, column));
+        };
+
+        let scope = raw_attributes
+            .iter()
+            .filter_map(|(name, value, ..)| {
+                let prefix = match *name {
+                    "xmlns" => "",
+                    name => name.strip_prefix("xmlns:")?,
+                };
+                Some((prefix.to_string(), value.clone()))
+            })
+            .collect();
+        self.scopes.push(scope);
+
+        let name = self
+            .resolve(qname, true)
+            .map_err(|kind| XmlError::new(kind, line, column))?;
+        let mut attributes = Vec::with_capacity(raw_attributes.len());
+        for (name, value, line, column) in raw_attributes {
+            if name == "xmlns" || name.starts_with("xmlns:") {
+                continue;
+            }
+            attributes.push(Attribute {
+                name: self
+                    .resolve(name, false)
+                    .map_err(|kind| XmlError::new(kind, line, column))?,
+                value,
+                line,
+                column,
+            });
+        }
+
+        let open = Open {
+            qname: qname.to_string(),
+            element: Element {
+                name,
+                attributes,
+                children: Vec::new(),
+                text: String::new(),
+                line,
+                column,
+            },
+        };
+        Ok((open, empty))
+    }
+
+    fn end_tag(&mut self, qname: &str) -> Result<(), XmlError> {
+        let (line, column) = self.location();
+        self.expect("</")?;
+        let name = self.name()?;
+        self.skip_whitespace();
+        self.expect(">")?;
+        if name != qname {
+            return Err(XmlError::new(
+                XmlErrorKind::MismatchedTag {
+                    expected: qname.to_string(),
+                    got: name.to_string(),
+                },
+                line,
+                column,
+            ));
+        }
+        Ok(())
+    }
+
+    fn name(&mut self) -> Result<&'input str, XmlError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || "/>=<\"'&;".contains(c))
+            .unwrap_or(rest.len());
+        match rest.chars().next() {
+            Some(c) if len > 0 && !c.is_ascii_digit() && c != '-' && c != '.' => {
+                self.advance(len);
+                Ok(&rest[..len])
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// Resolves a prefixed name. Unprefixed attributes are in no namespace,
+    /// while unprefixed elements are in the default one.
+    fn resolve(&self, qname: &str, is_element: bool) -> Result<Name, XmlErrorKind> {
+        let (prefix, local) = qname.split_once(':').unwrap_or(("", qname));
+        let namespace = if prefix.is_empty() && !is_element {
+            None
+        } else if prefix == "xml" {
+            Some(XML_NAMESPACE.to_string())
+        } else {
+            let declared = self
+                .scopes
+                .iter()
+                .rev()
+                .flat_map(|scope| scope.iter().rev())
+                .find(|(p, _)| p == prefix);
+            match declared {
+                // `xmlns=""` takes elements back out of the default namespace
+                Some((_, uri)) if uri.is_empty() => None,
+                Some((_, uri)) => Some(uri.clone()),
+                None if prefix.is_empty() => None,
+                None => return Err(XmlErrorKind::UnboundPrefix(prefix.to_string())),
+            }
+        };
+        Ok(Name {
+            namespace,
+            local: local.to_string(),
+        })
+    }
+
+    /// Reads character data up to the next markup, with line breaks
+    /// normalized to `\n`
+    fn text(&mut self, out: &mut String) -> Result<(), XmlError> {
+        loop {
+            match self.peek() {
+                None | Some('<') => return Ok(()),
+                Some('&') => out.push(self.reference()?),
+                Some('\r') => {
+                    self.bump();
+                    if self.peek() == Some('\n') {
+                        self.bump();
+                    }
+                    out.push('\n');
+                }
+                Some(c) => {
+                    self.bump();
+                    out.push(c);
+                }
+            }
+        }
+    }
+
+    /// Reads a quoted attribute value, with whitespace characters normalized
+    /// to spaces
+    fn attribute_value(&mut self) -> Result<String, XmlError> {
+        let quote = match self.peek() {
+            Some(c @ ('"' | '\'')) => c,
+            _ => return Err(self.unexpected()),
+        };
+        self.bump();
+
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error(XmlErrorKind::UnexpectedEof)),
+                Some('<') => return Err(self.unexpected()),
+                Some('&') => value.push(self.reference()?),
+                Some(c) if c == quote => {
+                    self.bump();
+                    return Ok(value);
+                }
+                Some('\r') => {
+                    self.bump();
+                    if self.peek() == Some('\n') {
+                        self.bump();
+                    }
+                    value.push(' ');
+                }
+                Some('\n' | '\t') => {
+                    self.bump();
+                    value.push(' ');
+                }
+                Some(c) => {
+                    self.bump();
+                    value.push(c);
+                }
+            }
+        }
+    }
+
+    /// Reads a predefined entity or a character reference
+    fn reference(&mut self) -> Result<char, XmlError> {
+        let (line, column) = self.location();
+        self.expect("&")?;
+        let rest = self.rest();
+        let name = match rest.find(';') {
+            Some(len) if len <= 10 => &rest[..len],
+            _ => {
+                let len = rest
+                    .find(|c: char| !c.is_alphanumeric() && c != '#')
+                    .unwrap_or(rest.len());
+                return Err(XmlError::new(
+                    XmlErrorKind::InvalidEntity(rest[..len].to_string()),
+                    line,
+                    column,
+                ));
+            }
+        };
+
+        let c = match name {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => {
+                let code = match name.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => name.strip_prefix('#').and_then(|dec| dec.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+            }
+        };
+        let Some(c) = c else {
+            return Err(XmlError::new(
+                XmlErrorKind::InvalidEntity(name.to_string()),
+                line,
+                column,
+            ));
+        };
+        self.advance(name.len() + 1);
+        Ok(c)
+    }
+}
@@ -0,0 +1,311 @@
+use std::io::{self, Write};
+
+use facet_core::{Def, Facet, Field, StructKind};
+use facet_reflect::{Peek, PeekEnum};
+
+use crate::{element_namespace, field_name, is_attribute, is_text, root_name};
+
+/// Serializes a value to XML. The root element is named after the type, or
+/// after the variant for enums.
+///
+/// # Example
+/// ```
+/// use facet::Facet;
+///
+/// #[derive(Facet)]
+/// struct Note {
+///     #[facet(xml_attribute)]
+///     to: String,
+///     body: String,
+/// }
+///
+/// let note = Note {
+///     to: "Tove & Jani".to_string(),
+///     body: "<Don't forget me>".to_string(),
+/// };
+/// assert_eq!(
+///     facet_xml::to_string(&note),
+///     r#"<Note to="Tove &amp; Jani"><body>&lt;Don't forget me&gt;</body></Note>"#
+/// );
+/// ```
+pub fn to_string<'a, T: Facet<'a>>(value: &T) -> String {
+    let mut output = Vec::new();
+    to_writer(value, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+/// Serializes a value to a writer in XML format
+pub fn to_writer<'a, T: Facet<'a>, W: Write>(value: &T, writer: &mut W) -> io::Result<()> {
+    let peek = Peek::new(value);
+    let mut serializer = Serializer {
+        writer,
+        open_start_tag: false,
+    };
+
+    let shape = peek.shape();
+    match shape.def {
+        Def::Enum(_) => {
+            let namespace = element_namespace(shape, None);
+            serializer.write_variant(peek.into_enum().map_err(other)?, namespace, None)
+        }
+        _ => serializer.write_element(&root_name(shape), peek, None, None),
+    }
+}
+
+fn other(e: impl core::fmt::Display) -> io::Error {
+    io::Error::other(e.to_string())
+}
+
+fn unsupported(peek: Peek<'_, '_>) -> io::Error {
+    io::Error::other(format!(
+        "Unsupported shape for serialization: {}",
+        peek.shape()
+    ))
+}
+
+struct Serializer<'w, W> {
+    writer: &'w mut W,
+    /// Whether the last start tag still lacks its `>`, which becomes `/>` if
+    /// the element turns out to be empty
+    open_start_tag: bool,
+}
+
+impl<W: Write> Serializer<'_, W> {
+    fn start(
+        &mut self,
+        name: &str,
+        xmlns: Option<&str>,
+        attributes: &[(&str, String)],
+    ) -> io::Result<()> {
+        self.close_start_tag()?;
+        write!(self.writer, "<{}", name)?;
+        if let Some(xmlns) = xmlns {
+            self.write_attribute("xmlns", xmlns)?;
+        }
+        for (name, value) in attributes {
+            self.write_attribute(name, value)?;
+        }
+        self.open_start_tag = true;
+        Ok(())
+    }
+
+    fn end(&mut self, name: &str) -> io::Result<()> {
+        if self.open_start_tag {
+            self.open_start_tag = false;
+            self.writer.write_all(b"/>")
+        } else {
+            write!(self.writer, "</{}>", name)
+        }
+    }
+
+    fn close_start_tag(&mut self) -> io::Result<()> {
+        if self.open_start_tag {
+            self.open_start_tag = false;
+            self.writer.write_all(b">")?;
+        }
+        Ok(())
+    }
+
+    fn write_attribute(&mut self, name: &str, value: &str) -> io::Result<()> {
+        write!(self.writer, " {}=\"", name)?;
+        self.write_escaped(value, true)?;
+        self.writer.write_all(b"\"")
+    }
+
+    fn write_text(&mut self, text: &str) -> io::Result<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        self.close_start_tag()?;
+        self.write_escaped(text, false)
+    }
+
+    /// Writes text with markup characters escaped. Line breaks and tabs are
+    /// escaped too in attributes, where parsers would turn them into spaces.
+    fn write_escaped(&mut self, text: &str, attribute: bool) -> io::Result<()> {
+        let mut start = 0;
+        for (i, c) in text.char_indices() {
+            let escaped = match c {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '\r' => "&#13;",
+                '"' if attribute => "&quot;",
+                '\n' if attribute => "&#10;",
+                '\t' if attribute => "&#9;",
+                _ => continue,
+            };
+            self.writer.write_all(text[start..i].as_bytes())?;
+            self.writer.write_all(escaped.as_bytes())?;
+            start = i + c.len_utf8();
+        }
+        self.writer.write_all(text[start..].as_bytes())
+    }
+
+    /// Writes a value as an element. `parent` is the namespace of the
+    /// parent element, which the element is in unless its shape declares
+    /// another, and `scope` the default namespace in effect where it is
+    /// written.
+    fn write_element(
+        &mut self,
+        name: &str,
+        peek: Peek<'_, '_>,
+        parent: Option<&'static str>,
+        scope: Option<&'static str>,
+    ) -> io::Result<()> {
+        let shape = peek.shape();
+        let namespace = element_namespace(shape, parent);
+        let xmlns = namespace.filter(|_| namespace != scope);
+
+        match shape.def {
+            Def::Option(_) => match peek.into_option().map_err(other)?.value() {
+                Some(value) => self.write_element(name, value, parent, scope),
+                None => Ok(()),
+            },
+            Def::Struct(sd) => match sd.kind {
+                StructKind::Struct | StructKind::Unit => {
+                    let ps = peek.into_struct().map_err(other)?;
+                    let fields: Vec<_> = ps.fields_for_serialize().collect();
+                    self.write_struct(name, xmlns, &fields, namespace)
+                }
+                _ if sd.fields.len() == 1 => {
+                    let value = peek.into_struct().map_err(other)?.field(0).map_err(other)?;
+                    self.write_element(name, value, parent, scope)
+                }
+                _ => Err(unsupported(peek)),
+            },
+            Def::List(_) => {
+                self.start(name, xmlns, &[])?;
+                for item in peek.into_list().map_err(other)?.iter() {
+                    self.write_element("item", item, namespace, namespace)?;
+                }
+                self.end(name)
+            }
+            Def::Map(_) => {
+                self.start(name, xmlns, &[])?;
+                for (key, value) in peek.into_map().map_err(other)?.iter() {
+                    self.write_element(&key.to_string(), value, namespace, namespace)?;
+                }
+                self.end(name)
+            }
+            Def::Enum(_) => {
+                let pe = peek.into_enum().map_err(other)?;
+                self.start(name, xmlns, &[])?;
+                if pe.active_variant().data.fields.is_empty() {
+                    self.write_text(pe.active_variant().name)?;
+                } else {
+                    self.write_variant(pe, namespace, namespace)?;
+                }
+                self.end(name)
+            }
+            Def::Scalar(_) => {
+                let text = text(peek)?.unwrap_or_default();
+                self.start(name, xmlns, &[])?;
+                self.write_text(&text)?;
+                self.end(name)
+            }
+            // Transparent wrappers and smart pointers
+            _ => match peek.borrow_inner() {
+                Some(inner) => self.write_element(name, inner, parent, scope),
+                None => Err(unsupported(peek)),
+            },
+        }
+    }
+
+    /// Writes the active variant of an enum as an element named after it
+    fn write_variant(
+        &mut self,
+        pe: PeekEnum<'_, '_>,
+        namespace: Option<&'static str>,
+        scope: Option<&'static str>,
+    ) -> io::Result<()> {
+        let variant = pe.active_variant();
+        let xmlns = namespace.filter(|_| namespace != scope);
+        let fields = variant.data.fields;
+
+        match variant.data.kind {
+            _ if fields.is_empty() => {
+                self.start(variant.name, xmlns, &[])?;
+                self.end(variant.name)
+            }
+            StructKind::Struct => {
+                let fields: Vec<_> = pe.fields_for_serialize().collect();
+                self.write_struct(variant.name, xmlns, &fields, namespace)
+            }
+            _ if fields.len() == 1 => match pe.field(0) {
+                Some(value) => self.write_element(variant.name, value, namespace, scope),
+                None => Ok(()),
+            },
+            _ => Err(io::Error::other(format!(
+                "Unsupported shape for serialization: tuple variant {}",
+                variant.name
+            ))),
+        }
+    }
+
+    /// Writes the fields of a struct or struct variant as the attributes,
+    /// text and child elements of an element
+    fn write_struct(
+        &mut self,
+        name: &str,
+        xmlns: Option<&str>,
+        fields: &[(&'static Field, Peek<'_, '_>)],
+        namespace: Option<&'static str>,
+    ) -> io::Result<()> {
+        let mut attributes = Vec::new();
+        for (field, value) in fields {
+            if is_attribute(field) {
+                if let Some(text) = text(*value)? {
+                    attributes.push((field_name(field), text));
+                }
+            }
+        }
+        self.start(name, xmlns, &attributes)?;
+
+        for (field, value) in fields {
+            if is_attribute(field) {
+                continue;
+            }
+            if is_text(field) {
+                if let Some(text) = text(*value)? {
+                    self.write_text(&text)?;
+                }
+                continue;
+            }
+
+            let name = field_name(field);
+            match value.shape().def {
+                // Lists are repeated elements
+                Def::List(_) => {
+                    for item in value.into_list().map_err(other)?.iter() {
+                        self.write_element(name, item, namespace, namespace)?;
+                    }
+                }
+                _ => self.write_element(name, *value, namespace, namespace)?,
+            }
+        }
+
+        self.end(name)
+    }
+}
+
+/// The text of a value held in an attribute or as an element's text:
+/// `None` for a missing option, the variant name for enums, and the
+/// `Display` output of scalars
+fn text(peek: Peek<'_, '_>) -> io::Result<Option<String>> {
+    match peek.shape().def {
+        Def::Option(_) => match peek.into_option().map_err(other)?.value() {
+            Some(value) => text(value),
+            None => Ok(None),
+        },
+        Def::Enum(_) => {
+            let variant = peek.into_enum().map_err(other)?.active_variant();
+            Ok(Some(variant.name.to_string()))
+        }
+        Def::Scalar(_) if peek.shape().vtable.display.is_some() => Ok(Some(peek.to_string())),
+        _ => match peek.borrow_inner() {
+            Some(inner) => text(inner),
+            None => Err(unsupported(peek)),
+        },
+    }
+}
@@ -0,0 +1,306 @@
+use eyre::Result;
+use facet::Facet;
+use facet_xml::{XmlErrorKind, from_str};
+use std::collections::HashMap;
+
+#[test]
+fn it_works() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- a comment -->
+<Person>
+    <name>Alice</name>
+    <age> 30 </age>
+</Person>
+"#;
+    let person: Person = from_str(xml)?;
+    assert_eq!(
+        person,
+        Person {
+            name: "Alice".to_string(),
+            age: 30,
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn attributes_and_text() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Price {
+        #[facet(xml_attribute)]
+        currency: String,
+        #[facet(xml_attribute, rename = "incl-vat")]
+        including_vat: Option<bool>,
+        #[facet(xml_text)]
+        amount: f64,
+    }
+
+    let price: Price = from_str(r#"<price currency='EUR' incl-vat="true">12.5</price>"#)?;
+    assert_eq!(
+        price,
+        Price {
+            currency: "EUR".to_string(),
+            including_vat: Some(true),
+            amount: 12.5,
+        }
+    );
+
+    let price: Price = from_str(r#"<price currency="USD">3</price>"#)?;
+    assert_eq!(price.including_vat, None);
+
+    Ok(())
+}
+
+#[test]
+fn repeated_elements() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Playlist {
+        name: String,
+        #[facet(rename = "track")]
+        tracks: Vec<String>,
+        tags: Vec<String>,
+    }
+
+    let xml = "<playlist><track>One</track><name>Mix</name><track>Two</track></playlist>";
+    let playlist: Playlist = from_str(xml)?;
+    assert_eq!(playlist.name, "Mix");
+    assert_eq!(playlist.tracks, ["One", "Two"]);
+    assert!(playlist.tags.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn nested_structs_and_options() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Address {
+        street: String,
+        city: String,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Customer {
+        #[facet(xml_attribute)]
+        id: u64,
+        billing: Address,
+        shipping: Option<Address>,
+        #[facet(default)]
+        notes: String,
+    }
+
+    let xml = r#"<customer id="7">
+        <billing><street>1 Main St</street><city>Springfield</city></billing>
+    </customer>"#;
+    let customer: Customer = from_str(xml)?;
+    assert_eq!(customer.id, 7);
+    assert_eq!(customer.billing.city, "Springfield");
+    assert_eq!(customer.shipping, None);
+    assert_eq!(customer.notes, "");
+
+    Ok(())
+}
+
+#[test]
+fn enums_by_element_name() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Shape {
+        Circle {
+            #[facet(xml_attribute)]
+            radius: f64,
+        },
+        Square(f64),
+        Empty,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Drawing {
+        #[facet(rename = "shape")]
+        shapes: Vec<Shape>,
+    }
+
+    let xml = r#"<drawing>
+        <shape><Circle radius="1.5"/></shape>
+        <shape><Square>2</Square></shape>
+        <shape>Empty</shape>
+    </drawing>"#;
+    let drawing: Drawing = from_str(xml)?;
+    assert_eq!(
+        drawing.shapes,
+        vec![
+            Shape::Circle { radius: 1.5 },
+            Shape::Square(2.0),
+            Shape::Empty
+        ]
+    );
+
+    // At the root, the element itself names the variant
+    let shape: Shape = from_str(r#"<Circle radius="3"/>"#)?;
+    assert_eq!(shape, Shape::Circle { radius: 3.0 });
+
+    let err = from_str::<Shape>("<Triangle/>").unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::UnknownVariant(ref v) if v == "Triangle"));
+
+    Ok(())
+}
+
+#[test]
+fn maps() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Config {
+        settings: HashMap<String, i32>,
+    }
+
+    let config: Config =
+        from_str("<config><settings><width>80</width><depth>3</depth></settings></config>")?;
+    assert_eq!(config.settings["width"], 80);
+    assert_eq!(config.settings["depth"], 3);
+
+    Ok(())
+}
+
+#[test]
+fn entities_and_cdata() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Snippet {
+        #[facet(xml_attribute)]
+        title: String,
+        code: String,
+    }
+
+    let xml = r#"<snippet title="a &lt; b &amp;&amp; &quot;c&quot;"><code>&#x3C;p&#62;<![CDATA[<b>&amp;</b>]]></code></snippet>"#;
+    let snippet: Snippet = from_str(xml)?;
+    assert_eq!(snippet.title, r#"a < b && "c""#);
+    assert_eq!(snippet.code, "<p><b>&amp;</b>");
+
+    Ok(())
+}
+
+#[test]
+fn namespaces() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(xml_namespace = "urn:example:order")]
+    struct Order {
+        #[facet(xml_attribute)]
+        id: u32,
+        item: String,
+    }
+
+    // The prefix doesn't matter, only the namespace it is bound to
+    let order: Order =
+        from_str(r#"<o:Order xmlns:o="urn:example:order" id="1"><o:item>Tea</o:item></o:Order>"#)?;
+    assert_eq!(order.item, "Tea");
+
+    let order: Order =
+        from_str(r#"<Order xmlns="urn:example:order" id="2"><item>Milk</item></Order>"#)?;
+    assert_eq!(order.id, 2);
+
+    let err = from_str::<Order>(r#"<Order xmlns="urn:example:other" id="3"/>"#).unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::WrongNamespace { .. }));
+
+    // An element in another namespace is not the field's
+    let err = from_str::<Order>(
+        r#"<Order xmlns="urn:example:order" xmlns:x="urn:x" id="4"><x:item>Milk</x:item></Order>"#,
+    )
+    .unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::MissingField(ref f) if f == "item"));
+
+    let err = from_str::<Order>(r#"<p:Order id="5"/>"#).unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::UnboundPrefix(ref p) if p == "p"));
+
+    Ok(())
+}
+
+#[test]
+fn unknown_fields() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Lenient {
+        #[facet(xml_attribute)]
+        id: u32,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(deny_unknown_fields)]
+    struct Strict {
+        #[facet(xml_attribute)]
+        id: u32,
+    }
+
+    let xml = r#"<item id="1" extra="x"><more/></item>"#;
+    assert_eq!(from_str::<Lenient>(xml)?, Lenient { id: 1 });
+
+    let err = from_str::<Strict>(xml).unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::UnknownElement(ref e) if e == "more"));
+
+    let err = from_str::<Strict>(r#"<item id="1" extra="x"/>"#).unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::UnknownAttribute(ref a) if a == "extra"));
+    assert_eq!((err.line, err.column), (1, 14));
+
+    Ok(())
+}
+
+#[test]
+fn errors_carry_line_and_column() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, Facet)]
+    struct Reading {
+        value: i32,
+        unit: String,
+    }
+
+    let err = from_str::<Reading>("<reading>\n  <value>hot</value>\n  <unit>C</unit>\n</reading>")
+        .unwrap_err();
+    assert!(matches!(
+        err.kind,
+        XmlErrorKind::InvalidValue { ref value, .. } if value == "hot"
+    ));
+    assert_eq!((err.line, err.column), (2, 3));
+
+    let err = from_str::<Reading>("<reading>\n  <value>1</value>\n</reading>").unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::MissingField(ref f) if f == "unit"));
+    assert_eq!((err.line, err.column), (1, 1));
+
+    let err = from_str::<Reading>("<reading>\n  <value>1</unit>").unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::MismatchedTag { .. }));
+    assert_eq!((err.line, err.column), (2, 11));
+    assert_eq!(
+        err.to_string(),
+        "Expected </value>, got </unit> at line 2, column 11"
+    );
+
+    let err = from_str::<Reading>("<reading><value>1</value>").unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::UnexpectedEof));
+
+    let err = from_str::<Reading>("<reading>&nbsp;</reading>").unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::InvalidEntity(ref e) if e == "nbsp"));
+    assert_eq!((err.line, err.column), (1, 10));
+
+    let err = from_str::<Reading>("<reading/><reading/>").unwrap_err();
+    assert!(matches!(err.kind, XmlErrorKind::UnexpectedChar('<')));
+}
@@ -0,0 +1,219 @@
+use eyre::Result;
+use facet::Facet;
+use facet_xml::{from_str, to_string, to_writer};
+
+#[derive(Debug, PartialEq, Facet)]
+struct Address {
+    street: String,
+    city: String,
+}
+
+#[derive(Debug, PartialEq, Facet)]
+struct Customer {
+    #[facet(xml_attribute)]
+    id: u64,
+    #[facet(xml_attribute)]
+    vip: Option<bool>,
+    name: String,
+    billing: Address,
+    shipping: Option<Address>,
+    #[facet(rename = "phone")]
+    phones: Vec<String>,
+}
+
+fn customer() -> Customer {
+    Customer {
+        id: 7,
+        vip: None,
+        name: "Ann".to_string(),
+        billing: Address {
+            street: "1 Main St".to_string(),
+            city: "Springfield".to_string(),
+        },
+        shipping: None,
+        phones: vec!["555-1234".to_string(), "555-9876".to_string()],
+    }
+}
+
+#[test]
+fn it_works() {
+    facet_testhelpers::setup();
+
+    assert_eq!(
+        to_string(&customer()),
+        concat!(
+            r#"<Customer id="7"><name>Ann</name>"#,
+            "<billing><street>1 Main St</street><city>Springfield</city></billing>",
+            "<phone>555-1234</phone><phone>555-9876</phone>",
+            "</Customer>"
+        )
+    );
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let mut customer = customer();
+    customer.vip = Some(true);
+    customer.shipping = Some(Address {
+        street: "2 Side St".to_string(),
+        city: "Shelbyville".to_string(),
+    });
+
+    let xml = to_string(&customer);
+    assert_eq!(from_str::<Customer>(&xml)?, customer);
+
+    Ok(())
+}
+
+#[test]
+fn escaping() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Message {
+        #[facet(xml_attribute)]
+        subject: String,
+        #[facet(xml_text)]
+        body: String,
+    }
+
+    let message = Message {
+        subject: "\"Hi\" <all>\n& welcome".to_string(),
+        body: "a < b && c > d\r\n".to_string(),
+    };
+    let xml = to_string(&message);
+    assert_eq!(
+        xml,
+        r#"<Message subject="&quot;Hi&quot; &lt;all&gt;&#10;&amp; welcome">a &lt; b &amp;&amp; c &gt; d&#13;
+</Message>"#
+    );
+    assert_eq!(from_str::<Message>(&xml)?, message);
+
+    Ok(())
+}
+
+#[test]
+fn empty_elements() {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Flags {
+        #[facet(xml_attribute)]
+        on: bool,
+        label: String,
+    }
+
+    assert_eq!(
+        to_string(&Flags {
+            on: true,
+            label: String::new(),
+        }),
+        r#"<Flags on="true"><label/></Flags>"#
+    );
+}
+
+#[test]
+fn enums() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[repr(u8)]
+    enum Shape {
+        Circle {
+            #[facet(xml_attribute)]
+            radius: f64,
+        },
+        Square(f64),
+        Empty,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Drawing {
+        #[facet(rename = "shape")]
+        shapes: Vec<Shape>,
+    }
+
+    let drawing = Drawing {
+        shapes: vec![
+            Shape::Circle { radius: 1.5 },
+            Shape::Square(2.0),
+            Shape::Empty,
+        ],
+    };
+    let xml = to_string(&drawing);
+    assert_eq!(
+        xml,
+        concat!(
+            "<Drawing>",
+            r#"<shape><Circle radius="1.5"/></shape>"#,
+            "<shape><Square>2</Square></shape>",
+            "<shape>Empty</shape>",
+            "</Drawing>"
+        )
+    );
+    assert_eq!(from_str::<Drawing>(&xml)?, drawing);
+
+    assert_eq!(to_string(&Shape::Square(4.0)), "<Square>4</Square>");
+    assert_eq!(to_string(&Shape::Empty), "<Empty/>");
+
+    Ok(())
+}
+
+#[test]
+fn namespaces() -> Result<()> {
+    facet_testhelpers::setup();
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(xml_namespace = "urn:example:invoice")]
+    struct Invoice {
+        number: u32,
+        party: Party,
+        #[facet(rename = "line")]
+        lines: Vec<Line>,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    #[facet(xml_namespace = "urn:example:party")]
+    struct Party {
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Facet)]
+    struct Line {
+        amount: u32,
+    }
+
+    let invoice = Invoice {
+        number: 12,
+        party: Party {
+            name: "ACME".to_string(),
+        },
+        lines: vec![Line { amount: 3 }],
+    };
+    let xml = to_string(&invoice);
+    assert_eq!(
+        xml,
+        concat!(
+            r#"<Invoice xmlns="urn:example:invoice"><number>12</number>"#,
+            r#"<party xmlns="urn:example:party"><name>ACME</name></party>"#,
+            "<line><amount>3</amount></line>",
+            "</Invoice>"
+        )
+    );
+    assert_eq!(from_str::<Invoice>(&xml)?, invoice);
+
+    Ok(())
+}
+
+#[test]
+fn writer() -> Result<()> {
+    facet_testhelpers::setup();
+
+    let mut output = Vec::new();
+    to_writer(&customer(), &mut output)?;
+    assert_eq!(String::from_utf8(output)?, to_string(&customer()));
+
+    Ok(())
+}
@@ -2,26 +2,60 @@
 #![doc = include_str!("../README.md")]
 
 use facet_core::{Def, Facet};
-use facet_reflect::Wip;
+use facet_reflect::{ReflectError, Wip};
 use yaml_rust2::{Yaml, YamlLoader};
 
+pub use facet_reflect::{LimitKind, Limits};
+
+mod limits;
+
 /// Deserializes a YAML string into a value of type `T` that implements `Facet`.
 pub fn from_str<'input: 'facet, 'facet, T: Facet<'facet>>(yaml: &'input str) -> Result<T, AnyErr> {
-    let wip = Wip::alloc::<T>()?;
+    from_str_with_limits(yaml, Limits::default())
+}
+
+/// Deserializes a YAML string into a value of type `T`, refusing documents
+/// that go over the given [`Limits`]: see [`AnyErr::limit_exceeded_info`].
+pub fn from_str_with_limits<'input: 'facet, 'facet, T: Facet<'facet>>(
+    yaml: &'input str,
+    limits: Limits,
+) -> Result<T, AnyErr> {
+    let wip = reflect(Wip::alloc::<T>()?, |wip| wip.with_limits(limits))?;
     let wip = from_str_value(wip, yaml)?;
-    let heap_value = wip.build().map_err(|e| AnyErr(e.to_string()))?;
+    let heap_value = wip.build().map_err(|e| AnyErr::from(e.to_string()))?;
     heap_value
         .materialize::<T>()
-        .map_err(|e| AnyErr(e.to_string()))
+        .map_err(|e| AnyErr::from(e.to_string()))
 }
 
 /// Any error
 #[derive(Debug, Clone)]
-pub struct AnyErr(String);
+pub struct AnyErr {
+    message: String,
+    limit: Option<(LimitKind, usize, String)>,
+}
+
+impl AnyErr {
+    fn limit_exceeded(kind: LimitKind, max: usize, path: String) -> Self {
+        Self {
+            message: format!("Exceeded the {} limit of {} at {}", kind, max, path),
+            limit: Some((kind, max, path)),
+        }
+    }
+
+    /// If the document went over one of the configured [`Limits`], returns
+    /// which one, its maximum, and where in the value it was hit, e.g.
+    /// `$.items[3]`.
+    pub fn limit_exceeded_info(&self) -> Option<(LimitKind, usize, &str)> {
+        self.limit
+            .as_ref()
+            .map(|(kind, max, path)| (*kind, *max, path.as_str()))
+    }
+}
 
 impl core::fmt::Display for AnyErr {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.message)
     }
 }
 
@@ -29,22 +63,38 @@ impl std::error::Error for AnyErr {}
 
 impl From<String> for AnyErr {
     fn from(s: String) -> Self {
-        Self(s)
+        Self {
+            message: s,
+            limit: None,
+        }
     }
 }
 
 impl From<&str> for AnyErr {
     fn from(s: &str) -> Self {
-        Self(s.to_string())
+        s.to_string().into()
     }
 }
 
-impl From<facet_reflect::ReflectError> for AnyErr {
-    fn from(value: facet_reflect::ReflectError) -> Self {
-        Self(format!("Reflection error: {value}"))
+impl From<ReflectError> for AnyErr {
+    fn from(value: ReflectError) -> Self {
+        format!("Reflection error: {value}").into()
     }
 }
 
+/// Runs an operation on the `Wip`, reporting limit violations with the path
+/// they happened at.
+fn reflect<'a>(
+    wip: Wip<'a>,
+    op: impl FnOnce(Wip<'a>) -> Result<Wip<'a>, ReflectError>,
+) -> Result<Wip<'a>, AnyErr> {
+    let path = wip.path();
+    op(wip).map_err(|e| match e {
+        ReflectError::LimitExceeded { kind, max } => AnyErr::limit_exceeded(kind, max, path),
+        e => e.to_string().into(),
+    })
+}
+
 fn yaml_type(ty: &Yaml) -> &'static str {
     match ty {
         Yaml::Real(_) => "real number",
@@ -63,17 +113,23 @@ fn yaml_to_u64(ty: &Yaml) -> Result<u64, AnyErr> {
     match ty {
         Yaml::Real(r) => r
             .parse::<u64>()
-            .map_err(|_| AnyErr("Failed to parse real as u64".into())),
+            .map_err(|_| AnyErr::from("Failed to parse real as u64")),
         Yaml::Integer(i) => Ok(*i as u64),
         Yaml::String(s) => s
             .parse::<u64>()
-            .map_err(|_| AnyErr("Failed to parse string as u64".into())),
+            .map_err(|_| AnyErr::from("Failed to parse string as u64")),
         Yaml::Boolean(b) => Ok(if *b { 1 } else { 0 }),
-        _ => Err(AnyErr(format!("Cannot convert {} to u64", yaml_type(ty)))),
+        _ => Err(AnyErr::from(format!(
+            "Cannot convert {} to u64",
+            yaml_type(ty)
+        ))),
     }
 }
 
 fn from_str_value<'a>(wip: Wip<'a>, yaml: &str) -> Result<Wip<'a>, AnyErr> {
+    if *wip.limits() != Limits::UNLIMITED {
+        limits::check(yaml, *wip.limits())?;
+    }
     let docs = YamlLoader::load_from_str(yaml).map_err(|e| e.to_string())?;
     if docs.len() != 1 {
         return Err("Expected exactly one YAML document".into());
@@ -87,40 +143,87 @@ fn deserialize_value<'a>(mut wip: Wip<'a>, value: &Yaml) -> Result<Wip<'a>, AnyE
         Def::Scalar(_) => {
             if shape.is_type::<u64>() {
                 let u = yaml_to_u64(value)?;
-                wip = wip.put(u).map_err(|e| AnyErr(e.to_string()))?;
+                wip = reflect(wip, |wip| wip.put(u))?;
             } else if shape.is_type::<String>() {
                 let s = value
                     .as_str()
-                    .ok_or_else(|| AnyErr(format!("Expected string, got: {}", yaml_type(value))))?
+                    .ok_or_else(|| {
+                        AnyErr::from(format!("Expected string, got: {}", yaml_type(value)))
+                    })?
                     .to_string();
-                wip = wip.put(s).map_err(|e| AnyErr(e.to_string()))?;
+                wip = reflect(wip, |wip| wip.put(s))?;
             } else {
-                return Err(AnyErr(format!("Unsupported scalar type: {}", shape)));
+                return Err(AnyErr::from(format!("Unsupported scalar type: {}", shape)));
+            }
+        }
+        Def::List(_) => {
+            let Yaml::Array(items) = value else {
+                return Err(AnyErr::from(format!(
+                    "Expected a YAML array, got: {}",
+                    yaml_type(value)
+                )));
+            };
+            wip = reflect(wip, |wip| wip.put_default())?;
+            for item in items {
+                wip = reflect(wip, |wip| wip.push())?;
+                wip = deserialize_value(wip, item)?;
+                wip = reflect(wip, |wip| wip.pop())?;
+            }
+        }
+        Def::Map(md) => {
+            let Yaml::Hash(hash) = value else {
+                return Err(AnyErr::from(format!(
+                    "Expected a YAML hash, got: {}",
+                    yaml_type(value)
+                )));
+            };
+            wip = reflect(wip, |wip| wip.put_default())?;
+            for (k, v) in hash {
+                wip = reflect(wip, |wip| wip.push_map_key())?;
+                if md.k.is_type::<String>() {
+                    wip = deserialize_value(wip, k)?;
+                } else {
+                    // Other keys (integers, newtypes, ...) are parsed from their text
+                    let key = match k {
+                        Yaml::String(s) | Yaml::Real(s) => s.clone(),
+                        Yaml::Integer(i) => i.to_string(),
+                        Yaml::Boolean(b) => b.to_string(),
+                        _ => {
+                            return Err(AnyErr::from(format!(
+                                "Unsupported map key: {}",
+                                yaml_type(k)
+                            )));
+                        }
+                    };
+                    wip = reflect(wip, |wip| wip.parse(&key))?;
+                }
+                wip = reflect(wip, |wip| wip.push_map_value())?;
+                wip = deserialize_value(wip, v)?;
+                wip = reflect(wip, |wip| wip.pop())?;
             }
         }
-        Def::List(_) => todo!(),
-        Def::Map(_) => todo!(),
         Def::Struct(_) => {
             if let Yaml::Hash(hash) = value {
                 for (k, v) in hash {
                     let k = k.as_str().ok_or_else(|| {
-                        AnyErr(format!("Expected string key, got: {}", yaml_type(k)))
+                        AnyErr::from(format!("Expected string key, got: {}", yaml_type(k)))
                     })?;
                     let field_index = wip
                         .field_index(k)
-                        .ok_or_else(|| AnyErr(format!("Field '{}' not found", k)))?;
-                    wip = wip
-                        .field(field_index)
-                        .map_err(|e| AnyErr(format!("Field '{}' error: {}", k, e)))?;
+                        .ok_or_else(|| AnyErr::from(format!("Field '{}' not found", k)))?;
+                    wip = reflect(wip, |wip| wip.field(field_index))?;
                     wip = deserialize_value(wip, v)?;
-                    wip = wip.pop().map_err(|e| AnyErr(e.to_string()))?;
+                    wip = reflect(wip, |wip| wip.pop())?;
                 }
             } else {
-                return Err(AnyErr(format!("Expected a YAML hash, got: {:?}", value)));
+                return Err(AnyErr::from(format!(
+                    "Expected a YAML hash, got: {:?}",
+                    value
+                )));
             }
         }
         Def::Enum(_) => todo!(),
-        _ => return Err(AnyErr(format!("Unsupported type: {:?}", shape))),
+        _ => return Err(AnyErr::from(format!("Unsupported type: {:?}", shape))),
     }
     Ok(wip)
 }
@@ -0,0 +1,158 @@
+//! Checking a document against [`Limits`] before it's loaded.
+//!
+//! `YamlLoader` builds the whole tree, expanding aliases as it goes, before
+//! there's a `Wip` to enforce the limits, so a document is first streamed
+//! through the parser and checked event by event.
+
+use std::collections::HashMap;
+
+use facet_reflect::{LimitKind, Limits, ReflectError};
+use yaml_rust2::parser::{Event, EventReceiver, Parser};
+
+use crate::AnyErr;
+
+/// Checks `yaml` against `limits`, without loading it.
+///
+/// Nodes are charged against [`Limits::max_alloc_bytes`] by the length of
+/// their text plus one, so that a tree is never free, and aliases by the
+/// size of the node they stand for: that is what loading them costs.
+pub(crate) fn check(yaml: &str, limits: Limits) -> Result<(), AnyErr> {
+    let mut checker = Checker {
+        limits,
+        stack: Vec::new(),
+        anchors: HashMap::new(),
+        total: 0,
+        error: None,
+    };
+    Parser::new_from_str(yaml)
+        .load(&mut checker, true)
+        .map_err(|e| e.to_string())?;
+    match checker.error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// A sequence or mapping that hasn't been closed yet
+struct Container {
+    /// Whether this is a mapping rather than a sequence
+    mapping: bool,
+    /// How many nodes it holds so far, keys and values alike
+    nodes: usize,
+    /// The key of the current entry, for mappings
+    key: Option<String>,
+    /// Its anchor, or 0 if it has none
+    anchor: usize,
+    /// What had been charged when it was opened
+    charged_before: usize,
+}
+
+struct Checker {
+    limits: Limits,
+    stack: Vec<Container>,
+    /// What each anchored node was charged
+    anchors: HashMap<usize, usize>,
+    total: usize,
+    /// The first limit exceeded. Later events are ignored.
+    error: Option<AnyErr>,
+}
+
+impl Checker {
+    /// The path to the node the event at hand is about, within the first
+    /// `depth` containers.
+    fn path(&self, depth: usize) -> String {
+        let mut path = String::from("$");
+        for container in &self.stack[..depth] {
+            if !container.mapping {
+                path.push_str(&format!("[{}]", container.nodes - 1));
+            } else if container.nodes % 2 == 0 {
+                path.push('.');
+                path.push_str(container.key.as_deref().unwrap_or("?"));
+            }
+        }
+        path
+    }
+
+    fn check(&self, kind: LimitKind, value: usize, depth: usize) -> Result<(), AnyErr> {
+        match self.limits.check(kind, value) {
+            Err(ReflectError::LimitExceeded { kind, max }) => {
+                Err(AnyErr::limit_exceeded(kind, max, self.path(depth)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts a new node in its container
+    fn enter(&mut self) -> Result<(), AnyErr> {
+        let depth = self.stack.len();
+        if let Some(parent) = self.stack.last_mut() {
+            parent.nodes += 1;
+            let len = if parent.mapping {
+                parent.nodes.div_ceil(2)
+            } else {
+                parent.nodes
+            };
+            self.check(LimitKind::CollectionLength, len, depth - 1)?;
+        }
+        self.check(LimitKind::Depth, depth + 1, depth)
+    }
+
+    fn charge(&mut self, cost: usize) -> Result<(), AnyErr> {
+        self.total = self.total.saturating_add(cost);
+        self.check(LimitKind::Allocation, self.total, self.stack.len())
+    }
+
+    fn event(&mut self, event: Event) -> Result<(), AnyErr> {
+        match event {
+            Event::Scalar(value, _, anchor, _) => {
+                self.enter()?;
+                self.check(LimitKind::StringLength, value.len(), self.stack.len())?;
+                self.charge(value.len() + 1)?;
+                if anchor != 0 {
+                    self.anchors.insert(anchor, value.len() + 1);
+                }
+                if let Some(parent) = self.stack.last_mut() {
+                    if parent.mapping && parent.nodes % 2 == 1 {
+                        parent.key = Some(value);
+                    }
+                }
+            }
+            Event::Alias(anchor) => {
+                let cost = self.anchors.get(&anchor).copied().unwrap_or(1);
+                self.enter()?;
+                self.charge(cost)?;
+            }
+            Event::SequenceStart(anchor, _) | Event::MappingStart(anchor, _) => {
+                self.enter()?;
+                self.charge(1)?;
+                self.stack.push(Container {
+                    mapping: matches!(event, Event::MappingStart(..)),
+                    nodes: 0,
+                    key: None,
+                    anchor,
+                    charged_before: self.total.saturating_sub(1),
+                });
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some(container) = self.stack.pop() {
+                    if container.anchor != 0 {
+                        let cost = self.total - container.charged_before;
+                        self.anchors.insert(container.anchor, cost);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl EventReceiver for Checker {
+    fn on_event(&mut self, event: Event) {
+        if self.error.is_none() {
+            if let Err(error) = self.event(event) {
+                self.error = Some(error);
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use eyre::Result;
 use facet::Facet;
+use std::collections::HashMap;
 
 #[derive(Debug, Facet, PartialEq)]
 struct Person {
@@ -25,3 +26,77 @@ fn test_deserialize_person() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_lists_and_maps() -> Result<()> {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Inventory {
+        tags: Vec<String>,
+        stock: HashMap<String, u64>,
+        bins: HashMap<u64, String>,
+    }
+
+    let yaml = r#"
+            tags: [red, blue]
+            stock:
+              apples: 3
+              pears: 5
+            bins:
+              1: top
+              2: bottom
+        "#;
+
+    let inventory: Inventory = facet_yaml::from_str(yaml)?;
+    assert_eq!(inventory.tags, vec!["red".to_string(), "blue".to_string()]);
+    assert_eq!(inventory.stock["pears"], 5);
+    assert_eq!(inventory.bins[&2], "bottom");
+
+    Ok(())
+}
+
+#[test]
+fn test_collection_length_limit() -> Result<()> {
+    #[derive(Debug, Facet, PartialEq)]
+    struct Batch {
+        ids: Vec<u64>,
+    }
+
+    let yaml = r#"
+            ids: [1, 2, 3, 4]
+        "#;
+
+    let batch: Batch = facet_yaml::from_str(yaml)?;
+    assert_eq!(batch.ids, vec![1, 2, 3, 4]);
+
+    let limits = facet_yaml::Limits::default().max_collection_len(3);
+    let err = facet_yaml::from_str_with_limits::<Batch>(yaml, limits).unwrap_err();
+    assert_eq!(
+        err.limit_exceeded_info(),
+        Some((facet_yaml::LimitKind::CollectionLength, 3, "$.ids"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_limits_apply_before_aliases_are_expanded() {
+    #[derive(Debug, Facet)]
+    struct Bomb {
+        a: Vec<String>,
+        b: Vec<Vec<String>>,
+        c: Vec<Vec<Vec<String>>>,
+    }
+
+    let yaml = r#"
+            a: &a ["lol", "lol", "lol", "lol", "lol", "lol", "lol", "lol"]
+            b: &b [*a, *a, *a, *a, *a, *a, *a, *a]
+            c: [*b, *b, *b, *b, *b, *b, *b, *b]
+        "#;
+
+    let limits = facet_yaml::Limits::default().max_alloc_bytes(1024);
+    let err = facet_yaml::from_str_with_limits::<Bomb>(yaml, limits).unwrap_err();
+    let (kind, max, path) = err.limit_exceeded_info().unwrap();
+    assert_eq!(kind, facet_yaml::LimitKind::Allocation);
+    assert_eq!(max, 1024);
+    assert!(path.starts_with("$.c["), "{path}");
+}